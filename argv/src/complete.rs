@@ -1,10 +1,11 @@
 //! Splitting a command line the way the shell that typed it would.
 //!
 //! A completion request arrives as a line and a cursor, not as an argv: the user has pressed
-//! Tab in the middle of something the shell has not run and may never run. Four of the five
+//! Tab in the middle of something the shell has not run and may never run. Four of the seven
 //! shells hand that over directly — bash's `COMP_LINE`/`COMP_POINT`, zsh's `$BUFFER`/`$CURSOR`,
-//! fish's `commandline -cp`, PowerShell's `$commandAst.Extent.Text` with `$cursorPosition` —
-//! and nushell, whose external completer only ever sees spans, re-quotes them into a line.
+//! fish's `commandline -cp`, PowerShell's `$commandAst.Extent.Text` with `$cursorPosition`.
+//! nushell and elvish, whose completers only ever see words, re-quote them into a line; xonsh
+//! hands over each word as it was typed, quotes included, and those are joined back up.
 //!
 //! Taking the line rather than the shell's own word split is what lets `mise use "my tool<TAB>`
 //! complete inside a quoted word at all: a word split has already thrown away the quote that
@@ -316,16 +317,20 @@ fn for_name_at<'a>(
 ///
 /// One command's own, not the tree's: a spec writes the block inside the command that declares
 /// it, so two siblings taking a `tool` and meaning different things by it each say so.
+///
+/// A field that also declares a `complete_type` is left out. Its spec says `type=`, which every
+/// consumer answers from the spec alone, and a `run=` beside it is one the grammar refuses; the
+/// function is then only this binary's own answer to the same question.
 #[cfg(feature = "spec")]
 pub fn completers_on(meta: &CommandMeta<'_>) -> Vec<String> {
     let mut out = Vec::new();
     for arg in meta.args {
-        if arg.complete.is_some() {
+        if arg.complete.is_some() && arg.complete_type.is_none() {
             out.push(arg.arg.name.to_ascii_lowercase());
         }
     }
     for flag in meta.flags {
-        if flag.complete.is_some() {
+        if flag.complete.is_some() && flag.complete_type.is_none() {
             out.push(
                 flag.value_name
                     .unwrap_or(flag.flag.name)
@@ -528,6 +533,55 @@ impl<'a> App<'a> {
         crate::install::install_for(spec.bin.unwrap_or(spec.name), alias, shell, env, on_foreign)
     }
 
+    /// Where this view's script goes for every shell on the described `PATH`. Writes nothing.
+    pub fn completion_install_plan_all_shells(
+        self,
+        env: &crate::install::Env,
+    ) -> Result<Vec<crate::install::Plan>, crate::install::Error> {
+        let spec = self.view.spec();
+        crate::install::plan_all(spec.bin.unwrap_or(spec.name), env)
+    }
+
+    /// Write this view's script for every shell on the described `PATH`.
+    pub fn install_completion_all_shells(
+        self,
+        env: &crate::install::Env,
+        on_foreign: crate::install::OnForeign,
+    ) -> Result<Vec<crate::install::Installed>, crate::install::Error> {
+        let spec = self.view.spec();
+        crate::install::install_all(spec.bin.unwrap_or(spec.name), env, on_foreign)
+    }
+
+    /// Remove this view's script for `shell`, if an install wrote it.
+    pub fn uninstall_completion(
+        self,
+        shell: Shell,
+        env: &crate::install::Env,
+    ) -> Result<crate::install::Uninstalled, crate::install::Error> {
+        let spec = self.view.spec();
+        crate::install::uninstall(spec.bin.unwrap_or(spec.name), shell, env)
+    }
+
+    /// Remove the script an alias was installed under.
+    pub fn uninstall_completion_for_alias(
+        self,
+        alias: &str,
+        shell: Shell,
+        env: &crate::install::Env,
+    ) -> Result<crate::install::Uninstalled, crate::install::Error> {
+        let spec = self.view.spec();
+        crate::install::uninstall_for(spec.bin.unwrap_or(spec.name), alias, shell, env)
+    }
+
+    /// Remove this view's script from every shell that has somewhere to keep one.
+    pub fn uninstall_completion_all_shells(
+        self,
+        env: &crate::install::Env,
+    ) -> Result<Vec<crate::install::Uninstalled>, crate::install::Error> {
+        let spec = self.view.spec();
+        crate::install::uninstall_all(spec.bin.unwrap_or(spec.name), env)
+    }
+
     /// Answer a hidden completion invocation, or return `None` for ordinary argv.
     pub async fn completion_request(self, argv: &[OsString]) -> Option<String> {
         let request = Request::parse(argv)?;
@@ -613,7 +667,7 @@ impl Request {
 
 /// The line a shell reads to mean "paths belong here too".
 ///
-/// A whole line rather than a flag on the protocol, because every one of the five shells can
+/// A whole line rather than a flag on the protocol, because every one of the seven shells can
 /// already split output into lines and look at the last one. `\x01` opens it because no
 /// candidate this crate produces can contain a control character, so it cannot be mistaken for
 /// one.
@@ -628,9 +682,9 @@ pub const COMMANDS_MARKER: &str = "\u{1}commands";
 /// Write an answer the way `shell` reads it.
 ///
 /// One line per candidate, in the shape the shell's own completion machinery expects — which is
-/// where the five differ. bash reads values only; fish, nu and PowerShell take a description
-/// after a tab; zsh takes a third field, the text to insert, because what it displays and what
-/// it types are not always the same string.
+/// where the seven differ. bash reads values only; fish, nu, PowerShell, elvish and xonsh take a
+/// description after a tab; zsh takes a third field, the text to insert, because what it
+/// displays and what it types are not always the same string.
 ///
 /// A trailing [`FILES_MARKER`] says the generated script should hand the position to the
 /// shell's own path completion afterwards.
@@ -655,7 +709,7 @@ pub fn render(answer: &Completions<'_>, shell: Shell) -> String {
                 out.push('\t');
                 out.push_str(&zsh_quote(&candidate.value));
             }
-            Shell::Fish | Shell::Nu | Shell::PowerShell => {
+            Shell::Fish | Shell::Nu | Shell::PowerShell | Shell::Elvish | Shell::Xonsh => {
                 out.push_str(&candidate.value);
                 if described {
                     out.push('\t');
@@ -1552,11 +1606,13 @@ fn arg_meta<'a>(meta: &'a CommandMeta<'a>, arg: &Arg<'_>) -> Option<&'a ArgMeta<
 
 /// Which shell's quoting rules a line follows.
 ///
-/// Only two rule sets, not five: bash, zsh, fish and nushell all follow the POSIX shape
-/// closely enough that a completion request cannot tell them apart, while PowerShell escapes
-/// with a backtick and doubles a quote to escape it. The distinction is kept per *shell*
-/// rather than per rule set so that a shell whose rules turn out to differ can be given its
-/// own without changing this type's public shape.
+/// Four rule sets, not seven: bash, zsh, fish and nushell all follow the POSIX shape closely
+/// enough that a completion request cannot tell them apart, while PowerShell escapes with a
+/// backtick and doubles a quote to escape it. elvish doubles a single quote too, but has no
+/// escape outside double quotes at all — `C:\Users` typed bare is that path. xonsh reads a
+/// quoted word as a Python string literal, so a backslash escapes inside single quotes as well.
+/// The distinction is kept per *shell* rather than per rule set so that a shell whose rules
+/// turn out to differ can be given its own without changing this type's public shape.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Shell {
@@ -1565,6 +1621,8 @@ pub enum Shell {
     Fish,
     Nu,
     PowerShell,
+    Elvish,
+    Xonsh,
 }
 
 impl Shell {
@@ -1576,6 +1634,8 @@ impl Shell {
             Shell::Fish => "fish",
             Shell::Nu => "nu",
             Shell::PowerShell => "powershell",
+            Shell::Elvish => "elvish",
+            Shell::Xonsh => "xonsh",
         }
     }
 
@@ -1587,6 +1647,8 @@ impl Shell {
             "fish" => Some(Shell::Fish),
             "nu" | "nushell" => Some(Shell::Nu),
             "powershell" | "pwsh" => Some(Shell::PowerShell),
+            "elvish" => Some(Shell::Elvish),
+            "xonsh" => Some(Shell::Xonsh),
             _ => None,
         }
     }
@@ -1596,9 +1658,23 @@ impl Shell {
         matches!(self, Shell::PowerShell)
     }
 
-    /// Whether a quote inside a quoted string is written by doubling it.
-    fn doubles_quotes(self) -> bool {
-        matches!(self, Shell::PowerShell)
+    /// Whether an escape means anything outside quotes.
+    fn bare_escapes(self) -> bool {
+        !matches!(self, Shell::Elvish)
+    }
+
+    /// Whether `quote` inside a string it opened is written by doubling it.
+    fn doubles(self, quote: char) -> bool {
+        match self {
+            Shell::PowerShell => true,
+            Shell::Elvish => quote == '\'',
+            _ => false,
+        }
+    }
+
+    /// Whether a backslash escapes inside single quotes, as it does in a Python string literal.
+    fn escapes_in_single_quotes(self) -> bool {
+        matches!(self, Shell::Xonsh)
     }
 }
 
@@ -1686,23 +1762,32 @@ pub fn split(line: &str, cursor: usize, shell: Shell) -> Split {
         match quote {
             Some('\'') => {
                 if c == '\'' {
-                    // PowerShell writes a quote inside a quoted string by doubling it; the
-                    // POSIX-shaped shells have no such rule, and there a second quote always
+                    // PowerShell and elvish write a quote inside a quoted string by doubling it;
+                    // the POSIX-shaped shells have no such rule, and there a second quote always
                     // ends the string.
-                    if shell.doubles_quotes() && chars.peek().map(|&(_, n)| n) == Some('\'') {
+                    if shell.doubles('\'') && chars.peek().map(|&(_, n)| n) == Some('\'') {
                         reached!(chars.peek().expect("peeked just above").0);
                         word.push('\'');
                         chars.next();
                     } else {
                         quote = None;
                     }
+                } else if c == '\\' && shell.escapes_in_single_quotes() {
+                    match chars.peek() {
+                        Some(&(j, next)) if matches!(next, '\'' | '\\') => {
+                            reached!(j);
+                            word.push(next);
+                            chars.next();
+                        }
+                        _ => word.push(c),
+                    }
                 } else {
                     word.push(c);
                 }
             }
             Some(q) => {
                 if c == q {
-                    if shell.doubles_quotes() && chars.peek().map(|&(_, n)| n) == Some(q) {
+                    if shell.doubles(q) && chars.peek().map(|&(_, n)| n) == Some(q) {
                         reached!(chars.peek().expect("peeked just above").0);
                         word.push(q);
                         chars.next();
@@ -1729,7 +1814,7 @@ pub fn split(line: &str, cursor: usize, shell: Shell) -> Split {
                 if c == '\'' || c == '"' {
                     quote = Some(c);
                     started = true;
-                } else if is_escape(c, shell) {
+                } else if shell.bare_escapes() && is_escape(c, shell) {
                     // Before the check, because the escape has already started the word: a
                     // cursor on the escaped character is inside it, not in the gap before it.
                     started = true;
@@ -1792,10 +1877,12 @@ fn is_escape(c: char, shell: Shell) -> bool {
 
 /// Whether an escape inside double quotes applies to the character after it.
 fn escapable_in_quotes(c: char, shell: Shell) -> bool {
-    if shell.backtick_escapes() {
-        matches!(c, '"' | '`' | '$')
-    } else {
-        matches!(c, '"' | '\\' | '$' | '`')
+    match shell {
+        Shell::PowerShell => matches!(c, '"' | '`' | '$'),
+        // Neither expands anything inside double quotes, so only the quote and the escape itself
+        // have anything to be escaped from.
+        Shell::Elvish | Shell::Xonsh => matches!(c, '"' | '\\'),
+        _ => matches!(c, '"' | '\\' | '$' | '`'),
     }
 }
 
@@ -2804,6 +2891,38 @@ mod tests {
         assert_eq!(s.words, ["mise", "run", "its"]);
     }
 
+    #[test]
+    fn elvish_doubles_a_single_quote_and_escapes_nothing_bare() {
+        let line = "mise run 'it''s here";
+        let s = split(line, line.len(), Shell::Elvish);
+        assert_eq!(s.words, ["mise", "run", "it's here"]);
+
+        // A backslash outside quotes is an ordinary character, so a bare path is the path.
+        let line = r"mise use C:\Users\me";
+        let s = split(line, line.len(), Shell::Elvish);
+        assert_eq!(s.words, ["mise", "use", r"C:\Users\me"]);
+
+        // Double quotes escape with a backslash, and a doubled `"` is not a quote there.
+        let line = r#"mise run "say \"hi"#;
+        let s = split(line, line.len(), Shell::Elvish);
+        assert_eq!(s.words, ["mise", "run", r#"say "hi"#]);
+    }
+
+    #[test]
+    fn xonsh_reads_a_quoted_word_as_a_python_string() {
+        // A backslash escapes a quote inside single quotes too, which no POSIX shell allows.
+        let line = r"mise run 'it\'s here";
+        let s = split(line, line.len(), Shell::Xonsh);
+        assert_eq!(s.words, ["mise", "run", "it's here"]);
+        assert_eq!(s.prefix, "it's here");
+
+        // Before any other character it is kept, which is the forgiving reading of a path that
+        // is still being typed.
+        let line = r"mise use 'C:\Users\me";
+        let s = split(line, line.len(), Shell::Xonsh);
+        assert_eq!(s.words, ["mise", "use", r"C:\Users\me"]);
+    }
+
     #[test]
     fn an_empty_line_is_still_completing_something() {
         let s = at_end("");
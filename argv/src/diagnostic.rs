@@ -293,6 +293,20 @@ fn shown<'a>(meta: Option<&'a CommandMeta<'a>>, name: &str) -> String {
     name.to_string()
 }
 
+/// What an error quotes in place of a value typed for a `sensitive` declaration.
+const REDACTED: &str = "<redacted>";
+
+/// Whether `name` is a secret, so the value typed for it is not quoted back.
+fn is_sensitive(meta: Option<&CommandMeta<'_>>, name: &str) -> bool {
+    meta.is_some_and(|meta| {
+        meta.args.iter().any(|a| a.arg.name == name && a.sensitive)
+            || meta
+                .flags
+                .iter()
+                .any(|f| f.flag.name == name && f.sensitive)
+    })
+}
+
 /// A group member is stored as a selector (`--file` or `-f`), not a field name.
 fn group_member_shown(meta: Option<&CommandMeta<'_>>, selector: &str) -> String {
     let Some(meta) = meta else {
@@ -738,7 +752,11 @@ fn render_inner<'a>(
                         out,
                         "{} invalid value '{}' for '{}'",
                         style.error("error:"),
-                        style.invalid(value),
+                        style.invalid(if is_sensitive(here, name) {
+                            REDACTED
+                        } else {
+                            value
+                        }),
                         style.literal(&shown_name)
                     );
                 }
@@ -768,7 +786,11 @@ fn render_inner<'a>(
                 out,
                 "{} invalid value '{}' for '{}': {}",
                 style.error("error:"),
-                style.invalid(&invalid.value),
+                style.invalid(if is_sensitive(here, invalid.name) {
+                    REDACTED
+                } else {
+                    &invalid.value
+                }),
                 style.literal(&shown(here, invalid.name)),
                 invalid.reason
             );
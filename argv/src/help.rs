@@ -953,7 +953,11 @@ fn short_help_with(
                     if a.hide_env { None } else { a.env },
                     if a.hide_env { &[] } else { a.env_fallback },
                     if a.hide_env { &[] } else { a.deprecated_env },
-                    if a.hide_default_value { &[] } else { a.default },
+                    if a.hide_default_value || a.sensitive {
+                        &[]
+                    } else {
+                        a.default
+                    },
                 );
                 return;
             }
@@ -976,7 +980,11 @@ fn short_help_with(
                 },
                 if a.hide_env { None } else { a.env },
                 environment.as_deref(),
-                if a.hide_default_value { &[] } else { a.default },
+                if a.hide_default_value || a.sensitive {
+                    &[]
+                } else {
+                    a.default
+                },
                 None,
             );
         },
@@ -1005,7 +1013,11 @@ fn short_help_with(
                 if f.hide_env { None } else { f.env },
                 if f.hide_env { &[] } else { f.env_fallback },
                 if f.hide_env { &[] } else { f.deprecated_env },
-                if f.hide_default_value { &[] } else { f.default },
+                if f.hide_default_value || f.sensitive {
+                    &[]
+                } else {
+                    f.default
+                },
             );
             flag_notes(out, f, 4);
             return;
@@ -1030,7 +1042,11 @@ fn short_help_with(
             },
             if f.hide_env { None } else { f.env },
             environment.as_deref(),
-            if f.hide_default_value { &[] } else { f.default },
+            if f.hide_default_value || f.sensitive {
+                &[]
+            } else {
+                f.default
+            },
             deprecation.as_deref(),
         );
     };
@@ -1209,7 +1225,7 @@ fn flat_commands_short(out: &mut String, path: &[&str], meta: &CommandMeta<'_>)
                     } else {
                         arg.deprecated_env
                     },
-                    if arg.hide_default_value {
+                    if arg.hide_default_value || arg.sensitive {
                         &[]
                     } else {
                         arg.default
@@ -1233,7 +1249,7 @@ fn flat_commands_short(out: &mut String, path: &[&str], meta: &CommandMeta<'_>)
                 },
                 if arg.hide_env { None } else { arg.env },
                 environment.as_deref(),
-                if arg.hide_default_value {
+                if arg.hide_default_value || arg.sensitive {
                     &[]
                 } else {
                     arg.default
@@ -1266,7 +1282,7 @@ fn flat_commands_short(out: &mut String, path: &[&str], meta: &CommandMeta<'_>)
                     } else {
                         flag.deprecated_env
                     },
-                    if flag.hide_default_value {
+                    if flag.hide_default_value || flag.sensitive {
                         &[]
                     } else {
                         flag.default
@@ -1296,7 +1312,7 @@ fn flat_commands_short(out: &mut String, path: &[&str], meta: &CommandMeta<'_>)
                 },
                 if flag.hide_env { None } else { flag.env },
                 environment.as_deref(),
-                if flag.hide_default_value {
+                if flag.hide_default_value || flag.sensitive {
                     &[]
                 } else {
                     flag.default
@@ -1648,7 +1664,11 @@ fn long_help_with(
                 if a.hide_env { None } else { a.env },
                 if a.hide_env { &[] } else { a.env_fallback },
                 if a.hide_env { &[] } else { a.deprecated_env },
-                if a.hide_default_value { &[] } else { a.default },
+                if a.hide_default_value || a.sensitive {
+                    &[]
+                } else {
+                    a.default
+                },
             );
         },
     );
@@ -1686,7 +1706,11 @@ fn long_help_with(
                 if f.hide_env { None } else { f.env },
                 if f.hide_env { &[] } else { f.env_fallback },
                 if f.hide_env { &[] } else { f.deprecated_env },
-                if f.hide_default_value { &[] } else { f.default },
+                if f.hide_default_value || f.sensitive {
+                    &[]
+                } else {
+                    f.default
+                },
             );
             flag_notes(out, f, 4);
         },
@@ -1713,7 +1737,11 @@ fn long_help_with(
                 if f.hide_env { None } else { f.env },
                 if f.hide_env { &[] } else { f.env_fallback },
                 if f.hide_env { &[] } else { f.deprecated_env },
-                if f.hide_default_value { &[] } else { f.default },
+                if f.hide_default_value || f.sensitive {
+                    &[]
+                } else {
+                    f.default
+                },
             );
             flag_notes(out, f, 4);
         },
@@ -2075,7 +2103,7 @@ fn flat_commands_long(out: &mut String, path: &[&str], meta: &CommandMeta<'_>, w
                 } else {
                     arg.deprecated_env
                 },
-                if arg.hide_default_value {
+                if arg.hide_default_value || arg.sensitive {
                     &[]
                 } else {
                     arg.default
@@ -2109,7 +2137,7 @@ fn flat_commands_long(out: &mut String, path: &[&str], meta: &CommandMeta<'_>, w
                 } else {
                     flag.deprecated_env
                 },
-                if flag.hide_default_value {
+                if flag.hide_default_value || flag.sensitive {
                     &[]
                 } else {
                     flag.default
@@ -8,16 +8,21 @@
 //!   line to `.zshrc` again is not, and a tool that owns a user's dotfiles has no undo to offer.
 //!   Where a shell needs a line somewhere anyway — zsh's `fpath+=`, PowerShell's dot-source — the
 //!   plan carries it as data for the caller to print.
-//! - **No shell detection.** The shell is named by the caller. `$SHELL` is the login shell, which is
-//!   not necessarily the one running, and a guess made here would be a guess owned here.
+//! - **No guessing the running shell.** The shell is named by the caller. `$SHELL` is the login
+//!   shell, which is not necessarily the one running, and a guess made here would be a guess owned
+//!   here. [`install_all`] asks a different question — which shells are on `PATH` at all — which
+//!   has an answer.
 //! - **No dependencies**, like the rest of this crate, and nothing on the parse path.
-//! - **No uninstall, no install-every-shell, no `$PROFILE` discovery.** All additive later; said
-//!   here so they arrive as requests rather than as bug reports.
+//! - **Nothing removed that this did not write.** [`uninstall`] deletes a script only when it
+//!   carries the generated marker, and leaves any line the install reported for the user to take
+//!   out.
+//! - **No `$PROFILE` discovery.** Additive later; said here so it arrives as a request rather than
+//!   as a bug report.
 //!
 //! The two layers are separate on purpose. [`plan`] resolves a target from a *described*
 //! environment and touches no filesystem, so every row of the table it holds is testable —
 //! including the Windows rows, on a machine that is not Windows. [`write`] is the thin half that
-//! creates directories and puts bytes in a file.
+//! creates directories and puts bytes in a file, and [`remove`] the thin half that takes one away.
 //!
 //! ```
 //! use usage_argv::complete::Shell;
@@ -251,6 +256,9 @@ fn file_name(shell: Shell, name: &str) -> String {
         Shell::Fish => format!("{name}.fish"),
         Shell::Nu => format!("{name}.nu"),
         Shell::PowerShell => format!("{name}.ps1"),
+        Shell::Elvish => format!("{name}.elv"),
+        // xonsh runs every `.xsh` in its `rc.d` at startup, in order, whatever it is called.
+        Shell::Xonsh => format!("{name}.xsh"),
     }
 }
 
@@ -378,6 +386,32 @@ fn sources(shell: Shell, platform: Platform) -> &'static [Source] {
         ),
     ];
 
+    // elvish keeps its configuration under `~/.config` on every Unix, macOS included, and under the
+    // roaming application data on Windows — the same two places it looks for `rc.elv`.
+    const ELVISH: &[Source] = &[
+        Source::var("XDG_CONFIG_HOME", &["elvish", "completions"]),
+        Source::var("HOME", &[".config", "elvish", "completions"]),
+    ];
+    const ELVISH_WINDOWS: &[Source] = &[
+        Source::var("APPDATA", &["elvish", "completions"]),
+        Source::var(
+            "USERPROFILE",
+            &["AppData", "Roaming", "elvish", "completions"],
+        ),
+    ];
+
+    // xonsh's `rc.d` is run at startup with no configuration, and it is under `~/.config` on every
+    // platform, Windows included.
+    const XONSH: &[Source] = &[
+        Source::var("XDG_CONFIG_HOME", &["xonsh", "rc.d"]),
+        Source::var("HOME", &[".config", "xonsh", "rc.d"]),
+    ];
+    const XONSH_WINDOWS: &[Source] = &[
+        Source::var("XDG_CONFIG_HOME", &["xonsh", "rc.d"]),
+        Source::var("HOME", &[".config", "xonsh", "rc.d"]),
+        Source::var("USERPROFILE", &[".config", "xonsh", "rc.d"]),
+    ];
+
     match (shell, platform) {
         (Shell::Bash, Windows) => BASH_WINDOWS,
         (Shell::Bash, Linux | MacOs) => BASH,
@@ -390,6 +424,10 @@ fn sources(shell: Shell, platform: Platform) -> &'static [Source] {
         (Shell::Nu, Linux) => NU,
         (Shell::PowerShell, Windows) => POWERSHELL_WINDOWS,
         (Shell::PowerShell, Linux | MacOs) => POWERSHELL,
+        (Shell::Elvish, Windows) => ELVISH_WINDOWS,
+        (Shell::Elvish, Linux | MacOs) => ELVISH,
+        (Shell::Xonsh, Windows) => XONSH_WINDOWS,
+        (Shell::Xonsh, Linux | MacOs) => XONSH,
     }
 }
 
@@ -463,7 +501,7 @@ fn is_absolute(value: &OsStr, platform: Platform) -> bool {
 fn loading(shell: Shell, resolved_from: &'static str, path: &Path) -> Loading {
     let dir = path.parent().unwrap_or(path);
     match shell {
-        Shell::Bash | Shell::Fish => Loading::Automatic,
+        Shell::Bash | Shell::Fish | Shell::Xonsh => Loading::Automatic,
         Shell::Zsh => Loading::Manual {
             line: format!(
                 "fpath+=({})\nautoload -Uz compinit && compinit",
@@ -493,6 +531,16 @@ fn loading(shell: Shell, resolved_from: &'static str, path: &Path) -> Loading {
                   dot-source the file. `New-Item -ItemType File -Force $PROFILE` first if there is \
                   no profile yet.",
         },
+        Shell::Elvish => Loading::Manual {
+            line: format!(
+                "eval (slurp < {})",
+                quote(shell, &path.display().to_string())
+            ),
+            file: "$runtime:rc-path".to_string(),
+            why: "elvish has no completion autoload directory, so rc.elv has to evaluate the \
+                  file. It is evaluated rather than `use`d because a module name cannot carry \
+                  every character a binary's name can.",
+        },
     }
 }
 
@@ -510,7 +558,11 @@ fn note(shell: Shell, resolved_from: &'static str) -> Option<&'static str> {
             "a config.nu that assigns $env.config wholesale after autoload replaces the completer \
              this script chained onto.",
         ),
-        Shell::Zsh | Shell::Fish | Shell::Nu | Shell::PowerShell => None,
+        Shell::Zsh | Shell::Fish | Shell::Nu | Shell::PowerShell | Shell::Elvish => None,
+        Shell::Xonsh => Some(
+            "xonsh reads rc.d only when a session starts, and never with --no-rc, so start a new \
+             one to see completion.",
+        ),
     }
 }
 
@@ -518,11 +570,12 @@ fn note(shell: Shell, resolved_from: &'static str) -> Option<&'static str> {
 ///
 /// Single quotes rather than double: a single-quoted string has nothing left to expand, so a `$` or
 /// a space in a home directory cannot change what the line means. The escape differs — POSIX shells
-/// and nushell end the string, add an escaped quote and start again; PowerShell doubles its own —
-/// and the two agree on every path containing no quote at all, which is almost all of them.
+/// and nushell end the string, add an escaped quote and start again; PowerShell and elvish double
+/// their own — and the two agree on every path containing no quote at all, which is almost all of
+/// them.
 fn quote(shell: Shell, text: &str) -> String {
     match shell {
-        Shell::PowerShell => format!("'{}'", text.replace('\'', "''")),
+        Shell::PowerShell | Shell::Elvish => format!("'{}'", text.replace('\'', "''")),
         _ => format!("'{}'", text.replace('\'', "'\\''")),
     }
 }
@@ -681,6 +734,215 @@ pub fn install_for(
     write(&plan, &script, on_foreign)
 }
 
+/// Every shell a completion script can be written for, in the order a report lists them.
+const EVERY_SHELL: [Shell; 7] = [
+    Shell::Bash,
+    Shell::Zsh,
+    Shell::Fish,
+    Shell::Nu,
+    Shell::PowerShell,
+    Shell::Elvish,
+    Shell::Xonsh,
+];
+
+/// The shells whose program is on the described environment's `PATH`.
+///
+/// Not the running shell — that is still the caller's to name — but which shells this machine has
+/// at all, which a `PATH` answers without a guess. Looks at the filesystem, unlike [`plan`]: whether
+/// `fish` is installed is not something a variable says.
+pub fn shells(env: &Env) -> Vec<Shell> {
+    EVERY_SHELL
+        .into_iter()
+        .filter(|shell| programs(*shell).iter().any(|program| on_path(program, env)))
+        .collect()
+}
+
+/// The names a shell's executable goes by.
+fn programs(shell: Shell) -> &'static [&'static str] {
+    match shell {
+        Shell::Bash => &["bash"],
+        Shell::Zsh => &["zsh"],
+        Shell::Fish => &["fish"],
+        Shell::Nu => &["nu"],
+        // Windows PowerShell is `powershell.exe`; the cross-platform one is `pwsh` everywhere.
+        Shell::PowerShell => &["pwsh", "powershell"],
+        Shell::Elvish => &["elvish"],
+        Shell::Xonsh => &["xonsh"],
+    }
+}
+
+/// Whether `program` is a file in some directory of the environment's `PATH`.
+///
+/// Split by the described platform's separator rather than `std::env::split_paths`, for the reason
+/// [`is_absolute`] does not ask the host either. A `PATH` that is not text finds nothing.
+fn on_path(program: &str, env: &Env) -> bool {
+    let Some(path) = env.get("PATH").and_then(OsStr::to_str) else {
+        return false;
+    };
+    let (separator, extensions) = if env.platform().is_windows() {
+        let pathext = env
+            .get("PATHEXT")
+            .and_then(OsStr::to_str)
+            .unwrap_or(".COM;.EXE;.BAT;.CMD");
+        (';', pathext.split(';').collect::<Vec<_>>())
+    } else {
+        (':', vec![""])
+    };
+    path.split(separator)
+        .filter(|dir| !dir.is_empty())
+        .any(|dir| {
+            extensions
+                .iter()
+                .any(|ext| Path::new(dir).join(format!("{program}{ext}")).is_file())
+        })
+}
+
+/// Where `bin`'s script goes for every shell on the described `PATH`. Writes nothing.
+///
+/// Planned as a whole: a shell with nowhere to put a script is an error for the set, so the report a
+/// preview prints is the install that would happen, not most of it.
+pub fn plan_all(bin: &str, env: &Env) -> Result<Vec<Plan>, Error> {
+    shells(env)
+        .into_iter()
+        .map(|shell| plan(bin, shell, env))
+        .collect()
+}
+
+/// Write one script per plan, having read every target first.
+///
+/// The many-shell form of [`write`]. A file this did not write refuses the whole set before anything
+/// is touched, so a refusal leaves every shell as it was rather than some of them upgraded.
+pub fn write_all(
+    scripts: &[(Plan, String)],
+    on_foreign: OnForeign,
+) -> Result<Vec<Installed>, Error> {
+    if on_foreign == OnForeign::Refuse {
+        for (plan, script) in scripts {
+            match std::fs::read(&plan.path) {
+                Ok(existing) if existing != script.as_bytes() && !ours(&existing) => {
+                    return Err(Error::Foreign {
+                        path: plan.path.clone(),
+                    })
+                }
+                // Anything else — absent, ours, or unreadable — is for `write` to decide, and to
+                // report in its own words.
+                _ => {}
+            }
+        }
+    }
+    scripts
+        .iter()
+        .map(|(plan, script)| write(plan, script, on_foreign))
+        .collect()
+}
+
+/// Plan, render and write for every shell on the described `PATH`.
+pub fn install_all(bin: &str, env: &Env, on_foreign: OnForeign) -> Result<Vec<Installed>, Error> {
+    let scripts: Vec<(Plan, String)> = plan_all(bin, env)?
+        .into_iter()
+        .map(|plan| {
+            let script = crate::script::script(bin, plan.shell);
+            (plan, script)
+        })
+        .collect();
+    write_all(&scripts, on_foreign)
+}
+
+/// What an uninstall found at the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Removed {
+    /// A script this had written was there, and is gone.
+    Deleted,
+    /// Nothing was there.
+    Absent,
+}
+
+/// What was removed, and from where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Uninstalled {
+    /// Where the script was looked for.
+    pub plan: Plan,
+    /// What was found there.
+    pub removed: Removed,
+}
+
+/// Delete the script at a planned path, if this wrote it.
+///
+/// Only a file carrying the generated marker is removed. Anything else is [`Error::Foreign`], and
+/// there is no `OnForeign` to pass: an install may overwrite a file once the user has seen the
+/// refusal, because it leaves a working script behind — a delete leaves nothing.
+///
+/// A line the install reported — zsh's `fpath+=`, PowerShell's dot-source — stays in the file the
+/// user put it in. [`Plan::loading`] still carries it, so a caller can say what to take out.
+pub fn remove(plan: &Plan) -> Result<Uninstalled, Error> {
+    let removed = match std::fs::read(&plan.path) {
+        Ok(existing) if ours(&existing) => {
+            io(
+                &plan.path,
+                Doing::Removing,
+                std::fs::remove_file(&plan.path),
+            )?;
+            Removed::Deleted
+        }
+        Ok(_) => {
+            return Err(Error::Foreign {
+                path: plan.path.clone(),
+            })
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Removed::Absent,
+        Err(e) => {
+            return Err(Error::Io {
+                path: plan.path.clone(),
+                doing: Doing::Reading,
+                source: e,
+            })
+        }
+    };
+    Ok(Uninstalled {
+        plan: plan.clone(),
+        removed,
+    })
+}
+
+/// Remove `bin`'s script for `shell` from where the described environment says it would be.
+pub fn uninstall(bin: &str, shell: Shell, env: &Env) -> Result<Uninstalled, Error> {
+    uninstall_for(bin, bin, shell, env)
+}
+
+/// The alias form of [`uninstall`].
+pub fn uninstall_for(bin: &str, name: &str, shell: Shell, env: &Env) -> Result<Uninstalled, Error> {
+    remove(&plan_for(bin, name, shell, env)?)
+}
+
+/// Remove `bin`'s script from every shell that has somewhere to keep one.
+///
+/// Every shell rather than the ones on `PATH`: a shell uninstalled since its script was written
+/// would otherwise leave that script behind for good. One with no location in the described
+/// environment is skipped, since nothing can have been installed there. As with [`write_all`], a
+/// file this did not write refuses the whole set before anything is deleted.
+pub fn uninstall_all(bin: &str, env: &Env) -> Result<Vec<Uninstalled>, Error> {
+    let mut plans = Vec::new();
+    for shell in EVERY_SHELL {
+        match plan(bin, shell, env) {
+            Ok(plan) => plans.push(plan),
+            Err(Error::NoBaseDir { .. }) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    for plan in &plans {
+        if let Ok(existing) = std::fs::read(&plan.path) {
+            if !ours(&existing) {
+                return Err(Error::Foreign {
+                    path: plan.path.clone(),
+                });
+            }
+        }
+    }
+    plans.iter().map(remove).collect()
+}
+
 /// Wrap a filesystem call with what it was doing and where.
 fn io<T>(path: &Path, doing: Doing, result: std::io::Result<T>) -> Result<T, Error> {
     result.map_err(|source| Error::Io {
@@ -690,7 +952,7 @@ fn io<T>(path: &Path, doing: Doing, result: std::io::Result<T>) -> Result<T, Err
     })
 }
 
-/// Why an install did not happen.
+/// Why an install or an uninstall did not happen.
 ///
 /// A type of its own rather than a variant of [`crate::Error`]: that one rides in the `Result` a
 /// successful parse returns, is 40 bytes, and borrows everything it holds. This owns a `PathBuf` and
@@ -712,7 +974,8 @@ pub enum Error {
         /// The variables that were looked at, in the order they were tried.
         tried: Vec<&'static str>,
     },
-    /// A file is already at the target path that this did not write.
+    /// A file is already at the target path that this did not write, so it was neither replaced
+    /// nor removed.
     ///
     /// Carries the path and not the file's contents: whatever is there is the user's, and a caller
     /// that wants to show it can read it itself.
@@ -731,7 +994,7 @@ pub enum Error {
     },
 }
 
-/// Which step of an install failed.
+/// Which step of an install or an uninstall failed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Doing {
@@ -741,6 +1004,8 @@ pub enum Doing {
     Reading,
     /// Writing the script, either beside the target or over it.
     Writing,
+    /// Deleting a script on uninstall.
+    Removing,
 }
 
 impl Doing {
@@ -750,6 +1015,7 @@ impl Doing {
             Doing::CreatingDir => "creating",
             Doing::Reading => "reading",
             Doing::Writing => "writing",
+            Doing::Removing => "removing",
         }
     }
 }
@@ -822,12 +1088,14 @@ mod tests {
         described(platform, &[("HOME", "/home/u")])
     }
 
-    const SHELLS: [Shell; 5] = [
+    const SHELLS: [Shell; 7] = [
         Shell::Bash,
         Shell::Zsh,
         Shell::Fish,
         Shell::Nu,
         Shell::PowerShell,
+        Shell::Elvish,
+        Shell::Xonsh,
     ];
 
     const PLATFORMS: [Platform; 3] = [Platform::Linux, Platform::MacOs, Platform::Windows];
@@ -1038,6 +1306,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn elvish_reports_the_line_that_evaluates_its_file() {
+        let env = described(Platform::Linux, &[("HOME", "/home/o'brien")]);
+        let target = plan("ex", Shell::Elvish, &env).unwrap();
+        assert_eq!(
+            target.path,
+            PathBuf::from("/home/o'brien/.config/elvish/completions/ex.elv")
+        );
+        let Loading::Manual { line, file, .. } = &target.loading else {
+            panic!("elvish has no autoload directory: {target:?}");
+        };
+        // elvish writes a quote inside single quotes by doubling it, as PowerShell does.
+        assert_eq!(
+            line,
+            "eval (slurp < '/home/o''brien/.config/elvish/completions/ex.elv')"
+        );
+        assert_eq!(file, "$runtime:rc-path");
+    }
+
+    #[test]
+    fn xonsh_loads_from_its_rc_directory_without_being_told() {
+        let target = plan("ex", Shell::Xonsh, &home(Platform::Linux)).unwrap();
+        assert_eq!(
+            target.path,
+            PathBuf::from("/home/u/.config/xonsh/rc.d/ex.xsh")
+        );
+        assert_eq!(target.loading, Loading::Automatic);
+        // But only for a session that starts afterwards, which is worth saying.
+        assert!(target.note.is_some());
+
+        let xdg = described(
+            Platform::MacOs,
+            &[("HOME", "/Users/u"), ("XDG_CONFIG_HOME", "/Users/u/cfg")],
+        );
+        let target = plan("ex", Shell::Xonsh, &xdg).unwrap();
+        assert_eq!(target.path, PathBuf::from("/Users/u/cfg/xonsh/rc.d/ex.xsh"));
+        assert_eq!(target.resolved_from, "XDG_CONFIG_HOME");
+    }
+
+    #[test]
+    fn no_path_finds_no_shell_and_plans_nothing() {
+        // Not every shell "just in case": an install for a shell nobody has is a directory
+        // created in a home for nothing.
+        let env = home(Platform::Linux);
+        assert_eq!(shells(&env), []);
+        assert_eq!(plan_all("ex", &env).unwrap(), []);
+    }
+
     #[test]
     fn windows_plans_land_under_the_windows_variables() {
         let env = described(
@@ -1061,6 +1377,8 @@ mod tests {
                 Shell::PowerShell,
                 ("LOCALAPPDATA", r"C:\Users\u\AppData\Local"),
             ),
+            (Shell::Elvish, ("APPDATA", r"C:\Users\u\AppData\Roaming")),
+            (Shell::Xonsh, ("USERPROFILE", r"C:\Users\u")),
         ] {
             let (var, base) = expected;
             let target = plan("ex", shell, &env).unwrap();
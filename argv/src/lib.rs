@@ -904,6 +904,36 @@ pub const fn concat_args<const N: usize>(
     out
 }
 
+/// Join groups of subcommand tables into one, at compile time.
+///
+/// For a command whose subcommands come from more than one place — a root that asked for a
+/// generated `config` command holds it beside its own enum's, and neither expansion can see
+/// the other's list. See [`concat_flags`] for the shape.
+pub const fn concat_commands<const N: usize>(
+    groups: &[&[&'static Command<'static>]],
+) -> [&'static Command<'static>; N] {
+    static PLACEHOLDER: Command<'static> = Command::EMPTY;
+    let mut out = [&PLACEHOLDER; N];
+    let mut at = 0;
+    let mut g = 0;
+    while g < groups.len() {
+        let group = groups[g];
+        let mut i = 0;
+        while i < group.len() {
+            out[at] = group[i];
+            at += 1;
+            i += 1;
+        }
+        g += 1;
+    }
+    assert!(
+        at == N,
+        "`N` must be `table_len` of the same groups, or the table would keep a placeholder \
+         that answers to nothing"
+    );
+    out
+}
+
 /// The key `--help` answers to, and the one `-h` does.
 ///
 /// Reserved rather than generated: a derive builds keys from a hash of the type they came from
@@ -2138,10 +2168,7 @@ impl<'t: 'v, 'a, 'v> Parser<'t, 'a, 'v> {
             if required_after == 0 {
                 return;
             }
-            let remaining_values = 1 + self.argv[self.pos..]
-                .iter()
-                .filter(|word| self.flags_stopped || !is_flag_like(bytes(word)))
-                .count();
+            let remaining_values = 1 + self.positional_words_ahead();
             if remaining_values > required_after {
                 return;
             }
@@ -2149,6 +2176,46 @@ impl<'t: 'v, 'a, 'v> Parser<'t, 'a, 'v> {
         }
     }
 
+    /// How many of the words after this one would bind to a positional.
+    ///
+    /// A word that is a known flag's separate value is not one: `optional required -f x` has one
+    /// positional left after `optional`, not two. Only as precise as a look-ahead can be — a flag
+    /// this command does not know is taken to have no value.
+    fn positional_words_ahead(&self) -> usize {
+        let mut count = 0;
+        let mut stopped = self.flags_stopped;
+        let mut words = self.argv[self.pos..].iter();
+        while let Some(word) = words.next() {
+            let word = bytes(word);
+            if stopped || !is_flag_like(word) {
+                count += 1;
+            } else if word == b"--" {
+                stopped = true;
+            } else if self.value_is_the_next_word(word) {
+                words.next();
+            }
+        }
+        count
+    }
+
+    /// Whether a flag-like word leaves its value to the word after it.
+    fn value_is_the_next_word(&self, word: &[u8]) -> bool {
+        if let Some(long) = word.strip_prefix(b"--") {
+            return !long.contains(&b'=') && self.find_long(long).is_some_and(|f| f.takes_value);
+        }
+        // A short cluster: the first flag that takes a value takes the rest of the word, and only
+        // when there is no rest does it take the next one.
+        let shorts = &word[1..];
+        for (i, byte) in shorts.iter().enumerate() {
+            match self.find_short(*byte) {
+                Some(flag) if flag.takes_value => return i + 1 == shorts.len(),
+                Some(_) => {}
+                None => return false,
+            }
+        }
+        false
+    }
+
     #[cfg(feature = "spec")]
     fn view_allows_own_flag(&self, flag: &Flag<'_>) -> bool {
         match self.view {
@@ -2662,6 +2729,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_flags_value_is_not_counted_as_a_positional_still_to_come() {
+        static FILE: Flag = Flag {
+            key: 92,
+            name: "file",
+            longs: &["file"],
+            shorts: b"f",
+            ..Flag::VALUE
+        };
+        static OPTIONAL: Arg = Arg {
+            key: 90,
+            name: "optional",
+            required: false,
+            ..Arg::REQUIRED
+        };
+        static REQUIRED: Arg = Arg {
+            key: 91,
+            name: "required",
+            ..Arg::REQUIRED
+        };
+        static CMD: Command = Command {
+            name: "ex",
+            flags: &[&FILE],
+            args: &[&OPTIONAL, &REQUIRED],
+            allow_missing_positional: true,
+            ..Command::EMPTY
+        };
+
+        for words in [["value", "-f", "spec.kdl"], ["value", "--file", "spec.kdl"]] {
+            let a = argv(words);
+            assert_eq!(
+                parse(&CMD, &a).unwrap()[0],
+                Event::Arg {
+                    arg: &REQUIRED,
+                    value: b"value",
+                    delimit: true
+                },
+                "{words:?}"
+            );
+        }
+        // An attached value leaves the next word a positional.
+        let a = argv(["one", "--file=spec.kdl", "two"]);
+        assert_eq!(
+            parse(&CMD, &a).unwrap()[0],
+            Event::Arg {
+                arg: &OPTIONAL,
+                value: b"one",
+                delimit: true
+            }
+        );
+    }
+
     #[test]
     fn negative_numbers_are_narrowly_opted_in() {
         static PLAIN: Flag = Flag {
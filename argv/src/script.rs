@@ -10,9 +10,11 @@
 //! spec files by age, and shell out to `usage complete-word` on every Tab. None of that is here.
 //!
 //! Where a shell already knows how to do something, it does it: zsh's `compadd` with aligned
-//! descriptions, fish's `__fish_complete_path`, PowerShell's `CompletionCompleters`. The one place
-//! this file argues with a shell is bash, which needs `compopt -o filenames` set from inside the
-//! function because whether paths are involved is not known until the answer comes back.
+//! descriptions, fish's `__fish_complete_path`, PowerShell's `CompletionCompleters`, elvish's
+//! `edit:complete-filename`. The one place this file argues with a shell is bash, which needs
+//! `compopt -o filenames` set from inside the function because whether paths are involved is not
+//! known until the answer comes back. xonsh has no path completer a completer can call, so its
+//! script globs for itself.
 
 use crate::complete::Shell;
 
@@ -89,20 +91,22 @@ pub fn script_for(bin: &str, name: &str, shell: Shell) -> String {
         Shell::Bash => bash(bin, name),
         Shell::Zsh => zsh(bin, name),
         Shell::Fish => fish(bin, name),
-        Shell::Nu => nu(bin, name, &nu_ident(name)),
+        Shell::Nu => nu(bin, name, &ident(name)),
         Shell::PowerShell => powershell(bin, name),
+        Shell::Elvish => elvish(bin, name),
+        Shell::Xonsh => xonsh(bin, name, &ident(name)),
     }
 }
 
-/// A nushell identifier for a binary's name, one name to one identifier.
+/// An identifier for a binary's name, one name to one identifier, for nushell and xonsh.
 ///
-/// The other four shells take a binary's name verbatim as part of a function name — bash, zsh and
-/// fish all accept `-`, `.` and `+` there, which is worth knowing because sanitizing them away is
-/// what makes two names collide. nushell binds a variable, where `-` would be read as
-/// subtraction, so its name has to be escaped rather than flattened: flattening mapped `foo-bar`
-/// and `foo+bar` both to `foo_bar`, and two scripts loaded together would each have completed the
-/// other's binary.
-fn nu_ident(bin: &str) -> String {
+/// bash, zsh and fish take a binary's name verbatim as part of a function name — all three accept
+/// `-`, `.` and `+` there, which is worth knowing because sanitizing them away is what makes two
+/// names collide. nushell binds a variable, where `-` would be read as subtraction, and xonsh
+/// defines a Python function, so there the name has to be escaped rather than flattened:
+/// flattening mapped `foo-bar` and `foo+bar` both to `foo_bar`, and two scripts loaded together
+/// would each have completed the other's binary.
+fn ident(bin: &str) -> String {
     let mut out = String::with_capacity(bin.len());
     for c in bin.chars() {
         if c.is_ascii_alphanumeric() {
@@ -472,6 +476,157 @@ Register-ArgumentCompleter -Native -CommandName '{name}' -ScriptBlock {{
     )
 }
 
+fn elvish(bin: &str, name: &str) -> String {
+    let head = header(bin, Shell::Elvish, "#");
+    // elvish hands an argument completer the words, already unquoted, and nothing of the line
+    // they came from — so, as for nushell, the line is put back together. Each word is quoted the
+    // way elvish itself quotes, so `--shell elvish` splits it back into the same words.
+    format!(
+        r#"{head}
+use os
+use path
+use re
+use str
+
+set edit:completion:arg-completer['{name}'] = {{|@words|
+    var line = (str:join ' ' [(each {{|word|
+        if (re:match '^$|[\s''"]' $word) {{ put "'"(str:replace "'" "''" $word)"'" }} else {{ put $word }}
+    }} $words)])
+    var cmd = (external '{bin}')
+    var out = [(try {{ $cmd __complete_word__ --shell elvish --line $line 2>$os:dev-null }} catch {{ }})]
+
+    var files = ''
+    for entry $out {{
+        if (eq $entry "\x01files") {{
+            set files = any
+        }} elif (eq $entry "\x01dirs") {{
+            set files = dirs
+        }} elif (eq $entry "\x01executables") {{
+            set files = executables
+        }} elif (eq $entry "\x01commands") {{
+            set files = commands
+        }} elif (not-eq $entry '') {{
+            var parts = [(str:split &max=2 "\t" $entry)]
+            if (and (> (count $parts) 1) (not-eq $parts[1] '')) {{
+                edit:complex-candidate $parts[0] &display=$parts[0]' -- '$parts[1]
+            }} else {{
+                edit:complex-candidate $parts[0]
+            }}
+        }}
+    }}
+
+    # elvish's own filename completion, filtered where only some paths belong. Candidates are
+    # quoted by elvish on the way to the prompt, here and above, so nothing is quoted twice.
+    var seed = $words[-1]
+    if (eq $files commands) {{
+        for dir $paths {{
+            put $dir/$seed*[nomatch-ok] | each {{|candidate|
+                if (and (not (path:is-dir $candidate)) (has-external $candidate)) {{
+                    edit:complex-candidate (path:base $candidate)
+                }}
+            }}
+        }}
+    }} elif (not-eq $files '') {{
+        edit:complete-filename $seed | each {{|candidate|
+            var stem = $candidate[stem]
+            if (or (eq $files any) (path:is-dir $stem)) {{
+                put $candidate
+            }} elif (and (eq $files executables) (has-external (path:abs $stem))) {{
+                put $candidate
+            }}
+        }}
+    }}
+}}
+"#
+    )
+}
+
+fn xonsh(bin: &str, name: &str, ident: &str) -> String {
+    let head = header(bin, Shell::Xonsh, "#");
+    // Plain Python, no xonsh syntax, so that any Python can at least parse it. xonsh hands a
+    // command completer each word as it was typed, quotes included, so joining them gives back a
+    // line that `--shell xonsh` splits the way xonsh did.
+    format!(
+        r#"{head}
+import glob
+import os
+import subprocess
+
+from xonsh.built_ins import XSH
+from xonsh.completers.completer import add_one_completer
+from xonsh.completers.tools import RichCompletion, contextual_command_completer_for
+
+
+@contextual_command_completer_for('{name}')
+def _usage_complete_{ident}(command):
+    typed = command.opening_quote + command.prefix
+    words = [arg.raw_value for arg in command.args[: command.arg_index]]
+    line = " ".join(words + [typed])
+    # xonsh's own environment rather than the process's, which it does not keep up to date.
+    env = XSH.env.detype()
+    try:
+        out = subprocess.run(
+            ['{bin}', '__complete_word__', '--shell', 'xonsh', '--line', line],
+            capture_output=True,
+            text=True,
+            check=True,
+            env=env,
+        ).stdout
+    except (OSError, subprocess.CalledProcessError):
+        return None
+
+    def candidate(value, description="", space=True):
+        # A value xonsh would split in two is inserted as a Python string, which it reads whole.
+        needs_quotes = any(c.isspace() or c in "'\"" for c in value)
+        return RichCompletion(
+            repr(value) if needs_quotes else value,
+            display=value,
+            description=description,
+            prefix_len=len(typed),
+            append_space=space,
+        )
+
+    files = None
+    found = set()
+    for entry in out.splitlines():
+        if entry.startswith("\x01"):
+            files = entry[1:]
+        elif entry:
+            value, _, description = entry.partition("\t")
+            found.add(candidate(value, description))
+
+    if files in ("files", "dirs", "executables"):
+        for path in glob.glob(glob.escape(os.path.expanduser(command.prefix)) + "*"):
+            is_dir = os.path.isdir(path)
+            if files == "dirs" and not is_dir:
+                continue
+            if files == "executables" and not (is_dir or os.access(path, os.X_OK)):
+                continue
+            found.add(candidate(path + os.sep if is_dir else path, space=not is_dir))
+    elif files == "commands":
+        for directory in env.get("PATH", "").split(os.pathsep):
+            try:
+                names = os.listdir(directory)
+            except OSError:
+                continue
+            for found_name in names:
+                path = os.path.join(directory, found_name)
+                if (
+                    found_name.startswith(command.prefix)
+                    and not os.path.isdir(path)
+                    and os.access(path, os.X_OK)
+                ):
+                    found.add(candidate(found_name))
+    return found
+
+
+# At the start, ahead of xonsh's own path completer, which would otherwise answer first with
+# files wherever this CLI has something better to say.
+add_one_completer('usage_{ident}', _usage_complete_{ident}, 'start')
+"#
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -484,6 +639,8 @@ mod tests {
             Shell::Fish,
             Shell::Nu,
             Shell::PowerShell,
+            Shell::Elvish,
+            Shell::Xonsh,
         ] {
             let out = script("mise", shell);
             assert!(out.contains("mise"), "{shell:?}");
@@ -511,6 +668,12 @@ mod tests {
                 "-CommandName 'm'",
                 "& 'mise' __complete_word__",
             ),
+            (Shell::Elvish, "arg-completer['m']", "(external 'mise')"),
+            (
+                Shell::Xonsh,
+                "contextual_command_completer_for('m')",
+                "['mise', '__complete_word__'",
+            ),
         ];
         for (shell, registration, invocation) in cases {
             let out = script_for("mise", "m", shell);
@@ -602,8 +765,14 @@ mod tests {
         // `foo-bar` and `foo+bar` are two binaries. Flattening both to `foo_bar` meant that with
         // two scripts loaded, completing one ran the other's completer — so bash, zsh and fish
         // take the name verbatim (all three accept these characters in a function name) and
-        // nushell, which cannot, escapes rather than flattens.
-        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Nu] {
+        // nushell and xonsh, which cannot, escape rather than flatten.
+        for shell in [
+            Shell::Bash,
+            Shell::Zsh,
+            Shell::Fish,
+            Shell::Nu,
+            Shell::Xonsh,
+        ] {
             let dash = script("foo-bar", shell);
             let plus = script("foo+bar", shell);
             assert_ne!(dash, plus, "{shell:?} generated the same script for both");
@@ -612,11 +781,11 @@ mod tests {
             assert!(!plus.contains("foo-bar"), "{shell:?} plus: {plus}");
         }
 
-        assert_eq!(nu_ident("foo-bar"), "foo_x2dbar");
-        assert_eq!(nu_ident("foo+bar"), "foo_x2bbar");
+        assert_eq!(ident("foo-bar"), "foo_x2dbar");
+        assert_eq!(ident("foo+bar"), "foo_x2bbar");
         // The underscore is escaped too, so a name cannot spell an escape and collide with what
         // it stands for.
-        assert_ne!(nu_ident("foo_x2dbar"), nu_ident("foo-bar"));
+        assert_ne!(ident("foo_x2dbar"), ident("foo-bar"));
     }
 
     #[test]
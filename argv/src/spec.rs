@@ -683,6 +683,35 @@ pub const fn concat_arg_metas<const N: usize>(
     out
 }
 
+/// Join groups of subcommand metadata into one, at compile time.
+///
+/// See [`concat_commands`](crate::concat_commands); the two are joined in the same order,
+/// because help and the emitted spec walk the metadata the parser's table describes.
+pub const fn concat_command_metas<const N: usize>(
+    groups: &[&[&'static CommandMeta<'static>]],
+) -> [&'static CommandMeta<'static>; N] {
+    static PLACEHOLDER: CommandMeta<'static> = CommandMeta::EMPTY;
+    let mut out = [&PLACEHOLDER; N];
+    let mut at = 0;
+    let mut g = 0;
+    while g < groups.len() {
+        let group = groups[g];
+        let mut i = 0;
+        while i < group.len() {
+            out[at] = group[i];
+            at += 1;
+            i += 1;
+        }
+        g += 1;
+    }
+    assert!(
+        at == N,
+        "`N` must be `table_len` of the same groups, or the metadata would describe a \
+         command that does not exist"
+    );
+    out
+}
+
 /// Join command alias lists at compile time.
 ///
 /// An `Args` struct can declare aliases belonging to the command itself, while the
@@ -980,6 +1009,9 @@ pub struct FlagMeta<'a> {
     pub hide_possible_values: bool,
     pub hide_short_help: bool,
     pub hide_long_help: bool,
+    /// A secret: help leaves its default and environment value out, and an error about a value
+    /// it was given does not quote it.
+    pub sensitive: bool,
     /// Whether repetition is counted rather than collected, as in `-vvv`.
     pub count: bool,
     /// What answers for this flag's value when a shell asks.
@@ -1078,6 +1110,7 @@ impl FlagMeta<'_> {
         hide_possible_values: false,
         hide_short_help: false,
         hide_long_help: false,
+        sensitive: false,
         count: false,
         repeatable: false,
         var_min: None,
@@ -1165,6 +1198,8 @@ pub struct ArgMeta<'a> {
     pub hide_possible_values: bool,
     pub hide_short_help: bool,
     pub hide_long_help: bool,
+    /// A secret, as [`FlagMeta::sensitive`].
+    pub sensitive: bool,
     /// Entries that cannot be given alongside this positional.
     pub conflicts: &'a [&'a str],
     pub requires: &'a [&'a str],
@@ -1215,6 +1250,7 @@ impl ArgMeta<'_> {
         hide_possible_values: false,
         hide_short_help: false,
         hide_long_help: false,
+        sensitive: false,
         conflicts: &[],
         requires: &[],
         required_if: &[],
@@ -2035,6 +2071,9 @@ fn write_flag(out: &mut String, meta: &FlagMeta<'_>, depth: usize) -> core::fmt:
         meta.hide_short_help,
         meta.hide_long_help,
     )?;
+    if meta.sensitive {
+        out.push_str(" sensitive=#true");
+    }
     if meta.count {
         out.push_str(" count=#true");
     }
@@ -2356,6 +2395,9 @@ fn write_arg(out: &mut String, meta: &ArgMeta<'_>, depth: usize) -> core::fmt::R
         meta.hide_short_help,
         meta.hide_long_help,
     )?;
+    if meta.sensitive {
+        out.push_str(" sensitive=#true");
+    }
     if meta.conflicts.len() == 1 {
         write!(out, " conflicts={}", quoted(meta.conflicts[0]))?;
     }
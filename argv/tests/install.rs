@@ -17,7 +17,10 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use usage_argv::complete::Shell;
-use usage_argv::install::{install, plan, Env, Error, OnForeign, Platform, Wrote};
+use usage_argv::install::{
+    install, install_all, plan, plan_all, shells, uninstall, uninstall_all, Env, Error, OnForeign,
+    Platform, Removed, Wrote,
+};
 use usage_argv::script::script;
 
 /// A directory of this test's own, and an environment that sends every shell into it.
@@ -74,12 +77,14 @@ impl Drop for Fixture {
     }
 }
 
-const SHELLS: [Shell; 5] = [
+const SHELLS: [Shell; 7] = [
     Shell::Bash,
     Shell::Zsh,
     Shell::Fish,
     Shell::Nu,
     Shell::PowerShell,
+    Shell::Elvish,
+    Shell::Xonsh,
 ];
 
 /// Whether a program is on `PATH`, so a missing shell is skipped rather than failing the suite.
@@ -222,6 +227,128 @@ fn a_finished_install_leaves_no_temporary_files_behind() {
     assert_eq!(entries, vec!["ex.fish".to_string()], "{entries:?}");
 }
 
+impl Fixture {
+    /// This environment, with a `PATH` holding a stand-in for each of `programs` and nothing else.
+    fn env_with_shells(&self, programs: &[&str]) -> Env {
+        let bin = self.dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        for program in programs {
+            let path = bin.join(if cfg!(windows) {
+                format!("{program}.exe")
+            } else {
+                (*program).to_string()
+            });
+            fs::write(&path, "").unwrap();
+            make_executable(&path);
+        }
+        self.env().with("PATH", bin)
+    }
+}
+
+#[test]
+fn install_all_writes_for_the_shells_on_path_and_no_others() {
+    let fixture = Fixture::new("all");
+    let env = fixture.env_with_shells(&["fish", "zsh", "pwsh"]);
+    assert_eq!(shells(&env), [Shell::Zsh, Shell::Fish, Shell::PowerShell]);
+
+    let done = install_all("ex", &env, OnForeign::Refuse).unwrap();
+    let written: Vec<Shell> = done.iter().map(|d| d.plan.shell).collect();
+    assert_eq!(written, [Shell::Zsh, Shell::Fish, Shell::PowerShell]);
+    for shell in SHELLS {
+        assert_eq!(
+            fixture.path_for(shell).exists(),
+            written.contains(&shell),
+            "{shell:?}"
+        );
+    }
+}
+
+#[test]
+fn planning_every_shell_writes_nothing() {
+    let fixture = Fixture::new("plan-all");
+    let env = fixture.env_with_shells(&["bash", "xonsh"]);
+    let plans = plan_all("ex", &env).unwrap();
+    assert_eq!(
+        plans.iter().map(|p| p.shell).collect::<Vec<_>>(),
+        [Shell::Bash, Shell::Xonsh]
+    );
+    assert!(plans.iter().all(|p| !p.path.exists()));
+}
+
+#[test]
+fn one_foreign_file_stops_install_all_before_anything_is_written() {
+    let fixture = Fixture::new("all-foreign");
+    let env = fixture.env_with_shells(&["bash", "fish"]);
+    let theirs = fixture.path_for(Shell::Fish);
+    fs::create_dir_all(theirs.parent().unwrap()).unwrap();
+    fs::write(&theirs, "complete -c ex -a mine\n").unwrap();
+
+    let err = install_all("ex", &env, OnForeign::Refuse).unwrap_err();
+    assert!(
+        matches!(&err, Error::Foreign { path } if path == &theirs),
+        "{err:?}"
+    );
+    // bash comes first, and was still not written: a refusal leaves the whole set as it was.
+    assert!(!fixture.path_for(Shell::Bash).exists());
+    assert_eq!(
+        fs::read_to_string(&theirs).unwrap(),
+        "complete -c ex -a mine\n"
+    );
+}
+
+#[test]
+fn uninstall_removes_what_install_wrote() {
+    let fixture = Fixture::new("uninstall");
+    let done = install("ex", Shell::Zsh, &fixture.env(), OnForeign::Refuse).unwrap();
+
+    let gone = uninstall("ex", Shell::Zsh, &fixture.env()).unwrap();
+    assert_eq!(gone.removed, Removed::Deleted);
+    assert!(!done.plan.path.exists());
+
+    // Again, there is nothing to do, and saying so is not an error.
+    let again = uninstall("ex", Shell::Zsh, &fixture.env()).unwrap();
+    assert_eq!(again.removed, Removed::Absent);
+}
+
+#[test]
+fn uninstall_leaves_a_file_this_did_not_write() {
+    let fixture = Fixture::new("uninstall-foreign");
+    let target = fixture.path_for(Shell::Bash);
+    fs::create_dir_all(target.parent().unwrap()).unwrap();
+    fs::write(&target, "complete -W mine ex\n").unwrap();
+
+    let err = uninstall("ex", Shell::Bash, &fixture.env()).unwrap_err();
+    assert!(
+        matches!(&err, Error::Foreign { path } if path == &target),
+        "{err:?}"
+    );
+    assert_eq!(
+        fs::read_to_string(&target).unwrap(),
+        "complete -W mine ex\n"
+    );
+}
+
+#[test]
+fn uninstall_all_reaches_shells_that_are_no_longer_on_path() {
+    let fixture = Fixture::new("uninstall-all");
+    install("ex", Shell::Fish, &fixture.env(), OnForeign::Refuse).unwrap();
+    install("ex", Shell::Nu, &fixture.env(), OnForeign::Refuse).unwrap();
+
+    // No shells on `PATH` at all: the scripts were written when there were.
+    let env = fixture.env_with_shells(&[]);
+    let done = uninstall_all("ex", &env).unwrap();
+    assert_eq!(done.len(), SHELLS.len());
+    for gone in &done {
+        let expected = if matches!(gone.plan.shell, Shell::Fish | Shell::Nu) {
+            Removed::Deleted
+        } else {
+            Removed::Absent
+        };
+        assert_eq!(gone.removed, expected, "{:?}", gone.plan.shell);
+        assert!(!gone.plan.path.exists());
+    }
+}
+
 #[test]
 fn an_installed_zsh_script_is_named_what_compinit_will_look_for() {
     // The path table and the script's own first line are two halves of one contract, and each is
@@ -469,3 +469,49 @@ fn the_zsh_script_works_however_it_was_installed() {
     assert!(script_text.contains("#compdef ex"), "{script_text}");
     assert!(script_text.contains("_ex() {"), "{script_text}");
 }
+
+/// xonsh's script is written as plain Python, so any Python can say whether it parses — which is
+/// the check `every_script_is_valid_in_its_own_shell` makes for the shells that have one.
+#[test]
+fn the_xonsh_script_is_plain_python() {
+    if !available("python3") {
+        println!("python3 is not installed; skipping");
+        return;
+    }
+    let fixture = Fixture::new("xonsh-syntax", Shell::Xonsh, "");
+    let out = Command::new("python3")
+        .args(["-c", "import ast, sys; ast.parse(open(sys.argv[1]).read())"])
+        .arg(fixture.dir.join("script"))
+        .output()
+        .expect("running python3");
+    assert!(
+        out.status.success(),
+        "the xonsh script does not parse as Python:\n{}\n--- script ---\n{}",
+        String::from_utf8_lossy(&out.stderr),
+        script("ex", Shell::Xonsh)
+    );
+}
+
+#[test]
+fn xonsh_offers_what_the_binary_answered() {
+    if !available("xonsh") {
+        println!("xonsh is not installed; skipping");
+        return;
+    }
+    let fixture = Fixture::new(
+        "xonsh-candidates",
+        Shell::Xonsh,
+        "install\tInstall a tool\nuninstall\tRemove it\n",
+    );
+    // The completer is called the way xonsh calls it, with the context of `ex i⌶`.
+    let out = fixture.run(
+        "xonsh",
+        r#"source ./script
+from xonsh.parsers.completion_context import CommandArg, CommandContext, CompletionContext
+ctx = CompletionContext(command=CommandContext(args=(CommandArg("ex"),), arg_index=1, prefix="i"))
+for c in sorted(_usage_complete_ex(ctx), key=str):
+    print(str(c) + "|" + c.description)
+"#,
+    );
+    assert_eq!(out, "install|Install a tool\nuninstall|Remove it\n");
+}
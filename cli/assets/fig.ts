@@ -150,7 +150,15 @@ const completionSpec: Fig.Spec = {
           isRepeatable: false,
           args: {
             name: "shell",
-            suggestions: ["bash", "fish", "nu", "powershell", "zsh"],
+            suggestions: [
+              "bash",
+              "elvish",
+              "fish",
+              "nu",
+              "powershell",
+              "xonsh",
+              "zsh",
+            ],
           },
         },
       ],
@@ -166,13 +174,27 @@ const completionSpec: Fig.Spec = {
       description:
         "Compare two usage specs and report what changed about the interface",
       options: [
+        {
+          name: "--against",
+          description:
+            "Compare against a git revision's copy of the spec, or the newest snapshot in a directory",
+          isRepeatable: false,
+          args: {
+            name: "against",
+          },
+        },
+        {
+          name: "--history",
+          description: "Walk every release rather than comparing two specs",
+          isRepeatable: false,
+        },
         {
           name: ["-f", "--format"],
           description: "Output format",
           isRepeatable: false,
           args: {
             name: "format",
-            suggestions: ["text", "json"],
+            suggestions: ["text", "json", "sarif", "github"],
           },
         },
         {
@@ -196,6 +218,7 @@ const completionSpec: Fig.Spec = {
         {
           name: "new",
           description: 'The spec as it is now, use "-" to read from stdin',
+          isOptional: true,
           template: "filepaths",
         },
       ],
@@ -317,15 +340,69 @@ const completionSpec: Fig.Spec = {
         },
       ],
     },
+    {
+      name: "fmt",
+      description: "Format spec files in the canonical layout",
+      options: [
+        {
+          name: "--check",
+          description:
+            "Print the files that are not formatted, and change nothing",
+          isRepeatable: false,
+        },
+      ],
+      args: {
+        name: "files",
+        description:
+          'Spec files to format, use "-" to read from stdin and write to stdout',
+        isVariadic: true,
+        template: "filepaths",
+      },
+    },
     {
       name: ["generate", "g"],
       description:
         "Generate completions, documentation, and other artifacts from usage specs",
       subcommands: [
+        {
+          name: "clap",
+          description:
+            "Scaffold a CLI's Rust source from a usage spec, for clap's derive",
+          options: [
+            {
+              name: ["-f", "--file"],
+              description:
+                'A usage spec taken in as a file, use "-" to read from stdin',
+              isRepeatable: false,
+              args: {
+                name: "file",
+                template: "filepaths",
+              },
+            },
+            {
+              name: ["-o", "--out-dir"],
+              description:
+                "Directory the modules are written to, the root's as mod.rs",
+              isRepeatable: false,
+              args: {
+                name: "out_dir",
+                template: "folders",
+              },
+            },
+            {
+              name: "--spec",
+              description: "Raw string spec input",
+              isRepeatable: false,
+              args: {
+                name: "spec",
+              },
+            },
+          ],
+        },
         {
           name: ["completion", "c"],
           description:
-            "Generate shell completion scripts for bash, fish, nu, powershell, or zsh",
+            "Generate shell completion scripts for bash, elvish, fish, nu, powershell, xonsh, or zsh",
           options: [
             {
               name: "--install",
@@ -339,6 +416,17 @@ const completionSpec: Fig.Spec = {
                 "Replace a file at the target path that usage did not write",
               isRepeatable: false,
             },
+            {
+              name: "--uninstall",
+              description:
+                "Remove the script an install wrote, instead of printing one",
+              isRepeatable: false,
+            },
+            {
+              name: "--all-shells",
+              description: "Every shell on PATH rather than one",
+              isRepeatable: false,
+            },
             {
               name: ["-f", "--file"],
               description:
@@ -381,7 +469,16 @@ const completionSpec: Fig.Spec = {
             {
               name: "shell",
               description: "Shell to generate completions for",
-              suggestions: ["bash", "fish", "nu", "powershell", "zsh"],
+              isOptional: true,
+              suggestions: [
+                "bash",
+                "elvish",
+                "fish",
+                "nu",
+                "powershell",
+                "xonsh",
+                "zsh",
+              ],
             },
             {
               name: "bin",
@@ -591,6 +688,22 @@ const completionSpec: Fig.Spec = {
                 name: "view",
               },
             },
+            {
+              name: ["-m", "--multi"],
+              description:
+                "Render a page per subcommand, git-style, with an index page for the whole tree",
+              isRepeatable: false,
+            },
+            {
+              name: "--out-dir",
+              description:
+                "Output pages to this directory (required when using --multi)",
+              isRepeatable: false,
+              args: {
+                name: "out_dir",
+                template: "folders",
+              },
+            },
             {
               name: ["-o", "--out-file"],
               description: 'Output file path, or "-" for stdout (default)',
@@ -608,6 +721,24 @@ const completionSpec: Fig.Spec = {
                 name: "section",
               },
             },
+            {
+              name: "--format",
+              description:
+                "Macro package to write: `man` for groff and man-db, `mdoc` for the BSDs and mandoc",
+              isRepeatable: false,
+              args: {
+                name: "format",
+                suggestions: ["man", "mdoc"],
+              },
+            },
+            {
+              name: "--date",
+              description: 'Date in the page header, e.g. "January 1, 2026"',
+              isRepeatable: false,
+              args: {
+                name: "date",
+              },
+            },
           ],
         },
         {
@@ -677,6 +808,41 @@ const completionSpec: Fig.Spec = {
             },
           ],
         },
+        {
+          name: "rust",
+          description:
+            "Scaffold a CLI's Rust source from a usage spec, for usage's derive",
+          options: [
+            {
+              name: ["-f", "--file"],
+              description:
+                'A usage spec taken in as a file, use "-" to read from stdin',
+              isRepeatable: false,
+              args: {
+                name: "file",
+                template: "filepaths",
+              },
+            },
+            {
+              name: ["-o", "--out-dir"],
+              description:
+                "Directory the modules are written to, the root's as mod.rs",
+              isRepeatable: false,
+              args: {
+                name: "out_dir",
+                template: "folders",
+              },
+            },
+            {
+              name: "--spec",
+              description: "Raw string spec input",
+              isRepeatable: false,
+              args: {
+                name: "spec",
+              },
+            },
+          ],
+        },
         {
           name: "sdk",
           description: "Generate a type-safe SDK from a usage spec",
@@ -696,7 +862,7 @@ const completionSpec: Fig.Spec = {
               isRepeatable: false,
               args: {
                 name: "language",
-                suggestions: ["typescript", "python"],
+                suggestions: ["typescript", "python", "go", "rust"],
               },
             },
             {
@@ -726,6 +892,117 @@ const completionSpec: Fig.Spec = {
             },
           ],
         },
+        {
+          name: "shell-parser",
+          description:
+            "Generate a shell prelude that parses a script's arguments without usage installed",
+          options: [
+            {
+              name: "--shell",
+              description: "Shell to write the prelude in",
+              isRepeatable: false,
+              args: {
+                name: "shell",
+                suggestions: ["bash", "fish", "posix", "zsh"],
+              },
+            },
+            {
+              name: ["-f", "--file"],
+              description:
+                'A usage spec taken in as a file, use "-" to read from stdin',
+              isRepeatable: false,
+              args: {
+                name: "file",
+                template: "filepaths",
+              },
+            },
+            {
+              name: ["-o", "--out-file"],
+              description:
+                'File path where the prelude will be saved, or "-" for stdout',
+              isRepeatable: false,
+              args: {
+                name: "out_file",
+                template: "filepaths",
+              },
+            },
+            {
+              name: "--spec",
+              description: "Raw string spec input",
+              isRepeatable: false,
+              args: {
+                name: "spec",
+              },
+            },
+          ],
+        },
+      ],
+    },
+    {
+      name: "import",
+      description:
+        "Write a usage spec from what an existing CLI says about itself",
+      subcommands: [
+        {
+          name: "help",
+          description:
+            "Write a usage spec for an existing CLI by reading its help",
+          options: [
+            {
+              name: ["-f", "--file"],
+              description: 'A captured help page, use "-" to read from stdin',
+              isRepeatable: false,
+              args: {
+                name: "file",
+                template: "filepaths",
+              },
+            },
+            {
+              name: ["-d", "--dir"],
+              description:
+                "A directory of captured help pages, a help.txt for each command",
+              isRepeatable: false,
+              args: {
+                name: "dir",
+                template: "folders",
+              },
+            },
+            {
+              name: "--bin",
+              description:
+                "The program's name, when it cannot be read from the help or the command",
+              isRepeatable: false,
+              args: {
+                name: "bin",
+              },
+            },
+            {
+              name: "--depth",
+              description: "How many levels of subcommands to follow",
+              isRepeatable: false,
+              args: {
+                name: "depth",
+              },
+            },
+            {
+              name: ["-o", "--out-file"],
+              description:
+                'File path where the spec will be saved, or "-" for stdout',
+              isRepeatable: false,
+              args: {
+                name: "out_file",
+                template: "filepaths",
+              },
+            },
+          ],
+          args: {
+            name: "command",
+            description:
+              "The command whose help to read, with any arguments it needs before a subcommand",
+            isOptional: true,
+            isVariadic: true,
+          },
+        },
       ],
     },
     {
@@ -738,7 +1015,7 @@ const completionSpec: Fig.Spec = {
           isRepeatable: false,
           args: {
             name: "format",
-            suggestions: ["text", "json"],
+            suggestions: ["text", "json", "sarif", "github"],
           },
         },
         {
@@ -752,6 +1029,22 @@ const completionSpec: Fig.Spec = {
             "Also check that subcommands and flags are declared in sorted order",
           isRepeatable: false,
         },
+        {
+          name: "--config",
+          description:
+            "A file holding a `lint` node, whose levels win over the spec's own",
+          isRepeatable: false,
+          args: {
+            name: "config",
+            template: "filepaths",
+          },
+        },
+        {
+          name: "--fix",
+          description:
+            "Fix what can be fixed in place, then report what remains",
+          isRepeatable: false,
+        },
       ],
       args: {
         name: "file",
@@ -759,6 +1052,10 @@ const completionSpec: Fig.Spec = {
         template: "filepaths",
       },
     },
+    {
+      name: "lsp",
+      description: "Serve a language server for usage spec files",
+    },
     {
       name: ["mcp", "mcp-server"],
       description: "Serve a usage spec over the Model Context Protocol",
@@ -780,6 +1077,16 @@ const completionSpec: Fig.Spec = {
             name: "spec",
           },
         },
+        {
+          name: "--max-effect",
+          description:
+            "Offer a `run_command` tool, for invocations whose effect is at most this",
+          isRepeatable: false,
+          args: {
+            name: "max_effect",
+            suggestions: ["read", "write", "destructive"],
+          },
+        },
       ],
     },
     {
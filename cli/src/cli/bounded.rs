@@ -0,0 +1,122 @@
+//! Running a program that was not written to be run this way.
+//!
+//! `mcp`'s `run_command` and `import help` both start programs someone else chose, and
+//! neither has a person to ask whether to keep waiting. One stuck or chatty child would
+//! otherwise hold the caller, and the whole of its output, for as long as it liked.
+
+use std::io::Read;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What a bounded child left behind.
+pub(crate) struct Ran {
+    /// `None` when it was killed for running past its time.
+    pub(crate) status: Option<ExitStatus>,
+    pub(crate) stdout: Captured,
+    pub(crate) stderr: Captured,
+}
+
+/// The start of one of a child's streams.
+#[derive(Default)]
+pub(crate) struct Captured {
+    pub(crate) bytes: Vec<u8>,
+    /// Whether there was more than was kept.
+    pub(crate) truncated: bool,
+}
+
+/// Run `argv`, killing it after `timeout` and keeping at most `limit` bytes of each stream.
+///
+/// Stdin is null, so a program that asks a question gets an end of file rather than waiting
+/// on a terminal, or on the transport of an MCP session.
+pub(crate) fn run(argv: &[String], timeout: Duration, limit: usize) -> std::io::Result<Ran> {
+    let mut child = Command::new(&argv[0])
+        .args(&argv[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (done, finished) = mpsc::channel();
+    let stdout = capture(child.stdout.take(), limit, done.clone());
+    let stderr = capture(child.stderr.take(), limit, done);
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status);
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    // A grandchild can hold a pipe open after the child is gone, so the readers get a moment
+    // to finish and no more: what they have read by then is what is returned.
+    let grace = Instant::now() + Duration::from_millis(200);
+    for _ in 0..2 {
+        let left = grace.saturating_duration_since(Instant::now());
+        if finished.recv_timeout(left).is_err() {
+            break;
+        }
+    }
+    let take = |captured: Arc<Mutex<Captured>>| {
+        std::mem::take(&mut *captured.lock().unwrap_or_else(|e| e.into_inner()))
+    };
+    Ok(Ran {
+        status,
+        stdout: take(stdout),
+        stderr: take(stderr),
+    })
+}
+
+/// Read `stream` on its own thread, keeping its first `limit` bytes.
+///
+/// The rest is read and thrown away rather than left in the pipe: a child writing into a full
+/// pipe blocks, and would sit there until the timeout killed it.
+fn capture(
+    stream: Option<impl Read + Send + 'static>,
+    limit: usize,
+    done: mpsc::Sender<()>,
+) -> Arc<Mutex<Captured>> {
+    let captured = Arc::new(Mutex::new(Captured::default()));
+    let Some(mut stream) = stream else {
+        let _ = done.send(());
+        return captured;
+    };
+    let into = captured.clone();
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        while let Ok(n @ 1..) = stream.read(&mut buf) {
+            let mut captured = into.lock().unwrap_or_else(|e| e.into_inner());
+            let room = limit.saturating_sub(captured.bytes.len());
+            captured.bytes.extend_from_slice(&buf[..n.min(room)]);
+            captured.truncated |= n > room;
+        }
+        let _ = done.send(());
+    });
+    captured
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn a_child_is_stopped_at_its_time_and_its_output_at_its_limit() {
+        let argv = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        let ran = run(&argv(&["sleep", "5"]), Duration::from_millis(100), 64).unwrap();
+        assert!(ran.status.is_none());
+
+        let ran = run(&argv(&["yes"]), Duration::from_millis(200), 10).unwrap();
+        assert_eq!(ran.stdout.bytes, b"y\ny\ny\ny\ny\n");
+        assert!(ran.stdout.truncated);
+
+        let ran = run(&argv(&["echo", "hi"]), Duration::from_secs(60), 1024).unwrap();
+        assert!(ran.status.is_some_and(|s| s.success()));
+        assert_eq!(ran.stdout.bytes, b"hi\n");
+        assert!(!ran.stdout.truncated);
+    }
+}
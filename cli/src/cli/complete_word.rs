@@ -78,7 +78,7 @@ pub struct CompleteWord {
     #[usage(
         long,
         default = "bash",
-        choices("bash", "fish", "nu", "powershell", "zsh")
+        choices("bash", "elvish", "fish", "nu", "powershell", "xonsh", "zsh")
     )]
     shell: String,
 }
@@ -754,7 +754,7 @@ impl usage_rs::Run for CompleteWord {
         for (c, description) in choices {
             match shell {
                 "bash" => println!("{c}"),
-                "fish" | "nu" | "powershell" => {
+                "elvish" | "fish" | "nu" | "powershell" | "xonsh" => {
                     if any_descriptions {
                         println!("{c}\t{description}")
                     } else {
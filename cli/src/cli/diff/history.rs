@@ -0,0 +1,428 @@
+//! `usage diff --history`: one spec across every release, and what each release did to
+//! each command, flag and config property.
+//!
+//! Two specs say what changed; a history says *when*, which is what a deprecation makes a
+//! promise about. `deprecated_remove_at` names the release a removal is due in, and the
+//! walk is the one place that sees both that promise and the release the removal actually
+//! came in — so it is where the two are checked against each other.
+//!
+//! Declarations are followed by name, not paired the way [`super::diff_specs`] pairs them:
+//! a renamed flag reads here as one removed and one added, and the pairwise diff of the two
+//! releases is what says it was a rename.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use indexmap::IndexMap;
+use usage::spec::SourceLocation;
+use usage::{Spec, SpecCommand};
+
+use super::revision::Release;
+use super::{primary_spelling, Changes, SpecChange};
+
+/// Every release walked, what happened to each declaration, and what broke a promise.
+#[derive(Debug, serde::Serialize)]
+pub struct History {
+    pub releases: Vec<String>,
+    pub timelines: Vec<Timeline>,
+    pub changes: Vec<SpecChange>,
+}
+
+/// One command, flag or config property, and the releases that changed it.
+#[derive(Debug, serde::Serialize)]
+pub struct Timeline {
+    /// The command it is declared under, as a finding is located.
+    pub location: String,
+    /// What it is, as a finding names it: `flag '--force'`.
+    pub subject: String,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct Event {
+    pub release: String,
+    pub kind: EventKind,
+    /// The release a deprecation said the removal would come in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Added,
+    Deprecated,
+    Undeprecated,
+    Removed,
+}
+
+impl fmt::Display for Timeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.subject)?;
+        for (i, event) in self.events.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            let kind = match event.kind {
+                EventKind::Added => "added",
+                EventKind::Deprecated => "deprecated",
+                EventKind::Undeprecated => "undeprecated",
+                EventKind::Removed => "removed",
+            };
+            write!(f, "{separator}{kind} in {}", event.release)?;
+            if let Some(at) = &event.remove_at {
+                write!(f, " for removal in {at}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A declaration as one release has it.
+struct Declaration {
+    location: String,
+    subject: String,
+    /// The full path of a command, which every declaration beneath it is under.
+    command: Option<String>,
+    deprecated: bool,
+    remove_at: Option<String>,
+    source: Option<SourceLocation>,
+}
+
+/// A declaration as the walk has followed it so far.
+struct Track {
+    timeline: Timeline,
+    command: Option<String>,
+    present: bool,
+    deprecated: bool,
+    remove_at: Option<String>,
+    /// Said once, at the first release that should not have had it, rather than at every
+    /// release after.
+    overdue: bool,
+    source: Option<SourceLocation>,
+}
+
+/// Walk `releases`, oldest first.
+///
+/// Findings are located by the newest release a declaration was in, which is the file a
+/// reader can open when that release is the spec they gave.
+pub fn walk(releases: &[Release]) -> History {
+    // Every release is located under the newest one's name for the binary, so renaming it
+    // does not read as every declaration being removed and added again.
+    let root = releases
+        .last()
+        .map(|r| root_of(&r.spec))
+        .unwrap_or_default();
+    let mut tracks: IndexMap<(String, String), Track> = IndexMap::new();
+    let mut c = Changes::default();
+
+    for release in releases {
+        let version = release.version.as_deref();
+        let declarations = declarations(&release.spec, &root);
+
+        for (key, now) in &declarations {
+            let track = tracks.entry(key.clone()).or_insert_with(|| Track {
+                timeline: Timeline {
+                    location: now.location.clone(),
+                    subject: now.subject.clone(),
+                    events: vec![],
+                },
+                command: now.command.clone(),
+                present: false,
+                deprecated: false,
+                remove_at: None,
+                overdue: false,
+                source: None,
+            });
+            // The newest spelling names it.
+            track.timeline.subject.clone_from(&now.subject);
+            track.source.clone_from(&now.source);
+            if !track.present {
+                track.present = true;
+                track.push(release, EventKind::Added, None);
+            }
+            match (track.deprecated, now.deprecated) {
+                (false, true) => track.push(release, EventKind::Deprecated, now.remove_at.clone()),
+                (true, false) => track.push(release, EventKind::Undeprecated, None),
+                _ => {}
+            }
+            track.deprecated = now.deprecated;
+            track.remove_at = now.remove_at.clone().filter(|_| now.deprecated);
+
+            if let (Some(at), Some(version)) = (&track.remove_at, version) {
+                if !track.overdue && reaches(version, at) {
+                    track.overdue = true;
+                    let subject = &track.timeline.subject;
+                    c.within(track.source.as_ref(), |c| {
+                        c.metadata(
+                            "removal-overdue",
+                            &track.timeline.location,
+                            format!(
+                                "{subject} is still in {}, though its deprecation said it would be removed in {at}",
+                                release.label
+                            ),
+                        )
+                    });
+                }
+            }
+        }
+
+        // Commands that went in this release: what was under one went with it, and the
+        // command's own finding says so once.
+        let removed_commands: Vec<String> = tracks
+            .iter()
+            .filter(|(key, t)| t.present && !declarations.contains_key(*key))
+            .filter_map(|(_, t)| t.command.clone())
+            .collect();
+        for (key, track) in tracks.iter_mut() {
+            if !track.present || declarations.contains_key(key) {
+                continue;
+            }
+            track.push(release, EventKind::Removed, None);
+            let location = &track.timeline.location;
+            let under_removed = removed_commands
+                .iter()
+                .any(|command| location == command || location.starts_with(&format!("{command} ")));
+            if !under_removed {
+                track.check_removal(release, version, &mut c);
+            }
+            track.present = false;
+            track.deprecated = false;
+            track.remove_at = None;
+            track.overdue = false;
+        }
+    }
+
+    c.changes.sort_by_key(|change| change.category);
+    History {
+        releases: releases.iter().map(|r| r.label.clone()).collect(),
+        timelines: tracks.into_values().map(|t| t.timeline).collect(),
+        changes: c.changes,
+    }
+}
+
+impl Track {
+    fn push(&mut self, release: &Release, kind: EventKind, remove_at: Option<String>) {
+        self.timeline.events.push(Event {
+            release: release.label.clone(),
+            kind,
+            remove_at,
+        });
+    }
+
+    /// A removal is breaking when nothing warned about it, or when it came sooner than the
+    /// warning said. Later than it said is already `removal-overdue`.
+    fn check_removal(&self, release: &Release, version: Option<&str>, c: &mut Changes) {
+        let subject = &self.timeline.subject;
+        let location = &self.timeline.location;
+        let label = &release.label;
+        c.within(self.source.as_ref(), |c| {
+            if !self.deprecated {
+                c.breaking(
+                    "removed-without-deprecation",
+                    location,
+                    format!("{subject} was removed in {label} without being deprecated first"),
+                );
+            } else if let (Some(at), Some(version)) = (&self.remove_at, version) {
+                if precedes(version, at) {
+                    c.breaking(
+                        "removed-before-promised",
+                        location,
+                        format!(
+                            "{subject} was removed in {label}, before the {at} its deprecation promised"
+                        ),
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// Whether `version` is `at` or later. A version this cannot read is neither this nor
+/// [`precedes`], so it never calls a removal overdue or early.
+fn reaches(version: &str, at: &str) -> bool {
+    matches!(
+        compare(version, at),
+        Some(Ordering::Greater | Ordering::Equal)
+    )
+}
+
+fn precedes(version: &str, at: &str) -> bool {
+    compare(version, at) == Some(Ordering::Less)
+}
+
+fn compare(version: &str, at: &str) -> Option<Ordering> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let at = at.strip_prefix('v').unwrap_or(at);
+    usage::warn::compare(version, at)
+}
+
+fn root_of(spec: &Spec) -> String {
+    if spec.bin.is_empty() {
+        spec.name.clone()
+    } else {
+        spec.bin.clone()
+    }
+}
+
+/// Every command, flag and config property in `spec`, keyed by where it is and what it is
+/// called — the flag's name rather than a spelling, which is what a rename keeps.
+fn declarations(spec: &Spec, root: &str) -> IndexMap<(String, String), Declaration> {
+    let mut out = IndexMap::new();
+    collect_command(&spec.cmd, root, &mut out);
+    for (key, prop) in &spec.config.props {
+        out.insert(
+            (root.to_string(), format!("config {key}")),
+            Declaration {
+                location: root.to_string(),
+                subject: format!("config property '{key}'"),
+                command: None,
+                deprecated: prop.deprecated.is_some(),
+                remove_at: prop.deprecated_remove_at.clone(),
+                source: None,
+            },
+        );
+    }
+    out
+}
+
+fn collect_command(
+    cmd: &SpecCommand,
+    path: &str,
+    out: &mut IndexMap<(String, String), Declaration>,
+) {
+    for flag in &cmd.flags {
+        out.insert(
+            (path.to_string(), format!("flag {}", flag.name)),
+            Declaration {
+                location: path.to_string(),
+                subject: format!("flag '{}'", primary_spelling(flag)),
+                command: None,
+                deprecated: flag.deprecated.is_some(),
+                remove_at: flag.deprecated_remove_at.clone(),
+                source: flag.source.clone(),
+            },
+        );
+    }
+    for (name, sub) in &cmd.subcommands {
+        let child = format!("{path} {name}");
+        out.insert(
+            (path.to_string(), format!("cmd {name}")),
+            Declaration {
+                location: path.to_string(),
+                subject: format!("command '{name}'"),
+                command: Some(child.clone()),
+                deprecated: sub.deprecated.is_some(),
+                remove_at: sub.deprecated_remove_at.clone(),
+                source: sub.source.clone(),
+            },
+        );
+        collect_command(sub, &child, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(label: &str, spec: &str) -> Release {
+        Release {
+            label: label.to_string(),
+            version: Some(label.to_string()),
+            spec: spec.parse().unwrap(),
+        }
+    }
+
+    fn codes(history: &History) -> Vec<&str> {
+        history.changes.iter().map(|c| c.code.as_str()).collect()
+    }
+
+    #[test]
+    fn each_declaration_says_when_it_came_and_went() {
+        let history = walk(&[
+            release("1.0.0", "bin \"ex\"\nflag \"--old\""),
+            release(
+                "1.1.0",
+                "bin \"ex\"\nflag \"--old\" deprecated=\"use --new\" { deprecated_remove_at \"2.0.0\"; }\nflag \"--new\"",
+            ),
+            release("2.0.0", "bin \"ex\"\nflag \"--new\""),
+        ]);
+        let lines: Vec<String> = history.timelines.iter().map(|t| t.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                "ex: flag '--old' added in 1.0.0, deprecated in 1.1.0 for removal in 2.0.0, removed in 2.0.0",
+                "ex: flag '--new' added in 1.1.0",
+            ]
+        );
+        assert!(history.changes.is_empty(), "{:?}", history.changes);
+    }
+
+    #[test]
+    fn a_removal_nothing_announced_is_breaking() {
+        let history = walk(&[
+            release("1.0.0", "bin \"ex\"\nflag \"--gone\""),
+            release("1.1.0", "bin \"ex\""),
+        ]);
+        assert_eq!(codes(&history), ["removed-without-deprecation"]);
+        assert_eq!(
+            history.changes[0].message,
+            "flag '--gone' was removed in 1.1.0 without being deprecated first"
+        );
+    }
+
+    #[test]
+    fn a_removal_sooner_than_promised_is_breaking() {
+        let history = walk(&[
+            release(
+                "1.0.0",
+                "bin \"ex\"\ncmd \"run\" deprecated=\"x\" { deprecated_remove_at \"3.0.0\"; }",
+            ),
+            release("2.0.0", "bin \"ex\""),
+        ]);
+        assert_eq!(codes(&history), ["removed-before-promised"]);
+    }
+
+    #[test]
+    fn a_release_that_kept_what_it_should_have_removed_says_so_once() {
+        let deprecated =
+            "bin \"ex\"\nconfig { prop \"color\" deprecated=\"x\" deprecated_remove_at=\"1.1.0\" }";
+        let history = walk(&[
+            release("1.0.0", deprecated),
+            release("1.1.0", deprecated),
+            release("1.2.0", deprecated),
+            release("1.3.0", "bin \"ex\""),
+        ]);
+        assert_eq!(codes(&history), ["removal-overdue"]);
+        assert_eq!(
+            history.changes[0].message,
+            "config property 'color' is still in 1.1.0, though its deprecation said it would be removed in 1.1.0"
+        );
+    }
+
+    #[test]
+    fn what_goes_with_a_removed_command_is_not_reported_again() {
+        let history = walk(&[
+            release(
+                "1.0.0",
+                "bin \"ex\"\ncmd \"run\" deprecated=\"x\" { flag \"--fast\"; cmd \"now\"; }",
+            ),
+            release("1.1.0", "bin \"ex\""),
+        ]);
+        assert!(history.changes.is_empty(), "{:?}", history.changes);
+        assert_eq!(
+            history.timelines[1].to_string(),
+            "ex run: flag '--fast' added in 1.0.0, removed in 1.1.0"
+        );
+    }
+
+    #[test]
+    fn a_version_that_cannot_be_read_excuses_nothing_and_accuses_nothing() {
+        let history = walk(&[
+            release(
+                "1.0.0",
+                "bin \"ex\"\nflag \"--old\" deprecated=\"x\" { deprecated_remove_at \"2.0.0\"; }",
+            ),
+            release("nightly", "bin \"ex\""),
+        ]);
+        assert!(history.changes.is_empty(), "{:?}", history.changes);
+    }
+}
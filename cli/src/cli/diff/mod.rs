@@ -12,23 +12,40 @@
 //! means what it meant. **metadata** means nothing about parsing moved: help text,
 //! headings, declaration order, hidden-ness, `effect`, deprecation notices.
 //!
+//! `stability` weighs removals. An item declared stable promised a deprecation first, and
+//! removing it without one is its own breaking code (`stable-flag-removed` and so on) so a
+//! release job can single out the promise that was broken. Removing anything else is
+//! breaking under the ordinary code: an experimental flag said it might go, but the command
+//! lines that used it stop parsing all the same.
+//!
 //! Two deliberate silences. `version` and `long_version` are never reported: a
 //! release bumps them, and a compatibility check that fires on every release is one
 //! nobody leaves running — tak sets `spec.version = None` by hand today for exactly
 //! this reason. Derived strings (`usage`, `full_cmd`, `help_first_line`) are not
 //! reported either, because they restate what the declarations already say.
+//!
+//! The old spec need not be a file: [`revision`] finds it at a git revision or in a
+//! directory of release snapshots, and [`history`] walks all of them in order.
+
+mod history;
+mod revision;
 
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use usage::{Spec, SpecArg, SpecCommand, SpecFlag};
+use usage::{Spec, SpecArg, SpecCommand, SpecFlag, SpecStability};
+
+use history::History;
+use revision::Release;
 
 use crate::cli::generate::parse_file_or_stdin;
-use crate::cli::OutputFormat;
+use crate::cli::report::{self, Finding, Level};
+use crate::cli::ReportFormat;
 use usage::spec::choices::SpecChoices;
 use usage::spec::config::{SpecConfig, SpecConfigProp, SpecConfigValue};
 use usage::spec::group::SpecGroup;
 use usage::spec::unknown_flags::UnknownFlags;
+use usage::spec::SourceLocation;
 
 /// Compare two usage specs and report what changed about the interface
 ///
@@ -37,27 +54,57 @@ use usage::spec::unknown_flags::UnknownFlags;
 /// changes (the interface gained something or relaxed a rule), and metadata
 /// changes (help text, effect, deprecation — nothing about parsing).
 ///
+/// Removing a stable item that was never deprecated is reported as such.
+///
 /// Exits 1 when there is a breaking change, so a release job can gate on it, and
 /// either spec may be "-":
 ///
 ///   mycli --usage-spec | usage diff released.usage.kdl -
 ///
+/// Or compare against the spec as a release had it, by git revision or from a
+/// directory of snapshots, and walk every release to see when each command, flag
+/// and config property was added, deprecated and removed:
+///
+///   usage diff --against v1.2.0 mycli.usage.kdl
+///   usage diff --history mycli.usage.kdl
+///
 /// `version` is ignored on purpose: a release bumps it, and a check that fires
 /// every release does not get left switched on.
 #[derive(usage_rs::Args)]
 #[usage(effect = "read", verbatim_doc_comment)]
 pub struct Diff {
     /// The spec as it was, typically the released one, use "-" to read from stdin
+    ///
+    /// With --against or --history the earlier specs come from elsewhere, and this is
+    /// the one spec given: the spec as it is now.
     #[usage(value_hint = usage_rs::ValueHint::FilePath)]
     old: PathBuf,
 
     /// The spec as it is now, use "-" to read from stdin
     #[usage(value_hint = usage_rs::ValueHint::FilePath)]
-    new: PathBuf,
+    new: Option<PathBuf>,
+
+    /// Compare against a git revision's copy of the spec, or the newest snapshot in a directory
+    ///
+    /// A revision is read with `git show`, so the spec has to be a file in a git work
+    /// tree. A directory holds one `*.usage.kdl` per release, ordered by the `version`
+    /// each declares, or by file name for one that declares none.
+    #[usage(long)]
+    against: Option<String>,
+
+    /// Walk every release rather than comparing two specs
+    ///
+    /// The releases are the git tags that have the spec, reachable from --against or
+    /// from HEAD, or every snapshot when --against names a directory. The spec given
+    /// comes last, as the release in progress. Reports when each command, flag and config
+    /// property was added, deprecated and removed, and checks every removal against the
+    /// `deprecated_remove_at` its deprecation declared.
+    #[usage(long)]
+    history: bool,
 
     /// Output format
     #[usage(long, short, default = "text", value_enum)]
-    format: OutputFormat,
+    format: ReportFormat,
 
     /// Report only breaking changes
     #[usage(long, short)]
@@ -105,6 +152,10 @@ pub struct SpecChange {
     pub code: String,
     pub message: String,
     pub location: String,
+    /// Where in the spec file the finding is: in the new spec wherever the thing it is about
+    /// still exists there, and at the command that lost it otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLocation>,
 }
 
 impl std::fmt::Display for SpecChange {
@@ -121,50 +172,125 @@ impl usage_rs::Run for Diff {
     type Output = miette::Result<()>;
 
     fn run(self) -> Self::Output {
-        if self.old.as_os_str() == "-" && self.new.as_os_str() == "-" {
-            miette::bail!("only one of the two specs can be read from stdin");
+        if self.history {
+            return self.run_history();
         }
-        let old = parse_file_or_stdin(&self.old)?;
-        let new = parse_file_or_stdin(&self.new)?;
+        let (old, new) = match (&self.against, &self.new) {
+            (Some(against), None) => {
+                let new = parse_file_or_stdin(&self.old)?;
+                (self.released(against)?, new)
+            }
+            (Some(_), Some(_)) => {
+                miette::bail!("--against names the old spec, so give only the one as it is now")
+            }
+            (None, Some(new)) => {
+                if self.old.as_os_str() == "-" && new.as_os_str() == "-" {
+                    miette::bail!("only one of the two specs can be read from stdin");
+                }
+                (parse_file_or_stdin(&self.old)?, parse_file_or_stdin(new)?)
+            }
+            (None, None) => miette::bail!("give the spec as it is now, or --against to find it"),
+        };
         let mut changes = diff_specs(&old, &new);
         if self.breaking {
             changes.retain(|c| c.category == Category::Breaking);
         }
 
         match self.format {
-            OutputFormat::Text => self.print_text(&changes),
-            OutputFormat::Json => self.print_json(&changes)?,
+            ReportFormat::Text => self.print_text(&changes),
+            ReportFormat::Json => self.print_json(&changes)?,
+            ReportFormat::Sarif => self.print_sarif(&changes)?,
+            ReportFormat::Github => self.print_github(&changes),
         }
 
-        if !self.exit_zero && changes.iter().any(|c| c.category == Category::Breaking) {
-            std::process::exit(1);
-        }
+        self.exit(&changes);
         Ok(())
     }
 }
 
 impl Diff {
-    fn print_text(&self, changes: &[SpecChange]) {
-        if changes.is_empty() {
-            if self.breaking {
-                println!("No breaking changes.");
-            } else {
-                println!("No interface changes.");
+    /// The spec `--against` names: this one as a revision had it, or the newest snapshot.
+    fn released(&self, against: &str) -> miette::Result<Spec> {
+        if Path::new(against).is_dir() {
+            let mut releases = revision::snapshots(Path::new(against))?;
+            // `snapshots` refuses a directory without any.
+            Ok(releases.pop().expect("at least one snapshot").spec)
+        } else {
+            revision::at_revision(self.tracked_path()?, against)
+        }
+    }
+
+    /// The file git is asked about, which stdin is not.
+    fn tracked_path(&self) -> miette::Result<&Path> {
+        if self.old.as_os_str() == "-" {
+            miette::bail!("a spec read from stdin has no path to look up in git history");
+        }
+        Ok(&self.old)
+    }
+
+    fn run_history(self) -> miette::Result<()> {
+        if self.new.is_some() {
+            miette::bail!(
+                "--history walks the releases of one spec, so give only the one as it is now"
+            );
+        }
+        let mut releases = match self.against.as_deref() {
+            Some(dir) if Path::new(dir).is_dir() => revision::snapshots(Path::new(dir))?,
+            rev => revision::tagged_releases(self.tracked_path()?, rev.unwrap_or("HEAD"))?,
+        };
+        let spec = parse_file_or_stdin(&self.old)?;
+        releases.push(Release {
+            label: "current".to_string(),
+            version: spec.version.clone(),
+            spec,
+        });
+        let mut history = history::walk(&releases);
+        if self.breaking {
+            history.changes.retain(|c| c.category == Category::Breaking);
+        }
+
+        match self.format {
+            ReportFormat::Text => self.print_history_text(&history),
+            ReportFormat::Json => {
+                let json = serde_json::to_string_pretty(&history)
+                    .map_err(|e| miette::miette!("Failed to serialize history: {}", e))?;
+                println!("{json}");
             }
-            return;
+            ReportFormat::Sarif => self.print_sarif(&history.changes)?,
+            ReportFormat::Github => self.print_github(&history.changes),
         }
 
-        for change in changes {
-            println!("{change}");
+        self.exit(&history.changes);
+        Ok(())
+    }
+
+    fn exit(&self, changes: &[SpecChange]) {
+        if !self.exit_zero && changes.iter().any(|c| c.category == Category::Breaking) {
+            std::process::exit(1);
+        }
+    }
+
+    fn print_text(&self, changes: &[SpecChange]) {
+        if self.breaking {
+            print_changes(changes, "No breaking changes.");
+        } else {
+            print_changes(changes, "No interface changes.");
         }
+    }
 
-        let count = |category: Category| changes.iter().filter(|c| c.category == category).count();
-        println!();
-        println!(
-            "Found {} breaking, {} compatible, {} metadata change(s)",
-            count(Category::Breaking),
-            count(Category::Compatible),
-            count(Category::Metadata),
+    /// The timelines first, as the release notes a reader came for, then what is wrong
+    /// with them. `--breaking` keeps only the second part.
+    fn print_history_text(&self, history: &History) {
+        if !self.breaking {
+            println!("Releases: {}", history.releases.join(", "));
+            for timeline in &history.timelines {
+                println!("{timeline}");
+            }
+            println!();
+        }
+        print_changes(
+            &history.changes,
+            "Every removal kept its deprecation's promise.",
         );
     }
 
@@ -174,6 +300,56 @@ impl Diff {
         println!("{json}");
         Ok(())
     }
+
+    fn print_sarif(&self, changes: &[SpecChange]) -> miette::Result<()> {
+        let findings: Vec<Finding> = changes.iter().map(SpecChange::finding).collect();
+        let json = serde_json::to_string_pretty(&report::sarif("usage diff", &findings))
+            .map_err(|e| miette::miette!("Failed to serialize changes: {}", e))?;
+        println!("{json}");
+        Ok(())
+    }
+
+    fn print_github(&self, changes: &[SpecChange]) {
+        let findings: Vec<Finding> = changes.iter().map(SpecChange::finding).collect();
+        print!("{}", report::github(&findings));
+    }
+}
+
+fn print_changes(changes: &[SpecChange], nothing: &str) {
+    if changes.is_empty() {
+        println!("{nothing}");
+        return;
+    }
+
+    for change in changes {
+        println!("{change}");
+    }
+
+    let count = |category: Category| changes.iter().filter(|c| c.category == category).count();
+    println!();
+    println!(
+        "Found {} breaking, {} compatible, {} metadata change(s)",
+        count(Category::Breaking),
+        count(Category::Compatible),
+        count(Category::Metadata),
+    );
+}
+
+impl SpecChange {
+    /// Breaking is the one category a reviewer has to act on; the other two are worth
+    /// seeing on the line they are about, and no more than that.
+    fn finding(&self) -> Finding<'_> {
+        Finding {
+            level: match self.category {
+                Category::Breaking => Level::Error,
+                Category::Compatible | Category::Metadata => Level::Note,
+            },
+            code: &self.code,
+            message: &self.message,
+            location: Some(&self.location),
+            source: self.source.as_ref(),
+        }
+    }
 }
 
 /// Where findings accumulate, so every comparison function is a `&mut self` push
@@ -181,6 +357,16 @@ impl Diff {
 #[derive(Default)]
 struct Changes {
     changes: Vec<SpecChange>,
+    /// The declaration being compared, which is where a finding pushed now points.
+    ///
+    /// Kept here rather than passed down beside `path` for the reason the pushes are:
+    /// a comparison that moves into a flag says so once, with [`Self::within`], and
+    /// every finding it raises inside points at that flag.
+    at: Option<SourceLocation>,
+    /// Each spec's `experimental_env`: whether an experimental item needed an opt-in
+    /// before, and whether it does now.
+    old_experimental_env: Option<String>,
+    new_experimental_env: Option<String>,
 }
 
 impl Changes {
@@ -190,9 +376,21 @@ impl Changes {
             code: code.to_string(),
             message,
             location: location.to_string(),
+            source: self.at.clone(),
         });
     }
 
+    /// Run a comparison with its findings pointing at `source`, or at whatever encloses it
+    /// when it has none — a declaration built in code rather than read from a file.
+    fn within(&mut self, source: Option<&SourceLocation>, compare: impl FnOnce(&mut Self)) {
+        let enclosing = match source {
+            Some(source) => self.at.replace(source.clone()),
+            None => self.at.clone(),
+        };
+        compare(self);
+        self.at = enclosing;
+    }
+
     fn breaking(&mut self, code: &str, location: &str, message: String) {
         self.push(Category::Breaking, code, location, message);
     }
@@ -212,7 +410,12 @@ impl Changes {
 /// the order is the walk order — root, its flags, its arguments, then each
 /// subcommand — which is the order the spec declares them in.
 pub fn diff_specs(old: &Spec, new: &Spec) -> Vec<SpecChange> {
-    let mut c = Changes::default();
+    let mut c = Changes {
+        at: new.cmd.source.clone(),
+        old_experimental_env: old.experimental_env.clone(),
+        new_experimental_env: new.experimental_env.clone(),
+        ..Changes::default()
+    };
 
     let root = if new.bin.is_empty() {
         &new.name
@@ -280,12 +483,29 @@ pub fn diff_specs(old: &Spec, new: &Spec) -> Vec<SpecChange> {
             );
         }
     }
-    for id in new.views.keys() {
+    for (id, view) in &new.views {
         if !old.views.contains_key(id) {
-            c.compatible("view-added", &root, format!("view '{id}' was added"));
+            c.within(view.source.as_ref(), |c| {
+                c.compatible("view-added", &root, format!("view '{id}' was added"))
+            });
         }
     }
 
+    match (&old.experimental_env, &new.experimental_env) {
+        (Some(was), Some(now)) if was != now => c.breaking(
+            "experimental-env-changed",
+            &root,
+            format!("the opt-in for experimental items changed from {was} to {now}"),
+        ),
+        (Some(was), None) => c.compatible(
+            "experimental-env-removed",
+            &root,
+            format!("experimental items no longer need {was} set"),
+        ),
+        // Added, it gates each experimental item, and each is reported where it is.
+        _ => {}
+    }
+
     if old.min_usage_version != new.min_usage_version {
         c.metadata(
             "min-usage-version-changed",
@@ -336,13 +556,15 @@ fn diff_command(
     renamed_from: Option<&str>,
     c: &mut Changes,
 ) {
-    diff_names(old, new, path, renamed_from, c);
-    diff_command_props(old, new, path, c);
-    diff_flags(old, new, path, c);
-    diff_args(&old.args, &new.args, path, c);
-    diff_groups(&old.groups, &new.groups, path, c);
-    diff_mounts(old, new, path, c);
-    diff_subcommands(old, new, path, c);
+    c.within(new.source.as_ref(), |c| {
+        diff_names(old, new, path, renamed_from, c);
+        diff_command_props(old, new, path, c);
+        diff_flags(old, new, path, c);
+        diff_args(&old.args, &new.args, path, c);
+        diff_groups(&old.groups, &new.groups, path, c);
+        diff_mounts(old, new, path, c);
+        diff_subcommands(old, new, path, c);
+    });
 }
 
 fn diff_names(
@@ -551,6 +773,13 @@ fn diff_command_props(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut
         "command",
         c,
     );
+    diff_stability(
+        (old.stability, old.since.as_deref()),
+        (new.stability, new.since.as_deref()),
+        path,
+        "command",
+        c,
+    );
 
     if [
         old.help != new.help,
@@ -630,17 +859,23 @@ fn diff_flags(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Changes)
     // Reported in the old spec's declaration order, whichever pass did the pairing.
     for (was, pairing) in old.flags.iter().zip(&pairings) {
         match pairing {
-            Pairing::Named(position) => diff_flag(was, &new.flags[*position], path, c),
+            Pairing::Named(position) => {
+                let now = &new.flags[*position];
+                c.within(now.source.as_ref(), |c| diff_flag(was, now, path, c));
+            }
             Pairing::Renamed(position) => {
                 let now = &new.flags[*position];
-                c.metadata(
-                    "flag-renamed",
-                    path,
-                    format!("flag '{}' was renamed to '{}'", was.name, now.name),
-                );
-                diff_flag(was, now, path, c);
+                c.within(now.source.as_ref(), |c| {
+                    c.metadata(
+                        "flag-renamed",
+                        path,
+                        format!("flag '{}' was renamed to '{}'", was.name, now.name),
+                    );
+                    diff_flag(was, now, path, c);
+                });
             }
-            Pairing::Gone => c.breaking(
+            Pairing::Gone => removed(
+                c,
                 "flag-removed",
                 path,
                 format!(
@@ -648,6 +883,10 @@ fn diff_flags(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Changes)
                     was.name,
                     flag_spellings(was).join(", ")
                 ),
+                was.stability,
+                was.deprecated.is_some()
+                    || was.deprecated_warn_at.is_some()
+                    || was.deprecated_remove_at.is_some(),
             ),
         }
     }
@@ -657,22 +896,24 @@ fn diff_flags(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Changes)
             continue;
         }
         let spellings = flag_spellings(now);
-        if now.required {
-            c.breaking(
-                "required-flag-added",
-                path,
-                format!(
-                    "required flag '{}' was added, so an invocation without it fails",
-                    spellings.join(", ")
-                ),
-            );
-        } else {
-            c.compatible(
-                "flag-added",
-                path,
-                format!("flag '{}' was added", spellings.join(", ")),
-            );
-        }
+        c.within(now.source.as_ref(), |c| {
+            if now.required {
+                c.breaking(
+                    "required-flag-added",
+                    path,
+                    format!(
+                        "required flag '{}' was added, so an invocation without it fails",
+                        spellings.join(", ")
+                    ),
+                );
+            } else {
+                c.compatible(
+                    "flag-added",
+                    path,
+                    format!("flag '{}' was added", spellings.join(", ")),
+                );
+            }
+        });
     }
 }
 
@@ -1013,6 +1254,13 @@ fn diff_flag(old: &SpecFlag, new: &SpecFlag, path: &str, c: &mut Changes) {
         &subject,
         c,
     );
+    diff_stability(
+        (old.stability, old.since.as_deref()),
+        (new.stability, new.since.as_deref()),
+        path,
+        &subject,
+        c,
+    );
 
     if !old.hide && new.hide {
         c.metadata("hidden", path, format!("{subject} is no longer documented"));
@@ -1038,7 +1286,7 @@ fn diff_flag(old: &SpecFlag, new: &SpecFlag, path: &str, c: &mut Changes) {
 fn diff_args(old: &[SpecArg], new: &[SpecArg], path: &str, c: &mut Changes) {
     for (position, was) in old.iter().enumerate() {
         match new.get(position) {
-            Some(now) => {
+            Some(now) => c.within(now.source.as_ref(), |c| {
                 if was.name != now.name {
                     // The slot still binds the same word, so nothing a caller types
                     // changes — but the name is what help, docs and `usage exec`'s
@@ -1056,31 +1304,38 @@ fn diff_args(old: &[SpecArg], new: &[SpecArg], path: &str, c: &mut Changes) {
                 }
                 let subject = format!("argument <{}>", now.name);
                 diff_arg(was, now, path, &subject, c);
-            }
-            None => c.breaking(
+            }),
+            // An argument cannot be deprecated, so a stable one has no way out that keeps
+            // its promise.
+            None => removed(
+                c,
                 "arg-removed",
                 path,
                 format!("argument <{}> was removed", was.name),
+                was.stability,
+                false,
             ),
         }
     }
     for now in new.iter().skip(old.len()) {
-        if now.required {
-            c.breaking(
-                "required-arg-added",
-                path,
-                format!(
-                    "required argument <{}> was added, so an invocation without it fails",
-                    now.name
-                ),
-            );
-        } else {
-            c.compatible(
-                "arg-added",
-                path,
-                format!("argument <{}> was added", now.name),
-            );
-        }
+        c.within(now.source.as_ref(), |c| {
+            if now.required {
+                c.breaking(
+                    "required-arg-added",
+                    path,
+                    format!(
+                        "required argument <{}> was added, so an invocation without it fails",
+                        now.name
+                    ),
+                );
+            } else {
+                c.compatible(
+                    "arg-added",
+                    path,
+                    format!("argument <{}> was added", now.name),
+                );
+            }
+        });
     }
 }
 
@@ -1266,6 +1521,14 @@ fn diff_arg(old: &SpecArg, new: &SpecArg, path: &str, subject: &str, c: &mut Cha
         );
     }
 
+    diff_stability(
+        (old.stability, old.since.as_deref()),
+        (new.stability, new.since.as_deref()),
+        path,
+        subject,
+        c,
+    );
+
     if !old.hide && new.hide {
         c.metadata("hidden", path, format!("{subject} is no longer documented"));
     } else if old.hide && !new.hide {
@@ -1495,14 +1758,16 @@ fn diff_subcommands(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Ch
                 // `install` reaching `toolchain install` is a rename, not a removal.
                 match new.find_subcommand(name) {
                     Some(covering_cmd) => {
-                        c.metadata(
-                            "cmd-renamed",
-                            path,
-                            format!(
-                                "command '{name}' was renamed to '{}', which still answers to '{name}'",
-                                covering_cmd.name
-                            ),
-                        );
+                        c.within(covering_cmd.source.as_ref(), |c| {
+                            c.metadata(
+                                "cmd-renamed",
+                                path,
+                                format!(
+                                    "command '{name}' was renamed to '{}', which still answers to '{name}'",
+                                    covering_cmd.name
+                                ),
+                            )
+                        });
                         // And then compared, because the rename is not the only thing that
                         // may have happened to it. Located under the *old* name: what a
                         // reader wants to know is what typing `{name}` does now, and the
@@ -1510,9 +1775,16 @@ fn diff_subcommands(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Ch
                         diff_command(was, covering_cmd, &child, Some(name), c);
                         covering.insert(covering_cmd.name.clone());
                     }
-                    None => {
-                        c.breaking("cmd-removed", path, format!("command '{name}' was removed"))
-                    }
+                    None => removed(
+                        c,
+                        "cmd-removed",
+                        path,
+                        format!("command '{name}' was removed"),
+                        was.stability,
+                        was.deprecated.is_some()
+                            || was.deprecated_warn_at.is_some()
+                            || was.deprecated_remove_at.is_some(),
+                    ),
                 }
             }
         }
@@ -1521,45 +1793,46 @@ fn diff_subcommands(old: &SpecCommand, new: &SpecCommand, path: &str, c: &mut Ch
         if old.subcommands.contains_key(name) || covering.contains(name) {
             continue;
         }
-        if old.find_subcommand(name).is_some() {
-            // The name used to be an alias of a sibling and is now a command of its
-            // own: what the word selects changed.
-            c.breaking(
-                "cmd-shadows-alias",
-                path,
-                format!("command '{name}' now takes a name that was an alias of another command"),
-            );
-            continue;
-        }
-        // A word only becomes newly meaningful if the old interface had nothing to do with
-        // it. Where it did, the invocation that used to work now reaches somewhere else,
-        // which is the definition of breaking rather than an exception to it.
-        if old.external_subcommand {
-            c.breaking(
-                "cmd-shadows-external",
-                path,
-                format!(
-                    "command '{name}' now takes a word that used to be forwarded to an external command"
-                ),
-            );
-            continue;
-        }
-        if let Some(arg) = old.args.first() {
-            c.breaking(
-                "cmd-shadows-arg",
-                path,
-                format!(
-                    "command '{name}' now takes a word that used to bind to '{}'",
-                    arg.usage()
-                ),
-            );
-            continue;
-        }
-        c.compatible(
-            "cmd-added",
-            path,
-            format!("command '{}' was added", now.name),
-        );
+        c.within(now.source.as_ref(), |c| {
+            if old.find_subcommand(name).is_some() {
+                // The name used to be an alias of a sibling and is now a command of its
+                // own: what the word selects changed.
+                c.breaking(
+                    "cmd-shadows-alias",
+                    path,
+                    format!(
+                        "command '{name}' now takes a name that was an alias of another command"
+                    ),
+                );
+            } else if old.external_subcommand {
+                // A word only becomes newly meaningful if the old interface had nothing to
+                // do with it. Where it did, the invocation that used to work now reaches
+                // somewhere else, which is the definition of breaking rather than an
+                // exception to it.
+                c.breaking(
+                    "cmd-shadows-external",
+                    path,
+                    format!(
+                        "command '{name}' now takes a word that used to be forwarded to an external command"
+                    ),
+                );
+            } else if let Some(arg) = old.args.first() {
+                c.breaking(
+                    "cmd-shadows-arg",
+                    path,
+                    format!(
+                        "command '{name}' now takes a word that used to bind to '{}'",
+                        arg.usage()
+                    ),
+                );
+            } else {
+                c.compatible(
+                    "cmd-added",
+                    path,
+                    format!("command '{}' was added", now.name),
+                );
+            }
+        });
     }
 }
 
@@ -1655,6 +1928,35 @@ fn diff_config(old: &SpecConfig, new: &SpecConfig, path: &str, c: &mut Changes)
             format!("config file '{file}' is now read"),
         );
     }
+    // A selector that goes away leaves every `[profiles.*]` table a user wrote unread, and one
+    // that changes spelling leaves their `--profile` or variable selecting nothing.
+    match (&old.profiles, &new.profiles) {
+        (Some(_), None) => c.breaking(
+            "config-profiles-removed",
+            path,
+            "named profiles are no longer read".to_string(),
+        ),
+        (None, Some(_)) => c.compatible(
+            "config-profiles-added",
+            path,
+            "named profiles are now read".to_string(),
+        ),
+        (Some(before), Some(after)) => {
+            for (what, before, after) in [
+                ("variable", &before.env, &after.env),
+                ("flag", &before.flag, &after.flag),
+            ] {
+                if let Some(before) = before.as_ref().filter(|_| before != after) {
+                    c.breaking(
+                        "config-profiles-selector-changed",
+                        path,
+                        format!("the profile {what} '{before}' no longer selects a profile"),
+                    );
+                }
+            }
+        }
+        (None, None) => {}
+    }
 }
 
 fn diff_config_prop(
@@ -1828,6 +2130,73 @@ fn diff_deprecation(
     }
 }
 
+/// A removal, weighed by what the old declaration promised.
+///
+/// A stable item promised a deprecation first, so going without one gets a code of its own.
+/// Everything else is the ordinary rule: an experimental flag that goes still breaks the
+/// command lines that used it, whatever it said about going.
+fn removed(
+    c: &mut Changes,
+    code: &str,
+    path: &str,
+    message: String,
+    stability: Option<SpecStability>,
+    deprecated: bool,
+) {
+    match stability {
+        Some(stability) if stability.is_promised() && !deprecated => c.breaking(
+            &format!("stable-{code}"),
+            path,
+            format!("{message} without being deprecated first, though it was declared stable"),
+        ),
+        _ => c.breaking(code, path, message),
+    }
+}
+
+/// `stability` and `since` are labels, so changing them is metadata — except that under an
+/// `experimental_env` an item that becomes experimental stops working until the user opts in.
+fn diff_stability(
+    (old, old_since): (Option<SpecStability>, Option<&str>),
+    (new, new_since): (Option<SpecStability>, Option<&str>),
+    path: &str,
+    subject: &str,
+    c: &mut Changes,
+) {
+    let experimental = Some(SpecStability::Experimental);
+    let gated_before = old == experimental && c.old_experimental_env.is_some();
+    if let Some(env) = c.new_experimental_env.clone() {
+        if new == experimental && !gated_before {
+            c.breaking(
+                "experimental-gated",
+                path,
+                format!("{subject} is now experimental, so it needs {env}=1 set"),
+            );
+        }
+    }
+    if old != new {
+        c.metadata(
+            "stability-changed",
+            path,
+            format!(
+                "{subject} stability changed from {} to {}",
+                option(&old),
+                option(&new)
+            ),
+        );
+    }
+    if old_since != new_since {
+        c.metadata(
+            "since-changed",
+            path,
+            format!(
+                "{subject} since changed from {} to {}",
+                option(&old_since),
+                option(&new_since)
+            ),
+        );
+    }
+}
+
 /// A default a caller was relying on. Adding one fills a hole — nothing was
 /// resolved there before — while changing or removing one moves ground the caller
 /// was already standing on.
@@ -2489,6 +2858,80 @@ arg "[out]" help="out"
         );
     }
 
+    #[test]
+    fn only_a_stable_removal_without_a_deprecation_gets_its_own_code() {
+        let old = r#"
+name "ex"
+bin "ex"
+flag "--fast" stability="experimental"
+flag "--retry" stability="beta"
+flag "--force" stability="stable"
+flag "--quiet" stability="stable" deprecated="use --silent"
+arg "[path]" stability="stable"
+cmd "sync" stability="stable"
+        "#;
+        let gone = "name \"ex\"\nbin \"ex\"\n";
+        assert_eq!(
+            codes(old, gone),
+            [
+                "breaking:flag-removed",
+                "breaking:flag-removed",
+                "breaking:stable-flag-removed",
+                "breaking:flag-removed",
+                "breaking:stable-arg-removed",
+                "breaking:stable-cmd-removed",
+            ]
+        );
+        let found = changes(old, gone);
+        assert_eq!(
+            find(&found, "stable-flag-removed").message,
+            "flag 'force' (--force) was removed without being deprecated first, though it was declared stable"
+        );
+        assert_eq!(
+            find(&found, "flag-removed").message,
+            "flag 'fast' (--fast) was removed"
+        );
+    }
+
+    #[test]
+    fn stability_and_since_are_labels_until_an_opt_in_gates_them() {
+        let old = r#"
+name "ex"
+bin "ex"
+flag "--fast" stability="stable" since="1.0.0"
+        "#;
+        let relabelled = r#"
+name "ex"
+bin "ex"
+flag "--fast" stability="beta" since="1.1.0"
+        "#;
+        assert_eq!(
+            codes(old, relabelled),
+            ["metadata:stability-changed", "metadata:since-changed"]
+        );
+        assert_eq!(
+            find(&changes(old, relabelled), "stability-changed").message,
+            "flag '--fast' stability changed from 'stable' to 'beta'"
+        );
+
+        let gated = r#"
+name "ex"
+bin "ex"
+experimental_env "EX_EXPERIMENTAL"
+flag "--fast" stability="experimental" since="1.0.0"
+        "#;
+        assert_eq!(
+            codes(old, gated),
+            ["breaking:experimental-gated", "metadata:stability-changed"]
+        );
+        assert_eq!(
+            find(&changes(old, gated), "experimental-gated").message,
+            "flag '--fast' is now experimental, so it needs EX_EXPERIMENTAL=1 set"
+        );
+        // Already behind the opt-in, it costs nothing more.
+        assert!(codes(gated, gated).is_empty());
+    }
+
     #[test]
     fn a_removed_command_is_breaking_unless_something_still_answers_to_it() {
         let old = r#"
@@ -2838,6 +3281,34 @@ config {
         );
     }
 
+    #[test]
+    fn a_profile_selector_that_changes_strands_what_it_selected() {
+        let old = r#"
+name "ex"
+bin "ex"
+config {
+    profiles env="EX_PROFILE" flag="--profile"
+}
+        "#;
+        let new = r#"
+name "ex"
+bin "ex"
+config {
+    profiles env="EX_ENV" flag="--profile"
+}
+        "#;
+        let found = changes(old, new);
+        assert_eq!(
+            find(&found, "config-profiles-selector-changed").message,
+            "the profile variable 'EX_PROFILE' no longer selects a profile"
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        let found = changes(old, "name \"ex\"\nbin \"ex\"\n");
+        assert!(found
+            .iter()
+            .any(|change| change.code == "config-profiles-removed"));
+    }
+
     #[test]
     fn a_renamed_config_property_says_where_it_went() {
         let old = r#"
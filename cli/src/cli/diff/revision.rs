@@ -0,0 +1,183 @@
+//! Where an earlier copy of a spec comes from: a git revision, or a directory holding a
+//! snapshot of it per release.
+//!
+//! Both end as a [`Spec`] parsed the way `usage diff` parses the one it was given, so a
+//! comparison never reports the difference between two ways of reading a file.
+
+use std::cmp::Ordering;
+use std::path::Path;
+use std::process::Command;
+
+use miette::{miette, IntoDiagnostic, WrapErr};
+use usage::Spec;
+
+/// One published copy of a spec.
+pub struct Release {
+    /// What a reader calls it: the tag, the snapshot's file name, or `current`.
+    pub label: String,
+    /// What a `deprecated_remove_at` is compared with, when there is a version to read.
+    pub version: Option<String>,
+    pub spec: Spec,
+}
+
+/// The spec at `path` as the revision `rev` had it.
+pub fn at_revision(path: &Path, rev: &str) -> miette::Result<Spec> {
+    // Checked first, so that `git show` failing below can only mean the file was not there.
+    git(
+        path,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+    )
+    .map_err(|_| miette!("'{rev}' is not a git revision"))?;
+    let text = git_show(path, rev)?
+        .ok_or_else(|| miette!("{} does not exist at {rev}", path.display()))?;
+    parse_revision(path, &text).wrap_err_with(|| format!("reading {} at {rev}", path.display()))
+}
+
+/// Every tag reachable from `rev` that has the spec, oldest version first.
+///
+/// A tag from before the spec existed has nothing to say about it and is skipped.
+pub fn tagged_releases(path: &Path, rev: &str) -> miette::Result<Vec<Release>> {
+    let tags = git(path, &["tag", "--merged", rev, "--sort=v:refname"])?;
+    let mut releases = vec![];
+    for tag in tags.lines().filter(|tag| !tag.is_empty()) {
+        let Some(text) = git_show(path, tag)? else {
+            continue;
+        };
+        let spec = parse_revision(path, &text)
+            .wrap_err_with(|| format!("reading {} at {tag}", path.display()))?;
+        releases.push(Release {
+            label: tag.to_string(),
+            version: Some(version_of(tag).to_string()),
+            spec,
+        });
+    }
+    Ok(releases)
+}
+
+/// Every `*.usage.kdl` in `dir`, oldest version first.
+///
+/// A snapshot's version is the one it declares, since that is what its release shipped
+/// as; the file name stands in for one that declares none.
+pub fn snapshots(dir: &Path) -> miette::Result<Vec<Release>> {
+    let mut releases = vec![];
+    let entries = std::fs::read_dir(dir)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("reading {}", dir.display()))?;
+    for entry in entries {
+        let path = entry.into_diagnostic()?.path();
+        let Some(label) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".usage.kdl"))
+        else {
+            continue;
+        };
+        let label = label.to_string();
+        let spec = Spec::parse_file(&path)?;
+        let version = spec
+            .version
+            .clone()
+            .unwrap_or_else(|| version_of(&label).to_string());
+        releases.push(Release {
+            label,
+            version: Some(version),
+            spec,
+        });
+    }
+    if releases.is_empty() {
+        miette::bail!("{} has no *.usage.kdl snapshots", dir.display());
+    }
+    releases.sort_by(by_version);
+    Ok(releases)
+}
+
+/// Readable versions in version order, then the rest by label, so that a name this cannot
+/// read still lands somewhere stable rather than making the sort inconsistent.
+fn by_version(a: &Release, b: &Release) -> Ordering {
+    fn readable(release: &Release) -> Option<&str> {
+        release
+            .version
+            .as_deref()
+            .filter(|v| usage::warn::compare(v, v).is_some())
+    }
+    match (readable(a), readable(b)) {
+        (Some(x), Some(y)) => usage::warn::compare(x, y).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+    .then_with(|| a.label.cmp(&b.label))
+}
+
+/// `v1.2.0` is the tag, `1.2.0` the version.
+fn version_of(label: &str) -> &str {
+    label.strip_prefix('v').unwrap_or(label)
+}
+
+/// The file as `rev` had it, or `None` when it had no such file.
+fn git_show(path: &Path, rev: &str) -> miette::Result<Option<String>> {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| miette!("{} is not a file", path.display()))?;
+    // Listed before it is shown, so that only a file the revision lacks reads as `None`. A
+    // failing `git show` is then a broken repository, and saying so beats a history that is
+    // quietly missing a release. Both take `name` relative to the directory git runs in,
+    // which is where `path` is relative to as well.
+    let listed = git(path, &["ls-tree", "--name-only", rev, "--", name])?;
+    if listed.trim().is_empty() {
+        return Ok(None);
+    }
+    git(path, &["show", &format!("{rev}:./{name}")]).map(Some)
+}
+
+/// Run git in the directory holding `path`.
+fn git(path: &Path, args: &[&str]) -> miette::Result<String> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .into_diagnostic()
+        .wrap_err("could not run git")?;
+    if !output.status.success() {
+        miette::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).into_diagnostic()
+}
+
+/// Parse a revision's copy as [`Spec::parse_file`] would parse the file itself: a `.kdl`
+/// file is a spec and anything else a script carrying one, and `bin` falls back to the
+/// file name.
+///
+/// Relative `include`s cannot be followed: what they name is the working tree's copy,
+/// not the revision's.
+fn parse_revision(path: &Path, text: &str) -> miette::Result<Spec> {
+    let mut spec = if path.extension().is_some_and(|ext| ext == "kdl") {
+        text.parse::<Spec>()?
+    } else {
+        Spec::parse_script_str(text)?
+    };
+    if spec.bin.is_empty() {
+        if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+            spec.bin = name.to_string();
+        }
+    }
+    if spec.name.is_empty() {
+        spec.name.clone_from(&spec.bin);
+    }
+    Ok(spec)
+}
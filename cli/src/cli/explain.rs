@@ -18,7 +18,9 @@ use std::path::PathBuf;
 use itertools::Itertools;
 use miette::Result;
 use usage::error::UsageErr;
-use usage::parse::{ParseOutput, Parser, TokenRole, ValueOrigin};
+use usage::parse::{ParseOutput, Parser, TokenBinding, TokenRole, ValueOrigin};
+use usage::spec::effect::SpecCommandEffect;
+use usage::spec::{redacted, REDACTED};
 use usage::{Spec, SpecArg, SpecFlag};
 
 use crate::cli::generate::{file_or_spec, select_view};
@@ -167,8 +169,8 @@ pub fn explain(spec: &Spec, argv: &[String], env: Option<HashMap<String, String>
     match parser(empty_mount_answers(&spec.cmd)).explain_refused(argv) {
         Ok(out) => Explanation::from_parse(argv, &out, false, refused),
         Err(tokens) => Explanation {
-            argv: argv.to_vec(),
-            tokens: tokens.iter().map(TokenRow::from_binding).collect(),
+            argv: redacted_argv(argv, &tokens),
+            tokens: token_rows(&tokens),
             refused,
             fallbacks_applied: false,
             ..Explanation::default()
@@ -182,6 +184,10 @@ pub struct Explanation {
     pub argv: Vec<String>,
     /// The command path the parse resolved to.
     pub command: Vec<String>,
+    /// What running this line would do: the command's effect, raised by the flags and
+    /// arguments argv supplied. Values from the environment or a default do not count.
+    /// `None` when nothing involved declares one, which is unknown rather than safe.
+    pub effect: Option<SpecCommandEffect>,
     pub tokens: Vec<TokenRow>,
     pub values: Vec<ValueRow>,
     /// Declared defaults that did not win, and what won instead.
@@ -305,7 +311,7 @@ impl Explanation {
         fallbacks_applied: bool,
         refused: Option<String>,
     ) -> Self {
-        let tokens = out.tokens.iter().map(TokenRow::from_binding).collect_vec();
+        let tokens = token_rows(&out.tokens);
         let mut values = vec![];
         let mut shadowed = vec![];
 
@@ -315,7 +321,7 @@ impl Explanation {
                 kind: "flag".to_string(),
                 name: flag.name.clone(),
                 display: flag_display(flag),
-                value: value.to_string(),
+                value: redacted(flag.sensitive, &value.to_string()).to_string(),
                 origins: origins.clone(),
             });
             // A declared default that did not supply the value lost to whatever did. This is
@@ -327,7 +333,7 @@ impl Explanation {
                         kind: "flag".to_string(),
                         name: flag.name.clone(),
                         display: flag_display(flag),
-                        value: declared,
+                        value: redacted(flag.sensitive, &declared).to_string(),
                         lost_to: origins,
                     });
                 }
@@ -339,7 +345,7 @@ impl Explanation {
                 kind: "arg".to_string(),
                 name: arg.name.clone(),
                 display: arg.usage(),
-                value: value.to_string(),
+                value: redacted(arg.sensitive, &value.to_string()).to_string(),
                 origins: origins.clone(),
             });
             if !arg.default.is_empty() && !origins.iter().any(|o| matches!(o, OriginRow::Default)) {
@@ -347,15 +353,16 @@ impl Explanation {
                     kind: "arg".to_string(),
                     name: arg.name.clone(),
                     display: arg.usage(),
-                    value: arg.default.join(" "),
+                    value: redacted(arg.sensitive, &arg.default.join(" ")).to_string(),
                     lost_to: origins,
                 });
             }
         }
 
         Self {
-            argv: argv.to_vec(),
+            argv: redacted_argv(argv, &out.tokens),
             command: out.cmds.iter().map(|cmd| cmd.name.clone()).collect(),
+            effect: out.effect(),
             tokens,
             values,
             shadowed,
@@ -391,6 +398,9 @@ impl Explanation {
         if !self.command.is_empty() {
             out.push_str(&format!("command  {}\n", self.command.join(" ")));
         }
+        if let Some(effect) = self.effect {
+            out.push_str(&format!("effect   {}\n", effect.label()));
+        }
         if !self.fallbacks_applied {
             out.push_str(
                 "\nthe parse stopped before the environment and defaults were applied,\n\
@@ -497,10 +507,10 @@ fn pad_row(row: &[String], widths: &[usize]) -> String {
 }
 
 impl TokenRow {
-    fn from_binding(token: &usage::parse::TokenBinding) -> Self {
+    fn from_binding(token: &TokenBinding, secret: bool) -> Self {
         Self {
             index: token.index,
-            text: token.word.clone(),
+            text: redacted(secret, &token.word).to_string(),
             synthesized: token.synthesized,
             roles: token.roles.iter().map(RoleRow::from_role).collect(),
         }
@@ -527,12 +537,12 @@ impl RoleRow {
                 attached,
             } => Self::Value {
                 name: flag.name.clone(),
-                values: values.clone(),
+                values: redacted_values(flag.sensitive, values),
                 attached: *attached,
             },
             TokenRole::Arg { arg, values } => Self::Arg {
                 name: arg.name.clone(),
-                values: values.clone(),
+                values: redacted_values(arg.sensitive, values),
             },
             TokenRole::Separator => Self::Separator,
             TokenRole::ValueTerminator { ends } => Self::ValueTerminator { ends: ends.clone() },
@@ -698,6 +708,62 @@ fn origin_row(origin: &ValueOrigin) -> OriginRow {
     }
 }
 
+fn token_rows(tokens: &[TokenBinding]) -> Vec<TokenRow> {
+    let secrets = secret_words(tokens);
+    tokens
+        .iter()
+        .map(|token| TokenRow::from_binding(token, secrets.contains(&token.index)))
+        .collect()
+}
+
+/// The argv positions holding a `sensitive` value.
+///
+/// The words that bound as one, and the value a sensitive flag was given when the parse refused
+/// it: a value `choices` turned down binds to nothing, and is still the secret — whether it was
+/// its own word or rode along on the flag's.
+fn secret_words(tokens: &[TokenBinding]) -> Vec<usize> {
+    let mut secrets = vec![];
+    for (at, token) in tokens.iter().enumerate() {
+        let attached = matches!(
+            token.roles.last(),
+            Some(TokenRole::Flag { flag, spelling, .. })
+                if flag.sensitive && flag.arg.is_some() && token.word != *spelling
+        );
+        let after_a_secret_flag = at > 0
+            && token.roles.is_empty()
+            && matches!(
+                tokens[at - 1].roles.last(),
+                Some(TokenRole::Flag { flag, negated: false, .. })
+                    if flag.sensitive && flag.arg.is_some()
+            );
+        if token.is_sensitive() || attached || after_a_secret_flag {
+            secrets.push(token.index);
+        }
+    }
+    secrets
+}
+
+/// The command line, with each word that carried a `sensitive` value masked.
+///
+/// The whole word rather than the part after `=`: the token table beside it already says which
+/// flag it was, and a report is what gets pasted into a bug.
+fn redacted_argv(argv: &[String], tokens: &[TokenBinding]) -> Vec<String> {
+    let mut argv = argv.to_vec();
+    for index in secret_words(tokens) {
+        if let Some(word) = argv.get_mut(index) {
+            *word = REDACTED.to_string();
+        }
+    }
+    argv
+}
+
+fn redacted_values(sensitive: bool, values: &[String]) -> Vec<String> {
+    match sensitive {
+        true => vec![REDACTED.to_string()],
+        false => values.to_vec(),
+    }
+}
+
 /// The default a flag would supply, from wherever it is declared.
 ///
 /// Two places, and the parser prefers them in this order (`Parser::parse` binds `flag.default`
@@ -957,6 +1023,52 @@ mount run="false --usage"
         assert_eq!(value(&explanation, "color"), "never env MYCLI_COLOR");
     }
 
+    #[test]
+    fn a_sensitive_value_is_masked_wherever_the_report_would_print_it() {
+        let spec: Spec = r#"
+name "mycli"
+bin "mycli"
+flag "--token <token>" env="MYCLI_TOKEN" default="dev" sensitive=#true
+flag "--key <key>" sensitive=#true {
+    choices "a" "b"
+}
+arg "<secret>" sensitive=#true
+"#
+        .parse()
+        .unwrap();
+        let explanation = explain(
+            &spec,
+            &argv(&["mycli", "--token=hunter2", "s3cr3t"]),
+            env(&[]),
+        );
+        let report = explanation.render();
+        for secret in ["hunter2", "s3cr3t", "dev"] {
+            assert!(!report.contains(secret), "{secret} in:\n{report}");
+        }
+        assert_eq!(explanation.argv, ["mycli", REDACTED, REDACTED]);
+        assert_eq!(
+            roles(&explanation, 1),
+            ["flag --token", "value of token = \"<redacted>\", attached"]
+        );
+        assert_eq!(value(&explanation, "token"), "<redacted> argv [1]");
+        assert_eq!(explanation.shadowed[0].value, REDACTED);
+
+        // And from the environment, and in the message a refused value leaves behind.
+        let explanation = explain(
+            &spec,
+            &argv(&["mycli", "--key", "hunter2", "s3cr3t"]),
+            env(&[("MYCLI_TOKEN", "hunter2")]),
+        );
+        let report = explanation.render();
+        assert!(!report.contains("hunter2"), "{report}");
+        assert!(
+            report.contains("Invalid choice for option key: <redacted>"),
+            "{report}"
+        );
+        let explanation = explain(&spec, &argv(&["mycli", "--key=hunter2"]), env(&[]));
+        assert!(!explanation.render().contains("hunter2"));
+    }
+
     #[test]
     fn a_shadowed_default_says_what_beat_it() {
         let spec = fixture();
@@ -1053,6 +1165,34 @@ mount run="false --usage"
         assert_eq!(value(&explanation, "jobs"), "1 default");
     }
 
+    #[test]
+    fn the_effect_counts_what_argv_supplied_and_nothing_else() {
+        // `--force` destroys, and the environment setting it is not the user typing it.
+        // An explanation is asked for before running, so over-reporting here would be the
+        // same false alarm on every line the environment touches.
+        let spec: Spec = r#"
+bin "mycli"
+cmd "deploy" effect="write" {
+    flag "--force" effect="destructive" env="MYCLI_FORCE"
+}
+"#
+        .parse()
+        .unwrap();
+        let quiet = explain(
+            &spec,
+            &argv(&["mycli", "deploy"]),
+            env(&[("MYCLI_FORCE", "1")]),
+        );
+        assert_eq!(quiet.effect, Some(SpecCommandEffect::Write));
+        let forced = explain(&spec, &argv(&["mycli", "deploy", "--force"]), env(&[]));
+        assert_eq!(forced.effect, Some(SpecCommandEffect::Destructive));
+        assert!(forced.render().contains("effect   destructive\n"));
+        assert_eq!(
+            serde_json::to_value(&forced).unwrap()["effect"],
+            "destructive"
+        );
+    }
+
     #[test]
     fn the_json_shape_carries_the_same_facts() {
         let spec = fixture();
@@ -0,0 +1,487 @@
+//! `usage fmt`: one layout for every spec file, so that a diff is only ever about content.
+//!
+//! Like `lint --fix`, this edits kdl's document model rather than printing the spec's own
+//! `Display`. That prints the canonical layout too, but it drops every comment and writes out
+//! what `include` and `use` pulled in, so the file it produces is not the file that was
+//! formatted. Here each node keeps its comments and is only re-laid out, and what the file
+//! means is checked unchanged before anything is written.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use kdl::{
+    KdlDocument, KdlDocumentFormat, KdlEntry, KdlEntryFormat, KdlNode, KdlNodeFormat, KdlValue,
+};
+use miette::{bail, IntoDiagnostic, Result};
+use usage::spec::helpers::string_entry;
+use usage::spec::SourceLocation;
+use usage::{Spec, SpecCommand};
+
+use crate::cli::lint::fix::Source;
+
+/// Format spec files in the canonical layout
+///
+/// Properties go in the order usage writes them, strings take usage's quoting,
+/// a flag's short forms come before its long ones ("-f --force <file>"), each
+/// level is indented four spaces, every node starts its own line, and runs of
+/// blank lines become one. Comments stay with the nodes they are beside, and
+/// `include` and `use` are left as written.
+///
+/// --check changes nothing: it prints each file that is not formatted and
+/// exits 1, for holding the layout in CI.
+#[derive(usage_rs::Args)]
+#[usage(effect = "write", verbatim_doc_comment)]
+pub struct Fmt {
+    /// Spec files to format, use "-" to read from stdin and write to stdout
+    #[usage(required, value_hint = usage_rs::ValueHint::FilePath)]
+    files: Vec<PathBuf>,
+
+    /// Print the files that are not formatted, and change nothing
+    #[usage(long)]
+    check: bool,
+}
+
+impl usage_rs::Run for Fmt {
+    type Output = Result<()>;
+
+    fn run(self) -> Self::Output {
+        let mut unformatted = false;
+        for file in &self.files {
+            let stdin = file.as_os_str() == "-";
+            let text = read(file)?;
+            let formatted = format(file, &text)?;
+            if self.check {
+                if formatted != text {
+                    println!("{}", file.display());
+                    unformatted = true;
+                }
+            } else if stdin {
+                print!("{formatted}");
+            } else if formatted != text {
+                std::fs::write(file, formatted)
+                    .map_err(|err| miette::miette!("Failed to write {}: {err}", file.display()))?;
+            }
+        }
+        if unformatted {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+fn read(file: &Path) -> Result<String> {
+    let mut text = String::new();
+    if file.as_os_str() == "-" {
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .into_diagnostic()?;
+    } else {
+        text = std::fs::read_to_string(file)
+            .map_err(|err| miette::miette!("Failed to read {}: {err}", file.display()))?;
+    }
+    Ok(text)
+}
+
+/// The spec file at `file`, which holds `text`, in the canonical layout.
+pub(crate) fn format(file: &Path, text: &str) -> Result<String> {
+    if text.starts_with("#!") {
+        bail!(
+            "{} keeps its spec in `#USAGE` comments, which fmt does not rewrite",
+            file.display()
+        );
+    }
+    // Stdin is parsed as a string, whose locations name no file.
+    let path = if file.as_os_str() == "-" {
+        Path::new("")
+    } else {
+        file
+    };
+    let parse = |text: &str| -> Result<Spec> {
+        let spec = if path.as_os_str().is_empty() {
+            text.parse()
+        } else {
+            Spec::parse_file_contents(path, text)
+        };
+        spec.map_err(miette::Report::new)
+    };
+    let spec = parse(text)?;
+    let source = Source::new(path, text.to_string());
+    let Some(mut doc) = source.document() else {
+        bail!("{} is not a KDL document", file.display());
+    };
+    let mut formatter = Formatter {
+        source: &source,
+        order: HashMap::new(),
+    };
+    formatter.declarations(&spec.cmd);
+    formatter.block(&mut doc, 0);
+    let formatted = doc.to_string();
+
+    // A layout bug should cost the run, not the spec: nothing is written unless the result
+    // reads back as the same spec.
+    let again = parse(&formatted)
+        .map_err(|err| err.wrap_err("formatting produced a spec that does not parse"))?;
+    if again.to_string() != spec.to_string() {
+        bail!(
+            "formatting {} would change what it means; left unchanged",
+            file.display()
+        );
+    }
+    Ok(formatted)
+}
+
+struct Formatter<'a> {
+    source: &'a Source,
+    /// The property names usage writes for each flag, argument and command declared in this
+    /// file, in the order it writes them, by where each was declared.
+    order: HashMap<(usize, usize), Vec<String>>,
+}
+
+impl Formatter<'_> {
+    fn declarations(&mut self, cmd: &SpecCommand) {
+        let mut declare = |at: &Option<SourceLocation>, node: KdlNode| {
+            let Some(at) = at.as_ref().filter(|at| at.file == self.source.path()) else {
+                return;
+            };
+            let names = node
+                .entries()
+                .iter()
+                .filter_map(|e| e.name())
+                .map(|n| n.value().to_string())
+                .collect();
+            self.order.insert((at.line, at.column), names);
+        };
+        declare(&cmd.source, cmd.into());
+        for flag in &cmd.flags {
+            declare(&flag.source, flag.into());
+        }
+        for arg in &cmd.args {
+            declare(&arg.source, arg.into());
+        }
+        for sub in cmd.subcommands.values() {
+            self.declarations(sub);
+        }
+    }
+
+    /// Lays out one block: the top level of the file, or a node's children `depth` levels in.
+    fn block(&self, doc: &mut KdlDocument, depth: usize) {
+        let indent = "    ".repeat(depth);
+        // Whether the line before the next node is still open: the `{` line of a block, or
+        // a node ended by `;`. Whatever else is on that line is read with the next node.
+        let mut open = depth > 0;
+        let mut first = true;
+        for node in doc.nodes_mut() {
+            let format = node.format().cloned().unwrap_or_default();
+            let leading = lay_out(&format.leading, &indent, open, first, None);
+            self.entries(node);
+            let mut empty = false;
+            if let Some(children) = node.children_mut() {
+                empty = children.nodes().is_empty()
+                    && children.format().is_none_or(|f| {
+                        f.leading.trim().is_empty() && f.trailing.trim().is_empty()
+                    });
+                self.block(children, depth + 1);
+            }
+            if empty {
+                node.clear_children();
+            }
+            let before_children = squeeze(&format.before_children)
+                .map(|s| format!(" {s} "))
+                .unwrap_or_else(|| " ".to_string());
+            let mut before_terminator = squeeze(&format.before_terminator)
+                .map(|s| format!(" {s}"))
+                .unwrap_or_default();
+            // `;` and the end of a block leave the line open for the next node to close.
+            let terminator = match format.terminator.trim() {
+                "" if format.terminator.contains('\n') => "\n".to_string(),
+                "" | ";" => String::new(),
+                comment => {
+                    before_terminator.push(' ');
+                    format!("{}\n", comment.trim_start_matches(';').trim_start())
+                }
+            };
+            open = terminator.is_empty();
+            first = false;
+            node.set_format(KdlNodeFormat {
+                leading,
+                before_children,
+                before_terminator,
+                terminator,
+                ..Default::default()
+            });
+        }
+        let trailing = doc.format().map(|f| f.trailing.clone()).unwrap_or_default();
+        let outer = "    ".repeat(depth.saturating_sub(1));
+        doc.set_format(KdlDocumentFormat {
+            leading: String::new(),
+            trailing: lay_out(&trailing, &indent, open, first, Some(&outer)),
+        });
+    }
+
+    /// Puts a node's arguments first and its properties in the order usage writes them, each
+    /// quoted the way usage quotes it.
+    fn entries(&self, node: &mut KdlNode) {
+        let order = self
+            .order
+            .get(&self.source.key(node.name().span().offset()));
+        let flag = node.name().value() == "flag";
+        let entries = node.entries_mut();
+        // Arguments keep their order among themselves, which is their meaning. A property
+        // usage does not write here goes last, in the order it was found.
+        entries.sort_by_key(|entry| match entry.name() {
+            None => 0,
+            Some(name) => match order.and_then(|o| o.iter().position(|n| n == name.value())) {
+                Some(rank) => rank + 1,
+                None => usize::MAX,
+            },
+        });
+        for (i, entry) in entries.iter_mut().enumerate() {
+            *entry = canonical(entry, flag && i == 0 && entry.name().is_none());
+        }
+    }
+}
+
+/// An entry as usage writes it, keeping any comment before it.
+///
+/// A flag's declaration is written the way usage writes it too, which is not how other
+/// strings are: `flag -v` rather than `flag "-v"`.
+fn canonical(entry: &KdlEntry, declaration: bool) -> KdlEntry {
+    let key = entry.name().map(|name| name.value());
+    let mut out = match (entry.value(), key) {
+        (KdlValue::String(s), None) if declaration => KdlEntry::new(spell(s)),
+        (KdlValue::String(s), key) => string_entry(key, s),
+        (value, Some(key)) => KdlEntry::new_prop(key, value.clone()),
+        (value, None) => KdlEntry::new(value.clone()),
+    };
+    if let Some(ty) = entry.ty() {
+        out.set_ty(ty.clone());
+    }
+    let value_repr = match out.format() {
+        Some(format) => format.value_repr.clone(),
+        None => out.value().to_string(),
+    };
+    let leading = entry
+        .format()
+        .map(|f| f.leading.as_str())
+        .unwrap_or_default();
+    out.set_format(KdlEntryFormat {
+        value_repr,
+        leading: squeeze(leading)
+            .map(|s| format!(" {s} "))
+            .unwrap_or_else(|| " ".to_string()),
+        ..Default::default()
+    });
+    out
+}
+
+/// A flag's spelling with its short forms ahead of its long ones, keeping the order within
+/// each: `--force -f <file>` is `-f --force <file>`.
+fn spell(declaration: &str) -> String {
+    let words: Vec<&str> = declaration.split_whitespace().collect();
+    let name = usize::from(words.first().is_some_and(|w| w.ends_with(':')));
+    let forms = words[name..]
+        .iter()
+        .take_while(|w| w.starts_with('-'))
+        .count();
+    let mut sorted = words.clone();
+    sorted[name..name + forms].sort_by_key(|w| w.starts_with("--"));
+    sorted.join(" ")
+}
+
+/// What is between two tokens besides whitespace, or nothing. A slashdashed entry or a
+/// comment is kept; a line continuation with nothing else on it is joined up.
+fn squeeze(between: &str) -> Option<String> {
+    if between.chars().all(|c| c.is_whitespace() || c == '\\') {
+        return None;
+    }
+    // A comment that ends at its line, or runs over several, keeps the line breaks it has.
+    if between.contains('\n') && (between.contains("//") || between.contains("/*")) {
+        return Some(between.trim_matches(' ').to_string());
+    }
+    let words: Vec<&str> = between
+        .split('\n')
+        .map(|line| line.trim().trim_end_matches('\\').trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    Some(words.join(" "))
+}
+
+/// The text between two nodes, laid out for the node after it at `indent`: comments
+/// re-indented, runs of blank lines made one, and none at the start of a block.
+///
+/// `open` is whether the node before left its line open, in which case the first line here is
+/// the rest of it: a `//` comment stays there, anything else gets a line of its own. `first`
+/// is whether nothing came before in this block. `closing` is for the text before a block's
+/// `}`, given the indent of the `}`, or the end of the file: trailing blank lines go too.
+fn lay_out(text: &str, indent: &str, open: bool, first: bool, closing: Option<&str>) -> String {
+    let mut out = String::new();
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    // What is in front of the node (or the `}`) on its own line.
+    let own = lines.pop().unwrap_or_default();
+    if open {
+        let rest = if lines.is_empty() {
+            ""
+        } else {
+            lines.remove(0)
+        };
+        if rest.trim().starts_with("//") {
+            out.push(' ');
+            out.push_str(rest.trim());
+        } else if !rest.trim().is_empty() {
+            lines.insert(0, rest);
+        }
+        out.push('\n');
+    }
+    let mut at_start = first;
+    let mut blank = false;
+    // A block comment, or a slashdashed node's children, carried over from an earlier line:
+    // how deep, whether it is a slashdash, and the indent its first line had.
+    let mut depth = 0i32;
+    let mut slashdash = false;
+    let mut was = "";
+    for line in lines {
+        if depth <= 0 {
+            if line.trim().is_empty() {
+                blank = true;
+                continue;
+            }
+            if blank && !at_start {
+                out.push('\n');
+            }
+            blank = false;
+            was = &line[..line.len() - line.trim_start().len()];
+            slashdash = line.trim_start().starts_with("/-");
+            out.push_str(indent);
+            out.push_str(line.trim());
+            depth = 0;
+        } else {
+            match line.strip_prefix(was) {
+                Some(rest) if !line.trim().is_empty() => {
+                    out.push_str(indent);
+                    out.push_str(rest.trim_end());
+                }
+                _ => out.push_str(line.trim_end()),
+            }
+        }
+        out.push('\n');
+        at_start = false;
+        depth += (line.matches("/*").count() as i32) - (line.matches("*/").count() as i32);
+        if slashdash {
+            depth += (line.matches('{').count() as i32) - (line.matches('}').count() as i32);
+        }
+    }
+    if blank && !at_start && closing.is_none() {
+        out.push('\n');
+    }
+    if !own.trim().is_empty() {
+        out.push_str(indent);
+        out.push_str(own.trim_start());
+        if closing.is_none() {
+            return out;
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    }
+    out.push_str(closing.unwrap_or(indent));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(text: &str) -> String {
+        format(Path::new("-"), text).unwrap()
+    }
+
+    #[test]
+    fn a_spec_comes_out_in_the_canonical_layout() {
+        assert_eq!(
+            fmt(r#"
+
+
+// head
+
+name "t"
+about "t"
+
+
+
+flag "--force -f <file>"   required=#true help="Force" // the big one
+cmd "build" help="b"   {   // builds
+  flag "--out" help="o" ; arg "<dir>"   help="d" required=#false
+    /* about
+       the rest */
+
+        cmd "x" {
+        }
+}
+// tail
+
+"#),
+            r#"// head
+
+name t
+about t
+
+flag "-f --force <file>" help=Force required=#true // the big one
+cmd build help=b { // builds
+    flag --out help=o
+    arg <dir> help=d required=#false
+    /* about
+       the rest */
+
+    cmd x
+}
+// tail
+"#
+        );
+    }
+
+    #[test]
+    fn formatting_twice_changes_nothing_more() {
+        let once = fmt(r#"name "t"
+flag "-v" help="v" { alias "-x"; }
+cmd "a" { flag "-q"; /* c */ arg "<x>" }
+flag "--all -a" /-hide=#true \
+    help="a"
+/-flag "--gone" {
+  long_help "g"
+}
+"#);
+        assert_eq!(
+            once,
+            r#"name t
+flag -v help=v {
+    alias "-x"
+}
+cmd a {
+    flag -q
+    /* c */ arg <x>
+}
+flag "-a --all" /-hide=#true help=a
+/-flag "--gone" {
+  long_help "g"
+}
+"#
+        );
+        assert_eq!(fmt(&once), once);
+    }
+
+    #[test]
+    fn a_script_is_left_to_its_author() {
+        let err = format(Path::new("tool"), "#!/bin/sh\n#USAGE flag \"-v\"\n").unwrap_err();
+        assert!(err.to_string().contains("#USAGE"), "{err}");
+    }
+
+    #[test]
+    fn short_forms_come_first() {
+        assert_eq!(spell("--force -f"), "-f --force");
+        assert_eq!(
+            spell("force:  --force -f <file>"),
+            "force: -f --force <file>"
+        );
+        assert_eq!(spell("--a -b --c -d <x> <y>"), "-b -d --a --c <x> <y>");
+    }
+}
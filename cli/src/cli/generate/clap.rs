@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use usage::scaffold::Framework;
+use usage_rs::Args;
+
+use crate::cli::generate;
+
+/// Scaffold a CLI's Rust source from a usage spec, for clap's derive
+///
+/// The same modules as `usage g rust`, written for clap 4 with its `derive` feature. clap
+/// has no spelling for some of what a spec says, such as effects and deprecations, so more
+/// of it is listed on stderr to be carried over by hand.
+#[derive(Args)]
+#[usage(effect = "write")]
+pub struct Clap {
+    /// A usage spec taken in as a file, use "-" to read from stdin
+    #[usage(short, long)]
+    file: Option<PathBuf>,
+
+    /// Directory the modules are written to, the root's as mod.rs
+    #[usage(short, long, value_hint = usage_rs::ValueHint::DirPath)]
+    out_dir: PathBuf,
+
+    /// Raw string spec input
+    #[usage(long, required_unless = "--file", overrides = "--file")]
+    spec: Option<String>,
+}
+
+impl usage_rs::Run for Clap {
+    type Output = miette::Result<()>;
+
+    fn run(self) -> Self::Output {
+        let spec = generate::file_or_spec(&self.file, &self.spec)?;
+        generate::rust::scaffold(&spec, self.file.as_deref(), &self.out_dir, Framework::Clap)
+    }
+}
@@ -5,13 +5,22 @@ use usage_rs::Args;
 
 use super::parse_file_or_stdin;
 
-/// Generate shell completion scripts for bash, fish, nu, powershell, or zsh
+/// Generate shell completion scripts for bash, elvish, fish, nu, powershell, xonsh, or zsh
 #[derive(Args)]
-#[usage(alias = "c", alias_hidden("complete", "completions"), effect = "read")]
+#[usage(
+    alias = "c",
+    alias_hidden("complete", "completions"),
+    effect = "read",
+    allow_missing_positional
+)]
 pub struct Completion {
     /// Shell to generate completions for
-    #[usage(choices("bash", "fish", "nu", "powershell", "zsh"))]
-    shell: String,
+    #[usage(
+        choices("bash", "elvish", "fish", "nu", "powershell", "xonsh", "zsh"),
+        required_unless = "--all-shells",
+        conflicts_with = "--all-shells"
+    )]
+    shell: Option<String>,
 
     /// The CLI which we're generating completions for
     bin: String,
@@ -28,6 +37,21 @@ pub struct Completion {
     #[usage(long, requires = "--install", effect = "write")]
     force: bool,
 
+    /// Remove the script an install wrote, instead of printing one
+    ///
+    /// Only a file usage generated is removed. A line the install asked you to add to a startup
+    /// file stays there, and is printed so you can take it out.
+    #[usage(long, conflicts_with = "--install", effect = "write")]
+    uninstall: bool,
+
+    /// Every shell on PATH rather than one
+    ///
+    /// With --install or --uninstall, acts on each of them. On its own, prints where each script
+    /// would go and writes nothing. --uninstall looks in every shell's directory, so a shell
+    /// removed since the install does not keep its script.
+    #[usage(long)]
+    all_shells: bool,
+
     /// A .usage.kdl spec file to use for generating completions, use "-" to read from stdin
     #[usage(short, long)]
     file: Option<PathBuf>,
@@ -45,7 +69,7 @@ pub struct Completion {
     /// A command which generates a usage spec
     /// e.g.: `mycli --usage` or `mycli completion usage`
     /// Defaults to "$bin --usage"
-    #[usage(long, required_unless = "--file")]
+    #[usage(long, required_unless("--file", "--uninstall"))]
     usage_cmd: Option<String>,
 }
 
@@ -53,6 +77,30 @@ impl usage_rs::Run for Completion {
     type Output = miette::Result<()>;
 
     fn run(self) -> Self::Output {
+        if self.uninstall {
+            return self.uninstall();
+        }
+        if self.all_shells {
+            return self.all_shells();
+        }
+        // `required_unless = "--all-shells"`, and that is the branch above.
+        let shell = self.shell.clone().unwrap_or_default();
+        let script = render(&mut self.options()?, &shell)?;
+        if !self.install {
+            // `write_stdout` rather than `println!`, which panics on a broken pipe — and
+            // `usage g completion bash mycli | head -1` is an ordinary thing to type.
+            return Ok(super::write_stdout(&script)?);
+        }
+        self.install(&shell, &script)
+    }
+}
+
+impl Completion {
+    /// What to render a script from, for whichever shell it turns out to be.
+    ///
+    /// Apart from the shell, so that `--all-shells` reads the spec once — `-f -` has only one stdin
+    /// to give.
+    fn options(&self) -> miette::Result<CompleteOptions> {
         // TODO: refactor this
         let spec = match &self.file {
             Some(file) => parse_file_or_stdin(file)?,
@@ -62,9 +110,9 @@ impl usage_rs::Run for Completion {
             true => Some(spec),
             false => None,
         };
-        let opts = CompleteOptions {
+        Ok(CompleteOptions {
             usage_bin: self.usage_bin.clone(),
-            shell: self.shell.clone(),
+            shell: String::new(),
             bin: self.bin.clone(),
             cache_key: self.cache_key.clone(),
             spec,
@@ -76,68 +124,161 @@ impl usage_rs::Run for Completion {
                     f.to_string_lossy().to_string()
                 }
             }),
-        };
+        })
+    }
 
-        // Trailing newline included: a script is a file, and one without a final newline is a file
-        // half the tools that read it complain about.
-        let script = format!("{}\n", usage::complete::complete(&opts)?.trim());
-        if !self.install {
-            // `write_stdout` rather than `println!`, which panics on a broken pipe — and
-            // `usage g completion bash mycli | head -1` is an ordinary thing to type.
-            return Ok(super::write_stdout(&script)?);
+    fn on_foreign(&self) -> usage_rs::install::OnForeign {
+        if self.force {
+            usage_rs::install::OnForeign::Overwrite
+        } else {
+            usage_rs::install::OnForeign::Refuse
         }
-        self.install(&script)
     }
-}
 
-impl Completion {
     /// Put the script where this shell looks for it, and say what is left to do.
     ///
     /// The resolver is the one a compiled binary uses to install its own script, so the location is
     /// decided in one place regardless of which side is asking.
-    fn install(&self, script: &str) -> miette::Result<()> {
-        use usage_rs::install::{self, OnForeign, Wrote};
+    fn install(&self, shell: &str, script: &str) -> miette::Result<()> {
+        use usage_rs::install;
 
-        let shell = usage_rs::complete::Shell::from_name(&self.shell)
-            .ok_or_else(|| miette::miette!("{} has no completion script", self.shell))?;
+        let shell = parse_shell(shell)?;
         // Described from this process rather than reached for inside the resolver, which is what
         // lets a test point the same code path at a directory of its own.
         let env = install::Env::from_process();
         let plan = install::plan(&self.bin, shell, &env).map_err(as_diagnostic)?;
+        let done = install::write(&plan, script, self.on_foreign()).map_err(as_diagnostic)?;
+        announce(&done);
+        Ok(())
+    }
 
-        let done = install::write(
-            &plan,
-            script,
-            if self.force {
-                OnForeign::Overwrite
-            } else {
-                OnForeign::Refuse
-            },
-        )
-        .map_err(as_diagnostic)?;
-
-        // Everything here goes to stderr, and stdout stays empty. A note about a write is not the
-        // thing written — the same reason `write_or_stdout` moved its progress line. And after the
-        // write rather than before it: a refusal that had already announced an installation would
-        // be describing something that did not happen.
-        eprintln!("installing to {}", done.plan.path.display());
-        if done.wrote == Wrote::Unchanged {
-            eprintln!("already up to date");
+    /// `--all-shells`: install for every shell on `PATH`, or, without `--install`, say where each
+    /// script would go.
+    ///
+    /// Planned in one piece before any script is rendered or written, so a shell with nowhere to go
+    /// fails the set rather than half of it.
+    fn all_shells(&self) -> miette::Result<()> {
+        use usage_rs::install;
+
+        let env = install::Env::from_process();
+        let plans = install::plan_all(&self.bin, &env).map_err(as_diagnostic)?;
+        if plans.is_empty() {
+            let message = "none of bash, zsh, fish, nu, pwsh, elvish or xonsh was found on PATH";
+            if self.install {
+                miette::bail!("{message}, so there is nothing to install for");
+            }
+            eprintln!("{message}");
+            return Ok(());
         }
-        if let Some(line) = done.plan.loading.instruction() {
-            let file = match &done.plan.loading {
-                install::Loading::Manual { file, .. } => file.as_str(),
-                _ => "your shell's startup file",
-            };
-            eprintln!("\nadd this to {file}, once:\n\n{line}\n");
+        if !self.install {
+            return Ok(super::write_stdout(&preview(&plans))?);
+        }
+
+        let mut opts = self.options()?;
+        let mut scripts = Vec::with_capacity(plans.len());
+        for plan in plans {
+            let script = render(&mut opts, plan.shell.as_str())?;
+            scripts.push((plan, script));
+        }
+        let done = install::write_all(&scripts, self.on_foreign()).map_err(as_diagnostic)?;
+        done.iter().for_each(announce);
+        Ok(())
+    }
+
+    /// Take the script back out, for one shell or for all of them.
+    fn uninstall(&self) -> miette::Result<()> {
+        use usage_rs::install::{self, Removed};
+
+        let env = install::Env::from_process();
+        let gone = if self.all_shells {
+            install::uninstall_all(&self.bin, &env).map_err(as_diagnostic)?
+        } else {
+            let shell = parse_shell(self.shell.as_deref().unwrap_or_default())?;
+            vec![install::uninstall(&self.bin, shell, &env).map_err(as_diagnostic)?]
+        };
+
+        // stderr, as an install reports: nothing here is output anyone would pipe.
+        for gone in &gone {
+            let path = gone.plan.path.display();
+            if gone.removed != Removed::Deleted {
+                // Said for one shell, which was asked about by name; for every shell it is the
+                // common case and only noise.
+                if !self.all_shells {
+                    eprintln!("nothing to remove at {path}");
+                }
+                continue;
+            }
+            eprintln!("removed {path}");
+            if let install::Loading::Manual { line, file, .. } = &gone.plan.loading {
+                eprintln!("\nif you added this to {file}, it can come out too:\n\n{line}\n");
+            }
         }
-        if let Some(note) = done.plan.note {
-            eprintln!("note: {note}");
+        if self.all_shells && gone.iter().all(|g| g.removed == Removed::Absent) {
+            eprintln!("no completion script for {} was installed", self.bin);
         }
         Ok(())
     }
 }
 
+/// The completion script for `shell`, as it is printed or installed.
+fn render(opts: &mut CompleteOptions, shell: &str) -> miette::Result<String> {
+    opts.shell = shell.to_string();
+    // Trailing newline included: a script is a file, and one without a final newline is a file half
+    // the tools that read it complain about.
+    Ok(format!("{}\n", usage::complete::complete(opts)?.trim()))
+}
+
+/// A shell named on the command line, as the resolver knows it.
+fn parse_shell(name: &str) -> miette::Result<usage_rs::complete::Shell> {
+    usage_rs::complete::Shell::from_name(name)
+        .ok_or_else(|| miette::miette!("{name} has no completion script"))
+}
+
+/// Say where a script went and what is left to do.
+///
+/// Everything here goes to stderr, and stdout stays empty. A note about a write is not the thing
+/// written — the same reason `write_or_stdout` moved its progress line. And after the write rather
+/// than before it: a refusal that had already announced an installation would be describing
+/// something that did not happen.
+fn announce(done: &usage_rs::install::Installed) {
+    use usage_rs::install::{Loading, Wrote};
+
+    eprintln!("installing to {}", done.plan.path.display());
+    if done.wrote == Wrote::Unchanged {
+        eprintln!("already up to date");
+    }
+    if let Some(line) = done.plan.loading.instruction() {
+        let file = match &done.plan.loading {
+            Loading::Manual { file, .. } => file.as_str(),
+            _ => "your shell's startup file",
+        };
+        eprintln!("\nadd this to {file}, once:\n\n{line}\n");
+    }
+    if let Some(note) = done.plan.note {
+        eprintln!("note: {note}");
+    }
+}
+
+/// Where `--all-shells --install` would put each script, one shell to a line, with any line a shell
+/// would still need indented beneath it.
+fn preview(plans: &[usage_rs::install::Plan]) -> String {
+    let mut out = String::new();
+    for plan in plans {
+        out.push_str(&format!(
+            "{}: {}\n",
+            plan.shell.as_str(),
+            plan.path.display()
+        ));
+        if let usage_rs::install::Loading::Manual { line, file, .. } = &plan.loading {
+            out.push_str(&format!("  then add to {file}:\n"));
+            for line in line.lines() {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+    }
+    out
+}
+
 /// An install failure as something the CLI can print, with the way out where there is one.
 ///
 /// The chain is walked rather than formatted away: `Display` on an install error names the step and
@@ -3,6 +3,7 @@ use std::vec;
 
 use indexmap::IndexMap;
 use itertools::Itertools;
+use usage::spec::config_type::Base;
 use usage::{SpecArg, SpecCommand, SpecComplete, SpecFlag};
 use usage_rs::Args;
 
@@ -237,15 +238,31 @@ impl FigArg {
     }
 
     pub fn parse_from_spec(arg: &SpecArg) -> Self {
+        // A declared type is better evidence than the name, for the reason
+        // `update_from_complete` gives: `file_count` with `type="uint"` is a number, and
+        // offering paths for it because it contains "file" is a guess losing to nothing.
+        // Not a `complete` declaration, though, so one of those still replaces this.
+        let guess = matches!(arg.value_type, None | Some(Base::String | Base::Custom(_)));
+        let template = match &arg.value_type {
+            Some(Base::Path) => Some("filepaths".to_string()),
+            _ if guess => FigArg::get_template(&arg.name),
+            _ => None,
+        };
+        let generators = guess.then(|| FigArg::get_generator(&arg.name)).flatten();
+        let suggestions = match (&arg.choices, &arg.value_type) {
+            (Some(choices), _) => choices.choices.clone(),
+            (None, Some(Base::Bool)) => vec!["true".to_string(), "false".to_string()],
+            (None, _) => vec![],
+        };
         Self {
             name: FigArg::get_name(&arg.name),
             description: arg.help.clone(),
             is_variadic: arg.var,
             is_optional: !arg.required,
-            template: FigArg::get_template(&arg.name),
-            generators: FigArg::get_generator(&arg.name),
-            suggestions: arg.choices.clone().map(|c| c.choices).unwrap_or_default(),
-            debounce: FigArg::get_generator(&arg.name).map(|_| true),
+            template,
+            debounce: generators.as_ref().map(|_| true),
+            generators,
+            suggestions,
             declared: false,
         }
     }
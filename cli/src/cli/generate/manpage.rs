@@ -1,7 +1,8 @@
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::{parse_file_or_stdin, select_view, write_or_stdout};
-use usage::docs::manpage::ManpageRenderer;
+use usage::docs::manpage::{ManpageFormat, ManpageRenderer};
 use usage_rs::Args;
 
 /// Generate a manpage from a usage spec
@@ -16,6 +17,23 @@ pub struct Manpage {
     #[usage(long)]
     view: Option<String>,
 
+    /// Render a page per subcommand, git-style, with an index page for the whole tree
+    ///
+    /// `mise.1` lists every command, and `mise-install.1`, `mise-use.1` and so on each
+    /// document one, linking to their parent and children under SEE ALSO.
+    #[usage(short, long, conflicts = "--out-file")]
+    multi: bool,
+
+    /// Output pages to this directory (required when using --multi)
+    #[usage(
+        long,
+        value_hint = usage_rs::ValueHint::DirPath,
+        requires = "--multi",
+        required_if = "--multi",
+        effect = "write"
+    )]
+    out_dir: Option<PathBuf>,
+
     /// Output file path, or "-" for stdout (default)
     #[usage(
         short,
@@ -34,6 +52,17 @@ pub struct Manpage {
     /// - 8: System administration commands
     #[usage(short, long, default = "1")]
     section: u8,
+
+    /// Macro package to write: `man` for groff and man-db, `mdoc` for the BSDs and mandoc
+    #[usage(long, default = "man", choices("man", "mdoc"))]
+    format: String,
+
+    /// Date in the page header, e.g. "January 1, 2026"
+    ///
+    /// `mdoc` requires one, and defaults to the date in SOURCE_DATE_EPOCH, or else today.
+    /// `man` pages carry none unless it is given.
+    #[usage(long)]
+    date: Option<String>,
 }
 
 impl usage_rs::Run for Manpage {
@@ -41,11 +70,76 @@ impl usage_rs::Run for Manpage {
 
     fn run(self) -> Self::Output {
         let spec = select_view(parse_file_or_stdin(&self.file)?, self.view.as_deref())?;
-        let renderer = ManpageRenderer::new(spec).with_section(self.section);
-        let manpage = renderer.render()?;
+        let format = match self.format.as_str() {
+            "mdoc" => ManpageFormat::Mdoc,
+            _ => ManpageFormat::Man,
+        };
+        let mut renderer = ManpageRenderer::new(spec)
+            .with_section(self.section)
+            .with_format(format);
+        let date = match (&self.date, format) {
+            (Some(date), _) => Some(date.clone()),
+            (None, ManpageFormat::Mdoc) => Some(build_date()),
+            (None, ManpageFormat::Man) => None,
+        };
+        if let Some(date) = date {
+            renderer = renderer.with_date(date);
+        }
 
-        write_or_stdout(self.out_file.as_deref(), &manpage)?;
+        if self.multi {
+            let out_dir = self.out_dir.as_ref().unwrap();
+            for page in renderer.render_pages()? {
+                let path = out_dir.join(&page.path);
+                eprintln!("writing to {}", path.display());
+                super::write_file(&path, page.content)?;
+            }
+        } else {
+            let manpage = renderer.render()?;
+            write_or_stdout(self.out_file.as_deref(), &manpage)?;
+        }
 
         Ok(())
     }
 }
+
+/// Today, or the day SOURCE_DATE_EPOCH names so a packaged build reproduces, as mdoc writes a
+/// date: "January 1, 2026".
+fn build_date() -> String {
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |now| now.as_secs() as i64)
+        });
+    long_date(secs.div_euclid(86_400))
+}
+
+/// A day since 1970-01-01 as "Month D, YYYY", by Howard Hinnant's `civil_from_days`.
+fn long_date(days: i64) -> String {
+    const MONTHS: [&str; 12] = [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ];
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{} {day}, {year}", MONTHS[(month - 1) as usize])
+}
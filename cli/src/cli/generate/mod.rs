@@ -4,6 +4,7 @@ use usage::error::UsageErr;
 
 use usage::Spec;
 
+mod clap;
 mod completion;
 mod completion_init;
 mod fig;
@@ -12,7 +13,9 @@ mod json;
 mod json_schema;
 mod manpage;
 mod markdown;
+mod rust;
 mod sdk;
+mod shell_parser;
 
 /// Generate completions, documentation, and other artifacts from usage specs
 // Cannot run alone, and every child starts at `read`, so the parent is `read` too.
@@ -30,6 +33,7 @@ pub struct Generate {
 #[derive(usage_rs::Subcommands)]
 #[usage(run)]
 pub enum Command {
+    Clap(clap::Clap),
     Completion(completion::Completion),
     CompletionInit(completion_init::CompletionInit),
     Fig(fig::Fig),
@@ -38,7 +42,9 @@ pub enum Command {
     JsonSchema(json_schema::JsonSchema),
     Manpage(manpage::Manpage),
     Markdown(markdown::Markdown),
+    Rust(rust::Rust),
     Sdk(sdk::Sdk),
+    ShellParser(shell_parser::ShellParser),
 }
 
 pub fn file_or_spec(file: &Option<PathBuf>, spec: &Option<String>) -> Result<Spec, UsageErr> {
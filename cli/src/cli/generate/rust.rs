@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use usage::scaffold::{Framework, ScaffoldOptions};
+use usage::Spec;
+use usage_rs::Args;
+
+use crate::cli::generate;
+
+/// Scaffold a CLI's Rust source from a usage spec, for usage's derive
+///
+/// Writes one module per command into the output directory, the root's struct named
+/// `Cli`, for a crate that depends on usage-rs as `usage`. `Cli::to_kdl()` gives the spec
+/// back.
+///
+/// This runs once, to start from: the files are the author's to edit afterwards, and
+/// running it again overwrites them. Whatever in the spec a derive has no spelling for is
+/// listed on stderr, to be carried over by hand.
+#[derive(Args)]
+#[usage(effect = "write")]
+pub struct Rust {
+    /// A usage spec taken in as a file, use "-" to read from stdin
+    #[usage(short, long)]
+    file: Option<PathBuf>,
+
+    /// Directory the modules are written to, the root's as mod.rs
+    #[usage(short, long, value_hint = usage_rs::ValueHint::DirPath)]
+    out_dir: PathBuf,
+
+    /// Raw string spec input
+    #[usage(long, required_unless = "--file", overrides = "--file")]
+    spec: Option<String>,
+}
+
+impl usage_rs::Run for Rust {
+    type Output = miette::Result<()>;
+
+    fn run(self) -> Self::Output {
+        let spec = generate::file_or_spec(&self.file, &self.spec)?;
+        scaffold(&spec, self.file.as_deref(), &self.out_dir, Framework::Usage)
+    }
+}
+
+/// Shared with `usage g clap`, which differs only in the derive it writes for.
+pub fn scaffold(
+    spec: &Spec,
+    file: Option<&Path>,
+    out_dir: &Path,
+    framework: Framework,
+) -> miette::Result<()> {
+    let output = usage::scaffold::generate(
+        spec,
+        &ScaffoldOptions {
+            framework,
+            source_file: file
+                .filter(|f| f.as_os_str() != "-")
+                .map(|f| f.display().to_string()),
+        },
+    );
+    for file in &output.files {
+        let path = out_dir.join(&file.path);
+        eprintln!("writing to {}", path.display());
+        generate::write_file(&path, &file.content)?;
+    }
+    if !output.left_out.is_empty() {
+        eprintln!("left out, to carry over by hand:");
+        for line in &output.left_out {
+            eprintln!("  {line}");
+        }
+    }
+    Ok(())
+}
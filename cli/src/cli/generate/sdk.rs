@@ -18,7 +18,7 @@ pub struct Sdk {
     file: Option<PathBuf>,
 
     /// Target language for the SDK
-    #[usage(short, long, choices("typescript", "python"))]
+    #[usage(short, long, choices("typescript", "python", "go", "rust"))]
     language: String,
 
     /// Output directory for generated SDK files
@@ -43,6 +43,8 @@ impl usage_rs::Run for Sdk {
         let language = match self.language.as_str() {
             "typescript" => SdkLanguage::TypeScript,
             "python" => SdkLanguage::Python,
+            "go" => SdkLanguage::Go,
+            "rust" => SdkLanguage::Rust,
             other => {
                 return Err(miette::miette!("unsupported language: {other}"));
             }
@@ -56,7 +58,7 @@ impl usage_rs::Run for Sdk {
             source_file,
         };
 
-        let output = usage::sdk::generate(&spec, &opts);
+        let output = usage::sdk::generate(&spec, &opts)?;
 
         std::fs::create_dir_all(&self.output)
             .map_err(|e| miette::miette!("failed to create output directory: {e}"))?;
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use miette::Result;
+use usage::shell_parser::ShellParserOptions;
+use usage_rs::Args;
+
+use crate::cli::generate;
+
+/// Generate a shell prelude that parses a script's arguments without usage installed
+///
+/// The prelude reads the arguments of the script that sources it the way `usage exec`
+/// would, and exports the same usage_* variables. It is plain shell, so the script runs
+/// anywhere the shell does:
+///
+///   usage generate shell-parser --shell bash -f deploy.usage.kdl -o deploy.parse.bash
+///
+/// A spec declaring something the prelude cannot check, such as `conflicts` or a
+/// `default_subcommand`, is refused with the feature named.
+#[derive(Args)]
+#[usage(effect = "read")]
+pub struct ShellParser {
+    /// Shell to write the prelude in
+    #[usage(long, choices("bash", "fish", "posix", "zsh"))]
+    shell: String,
+
+    /// A usage spec taken in as a file, use "-" to read from stdin
+    #[usage(short, long)]
+    file: Option<PathBuf>,
+
+    /// File path where the prelude will be saved, or "-" for stdout
+    #[usage(
+        short,
+        long,
+        value_hint = usage_rs::ValueHint::FilePath,
+        effect = "write"
+    )]
+    out_file: Option<PathBuf>,
+
+    /// Raw string spec input
+    #[usage(long, required_unless = "--file", overrides = "--file")]
+    spec: Option<String>,
+}
+
+impl usage_rs::Run for ShellParser {
+    type Output = Result<()>;
+
+    fn run(self) -> Self::Output {
+        let spec = generate::file_or_spec(&self.file, &self.spec)?;
+        let out = usage::shell_parser::generate(
+            &spec,
+            &ShellParserOptions {
+                shell: self.shell.parse()?,
+                source_file: self.file.as_ref().map(|f| {
+                    if f.as_os_str() == "-" {
+                        "stdin".to_string()
+                    } else {
+                        f.to_string_lossy().to_string()
+                    }
+                }),
+            },
+        )?;
+        generate::write_or_stdout(self.out_file.as_deref(), &out)?;
+        Ok(())
+    }
+}
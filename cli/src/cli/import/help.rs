@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use miette::{bail, miette, Result};
+use usage::error::UsageErr;
+use usage::{Spec, SpecCommand, SpecCommandBuilder, SpecFlag};
+
+use super::scrape::{self, Listed, Page};
+use crate::cli::{bounded, generate};
+
+/// Write a usage spec for an existing CLI by reading its help
+///
+/// Runs the command with --help, then each subcommand that help lists, and reads
+/// what they print in any of the layouts clap, cobra, argparse, click and GNU
+/// tools write:
+///
+///   usage import help -o kv.usage.kdl -- kv
+///
+/// Help captured earlier can be read instead: --file for a single page, or --dir
+/// for a tree of them, with the program's page in DIR/help.txt and each
+/// subcommand's in DIR/SUBCOMMAND/help.txt.
+///
+/// Help does not say everything a parser knows, so the spec is a draft to review.
+/// Each page read is summarised on stderr, followed by every line of it that
+/// could not be placed.
+///
+/// A subcommand is only safe to run with --help if it honours --help. Give
+/// --depth 0 for a CLI you do not trust to. A program that has not finished
+/// printing its help after ten seconds is stopped.
+// No effect of its own: given a command, this runs it, and each subcommand it finds, so what
+// it does is whatever that program does with `--help`. Only the two ways of reading captured
+// help instead can say they only read.
+#[derive(usage_rs::Args)]
+#[usage(verbatim_doc_comment)]
+pub struct Help {
+    /// A captured help page, use "-" to read from stdin
+    #[usage(short, long, value_hint = usage_rs::ValueHint::FilePath, effect = "read")]
+    file: Option<PathBuf>,
+
+    /// A directory of captured help pages, a help.txt for each command
+    #[usage(
+        short,
+        long,
+        value_hint = usage_rs::ValueHint::DirPath,
+        conflicts = "--file",
+        effect = "read"
+    )]
+    dir: Option<PathBuf>,
+
+    /// The program's name, when it cannot be read from the help or the command
+    #[usage(long)]
+    bin: Option<String>,
+
+    /// How many levels of subcommands to follow
+    #[usage(long, default = "8")]
+    depth: usize,
+
+    /// File path where the spec will be saved, or "-" for stdout
+    #[usage(
+        short,
+        long,
+        value_hint = usage_rs::ValueHint::FilePath,
+        effect = "write"
+    )]
+    out_file: Option<PathBuf>,
+
+    /// The command whose help to read, with any arguments it needs before a subcommand
+    #[usage(double_dash = "automatic", value_hint = usage_rs::ValueHint::CommandWithArguments)]
+    command: Vec<String>,
+}
+
+impl usage_rs::Run for Help {
+    type Output = Result<()>;
+
+    fn run(self) -> Self::Output {
+        let source = match (self.file, self.dir, self.command.is_empty()) {
+            (Some(file), None, true) => Source::File(file),
+            (None, Some(dir), true) => Source::Dir(dir),
+            (None, None, false) => Source::Run(self.command),
+            (None, None, true) => bail!("give a command to run, or --file or --dir to read"),
+            _ => bail!("a command to run cannot be given with --file or --dir"),
+        };
+        let Some(text) = source.page(&[])? else {
+            bail!("{} printed no help", source.describe(&[]));
+        };
+
+        let mut importer = Importer {
+            source: &source,
+            depth: self.depth,
+            report: vec![],
+        };
+        let page = scrape::scrape(&text, 0);
+        let bin = self
+            .bin
+            .or_else(|| source.bin())
+            .or_else(|| page.program.clone())
+            .ok_or_else(|| miette!("the help does not name the program; give it with --bin"))?;
+        let about = page.about.clone();
+        let mut path = vec![bin.clone()];
+        let mut cmd = importer.command(page, &text, &mut path, None);
+        inherit(&mut cmd, &[]);
+
+        let mut spec = Spec::default();
+        spec.name = bin.clone();
+        spec.bin = bin;
+        spec.about = about;
+        spec.cmd = cmd;
+        let out = format!(
+            "// Imported by `usage import help` from {}.\n\
+             // Help does not say everything a parser knows: review this before relying on it.\n\
+             {spec}",
+            source.describe(&[]),
+        );
+        // Not expected to fail, but what is written should be a spec, not merely text that
+        // looks like one.
+        out.parse::<Spec>()
+            .map_err(|err| miette!("the imported spec does not read back: {err}"))?;
+
+        for line in &importer.report {
+            eprintln!("{line}");
+        }
+        generate::write_or_stdout(self.out_file.as_deref(), &out)?;
+        Ok(())
+    }
+}
+
+/// How long a program gets to print one help page.
+const PAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How much of one help page is read. Far more than any help a person would read.
+const PAGE_LIMIT: usize = 1024 * 1024;
+
+/// Where help pages come from.
+enum Source {
+    /// A program to run, and the words to put before each subcommand path.
+    Run(Vec<String>),
+    Dir(PathBuf),
+    File(PathBuf),
+}
+
+impl Source {
+    /// The help for the subcommand at `path`, or `None` when there is none to read.
+    fn page(&self, path: &[String]) -> Result<Option<String>> {
+        match self {
+            Self::Run(command) => {
+                let argv: Vec<String> = command
+                    .iter()
+                    .chain(path)
+                    .cloned()
+                    .chain(["--help".to_string()])
+                    .collect();
+                let ran = bounded::run(&argv, PAGE_TIMEOUT, PAGE_LIMIT)
+                    .map_err(|err| miette!("could not run {}: {err}", command[0]))?;
+                if ran.status.is_none() {
+                    bail!(
+                        "{} did not finish printing its help within {} seconds",
+                        self.describe(path),
+                        PAGE_TIMEOUT.as_secs()
+                    );
+                }
+                // Some programs print help on stderr; what matters is that they printed it.
+                let bytes = if ran.stdout.bytes.is_empty() {
+                    ran.stderr.bytes
+                } else {
+                    ran.stdout.bytes
+                };
+                let text = String::from_utf8_lossy(&bytes).into_owned();
+                Ok((!text.trim().is_empty()).then_some(text))
+            }
+            Self::Dir(dir) => {
+                let file = path
+                    .iter()
+                    .fold(dir.clone(), |d, p| d.join(p))
+                    .join("help.txt");
+                match std::fs::read_to_string(&file) {
+                    Ok(text) => Ok(Some(text)),
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(err) => Err(UsageErr::FileError(err, file).into()),
+                }
+            }
+            Self::File(_) if !path.is_empty() => Ok(None),
+            Self::File(file) if file.as_os_str() == "-" => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|err| miette!("could not read stdin: {err}"))?;
+                Ok(Some(text))
+            }
+            Self::File(file) => std::fs::read_to_string(file)
+                .map(Some)
+                .map_err(|err| UsageErr::FileError(err, file.clone()).into()),
+        }
+    }
+
+    /// Where the page for `path` was read from, as the report names it.
+    fn describe(&self, path: &[String]) -> String {
+        match self {
+            Self::Run(command) => {
+                let words: Vec<&str> = command
+                    .iter()
+                    .chain(path)
+                    .map(String::as_str)
+                    .chain(["--help"])
+                    .collect();
+                format!("`{}`", shell_words::join(words))
+            }
+            Self::Dir(dir) => {
+                let file = path
+                    .iter()
+                    .fold(dir.clone(), |d, p| d.join(p))
+                    .join("help.txt");
+                file.display().to_string()
+            }
+            Self::File(file) if file.as_os_str() == "-" => "stdin".to_string(),
+            Self::File(file) => file.display().to_string(),
+        }
+    }
+
+    /// The program's name, from the command that runs it.
+    fn bin(&self) -> Option<String> {
+        match self {
+            Self::Run(command) => Path::new(&command[0])
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Whether a page for each subcommand is expected. One captured page has none.
+    fn follows(&self) -> bool {
+        !matches!(self, Self::File(_))
+    }
+}
+
+struct Importer<'a> {
+    source: &'a Source,
+    depth: usize,
+    report: Vec<String>,
+}
+
+impl Importer<'_> {
+    /// The command a page describes, with each subcommand it lists read from its own page.
+    ///
+    /// `path` starts with the program's name, then each subcommand down to this one.
+    fn command(
+        &mut self,
+        page: Page,
+        text: &str,
+        path: &mut Vec<String>,
+        listed: Option<&Listed>,
+    ) -> SpecCommand {
+        self.summarise(&page, path);
+        let name = path.last().cloned().unwrap_or_default();
+        let mut aliases = listed.map(|l| l.aliases.clone()).unwrap_or_default();
+        for alias in page.aliases {
+            if !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        }
+        let mut cmd = SpecCommandBuilder::new()
+            .name(name)
+            .aliases(aliases)
+            .flags(page.flags)
+            .args(page.args);
+        // The parent's one-liner is the summary; the page's own description, when it says
+        // more, is the long help.
+        match (listed.and_then(|l| l.help.clone()), page.about) {
+            (Some(short), Some(about))
+                if short.trim_end_matches('.') != about.trim_end_matches('.') =>
+            {
+                cmd = cmd.help(short).help_long(about);
+            }
+            (Some(help), _) => cmd = cmd.help(help),
+            // The root's description is the spec's `about`, not the command's.
+            (None, Some(about)) if listed.is_some() => cmd = cmd.help(about),
+            (None, _) => {}
+        }
+
+        for sub in &page.commands {
+            path.push(sub.name.clone());
+            let subcommand = self.subcommand(sub, text, path);
+            cmd = cmd.subcommand(subcommand);
+            path.pop();
+        }
+        cmd.build()
+    }
+
+    fn subcommand(&mut self, listed: &Listed, parent: &str, path: &mut Vec<String>) -> SpecCommand {
+        let label = path.join(" ");
+        let only_listed = || {
+            let mut cmd = SpecCommandBuilder::new()
+                .name(&listed.name)
+                .aliases(listed.aliases.clone());
+            if let Some(help) = &listed.help {
+                cmd = cmd.help(help);
+            }
+            cmd.build()
+        };
+        if !self.source.follows() {
+            return only_listed();
+        }
+        if path.len() > self.depth + 1 {
+            self.report
+                .push(format!("{label}: deeper than --depth, kept as listed"));
+            return only_listed();
+        }
+        match self.source.page(&path[1..]) {
+            // A program that ignores the subcommand and prints its own help again would
+            // otherwise be followed until --depth ran out.
+            Ok(Some(text)) if text == parent => {
+                self.report.push(format!(
+                    "{label}: {} printed its parent's help, kept as listed",
+                    self.source.describe(&path[1..])
+                ));
+                only_listed()
+            }
+            Ok(Some(text)) => {
+                let page = scrape::scrape(&text, path.len() - 1);
+                self.command(page, &text, path, Some(listed))
+            }
+            Ok(None) => {
+                self.report.push(format!(
+                    "{label}: no help at {}, kept as listed",
+                    self.source.describe(&path[1..])
+                ));
+                only_listed()
+            }
+            Err(err) => {
+                self.report.push(format!("{label}: {err}, kept as listed"));
+                only_listed()
+            }
+        }
+    }
+
+    fn summarise(&mut self, page: &Page, path: &[String]) {
+        let count = |n: usize, what: &str| match n {
+            1 => format!("1 {what}"),
+            n => format!("{n} {what}s"),
+        };
+        self.report.push(format!(
+            "{}: {} help, {}, {}, {}",
+            path.join(" "),
+            page.style.as_str(),
+            count(page.flags.len(), "flag"),
+            count(page.args.len(), "arg"),
+            count(page.commands.len(), "subcommand"),
+        ));
+        for line in &page.unclassified {
+            self.report.push(format!(
+                "  line {}: not classified: {}",
+                line.line,
+                line.text.trim()
+            ));
+        }
+    }
+}
+
+/// Take flags a subcommand's page repeats from an ancestor off the subcommand, and make the
+/// ancestor's `global`: clap lists global flags on every page, and cobra's subcommands list
+/// their parents' persistent ones under "Global Flags:".
+///
+/// Returns the names of the `inherited` flags that were found repeated below.
+fn inherit(cmd: &mut SpecCommand, inherited: &[SpecFlag]) -> HashSet<String> {
+    let mut repeated = HashSet::new();
+    cmd.flags
+        .retain(|flag| match inherited.iter().find(|f| same_flag(f, flag)) {
+            Some(ancestor) => {
+                repeated.insert(ancestor.name.clone());
+                false
+            }
+            None => true,
+        });
+    let mut visible: Vec<SpecFlag> = inherited.to_vec();
+    visible.extend(cmd.flags.iter().cloned());
+    let mut below = HashSet::new();
+    for sub in cmd.subcommands.values_mut() {
+        below.extend(inherit(sub, &visible));
+    }
+    for flag in &mut cmd.flags {
+        if below.contains(&flag.name) {
+            flag.global = true;
+        }
+    }
+    repeated.extend(below);
+    repeated
+}
+
+fn same_flag(a: &SpecFlag, b: &SpecFlag) -> bool {
+    a.long == b.long && a.short == b.short
+}
@@ -0,0 +1,26 @@
+//! `usage import`: writing a spec for a CLI that was not built with one.
+//!
+//! Adopting usage for a tool somebody else maintains means transcribing its interface, and
+//! the one place that interface is already written down is its help. [`help`] reads it;
+//! [`scrape`] is the reading of a single page, kept apart from the command that runs the
+//! program and walks its subcommands.
+
+mod help;
+mod scrape;
+
+/// Write a usage spec from what an existing CLI says about itself
+// Cannot run alone, and its one child is `read`: it runs the program it is pointed at, but only
+// to ask for --help.
+#[derive(usage_rs::Args)]
+#[usage(effect = "read", run)]
+pub struct Import {
+    #[usage(subcommand)]
+    pub command: Command,
+}
+
+/// What a spec can be imported from.
+#[derive(usage_rs::Subcommands)]
+#[usage(run)]
+pub enum Command {
+    Help(help::Help),
+}
@@ -0,0 +1,1231 @@
+//! Reading one help page into the flags, arguments and subcommands it lists.
+//!
+//! Help output is written for people, so nothing here is a grammar. Each line is classified
+//! by where it sits — which heading it is under, how far it is indented — and by what it
+//! starts with: a dash is a flag, a bracket is an argument, a word under "Commands:" is a
+//! subcommand. A line indented past the entry above it continues that entry's help.
+//!
+//! The five layouts this knows differ less than they look. They all put a synopsis first or
+//! nearly first, they all indent entries under headings, and they all separate an entry from
+//! its help with a run of spaces. What they differ in is wording: where a default is written
+//! (`[default: 4]`, `(default 4)`, `(default: 4)`), what a value looks like (`<N>`, `N`,
+//! `int`, `TEXT`, `=SIZE`), and how choices are listed. Those are read from every page
+//! whatever its [`Style`]; the style only decides whether a value placeholder is a type name
+//! (cobra's `string`, click's `TEXT`) rather than a name for the value.
+//!
+//! A line that fits none of this is kept as [`Unclassified`], with its line number, so the
+//! importer can say what it left out rather than leaving it out silently.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use usage::{SpecArg, SpecArgBuilder, SpecFlag, SpecFlagBuilder};
+
+/// Which library wrote the page, as far as its wording gives it away.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Style {
+    Clap,
+    Cobra,
+    Argparse,
+    Click,
+    Gnu,
+    #[default]
+    Unknown,
+}
+
+impl Style {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Clap => "clap",
+            Self::Cobra => "cobra",
+            Self::Argparse => "argparse",
+            Self::Click => "click",
+            Self::Gnu => "GNU",
+            Self::Unknown => "unrecognised",
+        }
+    }
+
+    /// By the boilerplate each library writes for its own help flag, which a CLI rarely
+    /// rewords, and then by the headings only one of them uses.
+    fn detect(text: &str) -> Self {
+        static COBRA_HELP: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"--help\s+help for \S+").unwrap());
+        if text.contains("Available Commands:")
+            || text.contains("Global Flags:")
+            || COBRA_HELP.is_match(text)
+        {
+            Self::Cobra
+        } else if text.contains("Show this message and exit.") {
+            Self::Click
+        } else if text.contains("show this help message and exit")
+            || text.contains("positional arguments:")
+        {
+            Self::Argparse
+        } else if text.contains("Print help")
+            || text.contains("Print this message or the help of the given subcommand(s)")
+        {
+            Self::Clap
+        } else if text.contains("display this help and exit") || text.contains("[OPTION]...") {
+            Self::Gnu
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// What one help page declares.
+#[derive(Debug, Default)]
+pub(super) struct Page {
+    pub style: Style,
+    /// The program's name, as the synopsis spells it.
+    pub program: Option<String>,
+    /// The first paragraph of prose, which every layout uses to say what the command does.
+    pub about: Option<String>,
+    /// Other names for the command, when its own page lists them. Only cobra's does.
+    pub aliases: Vec<String>,
+    pub flags: Vec<SpecFlag>,
+    pub args: Vec<SpecArg>,
+    pub commands: Vec<Listed>,
+    pub unclassified: Vec<Unclassified>,
+}
+
+/// A subcommand as its parent's page lists it.
+#[derive(Debug)]
+pub(super) struct Listed {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub help: Option<String>,
+}
+
+/// A line the page had that none of the rules here could place.
+#[derive(Debug)]
+pub(super) struct Unclassified {
+    /// 1-based, in the page as it was captured.
+    pub line: usize,
+    pub text: String,
+}
+
+/// Read a help page. `depth` is how many subcommand names come after the program's in its
+/// synopsis, so they are not mistaken for arguments.
+pub(super) fn scrape(text: &str, depth: usize) -> Page {
+    let text = clean(text);
+    let mut scraper = Scraper {
+        style: Style::detect(&text),
+        ..Default::default()
+    };
+    for (number, line) in text.lines().enumerate() {
+        scraper.line(number + 1, line);
+    }
+    scraper.close();
+    scraper.finish(depth)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    /// Before any heading, and after a line of prose ends a section. GNU tools list their
+    /// flags here, with no heading above them.
+    #[default]
+    Prose,
+    Usage,
+    Flags,
+    Args,
+    Commands,
+    Aliases,
+    Examples,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Flag,
+    Arg,
+    Command,
+}
+
+/// One flag, argument or subcommand, with every line of help that belongs to it.
+#[derive(Debug)]
+struct Entry {
+    kind: Kind,
+    line: usize,
+    indent: usize,
+    head: String,
+    /// The help, one element per line, with `None` for a blank line between paragraphs.
+    help: Vec<Option<String>>,
+    /// The column the help starts in, once a line has shown it.
+    help_column: Option<usize>,
+}
+
+#[derive(Default)]
+struct Scraper {
+    style: Style,
+    section: Section,
+    /// The first synopsis, with the lines it wraps onto joined to it.
+    usage: String,
+    about: Vec<String>,
+    about_closed: bool,
+    entries: Vec<Entry>,
+    current: Option<Entry>,
+    /// The subcommand names argparse lists as `{a,b}` under "positional arguments:", with
+    /// the indent of that line.
+    braces: Option<(usize, Vec<String>)>,
+    aliases: Vec<String>,
+    unclassified: Vec<Unclassified>,
+}
+
+impl Scraper {
+    fn line(&mut self, number: usize, line: &str) {
+        let text = line.trim_start();
+        if text.is_empty() {
+            self.blank();
+            return;
+        }
+        let indent = line.len() - text.len();
+
+        if indent == 0 {
+            if let Some((section, rest)) = heading(text) {
+                self.close();
+                self.section = section;
+                if section == Section::Usage && !rest.is_empty() {
+                    self.usage = rest.to_string();
+                }
+                return;
+            }
+            if !flag_like(text) {
+                self.close();
+                self.section = Section::Prose;
+                self.prose(text);
+                return;
+            }
+        }
+
+        match self.section {
+            Section::Usage => {
+                // cobra puts the synopsis on the line under the heading. Anything after the
+                // first is either that synopsis wrapping, or another synopsis: GNU's
+                // `  or:  wc …`, clap's second usage line, cobra's `kv [command]`.
+                let first = self.usage.split_whitespace().next();
+                let word = text.split_whitespace().next();
+                if self.usage.is_empty() {
+                    self.usage = text.to_string();
+                } else if word != first && word != Some("or:") {
+                    self.usage.push(' ');
+                    self.usage.push_str(text);
+                }
+            }
+            Section::Flags | Section::Prose => {
+                if flag_like(text) && self.starts_entry(indent) {
+                    self.open(Kind::Flag, number, indent, text);
+                } else if self.continues(indent) {
+                    self.continue_entry(indent, text);
+                } else if self.section == Section::Flags {
+                    self.unclassified(number, line);
+                } else {
+                    self.prose(text);
+                }
+            }
+            Section::Args => {
+                let word = text.split_whitespace().next().unwrap_or_default();
+                let listed = self.braces.as_ref().is_some_and(|(braces, names)| {
+                    indent > *braces && names.iter().any(|n| n == word)
+                });
+                if listed {
+                    self.open(Kind::Command, number, indent, text);
+                } else if self.starts_entry(indent) {
+                    if word.starts_with('{') {
+                        let names = word.trim_matches(['{', '}']).split(',');
+                        self.braces = Some((indent, names.map(str::to_string).collect()));
+                    }
+                    self.open(Kind::Arg, number, indent, text);
+                } else if self.continues(indent) {
+                    self.continue_entry(indent, text);
+                } else {
+                    self.unclassified(number, line);
+                }
+            }
+            Section::Commands => {
+                if self.starts_entry(indent) {
+                    self.open(Kind::Command, number, indent, text);
+                } else if self.continues(indent) {
+                    self.continue_entry(indent, text);
+                } else {
+                    self.unclassified(number, line);
+                }
+            }
+            Section::Aliases => {
+                // cobra's `  get, g, read`: the command's own name, then its aliases.
+                let names = text.split(',').map(str::trim).skip(1);
+                self.aliases.extend(names.map(str::to_string));
+            }
+            Section::Examples => {}
+            Section::Unknown => {
+                // A heading we don't know, like clap's `next_help_heading`: keep what still
+                // reads as a flag or a command row, report the rest.
+                if flag_like(text) && self.starts_entry(indent) {
+                    self.open(Kind::Flag, number, indent, text);
+                } else if command_like(text) && self.starts_entry(indent) {
+                    self.open(Kind::Command, number, indent, text);
+                } else if self.continues(indent) {
+                    self.continue_entry(indent, text);
+                } else {
+                    self.unclassified(number, line);
+                }
+            }
+        }
+    }
+
+    fn blank(&mut self) {
+        if !self.about.is_empty() {
+            self.about_closed = true;
+        }
+        if self.section == Section::Usage && !self.usage.is_empty() {
+            self.section = Section::Prose;
+        }
+        if let Some(entry) = &mut self.current {
+            if entry.help.last().is_some_and(Option::is_some) {
+                entry.help.push(None);
+            }
+        }
+    }
+
+    /// A line of description. Only the first paragraph is kept, and only while no entry has
+    /// been seen: prose after the flags is about them, not about the command.
+    fn prose(&mut self, text: &str) {
+        if self.about_closed || !self.entries.is_empty() || self.current.is_some() {
+            return;
+        }
+        // cobra's closing hint, which is about `--help` rather than the command.
+        if text.starts_with("Use \"") && text.ends_with("for more information about a command.") {
+            return;
+        }
+        self.about.push(text.to_string());
+    }
+
+    /// Whether a line at `indent` is a new entry rather than more help for the current one:
+    /// it has to start left of where that entry's help does.
+    fn starts_entry(&self, indent: usize) -> bool {
+        match &self.current {
+            None => true,
+            Some(entry) => match entry.help_column {
+                Some(column) => indent < column,
+                None => indent <= entry.indent,
+            },
+        }
+    }
+
+    fn continues(&self, indent: usize) -> bool {
+        self.current.as_ref().is_some_and(|e| indent > e.indent)
+    }
+
+    fn open(&mut self, kind: Kind, line: usize, indent: usize, text: &str) {
+        self.close();
+        let (head, help) = match text.find("  ") {
+            Some(gap) => (&text[..gap], Some(text[gap..].trim_start())),
+            None => (text, None),
+        };
+        let help_column = help.map(|help| indent + text.len() - help.len());
+        self.current = Some(Entry {
+            kind,
+            line,
+            indent,
+            head: head.to_string(),
+            help: help.map(|h| Some(h.to_string())).into_iter().collect(),
+            help_column,
+        });
+    }
+
+    fn continue_entry(&mut self, indent: usize, text: &str) {
+        if let Some(entry) = &mut self.current {
+            entry.help_column.get_or_insert(indent);
+            entry.help.push(Some(text.to_string()));
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(entry) = self.current.take() {
+            self.entries.push(entry);
+        }
+    }
+
+    fn unclassified(&mut self, line: usize, text: &str) {
+        self.unclassified.push(Unclassified {
+            line,
+            text: text.to_string(),
+        });
+    }
+
+    fn finish(mut self, depth: usize) -> Page {
+        let synopsis = Synopsis::read(&self.usage, depth);
+        let mut page = Page {
+            style: self.style,
+            program: synopsis.program.clone(),
+            about: (!self.about.is_empty()).then(|| self.about.join(" ")),
+            aliases: std::mem::take(&mut self.aliases),
+            ..Default::default()
+        };
+        let braces = self.braces.as_ref().map(|(_, names)| names);
+        let mut listed_braces = false;
+        for entry in &self.entries {
+            let read = match entry.kind {
+                Kind::Flag => flag(entry, &synopsis, self.style).map(|flag| {
+                    // Every parser here answers -h and --help itself, and a flag listed twice —
+                    // cobra's root repeats its persistent flags — is one flag.
+                    let help = flag.long.iter().any(|l| l == "help") || flag.short == ['h'];
+                    if !help && !page.flags.iter().any(|f| f.name == flag.name) {
+                        page.flags.push(flag);
+                    }
+                }),
+                Kind::Arg if entry.head.starts_with('{') => Some(()),
+                Kind::Arg => arg(entry, &synopsis).map(|arg| page.args.push(arg)),
+                Kind::Command => listed(entry).map(|listed| {
+                    listed_braces |= braces.is_some_and(|b| b.contains(&listed.name));
+                    if listed.name != "help" {
+                        page.commands.push(listed);
+                    }
+                }),
+            };
+            if read.is_none() {
+                page.unclassified.push(Unclassified {
+                    line: entry.line,
+                    text: format!("{}{}", " ".repeat(entry.indent), entry.head),
+                });
+            }
+        }
+        // argparse lists subcommands only when they have help. Without it the braces are
+        // all there is, and they still name the subcommands.
+        if let (Some(names), false) = (braces, listed_braces) {
+            page.commands.extend(names.iter().map(|name| Listed {
+                name: name.clone(),
+                aliases: vec![],
+                help: None,
+            }));
+        }
+        if !self.entries.iter().any(|e| e.kind == Kind::Arg) {
+            page.args = synopsis.args.iter().map(Positional::to_arg).collect();
+        }
+        page.unclassified.append(&mut self.unclassified);
+        page.unclassified.sort_by_key(|u| u.line);
+        page
+    }
+}
+
+/// A heading, and whatever follows its colon on the same line. `Usage:` is the only one that
+/// carries anything there.
+fn heading(text: &str) -> Option<(Section, &str)> {
+    let (name, rest) = text.split_once(':')?;
+    let rest = rest.trim();
+    let lower = name.to_lowercase();
+    if lower == "usage" {
+        return Some((Section::Usage, rest));
+    }
+    if !rest.is_empty() || name.split_whitespace().count() > 4 {
+        return None;
+    }
+    let section = if lower.contains("command") {
+        Section::Commands
+    } else if lower.contains("positional") {
+        Section::Args
+    } else if lower.contains("option") || lower.contains("flag") {
+        Section::Flags
+    } else if lower.contains("argument") || lower == "args" {
+        Section::Args
+    } else if lower == "aliases" {
+        Section::Aliases
+    } else if lower.starts_with("example") {
+        Section::Examples
+    } else {
+        Section::Unknown
+    };
+    Some((section, rest))
+}
+
+/// `-v`, `--verbose`, `--[no-]color`: a dash followed by a name, not a dash used as a
+/// bullet.
+fn flag_like(text: &str) -> bool {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('-'), Some('-'), Some(c)) => c.is_ascii_alphanumeric() || c == '[',
+        (Some('-'), Some(c), _) => c.is_ascii_alphanumeric() || c == '?' || c == '@',
+        _ => false,
+    }
+}
+
+/// `deploy  Deploy a build`, `get, g  Read a key`: lowercase names, then help after a gap.
+fn command_like(text: &str) -> bool {
+    let Some((names, help)) = text.split_once("  ") else {
+        return false;
+    };
+    let name = |n: &str| {
+        let mut chars = n.chars();
+        chars.next().is_some_and(|c| c.is_ascii_lowercase())
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    };
+    !help.trim().is_empty() && names.split(", ").all(name)
+}
+
+/// The page without colour codes, and with tabs expanded so indents can be compared.
+fn clean(text: &str) -> String {
+    static ANSI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").unwrap());
+    let text = ANSI.replace_all(text, "");
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut column = 0;
+        for c in line.chars() {
+            if c == '\t' {
+                let to = (column / 8 + 1) * 8;
+                out.extend(std::iter::repeat_n(' ', to - column));
+                column = to;
+            } else {
+                out.push(c);
+                column += 1;
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// What the synopsis says that the entries below it may not: which flags are required, and
+/// which arguments exist at all on a page with no section for them.
+#[derive(Debug, Default)]
+struct Synopsis {
+    program: Option<String>,
+    required_flags: HashSet<String>,
+    args: Vec<Positional>,
+}
+
+#[derive(Debug)]
+struct Positional {
+    name: String,
+    required: bool,
+    var: bool,
+}
+
+impl Positional {
+    fn to_arg(&self) -> SpecArg {
+        SpecArgBuilder::new()
+            .name(&self.name)
+            .required(self.required)
+            .var(self.var)
+            .build()
+    }
+}
+
+impl Synopsis {
+    fn read(usage: &str, depth: usize) -> Self {
+        let mut synopsis = Self::default();
+        let items = items(usage);
+        // The program, then the subcommands leading to this page. A capitalised word is
+        // never one of them: it is click's TITLE, an argument.
+        let skip = items
+            .iter()
+            .take(depth + 1)
+            .take_while(|i| !i.starts_with(['[', '<', '{', '-']) && !is_upper(i))
+            .count();
+        synopsis.program = items[..skip].first().cloned();
+        let mut commands = false;
+        let mut flag_value = false;
+        for item in &items[skip..] {
+            let (item, dots) = match item.strip_suffix("...") {
+                Some(item) => (item, true),
+                None => (item.as_str(), false),
+            };
+            if item.is_empty() {
+                if let Some(last) = synopsis.args.last_mut() {
+                    last.var = true;
+                }
+                continue;
+            }
+            let (inner, optional) = match item.strip_prefix('[').and_then(|i| i.strip_suffix(']')) {
+                Some(inner) => (inner.trim(), true),
+                None => (item, false),
+            };
+            let inner = inner.strip_prefix("-- ").unwrap_or(inner);
+            if inner.starts_with('-') {
+                if !optional {
+                    let spelling = inner.split([' ', '=', '|']).next().unwrap_or(inner);
+                    synopsis.required_flags.insert(spelling.to_string());
+                    // `--env <ENV>` is one thing split in two.
+                    flag_value = !inner.contains([' ', '=']);
+                }
+                continue;
+            }
+            if std::mem::take(&mut flag_value) && !optional {
+                continue;
+            }
+            if inner.starts_with('{') || inner.contains(' ') {
+                continue;
+            }
+            let word = inner.trim_matches(['<', '>']).to_lowercase();
+            match word.as_str() {
+                "options" | "option" | "flags" | "global options" => continue,
+                "command" | "commands" | "subcommand" | "cmd" => {
+                    commands = true;
+                    continue;
+                }
+                "args" | "arguments" if commands => continue,
+                _ => {}
+            }
+            synopsis.args.push(Positional {
+                name: value_name(&word),
+                required: !optional,
+                var: dots,
+            });
+        }
+        synopsis
+    }
+}
+
+/// A synopsis split into words, keeping a bracketed group together.
+fn items(usage: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut item = String::new();
+    let mut depth = 0usize;
+    for c in usage.chars() {
+        match c {
+            '[' | '<' | '{' => depth += 1,
+            ']' | '>' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if c.is_whitespace() && depth == 0 {
+            if !item.is_empty() {
+                items.push(std::mem::take(&mut item));
+            }
+        } else {
+            item.push(c);
+        }
+    }
+    if !item.is_empty() {
+        items.push(item);
+    }
+    // argparse's `{clean,check} ...`: the dots go with the word before them.
+    let mut joined: Vec<String> = vec![];
+    for item in items {
+        match joined.last_mut() {
+            Some(last) if item == "..." => last.push_str("..."),
+            _ => joined.push(item),
+        }
+    }
+    joined
+}
+
+fn is_upper(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_alphabetic()) && !word.chars().any(|c| c.is_ascii_lowercase())
+}
+
+/// A placeholder as a name: `<FILE>` and `FILE` are both `file`.
+fn value_name(word: &str) -> String {
+    let name: String = word
+        .trim_matches(['<', '>', '[', ']'])
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name.trim_matches('_').to_string()
+}
+
+/// What a help text says in its brackets and parentheses, which is where every layout puts
+/// a default, an environment variable and the accepted values.
+#[derive(Debug, Default)]
+struct Notes {
+    default: Option<String>,
+    env: Option<String>,
+    choices: Vec<String>,
+    required: bool,
+    aliases: Vec<String>,
+}
+
+/// The help for an entry, with its notes taken out: the first paragraph, and the whole of
+/// it when there is more than one.
+fn help(entry: &Entry) -> (Option<String>, Option<String>, Notes) {
+    static BRACKETS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\[([^\[\]]*)\]").unwrap());
+    static DEFAULT: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\s*\(default:? ([^()]*)\)").unwrap());
+    let mut notes = Notes::default();
+    let mut paragraphs: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut possible_values = false;
+    for line in entry.help.iter().chain([&None]) {
+        let Some(line) = line else {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph.join(" "));
+                paragraph.clear();
+            }
+            possible_values = false;
+            continue;
+        };
+        // clap's long help lists choices a line each, under a line of its own.
+        if line == "Possible values:" {
+            possible_values = true;
+            continue;
+        }
+        if possible_values {
+            if let Some(value) = line.strip_prefix("- ") {
+                let value = value.split(':').next().unwrap_or(value).trim();
+                notes.choices.push(value.to_string());
+                continue;
+            }
+        }
+        paragraph.push(line);
+    }
+
+    let paragraphs: Vec<String> = paragraphs
+        .into_iter()
+        .map(|text| {
+            let text = BRACKETS.replace_all(&text, |caps: &regex::Captures| {
+                if notes.read(&caps[1]) {
+                    String::new()
+                } else {
+                    caps[0].to_string()
+                }
+            });
+            let text = DEFAULT.replace_all(&text, |caps: &regex::Captures| {
+                notes.default = Some(unquote(&caps[1]));
+                String::new()
+            });
+            text.trim().to_string()
+        })
+        .filter(|p| !p.is_empty())
+        .collect();
+    let first = paragraphs.first().cloned();
+    let long = (paragraphs.len() > 1).then(|| paragraphs.join("\n\n"));
+    (first, long, notes)
+}
+
+impl Notes {
+    /// Read one bracket's worth — clap's `default: 4`, click's `env var: X; required` — and
+    /// say whether all of it was understood. A bracket that was not is part of the help.
+    fn read(&mut self, inside: &str) -> bool {
+        let mut read = Notes::default();
+        for part in inside.split(';').map(str::trim) {
+            let (key, value) = part.split_once(':').unwrap_or((part, ""));
+            let value = value.trim();
+            match key.trim() {
+                "default" => read.default = Some(unquote(value)),
+                "env" | "env var" => {
+                    read.env = Some(value.split('=').next().unwrap_or(value).to_string())
+                }
+                "possible values" => {
+                    read.choices = value.split(',').map(|v| v.trim().to_string()).collect()
+                }
+                "aliases" => {
+                    read.aliases = value.split(',').map(|v| v.trim().to_string()).collect()
+                }
+                "short aliases" => {}
+                "required" if value.is_empty() => read.required = true,
+                _ => return false,
+            }
+        }
+        self.default = read.default.or(self.default.take());
+        self.env = read.env.or(self.env.take());
+        self.required |= read.required;
+        self.choices.extend(read.choices);
+        self.aliases.extend(read.aliases);
+        true
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn flag(entry: &Entry, synopsis: &Synopsis, style: Style) -> Option<SpecFlag> {
+    // click's `--color / --no-color`: the second half is what turns it off.
+    let (head, negated) = match entry.head.split_once(" / ") {
+        Some((head, negated)) => (head, Some(negated.trim())),
+        None => (entry.head.as_str(), None),
+    };
+    let mut shorts = vec![];
+    let mut longs: Vec<String> = vec![];
+    let mut negate = negated.map(str::to_string);
+    let mut placeholder: Option<&str> = None;
+    let mut dots = false;
+    for word in head.split_whitespace() {
+        let word = word.trim_end_matches(',');
+        if word.contains("...") {
+            dots = true;
+        }
+        if !word.starts_with('-') {
+            placeholder.get_or_insert(word);
+            continue;
+        }
+        // `--size=SIZE`, `--color[=WHEN]`, `--verbose...`
+        let end = word.find(['=', '[', '.']).unwrap_or(word.len());
+        let (spelling, attached) = word.split_at(end);
+        let attached = attached.trim_start_matches('=').trim_end_matches("...");
+        if !attached.is_empty() {
+            placeholder.get_or_insert(attached);
+        }
+        if let Some(long) = spelling.strip_prefix("--") {
+            let long = match long.strip_prefix("[no-]") {
+                Some(long) => {
+                    negate = Some(format!("--no-{long}"));
+                    long
+                }
+                None => long,
+            };
+            if long.is_empty()
+                || !long
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_".contains(c))
+            {
+                return None;
+            }
+            longs.push(long.to_string());
+        } else {
+            let mut chars = spelling[1..].chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => shorts.push(c),
+                // Single-dash long names, Go's `flag` package: usage has no way to say those.
+                _ => return None,
+            }
+        }
+    }
+    if longs.is_empty() && shorts.is_empty() {
+        return None;
+    }
+    shorts.dedup();
+    longs.dedup();
+
+    let (help, help_long, notes) = help(entry);
+    let name = longs
+        .first()
+        .cloned()
+        .unwrap_or_else(|| shorts[0].to_string());
+    let mut flag = SpecFlagBuilder::new()
+        .name(&name)
+        .shorts(shorts.clone())
+        .longs(longs.clone());
+    if let Some(help) = help {
+        flag = flag.help(help);
+    }
+    if let Some(help_long) = help_long {
+        flag = flag.help_long(help_long);
+    }
+    if let Some(negate) = negate {
+        flag = flag.negate(negate);
+    }
+    if let Some(env) = notes.env {
+        flag = flag.env(env);
+    }
+    let required = notes.required
+        || longs
+            .iter()
+            .map(|l| format!("--{l}"))
+            .chain(shorts.iter().map(|s| format!("-{s}")))
+            .any(|s| synopsis.required_flags.contains(&s));
+    flag = flag.required(required);
+
+    match placeholder {
+        Some(raw) => {
+            let value = Placeholder::read(raw);
+            let mut var = dots;
+            let mut value_name = value.name.as_deref().map(value_name);
+            if let Some(word) = &value.name {
+                if let Some(is_var) = type_word(style, word) {
+                    var |= is_var;
+                    value_name = None;
+                }
+            }
+            let mut choices = value.choices;
+            if choices.is_empty() {
+                choices = notes.choices;
+            }
+            let mut arg = SpecArgBuilder::new()
+                .name(value_name.unwrap_or_else(|| name.clone()))
+                .required(!value.optional);
+            if !choices.is_empty() {
+                arg = arg.choices(choices);
+            }
+            flag = flag.arg(arg.build()).var(var);
+            if value.optional {
+                flag = flag.value_optional(true);
+            }
+            if let Some(default) = notes.default.filter(|d| !d.is_empty() && d != "[]") {
+                flag = flag.default_value(default);
+            }
+        }
+        None => flag = flag.count(dots),
+    }
+    Some(flag.build())
+}
+
+/// A value as the entry spells it.
+#[derive(Debug, Default)]
+struct Placeholder {
+    name: Option<String>,
+    choices: Vec<String>,
+    optional: bool,
+}
+
+impl Placeholder {
+    fn read(raw: &str) -> Self {
+        let raw = raw.trim_end_matches("...").trim_end_matches(',');
+        if let Some(inner) = raw.strip_prefix('{').and_then(|r| r.strip_suffix('}')) {
+            return Self {
+                choices: inner.split(',').map(str::to_string).collect(),
+                ..Default::default()
+            };
+        }
+        for (open, close) in [('[', ']'), ('<', '>')] {
+            if let Some(inner) = raw.strip_prefix(open).and_then(|r| r.strip_suffix(close)) {
+                // click's `[plain|json]` is a choice, not an optional value.
+                if inner.contains('|') {
+                    return Self {
+                        choices: inner.split('|').map(str::to_string).collect(),
+                        ..Default::default()
+                    };
+                }
+                if open == '[' {
+                    return Self {
+                        optional: true,
+                        ..Self::read(inner)
+                    };
+                }
+                return Self {
+                    name: Some(inner.to_string()),
+                    ..Default::default()
+                };
+            }
+        }
+        Self {
+            name: Some(raw.trim_matches(['[', ']']).to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether a placeholder names the value's type rather than the value, and if so whether
+/// the type is a list. cobra writes `--tags strings`, click writes `--limit INTEGER`; a
+/// value named for its type says nothing, so the flag's own name is used instead.
+fn type_word(style: Style, word: &str) -> Option<bool> {
+    match style {
+        Style::Cobra => {
+            static COBRA_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+                Regex::new(r"^(string|int|uint|float|bool|duration|ip|ipNet|ipMask|bytes)\w*$")
+                    .unwrap()
+            });
+            COBRA_TYPE.is_match(word).then(|| {
+                word.ends_with("Slice")
+                    || word.ends_with("Array")
+                    || word == "stringToString"
+                    || ["strings", "ints", "uints", "bools", "durations"].contains(&word)
+            })
+        }
+        Style::Click => [
+            "TEXT",
+            "INTEGER",
+            "FLOAT",
+            "BOOLEAN",
+            "UUID",
+            "PATH",
+            "FILENAME",
+            "FILE",
+            "DIRECTORY",
+        ]
+        .contains(&word)
+        .then_some(false),
+        _ => None,
+    }
+}
+
+fn arg(entry: &Entry, synopsis: &Synopsis) -> Option<SpecArg> {
+    let head = entry.head.trim();
+    let (head, dots) = match head.strip_suffix("...") {
+        Some(head) => (head, true),
+        None => (head, false),
+    };
+    let (inner, bracket) = match head.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        Some(inner) => (inner, Some(false)),
+        None => match head.strip_prefix('<').and_then(|h| h.strip_suffix('>')) {
+            Some(inner) => (inner, Some(true)),
+            None => (head, None),
+        },
+    };
+    if inner.is_empty() || inner.contains(char::is_whitespace) {
+        return None;
+    }
+    let name = value_name(inner);
+    // argparse writes the bare name here; whether it is required is in the synopsis.
+    let from_synopsis = synopsis.args.iter().find(|p| p.name == name);
+    let required = bracket
+        .or(from_synopsis.map(|p| p.required))
+        .unwrap_or(true);
+    let var = dots || from_synopsis.is_some_and(|p| p.var);
+
+    let (help, help_long, notes) = help(entry);
+    let mut arg = SpecArgBuilder::new()
+        .name(name)
+        .required(required && notes.default.is_none())
+        .var(var);
+    if let Some(help) = help {
+        arg = arg.help(help);
+    }
+    if let Some(help_long) = help_long {
+        arg = arg.help_long(help_long);
+    }
+    if let Some(env) = notes.env {
+        arg = arg.env(env);
+    }
+    if let Some(default) = notes.default {
+        arg = arg.default_value(default);
+    }
+    if !notes.choices.is_empty() {
+        arg = arg.choices(notes.choices);
+    }
+    Some(arg.build())
+}
+
+fn listed(entry: &Entry) -> Option<Listed> {
+    static NAME: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^[A-Za-z0-9][A-Za-z0-9_.:-]*$").unwrap());
+    let mut names = entry
+        .head
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+    let name = names.next()?;
+    let mut aliases: Vec<String> = names.map(str::to_string).collect();
+    if !NAME.is_match(name) || !aliases.iter().all(|a| NAME.is_match(a)) {
+        return None;
+    }
+    let (help, _, notes) = help(entry);
+    aliases.extend(notes.aliases);
+    Some(Listed {
+        name: name.to_string(),
+        aliases,
+        help,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_clap_page_gives_its_flags_args_and_subcommands() {
+        let page = scrape(
+            "Ship builds
+
+Usage: ship [OPTIONS] --env <ENV> <TARGET> [FILES]... [COMMAND]
+
+Commands:
+  deploy  Deploy a build [aliases: d]
+  help    Print this message or the help of the given subcommand(s)
+
+Arguments:
+  <TARGET>    Where to ship to
+  [FILES]...  Files to include
+
+Options:
+  -v, --verbose...    Print more
+  -e, --env <ENV>     Environment
+  -j, --jobs <N>      How many at once [env: SHIP_JOBS=] [default: 4]
+      --color <WHEN>  When to use colour [possible values: auto, always]
+  -h, --help          Print help
+",
+            0,
+        );
+        assert_eq!(page.style, Style::Clap);
+        assert_eq!(page.about.as_deref(), Some("Ship builds"));
+        let usages: Vec<&str> = page.flags.iter().map(|f| f.usage.as_str()).collect();
+        assert_eq!(
+            usages,
+            [
+                "-v --verbose",
+                "-e --env <env>",
+                "-j --jobs <n>",
+                "--color <when>"
+            ]
+        );
+        assert!(page.flags[0].count);
+        assert!(
+            page.flags[1].required,
+            "--env is outside brackets in the synopsis"
+        );
+        assert_eq!(page.flags[2].env.as_deref(), Some("SHIP_JOBS"));
+        assert_eq!(page.flags[2].default, ["4"]);
+        assert_eq!(page.flags[2].help.as_deref(), Some("How many at once"));
+        let choices = page.flags[3]
+            .arg
+            .as_ref()
+            .unwrap()
+            .choices
+            .as_ref()
+            .unwrap();
+        assert_eq!(choices.choices, ["auto", "always"]);
+        let args: Vec<&str> = page.args.iter().map(|a| a.usage.as_str()).collect();
+        assert_eq!(args, ["<target>", "[files]…"]);
+        assert_eq!(page.commands.len(), 1, "clap's own `help` is not kept");
+        assert_eq!(page.commands[0].aliases, ["d"]);
+        assert!(page.unclassified.is_empty(), "{:?}", page.unclassified);
+    }
+
+    #[test]
+    fn flags_and_commands_under_a_heading_of_its_own_are_kept() {
+        let page = scrape(
+            "Usage: ship [OPTIONS] [COMMAND]
+
+Package Selection:
+  -p, --package <SPEC>  Only this package
+      --workspace       Every package
+                        in the workspace
+  see the manual for more
+
+Maintenance:
+  prune  Drop old builds
+",
+            0,
+        );
+        let usages: Vec<&str> = page.flags.iter().map(|f| f.usage.as_str()).collect();
+        assert_eq!(usages, ["-p --package <spec>", "--workspace"]);
+        assert_eq!(
+            page.flags[1].help.as_deref(),
+            Some("Every package in the workspace")
+        );
+        let commands: Vec<&str> = page.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(commands, ["prune"]);
+        let lines: Vec<usize> = page.unclassified.iter().map(|u| u.line).collect();
+        assert_eq!(lines, [7], "prose under the heading is still reported");
+    }
+
+    #[test]
+    fn a_gnu_page_has_flags_under_no_heading_and_help_that_wraps() {
+        let page = scrape(
+            "Usage: wc [OPTION]... [FILE]...
+  or:  wc [OPTION]... --files0-from=F
+Print newline, word, and byte counts for each FILE.
+
+  -c, --bytes            print the byte counts
+      --files0-from=F    read input from the files specified by
+                           NUL-terminated names in file F
+      --color[=WHEN]     colour the output
+      --help     display this help and exit
+
+Exit status:
+ 0  if OK
+",
+            0,
+        );
+        assert_eq!(page.style, Style::Gnu);
+        let usages: Vec<&str> = page.flags.iter().map(|f| f.usage.as_str()).collect();
+        assert_eq!(
+            usages,
+            ["-c --bytes", "--files0-from <f>", "--color [when]"]
+        );
+        assert_eq!(
+            page.flags[1].help.as_deref(),
+            Some("read input from the files specified by NUL-terminated names in file F")
+        );
+        let args: Vec<&str> = page.args.iter().map(|a| a.usage.as_str()).collect();
+        assert_eq!(
+            args,
+            ["[file]…"],
+            "from the synopsis, the alternative ignored"
+        );
+        let lines: Vec<usize> = page.unclassified.iter().map(|u| u.line).collect();
+        assert_eq!(
+            lines,
+            [12],
+            "the exit status section is reported, not guessed at"
+        );
+    }
+
+    #[test]
+    fn argparse_required_options_and_subcommands_come_from_the_synopsis_and_braces() {
+        let page = scrape(
+            "usage: tidy [-h] -c CONFIG [-x PATTERN [PATTERN ...]]
+            path {clean,check} ...
+
+positional arguments:
+  path                  the tree
+  {clean,check}
+    clean               remove generated files
+
+options:
+  -h, --help            show this help message and exit
+  -c CONFIG, --config CONFIG
+                        config file to read
+  -x PATTERN [PATTERN ...], --exclude PATTERN [PATTERN ...]
+                        paths to leave alone
+  --level {1,2,3}       how hard to try (default: 1)
+",
+            0,
+        );
+        assert_eq!(page.style, Style::Argparse);
+        let usages: Vec<&str> = page.flags.iter().map(|f| f.usage.as_str()).collect();
+        assert_eq!(
+            usages,
+            [
+                "-c --config <config>",
+                "-x --exclude… <pattern>",
+                "--level <level>"
+            ]
+        );
+        assert!(page.flags[0].required);
+        assert!(page.flags[1].var);
+        assert_eq!(page.flags[2].default, ["1"]);
+        assert_eq!(page.args.len(), 1);
+        assert!(page.args[0].required);
+        let names: Vec<&str> = page.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["clean"]);
+    }
+
+    #[test]
+    fn cobra_and_click_type_names_are_not_value_names() {
+        let cobra = scrape(
+            "Usage:
+  kv get <key> [flags]
+
+Aliases:
+  get, g
+
+Flags:
+  -h, --help            help for get
+  -o, --output string   output format (default \"text\")
+      --tags strings    only these tags
+",
+            1,
+        );
+        assert_eq!(cobra.style, Style::Cobra);
+        assert_eq!(cobra.aliases, ["g"]);
+        assert_eq!(cobra.flags[0].usage, "-o --output <output>");
+        assert_eq!(cobra.flags[0].default, ["text"]);
+        assert!(cobra.flags[1].var);
+        assert_eq!(cobra.args[0].name, "key");
+
+        let click = scrape(
+            "Usage: notes add [OPTIONS] TITLE [BODY]...
+
+  Add a note.
+
+Options:
+  -t, --tag TEXT         Tag the note  [required]
+  --color / --no-color   Colour it
+  --format [plain|json]  Format  [env var: NOTES_FORMAT; default: plain]
+  --help                 Show this message and exit.
+",
+            1,
+        );
+        assert_eq!(click.style, Style::Click);
+        assert_eq!(click.about.as_deref(), Some("Add a note."));
+        assert_eq!(click.flags[0].usage, "-t --tag <tag>");
+        assert!(click.flags[0].required);
+        assert_eq!(click.flags[1].negate.as_deref(), Some("--no-color"));
+        assert_eq!(click.flags[2].env.as_deref(), Some("NOTES_FORMAT"));
+        assert_eq!(click.flags[2].default, ["plain"]);
+        let args: Vec<&str> = click.args.iter().map(|a| a.usage.as_str()).collect();
+        assert_eq!(args, ["<title>", "[body]…"]);
+    }
+}
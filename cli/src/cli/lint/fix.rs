@@ -0,0 +1,800 @@
+//! `usage lint --fix`: the findings with one right answer, applied to the file as written.
+//!
+//! Every edit goes through kdl's document model rather than the spec's own `Display`, which
+//! would rewrite the whole file in its canonical layout and drop every comment in it. The
+//! document keeps each node's comments and whitespace with the node, so a fixed file differs
+//! from the original only on the lines a finding was about.
+//!
+//! A fix is only made where it cannot change what a command line means. Where that cannot be
+//! shown from the file alone — a name that something else in the file refers to, an optional
+//! argument with a default — the finding is left for a person, and reported as remaining.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use kdl::{KdlDocument, KdlNode};
+use usage::spec::SourceLocation;
+use usage::{Spec, SpecArg, SpecCommand, SpecFlag};
+
+use super::{short_cmp, LintIssue, Severity};
+
+/// The rules `--fix` knows how to fix.
+pub(super) const FIXABLE: &[&str] = &[
+    "deprecated-spelling",
+    "inconsistent-naming",
+    "required-after-optional",
+    "unsorted-flags",
+    "unsorted-subcommands",
+];
+
+/// A spec file as `--fix` reads it: the text, and where each of its lines starts.
+pub(crate) struct Source {
+    path: PathBuf,
+    text: String,
+    starts: Vec<usize>,
+}
+
+impl Source {
+    /// The file, when it is a spec that can be rewritten in place.
+    ///
+    /// Not stdin, which has nowhere to write back to, and not a script: its spec is cut out
+    /// of `#USAGE` comments, so a KDL document is not what the file holds.
+    pub(super) fn read(path: &Path) -> miette::Result<Option<Self>> {
+        if path.as_os_str() == "-" {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| miette::miette!("Failed to read {}: {err}", path.display()))?;
+        if text.starts_with("#!") {
+            return Ok(None);
+        }
+        Ok(Some(Self::new(path, text)))
+    }
+
+    pub(crate) fn new(path: &Path, text: String) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            path: path.to_path_buf(),
+            text,
+            starts,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(super) fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn document(&self) -> Option<KdlDocument> {
+        self.text.parse().ok()
+    }
+
+    /// The location of a byte offset, counted the way the spec parser counts it.
+    fn locate(&self, offset: usize) -> SourceLocation {
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.text[self.starts[line]..offset].chars().count();
+        SourceLocation::new(&self.path, line + 1, column + 1)
+    }
+
+    /// The line and column of a byte offset, as a spec's `source` locations give them.
+    pub(crate) fn key(&self, offset: usize) -> (usize, usize) {
+        let at = self.locate(offset);
+        (at.line, at.column)
+    }
+}
+
+/// Spellings a spec still reads but usage no longer writes, by where they may appear.
+///
+/// Only what usage itself never emits is listed. `help_long` on an `arg` is not here: that
+/// is still how usage writes an argument's long help, however odd it looks beside a flag's.
+const ROOT_SPELLINGS: &[(&str, &str)] = &[
+    ("about_long", "long_about"),
+    ("before_help_long", "before_long_help"),
+    ("after_help_long", "after_long_help"),
+];
+const CMD_PROP_SPELLINGS: &[(&str, &str)] = &[
+    ("help_long", "long_help"),
+    ("before_help_long", "before_long_help"),
+    ("after_help_long", "after_long_help"),
+];
+const FLAG_SPELLINGS: &[(&str, &str)] = &[("help_long", "long_help")];
+const CONFIG_PROP_SPELLINGS: &[(&str, &str)] = &[("data_type", "type")];
+
+/// One old spelling, at the byte offset of the name to rename.
+struct Misspelling {
+    offset: usize,
+    old: &'static str,
+    new: &'static str,
+}
+
+/// Every old spelling in a document.
+fn misspellings(doc: &KdlDocument) -> Vec<Misspelling> {
+    fn renamed(
+        table: &[(&'static str, &'static str)],
+        name: &str,
+    ) -> Option<(&'static str, &'static str)> {
+        table.iter().copied().find(|(old, _)| *old == name)
+    }
+    fn props(node: &KdlNode, table: &[(&'static str, &'static str)], found: &mut Vec<Misspelling>) {
+        for entry in node.entries() {
+            let Some(name) = entry.name() else { continue };
+            let Some((old, new)) = renamed(table, name.value()) else {
+                continue;
+            };
+            // Both spellings on one node: renaming would leave the key twice.
+            if node.entry(new).is_some() {
+                continue;
+            }
+            found.push(Misspelling {
+                offset: name.span().offset(),
+                old,
+                new,
+            });
+        }
+    }
+    fn flag(node: &KdlNode, found: &mut Vec<Misspelling>) {
+        props(node, FLAG_SPELLINGS, found);
+        for child in node.children().map(|c| c.nodes()).unwrap_or_default() {
+            if let Some((old, new)) = renamed(FLAG_SPELLINGS, child.name().value()) {
+                found.push(Misspelling {
+                    offset: child.name().span().offset(),
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    fn commands(doc: &KdlDocument, root: bool, found: &mut Vec<Misspelling>) {
+        for node in doc.nodes() {
+            match node.name().value() {
+                "flag" => flag(node, found),
+                "cmd" => {
+                    props(node, CMD_PROP_SPELLINGS, found);
+                    if let Some(children) = node.children() {
+                        commands(children, false, found);
+                    }
+                }
+                "config" if root => {
+                    for prop in node.children().map(|c| c.nodes()).unwrap_or_default() {
+                        if prop.name().value() == "prop" {
+                            props(prop, CONFIG_PROP_SPELLINGS, found);
+                        }
+                    }
+                }
+                name if root => {
+                    if let Some((old, new)) = renamed(ROOT_SPELLINGS, name) {
+                        found.push(Misspelling {
+                            offset: node.name().span().offset(),
+                            old,
+                            new,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut found = vec![];
+    commands(doc, true, &mut found);
+    found
+}
+
+/// The `deprecated-spelling` findings: names the file still uses that usage no longer writes.
+///
+/// Read from the text because the parsed spec cannot tell them apart: both spellings set the
+/// same field.
+pub(super) fn spellings(source: &Source, doc: &KdlDocument) -> Vec<LintIssue> {
+    misspellings(doc)
+        .into_iter()
+        .map(|m| LintIssue {
+            severity: Severity::Info,
+            code: "deprecated-spelling".to_string(),
+            message: format!("`{}` is an old spelling of `{}`", m.old, m.new),
+            location: None,
+            source: Some(source.locate(m.offset)),
+        })
+        .collect()
+}
+
+/// The file with every fixable finding fixed, and the findings that were.
+pub(super) fn fix(spec: &Spec, source: &Source, issues: &[LintIssue]) -> (String, Vec<LintIssue>) {
+    let Some(mut doc) = source.document() else {
+        return (source.text.clone(), vec![]);
+    };
+    let mut fixer = Fixer::new(spec, source, issues);
+    let renames: HashMap<usize, &str> = misspellings(&doc)
+        .into_iter()
+        .filter(|m| fixer.take("deprecated-spelling", m.offset))
+        .map(|m| (m.offset, m.new))
+        .collect();
+    rename(&mut doc, &renames);
+    fixer.block(&mut doc, false);
+    (doc.to_string(), fixer.fixed)
+}
+
+/// Renames every node and property whose name starts at one of `renames`' offsets.
+fn rename(doc: &mut KdlDocument, renames: &HashMap<usize, &str>) {
+    if renames.is_empty() {
+        return;
+    }
+    for node in doc.nodes_mut() {
+        if let Some(new) = renames.get(&node.name().span().offset()) {
+            node.set_name(*new);
+        }
+        for entry in node.entries_mut() {
+            let Some(name) = entry.name() else { continue };
+            if let Some(new) = renames.get(&name.span().offset()) {
+                entry.set_name(Some(*new));
+            }
+        }
+        if let Some(children) = node.children_mut() {
+            rename(children, renames);
+        }
+    }
+}
+
+struct Fixer<'a> {
+    source: &'a Source,
+    /// Fixable findings not yet fixed, by rule and by the line and column they point at.
+    pending: HashMap<(&'a str, (usize, usize)), Vec<&'a LintIssue>>,
+    fixed: Vec<LintIssue>,
+    /// The spec's flags and arguments declared in this file, with their commands, by where
+    /// each was declared.
+    flags: HashMap<(usize, usize), (&'a SpecCommand, &'a SpecFlag)>,
+    args: HashMap<(usize, usize), (&'a SpecCommand, &'a SpecArg)>,
+}
+
+impl<'a> Fixer<'a> {
+    fn new(spec: &'a Spec, source: &'a Source, issues: &'a [LintIssue]) -> Self {
+        let mut pending: HashMap<_, Vec<_>> = HashMap::new();
+        for issue in issues {
+            let Some(at) = &issue.source else { continue };
+            if at.file == source.path && FIXABLE.contains(&issue.code.as_str()) {
+                pending
+                    .entry((issue.code.as_str(), (at.line, at.column)))
+                    .or_default()
+                    .push(issue);
+            }
+        }
+        let mut fixer = Self {
+            source,
+            pending,
+            fixed: vec![],
+            flags: HashMap::new(),
+            args: HashMap::new(),
+        };
+        fixer.declarations(&spec.cmd);
+        fixer
+    }
+
+    fn declarations(&mut self, cmd: &'a SpecCommand) {
+        let here = |at: &Option<SourceLocation>| {
+            at.as_ref()
+                .filter(|at| at.file == self.source.path)
+                .map(|at| (at.line, at.column))
+        };
+        for flag in &cmd.flags {
+            if let Some(at) = here(&flag.source) {
+                self.flags.insert(at, (cmd, flag));
+            }
+        }
+        for arg in &cmd.args {
+            if let Some(at) = here(&arg.source) {
+                self.args.insert(at, (cmd, arg));
+            }
+        }
+        for sub in cmd.subcommands.values() {
+            self.declarations(sub);
+        }
+    }
+
+    /// Whether a finding of `code` points at `offset`, marking it fixed if so.
+    fn take(&mut self, code: &'a str, offset: usize) -> bool {
+        let at = self.source.key(offset);
+        match self.pending.remove(&(code, at)) {
+            Some(issues) => {
+                self.fixed.extend(issues.into_iter().cloned());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn has(&self, code: &str, offset: usize) -> bool {
+        self.pending.contains_key(&(code, self.source.key(offset)))
+    }
+
+    /// Fixes one block: the top level of the file, or a command's children.
+    fn block(&mut self, doc: &mut KdlDocument, in_braces: bool) {
+        for node in doc.nodes_mut() {
+            let offset = node.name().span().offset();
+            match node.name().value() {
+                "flag" if self.has("inconsistent-naming", offset) => self.rename_flag(node),
+                "arg" if self.has("inconsistent-naming", offset) => self.rename_arg(node),
+                "cmd" => {
+                    if let Some(children) = node.children_mut() {
+                        self.block(children, true);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.require(doc);
+        self.sort_flags(doc, in_braces);
+        self.sort_subcommands(doc, in_braces);
+    }
+
+    /// `--dry_run-mode` becomes `--dry-run-mode`, and the old spelling stays on as a hidden
+    /// alias: a script that types it keeps working, and help and completions stop offering it.
+    fn rename_flag(&mut self, node: &mut KdlNode) {
+        let offset = node.name().span().offset();
+        let Some((cmd, flag)) = self.flags.get(&self.source.key(offset)).copied() else {
+            return;
+        };
+        let mixed: Vec<&String> = flag
+            .long
+            .iter()
+            .filter(|long| long.contains('_') && long.contains('-'))
+            .filter(|long| !flag.hidden_aliases.contains(long))
+            .collect();
+        let mut renamed = vec![];
+        for long in mixed {
+            let new = long.replace('_', "-");
+            let taken = cmd.flags.iter().any(|f| f.long.contains(&new));
+            if taken || !self.only_here(long) {
+                return;
+            }
+            renamed.push((format!("--{long}"), format!("--{new}")));
+        }
+        for (old, new) in &renamed {
+            if !replace_in_first_arg(node, old, new) {
+                return;
+            }
+        }
+        for (old, _) in &renamed {
+            add_child(node, &format!("alias \"{old}\" hide=#true"));
+        }
+        self.take("inconsistent-naming", offset);
+    }
+
+    /// `<tool_name-or-path>` becomes `<tool-name-or-path>`. Nothing outside the spec sees the
+    /// difference: a script's variable is `usage_` and the name with every `-` an `_`.
+    fn rename_arg(&mut self, node: &mut KdlNode) {
+        let offset = node.name().span().offset();
+        let Some((cmd, arg)) = self.args.get(&self.source.key(offset)).copied() else {
+            return;
+        };
+        let new = arg.name.replace('_', "-");
+        if cmd.args.iter().any(|a| a.name == new) || !self.only_here(&arg.name) {
+            return;
+        }
+        if replace_in_first_arg(node, &arg.name, &new) {
+            self.take("inconsistent-naming", offset);
+        }
+    }
+
+    /// Whether a name appears once in the file: in its declaration, and nowhere that refers
+    /// to it — a group, a `requires`, an `overrides` — which renaming would leave dangling.
+    fn only_here(&self, name: &str) -> bool {
+        self.source.text.matches(name).count() == 1
+    }
+
+    /// An optional argument ahead of a required one is required already: the parser hands it
+    /// the first word, so leaving it out leaves the required one unfilled. Saying so in the
+    /// spec changes no command line that works today, where moving the required argument
+    /// forward would change what every one of them means.
+    ///
+    /// Not under `allow_missing_positional`, where a lone word goes to the required argument
+    /// and the optional one really is optional: `ex value` works there, and would stop working.
+    fn require(&mut self, doc: &mut KdlDocument) {
+        let mut optional: Vec<usize> = vec![];
+        let mut done: HashSet<usize> = HashSet::new();
+        for index in 0..doc.nodes().len() {
+            let node = &doc.nodes()[index];
+            if node.name().value() != "arg" {
+                continue;
+            }
+            let offset = node.name().span().offset();
+            let Some((cmd, arg)) = self.args.get(&self.source.key(offset)).copied() else {
+                continue;
+            };
+            if cmd.allow_missing_positional {
+                continue;
+            }
+            if !arg.required {
+                optional.push(index);
+                continue;
+            }
+            if !self.has("required-after-optional", offset) {
+                continue;
+            }
+            // A default says the author expected it to be left out, so which of the two they
+            // meant is a question for them.
+            let settled = optional.iter().all(|i| {
+                let at = self.source.key(doc.nodes()[*i].name().span().offset());
+                self.args
+                    .get(&at)
+                    .is_some_and(|(_, arg)| !arg.var && arg.default.is_empty())
+            });
+            if !settled {
+                continue;
+            }
+            for i in &optional {
+                if done.insert(*i) && !make_required(&mut doc.nodes_mut()[*i]) {
+                    return;
+                }
+            }
+            self.take("required-after-optional", offset);
+        }
+    }
+
+    /// Sorts a block's flags the way `unsorted-flags` asks: those with a short option by it,
+    /// then, separately, those with only a long one by that. Each group keeps the places its
+    /// members had, so nothing else in the block moves.
+    fn sort_flags(&mut self, doc: &mut KdlDocument, in_braces: bool) {
+        let mut short = vec![];
+        let mut long = vec![];
+        let mut flagged = vec![];
+        for (index, node) in doc.nodes().iter().enumerate() {
+            if node.name().value() != "flag" {
+                continue;
+            }
+            let offset = node.name().span().offset();
+            let Some((_, flag)) = self.flags.get(&self.source.key(offset)).copied() else {
+                continue;
+            };
+            if self.has("unsorted-flags", offset) {
+                flagged.push(offset);
+            }
+            if let Some(c) = flag.short.first() {
+                short.push((index, Key::Short(*c)));
+            } else if let Some(l) = flag.long.first() {
+                long.push((index, Key::Name(l.clone())));
+            }
+        }
+        if flagged.is_empty() {
+            return;
+        }
+        permute(doc, short, in_braces);
+        permute(doc, long, in_braces);
+        for offset in flagged {
+            self.take("unsorted-flags", offset);
+        }
+    }
+
+    fn sort_subcommands(&mut self, doc: &mut KdlDocument, in_braces: bool) {
+        let mut cmds = vec![];
+        let mut flagged = vec![];
+        for (index, node) in doc.nodes().iter().enumerate() {
+            if node.name().value() != "cmd" {
+                continue;
+            }
+            let offset = node.name().span().offset();
+            if self.has("unsorted-subcommands", offset) {
+                flagged.push(offset);
+            }
+            let Some(name) = node.entries().first().and_then(|e| e.value().as_string()) else {
+                return;
+            };
+            cmds.push((index, Key::Name(name.to_string())));
+        }
+        if flagged.is_empty() {
+            return;
+        }
+        permute(doc, cmds, in_braces);
+        for offset in flagged {
+            self.take("unsorted-subcommands", offset);
+        }
+    }
+}
+
+/// What a node sorts by.
+#[derive(PartialEq, Eq)]
+enum Key {
+    Short(char),
+    Name(String),
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Key::Short(a), Key::Short(b)) => short_cmp(*a, *b),
+            (Key::Name(a), Key::Name(b)) => a.cmp(b),
+            (Key::Short(_), Key::Name(_)) => std::cmp::Ordering::Less,
+            (Key::Name(_), Key::Short(_)) => std::cmp::Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Reorders the nodes at the given places by their keys, stably, leaving the places as
+/// they were.
+///
+/// A node's comments above it and at the end of its line go with it. What belongs to the
+/// place rather than the node stays: the blank lines above it, the rest of the line a
+/// block's `{` is on, which kdl files under the block's first node, and the separator after
+/// it — a newline, or the `;` of a block written on one line.
+fn permute(doc: &mut KdlDocument, mut keyed: Vec<(usize, Key)>, in_braces: bool) {
+    let slots: Vec<usize> = keyed.iter().map(|(index, _)| *index).collect();
+    keyed.sort_by(|a, b| a.1.cmp(&b.1));
+    if keyed
+        .iter()
+        .map(|(index, _)| *index)
+        .eq(slots.iter().copied())
+    {
+        return;
+    }
+    let original = doc.nodes().to_vec();
+    // How much of a node's leading text is its place's.
+    let place_len = |index: usize| -> usize {
+        let leading = original[index].format().map_or("", |f| f.leading.as_str());
+        let mut len = match (in_braces && index == 0, leading.find('\n')) {
+            (true, Some(newline)) => newline + 1,
+            _ => 0,
+        };
+        while let Some(newline) = leading[len..].find('\n') {
+            if !leading[len..len + newline].trim().is_empty() {
+                break;
+            }
+            len += newline + 1;
+        }
+        len
+    };
+    for (slot, (from, _)) in slots.iter().zip(&keyed) {
+        let mut node = original[*from].clone();
+        let Some(place) = original[*slot].format() else {
+            continue;
+        };
+        if let Some(format) = node.format_mut() {
+            let own = &format.leading[place_len(*from)..];
+            format.leading = format!("{}{own}", &place.leading[..place_len(*slot)]);
+            if is_separator(&format.terminator) {
+                // The place's separator, without the comment that was the other node's.
+                let (before, separator) = match is_separator(&place.terminator) {
+                    true => (place.before_terminator.clone(), place.terminator.clone()),
+                    false => (String::new(), "\n".to_string()),
+                };
+                format.before_terminator = before;
+                format.terminator = separator;
+            }
+        }
+        doc.nodes_mut()[*slot] = node;
+    }
+}
+
+/// Whether a node's terminator only ends it, rather than also carrying a comment.
+fn is_separator(terminator: &str) -> bool {
+    matches!(terminator.trim(), "" | ";")
+}
+
+/// Replaces `old` with `new` in a node's first argument, keeping how the argument was quoted.
+fn replace_in_first_arg(node: &mut KdlNode, old: &str, new: &str) -> bool {
+    let Some(entry) = node.entries_mut().iter_mut().find(|e| e.name().is_none()) else {
+        return false;
+    };
+    let Some(value) = entry.value().as_string().map(str::to_string) else {
+        return false;
+    };
+    if !value.contains(old) {
+        return false;
+    }
+    let repr = entry.format().map(|f| f.value_repr.clone());
+    entry.set_value(value.replacen(old, new, 1));
+    if let (Some(format), Some(repr)) = (entry.format_mut(), repr) {
+        if !repr.contains(old) {
+            return false;
+        }
+        format.value_repr = repr.replacen(old, new, 1);
+    }
+    true
+}
+
+/// `arg "[file]"` becomes `arg "<file>"`, and a `required=#false` goes.
+fn make_required(node: &mut KdlNode) -> bool {
+    node.entries_mut()
+        .retain(|e| e.name().map(|n| n.value()) != Some("required"));
+    let Some(value) = node
+        .entries()
+        .iter()
+        .find(|e| e.name().is_none())
+        .and_then(|e| e.value().as_string())
+        .map(str::to_string)
+    else {
+        return false;
+    };
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(name) => replace_in_first_arg(node, &value, &format!("<{name}>")),
+        None => true,
+    }
+}
+
+/// Appends a child to a node, laid out like the children it already has.
+fn add_child(node: &mut KdlNode, child: &str) {
+    let Some(children) = node.children_mut() else {
+        // A one-line block, as `flag "--x" { alias "--y" }` would be written by hand.
+        let template: KdlDocument = format!("x {{ {child} }}").parse().expect("valid KDL");
+        let template = &template.nodes()[0];
+        node.set_children(template.children().cloned().unwrap_or_default());
+        if let Some(format) = node.format_mut() {
+            format.before_children = " ".into();
+        }
+        return;
+    };
+    let parsed: KdlDocument = child.parse().expect("valid KDL");
+    let mut new = parsed.nodes()[0].clone();
+    if let Some(last) = children.nodes_mut().last_mut() {
+        if let (Some(last), Some(format)) = (last.format_mut(), new.format_mut()) {
+            let leading = &last.leading;
+            format.leading = leading[leading.rfind('\n').map_or(0, |i| i + 1)..].to_string();
+            if last.terminator.is_empty() {
+                // `{ help "x" }`: the space before the `}` moves to the new last child.
+                last.terminator = ";".into();
+                format.before_terminator = std::mem::take(&mut last.before_terminator);
+            } else {
+                format.terminator = "\n".into();
+            }
+        }
+    }
+    children.nodes_mut().push(new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::lint::{lint_spec, LintOptions};
+
+    /// The spec fixed, and the codes of what was fixed.
+    fn fixed(text: &str) -> (String, Vec<String>) {
+        let spec: Spec = text.parse().unwrap();
+        let source = Source::new(Path::new(""), text.to_string());
+        let found = spellings(&source, &source.document().unwrap());
+        let issues = lint_spec(&spec, LintOptions { sorted: true }, found);
+        let (text, fixed) = fix(&spec, &source, &issues);
+        (text, fixed.into_iter().map(|i| i.code).collect())
+    }
+
+    #[test]
+    fn sorting_moves_comments_with_their_nodes_and_leaves_the_layout() {
+        let (text, codes) = fixed(
+            r#"name "t"
+about "t"
+
+// verbose output
+flag "-v --verbose" help="v"
+flag "-a --all" help="a" // everything
+cmd "zip" help="zip" { // the archive
+    flag "--b" help="b"
+    flag "--a" help="a"
+}
+cmd "install" help="i" { flag "--y" help="y"; flag "--x" help="x" }
+"#,
+        );
+        assert_eq!(
+            text,
+            r#"name "t"
+about "t"
+
+flag "-a --all" help="a" // everything
+// verbose output
+flag "-v --verbose" help="v"
+cmd "install" help="i" { flag "--x" help="x"; flag "--y" help="y" }
+cmd "zip" help="zip" { // the archive
+    flag "--a" help="a"
+    flag "--b" help="b"
+}
+"#
+        );
+        assert_eq!(
+            codes,
+            [
+                "unsorted-flags",
+                "unsorted-flags",
+                "unsorted-flags",
+                "unsorted-subcommands"
+            ]
+        );
+    }
+
+    #[test]
+    fn a_renamed_flag_keeps_answering_to_its_old_spelling() {
+        let (text, codes) = fixed(
+            r#"name "t"
+about "t"
+flag "--dry_run-mode" help="d"
+arg "<tool_name-or-path>" help="t"
+"#,
+        );
+        assert_eq!(
+            text,
+            r#"name "t"
+about "t"
+flag "--dry-run-mode" help="d" { alias "--dry_run-mode" hide=#true }
+arg "<tool-name-or-path>" help="t"
+"#
+        );
+        assert_eq!(codes, ["inconsistent-naming", "inconsistent-naming"]);
+        // And the alias it keeps is not reported in turn.
+        let spec: Spec = text.parse().unwrap();
+        assert!(lint_spec(&spec, LintOptions::default(), vec![]).is_empty());
+    }
+
+    #[test]
+    fn a_name_something_else_refers_to_is_left_alone() {
+        let text = r#"name "t"
+about "t"
+flag "--dry_run-mode" help="d"
+flag "--force" help="f" requires="--dry_run-mode"
+"#;
+        assert_eq!(fixed(text), (text.to_string(), vec![]));
+    }
+
+    #[test]
+    fn an_optional_argument_ahead_of_a_required_one_becomes_required() {
+        let (text, codes) = fixed(
+            r#"name "t"
+about "t"
+arg "[from]" help="f"
+arg "<to>" help="t"
+"#,
+        );
+        assert!(text.contains("arg \"<from>\" help=\"f\"\n"), "{text}");
+        assert_eq!(codes, ["required-after-optional"]);
+
+        // With a default, the author expected it to be left out, so the fix is theirs.
+        let text = r#"name "t"
+about "t"
+arg "[from]" help="f" default="."
+arg "<to>" help="t"
+"#;
+        assert_eq!(fixed(text), (text.to_string(), vec![]));
+    }
+
+    #[test]
+    fn an_optional_argument_the_command_lets_go_missing_is_left_optional() {
+        // `t value` binds `<to>` today. Requiring `[from]` would make that line an error.
+        let text = r#"name "t"
+about "t"
+allow_missing_positional #true
+arg "[from]" help="f"
+arg "<to>" help="t"
+"#;
+        assert_eq!(fixed(text), (text.to_string(), vec![]));
+    }
+
+    #[test]
+    fn old_spellings_are_renamed_where_usage_reads_them() {
+        let (text, codes) = fixed(
+            r#"name "t"
+about "t"
+about_long "l"
+flag "--x" help="x" help_long="more"
+cmd "c" help="c" help_long="more"
+config {
+    prop "jobs" data_type="integer" help="j"
+}
+"#,
+        );
+        assert_eq!(
+            text,
+            r#"name "t"
+about "t"
+long_about "l"
+flag "--x" help="x" long_help="more"
+cmd "c" help="c" long_help="more"
+config {
+    prop "jobs" type="integer" help="j"
+}
+"#
+        );
+        assert_eq!(codes.len(), 4);
+    }
+}
@@ -1,23 +1,42 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use usage::error::UsageErr;
 use usage::spec::cmd::SpecExample;
+use usage::spec::config_type::Base;
+use usage::spec::lint::{SpecLint, SpecLintLevel};
+use usage::spec::SourceLocation;
 use usage::{Parser, Spec, SpecArg, SpecCommand, SpecFlag, SpecFlagAction};
 
 use crate::cli::generate::parse_file_or_stdin;
-use crate::cli::{empty_mount_answers, OutputFormat};
+use crate::cli::lint::fix::Source;
+use crate::cli::report::{self, Finding, Level};
+use crate::cli::{empty_mount_answers, ReportFormat};
+
+pub(crate) mod fix;
 
 /// Lint a usage spec file for common issues
+///
+/// A `lint` node in the spec sets the level each rule reports at, `off` to drop it:
+///
+///   lint { missing-flag-help "off"; unsorted-flags "error" }
+///
+/// and a `// usage-lint-disable missing-flag-help` comment above a node, or at the
+/// end of its first line, silences the rules it names for that node and everything
+/// inside it. With no names it silences every rule.
+///
+/// --fix rewrites the file for the rules with one right answer — the order of
+/// flags and subcommands, mixed `_`/`-` names, an optional argument ahead of a
+/// required one, and old spellings — keeping its comments and layout.
 #[derive(usage_rs::Args)]
-#[usage(effect = "read")]
+#[usage(effect = "read", verbatim_doc_comment)]
 pub struct Lint {
     /// A usage spec file to lint, use "-" to read from stdin
     file: PathBuf,
 
     /// Output format
     #[usage(long, short, default = "text", value_enum)]
-    format: OutputFormat,
+    format: ReportFormat,
 
     /// Treat warnings as errors
     #[usage(long, short = 'W')]
@@ -30,6 +49,14 @@ pub struct Lint {
     /// Pair it with --warnings-as-errors to hold the order in CI.
     #[usage(long)]
     sorted: bool,
+
+    /// A file holding a `lint` node, whose levels win over the spec's own
+    #[usage(long, value_hint = usage_rs::ValueHint::FilePath)]
+    config: Option<PathBuf>,
+
+    /// Fix what can be fixed in place, then report what remains
+    #[usage(long)]
+    fix: bool,
 }
 
 /// The rules that only run when asked for.
@@ -63,6 +90,16 @@ pub struct LintIssue {
     pub code: String,
     pub message: String,
     pub location: Option<String>,
+    /// Where in the spec file the finding is, when the spec was read from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLocation>,
+}
+
+/// What `--fix --format json` prints: what it fixed, and what it left.
+#[derive(serde::Serialize)]
+struct FixReport<'a> {
+    fixed: &'a [LintIssue],
+    remaining: &'a [LintIssue],
 }
 
 impl std::fmt::Display for LintIssue {
@@ -111,29 +148,87 @@ impl Lint {
         );
     }
 
-    fn print_json(&self, issues: &[LintIssue]) -> miette::Result<()> {
+    fn print_json(&self, issues: &impl serde::Serialize) -> miette::Result<()> {
         let json = serde_json::to_string_pretty(issues)
             .map_err(|e| miette::miette!("Failed to serialize issues: {}", e))?;
         println!("{}", json);
         Ok(())
     }
+
+    fn print_sarif(&self, issues: &[LintIssue]) -> miette::Result<()> {
+        let findings: Vec<Finding> = issues.iter().map(LintIssue::finding).collect();
+        let json = serde_json::to_string_pretty(&report::sarif("usage lint", &findings))
+            .map_err(|e| miette::miette!("Failed to serialize issues: {}", e))?;
+        println!("{}", json);
+        Ok(())
+    }
+
+    fn print_github(&self, issues: &[LintIssue]) {
+        let findings: Vec<Finding> = issues.iter().map(LintIssue::finding).collect();
+        print!("{}", report::github(&findings));
+    }
+}
+
+impl LintIssue {
+    fn finding(&self) -> Finding<'_> {
+        Finding {
+            level: match self.severity {
+                Severity::Error => Level::Error,
+                Severity::Warning => Level::Warning,
+                Severity::Info => Level::Note,
+            },
+            code: &self.code,
+            message: &self.message,
+            location: self.location.as_deref(),
+            source: self.source.as_ref(),
+        }
+    }
 }
 
 impl usage_rs::Run for Lint {
     type Output = miette::Result<()>;
 
     fn run(self) -> Self::Output {
-        let spec = parse_file_or_stdin(&self.file)?;
-        let issues = lint_spec(
-            &spec,
-            LintOptions {
-                sorted: self.sorted,
-            },
-        );
+        let spec = self.read()?;
+        let source = Source::read(&self.file)?;
+        let mut issues = self.lint(&spec, source.as_ref());
+        let mut fixed = vec![];
+        if self.fix {
+            let Some(source) = source else {
+                if self.file.as_os_str() == "-" {
+                    miette::bail!("--fix rewrites a spec file in place, and stdin is not one");
+                }
+                miette::bail!(
+                    "--fix rewrites a spec file in place, and {} keeps its spec in comments",
+                    self.file.display()
+                );
+            };
+            let (text, done) = fix::fix(&spec, &source, &issues);
+            if !done.is_empty() {
+                self.write(&source, &text)?;
+                let source = Source::new(&self.file, text);
+                issues = self.lint(&self.read()?, Some(&source));
+                fixed = done;
+            }
+        }
 
         match self.format {
-            OutputFormat::Text => self.print_text(&issues),
-            OutputFormat::Json => self.print_json(&issues)?,
+            ReportFormat::Text => {
+                for issue in &fixed {
+                    println!("fixed {}", issue);
+                }
+                if !fixed.is_empty() {
+                    println!();
+                }
+                self.print_text(&issues)
+            }
+            ReportFormat::Json if self.fix => self.print_json(&FixReport {
+                fixed: &fixed,
+                remaining: &issues,
+            })?,
+            ReportFormat::Json => self.print_json(&issues)?,
+            ReportFormat::Sarif => self.print_sarif(&issues)?,
+            ReportFormat::Github => self.print_github(&issues),
         }
 
         let has_errors = issues.iter().any(|i| i.severity == Severity::Error);
@@ -147,8 +242,109 @@ impl usage_rs::Run for Lint {
     }
 }
 
-pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
-    let mut issues = Vec::new();
+impl Lint {
+    fn read(&self) -> miette::Result<Spec> {
+        let mut spec = parse_file_or_stdin(&self.file)?;
+        if let Some(config) = &self.config {
+            spec.lint.merge(SpecLint::parse_file(config)?);
+        }
+        Ok(spec)
+    }
+
+    fn lint(&self, spec: &Spec, source: Option<&Source>) -> Vec<LintIssue> {
+        let spellings = source
+            .and_then(|source| Some(fix::spellings(source, &source.document()?)))
+            .unwrap_or_default();
+        lint_spec(
+            spec,
+            LintOptions {
+                sorted: self.sorted,
+            },
+            spellings,
+        )
+    }
+
+    /// Writes the fixed file, and puts the original back if the result no longer reads: a
+    /// bug in a fix should cost a finding, not the spec.
+    fn write(&self, source: &Source, text: &str) -> miette::Result<()> {
+        let write = |text: &str| {
+            std::fs::write(&self.file, text)
+                .map_err(|err| miette::miette!("Failed to write {}: {err}", self.file.display()))
+        };
+        write(text)?;
+        if let Err(err) = parse_file_or_stdin(&self.file) {
+            write(source.text())?;
+            return Err(miette::Report::new(err)
+                .wrap_err("--fix produced a spec that does not parse; left unchanged"));
+        }
+        Ok(())
+    }
+}
+
+/// Every rule [`lint_spec`] can report, for checking the names a `lint` node or a
+/// `usage-lint-disable` comment uses.
+pub(crate) const RULES: &[&str] = &[
+    "ambiguous-view-program",
+    "count-flag-with-arg",
+    "deprecated-flag",
+    "deprecated-spelling",
+    "duplicate-arg",
+    "duplicate-flag",
+    "duplicate-subcommand",
+    "duplicate-view-bin",
+    "example-does-not-parse",
+    "example-not-checked",
+    "flag-no-option",
+    "inconsistent-naming",
+    "invalid-default-subcommand",
+    "invalid-view",
+    "missing-arg-help",
+    "missing-cmd-help",
+    "missing-flag-help",
+    "multicall-no-subcommands",
+    "required-after-optional",
+    "subcommand-required-no-subcommands",
+    "unknown-lint-rule",
+    "unknown-type",
+    "unsorted-flags",
+    "unsorted-subcommands",
+    "variadic-arg-not-last",
+    "view-host-collision",
+];
+
+/// What `usage lint` reports for a spec parsed from `text`, which is what the file at `path`
+/// holds — or will, once an editor saves it.
+pub(crate) fn lint_text(spec: &Spec, path: &Path, text: &str) -> Vec<LintIssue> {
+    // A script keeps its spec in comments, so its text is not a KDL document to read old
+    // spellings from; `Source::read` turns it down for the same reason.
+    let found = if text.starts_with("#!") {
+        vec![]
+    } else {
+        let source = Source::new(path, text.to_string());
+        source
+            .document()
+            .map(|doc| fix::spellings(&source, &doc))
+            .unwrap_or_default()
+    };
+    lint_spec(spec, LintOptions::default(), found)
+}
+
+/// The rules that only run when asked for: by `--sorted`, or by giving them a level.
+const OPT_IN: &[&str] = &["unsorted-flags", "unsorted-subcommands"];
+
+/// Lints a spec, starting from `found`: what was found in the file's text, which the parsed
+/// spec no longer shows.
+pub fn lint_spec(spec: &Spec, opts: LintOptions, found: Vec<LintIssue>) -> Vec<LintIssue> {
+    let mut issues = found;
+    let requested = opts;
+    let opts = LintOptions {
+        sorted: opts.sorted
+            || OPT_IN.iter().any(|rule| {
+                spec.lint
+                    .level(rule)
+                    .is_some_and(|l| l != SpecLintLevel::Off)
+            }),
+    };
 
     // Check default_subcommand reference
     if let Some(default_subcmd) = &spec.default_subcommand {
@@ -171,6 +367,7 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
                     default_subcmd, valid_list
                 ),
                 location: None,
+                source: spec.cmd.source.clone(),
             });
         }
     }
@@ -182,6 +379,7 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
             code: "multicall-no-subcommands".to_string(),
             message: "Spec has multicall=#true but no subcommands to select".to_string(),
             location: None,
+            source: spec.cmd.source.clone(),
         });
     }
 
@@ -192,6 +390,7 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
                 code: "invalid-view".to_string(),
                 message: error.to_string(),
                 location: Some(format!("view {id}")),
+                source: view.source.clone(),
             });
         }
         let host_name = program_basename(&spec.name);
@@ -207,6 +406,7 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
                     "view `{id}` uses the host command's name or bin as an executable selector"
                 ),
                 location: Some(format!("view {id}")),
+                source: view.source.clone(),
             });
         }
         if let Some((other, declared)) =
@@ -231,6 +431,7 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
                     "views `{other}` and `{id}` collide after executable basename normalization in the identifier and executable namespaces"
                 ),
                 location: Some(format!("view {id}")),
+                source: view.source.clone(),
             });
         }
     }
@@ -249,7 +450,68 @@ pub fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
         &mut issues,
     );
 
+    configure(spec, requested, issues)
+}
+
+/// Applies the spec's `lint` node and `usage-lint-disable` comments to what the rules found.
+///
+/// After the rules rather than inside them, so a rule only says what it found and one place
+/// decides what is reported. A name that is not a rule is itself reported: a typo in a
+/// suppression would otherwise silence nothing, and nobody would find out.
+fn configure(spec: &Spec, requested: LintOptions, mut issues: Vec<LintIssue>) -> Vec<LintIssue> {
+    let lint = &spec.lint;
+    for (name, rule) in &lint.rules {
+        if !RULES.contains(&name.as_str()) {
+            issues.push(LintIssue {
+                severity: Severity::Warning,
+                code: "unknown-lint-rule".to_string(),
+                message: format!("`lint` sets a level for '{name}', which is not a rule"),
+                location: Some("lint".to_string()),
+                source: rule.source.clone(),
+            });
+        }
+    }
+    for suppression in &lint.suppressions {
+        for name in &suppression.rules {
+            if !RULES.contains(&name.as_str()) {
+                issues.push(LintIssue {
+                    severity: Severity::Warning,
+                    code: "unknown-lint-rule".to_string(),
+                    message: format!("`usage-lint-disable` names '{name}', which is not a rule"),
+                    location: None,
+                    source: Some(suppression.source.clone()),
+                });
+            }
+        }
+    }
     issues
+        .into_iter()
+        .filter_map(|mut issue| {
+            debug_assert!(
+                RULES.contains(&issue.code.as_str()),
+                "{} is missing from RULES",
+                issue.code
+            );
+            let level = lint.level(&issue.code);
+            // Run because the config named the other opt-in rule, not this one.
+            if OPT_IN.contains(&issue.code.as_str()) && !requested.sorted && level.is_none() {
+                return None;
+            }
+            if let Some(at) = &issue.source {
+                if lint.suppressed(&issue.code, at) {
+                    return None;
+                }
+            }
+            issue.severity = match level {
+                Some(SpecLintLevel::Off) => return None,
+                Some(SpecLintLevel::Info) => Severity::Info,
+                Some(SpecLintLevel::Warning) => Severity::Warning,
+                Some(SpecLintLevel::Error) => Severity::Error,
+                None => issue.severity,
+            };
+            Some(issue)
+        })
+        .collect()
 }
 
 fn program_basename(program: &str) -> &str {
@@ -283,6 +545,7 @@ fn lint_command(
             code: "missing-cmd-help".to_string(),
             message: "Command has no help text".to_string(),
             location: Some(format!("cmd {}", cmd_path)),
+            source: cmd.source.clone(),
         });
     }
 
@@ -293,6 +556,7 @@ fn lint_command(
             code: "subcommand-required-no-subcommands".to_string(),
             message: "Command has subcommand_required=true but no subcommands defined".to_string(),
             location: Some(format!("cmd {}", cmd_path)),
+            source: cmd.source.clone(),
         });
     }
 
@@ -319,6 +583,7 @@ fn lint_command(
                         existing, sub.name, word
                     ),
                     location: Some(format!("cmd {}", cmd_path)),
+                    source: sub.source.clone(),
                 }),
                 None => {
                     seen_subcommands.insert(word.as_str(), &sub.name);
@@ -342,6 +607,7 @@ fn lint_command(
                         key, existing.name
                     ),
                     location: Some(format!("cmd {}", cmd_path)),
+                    source: flag.source.clone(),
                 });
             } else {
                 seen_flags.insert(key, flag);
@@ -358,6 +624,7 @@ fn lint_command(
                         key, existing.name
                     ),
                     location: Some(format!("cmd {}", cmd_path)),
+                    source: flag.source.clone(),
                 });
             } else {
                 seen_flags.insert(key, flag);
@@ -379,6 +646,7 @@ fn lint_command(
                 code: "duplicate-arg".to_string(),
                 message: format!("Argument '{}' is defined multiple times", existing.name),
                 location: Some(format!("cmd {}", cmd_path)),
+                source: arg.source.clone(),
             });
         } else {
             seen_args.insert(&arg.name, arg);
@@ -390,12 +658,13 @@ fn lint_command(
         lint_arg(arg, &cmd_path, issues);
     }
 
-    // Check for optional args before required args
+    // Check for optional args before required args. Under `allow_missing_positional` that
+    // order is the point: a lone word goes to the required one.
     let mut found_optional = false;
     for arg in &cmd.args {
         if !arg.required {
             found_optional = true;
-        } else if found_optional && !arg.var {
+        } else if found_optional && !arg.var && !cmd.allow_missing_positional {
             issues.push(LintIssue {
                 severity: Severity::Warning,
                 code: "required-after-optional".to_string(),
@@ -404,6 +673,7 @@ fn lint_command(
                     arg.name
                 ),
                 location: Some(format!("cmd {}", cmd_path)),
+                source: arg.source.clone(),
             });
         }
     }
@@ -416,6 +686,7 @@ fn lint_command(
                 code: "variadic-arg-not-last".to_string(),
                 message: format!("Variadic argument '{}' is not the last argument", arg.name),
                 location: Some(format!("cmd {}", cmd_path)),
+                source: arg.source.clone(),
             });
         }
     }
@@ -453,21 +724,19 @@ fn lint_command(
 fn lint_sorted(cmd: &SpecCommand, cmd_path: &str, issues: &mut Vec<LintIssue>) {
     // Subcommands merged in by a `mount` describe another program's CLI, so their
     // order is not this spec's to keep.
-    let subcommands: Vec<&str> = cmd
-        .subcommands
-        .values()
-        .filter(|c| !c.mounted)
-        .map(|c| c.name.as_str())
-        .collect();
-    if let Some((out_of_place, predecessor)) = first_unsorted(&subcommands, |a, b| a.cmp(b)) {
+    let subcommands: Vec<&SpecCommand> = cmd.subcommands.values().filter(|c| !c.mounted).collect();
+    if let Some((out_of_place, predecessor)) =
+        first_unsorted(&subcommands, |a, b| a.name.cmp(&b.name))
+    {
         issues.push(LintIssue {
             severity: Severity::Warning,
             code: "unsorted-subcommands".to_string(),
             message: format!(
                 "Subcommand '{}' is declared after '{}'",
-                out_of_place, predecessor
+                out_of_place.name, predecessor.name
             ),
             location: Some(format!("cmd {}", cmd_path)),
+            source: out_of_place.source.clone(),
         });
     }
 
@@ -489,6 +758,7 @@ fn lint_sorted(cmd: &SpecCommand, cmd_path: &str, issues: &mut Vec<LintIssue>) {
                 out_of_place.short[0], predecessor.short[0]
             ),
             location: Some(format!("cmd {}", cmd_path)),
+            source: out_of_place.source.clone(),
         });
     }
 
@@ -510,6 +780,7 @@ fn lint_sorted(cmd: &SpecCommand, cmd_path: &str, issues: &mut Vec<LintIssue>) {
                 out_of_place.long[0], predecessor.long[0]
             ),
             location: Some(format!("cmd {}", cmd_path)),
+            source: out_of_place.source.clone(),
         });
     }
 }
@@ -545,6 +816,7 @@ fn lint_flag(flag: &SpecFlag, cmd_path: &str, issues: &mut Vec<LintIssue>) {
             code: "flag-no-option".to_string(),
             message: format!("Flag '{}' has no short or long option", flag.name),
             location: Some(format!("cmd {} flag {}", cmd_path, flag.name)),
+            source: flag.source.clone(),
         });
     }
 
@@ -555,6 +827,7 @@ fn lint_flag(flag: &SpecFlag, cmd_path: &str, issues: &mut Vec<LintIssue>) {
             code: "missing-flag-help".to_string(),
             message: format!("Flag '{}' has no help text", flag.name),
             location: Some(format!("cmd {} flag {}", cmd_path, flag.name)),
+            source: flag.source.clone(),
         });
     }
 
@@ -565,21 +838,38 @@ fn lint_flag(flag: &SpecFlag, cmd_path: &str, issues: &mut Vec<LintIssue>) {
             code: "deprecated-flag".to_string(),
             message: format!("Flag '{}' is deprecated: {}", flag.name, deprecated),
             location: Some(format!("cmd {} flag {}", cmd_path, flag.name)),
+            source: flag.source.clone(),
         });
     }
 
-    // Check for inconsistent naming (mixing snake_case and kebab-case)
-    for long in &flag.long {
+    // Check for inconsistent naming (mixing snake_case and kebab-case). A hidden alias is
+    // exempt: it is how a flag keeps answering to the spelling it had before it was fixed.
+    for long in flag
+        .long
+        .iter()
+        .filter(|long| !flag.hidden_aliases.contains(long))
+    {
         if long.contains('_') && long.contains('-') {
             issues.push(LintIssue {
                 severity: Severity::Warning,
                 code: "inconsistent-naming".to_string(),
                 message: format!("Flag '--{}' mixes underscores and hyphens", long),
                 location: Some(format!("cmd {} flag {}", cmd_path, flag.name)),
+                source: flag.source.clone(),
             });
         }
     }
 
+    if let Some(arg) = &flag.arg {
+        lint_type(
+            arg.value_type.as_ref(),
+            &format!("Flag '{}'", flag.name),
+            format!("cmd {} flag {}", cmd_path, flag.name),
+            &flag.source,
+            issues,
+        );
+    }
+
     // Check for count flag with arg (conflicting semantics)
     if flag.count && flag.arg.is_some() {
         issues.push(LintIssue {
@@ -590,6 +880,7 @@ fn lint_flag(flag: &SpecFlag, cmd_path: &str, issues: &mut Vec<LintIssue>) {
                 flag.name
             ),
             location: Some(format!("cmd {} flag {}", cmd_path, flag.name)),
+            source: flag.source.clone(),
         });
     }
 }
@@ -602,6 +893,7 @@ fn lint_arg(arg: &SpecArg, cmd_path: &str, issues: &mut Vec<LintIssue>) {
             code: "missing-arg-help".to_string(),
             message: format!("Argument '{}' has no help text", arg.name),
             location: Some(format!("cmd {} arg {}", cmd_path, arg.name)),
+            source: arg.source.clone(),
         });
     }
 
@@ -612,6 +904,38 @@ fn lint_arg(arg: &SpecArg, cmd_path: &str, issues: &mut Vec<LintIssue>) {
             code: "inconsistent-naming".to_string(),
             message: format!("Argument '{}' mixes underscores and hyphens", arg.name),
             location: Some(format!("cmd {} arg {}", cmd_path, arg.name)),
+            source: arg.source.clone(),
+        });
+    }
+
+    lint_type(
+        arg.value_type.as_ref(),
+        &format!("Argument '{}'", arg.name),
+        format!("cmd {} arg {}", cmd_path, arg.name),
+        &arg.source,
+        issues,
+    );
+}
+
+/// A `type=` this version does not know. It loads, so a spec written for a newer usage
+/// keeps working, and is read as a string — which for a misspelt `uint` means nothing is
+/// checked and nothing says so.
+fn lint_type(
+    ty: Option<&Base>,
+    subject: &str,
+    location: String,
+    source: &Option<SourceLocation>,
+    issues: &mut Vec<LintIssue>,
+) {
+    if let Some(Base::Custom(name)) = ty {
+        issues.push(LintIssue {
+            severity: Severity::Warning,
+            code: "unknown-type".to_string(),
+            message: format!(
+                "{subject} has type \"{name}\", which usage does not know, so its values are not checked"
+            ),
+            location: Some(location),
+            source: source.clone(),
         });
     }
 }
@@ -663,12 +987,14 @@ fn lint_examples(
                          which names its commands only when run"
                     ),
                     location: Some(format!("cmd {} example", cmd_path)),
+                    source: example.source.clone(),
                 }),
                 Err(Unparsed::Refused(err)) => issues.push(LintIssue {
                     severity: Severity::Warning,
                     code: "example-does-not-parse".to_string(),
                     message: format!("Example `{}` does not parse: {}", line, one_line(&err)),
                     location: Some(format!("cmd {} example", cmd_path)),
+                    source: example.source.clone(),
                 }),
             }
         }
@@ -932,6 +1258,10 @@ fn is_this_program(spec: &Spec, word: &str) -> bool {
 mod tests {
     use super::*;
 
+    fn lint_spec(spec: &Spec, opts: LintOptions) -> Vec<LintIssue> {
+        super::lint_spec(spec, opts, vec![])
+    }
+
     /// The CLI reads specs written by other people, so a `validate=` expression that does not
     /// compile has to be refused when the spec is read rather than when a value reaches it.
     ///
@@ -1514,6 +1844,17 @@ arg "<required>" help="required arg"
 
         let issues = lint_spec(&spec, LintOptions::default());
         assert!(issues.iter().any(|i| i.code == "required-after-optional"));
+
+        let spec: Spec = r#"
+name "test"
+allow_missing_positional #true
+arg "[optional]" help="optional arg"
+arg "<required>" help="required arg"
+        "#
+        .parse()
+        .unwrap();
+        let issues = lint_spec(&spec, LintOptions::default());
+        assert!(!issues.iter().any(|i| i.code == "required-after-optional"));
     }
 
     #[test]
@@ -1706,6 +2047,32 @@ flag "-v --verbose" count=#true {
         assert!(issues.iter().any(|i| i.code == "count-flag-with-arg"));
     }
 
+    #[test]
+    fn an_unknown_type_is_reported_where_it_is_declared() {
+        let spec: Spec = r#"
+name "test"
+arg "<n>" type="intger" help="How many"
+flag "--wait <d>" type="duraton" help="How long"
+flag "--port <p>" type="uint" help="Where"
+        "#
+        .parse()
+        .unwrap();
+
+        let issues = lint_spec(&spec, LintOptions::default());
+        let unknown: Vec<_> = issues
+            .iter()
+            .filter(|i| i.code == "unknown-type")
+            .map(|i| (i.severity, i.location.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            unknown,
+            [
+                (Severity::Warning, "cmd test flag wait"),
+                (Severity::Warning, "cmd test arg n"),
+            ]
+        );
+    }
+
     fn sorted_issues(spec: &str) -> Vec<LintIssue> {
         let spec: Spec = spec.parse().unwrap();
         lint_spec(&spec, LintOptions { sorted: true })
@@ -1905,4 +2272,134 @@ cmd "update" help="update"
         assert!(issue.message.contains("install"));
         assert!(issue.message.contains("update"));
     }
+
+    #[test]
+    fn test_lint_issues_point_at_the_declaration() {
+        let spec: Spec = r#"
+name "test"
+cmd "install" {
+    flag "--dry_run-mode" help="dry run"
+}
+        "#
+        .parse()
+        .unwrap();
+
+        let issues = lint_spec(&spec, LintOptions::default());
+        let at = |code: &str, location: &str| {
+            let source = issues
+                .iter()
+                .find(|i| i.code == code && i.location.as_deref() == Some(location))
+                .and_then(|i| i.source.as_ref())
+                .unwrap();
+            (source.line, source.column)
+        };
+        // The root has no node of its own, so it is found at the one that names it.
+        assert_eq!(at("missing-cmd-help", "cmd test"), (2, 1));
+        assert_eq!(at("missing-cmd-help", "cmd test install"), (3, 1));
+        assert_eq!(
+            at("inconsistent-naming", "cmd test install flag dry_run-mode"),
+            (4, 5)
+        );
+    }
+
+    fn codes(spec: &str, opts: LintOptions) -> Vec<(String, Severity)> {
+        let spec: Spec = spec.parse().unwrap();
+        lint_spec(&spec, opts)
+            .into_iter()
+            .map(|i| (i.code, i.severity))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_node_sets_the_level_a_rule_reports_at() {
+        let issues = codes(
+            r#"
+name "test"
+about "test"
+lint {
+    missing-flag-help "off"
+    inconsistent-naming "error"
+}
+flag "--dry_run-mode"
+"#,
+            LintOptions::default(),
+        );
+        assert_eq!(
+            issues,
+            vec![("inconsistent-naming".to_string(), Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn test_lint_disable_comment_covers_the_node_and_what_is_inside_it() {
+        let issues = codes(
+            r#"
+name "test"
+about "test"
+// usage-lint-disable missing-flag-help
+cmd "install" help="install" {
+    flag "--force"
+    arg "<tool>"
+}
+flag "--quiet" // usage-lint-disable
+flag "--verbose"
+"#,
+            LintOptions::default(),
+        );
+        assert_eq!(
+            issues,
+            vec![
+                ("missing-flag-help".to_string(), Severity::Info),
+                ("missing-arg-help".to_string(), Severity::Info),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_reports_a_name_that_is_not_a_rule() {
+        // A typo in either place would otherwise silence nothing, quietly.
+        let issues = codes(
+            r#"
+name "test"
+about "test"
+lint { missing-flag-hlep "off" }
+// usage-lint-disable missing-arg-hlep
+arg "<tool>"
+"#,
+            LintOptions::default(),
+        );
+        assert_eq!(
+            issues,
+            vec![
+                ("missing-arg-help".to_string(), Severity::Info),
+                ("unknown-lint-rule".to_string(), Severity::Warning),
+                ("unknown-lint-rule".to_string(), Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_node_turns_on_an_opt_in_rule_by_itself() {
+        let spec = r#"
+name "test"
+about "test"
+lint { unsorted-flags "error" }
+flag "--b" help="b"
+flag "--a" help="a"
+cmd "z" help="z"
+cmd "y" help="y"
+"#;
+        // Only the rule it named: `unsorted-subcommands` stays off without `--sorted`.
+        assert_eq!(
+            codes(spec, LintOptions::default()),
+            vec![("unsorted-flags".to_string(), Severity::Error)]
+        );
+        assert_eq!(
+            codes(spec, LintOptions { sorted: true }),
+            vec![
+                ("unsorted-subcommands".to_string(), Severity::Warning),
+                ("unsorted-flags".to_string(), Severity::Error),
+            ]
+        );
+    }
 }
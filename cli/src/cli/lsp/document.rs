@@ -0,0 +1,375 @@
+//! An open spec: the editor's text, where its lines are, and what checking it found.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position};
+use usage::error::UsageErr;
+use usage::spec::SourceLocation;
+use usage::Spec;
+
+use super::syntax::Tree;
+use crate::cli::lint::{self, LintIssue, Severity};
+
+pub struct Document {
+    /// `None` for a buffer that is not a file, which has no directory for an `include` to
+    /// be relative to.
+    pub path: Option<PathBuf>,
+    pub text: String,
+    /// Where each line of `text` starts.
+    starts: Vec<usize>,
+    /// For a script, where each line of the spec in its `#USAGE` comments was taken from.
+    script: Option<Vec<(usize, usize)>>,
+    pub tree: Tree,
+    /// The spec as of the last text that parsed, for what only a parse knows — which files
+    /// the `include`s brought in. An edit that breaks the file keeps the one before it.
+    pub spec: Option<Spec>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Document {
+    pub fn new(path: Option<PathBuf>, text: String) -> Self {
+        let mut document = Self {
+            path,
+            text: String::new(),
+            starts: vec![],
+            script: None,
+            tree: Tree::default(),
+            spec: None,
+            diagnostics: vec![],
+        };
+        document.update(text);
+        document
+    }
+
+    pub fn update(&mut self, text: String) {
+        self.starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        self.script = usage::spec::script_lines(&text);
+        self.tree = Tree::parse(&masked(&text, self.script.as_deref()));
+        self.text = text;
+        self.check();
+    }
+
+    /// Re-read, for when a file it includes has changed.
+    pub fn recheck(&mut self) {
+        self.check();
+    }
+
+    fn check(&mut self) {
+        let parsed = match &self.path {
+            Some(path) => Spec::parse_file_contents(path, &self.text),
+            None if self.script.is_some() => Spec::parse_script_str(&self.text),
+            None => self.text.parse::<Spec>(),
+        };
+        match parsed {
+            Ok(spec) => {
+                let path = self.path.clone().unwrap_or_default();
+                self.diagnostics = lint::lint_text(&spec, &path, &self.text)
+                    .iter()
+                    .map(|issue| self.lint_diagnostic(issue))
+                    .collect();
+                self.spec = Some(spec);
+            }
+            Err(err) => self.diagnostics = self.error_diagnostics(&err),
+        }
+    }
+
+    fn lint_diagnostic(&self, issue: &LintIssue) -> Diagnostic {
+        let (range, message) = match &issue.source {
+            Some(at) if self.is_this(&at.file) => {
+                let offset = self.offset_of(at.line - 1, at.column - 1);
+                (self.word_range(offset), issue.message.clone())
+            }
+            Some(at) => (
+                self.include_range(&at.file),
+                format!("{}: {}", describe(at), issue.message),
+            ),
+            None => (0..0, issue.message.clone()),
+        };
+        Diagnostic {
+            range: self.range(range),
+            severity: Some(match issue.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(issue.code.clone())),
+            source: Some("usage lint".to_string()),
+            message: message.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Where a parse failed. One that failed in an included file is reported on the
+    /// `include` that brought it in, since that file is not the one being edited.
+    fn error_diagnostics(&self, err: &UsageErr) -> Vec<Diagnostic> {
+        let error = |range: Range<usize>, message: String| Diagnostic {
+            range: self.range(range),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("usage".to_string()),
+            message,
+            ..Default::default()
+        };
+        match err {
+            UsageErr::InvalidInput(message, span, source) => {
+                let file = Path::new(source.name());
+                let spec = source.inner();
+                if self.is_this(file) {
+                    let start = self.spec_offset(spec, span.offset());
+                    let end = self.spec_offset(spec, span.offset() + span.len());
+                    vec![error(start..end.max(start), message.clone())]
+                } else {
+                    let (line, column) = line_and_column(spec, span.offset());
+                    let at = SourceLocation::new(file, line + 1, column + 1);
+                    vec![error(
+                        self.include_range(file),
+                        format!("{}: {message}", describe(&at)),
+                    )]
+                }
+            }
+            UsageErr::KdlError(err) => {
+                // The whole document is read before any `include` is, so a syntax error is
+                // this file's unless an included file is the text that failed.
+                let included = self
+                    .includes()
+                    .into_iter()
+                    .map(|(_, path)| path)
+                    .find(|path| {
+                        std::fs::read_to_string(path).is_ok_and(|text| text == *err.input)
+                    });
+                err.diagnostics
+                    .iter()
+                    .map(|diagnostic| {
+                        let message = diagnostic.to_string();
+                        let message = match &diagnostic.help {
+                            Some(help) => format!("{message}\n{help}"),
+                            None => message,
+                        };
+                        let offset = diagnostic.span.offset();
+                        match &included {
+                            Some(path) => {
+                                let (line, column) = line_and_column(&err.input, offset);
+                                let at = SourceLocation::new(path, line + 1, column + 1);
+                                error(
+                                    self.include_range(path),
+                                    format!("{}: {message}", describe(&at)),
+                                )
+                            }
+                            None => {
+                                let start = self.spec_offset(&err.input, offset);
+                                let end =
+                                    self.spec_offset(&err.input, offset + diagnostic.span.len());
+                                error(start..end.max(start), message)
+                            }
+                        }
+                    })
+                    .collect()
+            }
+            UsageErr::FileError(err, path) => {
+                vec![error(
+                    self.include_range(path),
+                    format!("{}: {err}", path.display()),
+                )]
+            }
+            err => vec![error(0..0, err.to_string())],
+        }
+    }
+
+    fn is_this(&self, file: &Path) -> bool {
+        match &self.path {
+            Some(path) => path == file,
+            None => file.as_os_str().is_empty(),
+        }
+    }
+
+    /// The top-level `include`s, and the files they name.
+    pub fn includes(&self) -> Vec<(usize, PathBuf)> {
+        self.tree
+            .roots
+            .iter()
+            .filter_map(|id| Some((*id, self.include_target(*id)?)))
+            .collect()
+    }
+
+    /// The file an `include` node names, resolved the way the spec parser resolves it.
+    pub fn include_target(&self, id: usize) -> Option<PathBuf> {
+        let node = self.tree.node(id);
+        if node.name.text != "include" || node.parent.is_some() {
+            return None;
+        }
+        let file = Path::new(&node.prop("file")?.text);
+        if file.is_absolute() {
+            return Some(file.to_path_buf());
+        }
+        Some(self.path.as_ref()?.parent()?.join(file))
+    }
+
+    /// The `file=` of the include that names `file`, or the start of the document when none
+    /// does directly.
+    fn include_range(&self, file: &Path) -> Range<usize> {
+        self.includes()
+            .into_iter()
+            .find(|(_, path)| path == file)
+            .and_then(|(id, _)| self.tree.node(id).prop("file").map(|t| t.span.clone()))
+            .unwrap_or(0..0)
+    }
+
+    /// The offset in this file of an offset in the spec text it was parsed as: for a script,
+    /// the text cut out of its comments.
+    fn spec_offset(&self, spec: &str, offset: usize) -> usize {
+        let (line, column) = line_and_column(spec, offset);
+        match self.script.as_ref().and_then(|lines| lines.get(line)) {
+            Some((at, indent)) => self.offset_of(at - 1, indent + column),
+            None => self.offset_of(line, column),
+        }
+    }
+
+    /// The offset of a character column on a line, both counted from 0.
+    fn offset_of(&self, line: usize, column: usize) -> usize {
+        let Some(start) = self.starts.get(line) else {
+            return self.text.len();
+        };
+        let rest = self.line_text(line);
+        start
+            + rest
+                .char_indices()
+                .nth(column)
+                .map_or(rest.len(), |(i, _)| i)
+    }
+
+    fn line_text(&self, line: usize) -> &str {
+        let start = self.starts[line];
+        let end = self
+            .starts
+            .get(line + 1)
+            .map_or(self.text.len(), |next| next - 1);
+        &self.text[start..end]
+    }
+
+    /// The word a finding points at the start of, so that it underlines something.
+    fn word_range(&self, offset: usize) -> Range<usize> {
+        let rest = &self.text[offset..];
+        let end = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '{' | '}' | ';' | '='))
+            .unwrap_or(rest.len());
+        offset..offset + end
+    }
+
+    /// The byte offset of an LSP position, which counts UTF-16 units.
+    pub fn offset(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.starts.len() {
+            return self.text.len();
+        }
+        let mut units = 0;
+        for (i, c) in self.line_text(line).char_indices() {
+            if units >= position.character as usize {
+                return self.starts[line] + i;
+            }
+            units += c.len_utf16();
+        }
+        self.starts[line] + self.line_text(line).len()
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let character = self.text[self.starts[line]..offset].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    pub fn range(&self, range: Range<usize>) -> lsp_types::Range {
+        lsp_types::Range::new(self.position(range.start), self.position(range.end))
+    }
+}
+
+/// `path:line:column`, for a finding in another file.
+fn describe(at: &SourceLocation) -> String {
+    format!("{}:{}:{}", at.file.display(), at.line, at.column)
+}
+
+/// The line and character column of a byte offset, both from 0.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = text.get(..offset).unwrap_or(text);
+    let line = before.matches('\n').count();
+    let column = before[before.rfind('\n').map_or(0, |n| n + 1)..]
+        .chars()
+        .count();
+    (line, column)
+}
+
+/// A script with everything that is not its spec blanked out: the lines that are not
+/// `#USAGE` comments, and the markers on the ones that are. What the syntax tree is read
+/// from, so that an offset in it is an offset in the file.
+fn masked(text: &str, script: Option<&[(usize, usize)]>) -> String {
+    let Some(lines) = script else {
+        return text.to_string();
+    };
+    let mut out = String::with_capacity(text.len());
+    for (number, line) in text.split_inclusive('\n').enumerate() {
+        let keep = lines
+            .iter()
+            .find(|(at, _)| *at == number + 1)
+            .map(|(_, indent)| *indent);
+        for (column, c) in line.chars().enumerate() {
+            if c == '\n' || keep.is_some_and(|indent| column >= indent) {
+                out.push(c);
+            } else {
+                out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_count_utf16_units() {
+        let doc = Document::new(None, "about \"é𝄞\"\nbin \"x\"\n".to_string());
+        let offset = "about \"é𝄞".len();
+        assert_eq!(doc.position(offset), Position::new(0, 10));
+        assert_eq!(doc.offset(Position::new(0, 10)), offset);
+        assert_eq!(doc.offset(Position::new(1, 4)), "about \"é𝄞\"\nbin ".len());
+    }
+
+    #[test]
+    fn a_script_is_read_where_its_spec_is() {
+        let text =
+            "#!/usr/bin/env bash\n#USAGE flag \"--force\"\n#USAGE arg \"<file>\" help=#true\n";
+        let doc = Document::new(None, text.to_string());
+        let flag = doc.tree.node(doc.tree.roots[0]);
+        assert_eq!(&text[flag.name.span.clone()], "flag");
+        let [error] = &doc.diagnostics[..] else {
+            panic!("{:?}", doc.diagnostics);
+        };
+        assert_eq!(error.range.start.line, 2);
+        assert_eq!(
+            &text[doc.offset(error.range.start)..doc.offset(error.range.end)],
+            "help=#true"
+        );
+    }
+
+    #[test]
+    fn a_syntax_error_is_placed_in_the_file() {
+        let doc = Document::new(None, "bin \"x\"\ncmd \"a\" {\n".to_string());
+        assert!(doc.spec.is_none());
+        assert!(doc.diagnostics.iter().all(|d| d.range.start.line >= 1));
+        assert!(!doc.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn lint_findings_become_diagnostics() {
+        let doc = Document::new(None, "bin \"x\"\nflag \"--force\"\n".to_string());
+        let missing = doc
+            .diagnostics
+            .iter()
+            .find(|d| d.code == Some(NumberOrString::String("missing-flag-help".into())))
+            .expect("missing-flag-help");
+        assert_eq!(missing.range.start, Position::new(1, 0));
+    }
+}
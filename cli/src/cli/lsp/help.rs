@@ -0,0 +1,269 @@
+//! Hover and completion: what the vocabulary says about the name under the cursor, and
+//! which names fit where it is.
+
+use std::ops::Range;
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, Hover, HoverContents,
+    MarkupContent, MarkupKind, TextEdit,
+};
+
+use super::document::Document;
+use super::syntax::{Hit, Tree};
+use super::vocabulary::{self, Child, Node};
+use crate::cli::lint::RULES;
+
+/// What a block may hold, by where it is: `None` for the top level. `None` back for a block
+/// the vocabulary does not know, such as one inside a misspelled node.
+pub fn vocabulary_of(tree: &Tree, block: Option<usize>) -> Option<&'static Node> {
+    let mut node = vocabulary::root();
+    if let Some(id) = block {
+        for name in tree.path(id) {
+            match node.child(name)? {
+                Child::Node(child) => node = child,
+                Child::Leaf(_) => return None,
+            }
+        }
+    }
+    Some(node)
+}
+
+pub fn hover(doc: &Document, offset: usize) -> Option<Hover> {
+    let tree = &doc.tree;
+    let (text, span) = match tree.hit(offset)? {
+        Hit::Name(id) => {
+            let node = tree.node(id);
+            let name = node.name.text.as_str();
+            let block = vocabulary_of(tree, node.parent)?;
+            let text = if vocabulary::is_lint(block) {
+                RULES.contains(&name).then(|| {
+                    format!(
+                        "**{name}**: a `usage lint` rule, at the level given here: \
+                         `error`, `warning`, `info` or `off`."
+                    )
+                })?
+            } else {
+                let child = block.child(name)?;
+                let page = match child {
+                    Child::Node(node) => node,
+                    Child::Leaf(_) => block,
+                };
+                format!("**{name}**: {}\n\n[Reference]({})", child.doc(), page.url())
+            };
+            (text, node.name.span.clone())
+        }
+        Hit::Key(id, entry) => {
+            let node = tree.node(id);
+            let key = node.entries[entry].key.as_ref()?;
+            let vocabulary = vocabulary_of(tree, Some(id))?;
+            let word = vocabulary.prop(&key.text)?;
+            let text = format!(
+                "**{}** on `{}`: {}\n\n[Reference]({})",
+                word.name,
+                vocabulary.name,
+                word.doc,
+                vocabulary.url()
+            );
+            (text, key.span.clone())
+        }
+        Hit::Value(..) => return None,
+    };
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: text,
+        }),
+        range: Some(doc.range(span)),
+    })
+}
+
+/// The names that fit at `offset`: a node's, a property's, or a flagset's after `use`.
+pub fn complete(doc: &Document, offset: usize, flagsets: &[String]) -> Vec<CompletionItem> {
+    let tree = &doc.tree;
+    let Some(id) = tree.node_at(offset) else {
+        return node_names(doc, tree.block_at(offset), offset..offset);
+    };
+    let node = tree.node(id);
+    if offset <= node.name.span.end {
+        return node_names(doc, node.parent, node.name.span.start..offset);
+    }
+    let hit = tree.hit(offset);
+    if node.name.text == "use" {
+        let range = match hit {
+            Some(Hit::Value(_, entry)) => node.entries[entry].value.span.clone(),
+            _ => offset..offset,
+        };
+        return flagsets
+            .iter()
+            .map(|name| {
+                item(
+                    doc,
+                    name,
+                    CompletionItemKind::REFERENCE,
+                    None,
+                    range.clone(),
+                    format!("\"{name}\""),
+                )
+            })
+            .collect();
+    }
+    // A property's name is typed as a bare word, which reads as a value until its `=`.
+    let range = match hit {
+        Some(Hit::Key(_, entry)) => node.entries[entry]
+            .key
+            .as_ref()
+            .map_or(offset..offset, |key| key.span.start..offset),
+        Some(Hit::Value(_, entry)) => {
+            let value = &node.entries[entry].value;
+            let bare = !doc.text[value.span.clone()].starts_with(['"', '#', 'r']);
+            if node.entries[entry].key.is_some() || !bare {
+                return vec![];
+            }
+            value.span.start..offset
+        }
+        // Past the name, so not on it.
+        Some(Hit::Name(_)) | None => offset..offset,
+    };
+    let Some(vocabulary) = vocabulary_of(tree, Some(id)) else {
+        return vec![];
+    };
+    let written: Vec<&str> = node
+        .entries
+        .iter()
+        .filter_map(|e| e.key.as_ref().map(|k| k.text.as_str()))
+        .collect();
+    vocabulary
+        .props
+        .iter()
+        .filter(|word| !written.contains(&word.name))
+        .map(|word| {
+            item(
+                doc,
+                word.name,
+                CompletionItemKind::PROPERTY,
+                Some(word.doc),
+                range.clone(),
+                format!("{}=", word.name),
+            )
+        })
+        .collect()
+}
+
+fn node_names(doc: &Document, block: Option<usize>, range: Range<usize>) -> Vec<CompletionItem> {
+    let Some(vocabulary) = vocabulary_of(&doc.tree, block) else {
+        return vec![];
+    };
+    if vocabulary::is_lint(vocabulary) {
+        return RULES
+            .iter()
+            .map(|rule| {
+                item(
+                    doc,
+                    rule,
+                    CompletionItemKind::ENUM_MEMBER,
+                    None,
+                    range.clone(),
+                    rule.to_string(),
+                )
+            })
+            .collect();
+    }
+    let mut seen = vec![];
+    vocabulary
+        .children()
+        .filter(|child| {
+            let new = !seen.contains(&child.name());
+            seen.push(child.name());
+            new
+        })
+        .map(|child| {
+            let kind = match child {
+                Child::Node(_) => CompletionItemKind::STRUCT,
+                Child::Leaf(_) => CompletionItemKind::FIELD,
+            };
+            item(
+                doc,
+                child.name(),
+                kind,
+                Some(child.doc()),
+                range.clone(),
+                child.name().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn item(
+    doc: &Document,
+    label: &str,
+    kind: CompletionItemKind,
+    documentation: Option<&str>,
+    range: Range<usize>,
+    new_text: String,
+) -> CompletionItem {
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(kind),
+        documentation: documentation.map(|doc| {
+            Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: doc.to_string(),
+            })
+        }),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+            range: doc.range(range),
+            new_text,
+        })),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(text: &str, flagsets: &[String]) -> Vec<String> {
+        let doc = Document::new(None, text.to_string());
+        complete(&doc, text.len(), flagsets)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn node_names_fit_the_block() {
+        let top = labels("fl", &[]);
+        assert!(top.contains(&"flagset".to_string()) && top.contains(&"bin".to_string()));
+        let in_flag = labels("flag \"--x\" {\n  ", &[]);
+        assert!(in_flag.contains(&"choices".to_string()));
+        assert!(!in_flag.contains(&"cmd".to_string()));
+        assert!(labels("lint {\n  ", &[]).contains(&"missing-flag-help".to_string()));
+    }
+
+    #[test]
+    fn property_names_fit_the_node() {
+        let props = labels("cmd \"a\" {\n  flag \"--x\" help=\"h\" ne", &[]);
+        assert!(props.contains(&"negate".to_string()));
+        assert!(!props.contains(&"help".to_string()));
+        assert!(labels("flag \"--x\" help=\"", &[]).is_empty());
+    }
+
+    #[test]
+    fn flagset_names_after_use() {
+        let sets = vec!["output".to_string()];
+        assert_eq!(labels("cmd \"a\" {\n  use ", &sets), ["output"]);
+    }
+
+    #[test]
+    fn hover_on_a_node_and_a_property() {
+        let text = "flag \"--x\" negate=\"--no-x\"";
+        let doc = Document::new(None, text.to_string());
+        let markdown = |offset| match hover(&doc, offset).map(|h| h.contents) {
+            Some(HoverContents::Markup(markup)) => markup.value,
+            other => panic!("{other:?}"),
+        };
+        assert!(markdown(1).starts_with("**flag**"));
+        assert!(markdown(12).starts_with("**negate** on `flag`"));
+        assert!(hover(&doc, 6).is_none());
+    }
+}
@@ -0,0 +1,462 @@
+//! A language server for spec files, over stdio.
+//!
+//! Everything it says comes from something that already exists: diagnostics are what
+//! `Spec::parse_file_contents` fails with and what `usage lint` finds, run on the editor's
+//! buffer rather than the saved file; hover and completion read [`vocabulary`], whose test
+//! holds it to the parsers in `usage-lib`. What is new is [`syntax`], a reading of the buffer
+//! forgiving enough to say what the cursor is on while the spec is half-typed.
+//!
+//! Built on `lsp-server`, the synchronous transport rust-analyzer uses: an editor talks to
+//! this one message at a time, and every answer is computed from text already in memory, so
+//! there is nothing for an async runtime to overlap.
+
+mod document;
+mod help;
+mod rename;
+mod syntax;
+mod vocabulary;
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, GotoDefinition, HoverRequest, PrepareRenameRequest, Rename, Request as _,
+};
+use lsp_types::{
+    CompletionOptions, CompletionParams, CompletionResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverParams, HoverProviderCapability, Location, OneOf,
+    PrepareRenameResponse, PublishDiagnosticsParams, RenameOptions, RenameParams,
+    ServerCapabilities, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextDocumentSyncSaveOptions, TextEdit, Uri,
+    WorkspaceEdit,
+};
+use miette::{IntoDiagnostic, Result};
+use serde::de::DeserializeOwned;
+
+use document::Document;
+use syntax::Hit;
+
+/// Serve a language server for usage spec files
+///
+/// Speaks the Language Server Protocol over stdin and stdout, which is how an
+/// editor launches one. Point yours at `usage lsp` for `*.usage.kdl` files and
+/// scripts with `#USAGE` comments, and it offers:
+///
+/// - diagnostics from parsing the spec and from the `usage lint` rules
+/// - hover docs for every node and property a spec can hold
+/// - completion of node and property names, and of flagsets after `use`
+/// - go to definition from `include`, `use`, and flags named by `requires=`
+/// - renaming a command or a flag everywhere the file names it
+///
+/// The buffer is checked as it is typed; a file it includes is read from disk,
+/// and rechecked when the editor saves it.
+#[derive(usage_rs::Args)]
+#[usage(effect = "read", verbatim_doc_comment)]
+pub struct Lsp;
+
+impl usage_rs::Run for Lsp {
+    type Output = Result<()>;
+
+    fn run(self) -> Self::Output {
+        let (connection, io) = Connection::stdio();
+        let capabilities = serde_json::to_value(capabilities()).into_diagnostic()?;
+        connection.initialize(capabilities).into_diagnostic()?;
+        Server::default().serve(&connection)?;
+        // The writer has to be let go of before the threads can finish.
+        drop(connection);
+        io.join().into_diagnostic()
+    }
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        // The whole text on every change: a spec is small, and the parse is of all of it
+        // regardless, so applying increments would only be more to get wrong.
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        definition_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: Default::default(),
+        })),
+        ..Default::default()
+    }
+}
+
+/// The open documents, by the URI the editor knows each one as.
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+impl Server {
+    fn serve(mut self, connection: &Connection) -> Result<()> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request).into_diagnostic()? {
+                        return Ok(());
+                    }
+                    let response = self.handle(request);
+                    connection.sender.send(response.into()).into_diagnostic()?;
+                }
+                Message::Notification(notification) => {
+                    for out in self.notify(notification) {
+                        connection.sender.send(out.into()).into_diagnostic()?;
+                    }
+                }
+                // It asks the editor nothing, so nothing comes back worth reading.
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            HoverRequest::METHOD => respond(request, |params| Ok(self.hover(params))),
+            Completion::METHOD => respond(request, |params| Ok(self.complete(params))),
+            GotoDefinition::METHOD => respond(request, |params| Ok(self.definition(params))),
+            PrepareRenameRequest::METHOD => {
+                respond(request, |params| Ok(self.prepare_rename(params)))
+            }
+            Rename::METHOD => respond(request, |params| self.rename(params)),
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("`{method}` is not something `usage lsp` answers"),
+            ),
+        }
+    }
+
+    /// What a notification changes, as the diagnostics to publish because of it.
+    fn notify(&mut self, notification: Notification) -> Vec<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = params::<lsp_types::DidOpenTextDocumentParams>(notification)
+                else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                let document = Document::new(path_of(&uri), params.text_document.text);
+                let out = publish(&uri, &document);
+                self.documents.insert(uri, document);
+                vec![out]
+            }
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = params::<lsp_types::DidChangeTextDocumentParams>(notification)
+                else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                let (Some(document), Some(change)) = (
+                    self.documents.get_mut(&uri),
+                    params.content_changes.into_iter().next_back(),
+                ) else {
+                    return vec![];
+                };
+                document.update(change.text);
+                vec![publish(&uri, document)]
+            }
+            DidSaveTextDocument::METHOD => {
+                let Some(params) = params::<lsp_types::DidSaveTextDocumentParams>(notification)
+                else {
+                    return vec![];
+                };
+                // What is on disk is what the specs that include it read.
+                let Some(saved) = path_of(&params.text_document.uri) else {
+                    return vec![];
+                };
+                self.documents
+                    .iter_mut()
+                    .filter(|(_, doc)| doc.path.as_ref() != Some(&saved))
+                    .filter(|(_, doc)| included(doc).contains(&saved))
+                    .map(|(uri, doc)| {
+                        doc.recheck();
+                        publish(uri, doc)
+                    })
+                    .collect()
+            }
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = params::<lsp_types::DidCloseTextDocumentParams>(notification)
+                else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                // A closed file's problems are no longer the editor's to show.
+                vec![diagnostics(uri, vec![])]
+            }
+            _ => vec![],
+        }
+    }
+
+    fn document(&self, at: &TextDocumentPositionParams) -> Option<(&Document, usize)> {
+        let doc = self.documents.get(&at.text_document.uri)?;
+        Some((doc, doc.offset(at.position)))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let (doc, offset) = self.document(&params.text_document_position_params)?;
+        help::hover(doc, offset)
+    }
+
+    fn complete(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let (doc, offset) = self.document(&params.text_document_position)?;
+        // The sets as written, for one not yet parsed, and as parsed, for the included ones.
+        let mut flagsets: Vec<String> = doc
+            .tree
+            .roots
+            .iter()
+            .map(|id| doc.tree.node(*id))
+            .filter(|node| node.name.text == "flagset" && !node.commented)
+            .filter_map(|node| node.arg(0).map(|name| name.text.clone()))
+            .collect();
+        flagsets.extend(
+            doc.spec
+                .iter()
+                .flat_map(|spec| spec.flagsets.keys().cloned()),
+        );
+        flagsets.sort();
+        flagsets.dedup();
+        Some(CompletionResponse::Array(help::complete(
+            doc, offset, &flagsets,
+        )))
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let at = &params.text_document_position_params;
+        let (doc, offset) = self.document(at)?;
+        let Some(Hit::Value(id, entry)) = doc.tree.hit(offset) else {
+            return None;
+        };
+        let node = doc.tree.node(id);
+        let location = match node.name.text.as_str() {
+            "include" => {
+                let file = doc.include_target(id).filter(|file| file.exists())?;
+                Location::new(uri_of(&file)?, lsp_types::Range::default())
+            }
+            "use" => self.flagset(doc, &at.text_document.uri, &node.entries[entry].value.text)?,
+            _ => {
+                let range = rename::declaration(&doc.tree, &doc.text, offset)?;
+                Location::new(at.text_document.uri.clone(), doc.range(range))
+            }
+        };
+        Some(GotoDefinitionResponse::Scalar(location))
+    }
+
+    /// Where the flagset `name` is declared: in `doc`, or in a file it includes, open or not.
+    fn flagset(&self, doc: &Document, uri: &Uri, name: &str) -> Option<Location> {
+        let declared = |doc: &Document| {
+            let id = rename::flagset(&doc.tree, name)?;
+            let name = doc.tree.node(id).arg(0)?;
+            Some(doc.range(name.span.clone()))
+        };
+        if let Some(range) = declared(doc) {
+            return Some(Location::new(uri.clone(), range));
+        }
+        included(doc).into_iter().find_map(|path| {
+            let uri = uri_of(&path)?;
+            let range = match self.documents.get(&uri) {
+                Some(open) => declared(open)?,
+                None => {
+                    let text = std::fs::read_to_string(&path).ok()?;
+                    declared(&Document::new(Some(path), text))?
+                }
+            };
+            Some(Location::new(uri, range))
+        })
+    }
+
+    fn prepare_rename(&self, params: TextDocumentPositionParams) -> Option<PrepareRenameResponse> {
+        let (doc, offset) = self.document(&params)?;
+        let (_, range) = rename::target(&doc.tree, &doc.text, offset)?;
+        Some(PrepareRenameResponse::Range(doc.range(range)))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>, String> {
+        let at = &params.text_document_position;
+        let Some((doc, offset)) = self.document(at) else {
+            return Ok(None);
+        };
+        let edits = rename::rename(&doc.tree, &doc.text, offset, &params.new_name)?
+            .into_iter()
+            .map(|range| TextEdit::new(doc.range(range), params.new_name.clone()))
+            .collect();
+        Ok(Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(at.text_document.uri.clone(), edits)])),
+            ..Default::default()
+        }))
+    }
+}
+
+/// A request's answer, or why there is none.
+fn respond<P, R>(request: Request, answer: impl FnOnce(P) -> Result<R, String>) -> Response
+where
+    P: DeserializeOwned,
+    R: serde::Serialize,
+{
+    let params = match serde_json::from_value(request.params) {
+        Ok(params) => params,
+        Err(err) => {
+            return Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string())
+        }
+    };
+    match answer(params) {
+        Ok(result) => Response::new_ok(request.id, result),
+        Err(message) => Response::new_err(request.id, ErrorCode::RequestFailed as i32, message),
+    }
+}
+
+/// A notification's params. One that does not read is dropped: a notification has no
+/// answer to put the error in.
+fn params<P: DeserializeOwned>(notification: Notification) -> Option<P> {
+    serde_json::from_value(notification.params).ok()
+}
+
+fn publish(uri: &Uri, document: &Document) -> Notification {
+    diagnostics(uri.clone(), document.diagnostics.clone())
+}
+
+fn diagnostics(uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        PublishDiagnosticsParams::new(uri, diagnostics, None),
+    )
+}
+
+/// The files a document's spec is read from besides itself: those its `include`s name as
+/// written, and those the last parse brought in, which counts an include's own includes.
+fn included(doc: &Document) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = doc.includes().into_iter().map(|(_, file)| file).collect();
+    for file in doc.spec.iter().flat_map(|spec| &spec.sources) {
+        if !files.contains(file) && doc.path.as_ref() != Some(file) {
+            files.push(file.clone());
+        }
+    }
+    files
+}
+
+/// The file a `file:` URI names. `None` for anything else, such as an unsaved buffer.
+fn path_of(uri: &Uri) -> Option<PathBuf> {
+    if !uri.scheme()?.as_str().eq_ignore_ascii_case("file") {
+        return None;
+    }
+    let path = uri.path().as_estr().decode().into_string().ok()?;
+    Some(PathBuf::from(path.as_ref()))
+}
+
+fn uri_of(path: &Path) -> Option<Uri> {
+    let mut uri = String::from("file://");
+    for byte in path.to_str()?.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            let _ = write!(uri, "%{byte:02X}");
+        }
+    }
+    uri.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::RequestId;
+    use lsp_types::{Position, TextDocumentIdentifier, TextDocumentItem};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn open(server: &mut Server, uri: &Uri, text: &str) -> Vec<Notification> {
+        let params = lsp_types::DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri.clone(), "kdl".into(), 1, text.into()),
+        };
+        server.notify(Notification::new(
+            DidOpenTextDocument::METHOD.into(),
+            params,
+        ))
+    }
+
+    fn ask(server: &mut Server, method: &str, params: Value) -> Value {
+        let response = server.handle(Request::new(RequestId::from(1), method.into(), params));
+        assert!(response.error.is_none(), "{:?}", response.error);
+        response.result.unwrap_or_default()
+    }
+
+    fn at(uri: &Uri, line: u32, character: u32) -> Value {
+        json!({
+            "textDocument": TextDocumentIdentifier::new(uri.clone()),
+            "position": Position::new(line, character),
+        })
+    }
+
+    #[test]
+    fn uris_and_paths_round_trip() {
+        let path = Path::new("/tmp/my specs/tool.usage.kdl");
+        let uri = uri_of(path).unwrap();
+        assert_eq!(uri.as_str(), "file:///tmp/my%20specs/tool.usage.kdl");
+        assert_eq!(path_of(&uri).unwrap(), path);
+        assert_eq!(path_of(&"untitled:Untitled-1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn a_session_over_an_included_flagset() {
+        let dir = std::env::temp_dir().join(format!("usage_lsp_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let shared = dir.join("shared.usage.kdl");
+        std::fs::write(
+            &shared,
+            "flagset \"output\" {\n  flag \"--json\" help=\"JSON\"\n}\n",
+        )
+        .unwrap();
+        let root = dir.join("tool.usage.kdl");
+        let text = "bin \"tool\"\nabout \"A tool\"\ninclude file=\"shared.usage.kdl\"\ncmd \"list\" help=\"List\" {\n  use \"output\"\n  flag \"--all\"\n}\n";
+        let uri = uri_of(&root).unwrap();
+        let mut server = Server::default();
+
+        let published = open(&mut server, &uri, text);
+        let params: PublishDiagnosticsParams =
+            serde_json::from_value(published[0].params.clone()).unwrap();
+        let codes: Vec<_> = params.diagnostics.iter().map(|d| d.code.clone()).collect();
+        assert_eq!(
+            codes,
+            [Some(lsp_types::NumberOrString::String(
+                "missing-flag-help".into()
+            ))]
+        );
+
+        let definition = ask(&mut server, GotoDefinition::METHOD, at(&uri, 4, 9));
+        let location: Location = serde_json::from_value(definition).unwrap();
+        assert_eq!(location.uri, uri_of(&shared).unwrap());
+        assert_eq!(location.range.start, Position::new(0, 8));
+
+        let completion = ask(&mut server, Completion::METHOD, at(&uri, 4, 6));
+        assert_eq!(completion[0]["label"], "output");
+
+        let edit = ask(
+            &mut server,
+            Rename::METHOD,
+            json!({
+                "textDocument": { "uri": uri },
+                "position": Position::new(3, 6),
+                "newName": "ls",
+            }),
+        );
+        assert_eq!(edit["changes"][uri.as_str()][0]["newText"], "ls");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,510 @@
+//! Renaming a command or a flag spelling, everywhere in the file that names it.
+//!
+//! A flag is renamed where it can be seen from: the command that declares it and the
+//! commands below it, everywhere for one declared at the top level, and for one in a
+//! flagset, the set and every command that `use`s it. That is what keeps renaming `--force`
+//! on `deploy` from touching an unrelated `--force` on `clean`.
+
+use std::ops::Range;
+
+use super::syntax::{Hit, Token, Tree};
+
+/// What a rename starting at the cursor renames.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Target {
+    /// A `cmd` node, by id.
+    Command(usize),
+    /// A flag spelling, `--force` or `-f`.
+    Flag(String),
+}
+
+/// The props that name a flag by its spelling.
+const FLAG_PROPS: &[&str] = &[
+    "conflicts",
+    "overrides",
+    "required_if",
+    "required_unless",
+    "required_unless_all",
+    "requires",
+];
+
+/// What is at `offset`, and the range of the name a rename would replace.
+pub fn target(tree: &Tree, text: &str, offset: usize) -> Option<(Target, Range<usize>)> {
+    let Some(Hit::Value(id, entry)) = tree.hit(offset) else {
+        return None;
+    };
+    let node = tree.node(id);
+    let token = &node.entries[entry].value;
+    let key = node.entries[entry].key.as_ref().map(|k| k.text.as_str());
+    let first = node.arg(0).is_some_and(|arg| arg.span == token.span);
+    match (node.name.text.as_str(), key) {
+        ("cmd", None) if first => Some((Target::Command(id), inner(text, token)?)),
+        ("default_subcommand", None) if node.parent.is_none() => {
+            let cmd = command(tree, None, &token.text)?;
+            Some((Target::Command(cmd), inner(text, token)?))
+        }
+        ("flag", None) if first && !in_group(tree, id) => {
+            let range = spelling_at(text, token.span.clone(), offset)?;
+            Some((Target::Flag(text[range.clone()].to_string()), range))
+        }
+        _ if token.text.starts_with('-') && flag_references(tree, id).contains(&token.span) => {
+            Some((Target::Flag(token.text.clone()), inner(text, token)?))
+        }
+        _ => None,
+    }
+}
+
+/// The ranges to replace to rename the target at `offset` to `new_name`.
+pub fn rename(
+    tree: &Tree,
+    text: &str,
+    offset: usize,
+    new_name: &str,
+) -> Result<Vec<Range<usize>>, String> {
+    let Some((target, _)) = target(tree, text, offset) else {
+        return Err("nothing to rename here: put the cursor on a command or flag name".into());
+    };
+    if new_name.is_empty() || new_name.contains(char::is_whitespace) || new_name.contains('"') {
+        return Err(format!("`{new_name}` cannot be a name"));
+    }
+    match target {
+        Target::Command(id) => Ok(command_ranges(tree, text, id)),
+        Target::Flag(spelling) => {
+            let long = spelling.starts_with("--");
+            let valid = if long {
+                new_name.len() > 2 && new_name.starts_with("--")
+            } else {
+                new_name.starts_with('-')
+                    && !new_name.starts_with("--")
+                    && new_name.chars().count() == 2
+            };
+            if !valid {
+                return Err(format!(
+                    "`{spelling}` is a {} flag, and `{new_name}` is not one",
+                    if long { "long" } else { "short" }
+                ));
+            }
+            let Some(Hit::Value(at, _)) = tree.hit(offset) else {
+                return Ok(vec![]);
+            };
+            let block = declaring_block(tree, at, &spelling)
+                .ok_or_else(|| format!("no flag here declares `{spelling}`"))?;
+            Ok(flag_ranges(tree, text, &scope(tree, block), &spelling))
+        }
+    }
+}
+
+/// Where the flag a `requires=`, `conflicts=` or other reference at `offset` names is
+/// declared: the spelling on the `flag` node, in its command or in a set the command uses.
+pub fn declaration(tree: &Tree, text: &str, offset: usize) -> Option<Range<usize>> {
+    let (Target::Flag(spelling), _) = target(tree, text, offset)? else {
+        return None;
+    };
+    let Some(Hit::Value(at, _)) = tree.hit(offset) else {
+        return None;
+    };
+    let block = declaring_block(tree, at, &spelling)?;
+    tree.children(block).iter().find_map(|id| {
+        let node = tree.node(*id);
+        let declared = node.name.text == "flag" && spellings(tree, *id).contains(&spelling);
+        let arg = node.arg(0).filter(|_| declared)?;
+        spelling_ranges(text, arg.span.clone(), &spelling)
+            .into_iter()
+            .next()
+            .or_else(|| Some(node.name.span.clone()))
+    })
+}
+
+/// Where `cmd`'s name is written: the node, `default_subcommand` for a top-level one, and
+/// the `root` of any view that promotes it or a command below it.
+fn command_ranges(tree: &Tree, text: &str, id: usize) -> Vec<Range<usize>> {
+    let node = tree.node(id);
+    let Some(name) = node.arg(0) else {
+        return vec![];
+    };
+    let mut ranges: Vec<_> = inner(text, name).into_iter().collect();
+    let path = command_path(tree, id);
+    for root in &tree.roots {
+        let top = tree.node(*root);
+        match top.name.text.as_str() {
+            "default_subcommand" if node.parent.is_none() => {
+                if let Some(value) = top.arg(0).filter(|v| v.text == name.text) {
+                    ranges.extend(inner(text, value));
+                }
+            }
+            "view" => {
+                let Some(root) = top.prop("root") else {
+                    continue;
+                };
+                let segments: Vec<&str> = root.text.split_whitespace().collect();
+                if segments.len() >= path.len() && segments[..path.len()] == path[..] {
+                    if let Some(range) = inner(text, root) {
+                        ranges.extend(
+                            nth_word(&text[range.clone()], path.len() - 1)
+                                .map(|word| range.start + word.start..range.start + word.end),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges
+}
+
+/// The names of the commands from the top down to `id`.
+fn command_path(tree: &Tree, id: usize) -> Vec<&str> {
+    let mut path = vec![];
+    let mut at = Some(id);
+    while let Some(id) = at {
+        let node = tree.node(id);
+        if node.name.text == "cmd" {
+            path.extend(node.arg(0).map(|name| name.text.as_str()));
+        }
+        at = node.parent;
+    }
+    path.reverse();
+    path
+}
+
+/// The top-level or `cmd` child named `name` in `block`.
+fn command(tree: &Tree, block: Option<usize>, name: &str) -> Option<usize> {
+    tree.children(block).iter().copied().find(|id| {
+        let node = tree.node(*id);
+        node.name.text == "cmd" && node.arg(0).is_some_and(|arg| arg.text == name)
+    })
+}
+
+fn in_group(tree: &Tree, id: usize) -> bool {
+    tree.node(id)
+        .parent
+        .is_some_and(|parent| tree.node(parent).name.text == "group")
+}
+
+/// The block that declares `spelling` as seen from node `at`: the nearest enclosing command,
+/// flagset or top level that has a flag spelled that way, or uses a set that does.
+/// `None` inside the outer `Option` is the top level.
+fn declaring_block(tree: &Tree, at: usize, spelling: &str) -> Option<Option<usize>> {
+    let mut block = tree.node(at).parent;
+    loop {
+        let holder = match block {
+            Some(id) if !matches!(tree.node(id).name.text.as_str(), "cmd" | "flagset") => {
+                block = tree.node(id).parent;
+                continue;
+            }
+            holder => holder,
+        };
+        if declares(tree, holder, spelling) {
+            return Some(holder);
+        }
+        for set in used_sets(tree, holder) {
+            if let Some(found) = set_declaring(tree, set, spelling, &mut vec![]) {
+                return Some(Some(found));
+            }
+        }
+        match holder {
+            Some(id) => block = tree.node(id).parent,
+            None => return None,
+        }
+    }
+}
+
+fn declares(tree: &Tree, block: Option<usize>, spelling: &str) -> bool {
+    tree.children(block).iter().any(|id| {
+        let node = tree.node(*id);
+        node.name.text == "flag" && spellings(tree, *id).iter().any(|s| s == spelling)
+    })
+}
+
+/// Every spelling a `flag` node declares: its own, its negation, and its aliases.
+fn spellings(tree: &Tree, id: usize) -> Vec<String> {
+    let node = tree.node(id);
+    let mut all: Vec<String> = node
+        .arg(0)
+        .map(|arg| {
+            arg.text
+                .split_whitespace()
+                .filter_map(|word| {
+                    let end = word
+                        .find(|c: char| !is_spelling_char(c))
+                        .unwrap_or(word.len());
+                    word[..end]
+                        .starts_with('-')
+                        .then(|| word[..end].to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    all.extend(node.prop("negate").map(|t| t.text.clone()));
+    for child in &node.children {
+        let child = tree.node(*child);
+        if child.name.text == "alias" {
+            all.extend(child.args().map(|t| t.text.clone()));
+        }
+    }
+    all
+}
+
+/// The flagset nodes a block `use`s.
+fn used_sets(tree: &Tree, block: Option<usize>) -> Vec<usize> {
+    tree.children(block)
+        .iter()
+        .filter(|id| tree.node(**id).name.text == "use")
+        .flat_map(|id| tree.node(*id).args())
+        .filter_map(|name| flagset(tree, &name.text))
+        .collect()
+}
+
+/// The top-level `flagset` named `name`.
+pub fn flagset(tree: &Tree, name: &str) -> Option<usize> {
+    tree.roots.iter().copied().find(|id| {
+        let node = tree.node(*id);
+        node.name.text == "flagset" && node.arg(0).is_some_and(|arg| arg.text == name)
+    })
+}
+
+/// The set, or one it uses in turn, that declares `spelling`.
+fn set_declaring(tree: &Tree, set: usize, spelling: &str, seen: &mut Vec<usize>) -> Option<usize> {
+    if seen.contains(&set) {
+        return None;
+    }
+    seen.push(set);
+    if declares(tree, Some(set), spelling) {
+        return Some(set);
+    }
+    used_sets(tree, Some(set))
+        .into_iter()
+        .find_map(|inner| set_declaring(tree, inner, spelling, seen))
+}
+
+/// The nodes a flag declared in `block` can be seen from.
+fn scope(tree: &Tree, block: Option<usize>) -> Vec<usize> {
+    let mut nodes = vec![];
+    let mut pending = vec![block];
+    let mut done = vec![];
+    while let Some(block) = pending.pop() {
+        if done.contains(&block) {
+            continue;
+        }
+        done.push(block);
+        let Some(id) = block else {
+            return (0..tree.nodes.len()).collect();
+        };
+        nodes.extend(tree.subtree(id));
+        let node = tree.node(id);
+        if node.name.text != "flagset" {
+            continue;
+        }
+        // A set's flags are the flags of whatever uses it.
+        let Some(name) = node.arg(0) else {
+            continue;
+        };
+        for user in &tree.nodes {
+            if user.name.text == "use" && user.args().any(|arg| arg.text == name.text) {
+                let mut holder = user.parent;
+                while let Some(h) = holder {
+                    if matches!(tree.node(h).name.text.as_str(), "cmd" | "flagset") {
+                        break;
+                    }
+                    holder = tree.node(h).parent;
+                }
+                pending.push(holder);
+            }
+        }
+    }
+    nodes
+}
+
+/// Where `spelling` is written in `nodes`: on the flag that declares it, and wherever a
+/// flag is named by its spelling.
+fn flag_ranges(tree: &Tree, text: &str, nodes: &[usize], spelling: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    for id in nodes {
+        let node = tree.node(*id);
+        if node.name.text == "flag" && !in_group(tree, *id) {
+            if let Some(arg) = node.arg(0) {
+                ranges.extend(spelling_ranges(text, arg.span.clone(), spelling));
+            }
+        }
+        for span in flag_references(tree, *id) {
+            if text.get(span.clone()).is_some() {
+                let token = node
+                    .entries
+                    .iter()
+                    .map(|e| &e.value)
+                    .find(|v| v.span == span);
+                if let Some(token) = token.filter(|t| t.text == spelling) {
+                    ranges.extend(inner(text, token));
+                }
+            }
+        }
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges.dedup();
+    ranges
+}
+
+/// The values of node `id` that name a flag by its spelling.
+fn flag_references(tree: &Tree, id: usize) -> Vec<Range<usize>> {
+    let node = tree.node(id);
+    let parent = node.parent.map(|p| tree.node(p).name.text.as_str());
+    let args: Vec<&Token> = node.args().collect();
+    let mut values: Vec<&Token> = node
+        .entries
+        .iter()
+        .filter(|e| {
+            e.key
+                .as_ref()
+                .is_some_and(|k| FLAG_PROPS.contains(&k.text.as_str()) || k.text == "negate")
+        })
+        .map(|e| &e.value)
+        .collect();
+    match (node.name.text.as_str(), parent) {
+        (name, _) if FLAG_PROPS.contains(&name) => values.extend(&args),
+        ("required_if_eq" | "default_if", _) => values.extend(args.first()),
+        ("requires_if", _) => values.extend(args.get(1)),
+        ("required_if_eq_all", _) => values.extend(args.iter().step_by(2)),
+        ("group", _) => values.extend(args.iter().skip(1)),
+        ("flag", Some("group")) | ("alias", Some("flag")) => values.extend(&args),
+        ("global", Some("view")) | ("cli", Some("prop")) => values.extend(&args),
+        _ => {}
+    }
+    values.into_iter().map(|v| v.span.clone()).collect()
+}
+
+/// The range of a token's text inside its quotes.
+fn inner(text: &str, token: &Token) -> Option<Range<usize>> {
+    let raw = text.get(token.span.clone())?;
+    let at = raw.find(&token.text)?;
+    Some(token.span.start + at..token.span.start + at + token.text.len())
+}
+
+fn is_spelling_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// The spelling in a `flag` node's usage string that `offset` is on.
+fn spelling_at(text: &str, span: Range<usize>, offset: usize) -> Option<Range<usize>> {
+    let raw = text.get(span.clone())?;
+    let at = offset.checked_sub(span.start)?.min(raw.len());
+    let start = raw[..at]
+        .rfind(|c: char| !is_spelling_char(c))
+        .map_or(0, |i| i + 1);
+    let end = raw[at..]
+        .find(|c: char| !is_spelling_char(c))
+        .map_or(raw.len(), |i| at + i);
+    (raw[start..end].starts_with('-') && start < end).then(|| span.start + start..span.start + end)
+}
+
+/// Every place `spelling` is a whole spelling in a `flag` node's usage string.
+fn spelling_ranges(text: &str, span: Range<usize>, spelling: &str) -> Vec<Range<usize>> {
+    let Some(raw) = text.get(span.clone()) else {
+        return vec![];
+    };
+    raw.match_indices(spelling)
+        .filter(|(i, _)| {
+            let before = raw[..*i].chars().next_back();
+            let after = raw[i + spelling.len()..].chars().next();
+            !before.is_some_and(is_spelling_char) && !after.is_some_and(is_spelling_char)
+        })
+        .map(|(i, _)| span.start + i..span.start + i + spelling.len())
+        .collect()
+}
+
+/// The byte range of the `n`th whitespace-separated word of `text`.
+fn nth_word(text: &str, n: usize) -> Option<Range<usize>> {
+    let mut words = text
+        .split_whitespace()
+        .map(|word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            start..start + word.len()
+        })
+        .skip(n);
+    words.next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `text` with every range the rename at the first `^` in `text` would replace, renamed.
+    fn renamed(text: &str, new_name: &str) -> Result<String, String> {
+        let offset = text.find('^').unwrap();
+        let text = text.replacen('^', "", 1);
+        let tree = Tree::parse(&text);
+        let ranges = rename(&tree, &text, offset, new_name)?;
+        let mut out = text.clone();
+        for range in ranges.into_iter().rev() {
+            out.replace_range(range, new_name);
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn a_flag_is_renamed_in_its_command_and_below() {
+        let text = r#"cmd "deploy" {
+  flag "-f --fo^rce" negate="--no-force"
+  flag "--yes" conflicts="--force"
+  cmd "now" {
+    flag "--dry" requires="--force"
+  }
+}
+cmd "clean" {
+  flag "--force"
+}
+"#;
+        let out = renamed(text, "--hard").unwrap();
+        assert_eq!(out.matches("--hard").count(), 3);
+        assert!(out.contains("cmd \"clean\" {\n  flag \"--force\""));
+    }
+
+    #[test]
+    fn a_flagset_flag_is_renamed_where_it_is_used() {
+        let text = r#"flagset "out" {
+  flag "--json"
+}
+cmd "a" {
+  use "out"
+  flag "--pretty" requires="--js^on"
+}
+cmd "b" {
+  flag "--raw" conflicts="--json"
+}
+"#;
+        let out = renamed(text, "--yaml").unwrap();
+        assert!(out.contains("flag \"--yaml\"\n}"));
+        assert!(out.contains("requires=\"--yaml\""));
+        assert!(out.contains("conflicts=\"--json\""));
+    }
+
+    #[test]
+    fn a_command_is_renamed_with_what_names_it() {
+        let text = r#"default_subcommand "run"
+cmd "r^un" {
+  cmd "fast"
+}
+view "fastrun" root="run fast"
+"#;
+        let out = renamed(text, "go").unwrap();
+        assert!(out.starts_with("default_subcommand \"go\"\ncmd \"go\""));
+        assert!(out.contains("root=\"go fast\""));
+    }
+
+    #[test]
+    fn a_reference_leads_to_the_flag_it_names() {
+        let text = "flagset \"out\" {\n  flag \"-j --json\"\n}\ncmd \"a\" {\n  use \"out\"\n  flag \"--pretty\" requires=\"--json\"\n}\n";
+        let tree = Tree::parse(text);
+        let at = text.find("=\"--json").unwrap() + 3;
+        let range = declaration(&tree, text, at).unwrap();
+        assert_eq!(range.start, text.find("--json").unwrap());
+        assert_eq!(&text[range], "--json");
+    }
+
+    #[test]
+    fn a_rename_that_changes_the_kind_of_spelling_is_refused() {
+        let text = "flag \"-^f --force\"\n";
+        assert!(renamed(text, "--f").is_err());
+        assert_eq!(renamed(text, "-x").unwrap(), "flag \"-x --force\"\n");
+    }
+}
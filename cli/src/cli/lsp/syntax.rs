@@ -0,0 +1,527 @@
+//! A forgiving reading of a spec's KDL: enough of its shape to say what the cursor is on.
+//!
+//! The buffer an editor asks about is usually mid-edit, and so usually not a document kdl
+//! will parse — an unclosed string, a node with half a name. This reads it anyway: every
+//! node, its entries and its block, with byte ranges into the text, and anything it cannot
+//! make sense of skipped rather than fatal. What it is *not* is a second KDL parser to check
+//! a spec with; whether the spec is valid is still the question `Spec::parse_file_contents`
+//! answers.
+
+use std::ops::Range;
+
+/// A name or value, decoded, and where it is written.
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub text: String,
+    /// Quotes included, for a string.
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// The property's name, for `key=value`.
+    pub key: Option<Token>,
+    /// Empty, at the end of the key, for a property whose value is not written yet.
+    pub value: Token,
+}
+
+#[derive(Debug)]
+pub struct Node {
+    pub name: Token,
+    pub entries: Vec<Entry>,
+    pub children: Vec<usize>,
+    pub parent: Option<usize>,
+    /// From the name to the end of the line, or to the closing brace of its block.
+    pub span: Range<usize>,
+    /// Between the braces, when it has a block; to the end of the text when one is unclosed.
+    pub body: Option<Range<usize>>,
+    /// Commented out with `/-`, itself or an ancestor.
+    pub commented: bool,
+}
+
+impl Node {
+    /// The values, in order, properties left out.
+    pub fn args(&self) -> impl Iterator<Item = &Token> {
+        self.entries
+            .iter()
+            .filter(|e| e.key.is_none())
+            .map(|e| &e.value)
+    }
+
+    pub fn arg(&self, index: usize) -> Option<&Token> {
+        self.args().nth(index)
+    }
+
+    pub fn prop(&self, key: &str) -> Option<&Token> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.key.as_ref().is_some_and(|k| k.text == key))
+            .map(|e| &e.value)
+    }
+}
+
+/// Every node in a text.
+#[derive(Debug, Default)]
+pub struct Tree {
+    pub nodes: Vec<Node>,
+    /// The top level, in order.
+    pub roots: Vec<usize>,
+}
+
+/// What is at an offset in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hit {
+    Name(usize),
+    /// A property's key: the node, and the entry.
+    Key(usize, usize),
+    Value(usize, usize),
+}
+
+impl Tree {
+    pub fn parse(text: &str) -> Self {
+        Builder::new(text).build()
+    }
+
+    pub fn node(&self, id: usize) -> &Node {
+        &self.nodes[id]
+    }
+
+    /// The nodes a block holds: a node's children, or the top level for `None`.
+    pub fn children(&self, block: Option<usize>) -> &[usize] {
+        match block {
+            Some(id) => &self.nodes[id].children,
+            None => &self.roots,
+        }
+    }
+
+    /// The names from the top level down to `id`, itself included.
+    pub fn path(&self, id: usize) -> Vec<&str> {
+        let mut path = vec![];
+        let mut at = Some(id);
+        while let Some(id) = at {
+            path.push(self.nodes[id].name.text.as_str());
+            at = self.nodes[id].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// `id` and every node below it.
+    pub fn subtree(&self, id: usize) -> Vec<usize> {
+        let mut ids = vec![id];
+        let mut i = 0;
+        while i < ids.len() {
+            ids.extend(self.nodes[ids[i]].children.iter().copied());
+            i += 1;
+        }
+        ids
+    }
+
+    /// The innermost block `offset` is inside, `None` for the top level.
+    pub fn block_at(&self, offset: usize) -> Option<usize> {
+        let mut block = None;
+        loop {
+            let inner = self.children(block).iter().copied().find(|id| {
+                self.nodes[*id]
+                    .body
+                    .as_ref()
+                    .is_some_and(|body| body.start <= offset && offset <= body.end)
+            });
+            match inner {
+                Some(id) => block = Some(id),
+                None => return block,
+            }
+        }
+    }
+
+    /// The node whose line `offset` is on, within the block it is in.
+    pub fn node_at(&self, offset: usize) -> Option<usize> {
+        let block = self.block_at(offset);
+        self.children(block).iter().copied().rev().find(|id| {
+            let node = &self.nodes[*id];
+            node.span.start <= offset
+                && offset <= node.span.end
+                && !node
+                    .body
+                    .as_ref()
+                    .is_some_and(|body| body.start <= offset && offset <= body.end)
+        })
+    }
+
+    /// The name, key or value written at `offset`, its last character's end included, so that
+    /// a cursor at the end of a word is on it.
+    pub fn hit(&self, offset: usize) -> Option<Hit> {
+        let id = self.node_at(offset)?;
+        let node = &self.nodes[id];
+        let on = |span: &Range<usize>| span.start <= offset && offset <= span.end;
+        if on(&node.name.span) {
+            return Some(Hit::Name(id));
+        }
+        for (i, entry) in node.entries.iter().enumerate() {
+            if entry.key.as_ref().is_some_and(|key| on(&key.span)) {
+                return Some(Hit::Key(id, i));
+            }
+            if on(&entry.value.span) {
+                return Some(Hit::Value(id, i));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+enum Lexeme {
+    Word(Token),
+    Equals,
+    Open,
+    Close,
+    /// A newline or `;`.
+    End,
+    SlashDash,
+}
+
+struct Builder<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, at: 0 }
+    }
+
+    fn build(mut self) -> Tree {
+        // The blocks open around the current position, innermost last, and whether each was
+        // commented out; `None` for a stray brace with no node in front of it.
+        let mut blocks: Vec<(Option<usize>, bool)> = vec![];
+        let mut current: Option<usize> = None;
+        let mut slashdash = false;
+        let lexemes: Vec<_> = std::iter::from_fn(|| self.lex()).collect();
+        let mut lexemes = lexemes.into_iter().peekable();
+        let mut tree = Tree::default();
+        let commented_block = |blocks: &[(Option<usize>, bool)]| blocks.iter().any(|b| b.1);
+        while let Some((lexeme, span)) = lexemes.next() {
+            match lexeme {
+                Lexeme::End => {
+                    if let Some(id) = current.take() {
+                        tree.nodes[id].span.end = span.start;
+                    }
+                }
+                Lexeme::SlashDash => slashdash = true,
+                Lexeme::Word(token) => match current {
+                    None => {
+                        let parent = blocks.last().and_then(|b| b.0);
+                        let id = tree.nodes.len();
+                        tree.nodes.push(Node {
+                            span: token.span.clone(),
+                            name: token,
+                            entries: vec![],
+                            children: vec![],
+                            parent,
+                            body: None,
+                            commented: slashdash
+                                || commented_block(&blocks)
+                                || parent.is_some_and(|p| tree.nodes[p].commented),
+                        });
+                        match parent {
+                            Some(parent) => tree.nodes[parent].children.push(id),
+                            None => tree.roots.push(id),
+                        }
+                        slashdash = false;
+                        current = Some(id);
+                    }
+                    Some(id) => {
+                        let entry = if matches!(lexemes.peek(), Some((Lexeme::Equals, _))) {
+                            let (_, equals) =
+                                lexemes.next().unwrap_or((Lexeme::Equals, span.clone()));
+                            let value = match lexemes.peek() {
+                                Some((Lexeme::Word(_), _)) => match lexemes.next() {
+                                    Some((Lexeme::Word(value), _)) => value,
+                                    _ => unreachable!(),
+                                },
+                                _ => Token {
+                                    text: String::new(),
+                                    span: equals.end..equals.end,
+                                },
+                            };
+                            Entry {
+                                key: Some(token),
+                                value,
+                            }
+                        } else {
+                            Entry {
+                                key: None,
+                                value: token,
+                            }
+                        };
+                        let node = &mut tree.nodes[id];
+                        node.span.end = entry.value.span.end;
+                        if !std::mem::take(&mut slashdash) {
+                            node.entries.push(entry);
+                        }
+                    }
+                },
+                Lexeme::Equals => {}
+                Lexeme::Open => {
+                    if let Some(id) = current {
+                        tree.nodes[id].body = Some(span.end..self.text.len());
+                        tree.nodes[id].span.end = self.text.len();
+                    }
+                    blocks.push((current.take(), std::mem::take(&mut slashdash)));
+                }
+                Lexeme::Close => {
+                    if let Some(id) = current.take() {
+                        tree.nodes[id].span.end = span.start;
+                    }
+                    if let Some((Some(id), _)) = blocks.pop() {
+                        let node = &mut tree.nodes[id];
+                        if let Some(body) = &mut node.body {
+                            body.end = span.start;
+                        }
+                        node.span.end = span.end;
+                    }
+                }
+            }
+        }
+        if let Some(id) = current {
+            tree.nodes[id].span.end = self.text.len();
+        }
+        tree
+    }
+
+    /// The next lexeme and where it is, comments and whitespace skipped.
+    fn lex(&mut self) -> Option<(Lexeme, Range<usize>)> {
+        loop {
+            let rest = &self.text[self.at..];
+            let c = rest.chars().next()?;
+            let start = self.at;
+            match c {
+                '\n' | ';' => {
+                    self.at += 1;
+                    return Some((Lexeme::End, start..self.at));
+                }
+                '{' => {
+                    self.at += 1;
+                    return Some((Lexeme::Open, start..self.at));
+                }
+                '}' => {
+                    self.at += 1;
+                    return Some((Lexeme::Close, start..self.at));
+                }
+                '=' => {
+                    self.at += 1;
+                    return Some((Lexeme::Equals, start..self.at));
+                }
+                _ if rest.starts_with("//") => {
+                    self.at += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ if rest.starts_with("/*") => self.skip_block_comment(),
+                _ if rest.starts_with("/-") => {
+                    self.at += 2;
+                    return Some((Lexeme::SlashDash, start..self.at));
+                }
+                // A line continuation: the newline after it does not end the node.
+                '\\' => {
+                    self.at += 1;
+                    let line = &self.text[self.at..];
+                    let end = line.find('\n').map_or(line.len(), |n| n + 1);
+                    self.at += end;
+                }
+                // A type annotation, `(u8)`, which says nothing a spec reads.
+                '(' => {
+                    self.at += rest.find(')').map_or(rest.len(), |close| close + 1);
+                }
+                _ if c.is_whitespace() => self.at += c.len_utf8(),
+                '"' => return Some(self.string(start)),
+                '#' | 'r' if raw_hashes(rest).is_some() => {
+                    let prefix = usize::from(c == 'r');
+                    let hashes = raw_hashes(rest).unwrap_or(0);
+                    return Some(self.raw_string(start, prefix, hashes));
+                }
+                _ => return Some(self.bare(start)),
+            }
+        }
+    }
+
+    fn skip_block_comment(&mut self) {
+        let mut depth = 0;
+        while self.at < self.text.len() {
+            let rest = &self.text[self.at..];
+            if rest.starts_with("/*") {
+                depth += 1;
+                self.at += 2;
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                self.at += 2;
+                if depth == 0 {
+                    return;
+                }
+            } else {
+                self.at += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+
+    /// A quoted string, escapes decoded. An unclosed one runs to the end of its line, which
+    /// is where a reader typing it expects it to stop mattering.
+    fn string(&mut self, start: usize) -> (Lexeme, Range<usize>) {
+        let multiline = self.text[start..].starts_with("\"\"\"");
+        self.at = start + if multiline { 3 } else { 1 };
+        let mut text = String::new();
+        let mut chars = self.text[self.at..].char_indices().peekable();
+        let mut end = self.text.len();
+        while let Some((i, c)) = chars.next() {
+            let here = self.at + i;
+            match c {
+                '"' if !multiline => {
+                    end = here + 1;
+                    break;
+                }
+                '"' if self.text[here..].starts_with("\"\"\"") => {
+                    end = here + 3;
+                    break;
+                }
+                '\n' if !multiline => {
+                    end = here;
+                    break;
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => text.push('\n'),
+                    Some((_, 't')) => text.push('\t'),
+                    Some((_, 'r')) => text.push('\r'),
+                    Some((_, 's')) => text.push(' '),
+                    Some((_, c)) if c.is_whitespace() => {
+                        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                    }
+                    Some((_, c)) => text.push(c),
+                    None => {}
+                },
+                c => text.push(c),
+            }
+        }
+        self.at = end;
+        (
+            Lexeme::Word(Token {
+                text,
+                span: start..end,
+            }),
+            start..end,
+        )
+    }
+
+    /// `#"…"#`, or the older `r#"…"#`: no escapes, and as many `#` to close as to open.
+    fn raw_string(&mut self, start: usize, prefix: usize, hashes: usize) -> (Lexeme, Range<usize>) {
+        let open = start + prefix + hashes;
+        let quotes = if self.text[open..].starts_with("\"\"\"") {
+            3
+        } else {
+            1
+        };
+        let body = open + quotes;
+        let close = format!("{}{}", "\"".repeat(quotes), "#".repeat(hashes));
+        let (text, end) = match self.text[body..].find(&close) {
+            Some(at) => (&self.text[body..body + at], body + at + close.len()),
+            None => (&self.text[body..], self.text.len()),
+        };
+        self.at = end;
+        (
+            Lexeme::Word(Token {
+                text: text.to_string(),
+                span: start..end,
+            }),
+            start..end,
+        )
+    }
+
+    /// An identifier, number or keyword: everything up to a character that ends one.
+    fn bare(&mut self, start: usize) -> (Lexeme, Range<usize>) {
+        let rest = &self.text[start..];
+        let len = rest
+            .char_indices()
+            .find(|(i, c)| {
+                c.is_whitespace()
+                    || matches!(c, '{' | '}' | ';' | '=' | '"' | '(' | ')' | '\\')
+                    || (*c == '/' && rest[*i..].starts_with("//"))
+                    || (*c == '/' && rest[*i..].starts_with("/*"))
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        // Never nothing: a character that ends a word and is not a lexeme of its own.
+        let len = len.max(rest.chars().next().map_or(0, char::len_utf8));
+        self.at = start + len;
+        (
+            Lexeme::Word(Token {
+                text: rest[..len].to_string(),
+                span: start..start + len,
+            }),
+            start..start + len,
+        )
+    }
+}
+
+/// The number of `#` in front of a raw string's quote, when `text` starts one.
+fn raw_hashes(text: &str) -> Option<usize> {
+    let (r, text) = match text.strip_prefix('r') {
+        Some(text) => (true, text),
+        None => (false, text),
+    };
+    let hashes = text.len() - text.trim_start_matches('#').len();
+    (text[hashes..].starts_with('"') && (r || hashes > 0)).then_some(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_entries_and_blocks() {
+        let text = "cmd \"run\" help=\"Run it\" {\n  flag \"-f --force\"\n}\nflag #\"--raw\"#\n";
+        let tree = Tree::parse(text);
+        assert_eq!(tree.roots.len(), 2);
+        let cmd = tree.node(tree.roots[0]);
+        assert_eq!(cmd.name.text, "cmd");
+        assert_eq!(cmd.arg(0).unwrap().text, "run");
+        assert_eq!(cmd.prop("help").unwrap().text, "Run it");
+        let flag = tree.node(cmd.children[0]);
+        assert_eq!(flag.arg(0).unwrap().text, "-f --force");
+        assert_eq!(&text[flag.arg(0).unwrap().span.clone()], "\"-f --force\"");
+        assert_eq!(tree.path(cmd.children[0]), ["cmd", "flag"]);
+        assert_eq!(tree.node(tree.roots[1]).arg(0).unwrap().text, "--raw");
+    }
+
+    #[test]
+    fn a_buffer_mid_edit_still_has_a_shape() {
+        let text = "cmd \"run\" {\n  flag \"--unclosed\n  fl";
+        let tree = Tree::parse(text);
+        let cmd = tree.roots[0];
+        assert_eq!(tree.block_at(text.len()), Some(cmd));
+        assert_eq!(tree.node(cmd).children.len(), 2);
+        assert_eq!(
+            tree.hit(text.len()),
+            Some(Hit::Name(tree.node(cmd).children[1]))
+        );
+    }
+
+    #[test]
+    fn comments_and_slashdash() {
+        let text =
+            "/* a {\n } */ cmd \"a\" /-help=\"x\" // c {\n/-cmd \"b\" {\n  flag \"--c\"\n}\n";
+        let tree = Tree::parse(text);
+        assert_eq!(tree.roots.len(), 2);
+        let a = tree.node(tree.roots[0]);
+        assert!(a.entries.len() == 1 && !a.commented);
+        let b = tree.node(tree.roots[1]);
+        assert!(b.commented && tree.node(b.children[0]).commented);
+    }
+
+    #[test]
+    fn what_the_cursor_is_on() {
+        let text = "flag \"--x\" help=\"h\" ";
+        let tree = Tree::parse(text);
+        assert_eq!(tree.hit(2), Some(Hit::Name(0)));
+        assert_eq!(tree.hit(7), Some(Hit::Value(0, 0)));
+        assert_eq!(tree.hit(13), Some(Hit::Key(0, 1)));
+        assert_eq!(tree.hit(text.len()), None);
+        assert_eq!(tree.node_at(text.len()), Some(0));
+    }
+}
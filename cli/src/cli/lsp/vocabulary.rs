@@ -0,0 +1,1180 @@
+//! Every node and property the spec parsers in `usage-lib` accept, with what each one means.
+//!
+//! What hover says and completion offers. A table rather than something read off the parsers,
+//! because the parsers are `match` arms that know a name only when they meet it; the test at
+//! the bottom holds the two together, so a key added to a parser and not here fails the build
+//! rather than going without documentation.
+
+/// A name with nothing below it: a property, or a node that takes values and no block.
+pub struct Word {
+    pub name: &'static str,
+    pub doc: &'static str,
+}
+
+/// A node that can have properties or a block of its own.
+pub struct Node {
+    pub name: &'static str,
+    pub doc: &'static str,
+    /// The reference page under `/spec/reference/`, empty for the top-level page.
+    pub page: &'static str,
+    pub props: &'static [Word],
+    /// The children that hold nothing, which is most of them: the child spelling of a
+    /// property, like `help "…"` inside a flag's block.
+    pub leaves: &'static [Word],
+    pub nodes: &'static [&'static Node],
+}
+
+/// What a name found in a block turned out to be.
+#[derive(Clone, Copy)]
+pub enum Child {
+    Node(&'static Node),
+    Leaf(&'static Word),
+}
+
+impl Child {
+    pub fn name(self) -> &'static str {
+        match self {
+            Child::Node(node) => node.name,
+            Child::Leaf(word) => word.name,
+        }
+    }
+
+    pub fn doc(self) -> &'static str {
+        match self {
+            Child::Node(node) => node.doc,
+            Child::Leaf(word) => word.doc,
+        }
+    }
+}
+
+impl Node {
+    pub fn child(&'static self, name: &str) -> Option<Child> {
+        self.nodes
+            .iter()
+            .find(|node| node.name == name)
+            .map(|node| Child::Node(node))
+            .or_else(|| {
+                self.leaves
+                    .iter()
+                    .find(|word| word.name == name)
+                    .map(Child::Leaf)
+            })
+    }
+
+    pub fn children(&'static self) -> impl Iterator<Item = Child> {
+        self.nodes
+            .iter()
+            .map(|node| Child::Node(node))
+            .chain(self.leaves.iter().map(Child::Leaf))
+    }
+
+    pub fn prop(&self, name: &str) -> Option<&'static Word> {
+        self.props.iter().find(|word| word.name == name)
+    }
+
+    pub fn url(&self) -> String {
+        format!("https://usage.jdx.dev/spec/reference/{}", self.page)
+    }
+}
+
+const fn word(name: &'static str, doc: &'static str) -> Word {
+    Word { name, doc }
+}
+
+// Shared between commands, flags and arguments, which is where most of the repetition is.
+const HELP: Word = word("help", "One-line help, shown in `-h` and in lists.");
+const LONG_HELP: Word = word(
+    "long_help",
+    "Longer help, shown by `--help` in place of `help`.",
+);
+const HELP_LONG: Word = word("help_long", "The same as `long_help`.");
+const HELP_MD: Word = word(
+    "help_md",
+    "Help in Markdown, for the generated docs; the terminal still shows `help`.",
+);
+const HIDE: Word = word(
+    "hide",
+    "Leave it out of help, docs and completions. It still works.",
+);
+const HELP_HEADING: Word = word("help_heading", "The heading it is listed under in help.");
+const DISPLAY_ORDER: Word = word(
+    "display_order",
+    "Where it is listed in help, lower first, ahead of anything without one.",
+);
+const EFFECT: Word = word(
+    "effect",
+    "What running it does: `read`, `write` or `destructive`. Omitted means unknown.",
+);
+const DEPRECATED: Word = word(
+    "deprecated",
+    "Mark it deprecated; a string says what to use instead.",
+);
+const DEPRECATED_WARN_AT: Word = word(
+    "deprecated_warn_at",
+    "The version from which using it prints a deprecation warning.",
+);
+const DEPRECATED_REMOVE_AT: Word = word(
+    "deprecated_remove_at",
+    "The version it is promised to be removed in.",
+);
+const STABILITY: Word = word(
+    "stability",
+    "How settled it is: `experimental`, `beta` or `stable`. A stable one is deprecated before it goes.",
+);
+const SINCE: Word = word(
+    "since",
+    "The version it was added in, shown beside it in help.",
+);
+const REQUIRED: Word = word("required", "It must be given.");
+const REQUIRED_IF: Word = word(
+    "required_if",
+    "Required when any of the named flags is given.",
+);
+const REQUIRED_UNLESS: Word = word(
+    "required_unless",
+    "Required unless any of the named flags is given.",
+);
+const REQUIRED_UNLESS_ALL: Word = word(
+    "required_unless_all",
+    "Required unless every one of the named flags is given.",
+);
+const REQUIRED_IF_EQ: Word = word(
+    "required_if_eq",
+    "Required when a flag or argument has a value: `required_if_eq \"--mode\" \"prod\"`.",
+);
+const REQUIRED_IF_EQ_ALL: Word = word(
+    "required_if_eq_all",
+    "Required when every selector/value pair matches.",
+);
+const REQUIRES: Word = word("requires", "Giving it requires the named flag too.");
+const CONFLICTS: Word = word(
+    "conflicts",
+    "It cannot be given together with the named flag.",
+);
+const VAR: Word = word("var", "It takes any number of values.");
+const VAR_MIN: Word = word("var_min", "The fewest values a variadic one takes.");
+const VAR_MAX: Word = word("var_max", "The most values a variadic one takes.");
+const DEFAULT: Word = word(
+    "default",
+    "The value used when it is not given. As a block, one value per line.",
+);
+const ENV: Word = word("env", "An environment variable that supplies the value.");
+const ENV_FALLBACK: Word = word(
+    "env_fallback",
+    "More variables to read, in order, when `env` is unset.",
+);
+const DEPRECATED_ENV: Word = word(
+    "deprecated_env",
+    "Old variable names still read, with a deprecation warning.",
+);
+const TYPE: Word = word(
+    "type",
+    "The value's type, e.g. `int`, `float`, `bool`, `path`, `duration` or `list<str>`.",
+);
+const DELIMITER: Word = word("delimiter", "Split each value on this string.");
+const ALLOW_NEGATIVE_NUMBERS: Word = word(
+    "allow_negative_numbers",
+    "Accept a value like `-1` rather than reading it as a flag.",
+);
+const VALUE_TERMINATOR: Word = word(
+    "value_terminator",
+    "A word that ends a variadic run of values, e.g. `;`.",
+);
+const HIDE_DEFAULT_VALUE: Word = word("hide_default_value", "Leave the default out of help.");
+const HIDE_ENV: Word = word("hide_env", "Leave the environment variable out of help.");
+const HIDE_ENV_VALUES: Word = word(
+    "hide_env_values",
+    "Leave the environment variable's current value out of help.",
+);
+const HIDE_POSSIBLE_VALUES: Word = word(
+    "hide_possible_values",
+    "Leave the list of choices out of help.",
+);
+const HIDE_SHORT_HELP: Word = word("hide_short_help", "Show it in `--help` only, not in `-h`.");
+const HIDE_LONG_HELP: Word = word("hide_long_help", "Show it in `-h` only, not in `--help`.");
+const SENSITIVE: Word = word(
+    "sensitive",
+    "A secret: show `<redacted>` wherever its value would be printed.",
+);
+
+// Help text and command policy, which a command and the top level both take.
+const BEFORE_HELP: Word = word("before_help", "Text printed above the help.");
+const BEFORE_LONG_HELP: Word = word(
+    "before_long_help",
+    "Text printed above `--help`, in place of `before_help`.",
+);
+const BEFORE_HELP_LONG: Word = word("before_help_long", "The same as `before_long_help`.");
+const BEFORE_HELP_MD: Word = word("before_help_md", "`before_help` in Markdown, for docs.");
+const AFTER_HELP: Word = word("after_help", "Text printed below the help.");
+const AFTER_LONG_HELP: Word = word(
+    "after_long_help",
+    "Text printed below `--help`, in place of `after_help`.",
+);
+const AFTER_HELP_LONG: Word = word("after_help_long", "The same as `after_long_help`.");
+const AFTER_HELP_MD: Word = word("after_help_md", "`after_help` in Markdown, for docs.");
+const SUBCOMMAND_REQUIRED: Word = word(
+    "subcommand_required",
+    "Running it without a subcommand is an error.",
+);
+const SUBCOMMAND_HELP_HEADING: Word = word(
+    "subcommand_help_heading",
+    "The heading subcommands are listed under in help.",
+);
+const SUBCOMMAND_VALUE_NAME: Word = word(
+    "subcommand_value_name",
+    "What the subcommand slot is called in the usage line.",
+);
+const NEXT_LINE_HELP: Word = word(
+    "next_line_help",
+    "Put each flag's help on the line below it.",
+);
+const FLATTEN_HELP: Word = word(
+    "flatten_help",
+    "Show the subcommands' own usage in this command's help.",
+);
+const TERM_WIDTH: Word = word("term_width", "Wrap help at this width.");
+const MAX_TERM_WIDTH: Word = word("max_term_width", "Wrap help at no more than this width.");
+const EXTERNAL_SUBCOMMAND: Word = word(
+    "external_subcommand",
+    "An unknown subcommand is passed through rather than refused.",
+);
+const ARG_REQUIRED_ELSE_HELP: Word = word(
+    "arg_required_else_help",
+    "With no arguments at all, print help instead of running.",
+);
+const DISABLE_HELP_FLAG: Word = word("disable_help_flag", "Declare no `-h`/`--help` flag.");
+const DISABLE_HELP_SUBCOMMAND: Word =
+    word("disable_help_subcommand", "Declare no `help` subcommand.");
+const DISABLE_VERSION_FLAG: Word =
+    word("disable_version_flag", "Declare no `-V`/`--version` flag.");
+const DONT_DELIMIT_TRAILING_VALUES: Word = word(
+    "dont_delimit_trailing_values",
+    "Leave values after `--` unsplit even when their argument has a delimiter.",
+);
+const ARGS_OVERRIDE_SELF: Word = word(
+    "args_override_self",
+    "A scalar flag given twice keeps the last value rather than failing.",
+);
+const SUBCOMMAND_NEGATES_REQS: Word = word(
+    "subcommand_negates_reqs",
+    "Giving a subcommand satisfies this command's required arguments.",
+);
+const ARGS_CONFLICTS_WITH_SUBCOMMANDS: Word = word(
+    "args_conflicts_with_subcommands",
+    "This command's arguments cannot be combined with a subcommand.",
+);
+const SUBCOMMAND_PRECEDENCE_OVER_ARG: Word = word(
+    "subcommand_precedence_over_arg",
+    "A word naming a subcommand ends a variadic argument's values.",
+);
+const ALLOW_MISSING_POSITIONAL: Word = word(
+    "allow_missing_positional",
+    "An optional positional may be skipped ahead of a required one.",
+);
+const UNKNOWN_FLAGS: Word = word(
+    "unknown_flags",
+    "What an undeclared flag is: `value`, offered to the positionals, or `error`.",
+);
+const RESTART_TOKEN: Word = word(
+    "restart_token",
+    "A word that starts a fresh invocation of this command, e.g. `:::`.",
+);
+const USE: Word = word(
+    "use",
+    "Pull in the flags of the named flagsets here, as if they were declared in place.",
+);
+
+static ROOT: Node = Node {
+    name: "",
+    doc: "A usage spec.",
+    page: "",
+    props: &[],
+    leaves: &[
+        word("name", "The CLI's name, as a reader calls it."),
+        word("bin", "The executable, as a shell runs it."),
+        word("version", "The CLI's version."),
+        word(
+            "long_version",
+            "What `--version` prints in place of `version`.",
+        ),
+        word("author", "Who wrote it."),
+        word("license", "Its license."),
+        word("about", "One line about the CLI, shown atop help."),
+        word("long_about", "Longer prose about the CLI, for `--help`."),
+        word("about_long", "The same as `long_about`."),
+        word("about_md", "`about` in Markdown, for docs."),
+        word(
+            "schema",
+            "A named JSON Schema, which outputs name with `type=` and refer to as `#/$defs/<name>`.",
+        ),
+        word(
+            "usage",
+            "The usage line, written out rather than generated.",
+        ),
+        word(
+            "help_template",
+            "A template for the help page, in place of the built-in layout.",
+        ),
+        word(
+            "repository",
+            "Where the CLI's source lives, e.g. `https://github.com/jdx/mise`.",
+        ),
+        word(
+            "source_code_link_template",
+            "A Tera template turning a command into a link to the file that implements it.",
+        ),
+        word(
+            "min_usage_version",
+            "The oldest `usage` this spec is written for; older ones warn.",
+        ),
+        word(
+            "disable_help",
+            "Declare no help flag or subcommand anywhere.",
+        ),
+        word(
+            "default_subcommand",
+            "The subcommand run when no subcommand is named.",
+        ),
+        word(
+            "experimental_env",
+            "The variable that must be `1` to use anything marked `stability=\"experimental\"`.",
+        ),
+        word(
+            "multicall",
+            "Pick the subcommand by the name the program was run as, like busybox.",
+        ),
+        word(
+            "deprecated",
+            "Mark the whole CLI deprecated, saying what to use instead.",
+        ),
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        BEFORE_HELP,
+        BEFORE_LONG_HELP,
+        BEFORE_HELP_LONG,
+        AFTER_HELP,
+        AFTER_LONG_HELP,
+        AFTER_HELP_LONG,
+        SUBCOMMAND_REQUIRED,
+        SUBCOMMAND_HELP_HEADING,
+        SUBCOMMAND_VALUE_NAME,
+        NEXT_LINE_HELP,
+        FLATTEN_HELP,
+        TERM_WIDTH,
+        MAX_TERM_WIDTH,
+        EXTERNAL_SUBCOMMAND,
+        ARG_REQUIRED_ELSE_HELP,
+        DISABLE_HELP_FLAG,
+        DISABLE_HELP_SUBCOMMAND,
+        DISABLE_VERSION_FLAG,
+        DONT_DELIMIT_TRAILING_VALUES,
+        ARGS_OVERRIDE_SELF,
+        SUBCOMMAND_NEGATES_REQS,
+        ARGS_CONFLICTS_WITH_SUBCOMMANDS,
+        SUBCOMMAND_PRECEDENCE_OVER_ARG,
+        ALLOW_MISSING_POSITIONAL,
+        UNKNOWN_FLAGS,
+        USE,
+    ],
+    nodes: &[
+        &CMD, &FLAG, &ARG, &GROUP, &MOUNT, &COMPLETE, &EXAMPLE, &EXIT, &OUTPUT, &FLAGSET, &CONFIG,
+        &LINT, &VIEW, &INCLUDE,
+    ],
+};
+
+static CMD: Node = Node {
+    name: "cmd",
+    doc: "A subcommand. Its block declares its flags, arguments and own subcommands.",
+    page: "cmd",
+    props: &[
+        HELP,
+        LONG_HELP,
+        HELP_LONG,
+        HELP_MD,
+        BEFORE_HELP,
+        BEFORE_LONG_HELP,
+        BEFORE_HELP_LONG,
+        BEFORE_HELP_MD,
+        AFTER_HELP,
+        AFTER_LONG_HELP,
+        AFTER_HELP_LONG,
+        AFTER_HELP_MD,
+        HIDE,
+        HELP_HEADING,
+        DISPLAY_ORDER,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        DEPRECATED,
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        SUBCOMMAND_REQUIRED,
+        SUBCOMMAND_HELP_HEADING,
+        SUBCOMMAND_VALUE_NAME,
+        NEXT_LINE_HELP,
+        FLATTEN_HELP,
+        TERM_WIDTH,
+        MAX_TERM_WIDTH,
+        EXTERNAL_SUBCOMMAND,
+        ARG_REQUIRED_ELSE_HELP,
+        DISABLE_HELP_FLAG,
+        DISABLE_HELP_SUBCOMMAND,
+        DISABLE_VERSION_FLAG,
+        DONT_DELIMIT_TRAILING_VALUES,
+        ARGS_OVERRIDE_SELF,
+        SUBCOMMAND_NEGATES_REQS,
+        ARGS_CONFLICTS_WITH_SUBCOMMANDS,
+        SUBCOMMAND_PRECEDENCE_OVER_ARG,
+        ALLOW_MISSING_POSITIONAL,
+        UNKNOWN_FLAGS,
+        RESTART_TOKEN,
+    ],
+    leaves: &[
+        HELP,
+        LONG_HELP,
+        HELP_MD,
+        BEFORE_HELP,
+        BEFORE_LONG_HELP,
+        BEFORE_HELP_MD,
+        AFTER_HELP,
+        AFTER_LONG_HELP,
+        AFTER_HELP_MD,
+        HIDE,
+        HELP_HEADING,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        DEPRECATED,
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        SUBCOMMAND_REQUIRED,
+        SUBCOMMAND_HELP_HEADING,
+        SUBCOMMAND_VALUE_NAME,
+        NEXT_LINE_HELP,
+        FLATTEN_HELP,
+        TERM_WIDTH,
+        MAX_TERM_WIDTH,
+        EXTERNAL_SUBCOMMAND,
+        ARG_REQUIRED_ELSE_HELP,
+        DISABLE_HELP_FLAG,
+        DISABLE_HELP_SUBCOMMAND,
+        DISABLE_VERSION_FLAG,
+        DONT_DELIMIT_TRAILING_VALUES,
+        ARGS_OVERRIDE_SELF,
+        SUBCOMMAND_NEGATES_REQS,
+        ARGS_CONFLICTS_WITH_SUBCOMMANDS,
+        SUBCOMMAND_PRECEDENCE_OVER_ARG,
+        ALLOW_MISSING_POSITIONAL,
+        RESTART_TOKEN,
+        USE,
+    ],
+    nodes: &[
+        &CMD, &FLAG, &ARG, &GROUP, &MOUNT, &COMPLETE, &EXAMPLE, &EXIT, &OUTPUT, &CMD_ALIAS,
+    ],
+};
+
+static CMD_ALIAS: Node = Node {
+    name: "alias",
+    doc: "Other names the command answers to.",
+    page: "cmd",
+    props: &[word("hide", "Answer to these names without listing them.")],
+    leaves: &[],
+    nodes: &[],
+};
+
+static FLAG: Node = Node {
+    name: "flag",
+    doc: "A flag, by its spellings and value: `flag \"-f --force\"`, `flag \"--out <file>\"`.",
+    page: "flag",
+    props: &[
+        HELP,
+        LONG_HELP,
+        HELP_LONG,
+        HELP_MD,
+        REQUIRED,
+        REQUIRED_IF,
+        REQUIRED_UNLESS,
+        REQUIRED_UNLESS_ALL,
+        VAR,
+        VAR_MIN,
+        VAR_MAX,
+        HIDE,
+        HIDE_DEFAULT_VALUE,
+        HIDE_ENV,
+        HIDE_ENV_VALUES,
+        SENSITIVE,
+        HIDE_POSSIBLE_VALUES,
+        HIDE_SHORT_HELP,
+        HIDE_LONG_HELP,
+        DEPRECATED,
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        GLOBAL,
+        COUNT,
+        ACTION,
+        ALLOW_HYPHEN_VALUES,
+        ALLOW_NEGATIVE_NUMBERS,
+        VALUE_TERMINATOR,
+        DEFAULT,
+        NEGATE,
+        OVERRIDES,
+        CONFLICTS,
+        REQUIRES,
+        EXCLUSIVE,
+        REQUIRE_EQUALS,
+        VALUE_OPTIONAL,
+        BOOL_VALUE,
+        DEFAULT_MISSING,
+        DELIMITER,
+        TYPE,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        ENV,
+        ENV_FALLBACK,
+        DEPRECATED_ENV,
+        HELP_HEADING,
+        DISPLAY_ORDER,
+    ],
+    leaves: &[
+        HELP,
+        LONG_HELP,
+        HELP_LONG,
+        HELP_MD,
+        REQUIRED,
+        REQUIRED_IF,
+        REQUIRED_IF_EQ,
+        REQUIRED_IF_EQ_ALL,
+        REQUIRED_UNLESS,
+        REQUIRED_UNLESS_ALL,
+        VAR,
+        VAR_MIN,
+        VAR_MAX,
+        HIDE,
+        HIDE_DEFAULT_VALUE,
+        HIDE_ENV,
+        HIDE_ENV_VALUES,
+        SENSITIVE,
+        HIDE_POSSIBLE_VALUES,
+        HIDE_SHORT_HELP,
+        HIDE_LONG_HELP,
+        DEPRECATED,
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        GLOBAL,
+        COUNT,
+        ACTION,
+        ALLOW_HYPHEN_VALUES,
+        ALLOW_NEGATIVE_NUMBERS,
+        VALUE_TERMINATOR,
+        DEFAULT,
+        word(
+            "default_if",
+            "A default applied when another flag has a value: `default_if \"--json\" \"true\"`.",
+        ),
+        OVERRIDES,
+        CONFLICTS,
+        REQUIRES,
+        word(
+            "requires_if",
+            "This value requires another flag: `requires_if \"special.toml\" \"--key\"`.",
+        ),
+        EXCLUSIVE,
+        REQUIRE_EQUALS,
+        VALUE_OPTIONAL,
+        BOOL_VALUE,
+        DEFAULT_MISSING,
+        TYPE,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        ENV,
+        ENV_FALLBACK,
+        DEPRECATED_ENV,
+        HELP_HEADING,
+        DISPLAY_ORDER,
+    ],
+    nodes: &[&ARG, &CHOICES, &FLAG_ALIAS],
+};
+
+const GLOBAL: Word = word("global", "Accept it on every subcommand below, too.");
+const COUNT: Word = word("count", "Count how many times it is given: `-vvv` is 3.");
+const ACTION: Word = word(
+    "action",
+    "What giving it does: `set`, or `help`, `help_short`, `help_long`, `help_all` or \
+     `version` to answer with help or the version.",
+);
+const ALLOW_HYPHEN_VALUES: Word = word(
+    "allow_hyphen_values",
+    "Accept a value that starts with `-` rather than reading it as a flag.",
+);
+const NEGATE: Word = word(
+    "negate",
+    "A spelling that turns the switch off, e.g. `--no-color`.",
+);
+const OVERRIDES: Word = word(
+    "overrides",
+    "Giving it cancels the named flag; the later of the two wins.",
+);
+const EXCLUSIVE: Word = word("exclusive", "It must be the only flag or argument given.");
+const REQUIRE_EQUALS: Word = word(
+    "require_equals",
+    "The value must be attached: `--out=file`, not `--out file`.",
+);
+const VALUE_OPTIONAL: Word = word("value_optional", "The flag may be given without its value.");
+const BOOL_VALUE: Word = word(
+    "bool_value",
+    "The switch also takes an attached `=true` or `=false`.",
+);
+const DEFAULT_MISSING: Word = word(
+    "default_missing",
+    "The value used when the flag is given with no value.",
+);
+
+static FLAG_ALIAS: Node = Node {
+    name: "alias",
+    doc: "More spellings of the flag: `alias \"--colour\" \"-C\"`.",
+    page: "flag",
+    props: &[word("hide", "Accept these spellings without listing them.")],
+    leaves: &[],
+    nodes: &[],
+};
+
+static ARG: Node = Node {
+    name: "arg",
+    doc: "A positional argument, e.g. `arg \"<file>\"`, or the value of the flag it is inside.",
+    page: "arg",
+    props: &[
+        HELP,
+        LONG_HELP,
+        HELP_LONG,
+        HELP_MD,
+        REQUIRED,
+        DOUBLE_DASH,
+        VAR,
+        VAR_MIN,
+        VAR_MAX,
+        DELIMITER,
+        ALLOW_NEGATIVE_NUMBERS,
+        VALUE_TERMINATOR,
+        HIDE,
+        HIDE_DEFAULT_VALUE,
+        HIDE_ENV,
+        HIDE_ENV_VALUES,
+        SENSITIVE,
+        HIDE_POSSIBLE_VALUES,
+        HIDE_SHORT_HELP,
+        HIDE_LONG_HELP,
+        CONFLICTS,
+        REQUIRES,
+        REQUIRED_IF,
+        REQUIRED_UNLESS,
+        REQUIRED_UNLESS_ALL,
+        DEFAULT,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        ENV,
+        ENV_FALLBACK,
+        DEPRECATED_ENV,
+        VALIDATE,
+        VALIDATE_ERROR,
+        TYPE,
+        HELP_HEADING,
+        DISPLAY_ORDER,
+    ],
+    leaves: &[
+        HELP,
+        LONG_HELP,
+        HELP_LONG,
+        HELP_MD,
+        REQUIRED,
+        DOUBLE_DASH,
+        VAR,
+        VAR_MIN,
+        VAR_MAX,
+        ALLOW_NEGATIVE_NUMBERS,
+        VALUE_TERMINATOR,
+        HIDE,
+        HIDE_DEFAULT_VALUE,
+        HIDE_ENV,
+        HIDE_ENV_VALUES,
+        SENSITIVE,
+        HIDE_POSSIBLE_VALUES,
+        HIDE_SHORT_HELP,
+        HIDE_LONG_HELP,
+        CONFLICTS,
+        REQUIRES,
+        REQUIRED_IF,
+        REQUIRED_IF_EQ,
+        REQUIRED_IF_EQ_ALL,
+        REQUIRED_UNLESS,
+        REQUIRED_UNLESS_ALL,
+        DEFAULT,
+        EFFECT,
+        STABILITY,
+        SINCE,
+        ENV,
+        ENV_FALLBACK,
+        DEPRECATED_ENV,
+        VALIDATE,
+        VALIDATE_ERROR,
+        TYPE,
+        HELP_HEADING,
+        DISPLAY_ORDER,
+        word(
+            "value_names",
+            "What each value of a fixed-count argument is called in help.",
+        ),
+    ],
+    nodes: &[&CHOICES],
+};
+
+const DOUBLE_DASH: Word = word(
+    "double_dash",
+    "How it relates to `--`: `required`, `optional`, `automatic` or `preserve`.",
+);
+const VALIDATE: Word = word(
+    "validate",
+    "An expression each value must satisfy, e.g. `int(value) >= 1`.",
+);
+const VALIDATE_ERROR: Word = word("validate_error", "What a value failing `validate` is told.");
+
+static CHOICES: Node = Node {
+    name: "choices",
+    doc: "The values it accepts: `choices \"debug\" \"info\"`, or `choice` nodes in a block.",
+    page: "arg",
+    props: &[
+        word(
+            "env",
+            "An environment variable listing the choices, read when the CLI runs.",
+        ),
+        word(
+            "ignore_case",
+            "Match a value to a choice regardless of case.",
+        ),
+        word(
+            "strict",
+            "Refuse a value outside the list; `#false` accepts it and only offers the list.",
+        ),
+    ],
+    leaves: &[],
+    nodes: &[&CHOICE],
+};
+
+static CHOICE: Node = Node {
+    name: "choice",
+    doc: "One accepted value, with its help and other spellings.",
+    page: "arg",
+    props: &[
+        word("help", "What choosing it means, shown beside it."),
+        word("hide", "Accept it without listing it."),
+    ],
+    leaves: &[],
+    nodes: &[&CHOICE_ALIAS],
+};
+
+static CHOICE_ALIAS: Node = Node {
+    name: "alias",
+    doc: "Another spelling that means this choice.",
+    page: "arg",
+    props: &[word("hide", "Accept the spelling without listing it.")],
+    leaves: &[],
+    nodes: &[],
+};
+
+static GROUP: Node = Node {
+    name: "group",
+    doc: "A named set of flags that are offered as alternatives: `group \"input\" \"--file\" \"--url\"`.",
+    page: "group",
+    props: &[REQUIRED_IN_GROUP, MULTIPLE],
+    leaves: &[
+        REQUIRED_IN_GROUP,
+        MULTIPLE,
+        word("flag", "Members of the group, by spelling."),
+    ],
+    nodes: &[],
+};
+
+const REQUIRED_IN_GROUP: Word = word("required", "One of the members must be given.");
+const MULTIPLE: Word = word("multiple", "More than one member may be given.");
+
+static MOUNT: Node = Node {
+    name: "mount",
+    doc: "Commands read at run time from the spec a program prints.",
+    page: "cmd",
+    props: &[MOUNT_RUN, OVERRIDES_DEFAULT],
+    leaves: &[MOUNT_RUN, OVERRIDES_DEFAULT],
+    nodes: &[],
+};
+
+const MOUNT_RUN: Word = word("run", "The command whose output is the mounted spec.");
+const OVERRIDES_DEFAULT: Word = word(
+    "overrides_default",
+    "A mounted command wins over `default_subcommand`.",
+);
+
+static COMPLETE: Node = Node {
+    name: "complete",
+    doc: "Where completions for an argument named by it come from.",
+    page: "complete",
+    props: &[
+        word(
+            "run",
+            "A shell command printing one completion per line. Not with `type`.",
+        ),
+        word(
+            "descriptions",
+            "`run` prints `value:description` lines rather than bare values.",
+        ),
+        word(
+            "type",
+            "A kind of value usage completes itself, e.g. `config_key`. Not with `run`.",
+        ),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static EXAMPLE: Node = Node {
+    name: "example",
+    doc: "An example invocation, shown in help and docs and checked by `usage lint`.",
+    page: "",
+    props: &[
+        word("header", "A title for the example."),
+        word("help", "What the example shows."),
+        word("lang", "The language it is written in; `sh` when omitted."),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static EXIT: Node = Node {
+    name: "exit",
+    doc: "What an exit status means. Inherited by subcommands, which may redefine it.",
+    page: "cmd",
+    props: &[
+        word(
+            "help",
+            "What the status means; the second argument says the same.",
+        ),
+        word(
+            "name",
+            "What SDKs name the status's error after, instead of its help.",
+        ),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static OUTPUT: Node = Node {
+    name: "output",
+    doc: "What the command prints, as `json`, `jsonl` or `text`. SDKs generate a typed method for each JSON one.",
+    page: "cmd",
+    props: &[
+        word(
+            "flag",
+            "The flag that switches to this output, like `--json` or `--format json`.",
+        ),
+        word("help", "What the output holds."),
+        word("schema", "A JSON Schema for the output, inline; for `jsonl`, of one line."),
+        word("type", "The top-level `schema` the output conforms to."),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static FLAGSET: Node = Node {
+    name: "flagset",
+    doc: "A named set of flags that commands pull in with `use`.",
+    page: "flagset",
+    props: &[],
+    leaves: &[USE],
+    nodes: &[&FLAG],
+};
+
+static INCLUDE: Node = Node {
+    name: "include",
+    doc: "Read another spec file into this one.",
+    page: "",
+    props: &[word(
+        "file",
+        "The file to read, relative to the one including it.",
+    )],
+    leaves: &[],
+    nodes: &[],
+};
+
+static VIEW: Node = Node {
+    name: "view",
+    doc: "Another executable that runs a command of this spec as its root.",
+    page: "",
+    props: &[
+        word("name", "The view's name, as a reader calls it."),
+        word("bin", "The view's executable."),
+        word(
+            "root",
+            "The command it promotes, as a space-separated path.",
+        ),
+        word(
+            "globals",
+            "Carry every global flag of the root into the view.",
+        ),
+    ],
+    leaves: &[word(
+        "global",
+        "Root global flags the view carries, by spelling.",
+    )],
+    nodes: &[],
+};
+
+/// Its children are rule names, which [`super::rules`] supplies.
+static LINT: Node = Node {
+    name: "lint",
+    doc: "The level each `usage lint` rule reports at: `error`, `warning`, `info` or `off`.",
+    page: "",
+    props: &[],
+    leaves: &[],
+    nodes: &[],
+};
+
+static CONFIG: Node = Node {
+    name: "config",
+    doc: "The CLI's settings: where they are read from and what each one is.",
+    page: "config",
+    props: &[],
+    leaves: &[],
+    nodes: &[&CONFIG_PROP, &CONFIG_SOURCE, &CONFIG_FILE, &CONFIG_PROFILES],
+};
+
+static CONFIG_SOURCE: Node = Node {
+    name: "source",
+    doc: "A kind of place settings come from that usage does not know, e.g. `git`.",
+    page: "config",
+    props: &[
+        word("name", "What the kind is called in docs."),
+        word("doc_hint", "How a reader finds a setting's value there."),
+        word("set_hint", "How a reader sets a value there."),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static CONFIG_FILE: Node = Node {
+    name: "file",
+    doc: "A config file settings are read from.",
+    page: "config",
+    props: &[
+        word(
+            "findup",
+            "Look for it in the working directory and every one above.",
+        ),
+        word(
+            "scope",
+            "Who supplies the file: `project`, `global` or `system`.",
+        ),
+        word("format", "Its format, when the extension does not say."),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static CONFIG_PROFILES: Node = Node {
+    name: "profiles",
+    doc: "Named profiles: `[profiles.<name>]` tables and sibling files overlaid on each file.",
+    page: "config",
+    props: &[
+        word("env", "The environment variable that names the profile."),
+        word(
+            "flag",
+            "The flag that names it, which wins over the variable.",
+        ),
+    ],
+    leaves: &[],
+    nodes: &[],
+};
+
+static CONFIG_PROP: Node = Node {
+    name: "prop",
+    doc: "A setting, by its key.",
+    page: "config",
+    props: &[
+        word("default", "The value when nothing sets it."),
+        word(
+            "default_note",
+            "Prose describing a default that is computed.",
+        ),
+        word("optional", "It may be unset, with no default."),
+        word("data_type", "The old spelling of `type`."),
+        word(
+            "type",
+            "The value's type, e.g. `bool`, `int`, `path` or `list<str>`.",
+        ),
+        word("env", "The environment variable that sets it."),
+        HELP,
+        LONG_HELP,
+        HELP_HEADING,
+        word(
+            "merge",
+            "How values from several sources combine: `replace`, `union` or `deep`.",
+        ),
+        word(
+            "scope",
+            "Where it may be read from: `any`, `global` (no project file) or `env`.",
+        ),
+        word(
+            "deprecated",
+            "Mark it deprecated, saying what to use instead.",
+        ),
+        DEPRECATED_WARN_AT,
+        DEPRECATED_REMOVE_AT,
+        word("renamed_to", "The key it was renamed to."),
+        HIDE,
+        word("since", "The version it was added in."),
+        word(
+            "parse",
+            "A named parser turning one string into the type, e.g. `list_by_comma`.",
+        ),
+        word(
+            "interpolate",
+            "Expand `${env:NAME}`, `${config:key}` and `~`; read relative paths from the file.",
+        ),
+        SENSITIVE,
+        word(
+            "writes_to",
+            "Where `config set` writes it, when not the usual file.",
+        ),
+    ],
+    leaves: &[
+        word("env", "Environment variables that set it, in order."),
+        word(
+            "deprecated_env",
+            "Old variable names still read, with a warning.",
+        ),
+        word("alias", "Old keys still read."),
+        word("cli", "Flags that set it, by spelling."),
+        word("example", "Example values."),
+        LONG_HELP,
+        word("default", "A list default, one value per argument."),
+        word(
+            "source",
+            "Its keys in a `source` kind: `source \"git\" \"hk.jobs\"`.",
+        ),
+        word(
+            "x",
+            "Tool-private metadata, kept and written back as it was.",
+        ),
+    ],
+    nodes: &[&CONFIG_CHOICES],
+};
+
+static CONFIG_CHOICES: Node = Node {
+    name: "choices",
+    doc: "The values it accepts, one `choice` node each.",
+    page: "config",
+    props: &[],
+    leaves: &[],
+    nodes: &[&CONFIG_CHOICE],
+};
+
+static CONFIG_CHOICE: Node = Node {
+    name: "choice",
+    doc: "One accepted value.",
+    page: "config",
+    props: &[word("help", "What choosing it means.")],
+    leaves: &[],
+    nodes: &[],
+};
+
+/// The vocabulary of a spec's top level.
+pub fn root() -> &'static Node {
+    &ROOT
+}
+
+/// The lint node, whose children are not in the table.
+pub fn is_lint(node: &Node) -> bool {
+    std::ptr::eq(node, &LINT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// The names each parser file matches on, read from its `match` arms.
+    fn keys(source: &str) -> BTreeSet<String> {
+        let source = source.split("#[cfg(test)]").next().unwrap_or(source);
+        let arm = regex::Regex::new(r#"^\s*"([a-z_]+)"((?:\s*\|\s*"[a-z_]+")*)\s*=>"#).unwrap();
+        let name = regex::Regex::new(r#""([a-z_]+)""#).unwrap();
+        source
+            .lines()
+            .filter_map(|line| arm.captures(line))
+            .flat_map(|captures| {
+                let rest = captures.get(2).map_or("", |m| m.as_str());
+                std::iter::once(captures[1].to_string())
+                    .chain(name.captures_iter(rest).map(|c| c[1].to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn every_name(
+        node: &'static Node,
+        walked: &mut Vec<*const Node>,
+        seen: &mut BTreeSet<&'static str>,
+    ) {
+        // `cmd` holds itself.
+        if walked.contains(&(node as *const Node)) {
+            return;
+        }
+        walked.push(node);
+        seen.insert(node.name);
+        seen.extend(node.props.iter().map(|word| word.name));
+        seen.extend(node.leaves.iter().map(|word| word.name));
+        for child in node.nodes {
+            every_name(child, walked, seen);
+        }
+    }
+
+    #[test]
+    fn every_key_a_parser_accepts_is_documented() {
+        let mut documented = BTreeSet::new();
+        every_name(root(), &mut vec![], &mut documented);
+        let parsers = [
+            include_str!("../../../../lib/src/spec/mod.rs"),
+            include_str!("../../../../lib/src/spec/cmd.rs"),
+            include_str!("../../../../lib/src/spec/flag.rs"),
+            include_str!("../../../../lib/src/spec/arg.rs"),
+            include_str!("../../../../lib/src/spec/choices.rs"),
+            include_str!("../../../../lib/src/spec/complete.rs"),
+            include_str!("../../../../lib/src/spec/config.rs"),
+            include_str!("../../../../lib/src/spec/flagset.rs"),
+            include_str!("../../../../lib/src/spec/group.rs"),
+            include_str!("../../../../lib/src/spec/lint.rs"),
+            include_str!("../../../../lib/src/spec/mount.rs"),
+            include_str!("../../../../lib/src/spec/view.rs"),
+        ];
+        // Matched on as values rather than keys: the spellings of a flag's `action`.
+        let values = ["set", "help_short", "help_all"];
+        let missing: Vec<String> = parsers
+            .iter()
+            .flat_map(|source| keys(source))
+            .filter(|key| !documented.contains(key.as_str()) && !values.contains(&key.as_str()))
+            .collect();
+        assert!(missing.is_empty(), "undocumented keys: {missing:?}");
+    }
+
+    #[test]
+    fn a_flag_inside_a_command_is_found_by_name() {
+        let Some(Child::Node(cmd)) = root().child("cmd") else {
+            panic!("cmd is a node");
+        };
+        let Some(Child::Node(flag)) = cmd.child("flag") else {
+            panic!("flag is a node");
+        };
+        assert!(flag.prop("negate").is_some());
+        assert!(flag.prop("nope").is_none());
+        assert!(matches!(flag.child("help"), Some(Child::Leaf(_))));
+    }
+}
@@ -12,9 +12,17 @@
 //! Built on `rmcp`, the same as `mise mcp` and `fnox mcp`, so the three behave
 //! alike and inherit protocol details — version negotiation, pagination,
 //! cancellation, schema generation — rather than each reimplementing a subset.
+//!
+//! Running commands is opt-in. Without `--max-effect` the server only describes,
+//! and `run_command` is not offered at all; with it, an invocation is parsed
+//! against the spec first and its effect — the command's, raised by the flags
+//! and arguments the call supplied — has to be at or under the ceiling before
+//! anything is spawned.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use miette::{bail, IntoDiagnostic, Result};
 use rmcp::{
@@ -25,8 +33,13 @@ use rmcp::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use usage::{Spec, SpecArg, SpecCommand, SpecFlag};
+use usage::parse::Parser;
+use usage::spec::effect::SpecCommandEffect;
+use usage::spec::REDACTED;
+use usage::{Spec, SpecArg, SpecCommand, SpecFlag, SpecOutputFormat};
 
+use crate::cli::bounded;
+use crate::cli::explain::explain;
 use crate::cli::generate;
 
 /// What the client is told before it sees any command. The three effect values
@@ -54,6 +67,15 @@ pub struct Mcp {
     /// Raw string spec input
     #[usage(short, long, required_unless = "--file", overrides = "--file")]
     spec: Option<String>,
+
+    /// Offer a `run_command` tool, for invocations whose effect is at most this
+    ///
+    /// An invocation with no declared effect is unknown rather than safe, so
+    /// only `destructive` — which allows anything — lets one run. A value set by
+    /// this server's environment counts, because the command runs in it. A run is
+    /// stopped after a minute, and each of its streams is cut at 1 MiB.
+    #[usage(long, value_parser = ["read", "write", "destructive"])]
+    max_effect: Option<SpecCommandEffect>,
 }
 
 impl usage_rs::Run for Mcp {
@@ -74,7 +96,7 @@ impl usage_rs::Run for Mcp {
             .build()
             .into_diagnostic()?
             .block_on(async move {
-                let service = SpecServer::new(spec)
+                let service = SpecServer::new(spec, self.max_effect)
                     .serve(rmcp::transport::io::stdio())
                     .await
                     .into_diagnostic()?;
@@ -97,18 +119,57 @@ pub struct DescribeCommandParams {
     pub command: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ExplainInvocationParams {
+    /// The command line, one word per element, starting with the binary, e.g.
+    /// ["mise", "install", "-f", "node@20"]. A line that starts at a
+    /// subcommand has the binary put in front.
+    pub argv: Vec<String>,
+    /// Environment to explain against. Given at all, it is the whole
+    /// environment; omitted, the server's own is used.
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RunCommandParams {
+    /// Command path without the binary, e.g. "logs" or "daemons remove". Empty
+    /// runs the binary itself.
+    #[serde(default)]
+    pub command: String,
+    /// Flags by the `name` describe_command reports. `true` for a switch, a
+    /// string or number for a flag that takes a value, a count for a counting
+    /// flag, a list to repeat one. `false` and `null` leave the flag out.
+    #[serde(default)]
+    pub flags: BTreeMap<String, Value>,
+    /// Positional argument values, in order.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
 #[derive(Clone)]
 struct SpecServer {
     spec: Arc<Spec>,
+    /// The highest effect `run_command` will spawn. `None` means the tool is
+    /// not offered.
+    max_effect: Option<SpecCommandEffect>,
     tool_router: ToolRouter<Self>,
 }
 
 #[tool_router]
 impl SpecServer {
-    fn new(spec: Spec) -> Self {
+    fn new(spec: Spec, max_effect: Option<SpecCommandEffect>) -> Self {
+        let mut tool_router = Self::tool_router();
+        // Removed rather than answering with a refusal: a tool an agent can see is
+        // one it will try, and a server started only to describe should not look
+        // like one that runs things.
+        if max_effect.is_none() {
+            tool_router.remove_route("run_command");
+        }
         Self {
             spec: Arc::new(spec),
-            tool_router: Self::tool_router(),
+            max_effect,
+            tool_router,
         }
     }
 
@@ -124,7 +185,7 @@ impl SpecServer {
     }
 
     #[tool(
-        description = "Full detail for one command: help, flags, arguments, and the effect of each. Use before running an unfamiliar command."
+        description = "Full detail for one command: help, flags, arguments, the effect of each, and what its exit codes mean. Use before running an unfamiliar command."
     )]
     async fn describe_command(
         &self,
@@ -143,6 +204,275 @@ impl SpecServer {
             ))])),
         }
     }
+
+    #[tool(
+        description = "Check a command line without running it: what each word binds to, where every value came from, what failed to parse, and the effect of this exact invocation."
+    )]
+    async fn explain_invocation(
+        &self,
+        Parameters(ExplainInvocationParams { argv, env }): Parameters<ExplainInvocationParams>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let argv = with_program_name(&self.spec, argv);
+        let explanation = explain(&self.spec, &argv, env.map(|env| env.into_iter().collect()));
+        // The same JSON `usage explain --format json` prints, plus the one answer an
+        // agent wants before anything else and would otherwise derive from two fields.
+        let mut out = serde_json::to_value(&explanation)
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        out["valid"] = json!(explanation.errors.is_empty() && explanation.refused.is_none());
+        Ok(json_result(out))
+    }
+
+    #[tool(
+        description = "Run one command and return its stdout, stderr and exit code. The invocation is checked against the spec first, and refused if its effect is above what this server was started to allow."
+    )]
+    async fn run_command(
+        &self,
+        Parameters(params): Parameters<RunCommandParams>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let refused = |msg: String| Ok(CallToolResult::error(vec![ContentBlock::text(msg)]));
+        // Unreachable through the router, which drops the tool without a ceiling,
+        // but the method is callable on its own and must not be the one way in.
+        let Some(max_effect) = self.max_effect else {
+            return refused("running commands is disabled on this server".to_string());
+        };
+        let chain = if params
+            .command
+            .split_whitespace()
+            .all(|s| s == self.spec.bin)
+        {
+            vec![&self.spec.cmd]
+        } else {
+            match find_chain(&self.spec, &params.command) {
+                Some(chain) => chain,
+                None => {
+                    return refused(format!(
+                        "no such command: {:?}. Call list_commands to see what exists.",
+                        params.command
+                    ))
+                }
+            }
+        };
+        let argv = match invocation(&self.spec, &chain, &params.flags, &params.args) {
+            Ok(argv) => argv,
+            Err(msg) => return refused(msg),
+        };
+        // What the answer quotes: the same line with every `sensitive` value masked, because
+        // a tool result lands in a transcript that outlives the call.
+        let shown = redacted_invocation(&self.spec, &chain, &params.flags, &params.args)
+            .unwrap_or_else(|_| argv.clone());
+        // Parsed by the spec before anything runs. The effect is only as good as
+        // the parse it comes from, and a line the CLI would reject is better
+        // refused here, with the spec's reason, than half-run there.
+        let parsed = match Parser::new(&self.spec).parse(&argv) {
+            Ok(parsed) => parsed,
+            Err(err) => return refused(format!("`{}` does not parse: {err}", shown.join(" "))),
+        };
+        // The child inherits this server's environment, so what the environment sets counts
+        // here even though `explain_invocation` leaves it out.
+        let effect = parsed.effect_in_environment();
+        if let Err(msg) = allowed(effect, max_effect) {
+            return refused(format!("refused `{}`: {msg}", shown.join(" ")));
+        }
+
+        let child = argv.clone();
+        let ran =
+            tokio::task::spawn_blocking(move || bounded::run(&child, RUN_TIMEOUT, OUTPUT_LIMIT))
+                .await
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        match ran {
+            Ok(ran) => Ok(json_result(json!({
+                "argv": shown,
+                "effect": effect.map(|e| e.as_str()),
+                "exit_code": ran.status.and_then(|s| s.code()),
+                "success": ran.status.is_some_and(|s| s.success()),
+                "timed_out": ran.status.is_none(),
+                "truncated": ran.stdout.truncated || ran.stderr.truncated,
+                "stdout": String::from_utf8_lossy(&ran.stdout.bytes),
+                "stderr": String::from_utf8_lossy(&ran.stderr.bytes),
+            }))),
+            Err(err) => refused(format!("could not run `{}`: {err}", argv[0])),
+        }
+    }
+}
+
+/// How long `run_command` lets a child run before killing it.
+const RUN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How much of each of a child's streams `run_command` returns.
+const OUTPUT_LIMIT: usize = 1024 * 1024;
+
+/// The most times `run_command` repeats a `count` flag. More than any verbosity asks for, and
+/// few enough that the number cannot be used to build an argv the system refuses to exec.
+const MAX_COUNT: u64 = 64;
+
+/// `argv` with the binary in front, if the caller started at a subcommand.
+///
+/// Only added when the first word names a subcommand, the same leniency
+/// `find_chain` extends the other way. Anything else is left as given: the first
+/// word may be a multicall applet's name, which is not the bin and must not
+/// become an argument to it.
+fn with_program_name(spec: &Spec, mut argv: Vec<String>) -> Vec<String> {
+    let starts_at_a_subcommand = argv
+        .first()
+        .is_some_and(|w| *w != spec.bin && spec.cmd.find_subcommand(w).is_some());
+    if argv.is_empty() || starts_at_a_subcommand {
+        argv.insert(0, spec.bin.clone());
+    }
+    argv
+}
+
+/// Whether an invocation of `effect` may run under `max_effect`.
+///
+/// An unknown effect is not `read`: the spec said nothing, and nothing here can
+/// ask the user. Only a ceiling of `destructive`, which allows anything at all,
+/// lets it through.
+fn allowed(
+    effect: Option<SpecCommandEffect>,
+    max_effect: SpecCommandEffect,
+) -> std::result::Result<(), String> {
+    match effect {
+        Some(effect) if effect <= max_effect => Ok(()),
+        None if max_effect == SpecCommandEffect::Destructive => Ok(()),
+        Some(effect) => Err(format!(
+            "its effect is {effect}, above this server's limit of {max_effect}"
+        )),
+        None => Err(format!(
+            "it declares no effect, and an unknown effect is only run under a limit of destructive, not {max_effect}"
+        )),
+    }
+}
+
+/// The command line a `run_command` call describes, binary first.
+///
+/// Flags are spelled by their first long form, attached as `--name=value` so a
+/// value starting with a dash stays a value. Positionals go last, behind `--`
+/// when one of them would otherwise read as a flag or the command requires it.
+fn invocation(
+    spec: &Spec,
+    chain: &[&SpecCommand],
+    flags: &BTreeMap<String, Value>,
+    args: &[String],
+) -> std::result::Result<Vec<String>, String> {
+    let cmd = chain.last().expect("chain is never empty");
+    let mut argv = vec![spec.bin.clone()];
+    argv.extend(chain[1..].iter().map(|c| c.name.clone()));
+
+    let available = usage::available_flags(chain);
+    for (name, value) in flags {
+        let flag = available.iter().find(|f| &f.name == name).ok_or_else(|| {
+            format!("no flag {name:?} on this command. Call describe_command to see its flags.")
+        })?;
+        let values = match value {
+            Value::Array(values) => values.clone(),
+            value => vec![value.clone()],
+        };
+        for value in values {
+            push_flag(&mut argv, flag, &value)?;
+        }
+    }
+
+    if !args.is_empty() {
+        let required = cmd
+            .args
+            .iter()
+            .any(|a| a.double_dash == usage::spec::arg::SpecDoubleDashChoices::Required);
+        if required || args.iter().any(|a| a.starts_with('-')) {
+            argv.push("--".to_string());
+        }
+        argv.extend(args.iter().cloned());
+    }
+    Ok(argv)
+}
+
+/// [`invocation`], with the value of each `sensitive` flag and argument masked.
+fn redacted_invocation(
+    spec: &Spec,
+    chain: &[&SpecCommand],
+    flags: &BTreeMap<String, Value>,
+    args: &[String],
+) -> std::result::Result<Vec<String>, String> {
+    let available = usage::available_flags(chain);
+    let secret = |name: &String| {
+        available
+            .iter()
+            .any(|flag| &flag.name == name && flag.sensitive && flag.arg.is_some())
+    };
+    let masked = |value: &Value| match value {
+        Value::Array(values) => Value::Array(vec![json!(REDACTED); values.len()]),
+        _ => json!(REDACTED),
+    };
+    let flags = flags
+        .iter()
+        .map(|(name, value)| match secret(name) {
+            true => (name.clone(), masked(value)),
+            false => (name.clone(), value.clone()),
+        })
+        .collect();
+    // Positionals by position, a variadic last one taking every word left.
+    let declared = &chain.last().expect("chain is never empty").args;
+    let args = args
+        .iter()
+        .enumerate()
+        .map(|(at, arg)| {
+            let declaration = declared
+                .get(at)
+                .or_else(|| declared.last().filter(|arg| arg.var));
+            match declaration.is_some_and(|arg| arg.sensitive) {
+                true => REDACTED.to_string(),
+                false => arg.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+    invocation(spec, chain, &flags, &args)
+}
+
+fn push_flag(
+    argv: &mut Vec<String>,
+    flag: &SpecFlag,
+    value: &Value,
+) -> std::result::Result<(), String> {
+    let (long, short) = (flag.long.first(), flag.short.first());
+    let bare = match (long, short) {
+        (Some(long), _) => format!("--{long}"),
+        (None, Some(short)) => format!("-{short}"),
+        (None, None) => return Err(format!("flag {:?} has no spelling", flag.name)),
+    };
+    let text = match value {
+        Value::Null | Value::Bool(false) => return Ok(()),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(true) if flag.arg.is_none() => {
+            argv.push(bare);
+            return Ok(());
+        }
+        _ => {
+            return Err(format!(
+                "flag {:?} was given {value}, which is no value",
+                flag.name
+            ))
+        }
+    };
+    if flag.arg.is_none() {
+        return match value.as_u64() {
+            Some(times) if flag.count && times <= MAX_COUNT => {
+                argv.extend(std::iter::repeat_n(bare, times as usize));
+                Ok(())
+            }
+            Some(times) if flag.count => Err(format!(
+                "flag {:?} was given {times} times; the most this server passes is {MAX_COUNT}",
+                flag.name
+            )),
+            _ => Err(format!(
+                "flag {:?} takes no value; pass true to set it",
+                flag.name
+            )),
+        };
+    }
+    match long {
+        Some(_) => argv.push(format!("{bare}={text}")),
+        None => argv.extend([bare, text]),
+    }
+    Ok(())
 }
 
 // `router = self.tool_router` uses the router built once in `new`. The macro's
@@ -247,6 +577,32 @@ fn describe(spec: &Spec, chain: &[&SpecCommand]) -> Value {
         "args": cmd.args.iter().map(describe_arg).collect::<Vec<_>>(),
         "flags": flags_for(chain),
         "subcommands": cmd.subcommands.keys().collect::<Vec<_>>(),
+        // Inherited ones included: an agent reading a code back from `run_command`
+        // should not have to describe every ancestor to learn what it meant.
+        "exits": spec
+            .exits_for(cmd)
+            .iter()
+            .map(|exit| json!({ "code": exit.code, "help": exit.help, "name": exit.name }))
+            .collect::<Vec<_>>(),
+        "outputs": cmd
+            .outputs
+            .iter()
+            .map(|output| {
+                json!({
+                    "format": output.format.to_string(),
+                    "flag": output.flag,
+                    "help": output.help,
+                    "schema": spec.output_schema(output),
+                })
+            })
+            .collect::<Vec<_>>(),
+        // MCP's name for what a tool returns. The command's JSON document, with the flag
+        // that asks for it in `outputs`; `jsonl` has no one document to describe.
+        "outputSchema": cmd
+            .outputs
+            .iter()
+            .find(|output| output.format == SpecOutputFormat::Json)
+            .and_then(|output| spec.output_schema(output)),
     })
 }
 
@@ -257,7 +613,12 @@ fn describe_arg(arg: &SpecArg) -> Value {
         "variadic": arg.var,
         "help": arg.help,
         "effect": arg.effect.map(|e| e.as_str()),
+        "sensitive": arg.sensitive,
         "choices": arg.choices.as_ref().map(|c| c.choices.clone()),
+        // The spec's spelling and a JSON Schema for one value: the first is what a
+        // reader searches the docs for, the second what a client can validate with.
+        "type": arg.value_type.as_ref().map(ToString::to_string),
+        "schema": arg.value_type.as_ref().map(crate::schema::base_schema),
     })
 }
 
@@ -269,6 +630,7 @@ fn describe_flag(flag: &SpecFlag) -> Value {
         "help": flag.help,
         "effect": flag.effect.map(|e| e.as_str()),
         "hidden": flag.hide,
+        "sensitive": flag.sensitive,
         // Accepted by every subcommand, so it may appear anywhere in the line.
         // There is no separate "inherited" flag on the output: a subcommand
         // that re-declares a global is describing that same global, not a
@@ -379,6 +741,24 @@ cmd "start" help="Runs a daemon"
         assert!(tail["effect"].is_null());
     }
 
+    #[test]
+    fn a_declared_type_is_described_with_a_schema_for_its_values() {
+        let spec: Spec = r#"
+bin "srv"
+cmd "serve" {
+    flag "--port <port>" type="uint"
+    arg "[root]"
+}
+"#
+        .parse()
+        .unwrap();
+        let out = described(&spec, "serve");
+        let port = &flag(&out, "port")["arg"];
+        assert_eq!(port["type"], "uint");
+        assert_eq!(port["schema"], json!({"type": "integer", "minimum": 0}));
+        assert!(out["args"][0]["type"].is_null());
+    }
+
     #[test]
     fn nested_paths_and_aliases_resolve() {
         let spec = spec();
@@ -476,6 +856,56 @@ cmd "start" help="Runs a daemon"
         assert_eq!(described(&spec, "usage")["help"], "self");
     }
 
+    #[test]
+    fn exit_codes_are_described_with_the_ones_inherited() {
+        let spec: Spec = r#"
+bin "tool"
+exit 1 "something failed"
+cmd "install" {
+    exit 3 "lockfile out of date" name="stale"
+}
+"#
+        .parse()
+        .unwrap();
+        let out = described(&spec, "install");
+        assert_eq!(
+            out["exits"],
+            json!([
+                { "code": 1, "help": "something failed", "name": null },
+                { "code": 3, "help": "lockfile out of date", "name": "stale" },
+            ])
+        );
+    }
+
+    #[test]
+    fn a_json_output_is_advertised_as_the_output_schema() {
+        let spec: Spec = r###"
+bin "tool"
+schema "Package" #"{"type": "object"}"#
+cmd "list" {
+    flag "--json"
+    output "text"
+    output "json" flag="--json" schema=##"{"type": "array", "items": {"$ref": "#/$defs/Package"}}"##
+}
+"###
+        .parse()
+        .unwrap();
+        let out = described(&spec, "list");
+        assert_eq!(
+            out["outputSchema"],
+            json!({
+                "type": "array",
+                "items": { "$ref": "#/$defs/Package" },
+                "$defs": { "Package": { "type": "object" } },
+            })
+        );
+        assert_eq!(
+            out["outputs"][0],
+            json!({ "format": "text", "flag": null, "help": null, "schema": null })
+        );
+        assert_eq!(out["outputs"][1]["flag"], "--json");
+    }
+
     #[test]
     fn a_hidden_command_can_still_be_described() {
         // `list_commands` omits it, but an agent that names one already knows
@@ -497,7 +927,7 @@ cmd "start" help="Runs a daemon"
 
     #[tokio::test]
     async fn tools_are_registered_with_schemas() {
-        let server = SpecServer::new(spec());
+        let server = SpecServer::new(spec(), None);
         let names: Vec<_> = server
             .tool_router
             .list_all()
@@ -506,11 +936,15 @@ cmd "start" help="Runs a daemon"
             .collect();
         assert!(names.contains(&"list_commands".to_string()), "{names:?}");
         assert!(names.contains(&"describe_command".to_string()), "{names:?}");
+        assert!(
+            names.contains(&"explain_invocation".to_string()),
+            "{names:?}"
+        );
     }
 
     #[tokio::test]
     async fn describing_a_missing_command_is_a_tool_error() {
-        let server = SpecServer::new(spec());
+        let server = SpecServer::new(spec(), None);
         let res = server
             .describe_command(Parameters(DescribeCommandParams {
                 command: "nope".into(),
@@ -520,9 +954,244 @@ cmd "start" help="Runs a daemon"
         assert_eq!(res.is_error, Some(true));
     }
 
+    #[test]
+    fn run_command_is_offered_only_with_a_ceiling() {
+        let names = |max_effect| {
+            SpecServer::new(spec(), max_effect)
+                .tool_router
+                .list_all()
+                .into_iter()
+                .map(|t| t.name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert!(!names(None).contains(&"run_command".to_string()));
+        assert!(names(Some(SpecCommandEffect::Read)).contains(&"run_command".to_string()));
+    }
+
+    fn argv_for(
+        path: &str,
+        flags: Value,
+        args: &[&str],
+    ) -> std::result::Result<Vec<String>, String> {
+        let spec = spec();
+        let flags: BTreeMap<String, Value> = serde_json::from_value(flags).unwrap();
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        invocation(&spec, &find_chain(&spec, path).unwrap(), &flags, &args)
+    }
+
+    #[test]
+    fn an_invocation_is_spelled_so_the_spec_parses_it_back() {
+        let argv = argv_for(
+            "l",
+            json!({"clear": true, "tail": false, "verbose": true, "yes": null}),
+            &["-odd"],
+        )
+        .unwrap();
+        // The canonical name rather than the alias the caller used, a long form for
+        // every flag, and a `--` keeping a dashed positional from reading as a flag.
+        assert_eq!(
+            argv,
+            ["pitchfork", "logs", "--clear", "--verbose", "--", "-odd"]
+        );
+        assert!(argv_for("logs", json!({"nope": true}), &[]).is_err());
+        assert!(argv_for("logs", json!({"clear": "yes"}), &[]).is_err());
+    }
+
+    #[test]
+    fn values_attach_and_counts_repeat() {
+        let spec: Spec = r#"
+bin "x"
+cmd "y" {
+    flag "-v" count=#true
+    flag "--tag <tag>" var=#true
+}
+"#
+        .parse()
+        .unwrap();
+        let flags = serde_json::from_value(json!({"v": 2, "tag": ["a", "-b"]})).unwrap();
+        let argv = invocation(&spec, &find_chain(&spec, "y").unwrap(), &flags, &[]).unwrap();
+        assert_eq!(argv, ["x", "y", "--tag=a", "--tag=-b", "-v", "-v"]);
+    }
+
+    #[test]
+    fn what_a_tool_result_quotes_of_a_secret_is_masked() {
+        let spec: Spec = r#"
+bin "x"
+cmd "y" {
+    flag "--token <token>" sensitive=#true
+    flag "--tag <tag>" var=#true
+    arg "<name>"
+    arg "<secrets>..." sensitive=#true
+}
+"#
+        .parse()
+        .unwrap();
+        let chain = find_chain(&spec, "y").unwrap();
+        let flags = serde_json::from_value(json!({"token": "hunter2", "tag": ["a"]})).unwrap();
+        let args = ["n".to_string(), "s1".to_string(), "s2".to_string()];
+        let argv = redacted_invocation(&spec, &chain, &flags, &args).unwrap();
+        assert_eq!(
+            argv,
+            [
+                "x",
+                "y",
+                "--tag=a",
+                "--token=<redacted>",
+                "n",
+                REDACTED,
+                REDACTED
+            ]
+        );
+        // What is actually run is still what was asked for.
+        let argv = invocation(&spec, &chain, &flags, &args).unwrap();
+        assert!(argv.contains(&"--token=hunter2".to_string()));
+        assert_eq!(
+            describe_flag(chain[1].flags.first().unwrap())["sensitive"],
+            true
+        );
+    }
+
+    #[test]
+    fn the_ceiling_compares_effects_and_refuses_the_unknown() {
+        use SpecCommandEffect::*;
+        assert!(allowed(Some(Read), Read).is_ok());
+        assert!(allowed(Some(Write), Destructive).is_ok());
+        let err = allowed(Some(Destructive), Write).unwrap_err();
+        assert!(err.contains("destructive"), "{err}");
+        // Unknown is not read. Only the ceiling that allows anything allows it.
+        assert!(allowed(None, Write).is_err());
+        assert!(allowed(None, Destructive).is_ok());
+    }
+
+    async fn run(
+        server: &SpecServer,
+        command: &str,
+        flags: Value,
+        args: &[&str],
+    ) -> CallToolResult {
+        server
+            .run_command(Parameters(RunCommandParams {
+                command: command.to_string(),
+                flags: serde_json::from_value(flags).unwrap(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+            }))
+            .await
+            .unwrap()
+    }
+
+    fn text(res: &CallToolResult) -> String {
+        res.content[0].as_text().unwrap().text.clone()
+    }
+
+    #[tokio::test]
+    async fn a_flag_above_the_ceiling_is_refused_before_anything_runs() {
+        // `pitchfork` is not installed here, so reaching the spawn would report
+        // "could not run" instead. The refusal has to come first.
+        let server = SpecServer::new(spec(), Some(SpecCommandEffect::Read));
+        let res = run(&server, "logs", json!({"clear": true}), &[]).await;
+        assert_eq!(res.is_error, Some(true));
+        let msg = text(&res);
+        assert!(
+            msg.contains("refused") && msg.contains("destructive"),
+            "{msg}"
+        );
+
+        // A command with no effect at all is unknown, not read.
+        let res = run(&server, "start", json!({}), &[]).await;
+        assert!(text(&res).contains("declares no effect"), "{}", text(&res));
+    }
+
+    #[tokio::test]
+    async fn a_value_the_environment_sets_counts_against_the_ceiling() {
+        // The child inherits this environment, so `--target` would be set there as it is here.
+        // `PATH` stands in for any variable the server happens to have.
+        let spec: Spec = r#"
+bin "pitchfork"
+cmd "deploy" effect="read" {
+    flag "--target <dir>" effect="destructive" env="PATH"
+}
+"#
+        .parse()
+        .unwrap();
+        let server = SpecServer::new(spec, Some(SpecCommandEffect::Read));
+        let res = run(&server, "deploy", json!({}), &[]).await;
+        assert_eq!(res.is_error, Some(true));
+        assert!(text(&res).contains("destructive"), "{}", text(&res));
+    }
+
+    #[test]
+    fn a_count_is_capped() {
+        let spec: Spec =
+            "bin \"x\"\ncmd \"y\" effect=\"read\" { flag \"-v --verbose\" count=#true }"
+                .parse()
+                .unwrap();
+        let chain = vec![&spec.cmd, spec.cmd.find_subcommand("y").unwrap()];
+        let flags = |times: u64| BTreeMap::from([("verbose".to_string(), json!(times))]);
+        let argv = invocation(&spec, &chain, &flags(3), &[]).unwrap();
+        assert_eq!(argv, ["x", "y", "--verbose", "--verbose", "--verbose"]);
+        let err = invocation(&spec, &chain, &flags(u64::MAX), &[]).unwrap_err();
+        assert!(err.contains("the most this server passes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn a_line_the_spec_rejects_is_refused_with_the_specs_reason() {
+        let spec: Spec = "bin \"x\"\ncmd \"y\" effect=\"read\" { arg \"<name>\" }"
+            .parse()
+            .unwrap();
+        let server = SpecServer::new(spec, Some(SpecCommandEffect::Read));
+        let res = run(&server, "y", json!({}), &[]).await;
+        assert_eq!(res.is_error, Some(true));
+        assert!(text(&res).contains("does not parse"), "{}", text(&res));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn a_run_reports_its_output_and_exit_status() {
+        let spec: Spec = "bin \"echo\"\ncmd \"hello\" effect=\"read\" { arg \"<name>\" }"
+            .parse()
+            .unwrap();
+        let server = SpecServer::new(spec, Some(SpecCommandEffect::Read));
+        let res = run(&server, "hello", json!({}), &["world"]).await;
+        assert_ne!(res.is_error, Some(true), "{}", text(&res));
+        let out: Value = serde_json::from_str(&text(&res)).unwrap();
+        assert_eq!(out["stdout"], "hello world\n");
+        assert_eq!(out["exit_code"], 0);
+        assert_eq!(out["effect"], "read");
+    }
+
+    #[tokio::test]
+    async fn an_explanation_carries_the_effect_of_what_was_typed() {
+        let server = SpecServer::new(spec(), None);
+        let explained = |argv: &[&str]| {
+            let server = server.clone();
+            let argv = argv.iter().map(|a| a.to_string()).collect();
+            async move {
+                let res = server
+                    .explain_invocation(Parameters(ExplainInvocationParams {
+                        argv,
+                        env: Some(BTreeMap::new()),
+                    }))
+                    .await
+                    .unwrap();
+                serde_json::from_str::<Value>(&text(&res)).unwrap()
+            }
+        };
+
+        let out = explained(&["pitchfork", "logs", "--clear"]).await;
+        assert_eq!(out["effect"], "destructive");
+        assert_eq!(out["command"], json!(["pitchfork", "logs"]));
+        assert_eq!(out["valid"], true);
+        // Offered without the binary, as `list_commands` spells paths.
+        assert_eq!(explained(&["logs"]).await["effect"], "read");
+
+        let out = explained(&["pitchfork", "logs", "--nope"]).await;
+        assert_eq!(out["valid"], false);
+        assert!(out["refused"].is_string(), "{out}");
+    }
+
     #[test]
     fn server_info_declares_tools_and_instructions() {
-        let info = SpecServer::new(spec()).get_info();
+        let info = SpecServer::new(spec(), None).get_info();
         assert!(info.capabilities.tools.is_some());
         assert_eq!(info.server_info.name, "usage");
         assert!(info.instructions.is_some());
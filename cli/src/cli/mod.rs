@@ -4,13 +4,18 @@ use std::ffi::OsStr;
 use miette::Result;
 use usage_rs::{Cli as DeriveCli, Subcommands};
 
+mod bounded;
 pub mod complete_word;
 mod diff;
 mod exec;
 mod explain;
+mod fmt;
 pub(crate) mod generate;
+mod import;
 mod lint;
+mod lsp;
 mod mcp;
+mod report;
 mod shell;
 mod sponsors;
 
@@ -36,13 +41,15 @@ mod sponsors;
     repository = "https://github.com/jdx/usage",
     // The command path is not the file path: command names are hyphenated where the files
     // that implement them are snake_case, a command with subcommands lives in its directory's
-    // `mod.rs`, and the four shell commands are all served by a single `shell.rs`.
+    // `mod.rs` — as do `lint`, which keeps its fixes beside it, `diff`, which keeps its
+    // release history there, and `lsp`, which keeps the pieces of its server — and the four
+    // shell commands are all served by a single `shell.rs`.
     //
     // Unindented, because a raw string keeps every leading space it is given and only the
     // `{%-`/`-%}` markers take any back — so indenting to match the attribute would be
     // trusting each line to be surrounded by them.
     source_code_link_template = r#"{%- set path = path | replace(from='-', to='_') -%}
-{%- if cmd.subcommands | length > 0 -%}
+{%- if cmd.subcommands | length > 0 or path in ["diff", "lint", "lsp"] -%}
 {%- set path = path ~ "/mod.rs" -%}
 {%- elif path in ["bash", "fish", "powershell", "zsh"] -%}
 {%- set path = "shell.rs" -%}
@@ -112,8 +119,11 @@ enum Command {
     Exec(exec::Exec),
     Explain(explain::Explain),
     Fish(shell::Fish),
+    Fmt(fmt::Fmt),
     Generate(generate::Generate),
+    Import(import::Import),
     Lint(lint::Lint),
+    Lsp(lsp::Lsp),
     Mcp(mcp::Mcp),
     #[usage(name = "powershell")]
     PowerShell(shell::PowerShell),
@@ -195,8 +205,8 @@ fn collect_mount_answers(cmd: &usage::SpecCommand, answers: &mut HashMap<String,
 
 /// How a command that can print either prose or JSON was asked to print.
 ///
-/// Shared rather than declared twice: `lint` and `explain` both offer it, and a third
-/// copy of the same four-line `FromStr` is how the two spellings drift apart.
+/// `explain` offers it. `lint` and `diff` offer [`ReportFormat`], which is these two and the
+/// formats a CI job reads findings in.
 #[derive(Debug, Clone, Copy, Default, usage_rs::ValueEnum)]
 pub(crate) enum OutputFormat {
     #[default]
@@ -219,3 +229,31 @@ impl std::str::FromStr for OutputFormat {
         })
     }
 }
+
+/// How `lint` and `diff` were asked to print what they found.
+///
+/// [`OutputFormat`]'s two, and two more for CI that only make sense for a list of findings:
+/// SARIF 2.1.0 for a code-scanning upload, and GitHub's workflow commands, which put each
+/// finding on the line of the spec it is about in a pull request.
+#[derive(Debug, Clone, Copy, Default, usage_rs::ValueEnum)]
+pub(crate) enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+    Sarif,
+    Github,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        use usage_rs::spec::ValueEnum;
+        Self::from_choice(value).ok_or_else(|| {
+            format!(
+                "`{value}` is not one of: {}",
+                Self::ACCEPTED_CHOICES.join(", ")
+            )
+        })
+    }
+}
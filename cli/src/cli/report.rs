@@ -0,0 +1,253 @@
+//! The two CI formats `lint` and `diff` share: SARIF, and GitHub's workflow commands.
+//!
+//! Both commands produce a list of findings that already say how much each one matters,
+//! which rule raised it, and — for a spec read from a file — which line it is about. What
+//! differs between them is only the wording of a level, so each maps its findings onto
+//! [`Finding`] and the rendering lives here once.
+
+use serde_json::{json, Value};
+use usage::spec::SourceLocation;
+
+/// How much a finding matters, in the words both formats share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+/// One finding, as either format needs to see it.
+pub(crate) struct Finding<'a> {
+    pub level: Level,
+    pub code: &'a str,
+    pub message: &'a str,
+    /// Where in the interface, as `lint` and `diff` describe it in their text output.
+    pub location: Option<&'a str>,
+    /// Where in the file, when the spec was read from one.
+    pub source: Option<&'a SourceLocation>,
+}
+
+/// A SARIF 2.1.0 log with one run, as `github/codeql-action/upload-sarif` takes it.
+///
+/// Each distinct code becomes a rule, in the order the codes first appear. A finding about a
+/// spec read from stdin has no file to point at, so it carries only its logical location:
+/// a result pointing at a file that does not exist is refused by the upload.
+pub(crate) fn sarif(tool: &str, findings: &[Finding]) -> Value {
+    let mut rules: Vec<&str> = vec![];
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let index = match rules.iter().position(|code| *code == finding.code) {
+                Some(index) => index,
+                None => {
+                    rules.push(finding.code);
+                    rules.len() - 1
+                }
+            };
+            let mut location = serde_json::Map::new();
+            if let Some(source) = finding.source.filter(|s| has_file(s)) {
+                location.insert(
+                    "physicalLocation".into(),
+                    json!({
+                        "artifactLocation": { "uri": uri(source) },
+                        "region": { "startLine": source.line, "startColumn": source.column },
+                    }),
+                );
+            }
+            if let Some(logical) = finding.location {
+                location.insert(
+                    "logicalLocations".into(),
+                    json!([{ "fullyQualifiedName": logical }]),
+                );
+            }
+            let mut result = json!({
+                "ruleId": finding.code,
+                "ruleIndex": index,
+                "level": match finding.level {
+                    Level::Error => "error",
+                    Level::Warning => "warning",
+                    Level::Note => "note",
+                },
+                "message": { "text": finding.message },
+            });
+            if !location.is_empty() {
+                result["locations"] = json!([location]);
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": tool,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://usage.jdx.dev",
+                    "rules": rules.iter().map(|code| json!({ "id": code })).collect::<Vec<_>>(),
+                },
+            },
+            // What `SourceLocation` counts in. SARIF's default is UTF-16 code units, which
+            // only agrees for a spec written in the BMP.
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// One `::error`, `::warning` or `::notice` workflow command per finding.
+///
+/// GitHub puts each one on the named line of the pull request's diff, or on the run's
+/// summary when there is no file to name. The code is the title, since that is the part a
+/// reviewer searches the docs for.
+pub(crate) fn github(findings: &[Finding]) -> String {
+    let mut out = String::new();
+    for finding in findings {
+        let command = match finding.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "notice",
+        };
+        let mut properties = vec![];
+        if let Some(source) = finding.source.filter(|s| has_file(s)) {
+            properties.push(format!("file={}", property(&source.file.to_string_lossy())));
+            properties.push(format!("line={}", source.line));
+            properties.push(format!("col={}", source.column));
+        }
+        properties.push(format!("title={}", property(finding.code)));
+        let message = match finding.location {
+            Some(location) => format!("{location}: {}", finding.message),
+            None => finding.message.to_string(),
+        };
+        out.push_str(&format!(
+            "::{command} {}::{}\n",
+            properties.join(","),
+            data(&message)
+        ));
+    }
+    out
+}
+
+fn has_file(source: &SourceLocation) -> bool {
+    !source.file.as_os_str().is_empty()
+}
+
+/// The file as a URI: relative as it was given, which is what a code-scanning upload
+/// resolves against the checkout, and a `file:` URI when it was given absolute.
+///
+/// Forward slashes, and the two characters a path commonly holds that a URI cannot.
+fn uri(source: &SourceLocation) -> String {
+    let path = source
+        .file
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace('%', "%25")
+        .replace(' ', "%20");
+    match source.file.is_absolute() {
+        true if path.starts_with('/') => format!("file://{path}"),
+        true => format!("file:///{path}"),
+        false => path,
+    }
+}
+
+/// A workflow command's message, escaped the way the runner unescapes it.
+fn data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// A workflow command's property value, which also has to keep its `:` and `,` to itself.
+fn property(value: &str) -> String {
+    data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use usage::Spec;
+
+    /// The first flag's location, as if the spec had been read from a file with a space in
+    /// its name.
+    fn located(spec: &str) -> SourceLocation {
+        let spec: Spec = spec.parse().unwrap();
+        let mut source = spec.cmd.flags[0].source.clone().unwrap();
+        source.file = "specs/my cli.usage.kdl".into();
+        source
+    }
+
+    #[test]
+    fn a_github_annotation_names_the_line_and_escapes_what_the_runner_would_split_on() {
+        let source = located("bin \"demo\"\n\nflag \"--dry-run\"\n");
+        let out = github(&[Finding {
+            level: Level::Warning,
+            code: "missing-flag-help",
+            message: "Flag 'dry-run' has no help text\n100%",
+            location: Some("cmd demo flag dry-run"),
+            source: Some(&source),
+        }]);
+        assert_eq!(
+            out,
+            "::warning file=specs/my cli.usage.kdl,line=3,col=1,title=missing-flag-help::\
+             cmd demo flag dry-run: Flag 'dry-run' has no help text%0A100%25\n"
+        );
+    }
+
+    #[test]
+    fn a_finding_without_a_file_is_annotated_on_the_run_rather_than_a_line() {
+        let out = github(&[Finding {
+            level: Level::Note,
+            code: "cmd-added",
+            message: "command 'x' was added",
+            location: None,
+            source: None,
+        }]);
+        assert_eq!(out, "::notice title=cmd-added::command 'x' was added\n");
+    }
+
+    #[test]
+    fn sarif_results_point_at_the_rule_and_the_region() {
+        let source = located("bin \"demo\"\n  flag \"-v\"\n");
+        let findings = [
+            Finding {
+                level: Level::Note,
+                code: "missing-flag-help",
+                message: "one",
+                location: Some("cmd demo flag v"),
+                source: Some(&source),
+            },
+            Finding {
+                level: Level::Error,
+                code: "invalid-default-subcommand",
+                message: "two",
+                location: None,
+                source: None,
+            },
+            Finding {
+                level: Level::Note,
+                code: "missing-flag-help",
+                message: "three",
+                location: None,
+                source: None,
+            },
+        ];
+        let log = sarif("usage lint", &findings);
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules = &run["tool"]["driver"]["rules"];
+        assert_eq!(rules.as_array().unwrap().len(), 2);
+        assert_eq!(run["results"][2]["ruleIndex"], 0);
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(run["results"][1]["level"], "error");
+        assert!(run["results"][1].get("locations").is_none());
+        let physical = &run["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            physical["artifactLocation"]["uri"],
+            "specs/my%20cli.usage.kdl"
+        );
+        assert_eq!(physical["region"]["startLine"], 2);
+        assert_eq!(physical["region"]["startColumn"], 3);
+    }
+}
@@ -19,8 +19,8 @@
 
 /// Commands with no fixed effect, and why.
 ///
-/// These run a script the user supplied, so their effect is whatever that
-/// script does. Labeling them would be a lie in whichever direction it was
+/// These run a script or program the user supplied, so their effect is
+/// whatever that does. Labeling them would be a lie in whichever direction it was
 /// labeled, and `read` in particular would be dangerous.
 // Only the coverage test reads this; it exists so the reason a command is left
 // unclassified lives next to the decision rather than in a commit message.
@@ -31,6 +31,10 @@ const UNCLASSIFIED: &[(&str, &str)] = &[
     ("fish", "runs a user-supplied script"),
     ("powershell", "runs a user-supplied script"),
     ("zsh", "runs a user-supplied script"),
+    (
+        "import help",
+        "runs the program it is given, and each subcommand it finds",
+    ),
 ];
 
 #[cfg(test)]
@@ -157,6 +161,17 @@ mod tests {
         assert_eq!(flag("force").effect, Some(Effect::Write));
     }
 
+    #[test]
+    fn importing_help_only_reads_when_the_help_was_captured() {
+        // Given a command, `import help` runs it, so `usage import help -- sh -c …` is
+        // anything at all. Reading pages captured earlier is what can promise to read.
+        let import = find("import help").unwrap();
+        assert_eq!(import.effect, None);
+        let flag = |name: &str| import.flags.iter().find(|f| f.flag.name == name).unwrap();
+        assert_eq!(flag("file").effect, Some(Effect::Read));
+        assert_eq!(flag("dir").effect, Some(Effect::Read));
+    }
+
     #[test]
     fn a_required_output_flag_makes_the_command_write() {
         // `generate sdk` cannot print to stdout, so there is no read-only way
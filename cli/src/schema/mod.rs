@@ -35,7 +35,26 @@ pub fn config_schema(config: &SpecConfig, options: &SchemaOptions) -> Value {
     }
     schema.insert("type".into(), json!("object"));
     schema.insert("unevaluatedProperties".into(), json!(false));
-    schema.insert("properties".into(), Value::Object(nest(config)));
+    let mut properties = nest(config);
+    // A profile table holds the same settings the file does, under any name. Not another
+    // `profiles` table, though: an overlay is one level deep, and a nested one is read as keys
+    // nobody declared.
+    if config.profiles.is_some() {
+        let settings = json!({
+            "type": "object",
+            "unevaluatedProperties": false,
+            "properties": properties.clone(),
+        });
+        properties.insert(
+            "profiles".into(),
+            json!({
+                "type": "object",
+                "description": "Named profiles, each overlaying the settings above when selected",
+                "additionalProperties": settings,
+            }),
+        );
+    }
+    schema.insert("properties".into(), Value::Object(properties));
     Value::Object(schema)
 }
 
@@ -116,7 +135,12 @@ fn prop_schema(prop: &SpecConfigProp) -> Value {
     if let Some(description) = description {
         object.insert("description".into(), json!(description));
     }
-    if let Some(default) = &prop.default {
+    if prop.sensitive {
+        // `writeOnly` is the schema's word for a value a document may set and a reader should
+        // not be shown, which is what an editor needs to mask it. A default is left out for the
+        // same reason: a schema is published with the docs.
+        object.insert("writeOnly".into(), json!(true));
+    } else if let Some(default) = &prop.default {
         object.insert("default".into(), value_json(default));
     } else if !prop.default_list.is_empty() {
         object.insert("default".into(), json!(prop.default_list));
@@ -189,7 +213,9 @@ fn type_schema(ty: &SpecConfigType) -> Value {
     }
 }
 
-fn base_schema(base: &Base) -> Value {
+/// Also how a typed command-line value is described — an MCP client reads the same
+/// vocabulary for a tool's arguments as an editor does for a config file.
+pub(crate) fn base_schema(base: &Base) -> Value {
     match base {
         Base::Bool => json!({"type": "boolean"}),
         Base::Int => json!({"type": "integer"}),
@@ -318,6 +344,7 @@ config {
     prop "old" type="bool" deprecated="Use new instead." help="Old setting"
     prop "verbose" type="bool" default=#false help="Say more" \
         long_help="Say more.\n\nIncluding about things you did not ask about."
+    prop "token" type="string" default="dev-token" sensitive=#true
 }
 "##,
         );
@@ -340,6 +367,9 @@ config {
             .unwrap()
             .contains("did not ask about"));
         assert_eq!(props["verbose"]["default"], false);
+        assert_eq!(props["token"]["writeOnly"], true);
+        assert_eq!(props["token"].get("default"), None);
+        assert_eq!(props["shell"].get("writeOnly"), None);
     }
 
     #[test]
@@ -548,6 +578,33 @@ config {
         assert!(plain.get("enum").is_none());
     }
 
+    #[test]
+    fn a_profile_holds_the_settings_a_file_does() {
+        let schema = schema_of(
+            r#"
+name "ex"
+bin "ex"
+config {
+    profiles env="EX_PROFILE"
+    prop "jobs" type="uint"
+    prop "task.output" type="string"
+}
+"#,
+        );
+        let profile = &schema["properties"]["profiles"]["additionalProperties"];
+        assert_eq!(profile["unevaluatedProperties"], false);
+        assert_eq!(profile["properties"]["jobs"], schema["properties"]["jobs"]);
+        assert_eq!(
+            profile["properties"]["task"], schema["properties"]["task"],
+            "dotted keys nest the same way inside a profile"
+        );
+        assert!(profile["properties"].get("profiles").is_none());
+
+        // Without the declaration, `profiles` is as unknown as any other key.
+        let schema = schema_of("name \"ex\"\nbin \"ex\"\nconfig {\n  prop \"jobs\"\n}\n");
+        assert!(schema["properties"].get("profiles").is_none());
+    }
+
     #[test]
     fn the_envelope_says_what_it_is() {
         let schema = schema_of("name \"ex\"\nbin \"ex\"\nconfig {\n  prop \"a\"\n}\n");
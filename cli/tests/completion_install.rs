@@ -12,7 +12,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::sync::atomic::{AtomicU32, Ordering};
 
-const SHELLS: [&str; 5] = ["bash", "zsh", "fish", "nu", "powershell"];
+const SHELLS: [&str; 7] = ["bash", "zsh", "fish", "nu", "powershell", "elvish", "xonsh"];
 
 /// A scratch directory, and a `usage` pointed at it and nowhere else.
 struct Scratch {
@@ -72,6 +72,26 @@ impl Scratch {
             .unwrap()
     }
 
+    /// `usage`, with a `PATH` holding a stand-in for each of `programs` and nothing else.
+    ///
+    /// Empty files do: whether a shell is there is a question of whether `PATH` names it, and none
+    /// of them is ever run.
+    fn usage_with_shells(&self, programs: &[&str]) -> Command {
+        let bin = self.dir.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        for program in programs {
+            let file = if cfg!(windows) {
+                format!("{program}.exe")
+            } else {
+                (*program).to_string()
+            };
+            std::fs::write(bin.join(file), "").unwrap();
+        }
+        let mut cmd = self.usage();
+        cmd.env("PATH", bin);
+        cmd
+    }
+
     /// The path an install reported, checked to be inside this directory before it is used.
     fn installed_path(&self, stderr: &str) -> PathBuf {
         let line = stderr
@@ -386,3 +406,121 @@ fn an_environment_with_no_home_is_reported_rather_than_guessed() {
     assert!(stderr.contains("XDG_DATA_HOME"), "{stderr}");
     assert!(stderr.contains("HOME"), "{stderr}");
 }
+
+#[test]
+fn uninstall_removes_what_install_wrote() {
+    let scratch = Scratch::new("uninstall");
+    let out = scratch.install("zsh");
+    let path = scratch.installed_path(&String::from_utf8_lossy(&out.stderr));
+
+    // No spec and no --usage-cmd: taking a script out does not need one to render.
+    let out = scratch
+        .usage()
+        .args(["g", "completion", "zsh", "mycli", "--uninstall"])
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    assert!(!path.exists());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains(&format!("removed {}", path.display())),
+        "{stderr}"
+    );
+    // The line the install asked for is the user's to take out, so it is named.
+    assert!(stderr.contains("fpath+="), "{stderr}");
+}
+
+#[test]
+fn uninstall_leaves_a_file_usage_did_not_write() {
+    let scratch = Scratch::new("uninstall_foreign");
+    let out = scratch.install("fish");
+    let path = scratch.installed_path(&String::from_utf8_lossy(&out.stderr));
+    std::fs::write(&path, "complete -c mycli -a mine\n").unwrap();
+
+    scratch
+        .usage()
+        .args(["g", "completion", "fish", "mycli", "--uninstall"])
+        .assert()
+        .failure()
+        .stderr(contains("was not written by usage"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "complete -c mycli -a mine\n"
+    );
+}
+
+#[test]
+fn all_shells_on_its_own_says_where_each_would_go_and_writes_nothing() {
+    let scratch = Scratch::new("all_preview");
+    let out = scratch
+        .usage_with_shells(&["fish", "zsh"])
+        .args(["g", "completion", "--all-shells", "mycli", "-f"])
+        .arg(scratch.spec())
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let shells: Vec<&str> = stdout
+        .lines()
+        .filter(|l| !l.starts_with(' '))
+        .map(|l| l.split(':').next().unwrap())
+        .collect();
+    assert_eq!(shells, ["zsh", "fish"], "{stdout}");
+    assert!(stdout.contains("fpath+="), "{stdout}");
+    assert!(!scratch.dir.join("data").exists());
+    assert!(!scratch.dir.join("config").exists());
+}
+
+#[test]
+fn all_shells_installs_for_each_shell_on_path() {
+    let scratch = Scratch::new("all_install");
+    let out = scratch
+        .usage_with_shells(&["fish", "xonsh"])
+        .args([
+            "g",
+            "completion",
+            "--all-shells",
+            "--install",
+            "mycli",
+            "-f",
+        ])
+        .arg(scratch.spec())
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    let fish = scratch.dir.join("config/fish/completions/mycli.fish");
+    let xonsh = scratch.dir.join("config/xonsh/rc.d/mycli.xsh");
+    assert!(fish.is_file() && xonsh.is_file());
+    // Each shell gets its own script, not one shell's script under every name.
+    assert!(std::fs::read_to_string(&xonsh)
+        .unwrap()
+        .contains("RichCompletion"));
+    assert!(!scratch.dir.join("data").exists());
+
+    // And back out again, from every shell's directory.
+    scratch
+        .usage_with_shells(&[])
+        .args(["g", "completion", "--all-shells", "--uninstall", "mycli"])
+        .assert()
+        .success();
+    assert!(!fish.exists() && !xonsh.exists());
+}
+
+#[test]
+fn all_shells_with_none_on_path_installs_nothing_and_says_so() {
+    let scratch = Scratch::new("all_none");
+    scratch
+        .usage_with_shells(&[])
+        .args([
+            "g",
+            "completion",
+            "--all-shells",
+            "--install",
+            "mycli",
+            "-f",
+        ])
+        .arg(scratch.spec())
+        .assert()
+        .failure()
+        .stderr(contains("was found on PATH"));
+}
@@ -2,7 +2,8 @@
 //!
 //! The classification rules are unit-tested beside the comparison itself. What is
 //! only observable from outside is here: the exit status a release job gates on, the
-//! two output formats, and reading one of the two specs from stdin.
+//! output formats, reading one of the two specs from stdin, and finding the old one in git
+//! or a directory of snapshots.
 
 use assert_cmd::Command;
 use predicates::prelude::PredicateBooleanExt;
@@ -138,6 +139,53 @@ fn json_is_a_list_a_program_can_read() {
     std::fs::remove_dir_all(&dir).unwrap();
 }
 
+#[test]
+fn github_annotations_land_on_the_line_of_the_new_spec() {
+    let dir = fixtures("github");
+    usage_cmd()
+        .arg("diff")
+        .arg(old(&dir))
+        .arg(new(&dir))
+        .args(["--format", "github"])
+        .assert()
+        .code(1)
+        .stdout(contains(format!(
+            "::error file={},line=5,col=1,title=flag-spelling-removed::\
+             ex: flag '--jobs' no longer answers to '-j'\n",
+            new(&dir).display()
+        )))
+        .stdout(contains(format!(
+            "::notice file={},line=7,col=1,title=flag-added::",
+            new(&dir).display()
+        )));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sarif_is_a_log_code_scanning_can_upload() {
+    let dir = fixtures("sarif");
+    let output = usage_cmd()
+        .arg("diff")
+        .arg(old(&dir))
+        .arg(new(&dir))
+        .args(["--format", "sarif"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "usage diff");
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "flag-spelling-removed");
+    assert_eq!(result["level"], "error");
+    let location = &result["locations"][0];
+    assert_eq!(location["physicalLocation"]["region"]["startLine"], 5);
+    assert_eq!(location["logicalLocations"][0]["fullyQualifiedName"], "ex");
+    assert_eq!(run["results"][1]["level"], "note");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
 #[test]
 fn the_new_spec_can_come_from_stdin() {
     // The shape a release job wants: the released spec on disk against what the
@@ -163,3 +211,152 @@ fn both_specs_cannot_be_stdin() {
         .failure()
         .stderr(contains("only one of the two specs can be read from stdin"));
 }
+
+/// A git repository with `OLD` committed and tagged `v1.0.0`, and `NEW` in the work tree
+/// as the release in progress.
+fn repository(case: &str) -> PathBuf {
+    let dir = fixtures(case);
+    std::fs::write(dir.join("ex.usage.kdl"), OLD).unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&dir)
+            .args([
+                "-c",
+                "user.name=usage",
+                "-c",
+                "user.email=usage@example.com",
+            ])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?}");
+    };
+    git(&["init", "-q"]);
+    git(&["add", "ex.usage.kdl"]);
+    git(&["commit", "-q", "-m", "release"]);
+    git(&["tag", "v1.0.0"]);
+    std::fs::write(dir.join("ex.usage.kdl"), NEW).unwrap();
+    dir
+}
+
+#[test]
+fn the_old_spec_can_come_from_a_git_revision() {
+    let dir = repository("against_rev");
+    usage_cmd()
+        .arg("diff")
+        .args(["--against", "v1.0.0"])
+        .arg(dir.join("ex.usage.kdl"))
+        .assert()
+        .code(1)
+        .stdout(contains(
+            "breaking [flag-spelling-removed] at ex: flag '--jobs' no longer answers to '-j'",
+        ));
+    usage_cmd()
+        .arg("diff")
+        .args(["--against", "v9.9.9"])
+        .arg(dir.join("ex.usage.kdl"))
+        .assert()
+        .failure()
+        .stderr(contains("'v9.9.9' is not a git revision"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn history_walks_the_tags_and_then_the_work_tree() {
+    let dir = repository("history_tags");
+    usage_cmd()
+        .arg("diff")
+        .arg("--history")
+        .arg(dir.join("ex.usage.kdl"))
+        .assert()
+        .success()
+        .stdout(contains("Releases: v1.0.0, current"))
+        .stdout(contains("ex: flag '--quiet' added in current"))
+        .stdout(contains("Every removal kept its deprecation's promise."));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn history_skips_a_tag_from_before_the_spec_and_stops_at_one_it_cannot_read() {
+    let dir = repository("history_unreadable");
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+    // The first commit had no spec, and its tag has nothing to say about it.
+    let tree = git(&["mktree"]);
+    let empty = git(&[
+        "-c",
+        "user.name=usage",
+        "-c",
+        "user.email=usage@example.com",
+        "commit-tree",
+        tree.trim(),
+        "-m",
+        "before",
+    ]);
+    git(&["tag", "v0.1.0", empty.trim()]);
+    usage_cmd()
+        .arg("diff")
+        .arg("--history")
+        .arg(dir.join("ex.usage.kdl"))
+        .assert()
+        .success()
+        .stdout(contains("Releases: v1.0.0, current"));
+
+    // A release whose copy is gone from the object store is an error, not a release that
+    // never had the spec.
+    let blob = git(&["rev-parse", "v1.0.0:ex.usage.kdl"]);
+    let blob = blob.trim();
+    std::fs::remove_file(dir.join(".git/objects").join(&blob[..2]).join(&blob[2..])).unwrap();
+    usage_cmd()
+        .arg("diff")
+        .arg("--history")
+        .arg(dir.join("ex.usage.kdl"))
+        .assert()
+        .failure()
+        .stderr(contains("git show"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn history_can_read_a_directory_of_snapshots() {
+    let dir = fixtures("history_snapshots");
+    let releases = dir.join("releases");
+    std::fs::create_dir(&releases).unwrap();
+    // Named so that file-name order is not version order: the declared version decides.
+    std::fs::write(releases.join("b.usage.kdl"), OLD).unwrap();
+    std::fs::write(
+        releases.join("a.usage.kdl"),
+        OLD.replace("version \"1.0.0\"", "version \"1.5.0\"")
+            .replace("flag \"-f --force\" help=\"force it\"", ""),
+    )
+    .unwrap();
+    let output = usage_cmd()
+        .arg("diff")
+        .arg("--history")
+        .args(["--against", releases.to_str().unwrap()])
+        .args(["--format", "json"])
+        .arg(new(&dir))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let history: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        history["releases"],
+        serde_json::json!(["b", "a", "current"])
+    );
+    assert_eq!(history["changes"][0]["code"], "removed-without-deprecation");
+    assert_eq!(
+        history["changes"][0]["message"],
+        "flag '--force' was removed in a without being deprecated first"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
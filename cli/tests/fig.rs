@@ -200,3 +200,28 @@ cmd "diff" help="diff" {
     );
     assert!(!fig.contains("generators"), "{fig}");
 }
+
+#[test]
+fn a_value_type_outranks_a_guess_from_the_name() {
+    // `file_count` contains "file", and was offered paths for it. A declared number is
+    // not a path, and a declared path needs no name to say so.
+    let fig = fig_of(
+        r#"
+name "ex"
+bin "ex"
+cmd "d" help="d" {
+    arg "<file_count>" type="uint"
+    arg "<target>" type="path"
+    flag "--strict <on>" type="bool"
+}
+        "#,
+    );
+    assert_eq!(
+        fig.matches(r#""template": "filepaths""#).count(),
+        1,
+        "{fig}"
+    );
+    assert!(fig.contains(r#""name": "target""#), "{fig}");
+    assert!(fig.contains(r#""suggestions": ["#), "{fig}");
+    assert!(fig.contains(r#""true""#), "{fig}");
+}
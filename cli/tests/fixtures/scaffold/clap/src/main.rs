@@ -0,0 +1,13 @@
+//! `cli` is what `usage g clap` wrote from toolbox.usage.kdl. Built, it checks the command
+//! the way clap's own tests would, then prints the spec clap_usage makes of it.
+
+#[allow(dead_code)]
+mod cli;
+
+use clap::CommandFactory;
+
+fn main() {
+    let mut cmd = cli::Cli::command();
+    cmd.clone().debug_assert();
+    print!("{}", clap_usage::spec(&mut cmd, "toolbox"));
+}
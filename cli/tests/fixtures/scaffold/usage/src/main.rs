@@ -0,0 +1,9 @@
+//! `cli` is what `usage g rust` wrote from toolbox.usage.kdl. Built, it prints the spec the
+//! derive makes of it.
+
+#[allow(dead_code)]
+mod cli;
+
+fn main() {
+    print!("{}", cli::Cli::to_kdl());
+}
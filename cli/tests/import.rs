@@ -0,0 +1,200 @@
+//! `usage import help` against captured help, one fixture tree per layout.
+//!
+//! Each tree under `tests/fixtures/import` is what the program and its subcommands print for
+//! --help, laid out the way `--dir` reads it. The specs are snapshots, so a change to how a
+//! layout is read shows up as a diff of what it produces. How single lines are classified is
+//! unit-tested beside the scraper.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+fn usage_cmd() -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("usage"));
+    // Relative fixture paths, so the path the spec's header names is the same everywhere.
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// The spec and the report, once the run is known to have succeeded.
+fn import(args: &[&str]) -> (String, String) {
+    let output = usage_cmd()
+        .args(["import", "help"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+fn import_dir(layout: &str) -> String {
+    let (spec, _) = import(&["--dir", &format!("tests/fixtures/import/{layout}")]);
+    spec
+}
+
+#[test]
+fn reads_clap_help() {
+    insta::assert_snapshot!(import_dir("clap"));
+}
+
+#[test]
+fn reads_cobra_help() {
+    insta::assert_snapshot!(import_dir("cobra"));
+}
+
+#[test]
+fn reads_argparse_help() {
+    insta::assert_snapshot!(import_dir("argparse"));
+}
+
+#[test]
+fn reads_click_help() {
+    insta::assert_snapshot!(import_dir("click"));
+}
+
+#[test]
+fn reads_gnu_help() {
+    insta::assert_snapshot!(import_dir("gnu"));
+}
+
+#[test]
+fn the_report_names_each_page_and_the_lines_it_could_not_place() {
+    let (_, report) = import(&["--dir", "tests/fixtures/import/gnu"]);
+    assert_eq!(
+        report,
+        "wc: GNU help, 8 flags, 1 arg, 0 subcommands
+  line 25: not classified: 0  if every file was read
+  line 26: not classified: 1  if a file could not be
+"
+    );
+
+    let (_, report) = import(&["--dir", "tests/fixtures/import/cobra"]);
+    assert!(
+        report.contains("kv completion: no help at tests/fixtures/import/cobra/completion/help.txt, kept as listed"),
+        "{report}"
+    );
+}
+
+#[test]
+fn a_single_page_can_come_from_stdin_and_its_subcommands_stay_as_listed() {
+    let page = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/import/clap/help.txt"),
+    )
+    .unwrap();
+    usage_cmd()
+        .args(["import", "help", "--file", "-"])
+        .write_stdin(page)
+        .assert()
+        .success()
+        .stdout(contains("// Imported by `usage import help` from stdin."))
+        .stdout(contains(
+            "cmd deploy help=\"Deploy a build\" {\n    alias d\n}",
+        ))
+        .stderr(contains(
+            "ship: clap help, 5 flags, 2 args, 2 subcommands\n",
+        ));
+}
+
+#[test]
+fn depth_zero_reads_only_the_programs_own_page() {
+    let (spec, report) = import(&["--depth", "0", "--dir", "tests/fixtures/import/click"]);
+    assert!(spec.contains("cmd add help=\"Add a note.\"\n"), "{spec}");
+    assert!(
+        report.contains("notes add: deeper than --depth, kept as listed"),
+        "{report}"
+    );
+}
+
+#[test]
+fn the_spec_is_written_to_a_file_with_the_report_still_on_stderr() {
+    let dir = std::env::temp_dir().join(format!("usage_import_out_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let out = dir.join("tidy.usage.kdl");
+    usage_cmd()
+        .args([
+            "import",
+            "help",
+            "--dir",
+            "tests/fixtures/import/argparse",
+            "-o",
+        ])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout("")
+        .stderr(contains(
+            "tidy: argparse help, 4 flags, 1 arg, 2 subcommands",
+        ));
+    let spec = std::fs::read_to_string(&out).unwrap();
+    assert!(spec.contains("flag \"-c --config\" help=\"config file to read\" required=#true"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// The program is run for real: a script standing in for one, printing the fixture page for
+/// whichever subcommand it was asked about.
+#[cfg(unix)]
+#[test]
+fn runs_the_command_and_each_subcommand_with_help() {
+    let dir = std::env::temp_dir().join(format!("usage_import_run_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let fixtures =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/import/clap");
+    let script = dir.join("ship");
+    std::fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\ndir='{}'\nfor word in \"$@\"; do\n  [ \"$word\" = --help ] && break\n  dir=\"$dir/$word\"\ndone\ncat \"$dir/help.txt\"\n",
+            fixtures.display()
+        ),
+    )
+    .unwrap();
+
+    let (spec, report) = import(&["--", "sh", script.to_str().unwrap()]);
+    let (from_dir, _) = import(&["--bin", "sh", "--dir", "tests/fixtures/import/clap"]);
+    // The name comes from the command run, which here is `sh`; everything below it is the
+    // same as reading the captured tree.
+    let body = |spec: &str| spec.lines().skip(1).collect::<Vec<_>>().join("\n");
+    assert_eq!(body(&spec), body(&from_dir));
+    assert!(report.contains("sh deploy: clap help, 5 flags"), "{report}");
+
+    usage_cmd()
+        .args(["import", "help", "--bin", "ship", "--", "sh"])
+        .arg(&script)
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "// Imported by `usage import help` from `sh {} --help`.",
+            script.display()
+        )))
+        .stdout(contains("name ship\n"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn a_command_and_captured_help_cannot_both_be_given() {
+    usage_cmd()
+        .args([
+            "import",
+            "help",
+            "--dir",
+            "tests/fixtures/import/clap",
+            "--",
+            "ship",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "a command to run cannot be given with --file or --dir",
+        ));
+    usage_cmd()
+        .args(["import", "help"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "give a command to run, or --file or --dir to read",
+        ));
+}
@@ -264,3 +264,68 @@ fn test_generate_manpage_with_spec_examples() {
         "Should contain indented spec-level example code"
     );
 }
+
+#[test]
+fn test_generate_manpage_multi_writes_a_page_per_command() {
+    let out_dir = std::env::temp_dir().join("usage-test-manpage-multi");
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    let mut cmd = usage_cmd();
+    cmd.args([
+        "generate",
+        "manpage",
+        "-f",
+        &repo_path("benches/mise.usage.kdl"),
+        "--multi",
+        "--out-dir",
+        out_dir.to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let index = std::fs::read_to_string(out_dir.join("mise.1")).unwrap();
+    assert!(index.contains("\\fBmise\\-install\\fR(1)"), "{index}");
+    let install = std::fs::read_to_string(out_dir.join("mise-install.1")).unwrap();
+    assert!(install.starts_with(".TH MISE-INSTALL 1"), "{install}");
+    assert!(install.contains(".SH \"SEE ALSO\""), "{install}");
+    assert!(out_dir.join("mise-plugins-ls.1").exists());
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn test_generate_manpage_mdoc_is_dated_reproducibly() {
+    let mut cmd = usage_cmd();
+    cmd.args([
+        "generate",
+        "manpage",
+        "-f",
+        &example_path("basic.usage.kdl"),
+        "--format",
+        "mdoc",
+    ]);
+    cmd.env("SOURCE_DATE_EPOCH", "1767225600");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with(".Dd January 1, 2026\n.Dt BASIC.USAGE.KDL 1\n.Os\n"),
+        "{stdout}"
+    );
+
+    // An explicit date wins, and a `man` page carries one only when asked.
+    let mut cmd = usage_cmd();
+    cmd.args([
+        "generate",
+        "manpage",
+        "-f",
+        &example_path("basic.usage.kdl"),
+        "--date",
+        "March 3, 2025",
+    ]);
+    let stdout = String::from_utf8(cmd.output().unwrap().stdout).unwrap();
+    assert!(
+        stdout.contains(".TH BASIC.USAGE.KDL 1 \"March 3, 2025\""),
+        "{stdout}"
+    );
+}
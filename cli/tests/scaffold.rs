@@ -0,0 +1,106 @@
+//! `usage g rust` and `usage g clap`, built: the source they write compiles, and derives the
+//! spec it was written from.
+//!
+//! What each line of the source says is snapshot-tested beside the generator. What only a
+//! compiler can say is here. Each case copies a fixture crate from tests/fixtures/scaffold,
+//! scaffolds toolbox.usage.kdl into its `src/cli`, and runs it; the crate prints the spec
+//! its derive makes of the module, which `usage diff` then compares with the original.
+//!
+//! Building a crate means fetching its dependencies, so these are ignored by default. Run
+//! them with `mise run test:scaffold`.
+
+use assert_cmd::Command;
+use std::path::{Path, PathBuf};
+
+fn usage_cmd() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("usage"))
+}
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("scaffold")
+}
+
+/// Copy the fixture crate for `framework` somewhere of its own, scaffold into it, and
+/// return the spec it prints. The manifest is rewritten with absolute paths; the lockfile
+/// beside the fixture's is what pins everything else.
+fn derived_spec(framework: &str, generator: &str) -> PathBuf {
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let fixture = fixture_dir().join(framework);
+    let tmp = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("scaffold-{framework}"));
+    let krate = tmp.join("crate");
+    let _ = std::fs::remove_dir_all(&krate);
+    std::fs::create_dir_all(krate.join("src")).unwrap();
+    std::fs::copy(fixture.join("Cargo.lock"), krate.join("Cargo.lock")).unwrap();
+    std::fs::copy(fixture.join("src/main.rs"), krate.join("src/main.rs")).unwrap();
+    let manifest = std::fs::read_to_string(fixture.join("Cargo.toml"))
+        .unwrap()
+        .replace("../../../../../", &format!("{}/", workspace.display()));
+    std::fs::write(krate.join("Cargo.toml"), manifest).unwrap();
+
+    usage_cmd()
+        .args(["generate", generator, "-f"])
+        .arg(fixture_dir().join("toolbox.usage.kdl"))
+        .arg("-o")
+        .arg(krate.join("src/cli"))
+        .assert()
+        .success();
+
+    let output = std::process::Command::new(env!("CARGO"))
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(krate.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", tmp.join("target"))
+        .output()
+        .expect("cargo should run the scaffolded crate");
+    assert!(
+        output.status.success(),
+        "the {generator} scaffold failed:\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let derived = tmp.join("derived.usage.kdl");
+    std::fs::write(&derived, output.stdout).unwrap();
+    derived
+}
+
+#[test]
+#[ignore = "builds a crate, fetching its dependencies from crates.io"]
+fn usage_derive_gives_back_the_spec_it_was_scaffolded_from() {
+    let derived = derived_spec("usage", "rust");
+    usage_cmd()
+        .arg("diff")
+        .arg(fixture_dir().join("toolbox.usage.kdl"))
+        .arg(&derived)
+        .assert()
+        .success()
+        .stdout("No interface changes.\n");
+}
+
+/// Only the interface, since the spec is clap_usage's reading of clap's command and some of
+/// the spec has no clap equivalent: what clap can declare must not have gone missing.
+#[test]
+#[ignore = "builds a crate, fetching its dependencies from crates.io"]
+fn clap_derive_keeps_every_command_flag_and_value() {
+    let derived = derived_spec("clap", "clap");
+    let output = usage_cmd()
+        .arg("diff")
+        .arg(fixture_dir().join("toolbox.usage.kdl"))
+        .arg(&derived)
+        .output()
+        .unwrap();
+    let report = String::from_utf8_lossy(&output.stdout);
+    for lost in [
+        "[cmd-removed]",
+        "[alias-removed]",
+        "[flag-removed]",
+        "[flag-spelling-removed]",
+        "[arg-removed]",
+        "[choice-removed]",
+        "[default-removed]",
+        "[constraint-removed]",
+    ] {
+        assert!(!report.contains(lost), "{lost} in:\n{report}");
+    }
+}
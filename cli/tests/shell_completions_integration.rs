@@ -1229,6 +1229,94 @@ cmd other help="Another subcommand"
     let _ = fs::remove_dir_all(&temp_dir);
 }
 
+/// elvish and xonsh read `<value>\t<description>` like fish does, and quote the value
+/// themselves — so what comes back is the raw value, spaces and all.
+#[test]
+fn test_elvish_and_xonsh_complete_word_output_format() {
+    let usage_bin = build_usage_binary();
+
+    let temp_dir = env::temp_dir().join(format!("usage_elv_xsh_fmt_test_{}", std::process::id()));
+    fs::create_dir_all(&temp_dir).unwrap();
+
+    let usage_spec = r#"name testcli
+bin testcli
+flag "--env <env>" {
+    choices "dev" "my env"
+}
+cmd sub help="A subcommand"
+"#;
+    let spec_file = temp_dir.join("test.spec");
+    fs::write(&spec_file, usage_spec).unwrap();
+
+    for shell in ["elvish", "xonsh"] {
+        let output = run_complete_word(&usage_bin, shell, &spec_file, &["testcli", ""]);
+        assert!(
+            output.lines().any(|l| l == "sub\tA subcommand"),
+            "Expected 'sub\\tA subcommand' in {shell} output, got: {output:?}"
+        );
+        let output = run_complete_word(&usage_bin, shell, &spec_file, &["testcli", "--env", ""]);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(
+            lines.contains(&"my env"),
+            "Expected the unquoted 'my env' in {shell} output, got: {lines:?}"
+        );
+    }
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
+
+/// The xonsh completion is plain Python, so whether it parses can be checked without xonsh —
+/// which CI does not install.
+#[test]
+fn test_xonsh_completion_is_valid_python() {
+    let Ok(probe) = Command::new("python3").arg("--version").output() else {
+        eprintln!("Skipping xonsh completion test - python3 not found");
+        return;
+    };
+    if !probe.status.success() {
+        eprintln!("Skipping xonsh completion test - python3 not usable");
+        return;
+    }
+    let usage_bin = build_usage_binary();
+
+    let temp_dir = env::temp_dir().join(format!("usage_xonsh_test_{}", std::process::id()));
+    fs::create_dir_all(&temp_dir).unwrap();
+    let spec_kdl_file = temp_dir.join("testcli.kdl");
+    fs::write(
+        &spec_kdl_file,
+        "bin \"testcli\"\nflag \"-v --verbose\" help=\"it's verbose\"\n",
+    )
+    .unwrap();
+
+    let cached = ["--usage-cmd", "testcli --usage", "--cache-key", "1.2.3"];
+    for extra in [&[][..], &cached[..]] {
+        let output = Command::new(&usage_bin)
+            .args(["generate", "completion", "xonsh", "testcli"])
+            .args(extra)
+            .arg("-f")
+            .arg(&spec_kdl_file)
+            .output()
+            .expect("Failed to generate xonsh completion");
+        assert!(output.status.success(), "{output:?}");
+        let comp_file = temp_dir.join("testcli.xsh");
+        fs::write(&comp_file, &output.stdout).unwrap();
+
+        let check = Command::new("python3")
+            .args(["-c", "import ast, sys; ast.parse(open(sys.argv[1]).read())"])
+            .arg(&comp_file)
+            .output()
+            .unwrap();
+        assert!(
+            check.status.success(),
+            "generated xonsh completion is not valid Python:\n{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&check.stderr)
+        );
+    }
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
+
 /// Stage a `usage`-shebang test script onto a temp `bin/` directory and
 /// generate the `g completion-init <shell>` output. Returns (temp_dir,
 /// bin_dir, init_script_path).
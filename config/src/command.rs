@@ -0,0 +1,913 @@
+//! The `config` command, as one call per subcommand.
+//!
+//! Every CLI in the fleet has `config get`, `config set`, `config list` and `config explain`,
+//! and every one of them wrote the same hundred lines: find the files, resolve, look the key
+//! up, pick a file to write, print. They differ only in what they got wrong — a `set` that
+//! writes a file the CLI never reads, a `get` that prints the default when an environment
+//! variable says otherwise because it read one file rather than resolving.
+//!
+//! Everything here is already declared. The registry says what the settings are, a spec's
+//! `file` nodes say where they live, [`explain`](crate::explain) renders what a resolution
+//! found and [`FileWriter`] writes a file the way it is read. So this module only wires them
+//! together, and `#[usage(config_command)]` on a `usage::Cli` is what calls it.
+//!
+//! Nothing is printed. [`ConfigCommand::run`] answers with the text a CLI prints and the
+//! warnings it reports, for the same reason resolution returns its warnings: where output
+//! goes is the CLI's decision.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::env::EnvLayer;
+use crate::explain;
+use crate::files::{normalize, FileLayer, Format};
+use crate::layer::{Layer, LayerError};
+use crate::registry::{Registry, Scope};
+use crate::resolve::{resolve_with_context, Layers, ResolutionContext, Resolved};
+use crate::source::FileScope;
+use crate::spec::{ConfigSpec, SpecFile};
+use crate::ty::Ty;
+use crate::write::{FileWriter, WriteError};
+
+/// Which file a `set`, `unset`, `path` or `edit` means.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// The project file nearest the working directory, or where one would go.
+    Project,
+    /// The user's own file: `--global`.
+    Global,
+    /// A declared file, by path: `--file`.
+    File(PathBuf),
+}
+
+/// One `config` subcommand and what it was given.
+///
+/// A `target` of `None` is the file a setting belongs in: the one its `writes_to` names, the
+/// user's own for a `scope="global"` setting, and the project's otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Print a setting's value.
+    Get { key: String },
+    /// Write a setting into a config file.
+    Set {
+        key: String,
+        value: String,
+        target: Option<Target>,
+    },
+    /// Take a setting out of a config file.
+    Unset { key: String, target: Option<Target> },
+    /// Every setting and its value.
+    List,
+    /// Why a setting has the value it has.
+    Explain { key: String },
+    /// The config files read, or with a target, the file it names.
+    Path { target: Option<Target> },
+    /// Open a config file in the user's editor.
+    Edit { target: Option<Target> },
+}
+
+/// What a `config` subcommand has to say.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Answer {
+    /// For stdout, without a trailing newline. Empty when there is nothing to print.
+    pub out: String,
+    /// What the resolution warned about, one line each, for stderr.
+    pub warnings: Vec<String>,
+}
+
+/// Why a `config` subcommand did nothing.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CommandError {
+    /// No setting has this key, under any name.
+    UnknownSetting { key: String },
+    /// `get` of a setting nothing gives a value, not even a default.
+    NotSet { key: &'static str },
+    /// The setting is kept somewhere other than a config file, and the spec says how to set it
+    /// there when it can.
+    NotInAFile {
+        key: &'static str,
+        kind: &'static str,
+        hint: Option<String>,
+    },
+    /// The spec declares no file of this scope to write.
+    NoFile { scope: FileScope },
+    /// `--file` named a file this CLI does not read.
+    NotDeclared { path: PathBuf },
+    /// A config file could not be read.
+    Layer(LayerError),
+    /// The setting could not be written.
+    Write(WriteError),
+    /// The editor could not be run, or said it failed.
+    Editor { editor: String, why: String },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSetting { key } => write!(f, "unknown setting `{key}`"),
+            Self::NotSet { key } => write!(f, "{key} is unset"),
+            Self::NotInAFile {
+                key,
+                hint: Some(hint),
+                ..
+            } => write!(
+                f,
+                "{key} is not kept in a config file; set it with `{hint}`"
+            ),
+            Self::NotInAFile { key, kind, .. } => {
+                write!(f, "{key} is not kept in a config file; it is set in {kind}")
+            }
+            Self::NoFile { scope } => {
+                let scope = match scope {
+                    FileScope::Project => "project",
+                    FileScope::Global => "global",
+                    FileScope::System => "system",
+                };
+                write!(f, "no {scope} config file is declared")
+            }
+            Self::NotDeclared { path } => {
+                write!(
+                    f,
+                    "{} is not a config file this program reads",
+                    path.display()
+                )
+            }
+            Self::Layer(err) => err.fmt(f),
+            Self::Write(err) => err.fmt(f),
+            Self::Editor { editor, why } => write!(f, "could not run {editor}: {why}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<LayerError> for CommandError {
+    fn from(err: LayerError) -> Self {
+        Self::Layer(err)
+    }
+}
+
+impl From<WriteError> for CommandError {
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
+/// A CLI's settings and the files they live in, ready to answer `config`.
+///
+/// The environment is injected, like [`EnvLayer`]'s, so a test describes a home directory and
+/// an editor rather than borrowing the process's. [`ConfigCommand::from_process`] is what a
+/// CLI uses.
+pub struct ConfigCommand {
+    registry: Registry,
+    spec: ConfigSpec,
+    cwd: PathBuf,
+    env: EnvLayer,
+    version: Option<String>,
+    profile: Option<String>,
+    refuse_secrets: bool,
+}
+
+impl ConfigCommand {
+    /// The command for these settings, run from `cwd` in the environment `env`.
+    pub fn new(
+        registry: Registry,
+        spec: ConfigSpec,
+        cwd: impl Into<PathBuf>,
+        env: EnvLayer,
+    ) -> Self {
+        Self {
+            registry,
+            spec,
+            cwd: cwd.into(),
+            env,
+            version: None,
+            profile: None,
+            refuse_secrets: false,
+        }
+    }
+
+    /// The command as this process would run it: its working directory and its environment.
+    pub fn from_process(registry: Registry, spec: ConfigSpec) -> Self {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let env = EnvLayer::from_process();
+        // The variable only: the flag is the CLI's, and a CLI that parsed one says so with
+        // `in_profile`.
+        let profile = spec
+            .profiles
+            .and_then(|profiles| profiles.select(None, &env))
+            .map(str::to_string);
+        let command = Self::new(registry, spec, cwd, env);
+        match profile {
+            Some(profile) => command.in_profile(profile),
+            None => command,
+        }
+    }
+
+    /// Read, explain and list as the named profile, so `get` answers what the CLI will see
+    /// when run with it. Writes still go to the base: which profile a `set` meant is not
+    /// something to guess.
+    pub fn in_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// Resolve as this version of the CLI, so `deprecated_remove_at` is enforced the way the
+    /// CLI's own resolution enforces it. Without it, `get` could report a value the CLI ignores.
+    pub fn for_cli_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Refuse `sensitive` settings from project files, in what is read and in what `set`
+    /// writes, as the CLI does when it resolves with
+    /// [`ResolutionContext::refusing_secrets_from_projects`]. A `set` of one goes to the user's
+    /// own file unless told otherwise.
+    pub fn refusing_secrets_from_projects(mut self) -> Self {
+        self.refuse_secrets = true;
+        self
+    }
+
+    /// Run one subcommand.
+    pub fn run(&self, action: Action) -> Result<Answer, CommandError> {
+        let files = self.files();
+        match action {
+            Action::Get { key } => {
+                let resolved = self.resolve(&files)?;
+                let found = self.lookup(&key)?;
+                let meta = self.registry.get(found.id);
+                let value = resolved
+                    .get(found.id)
+                    .ok_or(CommandError::NotSet { key: meta.key })?;
+                Ok(Answer {
+                    out: value.display(),
+                    warnings: explain::warnings(&resolved),
+                })
+            }
+            Action::List => {
+                let resolved = self.resolve(&files)?;
+                Ok(Answer {
+                    out: explain::list(&resolved).join("\n"),
+                    warnings: explain::warnings(&resolved),
+                })
+            }
+            Action::Explain { key } => {
+                let resolved = self.resolve(&files)?;
+                let out = explain::explain(&resolved, &key)
+                    .ok_or(CommandError::UnknownSetting { key })?;
+                Ok(Answer {
+                    out: out.trim_end().to_string(),
+                    warnings: explain::warnings(&resolved),
+                })
+            }
+            Action::Set { key, value, target } => {
+                let writer = self.writer_for(&files, &key, target.as_ref(), Some(&value))?;
+                let change = writer.set(&key, value)?;
+                Ok(Answer {
+                    out: change.describe(),
+                    warnings: Vec::new(),
+                })
+            }
+            Action::Unset { key, target } => {
+                let writer = self.writer_for(&files, &key, target.as_ref(), None)?;
+                let change = writer.unset(&key)?;
+                Ok(Answer {
+                    out: change.describe(),
+                    warnings: Vec::new(),
+                })
+            }
+            Action::Path { target: None } => {
+                // The files that are there, lowest precedence first — the order they are read
+                // in, so the last line is the one that wins a disagreement.
+                let out = files
+                    .iter()
+                    .flat_map(|(_, layer)| layer.paths())
+                    .filter(|path| path.is_file())
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Ok(Answer {
+                    out,
+                    warnings: Vec::new(),
+                })
+            }
+            Action::Path {
+                target: Some(target),
+            } => {
+                let (path, _) = self.target(&files, &target)?;
+                Ok(Answer {
+                    out: path.display().to_string(),
+                    warnings: Vec::new(),
+                })
+            }
+            Action::Edit { target } => {
+                let target = target.unwrap_or(Target::Project);
+                let (path, _) = self.target(&files, &target)?;
+                self.edit(&path)?;
+                // Read back, so a typo made in the editor is reported now rather than the next
+                // time the CLI happens to start.
+                let resolved = self.resolve(&files)?;
+                Ok(Answer {
+                    out: String::new(),
+                    warnings: explain::warnings(&resolved),
+                })
+            }
+        }
+    }
+
+    /// Every declared file as a layer, in declaration order — lowest precedence first.
+    fn files(&self) -> Vec<(SpecFile, FileLayer)> {
+        self.spec
+            .files
+            .iter()
+            .filter_map(|file| {
+                // A `~` with no home to expand it to is a file that cannot be found, not one
+                // in a directory literally called `~`.
+                let path = self.expand(file.path)?;
+                let mut layer = if file.findup {
+                    FileLayer::find_up(&path.to_string_lossy(), &self.cwd, None, file.scope)
+                } else {
+                    FileLayer::at(self.cwd.join(path), file.scope)
+                };
+                if let Some(format) = file.format.and_then(named_format) {
+                    layer = layer.as_format(format);
+                }
+                if self.spec.profiles.is_some() {
+                    layer = layer.with_profiles();
+                }
+                Some((*file, layer))
+            })
+            .collect()
+    }
+
+    fn expand(&self, path: &str) -> Option<PathBuf> {
+        let rest = match path.strip_prefix('~') {
+            None => return Some(PathBuf::from(path)),
+            Some("") => "",
+            Some(rest) => rest.strip_prefix(['/', '\\'])?,
+        };
+        let home = self
+            .env
+            .get("HOME")
+            .or_else(|| self.env.get("USERPROFILE"))?;
+        Some(Path::new(home).join(rest))
+    }
+
+    fn resolve(&self, files: &[(SpecFile, FileLayer)]) -> Result<Resolved, CommandError> {
+        let mut layers = Layers::new().then(&self.env as &dyn Layer);
+        for (_, layer) in files.iter().rev() {
+            layers = layers.then(layer);
+        }
+        let context = match &self.version {
+            Some(version) => ResolutionContext::for_cli_version(version),
+            None => ResolutionContext::new(),
+        }
+        .with_profile(self.profile.as_deref())
+        .with_env(&self.env);
+        let context = match self.refuse_secrets {
+            true => context.refusing_secrets_from_projects(),
+            false => context,
+        };
+        Ok(resolve_with_context(self.registry, layers, context)?)
+    }
+
+    fn lookup(&self, key: &str) -> Result<crate::registry::Lookup, CommandError> {
+        self.registry
+            .lookup(key)
+            .ok_or_else(|| CommandError::UnknownSetting {
+                key: key.to_string(),
+            })
+    }
+
+    /// The writer a `set` or `unset` of `key` goes through.
+    fn writer_for(
+        &self,
+        files: &[(SpecFile, FileLayer)],
+        key: &str,
+        target: Option<&Target>,
+        value: Option<&str>,
+    ) -> Result<FileWriter, CommandError> {
+        let found = self.lookup(key)?;
+        let meta = self.registry.get(found.id);
+        let writes_to = self
+            .spec
+            .props
+            .get(found.id.index())
+            .and_then(|spec| spec.writes_to);
+        let default = match (writes_to, meta.scope) {
+            // A declared file is where the setting goes; a source kind is somewhere this
+            // cannot write, and saying how to set it there is the most that can be done.
+            (Some(writes_to), _) => match self.spec.files.iter().find(|f| f.path == writes_to) {
+                Some(_) => Target::File(self.expand(writes_to).unwrap_or_default()),
+                None => {
+                    let hint = self
+                        .spec
+                        .sources
+                        .iter()
+                        .find(|source| source.kind == writes_to)
+                        .and_then(|source| source.set_hint)
+                        .map(|hint| {
+                            let bound = meta
+                                .bindings
+                                .iter()
+                                .find(|(kind, _)| *kind == writes_to)
+                                .map_or(meta.key, |(_, key)| key);
+                            // A secret typed on the command line is in the shell's history
+                            // already; it need not be in the error message as well.
+                            let value = value.filter(|_| !meta.sensitive);
+                            hint.replace("{key}", bound)
+                                .replace("{value}", value.unwrap_or("<value>"))
+                        });
+                    return Err(CommandError::NotInAFile {
+                        key: meta.key,
+                        kind: writes_to,
+                        hint,
+                    });
+                }
+            },
+            (None, Scope::Global) => Target::Global,
+            (None, _) if meta.sensitive && self.refuse_secrets => Target::Global,
+            (None, _) => Target::Project,
+        };
+        let (path, layer) = self.target(files, target.unwrap_or(&default))?;
+        let writer = layer
+            .writer(self.registry, &path)
+            .expect("a target is one of its layer's paths");
+        Ok(match self.refuse_secrets {
+            true => writer.refusing_secrets(),
+            false => writer,
+        })
+    }
+
+    /// The file a target names, and the layer that reads it.
+    fn target<'f>(
+        &self,
+        files: &'f [(SpecFile, FileLayer)],
+        target: &Target,
+    ) -> Result<(PathBuf, &'f FileLayer), CommandError> {
+        let of_scope = |scope: FileScope| {
+            files
+                .iter()
+                .filter(move |(file, _)| file.scope == scope)
+                .map(|(_, layer)| layer)
+        };
+        match target {
+            Target::Project => {
+                // The nearest file that is there, and between two in the same directory the
+                // one declared first: `mycli.toml` is what a project shares, and a
+                // `mycli.local.toml` beside it is the user's business to create.
+                let nearest = of_scope(FileScope::Project)
+                    .flat_map(|layer| layer.paths().iter().map(move |path| (path, layer)))
+                    .filter(|(path, _)| path.is_file())
+                    .fold(
+                        None,
+                        |best: Option<(&PathBuf, &FileLayer)>, found| match best {
+                            Some(best)
+                                if best.0.components().count() >= found.0.components().count() =>
+                            {
+                                Some(best)
+                            }
+                            _ => Some(found),
+                        },
+                    );
+                // None there yet, so where the first one would be found from here.
+                let (path, layer) = nearest
+                    .or_else(|| {
+                        of_scope(FileScope::Project)
+                            .next()
+                            .and_then(|layer| Some((layer.paths().last()?, layer)))
+                    })
+                    .ok_or(CommandError::NoFile {
+                        scope: FileScope::Project,
+                    })?;
+                Ok((path.clone(), layer))
+            }
+            Target::Global => of_scope(FileScope::Global)
+                .next_back()
+                .and_then(|layer| Some((layer.paths().last()?.clone(), layer)))
+                .ok_or(CommandError::NoFile {
+                    scope: FileScope::Global,
+                }),
+            Target::File(path) => {
+                let wanted = normalize(&self.cwd.join(path));
+                files
+                    .iter()
+                    .find_map(|(_, layer)| {
+                        let found = layer.paths().iter().find(|p| normalize(p) == wanted)?;
+                        Some((found.clone(), layer))
+                    })
+                    .ok_or_else(|| CommandError::NotDeclared { path: path.clone() })
+            }
+        }
+    }
+
+    /// Open `path` in `$VISUAL`, `$EDITOR`, or `vi`, and wait for it.
+    fn edit(&self, path: &Path) -> Result<(), CommandError> {
+        let editor = self
+            .env
+            .get("VISUAL")
+            .or_else(|| self.env.get("EDITOR"))
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or("vi");
+        let failed = |why: String| CommandError::Editor {
+            editor: editor.to_string(),
+            why,
+        };
+        // `EDITOR="code --wait"` is a command line, not a program name.
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|err| failed(err.to_string()))?;
+        }
+        let status = std::process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .status()
+            .map_err(|err| failed(err.to_string()))?;
+        if !status.success() {
+            return Err(failed(status.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// The settings a `config` key argument completes to, and what each one is for.
+///
+/// The same list usage's spec-side `config_keys` completer offers, so a binary answering for
+/// itself and a shell reading its spec agree: hidden settings are left out, and a deprecated
+/// one is still offered — a config file in the wild names it — but never without saying so.
+pub fn key_candidates(registry: Registry) -> Vec<(&'static str, String)> {
+    registry
+        .props
+        .iter()
+        .filter(|meta| !meta.hide)
+        .map(|meta| {
+            let help = crate::value::one_line(meta.help.unwrap_or_default());
+            let description = match meta.deprecated {
+                Some(_) if help.is_empty() => "deprecated".to_string(),
+                Some(_) => format!("deprecated — {help}"),
+                None => help,
+            };
+            (meta.key, description)
+        })
+        .collect()
+}
+
+/// The values a `config` value argument completes to, given the words before it.
+///
+/// The last of `words` that names a setting decides, as the spec's `config_values` does: its
+/// choices, or `true` and `false` for a boolean. Anything else offers nothing.
+pub fn value_candidates(registry: Registry, words: &[String]) -> Vec<String> {
+    let Some(found) = words
+        .iter()
+        .rev()
+        .filter(|word| !word.starts_with('-'))
+        .find_map(|word| registry.lookup(word))
+    else {
+        return Vec::new();
+    };
+    let meta = registry.get(found.id);
+    if !meta.choices.is_empty() {
+        return meta
+            .choices
+            .iter()
+            .map(|choice| choice.to_value().display())
+            .collect();
+    }
+    match meta.ty {
+        Ty::Bool | Ty::Option(Ty::Bool) => vec!["false".to_string(), "true".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// A spec's `format=` name, when this build reads that format.
+fn named_format(name: &str) -> Option<Format> {
+    match name {
+        #[cfg(feature = "toml")]
+        "toml" => Some(Format::Toml),
+        #[cfg(feature = "json")]
+        "json" => Some(Format::Json),
+        #[cfg(feature = "yaml")]
+        "yaml" | "yml" => Some(Format::Yaml),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod tests {
+    use super::*;
+    use crate::registry::PropMeta;
+    use crate::spec::{PropSpec, SpecSource};
+    use crate::value::Const;
+
+    static PROPS: &[PropMeta] = &[
+        PropMeta {
+            envs: &["MYCLI_JOBS"],
+            default: Some(Const::Int(4)),
+            help: Some("How many jobs to run at once"),
+            ..PropMeta::new("jobs", Ty::Uint)
+        },
+        PropMeta {
+            choices: &[
+                Const::Str("auto"),
+                Const::Str("always"),
+                Const::Str("never"),
+            ],
+            ..PropMeta::new("color", Ty::String)
+        },
+        PropMeta {
+            scope: Scope::Global,
+            ..PropMeta::new("trusted", Ty::Bool)
+        },
+        PropMeta {
+            bindings: &[("git", "mycli.signing")],
+            ..PropMeta::new("signing", Ty::Bool)
+        },
+        PropMeta {
+            hide: true,
+            ..PropMeta::new("internal", Ty::String)
+        },
+        PropMeta {
+            deprecated: Some("use jobs"),
+            renamed_to: Some("jobs"),
+            ..PropMeta::new("threads", Ty::Uint)
+        },
+    ];
+    const REGISTRY: Registry = Registry::new(PROPS);
+    static PROP_SPECS: &[PropSpec] = &[
+        PropSpec::EMPTY,
+        PropSpec::EMPTY,
+        PropSpec::EMPTY,
+        PropSpec {
+            writes_to: Some("git"),
+            ..PropSpec::EMPTY
+        },
+        PropSpec::EMPTY,
+        PropSpec::EMPTY,
+    ];
+    static SOURCES: &[SpecSource] = &[SpecSource {
+        kind: "git",
+        name: None,
+        doc_hint: None,
+        set_hint: Some("git config {key} {value}"),
+    }];
+    static FILES: &[SpecFile] = &[
+        SpecFile {
+            path: "~/.config/mycli/config.toml",
+            findup: false,
+            scope: FileScope::Global,
+            format: None,
+        },
+        SpecFile {
+            path: "mycli.toml",
+            findup: true,
+            scope: FileScope::Project,
+            format: None,
+        },
+        SpecFile {
+            path: "mycli.local.toml",
+            findup: true,
+            scope: FileScope::Project,
+            format: None,
+        },
+    ];
+    const SPEC: ConfigSpec = ConfigSpec::new(PROP_SPECS, SOURCES, FILES);
+
+    /// A home and a checkout with a subdirectory, under a directory of the test's own.
+    fn sandbox(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "usage_config_command_{name}_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("home")).unwrap();
+        std::fs::create_dir_all(dir.join("repo").join("sub")).unwrap();
+        normalize(&dir)
+    }
+
+    fn command(dir: &Path, cwd: &str, env: &[(&str, &str)]) -> ConfigCommand {
+        let home = dir.join("home").display().to_string();
+        let env = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .chain([("HOME".to_string(), home)]);
+        ConfigCommand::new(REGISTRY, SPEC, dir.join(cwd), EnvLayer::new(env))
+    }
+
+    fn get(command: &ConfigCommand, key: &str) -> Result<String, CommandError> {
+        command
+            .run(Action::Get {
+                key: key.to_string(),
+            })
+            .map(|answer| answer.out)
+    }
+
+    fn set(command: &ConfigCommand, key: &str, value: &str, target: Option<Target>) -> String {
+        command
+            .run(Action::Set {
+                key: key.to_string(),
+                value: value.to_string(),
+                target,
+            })
+            .expect("should set")
+            .out
+    }
+
+    #[test]
+    fn a_set_is_what_the_next_get_reads() {
+        let dir = sandbox("set");
+        let cmd = command(&dir, "repo", &[]);
+        assert_eq!(get(&cmd, "jobs").unwrap(), "4");
+
+        // No project file yet, so the first declared one is created where the user stands.
+        let path = dir.join("repo").join("mycli.toml");
+        assert_eq!(
+            set(&cmd, "jobs", "8", None),
+            format!("set jobs to 8 in {}", path.display())
+        );
+        assert_eq!(get(&cmd, "jobs").unwrap(), "8");
+
+        // The environment still outranks the file, and `get` resolves rather than reading one.
+        let cmd = command(&dir, "repo", &[("MYCLI_JOBS", "2")]);
+        assert_eq!(get(&cmd, "jobs").unwrap(), "2");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn the_nearest_project_file_is_the_one_written() {
+        let dir = sandbox("nearest");
+        std::fs::write(dir.join("repo").join("mycli.toml"), "jobs = 2\n").unwrap();
+        std::fs::write(dir.join("repo").join("mycli.local.toml"), "").unwrap();
+        let cmd = command(&dir, "repo/sub", &[]);
+        let answer = cmd
+            .run(Action::Path {
+                target: Some(Target::Project),
+            })
+            .unwrap();
+        // Found from a subdirectory, and the shared file rather than the local one beside it.
+        assert_eq!(
+            answer.out,
+            dir.join("repo").join("mycli.toml").display().to_string()
+        );
+
+        set(&cmd, "color", "never", None);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("repo").join("mycli.toml")).unwrap(),
+            "jobs = 2\ncolor = \"never\"\n"
+        );
+        assert!(!dir.join("repo").join("sub").join("mycli.toml").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_global_setting_goes_to_the_users_own_file() {
+        let dir = sandbox("global");
+        let cmd = command(&dir, "repo", &[]);
+        set(&cmd, "trusted", "true", None);
+        let global = dir.join("home").join(".config/mycli/config.toml");
+        assert_eq!(
+            std::fs::read_to_string(&global).unwrap(),
+            "trusted = true\n"
+        );
+
+        // Asked for explicitly, the project file still refuses it.
+        let err = cmd
+            .run(Action::Set {
+                key: "trusted".into(),
+                value: "true".into(),
+                target: Some(Target::Project),
+            })
+            .unwrap_err();
+        assert!(
+            matches!(err, CommandError::Write(WriteError::OutOfScope { .. })),
+            "{err:?}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_file_is_only_written_when_it_is_one_the_cli_reads() {
+        let dir = sandbox("file");
+        let cmd = command(&dir, "repo", &[]);
+        let err = cmd
+            .run(Action::Unset {
+                key: "jobs".into(),
+                target: Some(Target::File("other.toml".into())),
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "other.toml is not a config file this program reads"
+        );
+
+        // A declared one, named relative to where the user stands.
+        let answer = cmd
+            .run(Action::Set {
+                key: "jobs".into(),
+                value: "3".into(),
+                target: Some(Target::File("mycli.local.toml".into())),
+            })
+            .unwrap();
+        assert!(answer.out.ends_with("mycli.local.toml"), "{}", answer.out);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_setting_kept_elsewhere_says_how_to_set_it() {
+        let dir = sandbox("elsewhere");
+        let err = command(&dir, "repo", &[])
+            .run(Action::Set {
+                key: "signing".into(),
+                value: "true".into(),
+                target: None,
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "signing is not kept in a config file; set it with `git config mycli.signing true`"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reading_commands_answer_with_the_resolution() {
+        let dir = sandbox("read");
+        std::fs::write(
+            dir.join("repo").join("mycli.toml"),
+            "threads = 6\nbogus = 1\n",
+        )
+        .unwrap();
+        let cmd = command(&dir, "repo", &[]);
+
+        let list = cmd.run(Action::List).unwrap();
+        assert_eq!(
+            list.out,
+            "color is unset\njobs = 6\nsigning is unset\ntrusted is unset"
+        );
+        assert_eq!(list.warnings.len(), 3, "{:?}", list.warnings);
+
+        let explained = cmd
+            .run(Action::Explain {
+                key: "threads".into(),
+            })
+            .unwrap();
+        assert!(explained.out.starts_with("threads is now jobs\njobs = 6"));
+
+        assert!(matches!(
+            get(&cmd, "nope"),
+            Err(CommandError::UnknownSetting { .. })
+        ));
+        assert!(matches!(
+            get(&cmd, "color"),
+            Err(CommandError::NotSet { key: "color" })
+        ));
+
+        // Only the files that are there, lowest precedence first.
+        let paths = cmd.run(Action::Path { target: None }).unwrap();
+        assert_eq!(
+            paths.out,
+            dir.join("repo").join("mycli.toml").display().to_string()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn edit_opens_the_file_and_reports_what_it_now_says() {
+        let dir = sandbox("edit");
+        let script = dir.join("editor.sh");
+        std::fs::write(&script, "#!/bin/sh\nprintf 'jobs = \"many\"\\n' > \"$1\"\n").unwrap();
+        let editor = format!("sh {}", script.display());
+        let cmd = command(&dir, "repo", &[("EDITOR", editor.as_str())]);
+        let answer = cmd.run(Action::Edit { target: None }).unwrap();
+        assert_eq!(answer.warnings.len(), 1, "{:?}", answer.warnings);
+
+        let cmd = command(&dir, "repo", &[("VISUAL", "false"), ("EDITOR", "true")]);
+        let err = cmd.run(Action::Edit { target: None }).unwrap_err();
+        assert!(matches!(err, CommandError::Editor { .. }), "{err:?}");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn completion_offers_what_the_spec_would() {
+        let keys = key_candidates(REGISTRY);
+        let names: Vec<&str> = keys.iter().map(|(key, _)| *key).collect();
+        assert_eq!(names, ["jobs", "color", "trusted", "signing", "threads"]);
+        assert_eq!(keys[0].1, "How many jobs to run at once");
+        assert_eq!(keys[4].1, "deprecated");
+
+        let words = |words: &[&str]| words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            value_candidates(REGISTRY, &words(&["--global", "color"])),
+            ["auto", "always", "never"]
+        );
+        assert_eq!(
+            value_candidates(REGISTRY, &words(&["trusted"])),
+            ["false", "true"]
+        );
+        assert!(value_candidates(REGISTRY, &words(&["jobs"])).is_empty());
+        assert!(value_candidates(REGISTRY, &words(&["--file", "x.toml"])).is_empty());
+    }
+}
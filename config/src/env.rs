@@ -18,6 +18,7 @@ use crate::registry::PropId;
 use crate::source::{Origin, SourceKind};
 
 /// Settings read from environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnvLayer {
     /// Keyed by the comparable form of each name, holding the name as it was actually set and its
     /// value. Both are kept because the comparison and the report want different things: one needs
@@ -15,9 +15,11 @@
 
 use std::path::{Component, Path, PathBuf};
 
-use crate::layer::{Layer, LayerCtx, LayerError, LayerOutput};
+use crate::layer::{Entry, Layer, LayerCtx, LayerError, LayerOutput};
+use crate::registry::Registry;
 use crate::source::{FileScope, Origin, SourceKind};
 use crate::value::Value;
+use crate::write::FileWriter;
 
 /// A hook that rewrites a file's text before it is parsed.
 ///
@@ -68,6 +70,8 @@ pub struct FileLayer {
     format: Option<Format>,
     prefix: Option<String>,
     preprocess: Option<Preprocess>,
+    profiles: bool,
+    interpolate: bool,
 }
 
 impl FileLayer {
@@ -79,6 +83,8 @@ impl FileLayer {
             format: None,
             prefix: None,
             preprocess: None,
+            profiles: false,
+            interpolate: false,
         }
     }
 
@@ -130,6 +136,8 @@ impl FileLayer {
             format: None,
             prefix: None,
             preprocess: None,
+            profiles: false,
+            interpolate: false,
         }
     }
 
@@ -162,12 +170,69 @@ impl FileLayer {
         self
     }
 
+    /// Read named profiles too: a `[profiles.<name>]` table in each file, and a sibling file
+    /// named for the profile — `mycli.prod.toml` beside `mycli.toml`.
+    ///
+    /// The profile [`ResolutionContext::with_profile`](crate::ResolutionContext::with_profile)
+    /// selects overlays each file at that file's own precedence: the table first, then the
+    /// sibling, both above the file's base and below the next file in the chain. So a project's
+    /// `[profiles.prod]` beats the project's own base but not the environment, and a user's
+    /// global `[profiles.prod]` cannot outrank the project file's base. Every other profile's
+    /// table is skipped rather than reported: it names settings for a run that is not this one.
+    ///
+    /// The table sits where the settings do, so under [`FileLayer::under`] it is
+    /// `[settings.profiles.prod]`.
+    pub fn with_profiles(mut self) -> Self {
+        self.profiles = true;
+        self
+    }
+
+    /// Expand `${env:NAME}`, `${config:key}` and `~` in every value these files hold, as though
+    /// each setting had asked with `interpolate=#true`.
+    ///
+    /// Whichever of the two opts in, a relative path is read as relative to the file that wrote
+    /// it: `cache_dir = ".cache"` in `~/work/mycli.toml` is `~/work/.cache` wherever the CLI
+    /// was started. A value's text is expanded after it has been read as its declared type, so
+    /// this is for strings and paths and lists of them — a `uint` written `${env:JOBS}` is
+    /// still text where a number belongs.
+    pub fn interpolate(mut self) -> Self {
+        self.interpolate = true;
+        self
+    }
+
     /// The paths this layer will read, farthest first.
     pub fn paths(&self) -> &[PathBuf] {
         &self.paths
     }
 
-    fn read(&self, path: &Path, ctx: &LayerCtx, out: &mut LayerOutput) -> Result<(), LayerError> {
+    /// A writer for one of this layer's files, which writes it the way this layer reads it.
+    ///
+    /// The format, the settings table and the scope all come from here, so what `config set`
+    /// writes is what the next read finds. `None` for a path this layer does not read: a
+    /// setting written where nothing looks is a change that never takes effect.
+    pub fn writer(&self, registry: Registry, path: &Path) -> Option<FileWriter> {
+        if !self.paths.iter().any(|p| p == path) {
+            return None;
+        }
+        let mut writer = FileWriter::new(registry, path, self.scope);
+        if let Some(format) = self.format {
+            writer = writer.as_format(format);
+        }
+        if let Some(prefix) = &self.prefix {
+            writer = writer.under(prefix.clone());
+        }
+        Some(writer)
+    }
+
+    /// Read one file. `profile` names the profile the whole file belongs to, which is what a
+    /// sibling like `mycli.prod.toml` is.
+    fn read(
+        &self,
+        path: &Path,
+        profile: Option<&str>,
+        ctx: &LayerCtx,
+        out: &mut LayerOutput,
+    ) -> Result<(), LayerError> {
         // Absent is the normal case, not a failure: a find-up chain is mostly directories with
         // no config file in them. *Only* absent, though — a file that is there and cannot be
         // read is the case this module's own doc calls an error, and `read_to_string` also
@@ -222,7 +287,16 @@ impl FileLayer {
         // and an *empty* table under a scalar key produced no keys at all, so nothing was set
         // and nothing was said. Handing the table over lets the declared type answer: a `map`
         // takes it, a union takes it, a `uint` refuses it and that refusal is the warning.
-        let names_a_setting = |key: &str| ctx.registry().names_file_value(key);
+        // Inside a profile table the settings are the ones the base declares, so the question
+        // is asked of the key with `profiles.<name>.` taken off: a `map` setting under
+        // `[profiles.prod]` is still a map.
+        let names_a_setting = |key: &str| {
+            let key = match self.profiles {
+                true => in_profile(key).map_or(key, |(_, rest)| rest),
+                false => key,
+            };
+            ctx.registry().names_file_value(key)
+        };
         let flat =
             parse(format, &text, self.prefix.as_deref(), &names_a_setting).map_err(|why| {
                 LayerError::Unreadable {
@@ -230,25 +304,48 @@ impl FileLayer {
                     why,
                 }
             })?;
+        // The base first and the selected profile's table after it, so within one file the
+        // profile is the later writer and wins — whatever order the file wrote them in.
+        let mut base = Vec::with_capacity(flat.len());
+        let mut overlay = Vec::new();
         for (key, found) in flat {
-            let origin = Origin::file(format!("{}#{key}", path.display()), self.scope);
-            match found {
+            match self.profiles.then(|| in_profile(&key)).flatten() {
+                Some((name, rest)) if Some(name) == ctx.profile() => {
+                    overlay.push((rest.to_string(), key.clone(), found, ctx.profile()))
+                }
+                Some(_) => {}
+                None => base.push((key.clone(), key, found, profile)),
+            }
+        }
+        for (key, written, found, profile) in base.into_iter().chain(overlay) {
+            let mut origin = Origin::file(format!("{}#{written}", path.display()), self.scope);
+            if let Some(name) = profile {
+                origin = origin.in_profile(name);
+            }
+            let entry = match found {
                 // Through `entry_for_key`, which is what makes an unknown key a warning,
                 // follows a rename while remembering the name that was written, and reads the
                 // value as the type the spec declares rather than as the type the file used.
-                Read::Text(raw) => match ctx.entry_for_key(&key, &raw, origin) {
-                    Ok(entry) => out.push(entry),
-                    Err(warning) => out.warn(warning),
-                },
+                Read::Text(raw) => ctx.entry_for_key(&key, &raw, origin),
                 // A value the file already gave a shape to — a table the spec declared, or an
                 // array, neither of which any text parser could produce. Through the declared
                 // type all the same: skipping it stored a number inside a
                 // `map<string, string>` without a word, which is the "wrong type costs a
                 // warning" promise broken for the settings that need it most.
-                Read::Shaped(value) => match ctx.entry_from_value(&key, value, origin) {
-                    Ok(entry) => out.push(entry),
-                    Err(warning) => out.warn(warning),
-                },
+                Read::Shaped(value) => ctx.entry_from_value(&key, value, origin),
+            };
+            match entry {
+                // The directory goes on every entry, not only this layer's interpolated ones: a
+                // setting can ask for interpolation of a layer that did not.
+                Ok(entry) => out.push(Entry {
+                    interpolate: self.interpolate,
+                    relative_to: path
+                        .parent()
+                        .filter(|dir| !dir.as_os_str().is_empty())
+                        .map(Path::to_path_buf),
+                    ..entry
+                }),
+                Err(warning) => out.warn(warning),
             }
         }
         Ok(())
@@ -263,14 +360,47 @@ impl Layer for FileLayer {
     fn load(&self, ctx: &LayerCtx) -> Result<LayerOutput, LayerError> {
         let mut out = LayerOutput::new();
         for path in &self.paths {
-            self.read(path, ctx, &mut out)?;
+            self.read(path, None, ctx, &mut out)?;
+            if let (true, Some(profile)) = (self.profiles, ctx.profile()) {
+                if let Some(sibling) = sibling(path, profile) {
+                    self.read(&sibling, Some(profile), ctx, &mut out)?;
+                }
+            }
         }
         Ok(out)
     }
 }
 
+/// `profiles.<name>.<rest>` split into the profile and the setting it names.
+///
+/// `None` for anything else, including `profiles.prod` on its own: that is a value where a table
+/// of settings belongs, and reading it as the base's unknown key is what reports it.
+fn in_profile(key: &str) -> Option<(&str, &str)> {
+    let rest = key.strip_prefix("profiles.")?;
+    let (name, rest) = rest.split_once('.')?;
+    (!name.is_empty() && !rest.is_empty()).then_some((name, rest))
+}
+
+/// The file beside `path` that holds `profile`: `mycli.toml` is `mycli.prod.toml`, and a name
+/// with no extension — `.myclirc` — takes the profile at the end, `.myclirc.prod`.
+///
+/// `None` for a profile name that would lead out of the directory: a name is a name, and
+/// `../x` spliced into a file name is a path somewhere else.
+fn sibling(path: &Path, profile: &str) -> Option<PathBuf> {
+    if profile.contains(['/', '\\']) {
+        return None;
+    }
+    let name = path.file_name()?.to_str()?;
+    let name = match (path.file_stem().and_then(|s| s.to_str()), path.extension()) {
+        (Some(stem), Some(ext)) => format!("{stem}.{profile}.{}", ext.to_str()?),
+        _ => format!("{name}.{profile}"),
+    };
+    Some(path.with_file_name(name))
+}
+
 /// What one key in a file turned out to hold.
-enum Read {
+#[derive(Clone)]
+pub(crate) enum Read {
     /// Text, which the spec's own named parser and declared type will make sense of.
     Text(String),
     /// A value the file already gave a shape to: an array, or a table the spec declared as a
@@ -290,7 +420,7 @@ enum Read {
 /// `parse="list_by_comma"` reads `"a,b"` as two items whether the file said so or not, and a
 /// layer that pre-decided would disagree with the environment about the same setting. Nested
 /// tables become dotted keys, which is the shape the registry is keyed by.
-fn parse(
+pub(crate) fn parse(
     format: Format,
     text: &str,
     prefix: Option<&str>,
@@ -419,7 +549,7 @@ fn parse(
 /// after it is applied, and a component that does not exist cannot be a link to anywhere, so
 /// stepping back out of it lexically is right. Both sides go through here, so a boundary check
 /// compares like with like even when both paths are strange.
-fn normalize(path: &Path) -> PathBuf {
+pub(crate) fn normalize(path: &Path) -> PathBuf {
     if let Ok(real) = path.canonicalize() {
         return real;
     }
@@ -507,7 +637,7 @@ fn flatten_toml(
 /// The values keep the shape the file gave them rather than becoming text: a `map` says what its
 /// values are, and there is no named parser in the middle to reinterpret them.
 #[cfg(feature = "toml")]
-fn table_toml(value: &toml::Value) -> Value {
+pub(crate) fn table_toml(value: &toml::Value) -> Value {
     match value {
         toml::Value::Table(table) => Value::Map(
             table
@@ -818,6 +948,7 @@ mod tests {
             merge: crate::registry::Merge::Deep,
             ..PropMeta::new("url_replacements", Ty::Map(&Ty::String))
         },
+        PropMeta::new("cache_dir", Ty::Path),
     ];
     const REGISTRY: Registry = Registry::new(PROPS);
 
@@ -1339,6 +1470,159 @@ mod tests {
         assert!(err.to_string().contains("wrong.toml"), "{err}");
     }
 
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_profile_overlays_the_file_it_is_written_in() {
+        use crate::resolve::{resolve_with_context, ResolutionContext};
+
+        let tree = Tree::new("profiles");
+        let global = tree.write(
+            "home/ex.toml",
+            "jobs = 1
+
+[profiles.prod]
+jobs = 16
+exclude = [\"global\"]
+",
+        );
+        let project = tree.write(
+            "ex.toml",
+            "jobs = 4
+
+[profiles.prod]
+url_replacements = { a = \"b\" }
+
+[profiles.dev]
+jobs = 2
+nonsense = 1
+",
+        );
+        tree.write(
+            "ex.prod.toml",
+            "exclude = [\"sibling\"]
+",
+        );
+        let global = FileLayer::at(&global, FileScope::Global).with_profiles();
+        let project = FileLayer::at(&project, FileScope::Project).with_profiles();
+        let layers = || Layers::new().then(&project).then(&global);
+
+        // No profile is the base alone, and the tables for other runs are not unknown keys.
+        let resolved = resolve(REGISTRY, layers()).expect("should resolve");
+        assert_eq!(resolved.get_key("jobs"), Some(&Value::Int(4)));
+        assert_eq!(resolved.get_key("exclude"), None);
+        assert!(resolved.warnings.is_empty(), "{:?}", resolved.warnings);
+
+        let resolved = resolve_with_context(
+            REGISTRY,
+            layers(),
+            ResolutionContext::new().with_profile(Some("prod")),
+        )
+        .expect("should resolve");
+        // At the precedence of the file it is in: the global file's profile beats the global
+        // base, and loses to the project file's base all the same.
+        assert_eq!(resolved.get_key("jobs"), Some(&Value::Int(4)));
+        // A table the spec declared is still one inside a profile.
+        assert_eq!(
+            resolved.get_key("url_replacements"),
+            Some(&Value::Map(
+                [("a".to_string(), Value::from("b"))].into_iter().collect()
+            ))
+        );
+        // The sibling file is above its own base, and says which profile it was.
+        assert_eq!(
+            resolved.get_key("exclude"),
+            Some(&Value::List(vec![Value::from("sibling")]))
+        );
+        let origin = resolved.origin_key("exclude").expect("set");
+        assert!(
+            origin.describe().ends_with("ex.prod.toml#exclude"),
+            "{origin:?}"
+        );
+        assert_eq!(origin.profile.as_deref(), Some("prod"));
+        let origin = resolved.origin_key("url_replacements").expect("set");
+        assert!(
+            origin
+                .describe()
+                .ends_with("ex.toml#profiles.prod.url_replacements"),
+            "{origin:?}"
+        );
+        assert_eq!(origin.profile.as_deref(), Some("prod"));
+        // And the selected profile's mistakes are still reported, where another's are not.
+        assert!(resolved.warnings.is_empty(), "{:?}", resolved.warnings);
+        let resolved = resolve_with_context(
+            REGISTRY,
+            layers(),
+            ResolutionContext::new().with_profile(Some("dev")),
+        )
+        .expect("should resolve");
+        assert_eq!(resolved.get_key("jobs"), Some(&Value::Int(2)));
+        assert_eq!(resolved.warnings.len(), 1, "{:?}", resolved.warnings);
+
+        // A layer that was not told about profiles reads the table as the keys it spells.
+        let plain = FileLayer::at(tree.0.join("ex.toml"), FileScope::Project);
+        let resolved = resolve_with_context(
+            REGISTRY,
+            Layers::new().then(&plain),
+            ResolutionContext::new().with_profile(Some("prod")),
+        )
+        .expect("should resolve");
+        assert_eq!(resolved.get_key("exclude"), None);
+        assert!(!resolved.warnings.is_empty());
+    }
+
+    #[test]
+    fn a_profile_file_sits_beside_the_one_it_overlays() {
+        assert_eq!(
+            sibling(Path::new("/a/ex.toml"), "prod"),
+            Some(PathBuf::from("/a/ex.prod.toml"))
+        );
+        assert_eq!(
+            sibling(Path::new("/a/.exrc"), "prod"),
+            Some(PathBuf::from("/a/.exrc.prod"))
+        );
+        assert_eq!(
+            in_profile("profiles.prod.task.output"),
+            Some(("prod", "task.output"))
+        );
+        assert_eq!(in_profile("profiles.prod"), None);
+        assert_eq!(in_profile("jobs"), None);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn an_interpolating_layer_reads_paths_from_where_the_file_is() {
+        use crate::env::EnvLayer;
+        use crate::resolve::{resolve_with_context, ResolutionContext};
+
+        let tree = Tree::new("interpolate");
+        let path = tree.write(
+            "project/ex.toml",
+            "cache_dir = \".cache\"\ntask.output = \"${env:EX_OUT}/${config:cache_dir}\"\n",
+        );
+        let env = EnvLayer::new([("EX_OUT".to_string(), "out".to_string())]);
+        let context = ResolutionContext::new().with_env(&env);
+
+        // Not asked, and the text is what the file says.
+        let plain = FileLayer::at(&path, FileScope::Project);
+        let resolved = resolve_with_context(REGISTRY, Layers::new().then(&plain), context)
+            .expect("should resolve");
+        assert_eq!(resolved.get_key("cache_dir"), Some(&Value::from(".cache")));
+
+        let layer = FileLayer::at(&path, FileScope::Project).interpolate();
+        let resolved = resolve_with_context(REGISTRY, Layers::new().then(&layer), context)
+            .expect("should resolve");
+        assert!(resolved.warnings.is_empty(), "{:?}", resolved.warnings);
+        let cache_dir = path.parent().unwrap().join(".cache").display().to_string();
+        assert_eq!(
+            resolved.get_key("cache_dir"),
+            Some(&Value::from(cache_dir.as_str()))
+        );
+        assert_eq!(
+            resolved.get_key("task.output"),
+            Some(&Value::from(format!("out/{cache_dir}").as_str()))
+        );
+    }
+
     #[cfg(feature = "toml")]
     #[test]
     fn a_key_nobody_knows_is_a_warning_and_the_rest_of_the_file_still_applies() {
@@ -0,0 +1,273 @@
+//! `${env:NAME}`, `${config:key}` and `~` in a value, for a setting or a layer that asks for them.
+//!
+//! mise runs every file through tera with `FileLayer::preprocess`, which is a template engine
+//! for what most adopters want as three small things: a variable from the environment, another
+//! setting, and a path that means the same thing whichever directory the CLI was started in. A
+//! template engine also runs before the file is parsed, so nothing downstream can say what the
+//! user actually wrote — which is the first thing `config explain` is asked about a value nobody
+//! recognizes.
+//!
+//! So this works on values, after each layer has read them, in two passes. The first is per
+//! contribution, because the environment is the same everywhere but "relative to the file that
+//! said it" is a fact about one file. The second is after the merge, because `${config:…}` means
+//! the other setting's *resolved* value, not whatever the same file happened to say about it.
+//!
+//! Only text is expanded. Values are read as their declared type before either pass, so a `uint`
+//! cannot be spelled `${env:JOBS}`: the file would have been refused for holding text where a
+//! number belongs. Strings, paths, URLs and durations, and lists and maps of them, can.
+
+use std::path::Path;
+
+use crate::ty::Ty;
+use crate::value::Value;
+
+/// One piece of a value's text.
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    Text(&'a str),
+    /// `$${`, which is how a value says a literal `${`.
+    Escape,
+    Env(&'a str),
+    Config(&'a str),
+}
+
+/// `text` cut into its pieces, or why it cannot be.
+fn pieces(text: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while let Some(at) = rest.find("${") {
+        if rest[..at].ends_with('$') {
+            if at > 1 {
+                out.push(Piece::Text(&rest[..at - 1]));
+            }
+            out.push(Piece::Escape);
+            rest = &rest[at + 2..];
+            continue;
+        }
+        if at > 0 {
+            out.push(Piece::Text(&rest[..at]));
+        }
+        let Some(end) = rest[at..].find('}') else {
+            return Err(format!("`{}` is never closed", &rest[at..]));
+        };
+        let inner = &rest[at + 2..at + end];
+        out.push(match inner.split_once(':') {
+            Some(("env", name)) if !name.is_empty() => Piece::Env(name),
+            Some(("config", key)) if !key.is_empty() => Piece::Config(key),
+            // Named rather than passed through: `${HOME}` left as it is reads as a path with a
+            // dollar sign in it, and the user who wrote it meant something else.
+            _ => {
+                return Err(format!(
+                    "`${{{inner}}}` is neither `${{env:NAME}}` nor `${{config:key}}`"
+                ))
+            }
+        });
+        rest = &rest[at + end + 1..];
+    }
+    if !rest.is_empty() {
+        out.push(Piece::Text(rest));
+    }
+    Ok(out)
+}
+
+/// Text as the first pass leaves it for the second: a literal `${` kept escaped, so that an
+/// environment variable holding one cannot become a reference.
+fn escaped(text: &str) -> String {
+    text.replace("${", "$${")
+}
+
+/// One contribution with the environment, `~` and relative paths expanded.
+///
+/// `${config:…}` is left where it is, for [`settle`] to fill in once every setting has its
+/// value. Returns whether anything was written in a way that expansion changes — which is when
+/// the text the user wrote is worth keeping beside the value.
+pub(crate) fn expand(
+    value: &Value,
+    ty: Ty,
+    env: &dyn Fn(&str) -> Option<String>,
+    relative_to: Option<&Path>,
+) -> Result<(Value, bool), String> {
+    let mut touched = false;
+    let value = walk(value, ty, &mut |text, path| {
+        let pieces = pieces(text)?;
+        let mut out = String::new();
+        for piece in &pieces {
+            match piece {
+                Piece::Text(text) => out.push_str(&escaped(text)),
+                Piece::Escape => out.push_str("$${"),
+                Piece::Env(name) => match env(name) {
+                    Some(value) => out.push_str(&escaped(&value)),
+                    None => return Err(format!("`{name}` is not set in the environment")),
+                },
+                Piece::Config(key) => out.push_str(&format!("${{config:{key}}}")),
+            }
+        }
+        touched |= pieces.iter().any(|piece| !matches!(piece, Piece::Text(_)));
+        if !path {
+            return Ok(out);
+        }
+        // A path whose root is another setting's is rooted wherever that one is, which is not
+        // a question this pass can answer.
+        if matches!(pieces.first(), Some(Piece::Config(_))) {
+            return Ok(out);
+        }
+        if out == "~" || out.starts_with("~/") || out.starts_with("~\\") {
+            let Some(home) = env("HOME").or_else(|| env("USERPROFILE")) else {
+                return Err("`~` needs `HOME`, and it is not set".to_string());
+            };
+            out = format!("{}{}", escaped(&home), &out[1..]);
+            touched = true;
+        }
+        if let Some(dir) = relative_to.filter(|_| Path::new(&out).is_relative()) {
+            // Joined as text so the escaping above survives, and with the directory escaped in
+            // turn: a checkout under `${x}` is unlikely, but it is a path, not a reference.
+            out = Path::new(&escaped(&dir.display().to_string()))
+                .join(&out)
+                .display()
+                .to_string();
+            touched = true;
+        }
+        Ok(out)
+    })?;
+    Ok((value, touched))
+}
+
+/// The settings a first-pass value refers to, in the order it names them.
+pub(crate) fn references(value: &Value) -> Vec<String> {
+    let mut found = Vec::new();
+    let _ = walk(value, Ty::Any, &mut |text, _| {
+        for piece in pieces(text)? {
+            if let Piece::Config(key) = piece {
+                found.push(key.to_string());
+            }
+        }
+        Ok(text.to_string())
+    });
+    found
+}
+
+/// A first-pass value with each `${config:…}` replaced by what `lookup` says, and its escapes
+/// undone.
+pub(crate) fn settle(
+    value: &Value,
+    env: &dyn Fn(&str) -> Option<String>,
+    lookup: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<Value, String> {
+    walk(value, Ty::Any, &mut |text, _| {
+        let mut out = String::new();
+        for piece in pieces(text)? {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Escape => out.push_str("${"),
+                // Only reached by a value the first pass never saw — a contribution from a layer
+                // that did not ask, merged into one that did.
+                Piece::Env(name) => match env(name) {
+                    Some(value) => out.push_str(&value),
+                    None => return Err(format!("`{name}` is not set in the environment")),
+                },
+                Piece::Config(key) => out.push_str(&lookup(key)?),
+            }
+        }
+        Ok(out)
+    })
+}
+
+/// `value` with `f` applied to each piece of text in it, told whether that text is a path.
+fn walk(
+    value: &Value,
+    ty: Ty,
+    f: &mut dyn FnMut(&str, bool) -> Result<String, String>,
+) -> Result<Value, String> {
+    let ty = ty.inner();
+    let item = match ty {
+        Ty::List(item) | Ty::Set(item) | Ty::Map(item) => *item,
+        _ => Ty::Any,
+    };
+    Ok(match value {
+        Value::String(text) => Value::String(f(text, ty == Ty::Path)?),
+        Value::List(items) => Value::List(
+            items
+                .iter()
+                .map(|value| walk(value, item, f))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Map(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), walk(value, item, f)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/me".to_string()),
+            "TEMPLATE" => Some("${config:x}".to_string()),
+            _ => None,
+        }
+    }
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn a_literal_dollar_brace_is_written_twice() {
+        let (first, touched) = expand(&text("a $${b} c"), Ty::String, &env, None).unwrap();
+        assert!(touched);
+        let settled = settle(&first, &env, &|_| unreachable!()).unwrap();
+        assert_eq!(settled, text("a ${b} c"));
+        // And a lone `$` is only a dollar sign.
+        let (plain, touched) = expand(&text("$5"), Ty::String, &env, None).unwrap();
+        assert!(!touched);
+        assert_eq!(plain, text("$5"));
+    }
+
+    #[test]
+    fn what_the_environment_holds_is_never_read_as_a_reference() {
+        // A variable whose value happens to look like `${config:x}` is text: nobody wrote a
+        // reference, and following one would let the environment reach into other settings.
+        let (first, _) = expand(&text("${env:TEMPLATE}"), Ty::String, &env, None).unwrap();
+        assert!(references(&first).is_empty());
+        let settled = settle(&first, &env, &|_| unreachable!()).unwrap();
+        assert_eq!(settled, text("${config:x}"));
+    }
+
+    #[test]
+    fn a_path_is_rooted_at_home_or_at_the_file_that_said_it() {
+        let dir = Path::new("/work/repo");
+        let expand_path = |raw| expand(&text(raw), Ty::Path, &env, Some(dir)).unwrap().0;
+        assert_eq!(expand_path("~/.cache/x"), text("/home/me/.cache/x"));
+        assert_eq!(expand_path("build"), text("/work/repo/build"));
+        assert_eq!(expand_path("/abs"), text("/abs"));
+        // Rooted by the setting it names, so left for the second pass.
+        assert_eq!(expand_path("${config:root}/x"), text("${config:root}/x"));
+        // A string is not a path, and `~` in it is a tilde.
+        let (string, _) = expand(&text("~/x"), Ty::String, &env, Some(dir)).unwrap();
+        assert_eq!(string, text("~/x"));
+        // Each item of a list of paths is a path.
+        let list = Value::List(vec![text("a"), text("~")]);
+        let (list, _) = expand(&list, Ty::List(&Ty::Path), &env, Some(dir)).unwrap();
+        assert_eq!(
+            list,
+            Value::List(vec![text("/work/repo/a"), text("/home/me")])
+        );
+    }
+
+    #[test]
+    fn what_cannot_be_expanded_says_why() {
+        let fails = |raw| expand(&text(raw), Ty::String, &env, None).unwrap_err();
+        assert_eq!(fails("${env:NOPE}"), "`NOPE` is not set in the environment");
+        assert_eq!(
+            fails("${HOME}"),
+            "`${HOME}` is neither `${env:NAME}` nor `${config:key}`"
+        );
+        assert_eq!(fails("a ${env:HOME"), "`${env:HOME` is never closed");
+    }
+}
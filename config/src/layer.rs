@@ -7,6 +7,8 @@
 //!
 //! [`Registry::bindings`]: crate::Registry::bindings
 
+use std::path::PathBuf;
+
 use crate::registry::{PropId, Registry};
 use crate::source::{Origin, SourceKind};
 use crate::value::Value;
@@ -26,6 +28,14 @@ pub struct Entry {
     pub renamed_from: Option<&'static str>,
     /// The exact canonical key or supported alias a keyed layer matched.
     pub written_key: Option<&'static str>,
+    /// Whether to expand `${env:…}`, `${config:…}` and `~` in this value even though its setting
+    /// did not ask: set by a layer whose whole source opts in, like `FileLayer::interpolate`.
+    pub interpolate: bool,
+    /// The directory a relative path in this value is relative to, when it came from a file.
+    ///
+    /// Only read when the value is interpolated. Otherwise a relative path means what it always
+    /// has — relative to wherever the CLI was started.
+    pub relative_to: Option<PathBuf>,
 }
 
 impl Entry {
@@ -36,6 +46,8 @@ impl Entry {
             origin,
             renamed_from: None,
             written_key: None,
+            interpolate: false,
+            relative_to: None,
         }
     }
 }
@@ -87,6 +99,9 @@ pub enum WarningKind {
     Renamed,
     /// A value that was passed over because another name for the same setting won.
     NotRead,
+    /// A value whose `${env:…}`, `${config:…}` or `~` could not be expanded: a variable that is
+    /// not set, a setting that refers back to itself.
+    Interpolation,
     /// Something a layer of the CLI's own says, which this crate has no name for.
     #[default]
     Other,
@@ -167,17 +182,32 @@ impl std::error::Error for LayerError {}
 /// value the way the *spec* says rather than the way the layer guesses.
 pub struct LayerCtx {
     registry: Registry,
+    profile: Option<String>,
 }
 
 impl LayerCtx {
     pub fn new(registry: Registry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            profile: None,
+        }
+    }
+
+    /// The same context, reading for the named profile.
+    pub fn in_profile(mut self, profile: Option<&str>) -> Self {
+        self.profile = profile.map(str::to_string);
+        self
     }
 
     pub fn registry(&self) -> Registry {
         self.registry
     }
 
+    /// The profile this resolution is for, which a layer that has profiles overlays on its base.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
     /// The setting a dotted key names, following renames.
     pub fn prop(&self, key: &str) -> Option<crate::registry::Lookup> {
         self.registry.lookup(key)
@@ -240,7 +270,11 @@ impl LayerCtx {
                 // adds it — as `explain::warnings` does for every warning — printed the place
                 // twice for exactly the warnings that had bothered to name it.
                 Err(Warning::at(
-                    format!("{key} expected {} but has `{}`", err.expected, err.found),
+                    format!(
+                        "{key} expected {} but has `{}`",
+                        err.expected,
+                        self.quoted(id, err.found)
+                    ),
                     origin,
                 )
                 .of(WarningKind::WrongType))
@@ -265,7 +299,7 @@ impl LayerCtx {
                 format!(
                     "{key} expected one of {} but has `{}`",
                     meta.allowed(),
-                    crate::value::shown(refused)
+                    crate::value::shown_for(meta.sensitive, refused)
                 ),
                 origin.clone(),
             )
@@ -273,6 +307,17 @@ impl LayerCtx {
         )
     }
 
+    /// Text a warning quotes out of a value, unless the setting is a secret.
+    ///
+    /// A token pasted under the wrong key's type is still a token, and a warning is the one line
+    /// a CLI prints without being asked.
+    fn quoted(&self, id: PropId, found: String) -> String {
+        match self.registry.get(id).sensitive {
+            true => crate::value::REDACTED.to_string(),
+            false => found,
+        }
+    }
+
     /// An entry for a dotted key, which is what a layer reading a file has in hand.
     ///
     /// The path worth taking: it looks the key up, follows a rename while remembering the name
@@ -298,7 +343,9 @@ impl LayerCtx {
             Err(err) => Err(Warning::at(
                 format!(
                     "{} expected {} but has `{}`",
-                    found.written, err.expected, err.found
+                    found.written,
+                    err.expected,
+                    self.quoted(found.id, err.found)
                 ),
                 origin,
             )
@@ -338,7 +385,9 @@ impl LayerCtx {
             Err(err) => Err(Warning::at(
                 format!(
                     "{} expected {} but has `{}`",
-                    found.written, err.expected, err.found
+                    found.written,
+                    err.expected,
+                    self.quoted(found.id, err.found)
                 ),
                 origin,
             )
@@ -59,10 +59,13 @@
 //! ```
 
 pub mod cli;
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+pub mod command;
 pub mod env;
 pub mod explain;
 #[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
 pub mod files;
+mod interpolate;
 pub mod layer;
 pub mod props;
 pub mod read;
@@ -72,6 +75,8 @@ pub mod source;
 pub mod spec;
 pub mod ty;
 pub mod value;
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+pub mod write;
 
 pub use cli::CliLayer;
 pub use env::EnvLayer;
@@ -84,6 +89,32 @@ pub use read::{Fold, FromValue, ReadError, ReadErrorKind, ReadErrors};
 pub use registry::{Lookup, Merge, PropId, PropMeta, Registry, Scope};
 pub use resolve::{resolve, resolve_with_context, Layers, ResolutionContext, Resolved};
 pub use source::{FileScope, Origin, SourceKind, Trust};
-pub use spec::{spec_kdl, spec_kdl_with, ConfigSpec, PropSpec, SpecFile, SpecSource};
+pub use spec::{spec_kdl, spec_kdl_with, ConfigSpec, PropSpec, SpecFile, SpecProfiles, SpecSource};
 pub use ty::{Parser, Ty, TypeError};
 pub use value::{Const, Value};
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+pub use write::{Change, Edit, FileWriter, WriteError};
+
+/// Checks that a file format is on, with an explanation when none is.
+///
+/// `#[usage(config_command)]` generates code that reaches into [`command`], which reads and
+/// writes files and so exists only with one of `toml`, `json` or `yaml` — features the
+/// *depending* crate enables. Without this, the failure was `unresolved module command`.
+#[cfg(any(feature = "toml", feature = "json", feature = "yaml"))]
+#[macro_export]
+macro_rules! __usage_needs_file_format {
+    () => {};
+}
+
+/// See [`__usage_needs_file_format`].
+#[cfg(not(any(feature = "toml", feature = "json", feature = "yaml")))]
+#[macro_export]
+macro_rules! __usage_needs_file_format {
+    () => {
+        ::core::compile_error!(
+            "`#[usage(config_command)]` reads and writes config files, and needs one of \
+             usage-config's `toml`, `json` or `yaml` features. Add it where usage-config is \
+             depended on: usage-config = { version = \"…\", features = [\"toml\"] }"
+        );
+    };
+}
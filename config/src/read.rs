@@ -147,6 +147,9 @@ impl Resolved {
     ///
     /// For a CLI reading a handful of settings by hand. Generated code uses [`Resolved::fold`],
     /// which reports every bad value instead of this one.
+    // The error carries the whole `Origin`, profile included, because naming the place is what
+    // it is for; this runs once per setting a CLI reads by hand, not in a loop worth boxing for.
+    #[allow(clippy::result_large_err)]
     pub fn read<T: FromValue>(&self, id: PropId) -> Result<Option<T>, ReadError> {
         let mut fold = self.fold();
         let value = fold.optional(id);
@@ -70,6 +70,17 @@ pub struct PropMeta {
     pub scope: Scope,
     /// How to split a single string into several values, when a layer hands over text.
     pub parse: Option<Parser>,
+    /// Whether its text may say `${env:NAME}`, `${config:key}` and, for a path, `~` — and a
+    /// relative path means relative to the file that wrote it. Off unless the spec says, because
+    /// a `${` that was literal yesterday should not become a reference by upgrading a library.
+    pub interpolate: bool,
+    /// A token or a password: its value is shown as `<redacted>` wherever this crate renders one —
+    /// an explanation, a listing, a warning that would quote it — and `config get`, which is
+    /// somebody asking for exactly that value, is the only place it is printed. A value that
+    /// takes it in through `${config:…}` is redacted too; see [`Resolved::sensitive`].
+    ///
+    /// [`Resolved::sensitive`]: crate::resolve::Resolved::sensitive
+    pub sensitive: bool,
     /// Environment variables that set it, highest precedence first.
     pub envs: &'static [&'static str],
     /// Deprecated environment aliases, consulted after every current name.
@@ -127,6 +138,8 @@ impl PropMeta {
             merge: Merge::Replace,
             scope: Scope::Any,
             parse: None,
+            interpolate: false,
+            sensitive: false,
             envs: &[],
             deprecated_envs: &[],
             cli: &[],
@@ -83,6 +83,9 @@ pub struct Origin {
     pub identifier: String,
     /// How much this place is trusted, which is what the scope check reads.
     pub trust: Trust,
+    /// The named profile this value was read for, when it came from one: a `[profiles.prod]`
+    /// table, or a `mycli.prod.toml` beside `mycli.toml`.
+    pub profile: Option<String>,
 }
 
 impl Origin {
@@ -103,6 +106,7 @@ impl Origin {
             kind,
             identifier: identifier.into(),
             trust,
+            profile: None,
         }
     }
 
@@ -124,9 +128,19 @@ impl Origin {
                 FileScope::Project => Trust::Project,
                 FileScope::Global | FileScope::System => Trust::Operator,
             },
+            profile: None,
         }
     }
 
+    /// The same origin, read for the named profile.
+    ///
+    /// The trust is left alone: a profile overlays the file it lives in, so a project file's
+    /// `[profiles.prod]` is exactly as much a thing a repository can carry as the file is.
+    pub fn in_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
     /// The declared default.
     ///
     /// Named for what it *is* rather than spelled `Default::default`, because an `Origin` has
@@ -9,6 +9,7 @@
 //! spec writer is: this crate has no dependencies, and the grammar being emitted is the small
 //! fixed one the spec parser defines.
 
+use crate::env::EnvLayer;
 use crate::registry::{Merge, PropMeta, Scope};
 use crate::source::FileScope;
 use crate::value::Const;
@@ -52,12 +53,37 @@ pub struct SpecFile {
     pub format: Option<&'static str>,
 }
 
+/// How a named profile is chosen: `profiles env="MYCLI_PROFILE" flag="--profile"`.
+///
+/// A profile is a `[profiles.<name>]` table in a config file, or a sibling file named for it,
+/// overlaid on the file's base — see [`FileLayer::with_profiles`](crate::FileLayer::with_profiles).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpecProfiles {
+    /// The variable that names the profile.
+    pub env: Option<&'static str>,
+    /// The flag that names it, which outranks the variable. Declared by the CLI like any other
+    /// flag; this only says which one it is, for docs and for [`SpecProfiles::select`].
+    pub flag: Option<&'static str>,
+}
+
+impl SpecProfiles {
+    /// The profile in effect: the flag's value when it was given, else the variable's.
+    ///
+    /// The CLI passes what its own parse found for the flag, since this crate never sees the
+    /// command line. An empty value is no profile at all, whichever of the two said it.
+    pub fn select<'a>(&self, flag: Option<&'a str>, env: &'a EnvLayer) -> Option<&'a str> {
+        flag.or_else(|| env.get(self.env?))
+            .filter(|name| !name.is_empty())
+    }
+}
+
 /// Spec-only declarations generated from a settings struct.
 #[derive(Debug, Copy, Clone)]
 pub struct ConfigSpec {
     pub props: &'static [PropSpec],
     pub sources: &'static [SpecSource],
     pub files: &'static [SpecFile],
+    pub profiles: Option<SpecProfiles>,
 }
 
 impl ConfigSpec {
@@ -70,8 +96,15 @@ impl ConfigSpec {
             props,
             sources,
             files,
+            profiles: None,
         }
     }
+
+    /// The same declarations, with named profiles.
+    pub const fn with_profiles(mut self, profiles: SpecProfiles) -> Self {
+        self.profiles = Some(profiles);
+        self
+    }
 }
 
 /// The spec `config` block for these settings, as KDL.
@@ -119,6 +152,16 @@ pub fn spec_kdl_with(props: &[PropMeta], spec: ConfigSpec) -> String {
         }
         out.push('\n');
     }
+    if let Some(profiles) = spec.profiles {
+        out.push_str("    profiles");
+        if let Some(env) = profiles.env {
+            let _ = write!(out, " env={}", quoted(env));
+        }
+        if let Some(flag) = profiles.flag {
+            let _ = write!(out, " flag={}", quoted(flag));
+        }
+        out.push('\n');
+    }
     for (index, meta) in props.iter().enumerate() {
         let prop_spec = spec.props.get(index).copied().unwrap_or(PropSpec::EMPTY);
         let _ = write_prop(&mut out, meta, prop_spec);
@@ -151,6 +194,12 @@ fn write_prop(out: &mut String, meta: &PropMeta, spec: PropSpec) -> std::fmt::Re
     if let Some(parse) = meta.parse {
         write!(out, " parse={}", quoted(parse.name()))?;
     }
+    if meta.interpolate {
+        out.push_str(" interpolate=#true");
+    }
+    if meta.sensitive {
+        out.push_str(" sensitive=#true");
+    }
     match meta.scope {
         Scope::Any => {}
         Scope::Global => out.push_str(" scope=\"global\""),
@@ -337,6 +386,7 @@ mod tests {
             PropMeta {
                 merge: Merge::Union,
                 parse: Some(Parser::ListByComma),
+                interpolate: true,
                 envs: &["HK_EXCLUDE"],
                 bindings: &[("pkl", "exclude"), ("pkl", "defaults.exclude")],
                 ..PropMeta::new("exclude", Ty::List(&Ty::String))
@@ -365,6 +415,7 @@ mod tests {
             // the `prop` node, or a child of it — is exactly what a golden string is for.
             PropMeta {
                 optional: Some(true),
+                sensitive: true,
                 aliases: &["fail-fast.legacy", "failfast"],
                 examples: &["true", "false"],
                 deprecated: Some("use `stop-on-error`"),
@@ -396,7 +447,7 @@ mod tests {
         cli "--jobs" "-j"
         source "git" "hk.jobs"
     }
-    prop "exclude" type="list<string>" merge="union" parse="list_by_comma" {
+    prop "exclude" type="list<string>" merge="union" parse="list_by_comma" interpolate=#true {
         env "HK_EXCLUDE"
         source "pkl" "exclude" "defaults.exclude"
     }
@@ -413,7 +464,7 @@ mod tests {
     prop "ci" type="bool" scope="env" hide=#true {
         env "CI"
     }
-    prop "fail_fast" type="option<bool>" optional=#true deprecated="use `stop-on-error`" deprecated_warn_at="6.0.0" deprecated_remove_at="7.0.0" since="5.2.0" help="Stop at the first failure" long_help="Whether a failing job stops the rest." {
+    prop "fail_fast" type="option<bool>" optional=#true sensitive=#true deprecated="use `stop-on-error`" deprecated_warn_at="6.0.0" deprecated_remove_at="7.0.0" since="5.2.0" help="Stop at the first failure" long_help="Whether a failing job stops the rest." {
         alias "fail-fast.legacy" "failfast"
         example "true"
         example "false"
@@ -489,7 +540,11 @@ mod tests {
                     format: None,
                 },
             ],
-        );
+        )
+        .with_profiles(SpecProfiles {
+            env: Some("EX_PROFILE"),
+            flag: Some("--profile"),
+        });
         assert_eq!(
             spec_kdl_with(PROPS, spec),
             r#"config {
@@ -497,6 +552,7 @@ mod tests {
     source "npmrc" name=".npmrc"
     file "/etc/ex.toml" scope="system" format="toml"
     file "ex.toml" findup=#true
+    profiles env="EX_PROFILE" flag="--profile"
     prop "jobs" type="uint" help_heading="Performance" writes_to="git" {
         x "ex.restart_required" #true
     }
@@ -506,6 +562,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_flag_names_the_profile_before_the_variable_does() {
+        let profiles = SpecProfiles {
+            env: Some("EX_PROFILE"),
+            flag: Some("--profile"),
+        };
+        let env = EnvLayer::new([("EX_PROFILE".to_string(), "staging".to_string())]);
+        assert_eq!(profiles.select(Some("prod"), &env), Some("prod"));
+        assert_eq!(profiles.select(None, &env), Some("staging"));
+        // Exported and empty is no profile, not one called nothing.
+        let env = EnvLayer::new([("EX_PROFILE".to_string(), String::new())]);
+        assert_eq!(profiles.select(None, &env), None);
+        assert_eq!(profiles.select(Some(""), &env), None);
+    }
+
     #[test]
     fn choices_no_single_value_can_hold_leave_no_block_behind() {
         static PROPS: &[PropMeta] = &[PropMeta {
@@ -25,6 +25,21 @@ pub(crate) fn one_line(text: &str) -> String {
     text.replace('\n', "\\n").replace('\r', "\\r")
 }
 
+/// What a `sensitive` setting's value is rendered as.
+///
+/// One fixed word rather than the value with its middle starred out: the first and last few
+/// characters of a token are exactly what a leaked screenshot should not carry, and a length is
+/// a fact about the secret too.
+pub const REDACTED: &str = "<redacted>";
+
+/// [`shown`], or [`REDACTED`] for a value that is a secret.
+pub(crate) fn shown_for(sensitive: bool, value: &Value) -> String {
+    match sensitive {
+        true => REDACTED.to_string(),
+        false => shown(value),
+    }
+}
+
 /// A value as one line of output, with its shape shown when there is nothing in it.
 ///
 /// [`Value::display`] writes a value the way a user would type it, and three values are typed as
@@ -0,0 +1,426 @@
+//! JSON, edited where it stands.
+//!
+//! JSON has no comments to keep, but it has an order and an indentation the user — or the
+//! formatter they run — chose, and a file rewritten from a map loses both. So a member's value
+//! is replaced in place, a new member goes after the last one in its object at the same
+//! indentation, and a removed one takes its comma with it.
+
+use std::ops::Range;
+
+use super::{dotted, nested, splice};
+use crate::value::Value;
+
+struct Doc {
+    members: Vec<Member>,
+    objects: Vec<Object>,
+}
+
+struct Member {
+    path: Vec<String>,
+    /// The key, quotes and all.
+    key: Range<usize>,
+    value: Range<usize>,
+}
+
+struct Object {
+    path: Vec<String>,
+    open: usize,
+    close: usize,
+    /// Indexes into [`Doc::members`], in the order the file has them.
+    members: Vec<usize>,
+}
+
+pub(super) fn set(text: &str, path: &[String], value: &Value) -> Result<String, String> {
+    let Some(doc) = scan(text)? else {
+        // Nothing here yet: a file of its own, the way a formatter would write it.
+        return Ok(format!(
+            "{}\n",
+            render(&nested(path, value.clone()), "", "  ")
+        ));
+    };
+    let unit = doc.unit(text);
+    if let Some(member) = doc.find(path) {
+        let indent = indentation(text, member.key.start);
+        let with = render(value, &indent, &unit);
+        return Ok(splice(text, vec![(member.value.clone(), with)]));
+    }
+    let object = doc
+        .objects
+        .iter()
+        .filter(|o| o.path.len() < path.len() && path.starts_with(&o.path))
+        .max_by_key(|o| o.path.len())
+        .ok_or("the file should be a table of settings, and is not")?;
+    let rest = &path[object.path.len()..];
+    // The way on is through a member that is not an object: a value a write cannot go inside.
+    if rest.len() > 1 {
+        if let Some(member) = doc.find(&path[..object.path.len() + 1]) {
+            return Err(format!("`{}` is not a table", dotted(&member.path)));
+        }
+    }
+    let name = quoted(&rest[0]);
+    let inner = nested(&rest[1..], value.clone());
+    let edit = match object.members.last().map(|i| &doc.members[*i]) {
+        // An object written on one line stays on one line.
+        Some(last) if !text[object.open..last.key.start].contains('\n') => (
+            last.value.end..last.value.end,
+            format!(", {name}: {}", render(&inner, "", "")),
+        ),
+        Some(last) => {
+            let indent = indentation(text, last.key.start);
+            (
+                last.value.end..last.value.end,
+                format!(",\n{indent}{name}: {}", render(&inner, &indent, &unit)),
+            )
+        }
+        None => {
+            let outer = indentation(text, object.open);
+            let indent = format!("{outer}{unit}");
+            (
+                object.open + 1..object.close,
+                format!(
+                    "\n{indent}{name}: {}\n{outer}",
+                    render(&inner, &indent, &unit)
+                ),
+            )
+        }
+    };
+    Ok(splice(text, vec![edit]))
+}
+
+pub(super) fn remove(text: &str, path: &[String]) -> Result<String, String> {
+    let Some(doc) = scan(text)? else {
+        return Ok(text.to_string());
+    };
+    let target = dotted(path);
+    let Some(index) = doc.members.iter().position(|m| dotted(&m.path) == target) else {
+        return Ok(text.to_string());
+    };
+    let object = doc
+        .objects
+        .iter()
+        .find(|o| o.members.contains(&index))
+        .expect("every member is in an object");
+    let at = object
+        .members
+        .iter()
+        .position(|i| *i == index)
+        .expect("found above");
+    let member = &doc.members[index];
+    let range = match (at.checked_sub(1), object.members.get(at + 1)) {
+        // Up to the next key, so the next member takes this one's place and its indentation.
+        (_, Some(next)) => member.key.start..doc.members[*next].key.start,
+        // The last of several: from the end of the one before, comma and all.
+        (Some(prev), None) => doc.members[object.members[prev]].value.end..member.value.end,
+        (None, None) => object.open + 1..object.close,
+    };
+    Ok(splice(text, vec![(range, String::new())]))
+}
+
+impl Doc {
+    fn find(&self, path: &[String]) -> Option<&Member> {
+        let target = dotted(path);
+        self.members.iter().find(|m| dotted(&m.path) == target)
+    }
+
+    /// One level of indentation, as the file does it.
+    fn unit(&self, text: &str) -> String {
+        self.members
+            .iter()
+            .filter(|m| m.path.len() == 1)
+            .map(|m| indentation(text, m.key.start))
+            .find(|indent| !indent.is_empty())
+            .unwrap_or_else(|| "  ".to_string())
+    }
+}
+
+/// The whitespace starting the line `pos` is on.
+fn indentation(text: &str, pos: usize) -> String {
+    let start = text[..pos].rfind('\n').map_or(0, |at| at + 1);
+    text[start..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect()
+}
+
+/// The document's members and objects, or `None` for a file with nothing in it.
+fn scan(text: &str) -> Result<Option<Doc>, String> {
+    let mut scanner = Scanner {
+        text,
+        b: text.as_bytes(),
+        doc: Doc {
+            members: Vec::new(),
+            objects: Vec::new(),
+        },
+    };
+    let start = scanner.ws(0);
+    if start == text.len() {
+        return Ok(None);
+    }
+    let end = scanner.value(start, Some(Vec::new()))?;
+    if scanner.ws(end) != text.len() {
+        return Err(scanner.unexpected(scanner.ws(end)));
+    }
+    Ok(Some(scanner.doc))
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    b: &'a [u8],
+    doc: Doc,
+}
+
+impl Scanner<'_> {
+    fn ws(&self, mut pos: usize) -> usize {
+        while matches!(self.b.get(pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            pos += 1;
+        }
+        pos
+    }
+
+    fn unexpected(&self, pos: usize) -> String {
+        let line = self.text[..pos].matches('\n').count() + 1;
+        match self.text[pos..].chars().next() {
+            Some(c) => format!("unexpected `{c}` on line {line}"),
+            None => "unexpected end of file".to_string(),
+        }
+    }
+
+    /// Where the value at `pos` ends. Objects are recorded when `path` says where they are,
+    /// which is everywhere but inside an array.
+    fn value(&mut self, pos: usize, path: Option<Vec<String>>) -> Result<usize, String> {
+        match self.b.get(pos) {
+            Some(b'{') => self.object(pos, path),
+            Some(b'[') => {
+                let mut p = self.ws(pos + 1);
+                if self.b.get(p) == Some(&b']') {
+                    return Ok(p + 1);
+                }
+                loop {
+                    let end = self.value(p, None)?;
+                    p = self.ws(end);
+                    match self.b.get(p) {
+                        Some(b',') => p = self.ws(p + 1),
+                        Some(b']') => return Ok(p + 1),
+                        _ => return Err(self.unexpected(p)),
+                    }
+                }
+            }
+            Some(b'"') => self.string_end(pos),
+            Some(_) => {
+                let mut p = pos;
+                while p < self.b.len() && !b",]} \t\r\n".contains(&self.b[p]) {
+                    p += 1;
+                }
+                match p == pos {
+                    true => Err(self.unexpected(pos)),
+                    false => Ok(p),
+                }
+            }
+            None => Err(self.unexpected(pos)),
+        }
+    }
+
+    fn object(&mut self, open: usize, path: Option<Vec<String>>) -> Result<usize, String> {
+        let index = path.as_ref().map(|path| {
+            self.doc.objects.push(Object {
+                path: path.clone(),
+                open,
+                close: open,
+                members: Vec::new(),
+            });
+            self.doc.objects.len() - 1
+        });
+        let mut p = self.ws(open + 1);
+        if self.b.get(p) != Some(&b'}') {
+            loop {
+                if self.b.get(p) != Some(&b'"') {
+                    return Err(self.unexpected(p));
+                }
+                let key_end = self.string_end(p)?;
+                let key: String =
+                    serde_json::from_str(&self.text[p..key_end]).map_err(|e| e.to_string())?;
+                let colon = self.ws(key_end);
+                if self.b.get(colon) != Some(&b':') {
+                    return Err(self.unexpected(colon));
+                }
+                let value_start = self.ws(colon + 1);
+                let inner = path.as_ref().map(|path| {
+                    let mut path = path.clone();
+                    path.push(key);
+                    path
+                });
+                let value_end = self.value(value_start, inner.clone())?;
+                if let (Some(index), Some(inner)) = (index, inner) {
+                    self.doc.members.push(Member {
+                        path: inner,
+                        key: p..key_end,
+                        value: value_start..value_end,
+                    });
+                    let member = self.doc.members.len() - 1;
+                    self.doc.objects[index].members.push(member);
+                }
+                p = self.ws(value_end);
+                match self.b.get(p) {
+                    Some(b',') => p = self.ws(p + 1),
+                    Some(b'}') => break,
+                    _ => return Err(self.unexpected(p)),
+                }
+            }
+        }
+        if let Some(index) = index {
+            self.doc.objects[index].close = p;
+        }
+        Ok(p + 1)
+    }
+
+    fn string_end(&self, pos: usize) -> Result<usize, String> {
+        let mut p = pos + 1;
+        loop {
+            match self.b.get(p) {
+                None => return Err("unterminated string".to_string()),
+                Some(b'\\') => p += 2,
+                Some(b'"') => return Ok(p + 1),
+                _ => p += 1,
+            }
+        }
+    }
+}
+
+/// A value as JSON, a table spread over lines at `indent` unless `unit` is empty.
+fn render(value: &Value, indent: &str, unit: &str) -> String {
+    match value {
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => {
+            serde_json::Number::from_f64(*f).map_or_else(|| "null".to_string(), |n| n.to_string())
+        }
+        Value::String(s) => quoted(s),
+        Value::List(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| render(item, "", ""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Map(map) if map.is_empty() => "{}".to_string(),
+        Value::Map(map) if unit.is_empty() => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(k, v)| format!("{}: {}", quoted(k), render(v, "", "")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Map(map) => {
+            let inner = format!("{indent}{unit}");
+            let members = map
+                .iter()
+                .map(|(k, v)| format!("{inner}{}: {}", quoted(k), render(v, &inner, unit)))
+                .collect::<Vec<_>>()
+                .join(",\n");
+            format!("{{\n{members}\n{indent}}}")
+        }
+    }
+}
+
+fn quoted(s: &str) -> String {
+    serde_json::Value::String(s.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(dotted: &str) -> Vec<String> {
+        dotted.split('.').map(String::from).collect()
+    }
+
+    fn set_at(text: &str, at: &str, value: impl Into<Value>) -> String {
+        set(text, &path(at), &value.into()).expect("should set")
+    }
+
+    #[test]
+    fn a_nested_member_is_replaced_where_it_is() {
+        let text =
+            "{\n    \"task\": {\n        \"output\": \"prefix\"\n    },\n    \"jobs\": 4\n}\n";
+        assert_eq!(
+            set_at(text, "task.output", "quiet"),
+            "{\n    \"task\": {\n        \"output\": \"quiet\"\n    },\n    \"jobs\": 4\n}\n"
+        );
+        assert_eq!(
+            set_at(text, "task.color", true),
+            "{\n    \"task\": {\n        \"output\": \"prefix\",\n        \"color\": true\n    },\n    \"jobs\": 4\n}\n"
+        );
+        assert_eq!(
+            set(text, &path("jobs.max"), &Value::Int(1)).unwrap_err(),
+            "`jobs` is not a table"
+        );
+    }
+
+    #[test]
+    fn a_new_table_is_written_at_the_file_s_indentation() {
+        let text = "{\n\t\"jobs\": 4\n}\n";
+        assert_eq!(
+            set_at(text, "task.output", "quiet"),
+            "{\n\t\"jobs\": 4,\n\t\"task\": {\n\t\t\"output\": \"quiet\"\n\t}\n}\n"
+        );
+        assert_eq!(
+            set_at("{}", "task.output", "quiet"),
+            "{\n  \"task\": {\n    \"output\": \"quiet\"\n  }\n}"
+        );
+        assert_eq!(
+            set_at("", "task.output", "quiet"),
+            "{\n  \"task\": {\n    \"output\": \"quiet\"\n  }\n}\n"
+        );
+        // An object on one line stays on one line.
+        assert_eq!(
+            set_at("{\"jobs\": 4}", "task.output", "quiet"),
+            "{\"jobs\": 4, \"task\": {\"output\": \"quiet\"}}"
+        );
+    }
+
+    #[test]
+    fn a_file_with_crlf_endings_keeps_them() {
+        let text = "{\r\n  \"jobs\": 4\r\n}\r\n";
+        assert_eq!(
+            set_at(text, "task.output", "quiet"),
+            "{\r\n  \"jobs\": 4,\r\n  \"task\": {\r\n    \"output\": \"quiet\"\r\n  }\r\n}\r\n"
+        );
+    }
+
+    #[test]
+    fn escapes_are_read_in_keys_and_written_in_values() {
+        let text = "{\"a\\\"b\": \"x\", \"c\\u0064\": 1}";
+        assert_eq!(
+            set(text, &["a\"b".to_string()], &"line\n\"quoted\"".into()).unwrap(),
+            "{\"a\\\"b\": \"line\\n\\\"quoted\\\"\", \"c\\u0064\": 1}"
+        );
+        assert_eq!(
+            set_at(text, "cd", 2),
+            "{\"a\\\"b\": \"x\", \"c\\u0064\": 2}"
+        );
+        // A brace inside a string is not the end of the object.
+        let text = "{\"env\": {\"A\": \"}\"}}";
+        assert_eq!(
+            set_at(text, "env.B", "{"),
+            "{\"env\": {\"A\": \"}\", \"B\": \"{\"}}"
+        );
+    }
+
+    #[test]
+    fn a_removed_member_takes_its_comma_with_it() {
+        let text = "{\n  \"a\": 1,\n  \"b\": [1, 2],\n  \"c\": {\"d\": 3}\n}\n";
+        assert_eq!(
+            remove(text, &path("a")).unwrap(),
+            "{\n  \"b\": [1, 2],\n  \"c\": {\"d\": 3}\n}\n"
+        );
+        assert_eq!(
+            remove(text, &path("c")).unwrap(),
+            "{\n  \"a\": 1,\n  \"b\": [1, 2]\n}\n"
+        );
+        assert_eq!(
+            remove(text, &path("c.d")).unwrap(),
+            "{\n  \"a\": 1,\n  \"b\": [1, 2],\n  \"c\": {}\n}\n"
+        );
+    }
+}
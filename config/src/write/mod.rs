@@ -0,0 +1,1194 @@
+//! Writing a setting back into a config file, and leaving the rest of the file alone.
+//!
+//! What `config set` and `config unset` do. The part that is easy to get wrong is not the
+//! value: it is everything around it. A user's config file has comments explaining why a
+//! setting is what it is, keys in the order they thought about them, and a quoting style of
+//! their own, and a tool that parses the file into a map and serializes the map back has
+//! thrown all of that away for one changed line. So an edit here is a splice into the text as
+//! it stands, and nothing else in the file moves.
+//!
+//! The checks are the ones reading makes, made before anything is written rather than
+//! reported afterwards: a key nobody declared, a value of the wrong type, one the spec's
+//! choices do not allow, and a `scope` the file cannot hold. A warning is the right answer for
+//! a file somebody else wrote; for a value the user is asking to write *now*, it is a refusal,
+//! because a file this CLI wrote should never be one it then warns about.
+//!
+//! Each edit is read back with the same parser [`FileLayer`] uses, and refused if the file now
+//! says anything different from what was asked — about this setting or any other. The editors
+//! only understand as much of each format as they need to find their place in it, and a file
+//! that goes beyond that gets an error that says to edit it by hand rather than a write that
+//! quietly changed something else.
+//!
+//! [`FileLayer`]: crate::FileLayer
+
+#[cfg(feature = "json")]
+mod json_doc;
+#[cfg(feature = "toml")]
+mod toml_doc;
+#[cfg(feature = "yaml")]
+mod yaml_doc;
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::files::{parse, Format, Read};
+use crate::registry::{PropId, PropMeta, Registry, Scope};
+use crate::source::FileScope;
+use crate::ty::Ty;
+use crate::value::{shown, shown_for, Value, REDACTED};
+
+/// What to do to one setting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    /// Write this value, replacing whatever the file held.
+    Set(Value),
+    /// Take the setting out of the file, so the next place down the precedence decides it.
+    Unset,
+    /// Add these items to the list the file holds — the file's own list, not the resolved
+    /// value, so a default or another file's items are not copied into this one.
+    Append(Value),
+}
+
+/// What an edit did to a file.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Change {
+    /// The setting, by its current name.
+    pub key: &'static str,
+    /// The key that was asked for, when it was an old name for `key`.
+    pub renamed_from: Option<&'static str>,
+    /// The file the edit was made in.
+    pub path: PathBuf,
+    /// What the file set it to before, read as the declared type. `None` when it did not.
+    pub before: Option<Value>,
+    /// What the file sets it to now. `None` after an unset.
+    pub after: Option<Value>,
+    /// Other spellings of the same setting taken out of the file along the way — an old name,
+    /// or an alias beside the key being written — so that the value written is the value read.
+    pub removed: Vec<&'static str>,
+    /// Whether the setting is `sensitive`, which [`Change::describe`] reads to leave its values
+    /// out of the line it prints.
+    pub sensitive: bool,
+}
+
+impl Change {
+    /// Whether the file is any different.
+    pub fn changed(&self) -> bool {
+        self.before != self.after || !self.removed.is_empty()
+    }
+
+    /// One line for a user who ran `config set`.
+    pub fn describe(&self) -> String {
+        let path = self.path.display();
+        let shown = |value: &Value| match self.sensitive {
+            true => REDACTED.to_string(),
+            false => shown(value),
+        };
+        match (&self.before, &self.after) {
+            (None, Some(after)) => format!("set {} to {} in {path}", self.key, shown(after)),
+            (Some(before), Some(after)) if before == after => {
+                format!("{} is already {} in {path}", self.key, shown(after))
+            }
+            (Some(before), Some(after)) => format!(
+                "changed {} from {} to {} in {path}",
+                self.key,
+                shown(before),
+                shown(after)
+            ),
+            (Some(before), None) => {
+                format!("removed {} ({}) from {path}", self.key, shown(before))
+            }
+            (None, None) => format!("{} is not set in {path}", self.key),
+        }
+    }
+}
+
+/// Why a setting was not written.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WriteError {
+    /// No setting has this key, under any name.
+    UnknownSetting { key: String },
+    /// The setting's `scope` says this file cannot hold it.
+    OutOfScope {
+        key: &'static str,
+        scope: Scope,
+        path: String,
+    },
+    /// The setting is `sensitive`, the file is one a repository can carry, and the writer was
+    /// asked to refuse that.
+    Secret { key: &'static str, path: String },
+    /// The value cannot be read as the declared type.
+    WrongType {
+        key: &'static str,
+        expected: &'static str,
+        found: String,
+    },
+    /// The value is not one of the setting's choices.
+    NotAllowed {
+        key: &'static str,
+        allowed: String,
+        found: String,
+    },
+    /// An append to a setting that is not a list.
+    NotAList {
+        key: &'static str,
+        expected: &'static str,
+    },
+    /// The file is there and could not be read, or is not valid as it stands.
+    Unreadable { path: String, why: String },
+    /// The file could not be written.
+    Unwritable { path: String, why: String },
+    /// The file says something in a way the editor cannot change without changing more than
+    /// was asked.
+    Unsupported {
+        key: &'static str,
+        path: String,
+        why: String,
+    },
+}
+
+impl std::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSetting { key } => write!(f, "unknown setting `{key}`"),
+            Self::OutOfScope {
+                key,
+                scope: Scope::Global,
+                path,
+            } => write!(
+                f,
+                "{key} cannot be set in {path}: a repository can carry that file, and {key} \
+                 is only read from your own configuration"
+            ),
+            Self::OutOfScope { key, path, .. } => write!(
+                f,
+                "{key} cannot be set in {path}: it is only read from the environment or the \
+                 command line"
+            ),
+            Self::Secret { key, path } => write!(
+                f,
+                "{key} cannot be set in {path}: a repository can carry that file, and {key} \
+                 holds a secret"
+            ),
+            Self::WrongType {
+                key,
+                expected,
+                found,
+            } => write!(f, "{key} expected {expected} but has `{found}`"),
+            Self::NotAllowed {
+                key,
+                allowed,
+                found,
+            } => write!(f, "{key} expected one of {allowed} but has `{found}`"),
+            Self::NotAList { key, expected } => {
+                write!(
+                    f,
+                    "cannot append to {key}, which is {expected} and not a list"
+                )
+            }
+            Self::Unreadable { path, why } => write!(f, "could not read {path}: {why}"),
+            Self::Unwritable { path, why } => write!(f, "could not write {path}: {why}"),
+            Self::Unsupported { key, path, why } => {
+                write!(f, "could not edit {key} in {path} ({why}); edit it by hand")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+/// Edits one config file, one setting at a time.
+///
+/// Built the way a [`FileLayer`] is, and [`FileLayer::writer`] builds one that reads a file the
+/// way its layer does, which is the way to get one for a file the CLI also reads.
+///
+/// [`FileLayer`]: crate::FileLayer
+/// [`FileLayer::writer`]: crate::FileLayer::writer
+pub struct FileWriter {
+    registry: Registry,
+    path: PathBuf,
+    scope: FileScope,
+    format: Option<Format>,
+    prefix: Option<String>,
+    refuse_secrets: bool,
+}
+
+impl FileWriter {
+    /// A writer for the file at `path`, which is a file of the given scope.
+    pub fn new(registry: Registry, path: impl Into<PathBuf>, scope: FileScope) -> Self {
+        Self {
+            registry,
+            path: path.into(),
+            scope,
+            format: None,
+            prefix: None,
+            refuse_secrets: false,
+        }
+    }
+
+    /// Write the file as `format`, whatever its name says.
+    pub fn as_format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Write settings into a table rather than at the top level, as [`FileLayer::under`] reads
+    /// them.
+    ///
+    /// [`FileLayer::under`]: crate::FileLayer::under
+    pub fn under(mut self, table: impl Into<String>) -> Self {
+        self.prefix = Some(table.into());
+        self
+    }
+
+    /// Refuse a `sensitive` setting when this is a project file, as a resolution asked to with
+    /// [`ResolutionContext::refusing_secrets_from_projects`] would refuse it on the next read.
+    ///
+    /// [`ResolutionContext::refusing_secrets_from_projects`]: crate::ResolutionContext::refusing_secrets_from_projects
+    pub fn refusing_secrets(mut self) -> Self {
+        self.refuse_secrets = true;
+        self
+    }
+
+    /// The file this writes.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Set `key` to `value`.
+    ///
+    /// Text is read the way the environment's is, so `config set jobs 8` and
+    /// `config set exclude a,b` write an integer and a list.
+    pub fn set(&self, key: &str, value: impl Into<Value>) -> Result<Change, WriteError> {
+        self.apply(key, Edit::Set(value.into()))
+    }
+
+    /// Take `key` out of the file.
+    pub fn unset(&self, key: &str) -> Result<Change, WriteError> {
+        self.apply(key, Edit::Unset)
+    }
+
+    /// Add `value`'s items to the list at `key`.
+    pub fn append(&self, key: &str, value: impl Into<Value>) -> Result<Change, WriteError> {
+        self.apply(key, Edit::Append(value.into()))
+    }
+
+    /// Make one edit to the file on disk.
+    ///
+    /// A file that is not there is an empty one, and is only created if the edit puts
+    /// something in it: unsetting a key in a file that does not exist leaves it not existing.
+    pub fn apply(&self, key: &str, edit: Edit) -> Result<Change, WriteError> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(WriteError::Unreadable {
+                    path: self.path.display().to_string(),
+                    why: err.to_string(),
+                })
+            }
+        };
+        let (edited, change) = self.edit(&text, key, edit)?;
+        if edited != text {
+            let unwritable = |err: std::io::Error| WriteError::Unwritable {
+                path: self.path.display().to_string(),
+                why: err.to_string(),
+            };
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent).map_err(unwritable)?;
+            }
+            replace(&self.path, &edited).map_err(unwritable)?;
+        }
+        Ok(change)
+    }
+
+    /// The same edit made to text in hand, returning the new text and what changed.
+    ///
+    /// Nothing is read or written, which is what a caller wants to show a diff first — and
+    /// what a test wants.
+    pub fn edit(&self, text: &str, key: &str, edit: Edit) -> Result<(String, Change), WriteError> {
+        let found = self
+            .registry
+            .lookup(key)
+            .ok_or_else(|| WriteError::UnknownSetting {
+                key: key.to_string(),
+            })?;
+        let meta = self.registry.get(found.id);
+        self.in_scope(meta)?;
+        let format = self.format()?;
+
+        // Every name the file might hold this setting under, and what it holds under each, in
+        // the order the file says them — the last one is the one a read keeps.
+        let spellings = self.spellings(found.id);
+        let held: Vec<(&'static str, Read)> = self
+            .flatten(format, text)
+            .map_err(|why| self.unreadable(why))?
+            .into_iter()
+            .filter_map(|(key, read)| {
+                let spelling = spellings.iter().find(|s| **s == key)?;
+                Some((*spelling, read))
+            })
+            .collect();
+        let before = held.last().map(|(_, read)| {
+            value_of(meta, read.clone()).map_err(|err| WriteError::Unsupported {
+                key: meta.key,
+                path: self.path.display().to_string(),
+                why: format!("it holds `{}`, which is not {}", err.found, err.expected),
+            })
+        });
+
+        let after = match edit {
+            Edit::Set(value) => Some(self.checked(meta, value)?),
+            Edit::Unset => None,
+            Edit::Append(value) => {
+                if !matches!(meta.ty.inner(), Ty::List(_) | Ty::Set(_)) {
+                    return Err(WriteError::NotAList {
+                        key: meta.key,
+                        expected: meta.ty.describe(),
+                    });
+                }
+                let Value::List(extra) = self.coerced(meta, value)? else {
+                    unreachable!("a list type coerces to a list")
+                };
+                // Appending needs what is there, so a value in the file that is not a list is
+                // a refusal here rather than something to overwrite.
+                let mut items = match before.clone().transpose()? {
+                    Some(Value::List(items)) => items,
+                    _ => Vec::new(),
+                };
+                for item in extra {
+                    if !(matches!(meta.ty.inner(), Ty::Set(_)) && items.contains(&item)) {
+                        items.push(item);
+                    }
+                }
+                Some(self.checked(meta, Value::List(items))?)
+            }
+        };
+        // Only now: a value the file holds that is the wrong type is something `set` and
+        // `unset` can replace, and only `append` needed to read it.
+        let before = before.and_then(Result::ok);
+
+        // Written where the file already has it when that is a current name, so an alias the
+        // user chose stays theirs. Every other spelling comes out, or it would be read after —
+        // or instead of — the value just written.
+        let current = |spelling: &str| spelling == meta.key || meta.aliases.contains(&spelling);
+        let written = match (&after, held.last()) {
+            (Some(_), Some((spelling, _))) if current(spelling) => Some(*spelling),
+            (Some(_), _) => Some(meta.key),
+            (None, _) => None,
+        };
+        let mut removed: Vec<&'static str> = Vec::new();
+        for (spelling, _) in &held {
+            if Some(*spelling) != written && !removed.contains(spelling) {
+                removed.push(spelling);
+            }
+        }
+
+        let change = Change {
+            key: meta.key,
+            renamed_from: found.renamed_from,
+            path: self.path.clone(),
+            before,
+            after,
+            removed: match written {
+                Some(_) => removed.clone(),
+                // An unset removes every spelling, and reporting them all as extra would say
+                // something besides the setting went.
+                None => removed
+                    .iter()
+                    .copied()
+                    .filter(|s| held.last().map(|(last, _)| last) != Some(s))
+                    .collect(),
+            },
+            sensitive: meta.sensitive,
+        };
+        if !change.changed() {
+            return Ok((text.to_string(), change));
+        }
+
+        let unsupported = |why: String| WriteError::Unsupported {
+            key: meta.key,
+            path: self.path.display().to_string(),
+            why,
+        };
+        let mut edited = text.to_string();
+        for spelling in &removed {
+            edited = remove_in(format, &edited, &self.path_of(spelling)).map_err(unsupported)?;
+        }
+        if let (Some(key), Some(value)) = (written, &change.after) {
+            let depth = self.prefix.iter().count();
+            edited =
+                set_in(format, &edited, &self.path_of(key), value, depth).map_err(unsupported)?;
+        }
+        self.verify(
+            format,
+            meta,
+            text,
+            &edited,
+            &spellings,
+            change.after.as_ref(),
+        )
+        .map_err(unsupported)?;
+        Ok((edited, change))
+    }
+
+    fn format(&self) -> Result<Format, WriteError> {
+        self.format
+            .or_else(|| Format::of(&self.path))
+            .ok_or_else(|| WriteError::Unwritable {
+                path: self.path.display().to_string(),
+                why: "cannot tell what format this is; name it with `as_format`".to_string(),
+            })
+    }
+
+    fn unreadable(&self, why: String) -> WriteError {
+        WriteError::Unreadable {
+            path: self.path.display().to_string(),
+            why,
+        }
+    }
+
+    /// The same check the merge makes, asked of the file before it is written: a value that
+    /// would be refused on the next read is not one to put there.
+    fn in_scope(&self, meta: &PropMeta) -> Result<(), WriteError> {
+        match (meta.scope, self.scope) {
+            (Scope::Env, _) | (Scope::Global, FileScope::Project) => Err(WriteError::OutOfScope {
+                key: meta.key,
+                scope: meta.scope,
+                path: self.path.display().to_string(),
+            }),
+            (Scope::Any, FileScope::Project) if meta.sensitive && self.refuse_secrets => {
+                Err(WriteError::Secret {
+                    key: meta.key,
+                    path: self.path.display().to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `value` read as the setting's type, with its named parser applied to text first.
+    fn coerced(&self, meta: &PropMeta, value: Value) -> Result<Value, WriteError> {
+        let value = match (value, meta.parse) {
+            (Value::String(raw), Some(parser)) => parser.split(&raw),
+            (value, _) => value,
+        };
+        meta.ty.coerce(value).map_err(|err| WriteError::WrongType {
+            key: meta.key,
+            expected: err.expected,
+            found: match meta.sensitive {
+                true => REDACTED.to_string(),
+                false => err.found,
+            },
+        })
+    }
+
+    /// The same, and refused if the spec's choices do not allow it.
+    fn checked(&self, meta: &PropMeta, value: Value) -> Result<Value, WriteError> {
+        let value = self.coerced(meta, value)?;
+        if let Some(refused) = meta.refuses(&value) {
+            return Err(WriteError::NotAllowed {
+                key: meta.key,
+                allowed: meta.allowed(),
+                found: shown_for(meta.sensitive, refused),
+            });
+        }
+        Ok(value)
+    }
+
+    /// Every key a file could hold this setting under: its own, its aliases, and the old names
+    /// a rename leads here from.
+    fn spellings(&self, id: PropId) -> Vec<&'static str> {
+        self.registry
+            .props
+            .iter()
+            .flat_map(|meta| std::iter::once(meta.key).chain(meta.aliases.iter().copied()))
+            .filter(|key| self.registry.lookup(key).map(|found| found.id) == Some(id))
+            .collect()
+    }
+
+    /// Where a key is in the file, as the tables leading to it.
+    fn path_of(&self, key: &str) -> Vec<String> {
+        self.prefix
+            .iter()
+            .map(String::as_str)
+            .chain(key.split('.'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn flatten(&self, format: Format, text: &str) -> Result<Vec<(String, Read)>, String> {
+        // A file that is not there yet is read as empty, and empty is not valid JSON.
+        if text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let names_a_setting = |key: &str| self.registry.names_file_value(key);
+        parse(format, text, self.prefix.as_deref(), &names_a_setting)
+    }
+
+    /// That `after` is `before` with this one setting changed and nothing else.
+    fn verify(
+        &self,
+        format: Format,
+        meta: &PropMeta,
+        before: &str,
+        after: &str,
+        spellings: &[&'static str],
+        expected: Option<&Value>,
+    ) -> Result<(), String> {
+        let split = |text: &str| -> Result<_, String> {
+            let mut others = BTreeMap::new();
+            let mut held = Vec::new();
+            for (key, read) in self.flatten(format, text)? {
+                match spellings.contains(&key.as_str()) {
+                    true => held.push(read),
+                    false => {
+                        others.insert(key, read.into_value());
+                    }
+                }
+            }
+            Ok((others, held))
+        };
+        let (others_before, _) = split(before)?;
+        let (others_after, held) =
+            split(after).map_err(|why| format!("the edit did not parse: {why}"))?;
+        if others_before != others_after {
+            return Err("the edit would have changed other settings".to_string());
+        }
+        let read_back = match held.as_slice() {
+            [] => None,
+            [read] => value_of(meta, read.clone()).ok(),
+            _ => return Err("the setting would be in the file more than once".to_string()),
+        };
+        match read_back.as_ref() == expected {
+            true => Ok(()),
+            false => Err("the file would not read back as the value written".to_string()),
+        }
+    }
+}
+
+/// A value a file holds, read as the setting's declared type — the way a read would.
+fn value_of(meta: &PropMeta, read: Read) -> Result<Value, crate::ty::TypeError> {
+    let value = match read {
+        Read::Text(raw) => match meta.parse {
+            Some(parser) => parser.split(&raw),
+            None => Value::String(raw),
+        },
+        Read::Shaped(value) => value,
+    };
+    meta.ty.coerce(value)
+}
+
+#[cfg_attr(not(feature = "toml"), allow(unused_variables))]
+fn set_in(
+    format: Format,
+    text: &str,
+    path: &[String],
+    value: &Value,
+    depth: usize,
+) -> Result<String, String> {
+    match format {
+        #[cfg(feature = "toml")]
+        Format::Toml => toml_doc::set(text, path, value, depth),
+        #[cfg(feature = "json")]
+        Format::Json => json_doc::set(text, path, value),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => yaml_doc::set(text, path, value),
+    }
+}
+
+fn remove_in(format: Format, text: &str, path: &[String]) -> Result<String, String> {
+    match format {
+        #[cfg(feature = "toml")]
+        Format::Toml => toml_doc::remove(text, path),
+        #[cfg(feature = "json")]
+        Format::Json => json_doc::remove(text, path),
+        #[cfg(feature = "yaml")]
+        Format::Yaml => yaml_doc::remove(text, path),
+    }
+}
+
+/// `text` with each range replaced, the ranges being positions in the original.
+///
+/// Applied from the end backwards so no edit moves another's position. An insertion at the
+/// place a removal starts goes after the removal, so what was inserted survives it. The
+/// editors write `\n`; every edit of every format comes through here, so this is where a file
+/// that ends its lines in `\r\n` gets its own ending back rather than a mix of the two.
+fn splice(text: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(range, _)| std::cmp::Reverse((range.start, range.end)));
+    let crlf = newline(text) == "\r\n";
+    let mut out = text.to_string();
+    for (range, with) in edits {
+        match crlf {
+            true => out.replace_range(range, &with.replace("\r\n", "\n").replace('\n', "\r\n")),
+            false => out.replace_range(range, &with),
+        }
+    }
+    out
+}
+
+/// The line ending the file uses, going by its first line.
+fn newline(text: &str) -> &'static str {
+    match text.find('\n') {
+        Some(at) if text[..at].ends_with('\r') => "\r\n",
+        _ => "\n",
+    }
+}
+
+fn dotted(path: &[String]) -> String {
+    path.join(".")
+}
+
+/// `value` at the end of `path`, as tables leading to it.
+#[cfg(any(feature = "toml", feature = "json"))]
+fn nested(path: &[String], value: Value) -> Value {
+    path.iter().rev().fold(value, |inner, key| {
+        Value::Map([(key.clone(), inner)].into())
+    })
+}
+
+/// `value` written at `path` inside a table, making the tables on the way.
+#[cfg(feature = "toml")]
+fn put(map: &mut BTreeMap<String, Value>, path: &[String], value: Value) {
+    let [first, rest @ ..] = path else { return };
+    if rest.is_empty() {
+        map.insert(first.clone(), value);
+        return;
+    }
+    match map.get_mut(first) {
+        Some(Value::Map(inner)) => put(inner, rest, value),
+        _ => {
+            map.insert(first.clone(), nested(rest, value));
+        }
+    }
+}
+
+/// Whatever is at `path` inside a table, taken out.
+#[cfg(feature = "toml")]
+fn take(map: &mut BTreeMap<String, Value>, path: &[String]) {
+    match path {
+        [] => {}
+        [last] => {
+            map.remove(last);
+        }
+        [first, rest @ ..] => {
+            if let Some(Value::Map(inner)) = map.get_mut(first) {
+                take(inner, rest);
+            }
+        }
+    }
+}
+
+impl Read {
+    /// What the file holds, as it holds it — for comparing two reads of the same file.
+    fn into_value(self) -> Value {
+        match self {
+            Read::Text(raw) => Value::String(raw),
+            Read::Shaped(value) => value,
+        }
+    }
+}
+
+/// Put `text` at `path`, whole.
+///
+/// Written beside the file and renamed over it, so a write cut short leaves the old file
+/// rather than half of the new one — a config file torn mid-way is one nothing can read. The
+/// old file's permissions come along: a file that holds a token was made private on purpose.
+/// Until then the scratch file is readable by its owner alone, so the text is never exposed
+/// more widely than either file allows. A symlink is followed, and the file it points at is
+/// the one replaced — renaming over the link would turn it into a copy.
+fn replace(path: &Path, text: &str) -> std::io::Result<()> {
+    let path = match std::fs::canonicalize(path) {
+        Ok(real) => real,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => path.to_path_buf(),
+        Err(err) => return Err(err),
+    };
+    let tmp = temp_beside(&path);
+    let written = create_private(&tmp).and_then(|mut file| {
+        use std::io::Write;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        match std::fs::metadata(&path) {
+            Ok(old) => std::fs::set_permissions(&tmp, old.permissions())?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        std::fs::rename(&tmp, &path)
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// A new file only its owner can read, so nothing is visible before its permissions are set.
+fn create_private(path: &Path) -> std::io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+/// A scratch path in the file's own directory, so the rename cannot cross a filesystem.
+fn temp_beside(path: &Path) -> PathBuf {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let unique = NEXT.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}-{unique}.tmp", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ty::Parser;
+    use crate::value::Const;
+
+    static PROPS: &[PropMeta] = &[
+        PropMeta {
+            aliases: &["parallelism"],
+            ..PropMeta::new("jobs", Ty::Uint)
+        },
+        PropMeta {
+            parse: Some(Parser::ListByComma),
+            ..PropMeta::new("exclude", Ty::List(&Ty::String))
+        },
+        PropMeta::new("skip", Ty::Set(&Ty::String)),
+        PropMeta {
+            choices: &[
+                Const::Str("git"),
+                Const::Str("patch-file"),
+                Const::Str("none"),
+            ],
+            ..PropMeta::new("stash", Ty::String)
+        },
+        PropMeta::new("task.output", Ty::String),
+        PropMeta::new("task.color", Ty::Bool),
+        PropMeta {
+            renamed_to: Some("task.output"),
+            ..PropMeta::new("output", Ty::String)
+        },
+        PropMeta::new("env", Ty::Map(&Ty::String)),
+        PropMeta {
+            scope: Scope::Global,
+            ..PropMeta::new("trusted", Ty::Bool)
+        },
+        PropMeta {
+            scope: Scope::Env,
+            ..PropMeta::new("token", Ty::String)
+        },
+    ];
+    const REGISTRY: Registry = Registry::new(PROPS);
+
+    fn writer(name: &str) -> FileWriter {
+        FileWriter::new(REGISTRY, name, FileScope::Project)
+    }
+
+    fn edited(name: &str, text: &str, key: &str, edit: Edit) -> String {
+        writer(name).edit(text, key, edit).expect("should edit").0
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_value_is_replaced_and_nothing_around_it_moves() {
+        let text = "# how many at once\njobs = 4 # one per core\n\n[task]\n# prefixed\noutput = 'prefix'\n";
+        assert_eq!(
+            edited("hk.toml", text, "jobs", Edit::Set("8".into())),
+            "# how many at once\njobs = 8 # one per core\n\n[task]\n# prefixed\noutput = 'prefix'\n"
+        );
+        assert_eq!(
+            edited("hk.toml", text, "task.output", Edit::Set("quiet".into())),
+            "# how many at once\njobs = 4 # one per core\n\n[task]\n# prefixed\noutput = \"quiet\"\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_new_key_goes_after_the_last_one_in_its_table() {
+        let text =
+            "jobs = 4\n\n# the task table\n[task]\noutput = \"prefix\"\n\n[tools]\nnode = \"22\"\n";
+        // Into the table it belongs to, not onto the end of the file.
+        assert_eq!(
+            edited("hk.toml", text, "task.color", Edit::Set(true.into())),
+            "jobs = 4\n\n# the task table\n[task]\noutput = \"prefix\"\ncolor = true\n\n[tools]\nnode = \"22\"\n"
+        );
+        // And a top-level key above the first header, under the keys already there.
+        assert_eq!(
+            edited("hk.toml", text, "stash", Edit::Set("git".into())),
+            "jobs = 4\nstash = \"git\"\n\n# the task table\n[task]\noutput = \"prefix\"\n\n[tools]\nnode = \"22\"\n"
+        );
+        // A table the file has no header for is a dotted key where it lands.
+        assert_eq!(
+            edited(
+                "hk.toml",
+                "jobs = 4\n",
+                "task.color",
+                Edit::Set(true.into())
+            ),
+            "jobs = 4\ntask.color = true\n"
+        );
+        // And a file that is not there yet is an empty one.
+        assert_eq!(
+            edited("hk.toml", "", "exclude", Edit::Set("a,b".into())),
+            "exclude = [\"a\", \"b\"]\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn keys_are_found_however_the_file_spells_their_table() {
+        let dotted = "task.output = \"prefix\"\njobs = 4\n";
+        assert_eq!(
+            edited("hk.toml", dotted, "task.output", Edit::Set("quiet".into())),
+            "task.output = \"quiet\"\njobs = 4\n"
+        );
+        // An inline table is one value, and is rewritten as one.
+        let inline = "task = { output = \"prefix\" } # keep\njobs = 4\n";
+        assert_eq!(
+            edited("hk.toml", inline, "task.color", Edit::Set(false.into())),
+            "task = { color = false, output = \"prefix\" } # keep\njobs = 4\n"
+        );
+        assert_eq!(
+            edited("hk.toml", inline, "task.output", Edit::Unset),
+            "task = {} # keep\njobs = 4\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_table_setting_keeps_its_header() {
+        let text = "[env]\n# old\nA = \"1\"\n\n[task]\noutput = \"x\"\n";
+        let value = Value::Map([("B".to_string(), Value::from("2"))].into());
+        assert_eq!(
+            edited("hk.toml", text, "env", Edit::Set(value)),
+            "[env]\nB = \"2\"\n# old\n\n[task]\noutput = \"x\"\n"
+        );
+        assert_eq!(
+            edited("hk.toml", text, "env", Edit::Unset),
+            "[task]\noutput = \"x\"\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn settings_under_a_table_go_into_it_and_it_is_made_when_missing() {
+        let writer = writer("mise.toml").under("settings");
+        let text = "[tools]\nnode = \"22\"\n\n[settings]\njobs = 4\n";
+        assert_eq!(
+            writer
+                .edit(text, "stash", Edit::Set("none".into()))
+                .unwrap()
+                .0,
+            "[tools]\nnode = \"22\"\n\n[settings]\njobs = 4\nstash = \"none\"\n"
+        );
+        // A file with no settings table gets one, with a header, at the end.
+        assert_eq!(
+            writer
+                .edit("[tools]\nnode = \"22\"\n", "jobs", Edit::Set(8.into()))
+                .unwrap()
+                .0,
+            "[tools]\nnode = \"22\"\n\n[settings]\njobs = 8\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn unset_takes_the_line_and_reports_what_was_there() {
+        let text = "jobs = 4 # one per core\nstash = \"git\"\n";
+        let (text, change) = writer("hk.toml").edit(text, "jobs", Edit::Unset).unwrap();
+        assert_eq!(text, "stash = \"git\"\n");
+        assert_eq!(change.before, Some(Value::Int(4)));
+        assert_eq!(change.after, None);
+        assert_eq!(change.describe(), "removed jobs (4) from hk.toml");
+
+        // Nothing to take is not an error, and not a change.
+        let (same, change) = writer("hk.toml").edit(&text, "jobs", Edit::Unset).unwrap();
+        assert_eq!(same, text);
+        assert!(!change.changed());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn an_old_name_is_followed_and_taken_out_of_the_file() {
+        let text = "output = \"prefix\"\njobs = 4\n";
+        let (text, change) = writer("hk.toml")
+            .edit(text, "output", Edit::Set("quiet".into()))
+            .unwrap();
+        // Written under the name a read will look for, with the old one gone so it cannot be
+        // read over the top of it.
+        assert_eq!(text, "jobs = 4\ntask.output = \"quiet\"\n");
+        assert_eq!(change.key, "task.output");
+        assert_eq!(change.renamed_from, Some("output"));
+        assert_eq!(change.before, Some(Value::from("prefix")));
+        assert_eq!(change.removed, vec!["output"]);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn an_alias_the_file_uses_is_edited_where_it_is() {
+        assert_eq!(
+            edited("hk.toml", "parallelism = 4\n", "jobs", Edit::Set(8.into())),
+            "parallelism = 8\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn append_adds_to_the_files_own_list() {
+        let text = "exclude = [\"target\"] # build output\nskip = [\"lint\"]\n";
+        assert_eq!(
+            edited(
+                "hk.toml",
+                text,
+                "exclude",
+                Edit::Append("vendor,dist".into())
+            ),
+            "exclude = [\"target\", \"vendor\", \"dist\"] # build output\nskip = [\"lint\"]\n"
+        );
+        // An array over several lines gets its new items on lines of their own, so the
+        // comments beside the old ones stay beside them.
+        assert_eq!(
+            edited(
+                "hk.toml",
+                "exclude = [\n  \"target\", # build output\n  \"vendor\" # vendored\n]\n",
+                "exclude",
+                Edit::Append("dist".into())
+            ),
+            "exclude = [\n  \"target\", # build output\n  \"vendor\", # vendored\n  \"dist\"\n]\n"
+        );
+        // A set does not take an item it already has.
+        let (same, change) = writer("hk.toml")
+            .edit(text, "skip", Edit::Append("lint".into()))
+            .unwrap();
+        assert_eq!(same, text);
+        assert!(!change.changed());
+        assert_eq!(
+            writer("hk.toml").edit(text, "jobs", Edit::Append(1.into())),
+            Err(WriteError::NotAList {
+                key: "jobs",
+                expected: "a non-negative integer"
+            })
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_write_the_spec_would_refuse_is_refused() {
+        let writer = writer("hk.toml");
+        let refused = |key: &str, value: Value| writer.edit("", key, Edit::Set(value)).unwrap_err();
+        assert_eq!(
+            refused("jobs", "many".into()).to_string(),
+            "jobs expected a non-negative integer but has `many`"
+        );
+        assert_eq!(
+            refused("stash", "stash".into()).to_string(),
+            "stash expected one of git, patch-file, none but has `stash`"
+        );
+        assert_eq!(
+            refused("nope", true.into()),
+            WriteError::UnknownSetting {
+                key: "nope".to_string()
+            }
+        );
+        // A project file is somewhere a repository can carry, so a global setting is not
+        // written there — and an env-only one is written in no file at all.
+        assert!(matches!(
+            refused("trusted", true.into()),
+            WriteError::OutOfScope {
+                key: "trusted",
+                scope: Scope::Global,
+                ..
+            }
+        ));
+        assert!(matches!(
+            refused("token", "x".into()),
+            WriteError::OutOfScope { key: "token", .. }
+        ));
+        let global = FileWriter::new(REGISTRY, "config.toml", FileScope::Global);
+        assert_eq!(
+            global
+                .edit("", "trusted", Edit::Set(true.into()))
+                .unwrap()
+                .0,
+            "trusted = true\n"
+        );
+        assert!(global.edit("", "token", Edit::Set("x".into())).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn an_edit_that_would_change_anything_else_is_refused() {
+        // `task` is a number here, which nothing can be written inside.
+        let err = writer("hk.toml")
+            .edit("task = 3\n", "task.color", Edit::Set(true.into()))
+            .unwrap_err();
+        assert!(matches!(err, WriteError::Unsupported { .. }), "{err:?}");
+        assert!(err.to_string().ends_with("edit it by hand"), "{err}");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_keeps_its_order_and_its_indentation() {
+        let text = "{\n    \"stash\": \"git\",\n    \"jobs\": 4\n}\n";
+        assert_eq!(
+            edited("hk.json", text, "jobs", Edit::Set(8.into())),
+            "{\n    \"stash\": \"git\",\n    \"jobs\": 8\n}\n"
+        );
+        assert_eq!(
+            edited("hk.json", text, "task.color", Edit::Set(true.into())),
+            "{\n    \"stash\": \"git\",\n    \"jobs\": 4,\n    \"task\": {\n        \"color\": true\n    }\n}\n"
+        );
+        // A removed member takes its comma with it, wherever it was.
+        assert_eq!(
+            edited("hk.json", text, "stash", Edit::Unset),
+            "{\n    \"jobs\": 4\n}\n"
+        );
+        assert_eq!(
+            edited("hk.json", text, "jobs", Edit::Unset),
+            "{\n    \"stash\": \"git\"\n}\n"
+        );
+        assert_eq!(
+            edited(
+                "hk.json",
+                "{\"jobs\": 4}",
+                "stash",
+                Edit::Set("none".into())
+            ),
+            "{\"jobs\": 4, \"stash\": \"none\"}"
+        );
+        assert_eq!(
+            edited("hk.json", "", "exclude", Edit::Set("a".into())),
+            "{\n  \"exclude\": [\"a\"]\n}\n"
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_keeps_its_comments_and_its_layout() {
+        let text = "# settings\njobs: 4 # one per core\ntask:\n  # how output looks\n  output: prefix\nexclude:\n- target\n";
+        assert_eq!(
+            edited("hk.yaml", text, "jobs", Edit::Set(8.into())),
+            "# settings\njobs: 8 # one per core\ntask:\n  # how output looks\n  output: prefix\nexclude:\n- target\n"
+        );
+        assert_eq!(
+            edited("hk.yaml", text, "task.color", Edit::Set(true.into())),
+            "# settings\njobs: 4 # one per core\ntask:\n  # how output looks\n  output: prefix\n  color: true\nexclude:\n- target\n"
+        );
+        // A block list stays a block list, at the indentation it was at.
+        assert_eq!(
+            edited("hk.yaml", text, "exclude", Edit::Append("vendor".into())),
+            "# settings\njobs: 4 # one per core\ntask:\n  # how output looks\n  output: prefix\nexclude:\n- target\n- vendor\n"
+        );
+        assert_eq!(
+            edited("hk.yaml", text, "task.output", Edit::Unset),
+            "# settings\njobs: 4 # one per core\ntask:\n  # how output looks\nexclude:\n- target\n"
+        );
+        // Text that would read as something else is quoted.
+        assert_eq!(
+            edited("hk.yaml", "", "task.output", Edit::Set("yes".into())),
+            "task:\n  output: \"yes\"\n"
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_it_cannot_edit_safely_is_left_alone() {
+        let err = writer("hk.yaml")
+            .edit("{jobs: 4}\n", "stash", Edit::Set("git".into()))
+            .unwrap_err();
+        assert!(matches!(err, WriteError::Unsupported { .. }), "{err:?}");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn a_layers_writer_writes_what_the_layer_reads() {
+        use crate::files::FileLayer;
+        use crate::resolve::{resolve, Layers};
+
+        let dir = std::env::temp_dir().join(format!("usage_config_write_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("mise.toml");
+        let layer = FileLayer::at(&path, FileScope::Project).under("settings");
+        assert!(layer.writer(REGISTRY, &dir.join("other.toml")).is_none());
+        let writer = layer
+            .writer(REGISTRY, &path)
+            .expect("a path the layer reads");
+
+        // Unsetting in a file that is not there does not create it.
+        assert!(!writer.unset("jobs").unwrap().changed());
+        assert!(!path.exists());
+
+        let change = writer.set("jobs", "8").unwrap();
+        assert_eq!(
+            change.describe(),
+            format!("set jobs to 8 in {}", path.display())
+        );
+        let resolved = resolve(REGISTRY, Layers::new().then(&layer)).unwrap();
+        assert_eq!(resolved.get_key("jobs"), Some(&Value::Int(8)));
+
+        // The layer's scope came along with it.
+        assert!(writer.set("trusted", true).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(all(feature = "toml", unix))]
+    #[test]
+    fn a_file_is_replaced_whole_and_keeps_its_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("usage_config_replace_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hk.toml");
+        std::fs::write(&path, "jobs = 4\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        writer(path.to_str().unwrap()).set("jobs", "8").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "jobs = 8\n");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Nothing is left beside it.
+        let names: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["hk.toml"]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(all(feature = "toml", unix))]
+    #[test]
+    fn a_symlink_is_followed_and_a_new_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("usage_config_symlink_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("dotfiles")).unwrap();
+        let real = dir.join("dotfiles/hk.toml");
+        std::fs::write(&real, "jobs = 4\n").unwrap();
+        let link = dir.join("hk.toml");
+        std::os::unix::fs::symlink("dotfiles/hk.toml", &link).unwrap();
+
+        writer(link.to_str().unwrap()).set("jobs", "8").unwrap();
+        assert!(std::fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "jobs = 8\n");
+        let names: Vec<_> = std::fs::read_dir(dir.join("dotfiles"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["hk.toml"], "the scratch file was beside the target");
+
+        let new = dir.join("new.toml");
+        replace(&new, "jobs = 1\n").unwrap();
+        let mode = std::fs::metadata(&new).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
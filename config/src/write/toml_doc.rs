@@ -0,0 +1,729 @@
+//! TOML, edited where it stands.
+//!
+//! A scan rather than a parse: enough of the grammar to know where each key's value starts and
+//! ends and which table it is in, so an edit is a splice and every comment, blank line and
+//! quoting choice around it stays as it was. Tables can be written three ways — a header, a
+//! dotted key, an inline table — and a key is found whichever one the file used.
+
+use std::ops::Range;
+
+use super::{dotted, put, splice, take};
+use crate::value::Value;
+
+struct Doc {
+    /// The root first, then each header in the order the file has them.
+    tables: Vec<Table>,
+    pairs: Vec<Pair>,
+}
+
+struct Table {
+    path: Vec<String>,
+    /// The header line, newline and all. Empty for the root.
+    header: Range<usize>,
+    /// Where the next header starts, or the end of the file.
+    end: usize,
+    /// `[[...]]`, whose keys are items of a list rather than settings.
+    array: bool,
+}
+
+struct Pair {
+    /// The index of the table it is under.
+    table: usize,
+    /// The whole path: the table's and then the key's own, dotted parts and all.
+    path: Vec<String>,
+    /// The line it is on, from its indentation to its newline.
+    line: Range<usize>,
+    value: Range<usize>,
+}
+
+pub(super) fn set(
+    text: &str,
+    path: &[String],
+    value: &Value,
+    depth: usize,
+) -> Result<String, String> {
+    let doc = scan(text)?;
+    if let Some(holder) = doc.holder(path) {
+        return inline(text, holder, path, Some(value));
+    }
+    let (tables, pairs) = doc.owned(path)?;
+    // The common case, and the one that touches the least: a key that is already a key.
+    if let ([], [pair]) = (tables.as_slice(), pairs.as_slice()) {
+        if pair.path == path {
+            let edits = extend(text, pair, value)
+                .unwrap_or_else(|| vec![(pair.value.clone(), render(value))]);
+            return Ok(splice(text, edits));
+        }
+    }
+    // A table written under its own header keeps the header, and its keys are written afresh
+    // beneath it: `[env]` is where the user put it, and an inline table in its place would be
+    // a rewrite of their file rather than an edit to it.
+    let keep = match value {
+        Value::Map(map) if !map.is_empty() => {
+            tables.iter().copied().find(|i| doc.tables[*i].path == path)
+        }
+        _ => None,
+    };
+    let mut edits = doc.cuts(text, &tables, &pairs, keep);
+    match (keep, value) {
+        (Some(i), Value::Map(map)) => {
+            let at = doc.tables[i].header.end;
+            let body = map
+                .iter()
+                .map(|(k, v)| format!("{} = {}\n", key(k), render(v)))
+                .collect::<String>();
+            edits.push((at..at, body));
+        }
+        _ => edits.push(doc.insertion(text, path, value, depth)),
+    }
+    Ok(splice(text, edits))
+}
+
+pub(super) fn remove(text: &str, path: &[String]) -> Result<String, String> {
+    let doc = scan(text)?;
+    if let Some(holder) = doc.holder(path) {
+        return inline(text, holder, path, None);
+    }
+    let (tables, pairs) = doc.owned(path)?;
+    Ok(splice(text, doc.cuts(text, &tables, &pairs, None)))
+}
+
+impl Doc {
+    /// A key whose value is an inline table with `path` somewhere inside it.
+    fn holder(&self, path: &[String]) -> Option<&Pair> {
+        self.pairs.iter().find(|pair| {
+            !self.tables[pair.table].array
+                && pair.path.len() < path.len()
+                && path.starts_with(&pair.path)
+        })
+    }
+
+    /// The tables and keys at `path` or under it: what a value there replaces.
+    fn owned(&self, path: &[String]) -> Result<(Vec<usize>, Vec<&Pair>), String> {
+        let tables: Vec<usize> = (1..self.tables.len())
+            .filter(|i| self.tables[*i].path.starts_with(path))
+            .collect();
+        if tables.iter().any(|i| self.tables[*i].array) {
+            return Err(format!("`{}` is an array of tables", dotted(path)));
+        }
+        let pairs = self
+            .pairs
+            .iter()
+            .filter(|pair| !self.tables[pair.table].array && pair.path.starts_with(path))
+            .collect();
+        Ok((tables, pairs))
+    }
+
+    /// Removals for everything owned: a table whole, from its header to the next one, and a key
+    /// by its line — unless it is inside a table going whole already.
+    ///
+    /// The comments right above the next header are that table's, not this one's: `# tools I
+    /// use` over `[tools]` stays when the table before it goes.
+    fn cuts(
+        &self,
+        text: &str,
+        tables: &[usize],
+        pairs: &[&Pair],
+        keep: Option<usize>,
+    ) -> Vec<(Range<usize>, String)> {
+        let gone = |i: usize| tables.contains(&i) && Some(i) != keep;
+        let end = |i: usize| {
+            let table = &self.tables[i];
+            if i + 1 == self.tables.len() || gone(i + 1) {
+                return table.end;
+            }
+            let last = self
+                .pairs
+                .iter()
+                .filter(|pair| pair.table == i)
+                .map(|pair| pair.line.end)
+                .max()
+                .unwrap_or(table.header.end);
+            let mut end = table.end;
+            while end > last {
+                let start = text[last..end - 1]
+                    .rfind('\n')
+                    .map_or(last, |at| last + at + 1);
+                if !text[start..end].trim_start().starts_with('#') {
+                    break;
+                }
+                end = start;
+            }
+            end
+        };
+        let mut edits: Vec<(Range<usize>, String)> = tables
+            .iter()
+            .filter(|i| gone(**i))
+            .map(|i| (self.tables[*i].header.start..end(*i), String::new()))
+            .collect();
+        edits.extend(
+            pairs
+                .iter()
+                .filter(|pair| !gone(pair.table))
+                .map(|pair| (pair.line.clone(), String::new())),
+        );
+        edits
+    }
+
+    /// Where a new key goes, and the text that goes there.
+    ///
+    /// Under the deepest table it belongs to, after that table's last key — which is where a
+    /// person adding it by hand would put it, and keeps it out of the comments that tend to
+    /// sit above the next header.
+    fn insertion(
+        &self,
+        text: &str,
+        path: &[String],
+        value: &Value,
+        depth: usize,
+    ) -> (Range<usize>, String) {
+        let (index, table) = self
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.array && t.path.len() < path.len() && path.starts_with(&t.path))
+            .max_by_key(|(_, t)| t.path.len())
+            .expect("the root is a table every path starts with");
+        // The table a layer reads settings from, when it is not there yet, gets a header of its
+        // own at the end: `[settings]` with a key under it, not `settings.jobs = 8` at the top
+        // of a file whose every other table has a header. Unless the file already writes it
+        // with dotted keys, which a second spelling would make a duplicate table.
+        let dotted_already = self
+            .pairs
+            .iter()
+            .any(|pair| pair.table == index && pair.path.starts_with(&path[..depth]));
+        if table.path.len() < depth && !dotted_already {
+            let mut out = String::new();
+            if !text.is_empty() {
+                if !text.ends_with('\n') {
+                    out.push('\n');
+                }
+                if !text.ends_with("\n\n") && !text.ends_with("\n\r\n") {
+                    out.push('\n');
+                }
+            }
+            out.push_str(&format!(
+                "[{}]\n{} = {}\n",
+                keys(&path[..depth]),
+                keys(&path[depth..]),
+                render(value)
+            ));
+            return (text.len()..text.len(), out);
+        }
+        let last = self
+            .pairs
+            .iter()
+            .filter(|pair| pair.table == index)
+            .map(|pair| pair.line.end)
+            .max();
+        let at = match last {
+            Some(end) => end,
+            None if index == 0 => table.end,
+            None => table.header.end,
+        };
+        let mut out = String::new();
+        if !text[..at].is_empty() && !text[..at].ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "{} = {}\n",
+            keys(&path[table.path.len()..]),
+            render(value)
+        ));
+        // The first key of a file that has only tables goes above them, with the blank line a
+        // person would leave.
+        if index == 0 && last.is_none() && at < text.len() {
+            out.push('\n');
+        }
+        (at..at, out)
+    }
+}
+
+/// An edit inside an inline table, which is rewritten whole: it is one value, and one line.
+fn inline(
+    text: &str,
+    holder: &Pair,
+    path: &[String],
+    value: Option<&Value>,
+) -> Result<String, String> {
+    let parsed: toml::Table = toml::from_str(&format!("v = {}", &text[holder.value.clone()]))
+        .map_err(|e| e.to_string())?;
+    let mut held = crate::files::table_toml(&parsed["v"]);
+    let Value::Map(map) = &mut held else {
+        return Err(format!("`{}` is not a table", dotted(&holder.path)));
+    };
+    let rest = &path[holder.path.len()..];
+    match value {
+        Some(value) => put(map, rest, value.clone()),
+        None => take(map, rest),
+    }
+    Ok(splice(text, vec![(holder.value.clone(), render(&held))]))
+}
+
+/// A list that is the one already there with items on the end, as those items added to it.
+///
+/// Only for an array written over several lines, where rewriting it whole would lose the
+/// comments between its items and the line each is on. An array on one line is one value either
+/// way, and is simply replaced.
+fn extend(text: &str, pair: &Pair, value: &Value) -> Option<Vec<(Range<usize>, String)>> {
+    let Value::List(items) = value else {
+        return None;
+    };
+    let raw = &text[pair.value.clone()];
+    if !raw.starts_with('[') || !raw.contains('\n') {
+        return None;
+    }
+    let parsed: toml::Table = toml::from_str(&format!("v = {raw}")).ok()?;
+    let Value::List(held) = crate::files::table_toml(&parsed["v"]) else {
+        return None;
+    };
+    if held.is_empty() || items.len() <= held.len() || !items.starts_with(&held) {
+        return None;
+    }
+    // The last thing in the array that is not whitespace or a comment: the end of its last
+    // item, or the comma after it.
+    let b = text.as_bytes();
+    let close = pair.value.end - 1;
+    let mut p = pair.value.start + 1;
+    let mut last = None;
+    while p < close {
+        match b[p] {
+            b'"' | b'\'' => {
+                p = string_end(b, p).ok()?;
+                last = Some(p - 1);
+                continue;
+            }
+            b'#' => {
+                p = next_line(b, p);
+                continue;
+            }
+            c if c.is_ascii_whitespace() => {}
+            _ => last = Some(p),
+        }
+        p += 1;
+    }
+    let last = last?;
+    // The closing bracket on a line of its own, or this is not the layout being kept.
+    let after = next_line(b, last);
+    if after > close {
+        return None;
+    }
+    let line = text[..last].rfind('\n').map_or(0, |at| at + 1);
+    let indent: String = text[line..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let trailing = b[last] == b',';
+    let added = &items[held.len()..];
+    let lines = added
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let comma = if trailing || i + 1 < added.len() {
+                ","
+            } else {
+                ""
+            };
+            format!("{indent}{}{comma}\n", render(item))
+        })
+        .collect::<String>();
+    let mut edits = vec![(after..after, lines)];
+    if !trailing {
+        edits.push((last + 1..last + 1, ",".to_string()));
+    }
+    Some(edits)
+}
+
+fn scan(text: &str) -> Result<Doc, String> {
+    let b = text.as_bytes();
+    let mut doc = Doc {
+        tables: vec![Table {
+            path: Vec::new(),
+            header: 0..0,
+            end: text.len(),
+            array: false,
+        }],
+        pairs: Vec::new(),
+    };
+    let mut pos = 0;
+    while pos < b.len() {
+        let start = pos;
+        pos = blank(b, pos);
+        match b.get(pos) {
+            None => break,
+            Some(b'\n' | b'\r') => pos += 1,
+            Some(b'#') => pos = next_line(b, pos),
+            Some(b'[') => {
+                let array = b.get(pos + 1) == Some(&b'[');
+                let (path, after) = keys_at(text, pos + if array { 2 } else { 1 })?;
+                let close = if array { "]]" } else { "]" };
+                if !text[after..].starts_with(close) {
+                    return Err(unexpected(text, after));
+                }
+                pos = end_of_line(text, after + close.len())?;
+                let last = doc.tables.len() - 1;
+                doc.tables[last].end = start;
+                doc.tables.push(Table {
+                    path,
+                    header: start..pos,
+                    end: text.len(),
+                    array,
+                });
+            }
+            Some(_) => {
+                let (key, after) = keys_at(text, pos)?;
+                if b.get(after) != Some(&b'=') {
+                    return Err(unexpected(text, after));
+                }
+                let value_start = blank(b, after + 1);
+                let value_end = value_end(text, value_start)?;
+                pos = end_of_line(text, value_end)?;
+                let table = doc.tables.len() - 1;
+                let mut path = doc.tables[table].path.clone();
+                path.extend(key);
+                doc.pairs.push(Pair {
+                    table,
+                    path,
+                    line: start..pos,
+                    value: value_start..value_end,
+                });
+            }
+        }
+    }
+    Ok(doc)
+}
+
+/// A dotted key starting at `pos`, and where it ends, past any whitespace after it.
+fn keys_at(text: &str, pos: usize) -> Result<(Vec<String>, usize), String> {
+    let b = text.as_bytes();
+    let mut keys = Vec::new();
+    let mut pos = pos;
+    loop {
+        pos = blank(b, pos);
+        let (key, after) = match b.get(pos) {
+            Some(b'"') => {
+                let end = string_end(b, pos)?;
+                let parsed: toml::Table = toml::from_str(&format!("k = {}", &text[pos..end]))
+                    .map_err(|e| e.to_string())?;
+                let key = parsed["k"].as_str().unwrap_or_default().to_string();
+                (key, end)
+            }
+            Some(b'\'') => {
+                let end = string_end(b, pos)?;
+                (text[pos + 1..end - 1].to_string(), end)
+            }
+            _ => {
+                let len = b[pos..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == b'_' || **c == b'-')
+                    .count();
+                if len == 0 {
+                    return Err(unexpected(text, pos));
+                }
+                (text[pos..pos + len].to_string(), pos + len)
+            }
+        };
+        keys.push(key);
+        pos = blank(b, after);
+        match b.get(pos) {
+            Some(b'.') => pos += 1,
+            _ => return Ok((keys, pos)),
+        }
+    }
+}
+
+/// Where the value starting at `pos` ends: after its closing quote or bracket, or before the
+/// comment or newline that ends a bare one.
+fn value_end(text: &str, pos: usize) -> Result<usize, String> {
+    let b = text.as_bytes();
+    match b.get(pos) {
+        Some(b'"' | b'\'') => string_end(b, pos),
+        Some(b'[' | b'{') => {
+            let mut depth = 0;
+            let mut p = pos;
+            loop {
+                match b.get(p) {
+                    None => return Err(format!("unclosed `{}`", b[pos] as char)),
+                    Some(b'"' | b'\'') => {
+                        p = string_end(b, p)?;
+                        continue;
+                    }
+                    Some(b'#') => {
+                        p = next_line(b, p);
+                        continue;
+                    }
+                    Some(b'[' | b'{') => depth += 1,
+                    Some(b']' | b'}') => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(p + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                p += 1;
+            }
+        }
+        _ => {
+            let mut p = pos;
+            while p < b.len() && b[p] != b'\n' && b[p] != b'#' {
+                p += 1;
+            }
+            while p > pos && matches!(b[p - 1], b' ' | b'\t' | b'\r') {
+                p -= 1;
+            }
+            match p == pos {
+                true => Err(unexpected(text, pos)),
+                false => Ok(p),
+            }
+        }
+    }
+}
+
+/// Where the string whose opening quote is at `pos` ends, multi-line strings included.
+fn string_end(b: &[u8], pos: usize) -> Result<usize, String> {
+    let quote = b[pos];
+    let triple = b[pos..].starts_with(&[quote; 3]);
+    let mut p = pos + if triple { 3 } else { 1 };
+    loop {
+        match b.get(p) {
+            None => return Err("unterminated string".to_string()),
+            Some(b'\\') if quote == b'"' => p += 2,
+            Some(c) if *c == quote => {
+                if !triple {
+                    return Ok(p + 1);
+                }
+                if b[p..].starts_with(&[quote; 3]) {
+                    // Up to two more quotes belong to the string, which may end in them.
+                    let mut end = p + 3;
+                    while end < b.len() && b[end] == quote && end < p + 5 {
+                        end += 1;
+                    }
+                    return Ok(end);
+                }
+                p += 1;
+            }
+            Some(b'\n') if !triple => return Err("unterminated string".to_string()),
+            _ => p += 1,
+        }
+    }
+}
+
+fn blank(b: &[u8], mut pos: usize) -> usize {
+    while matches!(b.get(pos), Some(b' ' | b'\t')) {
+        pos += 1;
+    }
+    pos
+}
+
+/// The start of the line after the one `pos` is on.
+fn next_line(b: &[u8], pos: usize) -> usize {
+    match b[pos..].iter().position(|c| *c == b'\n') {
+        Some(at) => pos + at + 1,
+        None => b.len(),
+    }
+}
+
+/// The start of the next line, when all that is left of this one is a comment.
+fn end_of_line(text: &str, pos: usize) -> Result<usize, String> {
+    let b = text.as_bytes();
+    let pos = blank(b, pos);
+    match b.get(pos) {
+        None => Ok(b.len()),
+        Some(b'#' | b'\n' | b'\r') => Ok(next_line(b, pos)),
+        Some(_) => Err(unexpected(text, pos)),
+    }
+}
+
+fn unexpected(text: &str, pos: usize) -> String {
+    let line = text[..pos].matches('\n').count() + 1;
+    match text[pos..].chars().next() {
+        Some(c) => format!("unexpected `{c}` on line {line}"),
+        None => format!("unexpected end of file on line {line}"),
+    }
+}
+
+/// A value as TOML writes it, on one line.
+fn render(value: &Value) -> String {
+    match value {
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => "nan".to_string(),
+        Value::Float(f) if f.is_infinite() => match *f > 0.0 {
+            true => "inf".to_string(),
+            false => "-inf".to_string(),
+        },
+        Value::Float(_) => value.display(),
+        Value::String(s) => quoted(s),
+        Value::List(items) => format!(
+            "[{}]",
+            items.iter().map(render).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Map(map) if map.is_empty() => "{}".to_string(),
+        Value::Map(map) => format!(
+            "{{ {} }}",
+            map.iter()
+                .map(|(k, v)| format!("{} = {}", key(k), render(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn quoted(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn key(k: &str) -> String {
+    let bare = !k.is_empty()
+        && k.bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-');
+    match bare {
+        true => k.to_string(),
+        false => quoted(k),
+    }
+}
+
+fn keys(path: &[String]) -> String {
+    path.iter().map(|k| key(k)).collect::<Vec<_>>().join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn path(dotted: &str) -> Vec<String> {
+        dotted.split('.').map(String::from).collect()
+    }
+
+    fn set_at(text: &str, at: &str, value: impl Into<Value>) -> String {
+        set(text, &path(at), &value.into(), 0).expect("should set")
+    }
+
+    #[test]
+    fn a_dotted_key_is_found_and_written_beside() {
+        let text = "# task\ntask.output = 'prefix' # why\njobs = 4\n";
+        assert_eq!(
+            set_at(text, "task.output", "quiet"),
+            "# task\ntask.output = \"quiet\" # why\njobs = 4\n"
+        );
+        assert_eq!(
+            set_at(text, "task.color", true),
+            "# task\ntask.output = 'prefix' # why\njobs = 4\ntask.color = true\n"
+        );
+        assert_eq!(
+            remove(text, &path("task.output")).unwrap(),
+            "# task\njobs = 4\n"
+        );
+        // Under a header, the dotted part is the key's own.
+        let text = "[task]\nrun.shell = \"bash\"\n";
+        assert_eq!(
+            set_at(text, "task.run.shell", "zsh"),
+            "[task]\nrun.shell = \"zsh\"\n"
+        );
+    }
+
+    #[test]
+    fn an_inline_table_is_rewritten_on_its_own_line() {
+        let text = "env = { A = \"1\", \"B.C\" = \"2\" } # vars\nnext = 1\n";
+        assert_eq!(
+            set_at(text, "env.A", "3"),
+            "env = { A = \"3\", \"B.C\" = \"2\" } # vars\nnext = 1\n"
+        );
+        assert_eq!(
+            remove(text, &path("env.A")).unwrap(),
+            "env = { \"B.C\" = \"2\" } # vars\nnext = 1\n"
+        );
+        let mut map = BTreeMap::new();
+        map.insert("x".to_string(), Value::Int(1));
+        assert_eq!(set_at("", "env", Value::Map(map)), "env = { x = 1 }\n");
+    }
+
+    #[test]
+    fn an_array_of_tables_is_not_a_setting() {
+        let text = "[[tools]]\nname = \"node\"\njobs = 1\n";
+        let err = set(text, &path("tools"), &Value::Int(1), 0).unwrap_err();
+        assert_eq!(err, "`tools` is an array of tables");
+        // A key under one is an item's, not the root's, so the root's goes above it.
+        assert_eq!(
+            set_at(text, "jobs", 4),
+            "jobs = 4\n\n[[tools]]\nname = \"node\"\njobs = 1\n"
+        );
+        assert_eq!(remove(text, &path("jobs")).unwrap(), text);
+    }
+
+    #[test]
+    fn a_table_a_layer_reads_gets_a_header_of_its_own() {
+        let text = "[tools]\nnode = \"22\"";
+        assert_eq!(
+            set(text, &path("settings.jobs"), &Value::Int(8), 1).unwrap(),
+            "[tools]\nnode = \"22\"\n\n[settings]\njobs = 8\n"
+        );
+        let text = "settings.color = true\n";
+        assert_eq!(
+            set(text, &path("settings.jobs"), &Value::Int(8), 1).unwrap(),
+            "settings.color = true\nsettings.jobs = 8\n"
+        );
+    }
+
+    #[test]
+    fn a_list_over_lines_keeps_its_comments_when_it_grows() {
+        let text = "skip = [\n  \"a\", # first\n  \"b\"\n]\n";
+        let grown = Value::List(vec!["a".into(), "b".into(), "c\"d".into()]);
+        assert_eq!(
+            set_at(text, "skip", grown),
+            "skip = [\n  \"a\", # first\n  \"b\",\n  \"c\\\"d\"\n]\n"
+        );
+    }
+
+    #[test]
+    fn a_removed_table_leaves_the_comments_over_the_next_one() {
+        let text =
+            "[env]\nA = \"1\"\n# was B\n\n# tools I use\n# and why\n[tools]\nnode = \"22\"\n";
+        assert_eq!(
+            remove(text, &path("env")).unwrap(),
+            "# tools I use\n# and why\n[tools]\nnode = \"22\"\n"
+        );
+        // A table with no keys of its own is only its header.
+        let text = "jobs = 1\n\n[env]\n# tools I use\n[tools]\n";
+        assert_eq!(
+            remove(text, &path("env")).unwrap(),
+            "jobs = 1\n\n# tools I use\n[tools]\n"
+        );
+    }
+
+    #[test]
+    fn a_file_with_crlf_endings_keeps_them() {
+        let text = "# hk\r\n[tools]\r\nnode = \"22\"\r\n";
+        assert_eq!(
+            set_at(text, "tools.go", "1.23"),
+            "# hk\r\n[tools]\r\nnode = \"22\"\r\ngo = \"1.23\"\r\n"
+        );
+        assert_eq!(
+            set(text, &path("settings.jobs"), &Value::Int(8), 1).unwrap(),
+            "# hk\r\n[tools]\r\nnode = \"22\"\r\n\r\n[settings]\r\njobs = 8\r\n"
+        );
+    }
+
+    #[test]
+    fn strings_and_keys_are_quoted_where_bare_would_misread() {
+        assert_eq!(
+            set_at("", "env.a b", "tab\there\n"),
+            "env.\"a b\" = \"tab\\there\\n\"\n"
+        );
+    }
+}
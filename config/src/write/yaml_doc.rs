@@ -0,0 +1,526 @@
+//! YAML, edited where it stands.
+//!
+//! Block mappings only, read a line at a time by indentation: a key is a line, and its value is
+//! what follows the colon plus every line indented further. That covers the way settings files
+//! are written. Anything else — a flow mapping at the top, anchors around the key being
+//! written, complex keys — is left to the read-back check to refuse, rather than guessed at.
+
+use std::ops::Range;
+
+use super::{dotted, splice};
+use crate::value::Value;
+
+struct Line {
+    start: usize,
+    /// After the newline, when there is one.
+    end: usize,
+    indent: usize,
+    kind: Kind,
+}
+
+#[derive(PartialEq)]
+enum Kind {
+    Blank,
+    Comment,
+    /// `---` or `...`.
+    Marker,
+    Content,
+}
+
+struct Entry {
+    path: Vec<String>,
+    indent: usize,
+    /// The start of the key's line.
+    start: usize,
+    /// The end of the key's own line.
+    line_end: usize,
+    /// Just after the colon.
+    colon: usize,
+    /// The value on the key's line, without its comment, when there is one.
+    inline: Option<Range<usize>>,
+    /// The end of the last line of its value.
+    end: usize,
+    /// Whether the lines under it are keys of their own.
+    mapping: bool,
+    /// The indentation of its items, when its value is a block sequence.
+    items: Option<usize>,
+}
+
+pub(super) fn set(text: &str, path: &[String], value: &Value) -> Result<String, String> {
+    let entries = scan(text)?;
+    let target = dotted(path);
+    if let Some(entry) = entries.iter().find(|e| dotted(&e.path) == target) {
+        return Ok(splice(text, vec![replace(text, &entries, entry, value)]));
+    }
+    let holder = entries
+        .iter()
+        .filter(|e| e.path.len() < path.len() && path.starts_with(&e.path))
+        .max_by_key(|e| e.path.len());
+    let (at, indent, rest) = match holder {
+        Some(e) if e.mapping => (
+            e.end,
+            child_indent(&entries, e).unwrap_or(e.indent + 2),
+            &path[e.path.len()..],
+        ),
+        // `key:` or `key: ~` — nothing there yet, so the table goes in its place.
+        Some(e) if e.inline.as_ref().is_none_or(|r| is_null(&text[r.clone()])) => {
+            let lines = block(&path[e.path.len()..], value, e.indent + 2);
+            let ending = line_ending(text, e.end);
+            let with = format!("\n{}", lines.strip_suffix('\n').unwrap_or(&lines)) + ending;
+            return Ok(splice(text, vec![(e.colon..e.end, with)]));
+        }
+        Some(e) => return Err(format!("`{}` is not a table", dotted(&e.path))),
+        None => {
+            let roots = || entries.iter().filter(|e| e.path.len() == 1);
+            (
+                roots().map(|e| e.end).max().unwrap_or(text.len()),
+                roots().map(|e| e.indent).next().unwrap_or(0),
+                path,
+            )
+        }
+    };
+    let mut with = String::new();
+    if at > 0 && !text[..at].ends_with('\n') {
+        with.push('\n');
+    }
+    with.push_str(&block(rest, value, indent));
+    Ok(splice(text, vec![(at..at, with)]))
+}
+
+pub(super) fn remove(text: &str, path: &[String]) -> Result<String, String> {
+    let entries = scan(text)?;
+    let target = dotted(path);
+    Ok(match entries.iter().find(|e| dotted(&e.path) == target) {
+        Some(entry) => splice(text, vec![(entry.start..entry.end, String::new())]),
+        None => text.to_string(),
+    })
+}
+
+/// A new value for a key that is already there, in the style the old one was written in where
+/// there is a choice: a flow list stays a flow list, and a block list keeps its indentation.
+fn replace(text: &str, entries: &[Entry], entry: &Entry, value: &Value) -> (Range<usize>, String) {
+    let was = entry.inline.as_ref().map(|r| &text[r.clone()]);
+    let flow = match value {
+        Value::List(items) => items.is_empty() || was.is_some_and(|was| was.starts_with('[')),
+        Value::Map(map) => map.is_empty() || was.is_some_and(|was| was.starts_with('{')),
+        _ => true,
+    };
+    let ending = line_ending(text, entry.end);
+    match (flow, &entry.inline) {
+        // Only the value, when it is all on the key's line, so a comment after it stays.
+        (true, Some(r)) if entry.end == entry.line_end => (r.clone(), inline(value)),
+        (true, Some(r)) => (r.start..entry.end, format!("{}{ending}", inline(value))),
+        (true, None) => (
+            entry.colon..entry.end,
+            format!(" {}{ending}", inline(value)),
+        ),
+        (false, _) => {
+            let items = entry.items.unwrap_or(entry.indent + 2);
+            let keys = child_indent(entries, entry).unwrap_or(entry.indent + 2);
+            let lines = after_key(value, keys, items);
+            let lines = lines.strip_suffix('\n').unwrap_or(&lines);
+            (entry.colon..entry.end, format!("{lines}{ending}"))
+        }
+    }
+}
+
+/// The indentation of a mapping's own keys.
+fn child_indent(entries: &[Entry], parent: &Entry) -> Option<usize> {
+    entries
+        .iter()
+        .find(|e| e.path.len() == parent.path.len() + 1 && e.path.starts_with(&parent.path))
+        .map(|e| e.indent)
+}
+
+/// The newline a replacement has to end with to leave the line after it where it was.
+fn line_ending(text: &str, end: usize) -> &'static str {
+    match text[..end].ends_with('\n') {
+        true => "\n",
+        false => "",
+    }
+}
+
+fn is_null(text: &str) -> bool {
+    matches!(text, "~" | "null" | "Null" | "NULL")
+}
+
+/// Lines for `value` under the keys in `path`, the first of them at `indent`.
+fn block(path: &[String], value: &Value, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    match path {
+        [] => String::new(),
+        [last] => format!(
+            "{pad}{}:{}",
+            scalar(last),
+            after_key(value, indent + 2, indent + 2)
+        ),
+        [first, rest @ ..] => format!(
+            "{pad}{}:\n{}",
+            scalar(first),
+            block(rest, value, indent + 2)
+        ),
+    }
+}
+
+/// What follows a key's colon: the value on the same line, or lines of its own at `keys` for a
+/// table and `items` for a list.
+fn after_key(value: &Value, keys: usize, items: usize) -> String {
+    match value {
+        Value::List(list) if !list.is_empty() => {
+            let pad = " ".repeat(items);
+            let lines: String = list
+                .iter()
+                .map(|item| format!("{pad}- {}\n", inline(item)))
+                .collect();
+            format!("\n{lines}")
+        }
+        Value::Map(map) if !map.is_empty() => {
+            let pad = " ".repeat(keys);
+            let lines: String = map
+                .iter()
+                .map(|(k, v)| format!("{pad}{}:{}", scalar(k), after_key(v, keys + 2, keys + 2)))
+                .collect();
+            format!("\n{lines}")
+        }
+        other => format!(" {}\n", inline(other)),
+    }
+}
+
+/// A value on one line.
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Bool(b) => b.to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) if f.is_nan() => ".nan".to_string(),
+        Value::Float(f) if f.is_infinite() => match *f > 0.0 {
+            true => ".inf".to_string(),
+            false => "-.inf".to_string(),
+        },
+        Value::Float(_) => value.display(),
+        Value::String(s) => scalar(s),
+        Value::List(items) => format!(
+            "[{}]",
+            items.iter().map(inline).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Map(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(k, v)| format!("{}: {}", scalar(k), inline(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Text as YAML, plain where plain cannot be misread and double-quoted where it can.
+///
+/// Misread is the trap: `yes`, `off`, `1.0` and `~` are all text a user can mean, and written
+/// plain a reader takes them for a boolean, a number and a null.
+fn scalar(s: &str) -> String {
+    let lower = s.to_ascii_lowercase();
+    let plain = !s.is_empty()
+        && s.trim() == s
+        && !s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`+.0123456789".contains(c))
+        && !s.contains(|c: char| c.is_control() || ",[]{}".contains(c))
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !matches!(
+            lower.as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n" | "null" | "~"
+        );
+    if plain {
+        return s.to_string();
+    }
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let end = text[start..]
+            .find('\n')
+            .map_or(text.len(), |at| start + at + 1);
+        let line = text[start..end].trim_end_matches(['\n', '\r']);
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        let content = line.trim();
+        let kind = if content.is_empty() {
+            Kind::Blank
+        } else if content.starts_with('#') {
+            Kind::Comment
+        } else if indent == 0
+            && ["---", "..."]
+                .iter()
+                .any(|m| content == *m || content.starts_with(&format!("{m} #")))
+        {
+            Kind::Marker
+        } else {
+            Kind::Content
+        };
+        lines.push(Line {
+            start,
+            end,
+            indent,
+            kind,
+        });
+        start = end;
+    }
+    lines
+}
+
+fn scan(text: &str) -> Result<Vec<Entry>, String> {
+    let lines = lines(text);
+    let mut entries: Vec<Entry> = Vec::new();
+    // The mappings the current line may be inside, by their key's indentation.
+    let mut open: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut seen = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        match line.kind {
+            Kind::Blank | Kind::Comment => {
+                i += 1;
+                continue;
+            }
+            Kind::Marker if seen => {
+                return Err("a settings file is one document, and this is more than one".into())
+            }
+            Kind::Marker => {
+                i += 1;
+                continue;
+            }
+            Kind::Content => seen = true,
+        }
+        while open
+            .last()
+            .is_some_and(|(indent, _)| *indent >= line.indent)
+        {
+            open.pop();
+        }
+        let Some((key, colon, inline)) = key_line(text, line) else {
+            return Err(format!("line {} is not a `key: value` line", i + 1));
+        };
+        let mut path = open
+            .last()
+            .map(|(_, path)| path.clone())
+            .unwrap_or_default();
+        path.push(key);
+
+        // Its value: every line after it indented further, and a sequence at the same
+        // indentation under a key with nothing after its colon.
+        let mut last = i;
+        let mut first = None;
+        for (j, next) in lines.iter().enumerate().skip(i + 1) {
+            match next.kind {
+                Kind::Blank | Kind::Comment => continue,
+                Kind::Marker => break,
+                Kind::Content
+                    if next.indent > line.indent
+                        || (inline.is_none()
+                            && next.indent == line.indent
+                            && is_item(text, next)) =>
+                {
+                    last = j;
+                    first.get_or_insert(j);
+                }
+                Kind::Content => break,
+            }
+        }
+        let first = first.map(|j| &lines[j]);
+        let mapping =
+            inline.is_none() && first.is_some_and(|c| c.indent > line.indent && !is_item(text, c));
+        let items = first
+            .filter(|c| inline.is_none() && is_item(text, c))
+            .map(|c| c.indent);
+        entries.push(Entry {
+            path: path.clone(),
+            indent: line.indent,
+            start: line.start,
+            line_end: line.end,
+            colon,
+            inline,
+            end: lines[last].end,
+            mapping,
+            items,
+        });
+        if mapping {
+            open.push((line.indent, path));
+            i += 1;
+        } else {
+            i = last + 1;
+        }
+    }
+    Ok(entries)
+}
+
+fn is_item(text: &str, line: &Line) -> bool {
+    let content = text[line.start + line.indent..line.end].trim_end();
+    content == "-" || content.starts_with("- ")
+}
+
+/// The key on a line, where its colon ends, and the value after it on the same line.
+fn key_line(text: &str, line: &Line) -> Option<(String, usize, Option<Range<usize>>)> {
+    let start = line.start + line.indent;
+    let s = text[start..line.end].trim_end_matches(['\n', '\r']);
+    let (key, after) = match s.as_bytes()[0] {
+        b'"' => {
+            let mut p = 1;
+            loop {
+                match s.as_bytes().get(p)? {
+                    b'\\' => p += 2,
+                    b'"' => break,
+                    _ => p += 1,
+                }
+            }
+            let key = yaml_serde::from_str::<String>(&s[..=p]).ok()?;
+            (key, p + 1)
+        }
+        b'\'' => {
+            let mut p = 1;
+            loop {
+                match s.as_bytes().get(p)? {
+                    b'\'' if s.as_bytes().get(p + 1) == Some(&b'\'') => p += 2,
+                    b'\'' => break,
+                    _ => p += 1,
+                }
+            }
+            (s[1..p].replace("''", "'"), p + 1)
+        }
+        c if "-?[{&*!|>%@`#".contains(c as char) => return None,
+        _ => {
+            let at = s
+                .match_indices(':')
+                .map(|(at, _)| at)
+                .find(|at| matches!(s.as_bytes().get(at + 1), None | Some(b' ' | b'\t')))?;
+            (s[..at].trim_end().to_string(), at)
+        }
+    };
+    let rest = s[after..].trim_start_matches([' ', '\t']);
+    let rest = rest.strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let colon = start + s.len() - rest.len();
+    let value = rest.trim_start_matches([' ', '\t']);
+    let value_start = start + s.len() - value.len();
+    if value.is_empty() || value.starts_with('#') {
+        return Some((key, colon, None));
+    }
+    let len = value[..comment(value)].trim_end().len();
+    Some((key, colon, Some(value_start..value_start + len)))
+}
+
+/// Where a comment starts in a value, or its length when there is none: a `#` after
+/// whitespace and outside quotes.
+fn comment(value: &str) -> usize {
+    let b = value.as_bytes();
+    let mut quote = None;
+    let mut p = 0;
+    while p < b.len() {
+        match (quote, b[p]) {
+            (Some(b'"'), b'\\') => p += 1,
+            (Some(q), c) if c == q => quote = None,
+            (None, b'"' | b'\'') => quote = Some(b[p]),
+            (None, b'#') if p > 0 && matches!(b[p - 1], b' ' | b'\t') => return p,
+            _ => {}
+        }
+        p += 1;
+    }
+    b.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(dotted: &str) -> Vec<String> {
+        dotted.split('.').map(String::from).collect()
+    }
+
+    fn set_at(text: &str, at: &str, value: impl Into<Value>) -> String {
+        set(text, &path(at), &value.into()).expect("should set")
+    }
+
+    #[test]
+    fn a_flow_list_stays_a_flow_list() {
+        let text = "skip: [a, b] # none of these\njobs: 4\n";
+        let list = Value::List(vec!["a".into(), "yes".into()]);
+        assert_eq!(
+            set_at(text, "skip", list.clone()),
+            "skip: [a, \"yes\"] # none of these\njobs: 4\n"
+        );
+        // A block one keeps its indentation.
+        let text = "skip:\n    - a\njobs: 4\n";
+        assert_eq!(
+            set_at(text, "skip", list),
+            "skip:\n    - a\n    - \"yes\"\njobs: 4\n"
+        );
+    }
+
+    #[test]
+    fn a_flow_mapping_under_a_key_is_replaced_on_its_line() {
+        let text = "env: {A: \"1\"}\njobs: 4\n";
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("A".to_string(), Value::from("2"));
+        map.insert("B: C".to_string(), Value::from("off"));
+        assert_eq!(
+            set_at(text, "env", Value::Map(map)),
+            "env: {A: \"2\", \"B: C\": \"off\"}\njobs: 4\n"
+        );
+    }
+
+    #[test]
+    fn a_new_key_goes_at_its_mapping_s_indentation() {
+        let text = "task:\n   output: prefix\n# after\nnext: 1\n";
+        assert_eq!(
+            set_at(text, "task.color", true),
+            "task:\n   output: prefix\n   color: true\n# after\nnext: 1\n"
+        );
+        // A key with nothing under it yet takes the table in its place.
+        assert_eq!(
+            set_at("task: ~\nnext: 1\n", "task.output.mode", "quiet"),
+            "task:\n  output:\n    mode: quiet\nnext: 1\n"
+        );
+        assert_eq!(
+            set(text, &path("task.output.mode"), &Value::from("quiet")).unwrap_err(),
+            "`task.output` is not a table"
+        );
+    }
+
+    #[test]
+    fn a_file_with_crlf_endings_keeps_them() {
+        let text = "task:\r\n  output: prefix\r\nskip:\r\n  - a\r\n";
+        assert_eq!(
+            set_at(text, "task.color", true),
+            "task:\r\n  output: prefix\r\n  color: true\r\nskip:\r\n  - a\r\n"
+        );
+        assert_eq!(
+            set_at(text, "skip", Value::List(vec!["a".into(), "b".into()])),
+            "task:\r\n  output: prefix\r\nskip:\r\n  - a\r\n  - b\r\n"
+        );
+    }
+
+    #[test]
+    fn a_removed_key_takes_its_lines_with_it() {
+        let text = "task:\n  env:\n    A: 1\n  output: prefix\n";
+        assert_eq!(
+            remove(text, &path("task.env")).unwrap(),
+            "task:\n  output: prefix\n"
+        );
+        assert_eq!(remove(text, &path("task.missing")).unwrap(), text);
+    }
+}
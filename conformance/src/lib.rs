@@ -18,6 +18,7 @@ pub mod complete;
 pub mod config;
 pub mod reference;
 pub mod render;
+pub mod shell_parser;
 pub mod tables;
 
 /// One `corpus/*.json` file: a themed group of vectors.
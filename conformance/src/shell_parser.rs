@@ -0,0 +1,208 @@
+//! Running the corpus against the preludes `usage g shell-parser` emits.
+//!
+//! Each vector's spec is compiled into a prelude, which a two-line driver sources with the
+//! vector's `argv` as its own arguments. What comes back is what a script would see: the
+//! `usage_*` variables the prelude exported, or a message on stderr and exit status 1.
+//!
+//! A script never sees the bindings themselves, only their exported text, so that is what
+//! is compared: `expect` is written out the way `usage exec` would export it, and the two
+//! sets of variables must be equal. The subcommand path is compared too, read from the
+//! prelude's own bookkeeping, because a word that selected the wrong command usually binds
+//! nothing that would show.
+//!
+//! # Scope
+//!
+//! Both layers, unlike usage-argv: the prelude is the whole parse, `required` and `choices`
+//! included. A spec the generator refuses is [`Outcome::OutOfScope`], carrying the
+//! generator's own reason, so what the prelude cannot express is reported by the code that
+//! decides it rather than listed again here.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use heck::ToSnakeCase;
+use usage::shell_parser::{self, Shell, ShellParserOptions};
+use usage::Spec;
+
+use crate::{ErrorCode, Expect, Value, Vector};
+
+/// What a prelude did with a vector.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The selected subcommand path, and every `usage_*` variable exported.
+    Parsed {
+        cmd: Vec<String>,
+        env: BTreeMap<String, String>,
+    },
+    Failed(ErrorCode),
+    /// A failure whose message names no error class the corpus distinguishes.
+    Unrecognized(String),
+    /// The generator refused the spec. The string is its reason.
+    OutOfScope(String),
+    /// The spec would not load. A bug in the vector.
+    BadSpec(String),
+}
+
+impl Outcome {
+    pub fn matches(&self, expect: &Expect) -> bool {
+        match (self, expect) {
+            (Outcome::Parsed { cmd, env }, Expect::Ok(want)) => {
+                *cmd == want.cmd && *env == exported(&want.flags, &want.args)
+            }
+            (Outcome::Failed(got), Expect::Error(want)) => got == want,
+            _ => false,
+        }
+    }
+}
+
+/// The shells a prelude can be run under on this machine, by the name each is invoked as.
+pub fn installed_shells() -> Vec<(Shell, &'static str)> {
+    let candidates = [
+        (Shell::Posix, "sh"),
+        (Shell::Bash, "bash"),
+        (Shell::Zsh, "zsh"),
+        (Shell::Fish, "fish"),
+    ];
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    candidates
+        .into_iter()
+        .filter(|(_, name)| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .collect()
+}
+
+/// Compile a vector's spec for `shell` and run the prelude under `program`.
+pub fn run(vector: &Vector, shell: Shell, program: &str) -> Outcome {
+    let mut spec: Spec = match vector.spec.parse() {
+        Ok(spec) => spec,
+        Err(e) => return Outcome::BadSpec(e.to_string()),
+    };
+    if !vector.mounts.is_empty() {
+        let outputs = vector.mounts.clone().into_iter().collect();
+        if let Err(error) = spec.resolve_mount_outputs(&outputs) {
+            return Outcome::BadSpec(error.to_string());
+        }
+    }
+    if vector.argv0.is_some() {
+        return Outcome::OutOfScope("argv[0] is the shell's, not the prelude's".to_string());
+    }
+    let opts = ShellParserOptions {
+        shell,
+        source_file: None,
+    };
+    let prelude = match shell_parser::generate(&spec, &opts) {
+        Ok(prelude) => prelude,
+        Err(e) => return Outcome::OutOfScope(e.to_string()),
+    };
+
+    let dir = scratch_dir();
+    let prelude_path = dir.join("prelude");
+    std::fs::write(&prelude_path, prelude).expect("the prelude should be writable");
+    let driver_path = dir.join("driver");
+    std::fs::write(&driver_path, driver(shell, &prelude_path)).expect("writable driver");
+
+    let output = Command::new(program)
+        .arg(&driver_path)
+        .args(&vector.argv)
+        .env_clear()
+        .env("PATH", std::env::var_os("PATH").unwrap_or_default())
+        .envs(&vector.env)
+        .output()
+        .unwrap_or_else(|e| panic!("{program} should run: {e}"));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return classify(&stderr);
+    }
+    let stdout = String::from_utf8(output.stdout).expect("corpus values are UTF-8");
+    let mut fields = stdout.split('\0');
+    let cmd = fields
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let env = fields
+        .filter_map(|field| field.split_once('='))
+        .filter(|(key, _)| key.starts_with("usage_"))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    Outcome::Parsed { cmd, env }
+}
+
+/// A script that sources the prelude, then prints the subcommand path and the environment,
+/// each entry NUL-terminated so a value with a newline in it survives.
+///
+/// Under `set -eu` for the shells that have it, since that is how a careful script runs, and
+/// a prelude that trips over either is broken for that script.
+fn driver(shell: Shell, prelude: &Path) -> String {
+    let prelude = prelude.display();
+    match shell {
+        Shell::Fish => {
+            format!("source '{prelude}' $argv\nprintf '%s\\0' \"$__usage_path\"\nenv -0\n")
+        }
+        _ => format!("set -eu\n. '{prelude}'\nprintf '%s\\0' \"$__usage_path\"\nenv -0\n"),
+    }
+}
+
+/// The error class a prelude's message names, by the words it starts with.
+fn classify(stderr: &str) -> Outcome {
+    let message = stderr
+        .lines()
+        .next()
+        .and_then(|line| line.split_once(": "))
+        .map(|(_, message)| message)
+        .unwrap_or(stderr);
+    let classes = [
+        ("unknown flag: ", ErrorCode::UnknownFlag),
+        ("unexpected argument: ", ErrorCode::UnexpectedArg),
+        ("missing value for ", ErrorCode::MissingFlagValue),
+        ("invalid value for ", ErrorCode::InvalidChoice),
+        ("missing required flag: ", ErrorCode::MissingRequiredFlag),
+        ("missing required argument: ", ErrorCode::MissingRequiredArg),
+        ("too few values for ", ErrorCode::VarTooFew),
+        ("too many values for ", ErrorCode::VarTooMany),
+    ];
+    if let Some((_, code)) = classes.iter().find(|(start, _)| message.starts_with(start)) {
+        return Outcome::Failed(*code);
+    }
+    if message.starts_with("argument ") && message.ends_with("can only be given after --") {
+        return Outcome::Failed(ErrorCode::ArgRequiresDoubleDash);
+    }
+    Outcome::Unrecognized(stderr.to_string())
+}
+
+/// A binding as `usage exec` exports it: flags, then arguments over them where a name is
+/// shared.
+fn exported(
+    flags: &BTreeMap<String, Value>,
+    args: &BTreeMap<String, Value>,
+) -> BTreeMap<String, String> {
+    flags
+        .iter()
+        .chain(args)
+        .map(|(name, value)| {
+            let text = match value {
+                Value::Bool(b) => b.to_string(),
+                Value::Str(s) => s.clone(),
+                Value::Bools(bools) => bools.iter().filter(|b| **b).count().to_string(),
+                Value::Strs(strs) => shell_words::join(strs),
+            };
+            (format!("usage_{}", name.to_snake_case()), text)
+        })
+        .collect()
+}
+
+/// A directory of its own for one run, so vectors can run in parallel.
+fn scratch_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "usage-shell-parser-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).expect("the temp dir should be writable");
+    dir
+}
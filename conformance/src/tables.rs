@@ -395,6 +395,7 @@ fn flag_meta(
         hide_possible_values: f.hide_possible_values,
         hide_short_help: f.hide_short_help,
         hide_long_help: f.hide_long_help,
+        sensitive: f.sensitive,
         count: f.count,
         repeatable: f.var,
         // The separator as declared, a `char`: the metadata is the cold model and says what
@@ -492,6 +493,7 @@ fn arg_meta(
         hide_possible_values: a.hide_possible_values,
         hide_short_help: a.hide_short_help,
         hide_long_help: a.hide_long_help,
+        sensitive: a.sensitive,
         conflicts: strs(&a.conflicts),
         requires: strs(&a.requires),
         required_if: strs(&a.required_if),
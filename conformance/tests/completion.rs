@@ -287,6 +287,8 @@ fn the_script_calls_the_command_this_cli_answers() {
         Shell::Fish,
         Shell::Nu,
         Shell::PowerShell,
+        Shell::Elvish,
+        Shell::Xonsh,
     ] {
         let script = Ex::completion_script(shell);
         assert!(
@@ -329,6 +331,8 @@ fn where_a_script_is_installed_is_the_name_that_script_registers() {
         (Shell::Fish, "ex.fish"),
         (Shell::Nu, "ex.nu"),
         (Shell::PowerShell, "ex.ps1"),
+        (Shell::Elvish, "ex.elv"),
+        (Shell::Xonsh, "ex.xsh"),
     ] {
         let plan = Ex::completion_install_plan(shell, &env).expect("a plan");
         assert_eq!(plan.path.file_name().unwrap(), expected, "{shell:?}");
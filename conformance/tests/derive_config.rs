@@ -44,6 +44,7 @@ struct TaskSettings {
 ))]
 #[usage(file(path = "/etc/ex.toml", scope = "system", format = "toml"))]
 #[usage(file(path = "ex.toml", findup))]
+#[usage(profiles(env = "EX_PROFILE", flag = "--profile"))]
 struct Settings {
     /// How many jobs to run at once
     #[usage(
@@ -67,7 +68,8 @@ struct Settings {
     #[usage(
         env = "EX_CACHE_DIR",
         default_fn = default_cache_dir,
-        default_note = "under the user cache directory"
+        default_note = "under the user cache directory",
+        interpolate
     )]
     cache_dir: PathBuf,
 
@@ -98,6 +100,10 @@ struct Settings {
     #[usage(merge = "deep")]
     url_replacements: Option<BTreeMap<String, String>>,
 
+    /// What to sign in with
+    #[usage(env = "EX_TOKEN", sensitive)]
+    token: Option<String>,
+
     #[usage(flatten)]
     task: TaskSettings,
 }
@@ -120,6 +126,7 @@ fn the_registry_is_the_struct_in_declaration_order() {
             "ports",
             "ci",
             "url_replacements",
+            "token",
             "task.output",
             "task.jobs",
         ],
@@ -151,6 +158,9 @@ fn the_registry_is_the_struct_in_declaration_order() {
     assert_eq!(cache_dir.ty, Ty::Path);
     assert_eq!(cache_dir.default, None, "a computed default is not a const");
     assert_eq!(cache_dir.optional, Some(true));
+    assert!(cache_dir.interpolate);
+    assert!(!cache_dir.sensitive);
+    assert!(Settings::SETTINGS_PROPS[9].sensitive);
     assert_eq!(
         cache_dir.default_note,
         Some("under the user cache directory")
@@ -195,6 +205,7 @@ fn nothing_supplied_reads_as_the_declared_defaults() {
             ports: vec![80, 443],
             ci: None,
             url_replacements: None,
+            token: None,
             task: TaskSettings {
                 output: "prefix".to_string(),
                 jobs: None,
@@ -293,6 +304,9 @@ fn the_emitted_config_block_is_the_spec_grammar() {
         "file declaration order is precedence"
     );
     assert!(spec.config.files[1].findup);
+    let profiles = spec.config.profiles.as_ref().expect("declared");
+    assert_eq!(profiles.env.as_deref(), Some("EX_PROFILE"));
+    assert_eq!(profiles.flag.as_deref(), Some("--profile"));
     assert!(
         !spec
             .config
@@ -311,6 +325,8 @@ fn the_emitted_config_block_is_the_spec_grammar() {
         Some("under the user cache directory")
     );
     assert_eq!(cache_dir.optional, Some(true));
+    assert!(cache_dir.interpolate);
+    assert!(spec.config.props["token"].sensitive);
 }
 
 #[test]
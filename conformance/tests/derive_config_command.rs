@@ -0,0 +1,237 @@
+//! The `config` command a CLI asks for rather than writes.
+//!
+//! The claim under test: `#[usage(config_command)]` puts a `config` subcommand tree beside the
+//! CLI's own commands — in the parse tables, in the spec, in help and in completions — and
+//! hands back what it was asked as a `usage_config::command::Action`, so the hundred lines each
+//! fleet CLI wrote for `config get/set/list/explain` are one attribute.
+
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+
+use usage_argv::Error;
+use usage_config::command::{Action, ConfigCommand, Target};
+use usage_config::{resolve, EnvLayer, FileLayer, FileScope, Layers};
+use usage_derive::{Cli, Config, Subcommands};
+
+#[derive(Config, Debug)]
+#[usage(file(path = "~/.config/tool/config.toml", scope = "global"))]
+#[usage(file(path = "tool.toml", findup))]
+struct Settings {
+    /// How many jobs to run at once
+    #[usage(default = 4)]
+    jobs: u64,
+
+    /// When to colour output
+    #[usage(default = "auto", choices("auto", "always", "never"))]
+    color: String,
+
+    /// Whether to say more
+    #[usage(default = false)]
+    verbose: bool,
+}
+
+#[derive(Subcommands, Debug, PartialEq)]
+enum Commands {
+    /// Build the project
+    Build,
+}
+
+#[derive(Cli, Debug)]
+#[usage(bin = "tool", version = "1.2.0", config = Settings, config_command, completion)]
+struct Tool {
+    #[usage(subcommand)]
+    command: Option<Commands>,
+}
+
+fn action(line: &[&str]) -> Option<Result<Action, String>> {
+    let argv: Vec<&OsStr> = line.iter().map(OsStr::new).collect();
+    Tool::config_action(&argv).map(|r| r.map_err(|e| format!("{e:?}")))
+}
+
+#[test]
+fn each_subcommand_becomes_its_action() {
+    assert_eq!(
+        action(&["config", "get", "jobs"]),
+        Some(Ok(Action::Get {
+            key: "jobs".to_string()
+        }))
+    );
+    assert_eq!(
+        action(&["config", "set", "color", "never"]),
+        Some(Ok(Action::Set {
+            key: "color".to_string(),
+            value: "never".to_string(),
+            target: None,
+        })),
+        "no flag is the file the setting belongs in, which the command decides"
+    );
+    assert_eq!(
+        action(&["config", "set", "--global", "jobs", "8"]),
+        Some(Ok(Action::Set {
+            key: "jobs".to_string(),
+            value: "8".to_string(),
+            target: Some(Target::Global),
+        }))
+    );
+    assert_eq!(
+        action(&["config", "unset", "jobs", "--file", "other.toml"]),
+        Some(Ok(Action::Unset {
+            key: "jobs".to_string(),
+            target: Some(Target::File(PathBuf::from("other.toml"))),
+        }))
+    );
+    assert_eq!(action(&["config", "ls"]), Some(Ok(Action::List)));
+    assert_eq!(
+        action(&["config", "explain", "color"]),
+        Some(Ok(Action::Explain {
+            key: "color".to_string()
+        }))
+    );
+    assert_eq!(
+        action(&["config", "path"]),
+        Some(Ok(Action::Path { target: None }))
+    );
+    assert_eq!(
+        action(&["config", "edit", "--global"]),
+        Some(Ok(Action::Edit {
+            target: Some(Target::Global)
+        }))
+    );
+}
+
+#[test]
+fn any_other_command_line_is_the_clis_own() {
+    assert_eq!(action(&["build"]), None);
+    assert_eq!(action(&[]), None);
+    // A help request made before `config` is about the CLI, and the ordinary parse answers it.
+    assert_eq!(action(&["--help", "config"]), None);
+
+    // And the ordinary parse does not see the generated command as one of its own.
+    let parsed = Tool::parse_from(&[OsStr::new("build")]).expect("parses");
+    assert_eq!(parsed.command, Some(Commands::Build));
+}
+
+#[test]
+fn a_wrong_or_curious_config_line_is_answered_as_one() {
+    let argv: Vec<&OsStr> = ["config"].iter().map(OsStr::new).collect();
+    assert!(
+        matches!(
+            Tool::config_action(&argv),
+            Some(Err(Error::MissingArgsHelp { cmd })) if cmd.name == "config"
+        ),
+        "a bare `config` shows what it can do"
+    );
+
+    let argv: Vec<&OsStr> = ["config", "set", "--help"].iter().map(OsStr::new).collect();
+    assert!(
+        matches!(
+            Tool::config_action(&argv),
+            Some(Err(Error::Help { cmd, long: true })) if cmd.name == "set"
+        ),
+        "help is asked about the command the words reached"
+    );
+
+    let argv: Vec<&OsStr> = ["config", "get"].iter().map(OsStr::new).collect();
+    assert!(
+        matches!(Tool::config_action(&argv), Some(Err(_))),
+        "a missing key is this command's error, not a fall-through to the CLI's parse"
+    );
+}
+
+#[test]
+fn the_spec_declares_the_tree_with_config_completions() {
+    let kdl = Tool::to_kdl();
+    let spec: usage::Spec = kdl.parse().expect("usage-lib reads the whole spec");
+    let config = spec
+        .cmd
+        .subcommands
+        .get("config")
+        .expect("a config command");
+    let names: Vec<&str> = config.subcommands.keys().map(String::as_str).collect();
+    for name in ["get", "set", "unset", "list", "explain", "path", "edit"] {
+        assert!(names.contains(&name), "{name} missing from {names:?}");
+    }
+    assert!(
+        spec.cmd.subcommands.contains_key("build"),
+        "beside the CLI's own commands"
+    );
+    // What each one does to the files, so an agent allowed only to read can still ask.
+    for (name, effect) in [
+        ("get", usage::SpecCommandEffect::Read),
+        ("set", usage::SpecCommandEffect::Write),
+        ("unset", usage::SpecCommandEffect::Write),
+        ("list", usage::SpecCommandEffect::Read),
+        ("explain", usage::SpecCommandEffect::Read),
+        ("path", usage::SpecCommandEffect::Read),
+        ("edit", usage::SpecCommandEffect::Write),
+    ] {
+        assert_eq!(config.subcommands[name].effect, Some(effect), "{name}");
+    }
+
+    // The spec's own completion types, which every consumer answers from the config block —
+    // not a `run=` naming this binary, which the grammar refuses beside `type=`.
+    assert!(kdl.contains(r#"complete key type=config_keys"#), "{kdl}");
+    assert!(
+        kdl.contains(r#"complete value type=config_values"#),
+        "{kdl}"
+    );
+    assert!(!kdl.contains("__complete_word__ --candidates key"), "{kdl}");
+}
+
+fn complete(line: &str) -> String {
+    let argv: Vec<OsString> = ["__complete_word__", "--shell", "bash", "--line", line]
+        .iter()
+        .map(OsString::from)
+        .collect();
+    Tool::completion_request(&argv).expect("a completion request")
+}
+
+#[test]
+fn the_binary_completes_keys_and_their_values() {
+    assert_eq!(complete("tool config get "), "color\njobs\nverbose\n");
+    assert_eq!(complete("tool config set co"), "color\n");
+    assert_eq!(
+        complete("tool config set color "),
+        "always\nauto\nnever\n",
+        "the key on the line decides what its value may be"
+    );
+    assert_eq!(complete("tool config set verbose "), "false\ntrue\n");
+    assert_eq!(complete("tool con"), "config\n");
+}
+
+#[test]
+fn an_action_runs_against_the_declared_files() {
+    let dir = std::env::temp_dir().join(format!(
+        "usage_conformance_config_command_{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("home")).unwrap();
+    let env = EnvLayer::new([("HOME".to_string(), dir.join("home").display().to_string())]);
+    let command = ConfigCommand::new(
+        Settings::SETTINGS_REGISTRY,
+        Settings::SETTINGS_SPEC,
+        &dir,
+        env,
+    );
+
+    let set = action(&["config", "set", "jobs", "8"]).unwrap().unwrap();
+    command.run(set).expect("writes the project file");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("tool.toml")).unwrap(),
+        "jobs = 8\n"
+    );
+    let get = action(&["config", "get", "jobs"]).unwrap().unwrap();
+    assert_eq!(command.run(get).expect("reads it back").out, "8");
+
+    // And what it wrote is what the CLI's own resolution reads.
+    let file = FileLayer::at(dir.join("tool.toml"), FileScope::Project);
+    let resolved = resolve(Settings::SETTINGS_REGISTRY, Layers::new().then(&file)).unwrap();
+    let settings = Settings::read(&resolved).expect("every field reads");
+    assert_eq!(
+        (settings.jobs, settings.color.as_str(), settings.verbose),
+        (8, "auto", false)
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
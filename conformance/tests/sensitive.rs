@@ -0,0 +1,59 @@
+//! A `sensitive` declaration is never quoted back: not as a default in help, not in an error.
+
+use std::ffi::OsStr;
+
+use usage_derive::Cli;
+
+#[derive(Cli)]
+#[usage(bin = "deploy")]
+#[allow(dead_code)]
+struct Deploy {
+    /// Token to sign in with
+    #[usage(long, sensitive, default = "dev-token")]
+    token: String,
+    /// Numeric PIN for the vault
+    #[usage(long, sensitive)]
+    pin: Option<u32>,
+    /// Region to deploy to
+    #[usage(long, sensitive, choices("eu", "us"))]
+    region: Option<String>,
+}
+
+fn error_text(argv: &[&str]) -> String {
+    let argv: Vec<&OsStr> = argv.iter().map(OsStr::new).collect();
+    let Err(error) = Deploy::parse_from(&argv) else {
+        panic!("{argv:?} should be refused");
+    };
+    usage_argv::diagnostic::render(
+        Deploy::spec(),
+        &argv,
+        &error,
+        usage_argv::diagnostic::Style::PLAIN,
+    )
+}
+
+#[test]
+fn help_leaves_a_secret_default_out() {
+    let help = usage_argv::help::render(Deploy::spec(), Deploy::command(), true).unwrap();
+    assert!(help.contains("--token"), "{help}");
+    assert!(!help.contains("dev-token"), "{help}");
+}
+
+#[test]
+fn an_error_masks_the_value_it_refused() {
+    let text = error_text(&["--pin", "hunter2"]);
+    assert!(text.contains("<redacted>"), "{text}");
+    assert!(!text.contains("hunter2"), "{text}");
+
+    let text = error_text(&["--region", "moon-base"]);
+    assert!(text.contains("invalid value '<redacted>'"), "{text}");
+    assert!(!text.contains("moon-base"), "{text}");
+}
+
+#[test]
+fn the_portable_spec_keeps_the_attribute() {
+    let kdl = Deploy::to_kdl();
+    assert!(kdl.contains("sensitive=#true"), "{kdl}");
+    let spec: usage::Spec = kdl.parse().expect("the emitted spec should load");
+    assert!(spec.cmd.flags.iter().all(|f| f.sensitive), "{kdl}");
+}
@@ -0,0 +1,52 @@
+//! Checks the preludes `usage g shell-parser` emits against the corpus, in every shell
+//! installed here.
+//!
+//! A spec the generator refuses is skipped, with the generator's reason. Everything else
+//! has to match, binding and post-binding vectors alike, since the prelude is the whole
+//! parse a script gets.
+
+use usage_conformance::shell_parser::{installed_shells, run, Outcome};
+use usage_conformance::{load, Vector};
+
+fn corpus() -> Vec<Vector> {
+    let files = load(usage_conformance::corpus_dir()).expect("corpus should load");
+    files.into_iter().flat_map(|f| f.vectors).collect()
+}
+
+#[test]
+fn every_vector_it_accepts_passes_in_every_installed_shell() {
+    let shells = installed_shells();
+    assert!(
+        !shells.is_empty(),
+        "no shell to run a prelude in; `sh` at least should be on PATH"
+    );
+    let mut failures = Vec::new();
+    let mut answered = 0;
+
+    for vector in corpus() {
+        for (shell, program) in &shells {
+            let outcome = run(&vector, *shell, program);
+            if let Outcome::OutOfScope(_) = outcome {
+                continue;
+            }
+            answered += 1;
+            if !outcome.matches(&vector.expect) {
+                failures.push(format!(
+                    "{} ({program}): {}\n     expected: {:?}\n     got:      {outcome:?}",
+                    vector.id, vector.doc, vector.expect
+                ));
+            }
+        }
+    }
+
+    assert!(
+        answered > 0,
+        "the generator refused every spec in the corpus"
+    );
+    assert!(
+        failures.is_empty(),
+        "{} vector run(s) failed:\n  - {}",
+        failures.len(),
+        failures.join("\n  - ")
+    );
+}
@@ -250,6 +250,16 @@ pub fn emit(cli: &Cli) -> TokenStream {
         .map(|p| p.external.clone())
         .unwrap_or_default();
     let sub_metas = parts.as_ref().map(|p| p.metas.clone()).unwrap_or_default();
+    // A generated `config` command is joined to the enum's own, so the tables name the join.
+    let config_command = config_command_fns(cli);
+    let (sub_commands, sub_metas) = match &config_command {
+        Some(c) => (c.commands.clone(), c.metas.clone()),
+        None => (sub_commands, sub_metas),
+    };
+    let config_command_items = config_command.as_ref().map(|c| c.items.clone());
+    let config_command_tables = config_command.as_ref().map(|c| c.tables.clone());
+    let config_command_functions = config_command.as_ref().map(|c| c.functions.clone());
+    let config_command_intercept = config_command.as_ref().map(|c| c.intercept.clone());
     let sub_build = parts.as_ref().map(|p| p.build.clone()).unwrap_or_default();
     let sub_default_view_build = parts
         .as_ref()
@@ -350,6 +360,7 @@ pub fn emit(cli: &Cli) -> TokenStream {
                     let __usage_selected_view = __usage_all
                         .first()
                         .and_then(|argv0| usage_argv::spec::view_for_program(&SPEC, argv0));
+                    #config_command_intercept
                     // This is the entry point that *is* the process — it already exits for a help
                     // request — so it answers a failure the way a command-line program does:
                     // the message on stderr, and a non-zero status. `parse_from` hands the error
@@ -827,6 +838,7 @@ pub fn emit(cli: &Cli) -> TokenStream {
             #(#flag_tables)*
             #(#arg_tables)*
             #table_decls
+            #config_command_tables
 
             pub static ROOT: usage_argv::Command = usage_argv::Command {
                 // Only where a version was declared, which is when clap adds the flag: a
@@ -1153,6 +1165,8 @@ pub fn emit(cli: &Cli) -> TokenStream {
 
                 #spec_endpoint
 
+                #config_command_functions
+
                 #settings_binding_forward
                 #settings_parse
 
@@ -1587,6 +1601,7 @@ pub fn emit(cli: &Cli) -> TokenStream {
             }
         };
 
+        #config_command_items
         #dispatch
     }
 }
@@ -1738,6 +1753,344 @@ fn spec_endpoint_fns(cli: &Cli) -> (TokenStream, TokenStream) {
     (functions, intercept)
 }
 
+/// What `#[usage(config_command)]` adds to a root, in the places it goes.
+struct ConfigCommandParts {
+    /// The synthesized enums, beside the root: a derive of their own, like any other enum.
+    items: TokenStream,
+    /// The joined subcommand tables, inside the root's const block.
+    tables: TokenStream,
+    /// `subcommands:` for the `Command` table, replacing the enum's own.
+    commands: TokenStream,
+    /// `subcommands:` for the `CommandMeta`.
+    metas: TokenStream,
+    /// `config_action`, on the root.
+    functions: TokenStream,
+    /// The line in `parse` that notices one.
+    intercept: TokenStream,
+}
+
+/// The generated `config` command, for a root that asked for one.
+///
+/// Declared as ordinary derived enums rather than written out as tables, so its help, its spec
+/// and its completions come from the same code every other command's do — the `key` positional
+/// is a `config_keys` field like one a CLI would write by hand. It is joined to the root's own
+/// subcommands rather than added to the user's enum, which the root cannot touch: the enum's
+/// routing looks its commands up by address, so one it does not own is simply never selected
+/// there, and `config_action` is what reads it.
+fn config_command_fns(cli: &Cli) -> Option<ConfigCommandParts> {
+    if !cli.config_command {
+        return None;
+    }
+    let settings = cli.config.as_ref()?;
+    let (_, ty) = cli.fields.iter().find_map(|f| match &f.kind {
+        Kind::Subcommand { ty, .. } => Some((f, ty)),
+        _ => None,
+    })?;
+    let ident = &cli.ident;
+    let derive = derive_path();
+    let cfg = crate::config::config_path();
+    let outer = format_ident!("__UsageConfig{ident}");
+    let action = format_ident!("__UsageConfigAction{ident}");
+
+    let items = quote! {
+        #[doc(hidden)]
+        #[derive(#derive::Subcommands)]
+        pub enum #outer {
+            /// Read and change this program's settings
+            Config {
+                #[usage(subcommand)]
+                action: #action,
+            },
+        }
+
+        #[doc(hidden)]
+        #[derive(#derive::Subcommands)]
+        pub enum #action {
+            /// Print a setting's value
+            #[usage(effect = "read")]
+            Get {
+                /// The setting to print
+                #[usage(config_keys = #settings)]
+                key: ::std::string::String,
+            },
+            /// Write a setting into a config file
+            #[usage(effect = "write")]
+            Set {
+                /// The setting to change
+                #[usage(config_keys = #settings)]
+                key: ::std::string::String,
+                /// What to set it to
+                #[usage(config_values = #settings)]
+                value: ::std::string::String,
+                /// Write the user's own config file
+                #[usage(long, conflicts = "--file")]
+                global: bool,
+                /// Write this config file
+                #[usage(long, value_hint = FilePath)]
+                file: Option<::std::path::PathBuf>,
+            },
+            /// Take a setting out of a config file
+            #[usage(effect = "write")]
+            Unset {
+                /// The setting to remove
+                #[usage(config_keys = #settings)]
+                key: ::std::string::String,
+                /// Change the user's own config file
+                #[usage(long, conflicts = "--file")]
+                global: bool,
+                /// Change this config file
+                #[usage(long, value_hint = FilePath)]
+                file: Option<::std::path::PathBuf>,
+            },
+            /// List every setting and its value
+            #[usage(alias = "ls", effect = "read")]
+            List,
+            /// Say where a setting's value came from
+            #[usage(effect = "read")]
+            Explain {
+                /// The setting to explain
+                #[usage(config_keys = #settings)]
+                key: ::std::string::String,
+            },
+            /// Print the config files read, lowest precedence first
+            #[usage(effect = "read")]
+            Path {
+                /// Print where the user's own config file is
+                #[usage(long, conflicts = "--file")]
+                global: bool,
+                /// Print this config file's path, if it is one that is read
+                #[usage(long, value_hint = FilePath)]
+                file: Option<::std::path::PathBuf>,
+            },
+            /// Open a config file in $VISUAL or $EDITOR
+            #[usage(effect = "write")]
+            Edit {
+                /// Edit the user's own config file
+                #[usage(long, conflicts = "--file")]
+                global: bool,
+                /// Edit this config file
+                #[usage(long, value_hint = FilePath)]
+                file: Option<::std::path::PathBuf>,
+            },
+        }
+    };
+
+    let tables = quote! {
+        const __USAGE_SUBCOMMAND_GROUPS: &[&[&'static usage_argv::Command<'static>]] = &[
+            <#ty as usage_argv::spec::Subcommands>::COMMANDS,
+            <#outer as usage_argv::spec::Subcommands>::COMMANDS,
+        ];
+        static __USAGE_SUBCOMMANDS: [&'static usage_argv::Command<'static>;
+            usage_argv::table_len(__USAGE_SUBCOMMAND_GROUPS)] =
+            usage_argv::concat_commands(__USAGE_SUBCOMMAND_GROUPS);
+        const __USAGE_SUBCOMMAND_META_GROUPS: &[&[&'static usage_argv::spec::CommandMeta<'static>]] = &[
+            <#ty as usage_argv::spec::Subcommands>::METAS,
+            <#outer as usage_argv::spec::Subcommands>::METAS,
+        ];
+        static __USAGE_SUBCOMMAND_METAS: [&'static usage_argv::spec::CommandMeta<'static>;
+            usage_argv::table_len(__USAGE_SUBCOMMAND_META_GROUPS)] =
+            usage_argv::spec::concat_command_metas(__USAGE_SUBCOMMAND_META_GROUPS);
+        // The enum's own check cannot see this one: a `config` command the CLI declares itself
+        // would otherwise be shadowed by whichever the parser reached first.
+        const _: () = usage_argv::assert_unique_subcommand_names(&__USAGE_SUBCOMMANDS);
+    };
+
+    let target = quote! {
+        match (global, file) {
+            (_, ::std::option::Option::Some(file)) => ::std::option::Option::Some(
+                #cfg::command::Target::File(file),
+            ),
+            (true, ::std::option::Option::None) => {
+                ::std::option::Option::Some(#cfg::command::Target::Global)
+            }
+            (false, ::std::option::Option::None) => ::std::option::Option::None,
+        }
+    };
+    let functions = quote! {
+        // Says what is missing, where the alternative is `unresolved module command`.
+        #cfg::__usage_needs_file_format!();
+
+        /// The `config` subcommand this command line asks for, if it asks for one.
+        ///
+        /// `None` for any other invocation, so the ordinary parse goes on to answer it — and
+        /// to report what is wrong with it, which is its business rather than this. `Some(Err)`
+        /// is a `config` command line that is wrong, or that asked for help. Takes the command
+        /// line *without* the program name, like [`Self::parse_from`].
+        ///
+        /// [`Self::parse`] runs the action and exits; `parse_from` does not look for one. An
+        /// embedder that wants the command calls this first and hands the action to
+        /// `usage_config::command::ConfigCommand`.
+        pub fn config_action<'v>(
+            argv: &[&'v ::std::ffi::OsStr],
+        ) -> ::std::option::Option<
+            ::std::result::Result<#cfg::command::Action, usage_argv::Error<'static, 'v>>,
+        > {
+            // One comparison per word on every invocation that is not this one.
+            if !argv
+                .iter()
+                .any(|word| *word == ::std::ffi::OsStr::new("config"))
+            {
+                return ::std::option::Option::None;
+            }
+            let __usage_config = <#outer as usage_argv::spec::Subcommands>::COMMANDS[0];
+            let mut __usage_parser = usage_argv::Parser::new(Self::command(), argv);
+            let mut __usage_partial =
+                <<#outer as usage_argv::spec::Subcommands>::Partial as ::std::default::Default>::default();
+            let mut __usage_selected = false;
+            while let ::std::option::Option::Some(__usage_event) = __usage_parser.next_event() {
+                // Before `config` is reached the line is the CLI's own, and so is whatever is
+                // wrong with it — including a `--help` that asks about the CLI.
+                let __usage_event = match __usage_event {
+                    ::std::result::Result::Ok(event) => event,
+                    ::std::result::Result::Err(e) if __usage_selected => {
+                        return ::std::option::Option::Some(::std::result::Result::Err(e));
+                    }
+                    ::std::result::Result::Err(_) => return ::std::option::Option::None,
+                };
+                if let usage_argv::Event::Flag { flag, .. } = &__usage_event {
+                    if usage_argv::is_help_flag(flag) || usage_argv::is_version_flag(flag) {
+                        if !__usage_selected {
+                            return ::std::option::Option::None;
+                        }
+                        let __usage_error = if usage_argv::is_version_flag(flag) {
+                            usage_argv::Error::Version {
+                                long: !flag.longs.is_empty(),
+                            }
+                        } else if flag.action == usage_argv::ArgAction::HelpAll {
+                            usage_argv::Error::HelpAll {
+                                cmd: __usage_parser.command(),
+                            }
+                        } else {
+                            usage_argv::Error::Help {
+                                cmd: __usage_parser.command(),
+                                long: match flag.action {
+                                    usage_argv::ArgAction::HelpLong => true,
+                                    usage_argv::ArgAction::Help => !flag.longs.is_empty(),
+                                    _ => false,
+                                },
+                            }
+                        };
+                        return ::std::option::Option::Some(::std::result::Result::Err(
+                            __usage_error,
+                        ));
+                    }
+                }
+                if let usage_argv::Event::Command(__usage_cmd) = &__usage_event {
+                    if ::core::ptr::eq(*__usage_cmd, __usage_config) {
+                        __usage_selected = true;
+                        <#outer as usage_argv::spec::Subcommands>::begin(&mut __usage_partial, 0);
+                    }
+                }
+                if __usage_selected {
+                    let _ = <#outer as usage_argv::spec::Subcommands>::apply(
+                        &mut __usage_partial,
+                        ::std::option::Option::Some(0),
+                        &__usage_event,
+                    );
+                }
+            }
+            if !__usage_selected {
+                return ::std::option::Option::None;
+            }
+            // A bare `config` is a question about what it can do, as a command that requires
+            // arguments answers one.
+            if ::core::ptr::eq(__usage_parser.command(), __usage_config) {
+                return ::std::option::Option::Some(::std::result::Result::Err(
+                    usage_argv::Error::MissingArgsHelp {
+                        cmd: __usage_config,
+                    },
+                ));
+            }
+            let __usage_built = <#outer as usage_argv::spec::Subcommands>::check(
+                &mut __usage_partial,
+                0,
+            )
+            .and_then(|()| {
+                <#outer as usage_argv::spec::Subcommands>::select(__usage_partial, 0)
+            });
+            let #outer::Config { action } = match __usage_built {
+                ::std::result::Result::Ok(::std::option::Option::Some(built)) => built,
+                ::std::result::Result::Ok(::std::option::Option::None) => {
+                    return ::std::option::Option::None;
+                }
+                ::std::result::Result::Err(e) => {
+                    return ::std::option::Option::Some(::std::result::Result::Err(e));
+                }
+            };
+            ::std::option::Option::Some(::std::result::Result::Ok(match action {
+                #action::Get { key } => #cfg::command::Action::Get { key },
+                #action::Set {
+                    key,
+                    value,
+                    global,
+                    file,
+                } => #cfg::command::Action::Set {
+                    key,
+                    value,
+                    target: #target,
+                },
+                #action::Unset { key, global, file } => #cfg::command::Action::Unset {
+                    key,
+                    target: #target,
+                },
+                #action::List => #cfg::command::Action::List,
+                #action::Explain { key } => #cfg::command::Action::Explain { key },
+                #action::Path { global, file } => #cfg::command::Action::Path { target: #target },
+                #action::Edit { global, file } => #cfg::command::Action::Edit { target: #target },
+            }))
+        }
+    };
+
+    let intercept = quote! {
+        // After the view and multicall rewrites, which are how a word reaches `config` at all,
+        // and before the parse, which has no variant to put it in.
+        if let ::std::option::Option::Some(__usage_request) = Self::config_action(&__usage_argv) {
+            match __usage_request {
+                ::std::result::Result::Ok(__usage_action) => {
+                    let mut __usage_command = #cfg::command::ConfigCommand::from_process(
+                        <#settings>::SETTINGS_REGISTRY,
+                        <#settings>::SETTINGS_SPEC,
+                    );
+                    if let ::std::option::Option::Some(__usage_version) = SPEC.version {
+                        __usage_command = __usage_command.for_cli_version(__usage_version);
+                    }
+                    match __usage_command.run(__usage_action) {
+                        ::std::result::Result::Ok(__usage_answer) => {
+                            for __usage_warning in &__usage_answer.warnings {
+                                ::std::eprintln!("warning: {__usage_warning}");
+                            }
+                            if !__usage_answer.out.is_empty() {
+                                ::std::println!("{}", __usage_answer.out);
+                            }
+                            usage_argv::__usage_process_exit(0);
+                        }
+                        // The command line was fine and the work was not, so not clap's 2.
+                        ::std::result::Result::Err(__usage_error) => {
+                            ::std::eprintln!("error: {__usage_error}");
+                            usage_argv::__usage_process_exit(1);
+                        }
+                    }
+                }
+                ::std::result::Result::Err(e) => Self::__usage_exit_on_error(
+                    e,
+                    &__usage_all_refs,
+                    &__usage_argv,
+                    __usage_selected_view,
+                ),
+            }
+        }
+    };
+
+    Some(ConfigCommandParts {
+        items,
+        tables,
+        commands: quote!(subcommands: &__USAGE_SUBCOMMANDS,),
+        metas: quote!(subcommands: &__USAGE_SUBCOMMAND_METAS,),
+        functions,
+        intercept,
+    })
+}
+
 /// The completion entry points, for a CLI that asked for them.
 ///
 /// Two pieces: a function that answers a request, and the line in `parse` that notices one. Both
@@ -1849,6 +2202,71 @@ fn completion_fns(cli: &Cli) -> (TokenStream, TokenStream) {
             usage_argv::install::install_for(&__usage_program, alias, shell, env, on_foreign)
         }
 
+        /// Where this CLI's script goes for every shell on `env`'s `PATH`. Writes nothing.
+        pub fn completion_install_plan_all_shells(
+            env: &usage_argv::install::Env,
+        ) -> ::std::result::Result<
+            ::std::vec::Vec<usage_argv::install::Plan>,
+            usage_argv::install::Error,
+        > {
+            let __usage_program = { #completion_program };
+            usage_argv::install::plan_all(&__usage_program, env)
+        }
+
+        /// Write this CLI's completion script for every shell on `env`'s `PATH`.
+        ///
+        /// Every target is read before any is written, so a file this did not write refuses the
+        /// whole set rather than leaving some shells upgraded and others not.
+        pub fn install_completion_all_shells(
+            env: &usage_argv::install::Env,
+            on_foreign: usage_argv::install::OnForeign,
+        ) -> ::std::result::Result<
+            ::std::vec::Vec<usage_argv::install::Installed>,
+            usage_argv::install::Error,
+        > {
+            let __usage_program = { #completion_program };
+            usage_argv::install::install_all(&__usage_program, env, on_foreign)
+        }
+
+        /// Remove this CLI's completion script for `shell`, if an install wrote it.
+        ///
+        /// A file without usage's generated marker is refused and left alone, and a line the
+        /// install reported stays wherever the user put it.
+        pub fn uninstall_completion(
+            shell: usage_argv::complete::Shell,
+            env: &usage_argv::install::Env,
+        ) -> ::std::result::Result<
+            usage_argv::install::Uninstalled,
+            usage_argv::install::Error,
+        > {
+            let __usage_program = { #completion_program };
+            usage_argv::install::uninstall(&__usage_program, shell, env)
+        }
+
+        /// Remove the script an alias was installed under.
+        pub fn uninstall_completion_for_alias(
+            alias: &str,
+            shell: usage_argv::complete::Shell,
+            env: &usage_argv::install::Env,
+        ) -> ::std::result::Result<
+            usage_argv::install::Uninstalled,
+            usage_argv::install::Error,
+        > {
+            let __usage_program = { #completion_program };
+            usage_argv::install::uninstall_for(&__usage_program, alias, shell, env)
+        }
+
+        /// Remove this CLI's completion script from every shell that has somewhere to keep one.
+        pub fn uninstall_completion_all_shells(
+            env: &usage_argv::install::Env,
+        ) -> ::std::result::Result<
+            ::std::vec::Vec<usage_argv::install::Uninstalled>,
+            usage_argv::install::Error,
+        > {
+            let __usage_program = { #completion_program };
+            usage_argv::install::uninstall_all(&__usage_program, env)
+        }
+
         /// A declared executable view's completion script.
         pub fn completion_script_for(
             view: &str,
@@ -2274,10 +2692,16 @@ fn completer_tokens(
     kind: &str,
     owner: &syn::Ident,
 ) -> (TokenStream, TokenStream) {
+    let wrapper = format_ident!("__usage_complete_{kind}_{i}");
+    if let Some(settings) = &field.config_complete {
+        return (
+            config_completer(&wrapper, field, settings, owner),
+            quote!(::std::option::Option::Some(#wrapper)),
+        );
+    }
     let Some(path) = &field.complete else {
         return (TokenStream::new(), quote!(::std::option::Option::None));
     };
-    let wrapper = format_ident!("__usage_complete_{kind}_{i}");
     // Through the same rewriting a field's *type* goes through: the generated module is one
     // level below where the user wrote the path, so a bare name shifts by one — while
     // `crate::…`, a leading `::` and `self::…` mean something already, and prefixing those
@@ -2329,6 +2753,54 @@ fn completer_tokens(
     (decl, quote!(::std::option::Option::Some(#wrapper)))
 }
 
+/// The completer for a `config_keys` or `config_values` field: the binary's own answer to what
+/// the spec's `type=` tells every other consumer to read from its `config` block.
+///
+/// No reparse, unlike a `complete` function's wrapper — a setting's values depend only on
+/// which setting the words before the cursor name — and so no `complete` feature either: the
+/// context and the candidates are the spec's.
+fn config_completer(
+    wrapper: &syn::Ident,
+    field: &Field,
+    settings: &syn::Type,
+    owner: &syn::Ident,
+) -> TokenStream {
+    let cfg = crate::config::config_path();
+    let body = if field.complete_type.as_deref() == Some("config_values") {
+        quote! {
+            let __usage_declaration = <#owner as usage_argv::spec::CommandArgs>::COMMAND;
+            let __usage_words = ctx
+                .command_for(__usage_declaration)
+                .map_or(ctx.command_words, |(_, words)| words);
+            #cfg::command::value_candidates(<#settings>::SETTINGS_REGISTRY, __usage_words)
+                .into_iter()
+                .map(usage_argv::spec::Candidate::new)
+                .collect()
+        }
+    } else {
+        quote! {
+            let _ = ctx;
+            #cfg::command::key_candidates(<#settings>::SETTINGS_REGISTRY)
+                .into_iter()
+                .map(|(key, help)| {
+                    if help.is_empty() {
+                        usage_argv::spec::Candidate::new(key)
+                    } else {
+                        usage_argv::spec::Candidate::described(key, help)
+                    }
+                })
+                .collect()
+        }
+    };
+    quote! {
+        fn #wrapper(
+            ctx: &usage_argv::spec::CompleteCtx<'_>,
+        ) -> ::std::vec::Vec<usage_argv::spec::Candidate<'static>> {
+            #body
+        }
+    }
+}
+
 fn flag_meta(cli: &Cli, i: usize, field: &Field, owner: &syn::Ident) -> TokenStream {
     let name = format_ident!("FLAG_META_{i}");
     let table = format_ident!("FLAG_{i}");
@@ -2354,6 +2826,7 @@ fn flag_meta(cli: &Cli, i: usize, field: &Field, owner: &syn::Ident) -> TokenStr
     let hide_possible_values = field.hide_possible_values;
     let hide_short_help = field.hide_short_help;
     let hide_long_help = field.hide_long_help;
+    let sensitive = field.sensitive;
     let count = field.shape == Shape::Count;
     let repeatable = field.repeatable;
     let hidden_longs = match &field.kind {
@@ -2489,6 +2962,7 @@ fn flag_meta(cli: &Cli, i: usize, field: &Field, owner: &syn::Ident) -> TokenStr
             hide_possible_values: #hide_possible_values,
             hide_short_help: #hide_short_help,
             hide_long_help: #hide_long_help,
+            sensitive: #sensitive,
             count: #count,
             repeatable: #repeatable,
             hidden_shorts: &[],
@@ -2545,6 +3019,7 @@ fn arg_meta(cli: &Cli, i: usize, field: &Field, owner: &syn::Ident) -> TokenStre
     let hide_possible_values = field.hide_possible_values;
     let hide_short_help = field.hide_short_help;
     let hide_long_help = field.hide_long_help;
+    let sensitive = field.sensitive;
     let conflicts: Vec<String> = field
         .conflicts
         .iter()
@@ -2619,6 +3094,7 @@ fn arg_meta(cli: &Cli, i: usize, field: &Field, owner: &syn::Ident) -> TokenStre
             hide_possible_values: #hide_possible_values,
             hide_short_help: #hide_short_help,
             hide_long_help: #hide_long_help,
+            sensitive: #sensitive,
             conflicts: &[#(#conflicts),*],
             requires: &[#(#requires),*],
             required_if: &[#(#required_if),*],
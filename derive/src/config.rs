@@ -86,6 +86,7 @@ pub struct Config {
     fields: Vec<Field>,
     sources: Vec<Source>,
     files: Vec<File>,
+    profiles: Option<Profiles>,
 }
 
 struct Source {
@@ -102,6 +103,11 @@ struct File {
     format: Option<String>,
 }
 
+struct Profiles {
+    env: Option<String>,
+    flag: Option<String>,
+}
+
 #[derive(Clone, Copy)]
 enum FileScope {
     Project,
@@ -140,6 +146,8 @@ struct Prop {
     merge: Option<Merge>,
     scope: Option<Scope>,
     parse: Option<String>,
+    interpolate: bool,
+    sensitive: bool,
     hide: bool,
     deprecated: Option<String>,
     deprecated_warn_at: Option<String>,
@@ -326,12 +334,22 @@ impl Config {
         let mut prefix = None;
         let mut sources = Vec::new();
         let mut files = Vec::new();
+        let mut profiles = None;
         for attr in attrs(&input.attrs) {
             for meta in nested(attr)? {
                 match ident_of(meta.path()).as_str() {
                     "prefix" => prefix = Some(string_value(&meta)?),
                     "source" => sources.push(source_decl(&meta)?),
                     "file" => files.push(file_decl(&meta)?),
+                    "profiles" => {
+                        if profiles.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                &meta,
+                                "`profiles` is given twice",
+                            ));
+                        }
+                        profiles = Some(profiles_decl(&meta)?);
+                    }
                     other => {
                         return Err(syn::Error::new_spanned(
                             meta.path(),
@@ -417,6 +435,7 @@ impl Config {
             fields,
             sources,
             files,
+            profiles,
         })
     }
 }
@@ -566,6 +585,52 @@ fn file_decl(meta: &Meta) -> syn::Result<File> {
     })
 }
 
+fn profiles_decl(meta: &Meta) -> syn::Result<Profiles> {
+    let Meta::List(_) = meta else {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "profiles are `profiles(env = \"EX_PROFILE\", flag = \"--profile\")`",
+        ));
+    };
+    let mut env = None;
+    let mut flag = None;
+    for item in nested_meta(meta)? {
+        let (slot, name) = match ident_of(item.path()).as_str() {
+            "env" => (&mut env, "env"),
+            "flag" => (&mut flag, "flag"),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    item.path(),
+                    format!("profiles do not understand `{other}`; use `env` or `flag`"),
+                ))
+            }
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(
+                &item,
+                format!("`{name}` is given twice"),
+            ));
+        }
+        *slot = Some(string_value(&item)?);
+    }
+    // A profile nothing can select is a table every file carries for no run at all.
+    if env.is_none() && flag.is_none() {
+        return Err(syn::Error::new_spanned(
+            meta,
+            "profiles need an `env` or a `flag` to be selected by",
+        ));
+    }
+    if let Some(flag) = &flag {
+        if !flag.starts_with("--") || flag.len() < 3 {
+            return Err(syn::Error::new_spanned(
+                meta,
+                format!("`{flag}` is not a long flag; a profile flag is spelled `--profile`"),
+            ));
+        }
+    }
+    Ok(Profiles { env, flag })
+}
+
 impl Field {
     fn from_field(field: &syn::Field, prefix: Option<&str>) -> syn::Result<Self> {
         let ident = field.ident.clone().expect("named fields only");
@@ -592,6 +657,8 @@ impl Field {
             merge: None,
             scope: None,
             parse: None,
+            interpolate: false,
+            sensitive: false,
             hide: false,
             deprecated: None,
             deprecated_warn_at: None,
@@ -684,6 +751,8 @@ impl Field {
                         }
                         prop.parse = Some(parse);
                     }
+                    "interpolate" => prop.interpolate = flag_value(&meta)?,
+                    "sensitive" => prop.sensitive = flag_value(&meta)?,
                     "hide" => prop.hide = flag_value(&meta)?,
                     "optional" => explicit_optional = Some(flag_value(&meta)?),
                     "deprecated" => prop.deprecated = Some(string_value(&meta)?),
@@ -719,6 +788,8 @@ impl Field {
                 (prop.merge.is_some(), "merge"),
                 (prop.scope.is_some(), "scope"),
                 (prop.parse.is_some(), "parse"),
+                (prop.interpolate, "interpolate"),
+                (prop.sensitive, "sensitive"),
                 (prop.hide, "hide"),
                 (key_attr.is_some(), "key"),
                 (ty_attr.is_some(), "ty"),
@@ -1433,6 +1504,15 @@ pub fn emit(config: &Config) -> TokenStream {
         })
     });
 
+    let profiles = config.profiles.as_ref().map(|profiles| {
+        let env = option_str(&profiles.env);
+        let flag = option_str(&profiles.flag);
+        quote!(.with_profiles(#cfg::SpecProfiles {
+            env: #env,
+            flag: #flag,
+        }))
+    });
+
     // Reads in declaration order, advancing a cursor: one id per own prop, a group's length
     // for a flattened child. Everything is read before anything is judged, so the fold holds
     // every error rather than the first one.
@@ -1525,7 +1605,7 @@ pub fn emit(config: &Config) -> TokenStream {
                     <Self as #cfg::Props>::PROP_SPECS,
                     &[#(#sources),*],
                     &[#(#files),*],
-                );
+                )#profiles;
 
                 /// This resolution's values, as the struct.
                 ///
@@ -1668,6 +1748,12 @@ fn prop_meta(prop: &Prop, cfg: &TokenStream) -> TokenStream {
         let choices = prop.choices.iter().map(|choice| choice.tokens(cfg));
         fields.push(quote!(choices: &[#(#choices),*]));
     }
+    if prop.interpolate {
+        fields.push(quote!(interpolate: true));
+    }
+    if prop.sensitive {
+        fields.push(quote!(sensitive: true));
+    }
     if prop.hide {
         fields.push(quote!(hide: true));
     }
@@ -2289,6 +2375,8 @@ mod tests {
             r#"deprecated_warn_at = "6.0.0""#,
             r#"deprecated_remove_at = "7.0.0""#,
             r#"since = "5.2.0""#,
+            "interpolate",
+            "sensitive",
             r#"help_heading = "Performance""#,
             r#"writes_to = "git""#,
             r#"x("tool.key", true)"#,
@@ -2393,6 +2481,33 @@ mod tests {
         );
         assert!(err.contains("not a config file scope"), "unhelpful: {err}");
 
+        accepted(
+            r#"
+            #[usage(profiles(env = "EX_PROFILE", flag = "--profile"))]
+            struct Settings {
+                jobs: u64,
+            }
+        "#,
+        );
+        let err = rejection(
+            r#"
+            #[usage(profiles())]
+            struct Settings {
+                jobs: u64,
+            }
+        "#,
+        );
+        assert!(err.contains("an `env` or a `flag`"), "unhelpful: {err}");
+        let err = rejection(
+            r#"
+            #[usage(profiles(flag = "-p"))]
+            struct Settings {
+                jobs: u64,
+            }
+        "#,
+        );
+        assert!(err.contains("not a long flag"), "unhelpful: {err}");
+
         let err = rejection(
             r#"
             struct Settings {
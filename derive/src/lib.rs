@@ -263,6 +263,8 @@
 //! rather than on the root, which does nothing itself — `completion`, which adds the hidden command a generated shell
 //! script calls, and needs usage-argv's `complete` feature enabled where it is depended on —
 //! `settings`, for a CLI whose bound flags all live in a flattened group (see [Settings]) —
+//! `config_command`, which adds a `config` subcommand beside the CLI's own, wired to the type
+//! `config = Settings` names and to the files it declares —
 //! and `run`, `run_with`, `run_async` and `run_async_with`, which write the forward from a
 //! container command to its subcommands (see [Dispatch](#dispatch)).
 //!
@@ -298,6 +300,7 @@
 //! | `value_enum` | the words come from the field's type, which derives [`ValueEnum`] |
 //! | `arg_group` | the flags come from the field's type, which derives [`ArgGroup`]; at most one may be given |
 //! | `value_hint = usage::ValueHint::FilePath` | ask the shell for paths, executables, or forwarded command argv |
+//! | `config_keys = Settings`, `config_values = Settings` | complete a setting's key, or a value for the key earlier on the line, from `Settings`' registry |
 //! | `arg` | force a field to be positional |
 //! | `id = "name"` | clap-compatible spelling for the field identity / positional name |
 //! | `value_name = "NAME"` | clap-compatible positional name, or the placeholder for a flag value |
@@ -93,6 +93,13 @@ pub struct Cli {
     /// type's `config` block and the spec documents the settings the way it documents the
     /// commands. Root-only, like `settings`: one spec, one `config` block.
     pub config: Option<syn::Type>,
+    /// Whether the root gains a generated `config` command over the settings `config` names.
+    ///
+    /// Opt-in, because it adds a command to somebody's CLI and a dependency on
+    /// `usage-config`'s file formats to their binary. Root-only, and only beside `config` and a
+    /// subcommand field: the command is joined into the root's subcommand table, and there has
+    /// to be one for it to join.
+    pub config_command: bool,
     /// The oldest `usage` that can read the emitted spec, when the CLI says.
     ///
     /// Declared rather than computed. Working it out would mean a table from every property to
@@ -338,6 +345,11 @@ pub struct Field {
     pub complete: Option<syn::Path>,
     /// A built-in completion class, in the spec's vocabulary (`path` or `dir`).
     pub complete_type: Option<String>,
+    /// The settings type a `config_keys` or `config_values` completion reads.
+    ///
+    /// The spec says `type="config_keys"` and every consumer answers from its `config` block;
+    /// the binary has no block to read, so it answers from the type's registry instead.
+    pub config_complete: Option<syn::Type>,
     pub var_min: Option<usize>,
     pub var_max: Option<usize>,
     /// Bounds on the values consumed by one flag occurrence.
@@ -411,6 +423,8 @@ pub struct Field {
     pub hide_possible_values: bool,
     pub hide_short_help: bool,
     pub hide_long_help: bool,
+    /// A secret: help leaves its default out and errors do not quote it.
+    pub sensitive: bool,
     /// Whether the flag may be given more than once, taking one value each time.
     ///
     /// Distinct from [`Kind::Flag::variadic`], which is one occurrence taking
@@ -711,6 +725,7 @@ impl Cli {
             settings: false,
             dispatch: Dispatch::default(),
             config: None,
+            config_command: false,
             min_usage_version: None,
             usage: None,
             help_template: None,
@@ -854,6 +869,7 @@ impl Cli {
                         }
                         cli.config = Some(syn::parse2(quote::ToTokens::to_token_stream(value))?);
                     }
+                    "config_command" => cli.config_command = flag_value(&meta)?,
                     "verbatim_doc_comment" => verbatim_doc_comment = flag_value(&meta)?,
                     "effect" => cli.effect = Some(effect_value(&meta)?),
                     "alias" | "aliases" if clap_attr => {
@@ -1239,6 +1255,13 @@ impl Cli {
                      spec carries one `config` block for the whole program",
                 ));
             }
+            if self.config_command {
+                return Err(self.misplaced(
+                    ident,
+                    "`config_command` belongs on the root, where `#[derive(Cli)]` is: the \
+                     `config` command it adds reads and writes the whole program's settings",
+                ));
+            }
             // One spec, one claim about which `usage` can read it — and only the root writes a
             // spec at all, so a command declaring it was storing a value with nowhere to go.
             if self.min_usage_version.is_some() {
@@ -1328,6 +1351,29 @@ impl Cli {
             ));
         }
 
+        // The command is generated over a settings type, and joined into the root's own
+        // subcommand table — so both have to be there. Without either, the generated code
+        // failed on a name that appears nowhere in the adopter's source.
+        if self.config_command && self.config.is_none() {
+            return Err(self.misplaced(
+                ident,
+                "`config_command` needs `config = Settings`, naming the type deriving \
+                 `usage::Config` whose settings the command reads and writes",
+            ));
+        }
+        if self.config_command
+            && !self
+                .fields
+                .iter()
+                .any(|f| matches!(f.kind, Kind::Subcommand { .. }))
+        {
+            return Err(self.misplaced(
+                ident,
+                "`config_command` adds a `config` subcommand, and this CLI has no \
+                 `#[usage(subcommand)]` field for it to sit beside",
+            ));
+        }
+
         // `mount` and `restart_token` are written on a `cmd` node, and the root is not one.
         // Verified against usage-lib, which rejects a spec that puts either at the top.
         for (present, what) in [
@@ -1888,6 +1934,7 @@ impl Field {
             effect: None,
             complete: None,
             complete_type: None,
+            config_complete: None,
             shape: Shape::Bool,
             value_ty: None,
             optional_collection: false,
@@ -1943,6 +1990,7 @@ impl Field {
             hide_possible_values: false,
             hide_short_help: false,
             hide_long_help: false,
+            sensitive: false,
             repeatable: false,
             action: ArgAction::Set,
             span,
@@ -2021,6 +2069,7 @@ impl Field {
             effect: None,
             complete: None,
             complete_type: None,
+            config_complete: None,
             // A flattened field holds declarations, not a value, so none of what describes a
             // value applies — the same as a subcommand field.
             shape: Shape::Bool,
@@ -2078,6 +2127,7 @@ impl Field {
             hide_possible_values: false,
             hide_short_help: false,
             hide_long_help: false,
+            sensitive: false,
             repeatable: false,
             action: ArgAction::Set,
             span,
@@ -2173,6 +2223,7 @@ impl Field {
             effect: None,
             complete: None,
             complete_type: None,
+            config_complete: None,
             // The field holds declarations rather than a value, the same as a flatten.
             shape: Shape::Bool,
             value_ty: None,
@@ -2229,6 +2280,7 @@ impl Field {
             hide_possible_values: false,
             hide_short_help: false,
             hide_long_help: false,
+            sensitive: false,
             repeatable: false,
             action: ArgAction::Set,
             span,
@@ -2301,6 +2353,7 @@ impl Field {
             effect: None,
             complete: None,
             complete_type: None,
+            config_complete: None,
             // A subcommand field holds a command, not a value, so none of what
             // describes a value applies to it.
             shape: Shape::Bool,
@@ -2358,6 +2411,7 @@ impl Field {
             hide_possible_values: false,
             hide_short_help: false,
             hide_long_help: false,
+            sensitive: false,
             repeatable: false,
             action: ArgAction::Set,
             span,
@@ -2442,6 +2496,7 @@ impl Field {
         let mut hide_possible_values = false;
         let mut hide_short_help = false;
         let mut hide_long_help = false;
+        let mut sensitive = false;
         let mut is_arg = false;
         let mut choices: Vec<String> = Vec::new();
         let mut allow_unknown_choices = false;
@@ -2449,6 +2504,7 @@ impl Field {
         let mut validate_error: Option<String> = None;
         let mut complete: Option<syn::Path> = None;
         let mut complete_type: Option<String> = None;
+        let mut config_complete: Option<syn::Type> = None;
         let mut value_enum = false;
         let mut var_min: Option<usize> = None;
         let mut var_max: Option<usize> = None;
@@ -2549,6 +2605,7 @@ impl Field {
                     "hide_possible_values" => hide_possible_values = flag_value(&meta)?,
                     "hide_short_help" => hide_short_help = flag_value(&meta)?,
                     "hide_long_help" => hide_long_help = flag_value(&meta)?,
+                    "sensitive" => sensitive = flag_value(&meta)?,
                     "arg" => is_arg = flag_value(&meta)?,
                     "env" => match &meta {
                         Meta::Path(_) => {
@@ -2581,7 +2638,39 @@ impl Field {
                         };
                         complete = Some(path.path.clone());
                     }
+                    "value_hint" if config_complete.is_some() => {
+                        return Err(syn::Error::new_spanned(
+                            path,
+                            "`value_hint` and `config_keys`/`config_values` both answer \
+                             completion for this value; use one",
+                        ));
+                    }
                     "value_hint" => complete_type = Some(value_hint(&meta)?),
+                    // The settings a hand-written `config get` or `config set` takes, completed
+                    // from the registry of the type named. A type rather than a flag, because
+                    // a field cannot see the root's `config = ...` from here.
+                    word @ ("config_keys" | "config_values") => {
+                        if complete_type.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                "`value_hint` and `config_keys`/`config_values` both answer \
+                                 completion for this value; use one",
+                            ));
+                        }
+                        let value = &meta.require_name_value()?.value;
+                        if !matches!(value, Expr::Path(_)) {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                format!(
+                                    "`{word}` names a type deriving `usage::Config`, as in \
+                                     `{word} = Settings`"
+                                ),
+                            ));
+                        }
+                        complete_type = Some(word.to_string());
+                        config_complete =
+                            Some(syn::parse2(quote::ToTokens::to_token_stream(value))?);
+                    }
                     "choices" => {
                         let Meta::List(list) = &meta else {
                             return Err(syn::Error::new_spanned(
@@ -2783,10 +2872,10 @@ impl Field {
                                 "unknown option `{other}`; a field takes `name`, `id`, `long`, \
                                  `short`, `negate`, `global`, `var`, `variadic`, \
                                  `count`, `action`, `hide`, `hide_default_value`, `hide_env`, `hide_env_values`, `deprecated`, `deprecated_warn_at`, `deprecated_remove_at`, \
-                                 `hide_possible_values`, `hide_short_help`, `hide_long_help`, \
+                                 `hide_possible_values`, `hide_short_help`, `hide_long_help`, `sensitive`, \
                                  `arg`, `env`, `env_fallback`, `deprecated_env`, `default`, `default_value_t`, `choices`, `validate`, \
                                  `validate_error`, \
-                                 `var_min`, `var_max`, `value_enum`, `value_hint`, `overrides`, \
+                                 `var_min`, `var_max`, `value_enum`, `value_hint`, `config_keys`, `config_values`, `overrides`, \
                                  `conflicts`, `requires`, `group`, `exclusive`, \
                                  `delimiter`, `allow_hyphen_values`, `allow_negative_numbers`, \
                                  `value_terminator`, `require_equals`, `bool_value`, \
@@ -3054,6 +3143,19 @@ impl Field {
                 "`validate_error` needs a `validate` expression to report for",
             ));
         }
+        if config_complete.is_some() && matches!(shape, Shape::Bool | Shape::Count) {
+            return Err(syn::Error::new(
+                span,
+                "`config_keys` and `config_values` complete a value, and this field takes none",
+            ));
+        }
+        if config_complete.is_some() && complete.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`config_keys` and `config_values` already answer completion for this value, \
+                 so a `complete` function beside them would never be asked",
+            ));
+        }
         if complete_type.is_some() && matches!(shape, Shape::Bool | Shape::Count) {
             return Err(syn::Error::new(
                 span,
@@ -3712,6 +3814,7 @@ impl Field {
             validate_error,
             complete,
             complete_type,
+            config_complete,
             value_enum,
             var_min,
             var_max,
@@ -3743,6 +3846,7 @@ impl Field {
             hide_possible_values,
             hide_short_help,
             hide_long_help,
+            sensitive,
             repeatable,
             action,
             span,
@@ -64,6 +64,7 @@ export default defineConfig({
         link: "/rust/",
         items: [
           { text: "Quickstart", link: "/rust/quickstart" },
+          { text: "Starting from a Spec", link: "/rust/scaffold" },
           { text: "Args and Flags", link: "/rust/args-and-flags" },
           { text: "Updating Values", link: "/rust/update-from" },
           { text: "Subcommands", link: "/rust/subcommands" },
@@ -731,12 +731,7 @@ func (p *Parser) reserveForRequiredPositionals() {
 		if requiredAfter == 0 {
 			return
 		}
-		remainingValues := 1
-		for _, word := range p.argv[p.pos:] {
-			if p.flagsStopped || !isFlagLike(word) {
-				remainingValues++
-			}
-		}
+		remainingValues := 1 + p.positionalWordsAhead()
 		if remainingValues > requiredAfter {
 			return
 		}
@@ -744,6 +739,58 @@ func (p *Parser) reserveForRequiredPositionals() {
 	}
 }
 
+// positionalWordsAhead counts the words after this one that would bind to a
+// positional.
+//
+// A word that is a known flag's separate value is not one: `optional required
+// -f x` has one positional left after `optional`, not two. Only as precise as a
+// look-ahead can be — a flag this command does not know is taken to have no
+// value. Kept in step with usage-argv and usage-lib, which reserve the same words.
+func (p *Parser) positionalWordsAhead() int {
+	count := 0
+	stopped := p.flagsStopped
+	words := p.argv[p.pos:]
+	for i := 0; i < len(words); i++ {
+		word := words[i]
+		switch {
+		case stopped || !isFlagLike(word):
+			count++
+		case word == "--":
+			stopped = true
+		case p.valueIsTheNextWord(word):
+			i++
+		}
+	}
+	return count
+}
+
+// valueIsTheNextWord reports whether a flag-like word leaves its value to the
+// word after it.
+func (p *Parser) valueIsTheNextWord(word string) bool {
+	if len(word) >= 2 && word[:2] == "--" {
+		for i := 2; i < len(word); i++ {
+			if word[i] == '=' {
+				return false
+			}
+		}
+		flag := p.findLong(word[2:])
+		return flag != nil && flag.TakesValue
+	}
+	// A short cluster: the first flag that takes a value takes the rest of the
+	// word, and only when there is no rest does it take the next one.
+	shorts := word[1:]
+	for i := 0; i < len(shorts); i++ {
+		flag := p.findShort(shorts[i])
+		if flag == nil {
+			return false
+		}
+		if flag.TakesValue {
+			return i+1 == len(shorts)
+		}
+	}
+	return false
+}
+
 // eachInScope calls fn with every flag a token here could name: this command's
 // own, then any ancestor's globals, stopping at the first one fn accepts.
 //
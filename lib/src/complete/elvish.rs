@@ -0,0 +1,142 @@
+use heck::ToSnakeCase;
+
+use crate::complete::CompleteOptions;
+
+pub fn complete_elvish(opts: &CompleteOptions) -> String {
+    let usage_bin = &opts.usage_bin;
+    let bin = &opts.bin;
+    let bin_snake = bin.to_snake_case();
+    let spec_variable = if let Some(cache_key) = &opts.cache_key {
+        format!("_usage_spec_{bin_snake}_{}", cache_key.to_snake_case())
+    } else {
+        format!("_usage_spec_{bin_snake}")
+    };
+    let generated_comment = if let Some(source_file) = &opts.source_file {
+        format!("# @generated by usage-cli from {source_file}")
+    } else {
+        "# @generated by usage-cli from usage spec".to_string()
+    };
+    let mut out = vec![
+        generated_comment,
+        r#"
+use os
+use str"#
+            .to_string(),
+    ];
+
+    if let Some(spec) = &opts.spec {
+        // elvish writes a quote inside single quotes by doubling it, and nothing else in a
+        // single-quoted string means anything.
+        let spec_escaped = spec.to_string().replace('\'', "''");
+        out.push(format!(
+            r#"
+var {spec_variable} = '{spec_escaped}'"#
+        ));
+    }
+
+    // The cache filename is version-keyed and the cache dir is now persistent
+    // (unlike the reboot-cleared temp dir), so old versions would accumulate
+    // forever. On a cache miss, reap this bin's spec files not regenerated in
+    // the last 30 days. Age-based (not "delete all other versions") so that
+    // running two versions of the same tool concurrently doesn't thrash — each
+    // live version's spec stays recent and survives.
+    let prune_stale = format!(
+        r#"try {{ e:find $spec_dir -maxdepth 1 -name 'usage__usage_spec_{bin_snake}_*.spec' -type f -mtime +30 -delete 2>$os:dev-null }} catch {{ }}"#
+    );
+
+    // Build logic to write spec directly to file without storing in shell variables
+    let file_write_logic = if let Some(usage_cmd) = &opts.usage_cmd {
+        if opts.cache_key.is_some() {
+            format!(
+                r#"if (not (os:exists $spec_file)) {{
+            {prune_stale}
+            {usage_cmd} > $spec_file
+        }}"#
+            )
+        } else {
+            format!(r#"{usage_cmd} > $spec_file"#)
+        }
+    } else if let Some(_spec) = &opts.spec {
+        if opts.cache_key.is_some() {
+            format!(
+                r#"if (not (os:exists $spec_file)) {{
+            {prune_stale}
+            print ${spec_variable} > $spec_file
+        }}"#
+            )
+        } else {
+            format!(r#"print ${spec_variable} > $spec_file"#)
+        }
+    } else {
+        String::new()
+    };
+
+    out.push(format!(
+        r#"
+# if "{usage_bin}" is not installed show an error
+if (not (has-external '{usage_bin}')) {{
+    echo >&2 "Error: {usage_bin} CLI not found. This is required for completions to work in {bin}."
+    echo >&2 "See https://usage.jdx.dev for more information."
+}} else {{
+    set edit:completion:arg-completer['{bin}'] = {{|@words|
+        var spec_dir = (if (has-env XDG_CACHE_HOME) {{ put $E:XDG_CACHE_HOME }} else {{ put ~/.cache }})/usage
+        os:mkdir-all &perm=0o700 $spec_dir
+        var spec_file = $spec_dir/usage_{spec_variable}.spec
+        {file_write_logic}
+
+        # elvish passes the words already unquoted, the one being completed last, and quotes
+        # whatever is put back on the way to the prompt.
+        var usage = (external '{usage_bin}')
+        $usage complete-word --shell elvish -f $spec_file -- $@words | from-lines | each {{|line|
+            var parts = [(str:split &max=2 "\t" $line)]
+            if (and (> (count $parts) 1) (not-eq $parts[1] '')) {{
+                edit:complex-candidate $parts[0] &display=$parts[0]' -- '$parts[1]
+            }} else {{
+                edit:complex-candidate $parts[0]
+            }}
+        }}
+    }}
+}}
+"#
+    ));
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::SPEC_KITCHEN_SINK;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_complete_elvish() {
+        assert_snapshot!(complete_elvish(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "elvish".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: None,
+            spec: None,
+            usage_cmd: Some("mycli complete --usage".to_string()),
+            source_file: None,
+        }));
+        assert_snapshot!(complete_elvish(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "elvish".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: Some("1.2.3".to_string()),
+            spec: None,
+            usage_cmd: Some("mycli complete --usage".to_string()),
+            source_file: None,
+        }));
+        assert_snapshot!(complete_elvish(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "elvish".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: None,
+            spec: Some(SPEC_KITCHEN_SINK.clone()),
+            usage_cmd: None,
+            source_file: None,
+        }));
+    }
+}
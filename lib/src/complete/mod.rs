@@ -2,16 +2,18 @@ use crate::error::UsageErr;
 use crate::Spec;
 
 mod bash;
+mod elvish;
 mod fish;
 mod nu;
 mod powershell;
+mod xonsh;
 mod zsh;
 
 /// Options for generating shell completion scripts.
 pub struct CompleteOptions {
     /// Path to the `usage` binary (e.g., "usage" or "/usr/local/bin/usage").
     pub usage_bin: String,
-    /// Target shell: "bash", "fish", "zsh", or "powershell".
+    /// Target shell: "bash", "elvish", "fish", "nu", "powershell", "xonsh", or "zsh".
     pub shell: String,
     /// Name of the CLI binary to generate completions for.
     pub bin: String,
@@ -35,7 +37,10 @@ pub struct CompleteOptions {
 ///
 /// # Supported Shells
 /// - `bash` - Bash completion using `complete` builtin
+/// - `elvish` - Elvish completion using `edit:completion:arg-completer`
 /// - `fish` - Fish shell completions
+/// - `nu` - Nushell completion using an `@complete` external
+/// - `xonsh` - Xonsh completion using a contextual command completer
 /// - `zsh` - Zsh completion using `compdef`
 /// - `powershell` - PowerShell completion using `Register-ArgumentCompleter`
 pub fn complete(options: &CompleteOptions) -> Result<String, UsageErr> {
@@ -62,9 +67,11 @@ pub fn complete(options: &CompleteOptions) -> Result<String, UsageErr> {
     };
     match options.shell.as_str() {
         "bash" => Ok(bash::complete_bash(options)),
+        "elvish" => Ok(elvish::complete_elvish(options)),
         "fish" => Ok(fish::complete_fish(options)),
         "nu" => Ok(nu::complete_nu(options)),
         "powershell" => Ok(powershell::complete_powershell(options)),
+        "xonsh" => Ok(xonsh::complete_xonsh(options)),
         "zsh" => Ok(zsh::complete_zsh(options)),
         _ => Err(UsageErr::UnsupportedShell(options.shell.clone())),
     }
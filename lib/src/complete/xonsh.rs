@@ -0,0 +1,220 @@
+use heck::ToSnakeCase;
+
+use crate::complete::CompleteOptions;
+
+pub fn complete_xonsh(opts: &CompleteOptions) -> String {
+    let usage_bin_raw = &opts.usage_bin;
+    let usage_bin = python_str(usage_bin_raw);
+    let bin = &opts.bin;
+    let bin_py = python_str(bin);
+    let bin_snake = bin.to_snake_case();
+    let spec_variable = if let Some(cache_key) = &opts.cache_key {
+        format!("_usage_spec_{bin_snake}_{}", cache_key.to_snake_case())
+    } else {
+        format!("_usage_spec_{bin_snake}")
+    };
+    let generated_comment = if let Some(source_file) = &opts.source_file {
+        format!("# @generated by usage-cli from {source_file}")
+    } else {
+        "# @generated by usage-cli from usage spec".to_string()
+    };
+    // Plain Python rather than xonsh's subprocess syntax, so what it runs is never up to how
+    // xonsh happens to tokenize a line.
+    let mut out = vec![
+        generated_comment,
+        r#"
+import glob
+import os
+import shlex
+import shutil
+import subprocess
+import sys
+import time
+
+from xonsh.built_ins import XSH
+from xonsh.completers.completer import add_one_completer
+from xonsh.completers.tools import RichCompletion, contextual_command_completer_for"#
+            .to_string(),
+    ];
+
+    if let Some(spec) = &opts.spec {
+        out.push(format!(
+            r#"
+{spec_variable} = {}"#,
+            python_str(&spec.to_string())
+        ));
+    }
+
+    // The cache filename is version-keyed and the cache dir is now persistent
+    // (unlike the reboot-cleared temp dir), so old versions would accumulate
+    // forever. On a cache miss, reap this bin's spec files not regenerated in
+    // the last 30 days. Age-based (not "delete all other versions") so that
+    // running two versions of the same tool concurrently doesn't thrash — each
+    // live version's spec stays recent and survives.
+    let prune_stale = format!(
+        r#"for stale in glob.glob(os.path.join(spec_dir, "usage__usage_spec_{bin_snake}_*.spec")):
+            if os.path.getmtime(stale) < time.time() - 30 * 86400:
+                os.remove(stale)"#
+    );
+
+    // Build logic to write spec directly to file without storing in shell variables
+    let file_write_logic = if let Some(usage_cmd) = &opts.usage_cmd {
+        let usage_cmd = python_str(usage_cmd);
+        let write = format!(
+            r#"with open(spec_file, "w") as f:
+        subprocess.run(shlex.split({usage_cmd}), stdout=f, env=env)"#
+        );
+        if opts.cache_key.is_some() {
+            format!(
+                r#"if not os.path.exists(spec_file):
+        {prune_stale}
+        {}"#,
+                indent(&write)
+            )
+        } else {
+            write
+        }
+    } else if let Some(_spec) = &opts.spec {
+        let write = format!(
+            r#"with open(spec_file, "w") as f:
+        f.write({spec_variable})"#
+        );
+        if opts.cache_key.is_some() {
+            format!(
+                r#"if not os.path.exists(spec_file):
+        {prune_stale}
+        {}"#,
+                indent(&write)
+            )
+        } else {
+            write
+        }
+    } else {
+        String::new()
+    };
+
+    out.push(format!(
+        r#"
+
+@contextual_command_completer_for({bin_py})
+def _usage_complete_{bin_snake}(command):
+    # xonsh's own environment rather than the process's, which it does not keep up to date.
+    env = XSH.env.detype()
+    spec_dir = os.path.join(
+        env.get("XDG_CACHE_HOME") or os.path.join(os.path.expanduser("~"), ".cache"), "usage"
+    )
+    os.makedirs(spec_dir, mode=0o700, exist_ok=True)
+    spec_file = os.path.join(spec_dir, "usage_{spec_variable}.spec")
+    {file_write_logic}
+
+    words = [arg.value for arg in command.args[: command.arg_index]] + [command.prefix]
+    try:
+        out = subprocess.run(
+            [{usage_bin}, "complete-word", "--shell", "xonsh", "-f", spec_file, "--", *words],
+            capture_output=True,
+            text=True,
+            env=env,
+        ).stdout
+    except OSError:
+        return None
+    typed = command.opening_quote + command.prefix
+    completions = set()
+    for line in out.splitlines():
+        if not line:
+            continue
+        value, _, description = line.partition("\t")
+        # A value xonsh would split in two is inserted as a Python string, which it reads whole.
+        needs_quotes = any(c.isspace() or c in "'\"" for c in value)
+        completions.add(
+            RichCompletion(
+                repr(value) if needs_quotes else value,
+                display=value,
+                description=description,
+                prefix_len=len(typed),
+            )
+        )
+    return completions
+
+
+# if "{usage_bin_raw}" is not installed show an error
+if shutil.which({usage_bin}, path=XSH.env.detype().get("PATH")) is None:
+    print(
+        "Error: " + {usage_bin} + " CLI not found. This is required for completions to work in " + {bin_py} + ".",
+        file=sys.stderr,
+    )
+    print("See https://usage.jdx.dev for more information.", file=sys.stderr)
+else:
+    add_one_completer("usage_{bin_snake}", _usage_complete_{bin_snake}, "start")
+"#
+    ));
+
+    out.join("\n")
+}
+
+/// `text` as a single-quoted Python string literal.
+fn python_str(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('\'');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// Indent every line after the first by another four spaces, for a block nested one deeper.
+fn indent(block: &str) -> String {
+    block.replace('\n', "\n    ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::SPEC_KITCHEN_SINK;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_complete_xonsh() {
+        assert_snapshot!(complete_xonsh(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "xonsh".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: None,
+            spec: None,
+            usage_cmd: Some("mycli complete --usage".to_string()),
+            source_file: None,
+        }));
+        assert_snapshot!(complete_xonsh(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "xonsh".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: Some("1.2.3".to_string()),
+            spec: None,
+            usage_cmd: Some("mycli complete --usage".to_string()),
+            source_file: None,
+        }));
+        assert_snapshot!(complete_xonsh(&CompleteOptions {
+            usage_bin: "usage".to_string(),
+            shell: "xonsh".to_string(),
+            bin: "mycli".to_string(),
+            cache_key: None,
+            spec: Some(SPEC_KITCHEN_SINK.clone()),
+            usage_cmd: None,
+            source_file: None,
+        }));
+    }
+
+    #[test]
+    fn a_spec_is_embedded_as_a_python_literal() {
+        assert_eq!(python_str("it's"), r"'it\'s'");
+        assert_eq!(python_str("a\\b\nc"), r"'a\\b\nc'");
+    }
+}
@@ -6,6 +6,7 @@ pub fn render_help(spec: &Spec, cmd: &SpecCommand, long: bool) -> String {
     // Convert to docs models to get layout calculations
     let docs_spec = crate::docs::models::Spec::from(spec.clone());
     let mut docs_cmd = crate::docs::models::SpecCommand::from(&without_hidden(cmd, long));
+    docs_cmd.inherit_exits(&docs_spec.inherited_exits(&docs_cmd.full_cmd));
 
     let mut ctx = tera::Context::new();
     ctx.insert("spec", &docs_spec);
@@ -1033,6 +1034,34 @@ example "testcli" header="Run normally" help="Just runs the tool"
         ");
     }
 
+    #[test]
+    fn a_long_page_lists_the_exit_statuses_a_command_inherits_with_its_own() {
+        let spec = crate::spec! { r#"
+bin "testcli"
+exit 1 "something failed"
+exit 3 "lockfile out of date"
+cmd "install" {
+    exit 3 "the package is not in the lockfile"
+    exit 75 "try again later"
+}
+        "# }
+        .unwrap();
+        let install = &spec.cmd.subcommands["install"];
+
+        assert!(!render_help(&spec, install, false).contains("Exit status"));
+        assert_snapshot!(render_help(&spec, install, true), @"
+        Usage: testcli install
+
+        Flags:
+          -h, --help  Print help
+
+        Exit status:
+          1    something failed
+          3    the package is not in the lockfile
+          75   try again later
+        ");
+    }
+
     #[test]
     fn test_render_help_with_version() {
         let spec = crate::spec! { r#"
@@ -1152,6 +1181,83 @@ cmd "new-cmd" help="Do something better"
         ");
     }
 
+    #[test]
+    fn test_render_help_with_stability_badges() {
+        let spec = crate::spec! { r#"
+bin "testcli"
+flag "--fast" help="Go fast" stability="experimental"
+arg "[path]" help="Where" since="2.0.0"
+cmd "sync" help="Sync things" stability="beta" since="2024.3.0"
+cmd "old" help="Old thing" deprecated="use sync" stability="experimental"
+cmd "status" help="Show status" stability="stable"
+        "# }
+        .unwrap();
+
+        assert_snapshot!(render_help(&spec, &spec.cmd, false), @"
+        Usage: testcli [--fast] [path] <SUBCOMMAND>
+
+        Commands:
+          old [deprecated: use sync] [experimental]  Old thing
+          status  Show status
+          sync [beta, since 2024.3.0]  Sync things
+          help  Print this message or the help of the given subcommand(s)
+
+        Arguments:
+          [path]  Where [since 2.0.0]
+
+        Flags:
+              --fast  Go fast [experimental]
+          -h, --help  Print help
+        ");
+        assert_snapshot!(render_help(&spec, &spec.cmd, true), @"
+        Usage: testcli [--fast] [path] <SUBCOMMAND>
+
+        Commands:
+          old [deprecated: use sync] [experimental]
+            Old thing
+
+          status
+            Show status
+
+          sync [beta, since 2024.3.0]
+            Sync things
+
+          help
+            Print this message or the help of the given subcommand(s)
+
+        Arguments:
+          [path]  Where
+            [since 2.0.0]
+
+        Flags:
+              --fast  Go fast
+            [experimental]
+          -h, --help  Print help
+        ");
+        assert_snapshot!(render_help(&spec, &spec.cmd.subcommands["sync"], false), @"
+        Sync things
+
+        [beta, since 2024.3.0]
+
+        Usage: testcli sync
+
+        Flags:
+          -h, --help  Print help
+        ");
+        assert_snapshot!(render_help(&spec, &spec.cmd.subcommands["old"], false), @"
+        Old thing
+
+        [deprecated: use sync]
+
+        [experimental]
+
+        Usage: testcli old
+
+        Flags:
+          -h, --help  Print help
+        ");
+    }
+
     #[test]
     fn deprecation_milestones_do_not_need_a_message() {
         let spec = crate::spec! { r#"
@@ -1,3 +1,5 @@
+mod page;
 mod renderer;
 
-pub use renderer::ManpageRenderer;
+pub use page::ManpageFormat;
+pub use renderer::{ManpageFile, ManpageRenderer};
@@ -0,0 +1,463 @@
+use roff::Roff;
+use strum::{Display as StrumDisplay, EnumString};
+
+/// The macro package a manual page is written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, StrumDisplay)]
+#[strum(serialize_all = "lowercase")]
+pub enum ManpageFormat {
+    /// The `man` macros (`.TH`, `.SH`, `.TP`), which man-db and groff read everywhere.
+    #[default]
+    Man,
+    /// BSD `mdoc` (`.Dd`, `.Sh`, `.Bl`), which the BSDs ship and `mandoc -Tlint` checks.
+    Mdoc,
+}
+
+/// A run of text in one font, or a reference to another page.
+#[derive(Debug, Clone)]
+pub(crate) enum Span {
+    Roman(String),
+    Bold(String),
+    Italic(String),
+    /// Another manual page, by name and section: `mise-install(1)`.
+    Xref(String, u8),
+}
+
+pub(crate) fn roman(text: impl Into<String>) -> Span {
+    Span::Roman(text.into())
+}
+
+pub(crate) fn bold(text: impl Into<String>) -> Span {
+    Span::Bold(text.into())
+}
+
+pub(crate) fn italic(text: impl Into<String>) -> Span {
+    Span::Italic(text.into())
+}
+
+pub(crate) fn xref(page: impl Into<String>, section: u8) -> Span {
+    Span::Xref(page.into(), section)
+}
+
+/// One manual page being written, in either macro package.
+///
+/// The renderer says what it means — a section, a tagged item, an indented block — and this
+/// says it in the package's own terms. The two differ most in structure: `man` ends a `.TP`
+/// list implicitly at the next paragraph, where `mdoc` wants every `.Bl` closed by an `.El`,
+/// so the mdoc side tracks what is open.
+pub(crate) enum Page {
+    Man(Roff),
+    Mdoc(Mdoc),
+}
+
+#[derive(Default)]
+pub(crate) struct Mdoc {
+    lines: Vec<String>,
+    open: Vec<Block>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// A `.Bl -tag` list, from the first `.It` to its `.El`.
+    List,
+    /// A `.Bd` display, from an indent to its outdent.
+    Display,
+}
+
+impl Page {
+    pub(crate) fn new(format: ManpageFormat) -> Self {
+        match format {
+            ManpageFormat::Man => Page::Man(Roff::new()),
+            ManpageFormat::Mdoc => Page::Mdoc(Mdoc::default()),
+        }
+    }
+
+    /// The page's header. `man` takes no date unless one is given; `mdoc` requires one, and
+    /// without it says `$Mdocdate$`, the placeholder OpenBSD's tooling fills in.
+    pub(crate) fn title(&mut self, title: &str, section: u8, date: Option<&str>) {
+        let section = section.to_string();
+        match self {
+            Page::Man(roff) => {
+                match date {
+                    Some(date) => roff.control("TH", [title, section.as_str(), date]),
+                    None => roff.control("TH", [title, section.as_str()]),
+                };
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.macro_line(format!(".Dd {}", date.unwrap_or("$Mdocdate$")));
+                mdoc.macro_line(format!(".Dt {} {section}", escape_args(title)));
+                mdoc.macro_line(".Os".to_string());
+            }
+        }
+    }
+
+    /// The NAME section: the page's name and a one-line description.
+    pub(crate) fn name(&mut self, name: &str, description: &str) {
+        self.section("NAME");
+        match self {
+            Page::Man(roff) => {
+                roff.text([roff::roman(format!("{name} - {description}"))]);
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.macro_line(format!(".Nm {}", quote(name)));
+                mdoc.macro_line(format!(".Nd {}", quote(description)));
+            }
+        }
+    }
+
+    pub(crate) fn section(&mut self, title: &str) {
+        match self {
+            Page::Man(roff) => {
+                roff.control("SH", [title]);
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.close_all();
+                mdoc.macro_line(format!(".Sh {}", escape_args(title)));
+            }
+        }
+    }
+
+    pub(crate) fn subsection(&mut self, title: &str) {
+        match self {
+            Page::Man(roff) => {
+                roff.control("SS", [title]);
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.close_until_display();
+                mdoc.open_block(format!(".Ss {}", escape_args(title)));
+            }
+        }
+    }
+
+    /// A paragraph break. Like `.PP`, it also ends a run of tagged items.
+    pub(crate) fn paragraph(&mut self) {
+        match self {
+            Page::Man(roff) => {
+                roff.control("PP", [] as [&str; 0]);
+            }
+            Page::Mdoc(mdoc) => {
+                while mdoc.open.last() == Some(&Block::List) {
+                    mdoc.close();
+                }
+                mdoc.paragraph();
+            }
+        }
+    }
+
+    pub(crate) fn text(&mut self, spans: impl Into<Vec<Span>>) {
+        let spans = spans.into();
+        match self {
+            Page::Man(roff) => {
+                roff.text(man_inlines(spans));
+            }
+            Page::Mdoc(mdoc) => {
+                for span in spans {
+                    mdoc.span(span);
+                }
+            }
+        }
+    }
+
+    /// A tagged item: `head` in the margin, and whatever is written next beside it.
+    pub(crate) fn item(&mut self, head: impl Into<Vec<Span>>) {
+        let head = head.into();
+        match self {
+            Page::Man(roff) => {
+                roff.control("TP", [] as [&str; 0]);
+                roff.text(man_inlines(head));
+            }
+            Page::Mdoc(mdoc) => {
+                if mdoc.open.last() != Some(&Block::List) {
+                    mdoc.open_block(".Bl -tag -width Ds".to_string());
+                    mdoc.open.push(Block::List);
+                }
+                let args: Vec<String> = head.into_iter().filter_map(item_arg).collect();
+                mdoc.macro_line(format!(".It {}", args.join(" ")));
+            }
+        }
+    }
+
+    /// Start an indented block, `by` as many ens as `.RS` takes when given.
+    pub(crate) fn indent(&mut self, by: Option<&str>) {
+        match self {
+            Page::Man(roff) => {
+                match by {
+                    Some(by) => roff.control("RS", [by]),
+                    None => roff.control("RS", [] as [&str; 0]),
+                };
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.open_block(".Bd -ragged -offset indent".to_string());
+                mdoc.open.push(Block::Display);
+            }
+        }
+    }
+
+    /// End the innermost [`Page::indent`].
+    pub(crate) fn outdent(&mut self) {
+        match self {
+            Page::Man(roff) => {
+                roff.control("RE", [] as [&str; 0]);
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.close_until_display();
+                if mdoc.open.last() == Some(&Block::Display) {
+                    mdoc.close();
+                }
+            }
+        }
+    }
+
+    /// A block of code, indented and kept as written.
+    pub(crate) fn code(&mut self, code: &str) {
+        match self {
+            Page::Man(roff) => {
+                roff.control("RS", ["4"]);
+                roff.text([roff::roman(code)]);
+                roff.control("RE", [] as [&str; 0]);
+            }
+            Page::Mdoc(mdoc) => {
+                mdoc.open_block(".Bd -literal -offset indent".to_string());
+                for line in code.lines() {
+                    mdoc.lines.push(escape_text_line(line.trim_end()));
+                }
+                mdoc.macro_line(".Ed".to_string());
+            }
+        }
+    }
+
+    /// The SEE ALSO section, one reference per page named.
+    pub(crate) fn see_also(&mut self, pages: &[String], section: u8) {
+        if pages.is_empty() {
+            return;
+        }
+        self.section("SEE ALSO");
+        match self {
+            Page::Man(roff) => {
+                let mut inlines = Vec::new();
+                for (i, page) in pages.iter().enumerate() {
+                    if i > 0 {
+                        inlines.push(roff::roman(", "));
+                    }
+                    inlines.push(roff::bold(page));
+                    inlines.push(roff::roman(format!("({section})")));
+                }
+                roff.text(inlines);
+            }
+            Page::Mdoc(mdoc) => {
+                for (i, page) in pages.iter().enumerate() {
+                    let comma = if i + 1 < pages.len() { " ," } else { "" };
+                    mdoc.macro_line(format!(".Xr {} {section}{comma}", escape_args(page)));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        match self {
+            Page::Man(roff) => roff.to_roff(),
+            Page::Mdoc(mut mdoc) => {
+                mdoc.close_all();
+                let mut out = mdoc.lines.join("\n");
+                out.push('\n');
+                out
+            }
+        }
+    }
+}
+
+fn man_inlines(spans: Vec<Span>) -> Vec<roff::Inline> {
+    let mut inlines = Vec::with_capacity(spans.len());
+    for span in spans {
+        match span {
+            Span::Roman(text) => inlines.push(roff::roman(text)),
+            Span::Bold(text) => inlines.push(roff::bold(text)),
+            Span::Italic(text) => inlines.push(roff::italic(text)),
+            Span::Xref(page, section) => {
+                inlines.push(roff::bold(page));
+                inlines.push(roff::roman(format!("({section})")));
+            }
+        }
+    }
+    inlines
+}
+
+/// One span as arguments on an `.It` line, where every change of font is a macro.
+fn item_arg(span: Span) -> Option<String> {
+    match span {
+        Span::Roman(text) if text.trim().is_empty() => None,
+        Span::Roman(text) => Some(format!("No {}", quote(text.trim()))),
+        Span::Bold(text) => Some(format!("Sy {}", quote(&text))),
+        Span::Italic(text) => Some(format!("Em {}", quote(&text))),
+        Span::Xref(page, section) => Some(format!("Xr {} {section}", escape_args(&page))),
+    }
+}
+
+impl Mdoc {
+    fn macro_line(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    fn span(&mut self, span: Span) {
+        match span {
+            Span::Roman(text) => {
+                for line in text.lines() {
+                    for sentence in sentences(line.trim()) {
+                        self.lines.push(escape_text_line(sentence));
+                    }
+                }
+            }
+            Span::Bold(text) => self.macro_line(format!(".Sy {}", quote(&text))),
+            Span::Italic(text) => self.macro_line(format!(".Em {}", quote(&text))),
+            Span::Xref(page, section) => {
+                self.macro_line(format!(".Xr {} {section}", escape_args(&page)))
+            }
+        }
+    }
+
+    /// `.Pp`, unless it would open a block or follow another: mandoc reports a paragraph
+    /// macro with nothing before it as one it had to skip.
+    fn paragraph(&mut self) {
+        let skip = match self.lines.last() {
+            None => true,
+            Some(last) => [".Sh", ".Ss", ".Pp", ".It", ".Bd", ".Bl"]
+                .iter()
+                .any(|opener| last == opener || last.starts_with(&format!("{opener} "))),
+        };
+        if !skip {
+            self.lines.push(".Pp".to_string());
+        }
+    }
+
+    /// A line that starts a block of its own, and so its own spacing: mandoc reports a `.Pp`
+    /// before one as redundant.
+    fn open_block(&mut self, line: String) {
+        self.trim_paragraph();
+        self.lines.push(line);
+    }
+
+    /// Drop a paragraph break nothing follows, which mandoc also reports.
+    fn trim_paragraph(&mut self) {
+        if self.lines.last().is_some_and(|last| last == ".Pp") {
+            self.lines.pop();
+        }
+    }
+
+    fn close(&mut self) {
+        self.trim_paragraph();
+        match self.open.pop() {
+            Some(Block::List) => self.lines.push(".El".to_string()),
+            Some(Block::Display) => self.lines.push(".Ed".to_string()),
+            None => {}
+        }
+    }
+
+    fn close_until_display(&mut self) {
+        while self.open.last() == Some(&Block::List) {
+            self.close();
+        }
+    }
+
+    fn close_all(&mut self) {
+        while !self.open.is_empty() {
+            self.close();
+        }
+        self.trim_paragraph();
+    }
+}
+
+/// Split a line after each sentence: mdoc wants every sentence to start a line of its own,
+/// which is how it knows to put two spaces after the full stop.
+fn sentences(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let bytes = line.as_bytes();
+    for i in 1..bytes.len().saturating_sub(1) {
+        if matches!(bytes[i - 1], b'.' | b'?' | b'!')
+            && bytes[i] == b' '
+            && bytes[i + 1].is_ascii_uppercase()
+            // A word of two letters or more, so "e.g. Not" stays one sentence.
+            && i >= 3
+            && bytes[i - 2].is_ascii_lowercase()
+            && bytes[i - 3].is_ascii_alphabetic()
+        {
+            out.push(line[start..i].trim());
+            start = i + 1;
+        }
+    }
+    out.push(line[start..].trim());
+    out.retain(|sentence| !sentence.is_empty());
+    out
+}
+
+/// A text line: backslashes escaped, and a leading `.` or `'` kept from reading as a macro.
+fn escape_text_line(line: &str) -> String {
+    let line = line.replace('\\', "\\e");
+    if line.starts_with('.') || line.starts_with('\'') {
+        format!("\\&{line}")
+    } else {
+        line
+    }
+}
+
+/// Words on a macro line, escaped but not quoted.
+fn escape_args(text: &str) -> String {
+    text.replace('\\', "\\e")
+}
+
+/// One macro argument, quoted so its words are not read as macros of their own: help text
+/// that says "No" or "Op" would otherwise be parsed.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", escape_args(text).replace('"', "\\(dq"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentences_start_lines_of_their_own() {
+        assert_eq!(
+            sentences("Install a tool. Then use it. e.g. Not this"),
+            ["Install a tool.", "Then use it. e.g. Not this"]
+        );
+    }
+
+    #[test]
+    fn test_mdoc_closes_what_it_opens() {
+        let mut page = Page::new(ManpageFormat::Mdoc);
+        page.title("TOOL", 1, Some("January 1, 2026"));
+        page.name("tool", "Does things");
+        page.section("OPTIONS");
+        page.item([bold("--force")]);
+        page.text([roman("Force it.")]);
+        page.indent(None);
+        page.text([italic("Default: "), roman("no")]);
+        page.outdent();
+        page.item([bold("--out"), roman(" "), italic("<file>")]);
+        page.text([roman("Where to write.")]);
+        page.paragraph();
+        page.see_also(&["tool-run".to_string(), "tool-sync".to_string()], 1);
+        insta::assert_snapshot!(page.finish(), @r#"
+        .Dd January 1, 2026
+        .Dt TOOL 1
+        .Os
+        .Sh NAME
+        .Nm "tool"
+        .Nd "Does things"
+        .Sh OPTIONS
+        .Bl -tag -width Ds
+        .It Sy "--force"
+        Force it.
+        .Bd -ragged -offset indent
+        .Em "Default: "
+        no
+        .Ed
+        .It Sy "--out" Em "<file>"
+        Where to write.
+        .El
+        .Sh SEE ALSO
+        .Xr tool-run 1 ,
+        .Xr tool-sync 1
+        "#);
+    }
+}
@@ -1,13 +1,24 @@
-use crate::docs::models::{Spec, SpecArg, SpecCommand, SpecFlag};
+use std::path::PathBuf;
+
+use super::page::{bold, italic, roman, xref, ManpageFormat, Page, Span};
+use crate::docs::models::{Spec, SpecArg, SpecCommand, SpecExample, SpecFlag};
 use crate::error::UsageErr;
 use itertools::Itertools;
-use roff::{bold, italic, roman, Roff};
 
 /// Renderer for generating Unix man pages from Usage specifications
 #[derive(Debug, Clone)]
 pub struct ManpageRenderer {
     spec: Spec,
     section: u8,
+    format: ManpageFormat,
+    date: Option<String>,
+}
+
+/// One page of a [`ManpageRenderer::render_pages`] set, named as `man` looks it up.
+#[derive(Debug)]
+pub struct ManpageFile {
+    pub path: PathBuf,
+    pub content: String,
 }
 
 impl ManpageRenderer {
@@ -16,6 +27,8 @@ impl ManpageRenderer {
         Self {
             spec: spec.into(),
             section: 1,
+            format: ManpageFormat::default(),
+            date: None,
         }
     }
 
@@ -31,73 +44,238 @@ impl ManpageRenderer {
         self
     }
 
+    /// Set the macro package the page is written in (default: `man`)
+    pub fn with_format(mut self, format: ManpageFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the date in the page header, e.g. "January 1, 2026"
+    ///
+    /// Left to the caller so a build can be reproduced: `mdoc` requires a date and says
+    /// `$Mdocdate$` without one, and `man` goes without.
+    pub fn with_date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
     /// Render the complete man page
     pub fn render(&self) -> Result<String, UsageErr> {
-        let mut roff = Roff::new();
+        Ok(self.render_root(false))
+    }
+
+    /// Render a page per command, git-style: `mise.1` for the whole tree, listing every
+    /// command, and `mise-install.1`, `mise-use.1` and so on beside it, each standing alone
+    /// and pointing at its parent and children under SEE ALSO. Hidden commands get no page.
+    pub fn render_pages(&self) -> Result<Vec<ManpageFile>, UsageErr> {
+        let mut files = vec![ManpageFile {
+            path: self.file_name(&self.spec.cmd),
+            content: self.render_root(true),
+        }];
+        self.collect_pages(&self.spec.cmd, &mut files);
+        Ok(files)
+    }
+
+    fn collect_pages(&self, cmd: &SpecCommand, files: &mut Vec<ManpageFile>) {
+        for subcmd in cmd.subcommands.values().filter(|subcmd| !subcmd.hide) {
+            files.push(ManpageFile {
+                path: self.file_name(subcmd),
+                content: self.render_command_page(cmd, subcmd),
+            });
+            self.collect_pages(subcmd, files);
+        }
+    }
+
+    /// `mise-install`: the name `man` finds a command's own page under.
+    fn page_name(&self, cmd: &SpecCommand) -> String {
+        std::iter::once(self.spec.bin.as_str())
+            .chain(cmd.full_cmd.iter().map(String::as_str))
+            .join("-")
+    }
+
+    fn file_name(&self, cmd: &SpecCommand) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.page_name(cmd), self.section))
+    }
+
+    /// The page for the root command. With `pages`, the index of a [`Self::render_pages`]
+    /// set: commands are listed as references to their own pages rather than detailed here.
+    fn render_root(&self, pages: bool) -> String {
+        let mut page = Page::new(self.format);
 
         // TH (Title Header) - program name, section, date, source, manual
-        let section_str = self.section.to_string();
-        roff.control(
-            "TH",
-            [self.spec.name.to_uppercase().as_str(), section_str.as_str()],
+        page.title(
+            &self.spec.name.to_uppercase(),
+            self.section,
+            self.date.as_deref(),
         );
 
         // NAME section
-        self.render_name(&mut roff);
+        self.render_name(&mut page);
 
         // SYNOPSIS section
-        self.render_synopsis(&mut roff);
+        self.render_synopsis(&mut page);
 
         // DESCRIPTION section
-        self.render_description(&mut roff);
+        self.render_description(&mut page);
 
         // Render the main command
-        self.render_command(&mut roff, &self.spec.cmd, true);
+        self.render_command(&mut page, &self.spec.cmd, true, pages);
 
         // Render detailed sections for each subcommand
-        self.render_subcommand_details(&mut roff, &self.spec.cmd, &self.spec.bin);
+        if !pages {
+            self.render_subcommand_details(&mut page, &self.spec.cmd, &self.spec.bin);
+        }
+
+        // EXIT STATUS section: the root's. A subcommand's own are in its section above.
+        if !self.spec.cmd.exits.is_empty() {
+            page.section("EXIT STATUS");
+            self.render_exits(&mut page, &self.spec.cmd, false);
+        }
 
         // EXAMPLES section (spec-level)
         if !self.spec.examples.is_empty() {
-            roff.control("SH", ["EXAMPLES"]);
-            for (i, example) in self.spec.examples.iter().enumerate() {
-                // Add spacing between examples (but not before the first one)
-                if i > 0 {
-                    roff.control("PP", [] as [&str; 0]);
-                }
-                if let Some(header) = &example.header {
-                    roff.text([bold(header)]);
-                }
-                if let Some(help) = &example.help {
-                    roff.text([roman(help.as_str())]);
-                }
-                roff.control("PP", [] as [&str; 0]);
-                roff.control("RS", ["4"]);
-                roff.text([roman(example.code.as_str())]);
-                roff.control("RE", [] as [&str; 0]);
-            }
+            page.section("EXAMPLES");
+            self.render_examples(&mut page, &self.spec.examples);
         }
 
         // CONFIGURATION section
-        self.render_configuration(&mut roff);
+        self.render_configuration(&mut page);
+
+        if pages {
+            let children = self.children(&self.spec.cmd);
+            page.see_also(&children, self.section);
+        }
 
         if let Some(license) = &self.spec.license {
-            roff.control("SH", ["LICENSE"]);
-            roff.text([roman(license)]);
+            page.section("LICENSE");
+            page.text([roman(license)]);
         }
 
         if let Some(repository) = &self.spec.repository {
-            roff.control("SH", ["SOURCE"]);
-            roff.text([roman(repository)]);
+            page.section("SOURCE");
+            page.text([roman(repository)]);
         }
 
         // AUTHOR section (if present)
         if let Some(author) = &self.spec.author {
-            roff.control("SH", ["AUTHOR"]);
-            roff.text([roman(author)]);
+            page.section("AUTHOR");
+            page.text([roman(author)]);
         }
 
-        Ok(roff.to_roff())
+        page.finish()
+    }
+
+    /// A subcommand's own page: everything about it, its inherited exit statuses included,
+    /// since a reader who opened `mise-install(1)` has no reason to open `mise(1)` as well.
+    fn render_command_page(&self, parent: &SpecCommand, cmd: &SpecCommand) -> String {
+        let mut page = Page::new(self.format);
+        let name = self.page_name(cmd);
+        page.title(&name.to_uppercase(), self.section, self.date.as_deref());
+
+        let help = cmd.help_long.as_ref().or(cmd.help.as_ref());
+        let summary = help
+            .and_then(|help| help.lines().next())
+            .unwrap_or("No description available");
+        page.name(&name, summary);
+
+        page.section("SYNOPSIS");
+        let invocation = std::iter::once(self.spec.bin.as_str())
+            .chain(cmd.full_cmd.iter().map(String::as_str))
+            .join(" ");
+        let synopsis = self.build_synopsis(cmd, &invocation);
+        page.text([bold(&invocation), roman(" "), roman(&synopsis)]);
+
+        page.section("DESCRIPTION");
+        if let Some(help) = help {
+            for paragraph in help.split("\n\n") {
+                page.text([roman(paragraph.trim())]);
+                page.paragraph();
+            }
+        }
+        if let Some(notice) = deprecation_notice(
+            cmd.deprecated.as_deref(),
+            cmd.deprecated_warn_at.as_deref(),
+            cmd.deprecated_remove_at.as_deref(),
+        ) {
+            page.text([italic(notice)]);
+            page.paragraph();
+        }
+        if let Some(notice) = stability_notice(cmd.badge.as_deref()) {
+            page.text([italic(notice)]);
+            page.paragraph();
+        }
+        if !cmd.aliases.is_empty() {
+            page.text([italic("Aliases: "), roman(cmd.aliases.iter().join(", "))]);
+        }
+
+        if !cmd.flags.is_empty() {
+            page.section("OPTIONS");
+            for flag in &cmd.flags {
+                self.render_flag(&mut page, flag);
+            }
+        }
+
+        if cmd
+            .args
+            .iter()
+            .any(|a| a.help.is_some() || a.help_long.is_some())
+        {
+            page.section("ARGUMENTS");
+            for arg in &cmd.args {
+                self.render_arg(&mut page, arg);
+            }
+        }
+
+        let children = self.children(cmd);
+        if !children.is_empty() {
+            page.section("COMMANDS");
+            for subcmd in cmd.subcommands.values().filter(|subcmd| !subcmd.hide) {
+                let head = xref(self.page_name(subcmd), self.section);
+                self.render_subcommand_summary(&mut page, head, subcmd);
+            }
+        }
+
+        if !cmd.examples.is_empty() {
+            page.section("EXAMPLES");
+            self.render_examples(&mut page, &cmd.examples);
+        }
+
+        if !cmd.exits.is_empty() {
+            page.section("EXIT STATUS");
+            self.render_exits(&mut page, cmd, true);
+        }
+
+        let mut see_also = vec![self.page_name(parent)];
+        see_also.extend(children);
+        page.see_also(&see_also, self.section);
+
+        page.finish()
+    }
+
+    /// The pages of a command's visible children.
+    fn children(&self, cmd: &SpecCommand) -> Vec<String> {
+        cmd.subcommands
+            .values()
+            .filter(|subcmd| !subcmd.hide)
+            .map(|subcmd| self.page_name(subcmd))
+            .collect()
+    }
+
+    fn render_examples(&self, page: &mut Page, examples: &[SpecExample]) {
+        for (i, example) in examples.iter().enumerate() {
+            // Add spacing between examples (but not before the first one)
+            if i > 0 {
+                page.paragraph();
+            }
+            if let Some(header) = &example.header {
+                page.text([bold(header)]);
+            }
+            if let Some(help) = &example.help {
+                page.text([roman(help.as_str())]);
+            }
+            page.paragraph();
+            page.code(&example.code);
+        }
     }
 
     /// The settings, where a man page conventionally describes them: after the commands and
@@ -106,7 +284,7 @@ impl ManpageRenderer {
     /// Deliberately terser than the markdown: a man page is read in a terminal, so each
     /// setting gets its type, its default and how to set it, and the long-form prose stays
     /// on the web page.
-    fn render_configuration(&self, roff: &mut Roff) {
+    fn render_configuration(&self, page: &mut Page) {
         let config = &self.spec.config;
         // The same predicate the markdown page uses: a block that declares only where files
         // live is worth a CONFIGURATION section, and gating on props alone meant the same
@@ -114,41 +292,56 @@ impl ManpageRenderer {
         if config.is_empty() {
             return;
         }
-        roff.control("SH", ["CONFIGURATION"]);
+        page.section("CONFIGURATION");
         if !config.files.is_empty() {
-            roff.text([roman("Read from the following, in ascending precedence:")]);
-            roff.control("RS", ["4"]);
+            page.text([roman("Read from the following, in ascending precedence:")]);
+            page.indent(Some("4"));
             for file in &config.files {
                 let mut line = file.path.clone();
                 if file.findup {
                     line.push_str(" (and in every parent directory)");
                 }
-                roff.control("PP", [] as [&str; 0]);
-                roff.text([roman(line)]);
+                page.paragraph();
+                page.text([roman(line)]);
             }
-            roff.control("RE", [] as [&str; 0]);
+            page.outdent();
+        }
+        if let Some(profiles) = &config.profiles {
+            let selected_by: Vec<String> = [
+                profiles.flag.as_ref().map(|flag| format!("{flag} <name>")),
+                profiles.env.as_ref().map(|env| format!("{env}=<name>")),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            page.paragraph();
+            page.text([roman(format!(
+                "A named profile overlays each file with its [profiles.<name>] table, then with a \
+                 sibling file that has the profile's name before its extension. Select one with {}.",
+                selected_by.join(" or ")
+            ))]);
         }
         // By heading group, like the markdown page: the docs model already partitions the
         // settings so the two formats stay aligned, and walking the flat list dropped every
         // `help_heading` and interleaved headed settings with unheaded ones.
         for group in &config.prop_groups {
             if let Some(heading) = &group.heading {
-                roff.control("SS", [heading.as_str()]);
+                page.subsection(heading.as_str());
             }
             for prop in &group.items {
-                self.render_prop(roff, prop);
+                self.render_prop(page, prop);
             }
         }
     }
 
     /// One setting: a paragraph, its help, and its facts on one line.
-    fn render_prop(&self, roff: &mut Roff, prop: &crate::docs::models::SpecConfigProp) {
+    fn render_prop(&self, page: &mut Page, prop: &crate::docs::models::SpecConfigProp) {
         {
-            roff.control("PP", [] as [&str; 0]);
-            roff.text([bold(&prop.key)]);
-            roff.control("RS", ["4"]);
+            page.paragraph();
+            page.text([bold(&prop.key)]);
+            page.indent(Some("4"));
             if let Some(help) = prop.help.as_deref() {
-                roff.text([roman(help)]);
+                page.text([roman(help)]);
             }
             let mut facts = Vec::new();
             if let Some(ty) = &prop.type_ {
@@ -180,11 +373,11 @@ impl ManpageRenderer {
                 facts.push(format!("one of: {}", values.join(", ")));
             }
             if !facts.is_empty() {
-                roff.control("PP", [] as [&str; 0]);
-                roff.text([roman(facts.join("; "))]);
+                page.paragraph();
+                page.text([roman(facts.join("; "))]);
             }
             if let Some(deprecated) = &prop.deprecated {
-                roff.control("PP", [] as [&str; 0]);
+                page.paragraph();
                 // With the version it goes away in, as the markdown page says: a deprecation
                 // notice without the date leaves the reader with nothing to plan around, and
                 // the terminal is the one place this is *supposed* to surface.
@@ -192,39 +385,38 @@ impl ManpageRenderer {
                 if let Some(remove_at) = &prop.deprecated_remove_at {
                     notice.push_str(&format!(" Removed in {remove_at}."));
                 }
-                roff.text([roman(notice)]);
+                page.text([roman(notice)]);
             }
-            roff.control("RE", [] as [&str; 0]);
+            page.outdent();
         }
     }
 
-    fn render_name(&self, roff: &mut Roff) {
-        roff.control("SH", ["NAME"]);
+    fn render_name(&self, page: &mut Page) {
         let description = self
             .spec
             .about
             .as_deref()
             .unwrap_or("No description available");
-        roff.text([roman(format!("{} - {}", self.spec.name, description))]);
+        page.name(&self.spec.name, description);
     }
 
-    fn render_synopsis(&self, roff: &mut Roff) {
-        roff.control("SH", ["SYNOPSIS"]);
+    fn render_synopsis(&self, page: &mut Page) {
+        page.section("SYNOPSIS");
 
         if !self.spec.usage.trim().is_empty() {
             for line in self.spec.usage.lines() {
                 let line = line.trim().strip_prefix("Usage: ").unwrap_or(line.trim());
                 if let Some(rest) = line.strip_prefix(&self.spec.bin) {
-                    roff.text([bold(&self.spec.bin), roman(rest)]);
+                    page.text([bold(&self.spec.bin), roman(rest)]);
                 } else {
-                    roff.text([roman(line)]);
+                    page.text([roman(line)]);
                 }
             }
             return;
         }
 
         let synopsis = self.build_synopsis(&self.spec.cmd, &self.spec.bin);
-        roff.text([bold(&self.spec.bin), roman(" "), roman(&synopsis)]);
+        page.text([bold(&self.spec.bin), roman(" "), roman(&synopsis)]);
     }
 
     fn build_synopsis(&self, cmd: &SpecCommand, _prefix: &str) -> String {
@@ -259,14 +451,14 @@ impl ManpageRenderer {
         parts.join(" ")
     }
 
-    fn render_description(&self, roff: &mut Roff) {
-        roff.control("SH", ["DESCRIPTION"]);
+    fn render_description(&self, page: &mut Page) {
+        page.section("DESCRIPTION");
 
         if let Some(about) = &self.spec.about_long.as_ref().or(self.spec.about.as_ref()) {
             // Split into paragraphs and render each
             for paragraph in about.split("\n\n") {
-                roff.text([roman(paragraph.trim())]);
-                roff.control("PP", [] as [&str; 0]);
+                page.text([roman(paragraph.trim())]);
+                page.paragraph();
             }
         }
 
@@ -278,8 +470,8 @@ impl ManpageRenderer {
             .or(self.spec.cmd.help.as_ref())
         {
             for paragraph in help.split("\n\n") {
-                roff.text([roman(paragraph.trim())]);
-                roff.control("PP", [] as [&str; 0]);
+                page.text([roman(paragraph.trim())]);
+                page.paragraph();
             }
         }
         if let Some(notice) = deprecation_notice(
@@ -287,17 +479,21 @@ impl ManpageRenderer {
             self.spec.cmd.deprecated_warn_at.as_deref(),
             self.spec.cmd.deprecated_remove_at.as_deref(),
         ) {
-            roff.text([italic(notice)]);
-            roff.control("PP", [] as [&str; 0]);
+            page.text([italic(notice)]);
+            page.paragraph();
+        }
+        if let Some(notice) = stability_notice(self.spec.cmd.badge.as_deref()) {
+            page.text([italic(notice)]);
+            page.paragraph();
         }
     }
 
-    fn render_command(&self, roff: &mut Roff, cmd: &SpecCommand, is_root: bool) {
+    fn render_command(&self, page: &mut Page, cmd: &SpecCommand, is_root: bool, pages: bool) {
         // OPTIONS section
         if !cmd.flags.is_empty() {
-            roff.control("SH", ["OPTIONS"]);
+            page.section("OPTIONS");
             for flag in &cmd.flags {
-                self.render_flag(roff, flag);
+                self.render_flag(page, flag);
             }
         }
 
@@ -310,45 +506,28 @@ impl ManpageRenderer {
                     .any(|a| a.help.is_some() || a.help_long.is_some()))
         {
             if is_root {
-                roff.control("SH", ["ARGUMENTS"]);
+                page.section("ARGUMENTS");
             }
             for arg in &cmd.args {
-                self.render_arg(roff, arg);
+                self.render_arg(page, arg);
             }
         }
 
         // SUBCOMMANDS section - show all subcommands recursively
         let all_subcommands = cmd.all_subcommands();
         if !all_subcommands.is_empty() {
-            roff.control("SH", ["COMMANDS"]);
-            self.render_all_subcommands(roff, &self.spec.cmd, "");
+            page.section("COMMANDS");
+            self.render_all_subcommands(page, &self.spec.cmd, "", pages);
         }
 
         // EXAMPLES section
         if !cmd.examples.is_empty() {
-            roff.control("SH", ["EXAMPLES"]);
-            for (i, example) in cmd.examples.iter().enumerate() {
-                // Add spacing between examples (but not before the first one)
-                if i > 0 {
-                    roff.control("PP", [] as [&str; 0]);
-                }
-                if let Some(header) = &example.header {
-                    roff.text([bold(header)]);
-                }
-                if let Some(help) = &example.help {
-                    roff.text([roman(help.as_str())]);
-                }
-                roff.control("PP", [] as [&str; 0]);
-                roff.control("RS", ["4"]);
-                roff.text([roman(example.code.as_str())]);
-                roff.control("RE", [] as [&str; 0]);
-            }
+            page.section("EXAMPLES");
+            self.render_examples(page, &cmd.examples);
         }
     }
 
-    fn render_flag(&self, roff: &mut Roff, flag: &SpecFlag) {
-        roff.control("TP", [] as [&str; 0]);
-
+    fn render_flag(&self, page: &mut Page, flag: &SpecFlag) {
         // Build flag usage line
         let mut flag_parts = Vec::new();
 
@@ -362,90 +541,103 @@ impl ManpageRenderer {
         let flag_usage = flag_parts.join(", ");
 
         if let Some(arg) = &flag.arg {
-            roff.text([
+            page.item([
                 bold(&flag_usage),
                 roman(" "),
                 italic(format!("<{}>", arg.name)),
             ]);
         } else {
-            roff.text([bold(&flag_usage)]);
+            page.item([bold(&flag_usage)]);
         }
 
         // Flag help text
         if let Some(help) = &flag.help_long.as_ref().or(flag.help.as_ref()) {
-            roff.text([roman(help.as_str())]);
+            page.text([roman(help.as_str())]);
         }
         if let Some(notice) = deprecation_notice(
             flag.deprecated.as_deref(),
             flag.deprecated_warn_at.as_deref(),
             flag.deprecated_remove_at.as_deref(),
         ) {
-            roff.text([italic(notice)]);
+            page.text([italic(notice)]);
+        }
+        if let Some(notice) = stability_notice(flag.badge.as_deref()) {
+            page.text([italic(notice)]);
         }
 
         // Default value
         if !flag.default.is_empty() {
-            roff.control("RS", [] as [&str; 0]);
+            page.indent(None);
             let default_str = flag.default.join(", ");
-            roff.text([italic("Default: "), roman(default_str.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.text([italic("Default: "), roman(default_str.as_str())]);
+            page.outdent();
         }
 
         // Environment variable
         if let Some(env) = &flag.env {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Environment: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Environment: "), bold(env.as_str())]);
+            page.outdent();
         }
         for env in &flag.env_fallback {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Environment fallback: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Environment fallback: "), bold(env.as_str())]);
+            page.outdent();
         }
         for env in &flag.deprecated_env {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Deprecated environment: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Deprecated environment: "), bold(env.as_str())]);
+            page.outdent();
         }
     }
 
-    fn render_arg(&self, roff: &mut Roff, arg: &SpecArg) {
+    fn render_arg(&self, page: &mut Page, arg: &SpecArg) {
         if arg.help.is_none() && arg.help_long.is_none() {
             return;
         }
 
-        roff.control("TP", [] as [&str; 0]);
-        roff.text([bold(format!("<{}>", arg.name))]);
+        page.item([bold(format!("<{}>", arg.name))]);
 
         if let Some(help) = &arg.help_long.as_ref().or(arg.help.as_ref()) {
-            roff.text([roman(help.as_str())]);
+            page.text([roman(help.as_str())]);
+        }
+        if let Some(notice) = stability_notice(arg.badge.as_deref()) {
+            page.text([italic(notice)]);
         }
 
         if !arg.default.is_empty() {
-            roff.control("RS", [] as [&str; 0]);
+            page.indent(None);
             let default_str = arg.default.join(", ");
-            roff.text([italic("Default: "), roman(default_str.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.text([italic("Default: "), roman(default_str.as_str())]);
+            page.outdent();
         }
 
         if let Some(env) = &arg.env {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Environment: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Environment: "), bold(env.as_str())]);
+            page.outdent();
         }
         for env in &arg.env_fallback {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Environment fallback: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Environment fallback: "), bold(env.as_str())]);
+            page.outdent();
         }
         for env in &arg.deprecated_env {
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Deprecated environment: "), bold(env.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Deprecated environment: "), bold(env.as_str())]);
+            page.outdent();
         }
     }
 
-    fn render_all_subcommands(&self, roff: &mut Roff, cmd: &SpecCommand, prefix: &str) {
+    /// Every visible command in the tree. With `pages`, each is named by a reference to its
+    /// own page rather than in bold.
+    fn render_all_subcommands(
+        &self,
+        page: &mut Page,
+        cmd: &SpecCommand,
+        prefix: &str,
+        pages: bool,
+    ) {
         for (name, subcmd) in &cmd.subcommands {
             if subcmd.hide {
                 continue;
@@ -457,14 +649,19 @@ impl ManpageRenderer {
                 format!("{} {}", prefix, name)
             };
 
-            self.render_subcommand_summary(roff, &full_name, subcmd);
+            let head = if pages {
+                xref(self.page_name(subcmd), self.section)
+            } else {
+                bold(&full_name)
+            };
+            self.render_subcommand_summary(page, head, subcmd);
 
             // Recursively render nested subcommands
-            self.render_all_subcommands(roff, subcmd, &full_name);
+            self.render_all_subcommands(page, subcmd, &full_name, pages);
         }
     }
 
-    fn render_subcommand_details(&self, roff: &mut Roff, cmd: &SpecCommand, prefix: &str) {
+    fn render_subcommand_details(&self, page: &mut Page, cmd: &SpecCommand, prefix: &str) {
         for (name, subcmd) in &cmd.subcommands {
             if subcmd.hide {
                 continue;
@@ -483,106 +680,115 @@ impl ManpageRenderer {
                 .iter()
                 .any(|a| a.help.is_some() || a.help_long.is_some());
             let has_examples = !subcmd.examples.is_empty();
+            // Its own statuses only: what it inherits is listed where it was declared.
+            let has_exits = subcmd.exits.iter().any(|exit| !exit.inherited);
 
-            if has_flags || has_documented_args || has_examples {
+            if has_flags || has_documented_args || has_examples || has_exits {
                 // Section header for this subcommand
-                roff.control("SH", [full_name.to_uppercase().as_str()]);
+                page.section(&full_name.to_uppercase());
 
                 // Description
                 if let Some(help) = &subcmd.help_long.as_ref().or(subcmd.help.as_ref()) {
-                    roff.text([roman(help.as_str())]);
-                    roff.control("PP", [] as [&str; 0]);
+                    page.text([roman(help.as_str())]);
+                    page.paragraph();
                 }
                 if let Some(notice) = deprecation_notice(
                     subcmd.deprecated.as_deref(),
                     subcmd.deprecated_warn_at.as_deref(),
                     subcmd.deprecated_remove_at.as_deref(),
                 ) {
-                    roff.text([italic(notice)]);
-                    roff.control("PP", [] as [&str; 0]);
+                    page.text([italic(notice)]);
+                    page.paragraph();
+                }
+                if let Some(notice) = stability_notice(subcmd.badge.as_deref()) {
+                    page.text([italic(notice)]);
+                    page.paragraph();
                 }
 
                 // Synopsis
                 let synopsis = self.build_synopsis(subcmd, &full_name);
-                roff.text([
+                page.text([
                     bold("Usage:"),
                     roman(" "),
                     roman(&full_name),
                     roman(" "),
                     roman(&synopsis),
                 ]);
-                roff.control("PP", [] as [&str; 0]);
+                page.paragraph();
 
                 // Render flags if any
                 if !subcmd.flags.is_empty() {
-                    roff.text([bold("Options:")]);
-                    roff.control("PP", [] as [&str; 0]);
+                    page.text([bold("Options:")]);
+                    page.paragraph();
                     for flag in &subcmd.flags {
-                        self.render_flag(roff, flag);
+                        self.render_flag(page, flag);
                     }
                 }
 
                 // Render args if any with help
                 if has_documented_args {
-                    roff.text([bold("Arguments:")]);
-                    roff.control("PP", [] as [&str; 0]);
+                    page.text([bold("Arguments:")]);
+                    page.paragraph();
                     for arg in &subcmd.args {
-                        self.render_arg(roff, arg);
+                        self.render_arg(page, arg);
                     }
                 }
 
                 // Render examples if any
                 if has_examples {
-                    roff.text([bold("Examples:")]);
-                    roff.control("PP", [] as [&str; 0]);
-                    for (i, example) in subcmd.examples.iter().enumerate() {
-                        // Add spacing between examples (but not before the first one)
-                        if i > 0 {
-                            roff.control("PP", [] as [&str; 0]);
-                        }
-                        if let Some(header) = &example.header {
-                            roff.text([bold(header)]);
-                        }
-                        if let Some(help) = &example.help {
-                            roff.text([roman(help.as_str())]);
-                        }
-                        roff.control("PP", [] as [&str; 0]);
-                        roff.control("RS", ["4"]);
-                        roff.text([roman(example.code.as_str())]);
-                        roff.control("RE", [] as [&str; 0]);
-                    }
+                    page.text([bold("Examples:")]);
+                    page.paragraph();
+                    self.render_examples(page, &subcmd.examples);
+                }
+
+                if has_exits {
+                    page.text([bold("Exit status:")]);
+                    page.paragraph();
+                    self.render_exits(page, subcmd, false);
                 }
             }
 
             // Recursively render nested subcommands
-            self.render_subcommand_details(roff, subcmd, &full_name);
+            self.render_subcommand_details(page, subcmd, &full_name);
         }
     }
 
-    fn render_subcommand_summary(&self, roff: &mut Roff, name: &str, cmd: &SpecCommand) {
-        roff.control("TP", [] as [&str; 0]);
-        roff.text([bold(name)]);
+    /// The command's exit statuses: its own, and with `inherited` its ancestors' as well.
+    fn render_exits(&self, page: &mut Page, cmd: &SpecCommand, inherited: bool) {
+        for exit in cmd.exits.iter().filter(|exit| inherited || !exit.inherited) {
+            page.item([bold(exit.code.to_string())]);
+            if let Some(help) = &exit.help {
+                page.text([roman(help.as_str())]);
+            }
+        }
+    }
+
+    fn render_subcommand_summary(&self, page: &mut Page, head: Span, cmd: &SpecCommand) {
+        page.item([head]);
 
         // Prefer help_long, fall back to help
         if let Some(help) = &cmd.help_long.as_ref().or(cmd.help.as_ref()) {
             // Take just the first line for the summary
             let first_line = help.lines().next().unwrap_or("");
-            roff.text([roman(first_line)]);
+            page.text([roman(first_line)]);
         }
         if let Some(notice) = deprecation_notice(
             cmd.deprecated.as_deref(),
             cmd.deprecated_warn_at.as_deref(),
             cmd.deprecated_remove_at.as_deref(),
         ) {
-            roff.text([italic(notice)]);
+            page.text([italic(notice)]);
+        }
+        if let Some(notice) = stability_notice(cmd.badge.as_deref()) {
+            page.text([italic(notice)]);
         }
 
         // Show aliases if any
         if !cmd.aliases.is_empty() {
             let aliases = cmd.aliases.iter().join(", ");
-            roff.control("RS", [] as [&str; 0]);
-            roff.text([italic("Aliases: "), roman(aliases.as_str())]);
-            roff.control("RE", [] as [&str; 0]);
+            page.indent(None);
+            page.text([italic("Aliases: "), roman(aliases.as_str())]);
+            page.outdent();
         }
     }
 }
@@ -608,9 +814,17 @@ fn deprecation_notice(
     Some(format!("Deprecated: {}", parts.join("; ")))
 }
 
+/// The help badge as a sentence: `Experimental, since 2024.3.0.`
+fn stability_notice(badge: Option<&str>) -> Option<String> {
+    let mut chars = badge?.chars();
+    let first = chars.next()?.to_uppercase();
+    Some(format!("{first}{}.", chars.as_str()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::docs::manpage::ManpageFormat;
     use crate::Spec;
 
     #[test]
@@ -827,6 +1041,35 @@ config {
         assert!(output.contains("commit"));
     }
 
+    #[test]
+    fn exit_statuses_are_listed_where_they_are_declared() {
+        let spec: Spec = r#"
+            name "tool"
+            bin "tool"
+            exit 1 "something failed"
+            cmd "install" {
+                exit 3 "lockfile out of date"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let output = ManpageRenderer::new(spec).render().unwrap();
+        let exit_status = output
+            .find(".SH \"EXIT STATUS\"")
+            .expect("an EXIT STATUS section");
+        assert!(
+            output[exit_status..].contains(".TP\n\\fB1\\fR\nsomething failed"),
+            "{output}"
+        );
+        // The subcommand's own, in its section; its parent's are not repeated there.
+        let install = output
+            .find(".SH \"TOOL INSTALL\"")
+            .expect("a section for install");
+        let section = &output[install..exit_status];
+        assert!(section.contains("lockfile out of date"), "{output}");
+        assert!(!section.contains("something failed"), "{output}");
+    }
+
     #[test]
     fn test_arguments_with_only_long_help() {
         let spec: Spec = r#"
@@ -848,6 +1091,139 @@ config {
         assert!(output.contains("long help text"));
     }
 
+    #[test]
+    fn test_stability_and_since_are_noted() {
+        let spec: Spec = r#"
+            name "mycli"
+            bin "mycli"
+            cmd "sync" help="Sync things" stability="experimental" since="2024.3.0" {
+                flag "--prune" help="Prune" stability="beta"
+                flag "--force" help="Force" stability="stable"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let output = ManpageRenderer::new(spec).render().unwrap();
+        assert!(output.contains("Experimental, since 2024.3.0."), "{output}");
+        assert!(output.contains("Beta."), "{output}");
+        // Stable is the expectation, so it is not remarked on.
+        assert!(!output.contains("Stable"), "{output}");
+    }
+
+    #[test]
+    fn test_a_page_per_command_with_cross_links() {
+        let spec: Spec = r#"
+            name "mise"
+            bin "mise"
+            about "The front-end to your dev env"
+            exit 1 "something failed"
+            cmd "install" help="Install a tool" {
+                alias "i"
+                flag "-f --force" help="Force it"
+                arg "[tool]" help="The tool to install"
+                exit 3 "lockfile out of date"
+            }
+            cmd "plugins" help="Manage plugins" {
+                cmd "ls" help="List plugins"
+            }
+            cmd "secret" hide=#true help="Not documented"
+        "#
+        .parse()
+        .unwrap();
+        let pages = ManpageRenderer::new(spec).render_pages().unwrap();
+        let paths: Vec<String> = pages.iter().map(|p| p.path.display().to_string()).collect();
+        assert_eq!(
+            paths,
+            [
+                "mise.1",
+                "mise-install.1",
+                "mise-plugins.1",
+                "mise-plugins-ls.1"
+            ]
+        );
+
+        // The index lists every command as a reference to its page, and details none.
+        let index = &pages[0].content;
+        assert!(index.contains("\\fBmise\\-plugins\\-ls\\fR(1)"), "{index}");
+        assert!(!index.contains("Force it"), "{index}");
+        assert!(!index.contains("secret"), "{index}");
+
+        // A command's page stands alone: its name, its own options, every status it can exit
+        // with, and a way back up.
+        let install = &pages[1].content;
+        assert!(install.starts_with(".TH MISE-INSTALL 1"), "{install}");
+        assert!(
+            install.contains("mise\\-install \\- Install a tool"),
+            "{install}"
+        );
+        assert!(
+            install.contains("\\fBmise install\\fR [OPTIONS] [<tool>]"),
+            "{install}"
+        );
+        assert!(install.contains("Force it"), "{install}");
+        assert!(install.contains("lockfile out of date"), "{install}");
+        assert!(install.contains("something failed"), "{install}");
+        assert!(
+            install.contains(".SH \"SEE ALSO\"\n\\fBmise\\fR(1)"),
+            "{install}"
+        );
+
+        let plugins = &pages[2].content;
+        assert!(
+            plugins.contains("\\fBmise\\fR(1), \\fBmise\\-plugins\\-ls\\fR(1)"),
+            "{plugins}"
+        );
+    }
+
+    #[test]
+    fn test_mdoc_output() {
+        let spec: Spec = r#"
+            name "mycli"
+            bin "mycli"
+            about "A sample CLI tool"
+            flag "-v --verbose" help="Enable verbose output. Very \"loud\"."
+            cmd "run" help="Run it" {
+                flag "--fast" help="Go fast" default="no"
+                example ".mycli run --fast" header="Quickly"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let output = ManpageRenderer::new(spec)
+            .with_format(ManpageFormat::Mdoc)
+            .with_date("January 1, 2026")
+            .render()
+            .unwrap();
+        assert!(
+            output.starts_with(".Dd January 1, 2026\n.Dt MYCLI 1\n.Os\n.Sh NAME\n.Nm \"mycli\"\n"),
+            "{output}"
+        );
+        assert!(output.contains(".It Sy \"-v, --verbose\""), "{output}");
+        // A sentence to a line, as mdoc wants.
+        assert!(
+            output.contains("Enable verbose output.\nVery \"loud\"."),
+            "{output}"
+        );
+        // Code is kept as written, with a leading dot that would otherwise be a macro escaped.
+        assert!(
+            output.contains(".Bd -literal -offset indent\n\\&.mycli run --fast\n.Ed"),
+            "{output}"
+        );
+        // Every list and display is closed, and no paragraph break comes right before one.
+        assert_eq!(
+            output.matches(".Bl ").count(),
+            output.matches(".El").count()
+        );
+        assert_eq!(
+            output.matches(".Bd ").count(),
+            output.matches(".Ed").count()
+        );
+        for opener in [".Bl", ".Bd", ".Sh", ".Ss"] {
+            assert!(!output.contains(&format!(".Pp\n{opener}")), "{output}");
+        }
+        assert!(!output.contains(".TH"), "{output}");
+    }
+
     #[test]
     fn test_subcommand_with_only_long_help() {
         let spec: Spec = r#"
@@ -5,6 +5,7 @@ use crate::error::UsageErr;
 impl MarkdownRenderer {
     pub fn render_cmd(&self, cmd: &crate::SpecCommand) -> Result<String, UsageErr> {
         let mut cmd = SpecCommand::from(cmd);
+        cmd.inherit_exits(&self.spec.inherited_exits(&cmd.full_cmd));
         cmd.render_md(self);
         self.render_with("cmd_template.md.tera", |ctx| ctx.insert("cmd", &cmd))
     }
@@ -166,6 +167,47 @@ cmd "version" help="Show the version"
         ");
     }
 
+    #[test]
+    fn test_render_markdown_stability_and_since() {
+        let spec: Spec = r#"
+bin "mise"
+cmd "sync" stability="experimental" since="2024.3.0" help="Sync tools" {
+    flag "--prune" stability="beta" since="2024.4.0" help="Prune old tools"
+    arg "[tool]" stability="stable" help="The tool"
+}
+        "#
+        .parse()
+        .unwrap();
+        let ctx = MarkdownRenderer::new(spec.clone()).with_multi(true);
+        assert_snapshot!(ctx.render_cmd(&spec.cmd.subcommands["sync"]).unwrap(), @"
+        # `mise sync`
+
+        - **Usage**: `mise sync [--prune] [tool]`
+        - **Stability**: experimental
+        - **Since**: 2024.3.0
+
+        Sync tools
+
+        ## Arguments
+
+        ### `[tool]`
+
+        **Stability:** stable
+
+        The tool
+
+        ## Flags
+
+        ### `--prune`
+
+        **Stability:** beta
+
+        **Since:** 2024.4.0
+
+        Prune old tools
+        ");
+    }
+
     #[test]
     fn test_render_markdown_groups_by_heading() {
         let spec: Spec = r#"
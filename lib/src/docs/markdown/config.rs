@@ -250,6 +250,34 @@ config {
         );
     }
 
+    #[test]
+    fn profiles_say_how_one_is_selected() {
+        assert_snapshot!(rendered(
+            r##"
+name "ex"
+bin "ex"
+config {
+    file "ex.toml" findup=#true
+    profiles env="EX_PROFILE" flag="--profile"
+    prop "jobs" type="uint"
+}
+"##
+        ), @r#"
+        # Configuration
+
+        Read from, in ascending precedence — the last one that names a setting wins:
+
+        - `ex.toml` — and in every parent directory
+
+
+        A named profile overlays each file with its `[profiles.<name>]` table, then with a sibling file that has the profile's name before its extension, at that file's own precedence. Select one with `--profile <name>` or `EX_PROFILE=<name>`.
+
+        ## `jobs`
+
+        - **type**: `uint`
+        "#);
+    }
+
     #[test]
     fn every_part_of_a_prop_reaches_the_page() {
         assert_snapshot!(rendered(
@@ -175,4 +175,61 @@ mod tests {
             "{output}"
         );
     }
+
+    #[test]
+    fn one_page_lists_each_exit_status_under_the_command_that_declared_it() {
+        let spec: Spec = r#"
+            bin "tool"
+            exit 1 "something failed"
+            cmd "install" {
+                exit 3 "lockfile out of date"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let output = MarkdownRenderer::new(spec.clone()).render_spec().unwrap();
+        assert_eq!(
+            output.matches("- `1`: something failed").count(),
+            1,
+            "{output}"
+        );
+        assert!(output.contains("- `3`: lockfile out of date"), "{output}");
+
+        // A page of its own for `install` says everything it exits with.
+        let page = MarkdownRenderer::new(spec.clone())
+            .with_multi(true)
+            .render_cmd(&spec.cmd.subcommands["install"])
+            .unwrap();
+        assert!(
+            page.contains("## Exit status\n\n- `1`: something failed\n- `3`: lockfile out of date"),
+            "{page}"
+        );
+    }
+
+    #[test]
+    fn a_page_lists_what_the_command_prints() {
+        let spec: Spec = r##"
+            bin "tool"
+            schema "Package" #"{"type": "object"}"#
+            cmd "list" {
+                flag "--json"
+                flag "--format <format>"
+                output "text" help="a table"
+                output "json" flag="--json" schema=#"{"type": "array"}"#
+                output "jsonl" flag="--format jsonl" type="Package"
+            }
+        "##
+        .parse()
+        .unwrap();
+        let page = MarkdownRenderer::new(spec.clone())
+            .with_multi(true)
+            .render_cmd(&spec.cmd.subcommands["list"])
+            .unwrap();
+        assert!(
+            page.contains(
+                "## Output\n\n- `text`: a table\n- `json` with `--json`\n\n```json\n{\n  \"type\": \"array\"\n}\n```\n- `jsonl` with `--format jsonl`, one `Package` per line"
+            ),
+            "{page}"
+        );
+    }
 }
@@ -1,5 +1,7 @@
 use crate::docs::markdown::MarkdownRenderer;
 use crate::spec::effect::SpecCommandEffect;
+use crate::spec::redacted;
+use crate::spec::stability::{badge, SpecStability};
 use crate::SpecChoices;
 use indexmap::IndexMap;
 use serde::Serialize;
@@ -50,6 +52,10 @@ pub struct SpecCommand {
     pub deprecated_warn_at: Option<String>,
     pub deprecated_remove_at: Option<String>,
     pub effect: Option<SpecCommandEffect>,
+    pub stability: Option<SpecStability>,
+    pub since: Option<String>,
+    /// The label help puts beside the command: see [`crate::spec::stability::badge`].
+    pub badge: Option<String>,
     pub hide: bool,
     pub help_heading: Option<String>,
     pub display_order: Option<usize>,
@@ -77,6 +83,10 @@ pub struct SpecCommand {
     pub after_help_long: Option<String>,
     pub after_help_md: Option<String>,
     pub examples: Vec<SpecExample>,
+    /// Every status this command exits with, its ancestors' included. Ordered by code.
+    pub exits: Vec<SpecExit>,
+    /// What the command prints, in the order it declares its formats.
+    pub outputs: Vec<SpecOutput>,
     // pub complete: IndexMap<String, SpecComplete>,
     pub rendered: bool,
 }
@@ -91,6 +101,7 @@ pub struct HelpCommand {
     pub deprecated: Option<String>,
     pub deprecated_warn_at: Option<String>,
     pub deprecated_remove_at: Option<String>,
+    pub badge: Option<String>,
     pub aliases: Vec<String>,
     pub help: Option<String>,
     pub help_long: Option<String>,
@@ -104,6 +115,7 @@ impl From<&SpecCommand> for HelpCommand {
             deprecated: cmd.deprecated.clone(),
             deprecated_warn_at: cmd.deprecated_warn_at.clone(),
             deprecated_remove_at: cmd.deprecated_remove_at.clone(),
+            badge: badge(cmd.stability, cmd.since.as_deref()),
             aliases: cmd.aliases.clone(),
             help: cmd.help.clone(),
             help_long: cmd.help_long.clone(),
@@ -116,6 +128,9 @@ impl From<&SpecCommand> for HelpCommand {
 pub struct SpecFlag {
     pub name: String,
     pub effect: Option<crate::spec::effect::SpecCommandEffect>,
+    pub stability: Option<SpecStability>,
+    pub since: Option<String>,
+    pub badge: Option<String>,
     pub usage: String,
     pub display_usage: String,
     pub help: Option<String>,
@@ -178,9 +193,17 @@ pub struct SpecConfig {
     pub prop_groups: Vec<Group<SpecConfigProp>>,
     /// Config file locations, in the precedence order the spec declared.
     pub files: Vec<SpecConfigFile>,
+    /// What selects a named profile, when the files hold any.
+    pub profiles: Option<SpecConfigProfiles>,
     rendered: bool,
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SpecConfigProfiles {
+    pub env: Option<String>,
+    pub flag: Option<String>,
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SpecConfigFile {
     pub path: String,
@@ -241,7 +264,7 @@ impl SpecConfig {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.props.is_empty() && self.files.is_empty()
+        self.props.is_empty() && self.files.is_empty() && self.profiles.is_none()
     }
 }
 
@@ -280,6 +303,10 @@ impl From<&crate::spec::config::SpecConfig> for SpecConfig {
                     format: file.format.clone(),
                 })
                 .collect(),
+            profiles: config.profiles.as_ref().map(|profiles| SpecConfigProfiles {
+                env: profiles.env.clone(),
+                flag: profiles.flag.clone(),
+            }),
             rendered: false,
         }
     }
@@ -296,19 +323,23 @@ impl SpecConfigProp {
             aliases: prop.aliases.clone(),
             optional: prop.optional,
             type_: prop.value_type.as_ref().map(|t| t.to_string()),
-            default: prop
-                .default_note
-                .clone()
-                .or_else(|| prop.default.as_ref().map(|d| d.display()))
-                .or_else(|| {
-                    (!prop.default_list.is_empty()).then(|| {
-                        prop.default_list
-                            .iter()
-                            .map(|value| value.display())
-                            .collect::<Vec<_>>()
-                            .join(", ")
+            default: prop.default_note.clone().or_else(|| {
+                prop.default
+                    .as_ref()
+                    .map(|d| d.display())
+                    .or_else(|| {
+                        (!prop.default_list.is_empty()).then(|| {
+                            prop.default_list
+                                .iter()
+                                .map(|value| value.display())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
                     })
-                }),
+                    // The note is prose the author wrote to be read; the value may be a real
+                    // credential somebody put in a spec to get a demo working.
+                    .map(|default| redacted(prop.sensitive, &default).to_string())
+            }),
             default_note: prop.default_note.clone(),
             help: prop.help.clone(),
             help_long: prop.long_help.clone(),
@@ -413,10 +444,33 @@ pub struct SpecExample {
     pub rendered: bool,
 }
 
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct SpecExit {
+    pub code: u8,
+    pub help: Option<String>,
+    pub name: Option<String>,
+    /// Declared by an ancestor rather than by this command. A page that documents the whole
+    /// tree says these once, where they were declared.
+    pub inherited: bool,
+}
+
+#[derive(Debug, Default, Serialize, Clone)]
+pub struct SpecOutput {
+    pub format: String,
+    pub flag: Option<String>,
+    pub help: Option<String>,
+    /// The inline schema, pretty-printed for a code block.
+    pub schema: Option<String>,
+    pub type_name: Option<String>,
+}
+
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct SpecArg {
     pub name: String,
     pub usage: String,
+    pub stability: Option<SpecStability>,
+    pub since: Option<String>,
+    pub badge: Option<String>,
     pub help: Option<String>,
     pub help_long: Option<String>,
     pub help_md: Option<String>,
@@ -436,6 +490,8 @@ pub struct SpecArg {
     pub choices: Option<SpecChoices>,
     pub validate: Option<String>,
     pub validate_error: Option<String>,
+    /// The declared `type=`, as the spec spells it.
+    pub value_type: Option<String>,
     pub env: Option<String>,
     pub env_fallback: Vec<String>,
     pub deprecated_env: Vec<String>,
@@ -450,10 +506,12 @@ pub struct SpecArg {
 
 impl From<crate::Spec> for Spec {
     fn from(spec: crate::Spec) -> Self {
+        let mut cmd = SpecCommand::from(&spec.cmd);
+        cmd.inherit_exits(&[]);
         Self {
             name: spec.name,
             bin: spec.bin,
-            cmd: SpecCommand::from(&spec.cmd),
+            cmd,
             config: SpecConfig::from(&spec.config),
             version: spec.version,
             usage: spec.usage,
@@ -572,6 +630,8 @@ impl From<&crate::SpecCommand> for SpecCommand {
             deprecated_warn_at,
             deprecated_remove_at,
             effect,
+            stability,
+            since,
             hide,
             help_heading,
             display_order,
@@ -596,6 +656,8 @@ impl From<&crate::SpecCommand> for SpecCommand {
             after_help_long,
             after_help_md,
             examples,
+            exits,
+            outputs,
             restart_token,
             // How a command line is read, which no rendered page shows.
             unknown_flags: _,
@@ -620,6 +682,8 @@ impl From<&crate::SpecCommand> for SpecCommand {
             complete: _,
             mounted: _,
             flags_from_mount: _,
+            // Where the spec file declared it, which a rendered page has no use for.
+            source: _,
             subcommand_lookup: _,
             // Presentational output does not describe relationships between flags, the
             // way it already does not describe `conflicts`.
@@ -703,6 +767,9 @@ impl From<&crate::SpecCommand> for SpecCommand {
             deprecated_warn_at: deprecated_warn_at.clone(),
             deprecated_remove_at: deprecated_remove_at.clone(),
             effect: *effect,
+            stability: *stability,
+            since: since.clone(),
+            badge: badge(*stability, since.as_deref()),
             hide: *hide,
             help_heading: help_heading.clone(),
             display_order: *display_order,
@@ -736,11 +803,23 @@ impl From<&crate::SpecCommand> for SpecCommand {
             after_help_long: after_help_long.clone(),
             after_help_md: after_help_md.clone(),
             examples: examples.iter().map(SpecExample::from).collect(),
+            // Its own only: what it inherits is laid over these by `inherit_exits`, once the
+            // ancestors are known.
+            exits: exits.iter().map(SpecExit::from).collect(),
+            outputs: outputs.iter().map(SpecOutput::from).collect(),
             rendered: false,
         }
     }
 }
 
+/// A flag's or argument's defaults, each masked when it is a secret.
+fn redacted_default(sensitive: bool, default: &[String]) -> Vec<String> {
+    default
+        .iter()
+        .map(|value| redacted(sensitive, value).to_string())
+        .collect()
+}
+
 /// Help text, with whitespace-only treated as none.
 ///
 /// `usage-argv` filters a blank description out everywhere it reads one, so a spec written with
@@ -859,6 +938,9 @@ impl From<&crate::SpecFlag> for SpecFlag {
         Self {
             name: flag.name.clone(),
             effect: flag.effect,
+            stability: flag.stability,
+            since: flag.since.clone(),
+            badge: badge(flag.stability, flag.since.as_deref()),
             usage: reference_usage(flag),
             display_usage: column_usage(flag),
             help: said(&flag.help),
@@ -894,7 +976,7 @@ impl From<&crate::SpecFlag> for SpecFlag {
             global: flag.global,
             count: flag.count,
             arg: flag.arg.as_ref().map(SpecArg::from),
-            default: flag.default.clone(),
+            default: redacted_default(flag.sensitive, &flag.default),
             negate: flag.negate.clone(),
             env: flag.env.clone(),
             env_fallback: flag.env_fallback.clone(),
@@ -921,11 +1003,39 @@ impl From<&crate::spec::cmd::SpecExample> for SpecExample {
     }
 }
 
+impl From<&crate::SpecExit> for SpecExit {
+    fn from(exit: &crate::SpecExit) -> Self {
+        Self {
+            code: exit.code,
+            help: exit.help.clone(),
+            name: exit.name.clone(),
+            inherited: false,
+        }
+    }
+}
+
+impl From<&crate::SpecOutput> for SpecOutput {
+    fn from(output: &crate::SpecOutput) -> Self {
+        Self {
+            format: output.format.to_string(),
+            flag: output.flag.clone(),
+            help: output.help.clone(),
+            schema: output.schema.as_ref().map(|schema| {
+                serde_json::to_string_pretty(schema).expect("a JSON value serializes")
+            }),
+            type_name: output.type_name.clone(),
+        }
+    }
+}
+
 impl From<&crate::SpecArg> for SpecArg {
     fn from(arg: &crate::SpecArg) -> Self {
         Self {
             name: arg.name.clone(),
             usage: arg.usage.clone(),
+            stability: arg.stability,
+            since: arg.since.clone(),
+            badge: badge(arg.stability, arg.since.as_deref()),
             help: said(&arg.help),
             help_long: arg.help_long.clone(),
             help_md: arg.help_md.clone(),
@@ -941,10 +1051,11 @@ impl From<&crate::SpecArg> for SpecArg {
             hide_possible_values: arg.hide_possible_values,
             hide_short_help: arg.hide_short_help,
             hide_long_help: arg.hide_long_help,
-            default: arg.default.clone(),
+            default: redacted_default(arg.sensitive, &arg.default),
             choices: arg.choices.as_ref().map(|choices| choices.for_help()),
             validate: arg.validate.clone(),
             validate_error: arg.validate_error.clone(),
+            value_type: arg.value_type.as_ref().map(ToString::to_string),
             env: arg.env.clone(),
             env_fallback: arg.env_fallback.clone(),
             deprecated_env: arg.deprecated_env.clone(),
@@ -973,6 +1084,24 @@ impl Spec {
         self.cmd.render_md(renderer);
         self.config.render_md(renderer);
     }
+
+    /// The statuses the command at `full_cmd` inherits: its parent's, resolved.
+    ///
+    /// For a page rendered from one command rather than from the whole tree, which
+    /// `inherit_exits` otherwise reaches from the root.
+    pub(crate) fn inherited_exits(&self, full_cmd: &[String]) -> Vec<SpecExit> {
+        let Some((_, ancestors)) = full_cmd.split_last() else {
+            return vec![];
+        };
+        let mut cmd = &self.cmd;
+        for name in ancestors {
+            match cmd.subcommands.get(name) {
+                Some(sub) => cmd = sub,
+                None => return vec![],
+            }
+        }
+        cmd.exits.clone()
+    }
 }
 
 impl SpecCommand {
@@ -1023,6 +1152,24 @@ impl SpecCommand {
         }
     }
 
+    /// Lay `inherited` under this command's own statuses, and its resolved ones under each
+    /// subcommand's, the way [`crate::Spec::exits_for`] resolves them.
+    pub(crate) fn inherit_exits(&mut self, inherited: &[SpecExit]) {
+        for exit in inherited {
+            if !self.exits.iter().any(|own| own.code == exit.code) {
+                self.exits.push(SpecExit {
+                    inherited: true,
+                    ..exit.clone()
+                });
+            }
+        }
+        self.exits.sort_by_key(|exit| exit.code);
+        let exits = self.exits.clone();
+        for cmd in self.subcommands.values_mut() {
+            cmd.inherit_exits(&exits);
+        }
+    }
+
     /// Rebuild the grouped views from `flags` and `args`.
     ///
     /// Anything that mutates either list has to call this, or the groups go stale.
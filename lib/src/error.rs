@@ -55,6 +55,11 @@ pub enum UsageErr {
     #[error("Argument <{0}> can only be set after a `--` separator")]
     ArgRequiresDoubleDash(String),
 
+    /// A command line named an experimental command, flag or argument of a spec that
+    /// declares `experimental_env`, and the variable was not set.
+    #[error("{item} is experimental: set {env}=1 to use it")]
+    Experimental { item: String, env: String },
+
     #[error("{0}")]
     Help(String),
 
@@ -137,9 +142,25 @@ pub enum UsageErr {
         reason: String,
     },
 
+    /// A value that does not read as the `type=` its argument declares.
+    #[error("Invalid value for {name}: {value}: expected {expected}")]
+    InvalidType {
+        name: String,
+        value: String,
+        /// The type as prose, e.g. "an integer".
+        expected: String,
+    },
+
     #[error("Unsupported shell: {0}")]
     UnsupportedShell(String),
 
+    /// A generator was asked to emit a spec that declares something it cannot express.
+    #[error("{generator} cannot express {feature}")]
+    UnsupportedFeature {
+        generator: &'static str,
+        feature: String,
+    },
+
     #[error("No injected output was provided for mount command: {0}")]
     MissingMountOutput(String),
 }
@@ -149,7 +149,7 @@ impl<'a> Emitter<'a> {
         let mut commands = Vec::new();
         self.collect(&self.spec.cmd.clone(), &[], true, &mut commands);
 
-        self.header();
+        self.header(&commands);
         self.constants(&commands);
         self.tables(&commands);
         self.metadata(&commands);
@@ -224,7 +224,26 @@ impl<'a> Emitter<'a> {
         out[index].subcommands = children;
     }
 
-    fn header(&mut self) {
+    fn header(&mut self, commands: &[Emitted]) {
+        // `time` only when a struct field is a `time.Duration`: an unused import is a
+        // compile error in Go, not a warning.
+        let uses_time = commands.iter().any(|e| {
+            e.args
+                .iter()
+                .map(|(arg, _)| arg)
+                .chain(
+                    e.flags
+                        .iter()
+                        .filter(|(f, _)| !f.count)
+                        .filter_map(|(f, _)| f.arg.as_ref()),
+                )
+                .any(|arg| structs::converter(arg).is_some_and(|(ty, _)| ty == "time.Duration"))
+        });
+        let imports = if uses_time {
+            "import (\n\t\"time\"\n\n\t\"github.com/jdx/usage/go/argv\"\n)"
+        } else {
+            "import \"github.com/jdx/usage/go/argv\""
+        };
         let _ = writeln!(
             self.out,
             "// Code generated by `usage generate go`. DO NOT EDIT.\n\
@@ -238,7 +257,7 @@ impl<'a> Emitter<'a> {
              \n\
              package {}\n\
              \n\
-             import \"github.com/jdx/usage/go/argv\"\n",
+             {imports}\n",
             self.spec.bin, self.package
         );
 
@@ -2130,6 +2149,43 @@ cmd "run" arg_required_else_help=#true {
         );
     }
 
+    #[test]
+    fn declared_types_reach_the_struct_fields_and_parse() {
+        let out = go(r#"
+name "ex"
+bin "ex"
+flag "-j --jobs <n>" type="int"
+flag "--wait <d>" type="duration" default="30s"
+flag "--port <p>..." type="uint"
+arg "<ratio>" type="float"
+arg "[file]" type="path"
+"#);
+        assert!(out.contains("import (\n\t\"time\"\n\n\t\"github.com/jdx/usage/go/argv\"\n)"));
+        assert!(out.contains("\tJobs  int64         // FlagJobs"), "{out}");
+        assert!(out.contains("Wait  time.Duration"), "{out}");
+        assert!(out.contains("Port  []uint64"), "{out}");
+        assert!(out.contains("Ratio float64"), "{out}");
+        assert!(out.contains("File  string"), "{out}");
+        assert!(
+            out.contains("v, err := argv.Int(\"jobs\", values[len(values)-1])"),
+            "{out}"
+        );
+        assert!(
+            out.contains("v, err := argv.Each(\"port\", values, argv.Uint)"),
+            "{out}"
+        );
+        // Converted once, after the fallbacks, rather than also as each event arrives.
+        assert!(!out.contains("out.Jobs = ev.Value"), "{out}");
+        assert!(!out.contains("out.Wait = values[len(values)-1]"), "{out}");
+        // An untyped spec has neither the import nor the conversion block.
+        let plain = go("name \"ex\"\nbin \"ex\"\nflag \"--wait <d>\"\n");
+        assert!(
+            plain.contains("import \"github.com/jdx/usage/go/argv\"\n"),
+            "{plain}"
+        );
+        assert!(!plain.contains("Declared types"), "{plain}");
+    }
+
     #[test]
     fn explicit_boolean_values_reach_generated_go() {
         let out = go(
@@ -5,16 +5,20 @@
 //! post-binding rules, the three tables — is unchanged, and this is the shape
 //! that makes it usable without knowing any of it.
 //!
-//! Fields are `string`, `bool` and `[]string`, because that is what a usage spec
-//! knows. A spec says what a value is *called* and never what type it is, so
-//! turning `"8"` into an `int` stays the caller's business — `argv.Int` and its
-//! neighbours exist for exactly that, and inferring a type from an argument's
-//! name would be guessing.
+//! Fields are `string`, `bool` and `[]string` unless the spec declares a
+//! `type=`, in which case they are that type — `int64`, `time.Duration` — and
+//! `Parse` converts with `argv.Int` and its neighbours. Converted once every
+//! value has landed, after the post-binding checks, so a value from the
+//! environment is judged the same way as one typed on the command line and a
+//! `--help` later in the line still wins over a bad number before it. Without a
+//! declaration the conversion stays the caller's business: inferring a type from
+//! an argument's name would be guessing.
 
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 
-use super::{field_name, Emitted};
+use super::{field_name, go_string, Emitted};
+use crate::spec::config_type::Base;
 use crate::{SpecArg, SpecFlag};
 
 /// The field each entry is assigned to, by key.
@@ -61,16 +65,12 @@ pub(super) fn emit(out: &mut String, commands: &[Emitted]) {
 
         for (flag, named) in &e.flags {
             let field = claim(field_name(&flag.name), "Flag");
-            fields.push((
-                field.clone(),
-                flag_type(flag).to_string(),
-                named.key.clone(),
-            ));
+            fields.push((field.clone(), flag_type(flag), named.key.clone()));
             assigned.insert(named.key.clone(), field);
         }
         for (arg, named) in &e.args {
             let field = claim(field_name(&arg.name), "Arg");
-            fields.push((field.clone(), arg_type(arg).to_string(), named.key.clone()));
+            fields.push((field.clone(), arg_type(arg), named.key.clone()));
             assigned.insert(named.key.clone(), field);
         }
         for at in &e.subcommands {
@@ -292,6 +292,9 @@ fn parse_fn(out: &mut String, commands: &[Emitted], assigned: &Fields) {
     for e in commands {
         let owner = owner_of(e);
         for (flag, named) in &e.flags {
+            if flag_converter(flag).is_some() {
+                continue;
+            }
             let _ = writeln!(
                 out,
                 "\t\t\tcase {}:\n{}",
@@ -311,6 +314,9 @@ fn parse_fn(out: &mut String, commands: &[Emitted], assigned: &Fields) {
     for e in commands {
         let owner = owner_of(e);
         for (arg, named) in &e.args {
+            if converter(arg).is_some() {
+                continue;
+            }
             let field = &assigned[&named.key];
             let assign = if arg.var {
                 format!("\t\t\t\t{owner}.{field} = append({owner}.{field}, values...)")
@@ -366,7 +372,9 @@ fn parse_fn(out: &mut String, commands: &[Emitted], assigned: &Fields) {
          \t\t\tswitch key {{"
     );
     fallback_cases(out, commands, assigned);
-    let _ = writeln!(out, "\t\t\t}}\n\t\t}}\n\t}}");
+    let _ = writeln!(out, "\t\t\t}}\n\t\t}}");
+    typed_cases(out, commands, assigned);
+    let _ = writeln!(out, "\t}}");
     if has_relationship_values {
         let _ = writeln!(
             out,
@@ -390,8 +398,11 @@ fn fallback_cases(out: &mut String, commands: &[Emitted], assigned: &Fields) {
     for e in commands {
         let owner = owner_of(e);
         for (flag, named) in &e.flags {
+            if flag_converter(flag).is_some() {
+                continue;
+            }
             let field = &assigned[&named.key];
-            let assign = match flag_type(flag) {
+            let assign = match flag_type(flag).as_str() {
                 // A value-less flag has nowhere to put text, so the variable is
                 // read as a yes or a no — by usage-lib's allow-list, which is
                 // narrower than Go's own spellings on purpose.
@@ -410,6 +421,9 @@ fn fallback_cases(out: &mut String, commands: &[Emitted], assigned: &Fields) {
             let _ = writeln!(out, "\t\t\tcase {}:\n{assign}", named.key);
         }
         for (arg, named) in &e.args {
+            if converter(arg).is_some() {
+                continue;
+            }
             let field = &assigned[&named.key];
             let assign = if arg.var {
                 format!("\t\t\t\t{owner}.{field} = append({owner}.{field}, values...)")
@@ -421,6 +435,50 @@ fn fallback_cases(out: &mut String, commands: &[Emitted], assigned: &Fields) {
     }
 }
 
+/// The conversions for entries with a declared type, whatever source filled them.
+///
+/// Nothing is emitted for a spec without one, so an untyped CLI's `Parse` is
+/// what it was before types existed.
+fn typed_cases(out: &mut String, commands: &[Emitted], assigned: &Fields) {
+    let mut cases = String::new();
+    for e in commands {
+        let owner = owner_of(e);
+        let typed =
+            e.flags
+                .iter()
+                .filter_map(|(flag, named)| {
+                    let several = flag.var || flag.arg.as_ref().is_some_and(|a| a.var);
+                    Some((&flag.name, flag_converter(flag)?.1, several, named))
+                })
+                .chain(e.args.iter().filter_map(|(arg, named)| {
+                    Some((&arg.name, converter(arg)?.1, arg.var, named))
+                }));
+        for (name, convert, several, named) in typed {
+            let field = &assigned[&named.key];
+            let call = if several {
+                format!("argv.Each({}, values, {convert})", go_string(name))
+            } else {
+                format!("{convert}({}, values[len(values)-1])", go_string(name))
+            };
+            let _ = writeln!(
+                cases,
+                "\t\tcase {}:\n\t\t\tv, err := {call}\n\t\t\tif err != nil {{\n\t\t\t\treturn nil, err\n\t\t\t}}\n\t\t\t{owner}.{field} = v",
+                named.key
+            );
+        }
+    }
+    if cases.is_empty() {
+        return;
+    }
+    let _ = write!(
+        out,
+        "\t\t// Declared types, converted from whatever landed: argv, the\n\
+         \t\t// environment or a default.\n\
+         \t\tif len(values) == 0 {{\n\t\t\tcontinue\n\t\t}}\n\
+         \t\tswitch key {{\n{cases}\t\t}}\n"
+    );
+}
+
 /// Where a command's own entries are assigned.
 fn owner_of(e: &Emitted) -> String {
     if e.root {
@@ -448,25 +506,52 @@ fn name(e: &Emitted) -> String {
     format!("{}Cmd", &e.named.key["Cmd".len()..])
 }
 
-fn flag_type(flag: &SpecFlag) -> &'static str {
+fn flag_type(flag: &SpecFlag) -> String {
+    let several = flag.var || flag.arg.as_ref().is_some_and(|a| a.var);
     match () {
-        _ if flag.count => "int",
-        _ if flag.arg.is_none() => "bool",
-        _ if flag.var || flag.arg.as_ref().is_some_and(|a| a.var) => "[]string",
-        _ => "string",
+        _ if flag.count => "int".to_string(),
+        _ if flag.arg.is_none() => "bool".to_string(),
+        _ => go_type(flag_converter(flag).map_or("string", |(ty, _)| ty), several),
     }
 }
 
-fn arg_type(arg: &SpecArg) -> &'static str {
-    if arg.var {
-        "[]string"
+fn arg_type(arg: &SpecArg) -> String {
+    go_type(converter(arg).map_or("string", |(ty, _)| ty), arg.var)
+}
+
+fn go_type(base: &str, several: bool) -> String {
+    if several {
+        format!("[]{base}")
     } else {
-        "string"
+        base.to_string()
+    }
+}
+
+/// The Go type and `argv` conversion for a declared `type=`, when it is not text.
+///
+/// A path and a URL stay strings: Go has no path type, and `url.Parse` accepts
+/// nearly anything, so it would convert without checking.
+pub(super) fn converter(arg: &SpecArg) -> Option<(&'static str, &'static str)> {
+    match arg.value_type.as_ref()? {
+        Base::Bool => Some(("bool", "argv.Bool")),
+        Base::Int => Some(("int64", "argv.Int")),
+        Base::Uint => Some(("uint64", "argv.Uint")),
+        Base::Float => Some(("float64", "argv.Float")),
+        Base::Duration => Some(("time.Duration", "argv.Duration")),
+        _ => None,
+    }
+}
+
+/// [`converter`] for a flag's value. A count's value is its occurrences, not text.
+fn flag_converter(flag: &SpecFlag) -> Option<(&'static str, &'static str)> {
+    if flag.count {
+        return None;
     }
+    flag.arg.as_ref().and_then(converter)
 }
 
 fn flag_assign(flag: &SpecFlag, owner: &str, field: &str) -> String {
-    match flag_type(flag) {
+    match flag_type(flag).as_str() {
         // A count is the number of occurrences, which is what the parser reports
         // one event at a time.
         "int" => format!("\t\t\t\t{owner}.{field}++"),
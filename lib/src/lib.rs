@@ -9,10 +9,13 @@ pub use crate::spec::choices::{SpecChoice, SpecChoiceAlias, SpecChoices};
 pub use crate::spec::cmd::SpecCommand;
 pub use crate::spec::complete::SpecComplete;
 pub use crate::spec::effect::SpecCommandEffect;
+pub use crate::spec::exit::SpecExit;
 pub use crate::spec::flag::{SpecDefaultIf, SpecFlag, SpecFlagAction, SpecRequiresIf};
 pub use crate::spec::flagset::{SpecFlagSet, SpecUse};
 pub use crate::spec::group::SpecGroup;
 pub use crate::spec::mount::SpecMount;
+pub use crate::spec::output::{SpecOutput, SpecOutputFormat, SpecSchema};
+pub use crate::spec::stability::SpecStability;
 pub use crate::spec::unknown_flags::UnknownFlags;
 pub use crate::spec::view::SpecView;
 pub use crate::spec::Spec;
@@ -32,9 +35,12 @@ pub mod docs;
 pub mod go;
 pub mod help_template;
 pub mod parse;
+pub mod scaffold;
 pub mod sdk;
 pub mod sh;
+pub mod shell_parser;
 pub(crate) mod string;
 #[cfg(test)]
 mod test;
+pub mod typed;
 pub mod warn;
@@ -12,7 +12,11 @@ use strum::EnumTryAs;
 use crate::docs;
 use crate::error::UsageErr;
 use crate::spec::arg::SpecDoubleDashChoices;
+use crate::spec::config_type::Base;
+use crate::spec::stability::SpecStability;
 use crate::spec::unknown_flags::UnknownFlags;
+use crate::spec::{redacted, REDACTED};
+use crate::typed::{self, TypedValue};
 use crate::warn::Warning;
 use crate::{Spec, SpecArg, SpecChoices, SpecCommand, SpecFlag};
 
@@ -373,6 +377,18 @@ pub struct TokenBinding {
     pub roles: Vec<TokenRole>,
 }
 
+impl TokenBinding {
+    /// Whether the word carries the value of a `sensitive` flag or argument, and so is not
+    /// to be printed as it was written.
+    pub fn is_sensitive(&self) -> bool {
+        self.roles.iter().any(|role| match role {
+            TokenRole::Value { flag, .. } => flag.sensitive,
+            TokenRole::Arg { arg, .. } => arg.sensitive,
+            _ => false,
+        })
+    }
+}
+
 #[non_exhaustive]
 pub struct ParseOutput {
     pub cmd: SpecCommand,
@@ -521,6 +537,46 @@ fn flag_deprecation(flag: &SpecFlag) -> Option<Warning> {
     ))
 }
 
+/// The experimental declarations a command line asked for, refused because the spec's
+/// `experimental_env` is not set.
+///
+/// Asked for means named: a command descended through, or a flag or argument whose value came
+/// from argv or the environment. A default is nobody's request, so a flag that only has one
+/// passes. The root is skipped for the reason [`collect_deprecations`] skips it — refusing it
+/// would refuse the whole CLI, `--help` included.
+fn experimental_refusals(out: &ParseOutput, env: &str) -> Vec<UsageErr> {
+    let experimental =
+        |stability: Option<SpecStability>| stability == Some(SpecStability::Experimental);
+    let requested = |origins: Option<&Vec<ValueOrigin>>| {
+        origins.is_none_or(|origins| {
+            origins
+                .iter()
+                .any(|o| matches!(o, ValueOrigin::Env(_) | ValueOrigin::DefaultMissing))
+        })
+    };
+    let refuse = |item: String| UsageErr::Experimental {
+        item,
+        env: env.to_string(),
+    };
+    let commands = out
+        .cmds
+        .iter()
+        .skip(1)
+        .filter(|cmd| experimental(cmd.stability))
+        .map(|cmd| refuse(format!("`{}`", cmd.full_cmd.join(" "))));
+    let flags = out
+        .flags
+        .keys()
+        .filter(|flag| experimental(flag.stability) && requested(out.flag_origins.get(*flag)))
+        .map(|flag| refuse(format!("`{}`", flag_spelling(flag))));
+    let args = out
+        .args
+        .keys()
+        .filter(|arg| experimental(arg.stability) && requested(out.arg_origins.get(*arg)))
+        .map(|arg| refuse(format!("`<{}>`", arg.name)));
+    commands.chain(flags).chain(args).collect()
+}
+
 /// A flag named the way the user names it. The spec's name for it has no dashes, and a warning
 /// about `old-flag` would be about a word nobody typed.
 fn flag_spelling(flag: &SpecFlag) -> String {
@@ -930,26 +986,56 @@ impl<'a> Parser<'a> {
         // environment fallbacks have landed by here, and delimiters were already split
         // while binding. Like clap's value parsers, a declaration judges each resulting
         // raw value independently.
+        // A declared type is checked first: an expression written against an integer has
+        // nothing useful to say about `abc`, and one error per value is what clap reports.
         for (arg, parsed) in &out.args {
+            if !validate_type(
+                &arg.name,
+                arg.value_type.as_ref(),
+                arg.sensitive,
+                parsed,
+                &mut out.errors,
+            ) {
+                continue;
+            }
             validate_expression(
                 &arg.name,
                 arg.validate.as_deref(),
                 arg.validate_error.as_deref(),
+                arg.sensitive,
                 parsed,
                 &mut out.errors,
             );
         }
         for (flag, parsed) in &out.flags {
             if let Some(arg) = &flag.arg {
+                if !validate_type(
+                    &flag.name,
+                    arg.value_type.as_ref(),
+                    flag.sensitive,
+                    parsed,
+                    &mut out.errors,
+                ) {
+                    continue;
+                }
                 validate_expression(
                     &flag.name,
                     arg.validate.as_deref(),
                     arg.validate_error.as_deref(),
+                    flag.sensitive,
                     parsed,
                     &mut out.errors,
                 );
             }
         }
+        // Last, once every value has landed: an experimental flag a variable set was asked for
+        // as surely as one that was typed.
+        if let Some(env) = &self.spec.experimental_env {
+            if !crate::spec::stability::opted_in(get_env(env).as_deref()) {
+                let refused = experimental_refusals(&out, env);
+                out.errors.extend(refused);
+            }
+        }
         // Applied once, here, because this is where the CLI's own version is known: a
         // `deprecated_warn_at` the spec has not reached yet is an author saying *not yet*.
         crate::warn::retain_reached(&mut out.warnings, self.spec.version.as_deref());
@@ -1730,7 +1816,7 @@ fn parse_partial_traced(
                             Some(value) => {
                                 out.errors.push(UsageErr::InvalidValue {
                                     name: f.name.clone(),
-                                    value: value.to_string(),
+                                    value: redacted(f.sensitive, value).to_string(),
                                     reason: "expected `true` or `false`".to_string(),
                                 });
                                 continue;
@@ -2022,10 +2108,8 @@ fn parse_partial_traced(
                 if required_after == 0 {
                     break;
                 }
-                let remaining_values = 1 + input
-                    .iter()
-                    .filter(|token| !enable_flags || !is_flag_like(&token.word))
-                    .count();
+                let remaining_values =
+                    1 + positional_words_ahead(&input, &out.available_flags, enable_flags);
                 if remaining_values > required_after {
                     break;
                 }
@@ -2344,9 +2428,9 @@ fn parse_partial_traced(
                 && arg.required_unless_all.iter().all(|selector| {
                     selector_is_explicit(selector, &out, &overridden_flags, custom_env)
                 });
-            let required_unless = (!arg.required_unless.is_empty()
-                || !arg.required_unless_all.is_empty())
-                && !(unless_any || unless_all);
+            let declares_unless =
+                !arg.required_unless.is_empty() || !arg.required_unless_all.is_empty();
+            let required_unless = declares_unless && !(unless_any || unless_all);
             if (arg.required
                 || required_if
                 || required_if_eq
@@ -2630,9 +2714,9 @@ fn parse_partial_traced(
                 && flag.required_unless_all.iter().all(|selector| {
                     selector_is_explicit(selector, &out, &overridden_flags, custom_env)
                 });
-            let required_unless = (!flag.required_unless.is_empty()
-                || !flag.required_unless_all.is_empty())
-                && !(unless_any || unless_all);
+            let declares_unless =
+                !flag.required_unless.is_empty() || !flag.required_unless_all.is_empty();
+            let required_unless = declares_unless && !(unless_any || unless_all);
             if (flag.required
                 || required_if
                 || required_if_eq
@@ -2722,10 +2806,47 @@ fn parse_partial_traced(
     Ok((out, overridden_flags))
 }
 
+/// Check each raw value against the declared type. False when one was refused, having
+/// reported it.
+fn validate_type(
+    name: &str,
+    ty: Option<&Base>,
+    sensitive: bool,
+    parsed: &ParseValue,
+    errors: &mut Vec<UsageErr>,
+) -> bool {
+    let Some(ty) = ty else {
+        return true;
+    };
+    let values: &[String] = match parsed {
+        ParseValue::String(value) => std::slice::from_ref(value),
+        ParseValue::MultiString(values) => values,
+        ParseValue::Bool(_) | ParseValue::MultiBool(_) => return true,
+    };
+    for value in values {
+        if let Err(err) = coerce_named(name, ty, sensitive, value) {
+            errors.push(err);
+            return false;
+        }
+    }
+    true
+}
+
+/// [`typed::coerce`], with a refusal said the way the parser says it: which argument, and
+/// the value it was given unless that is a secret.
+fn coerce_named(name: &str, ty: &Base, sensitive: bool, raw: &str) -> Result<TypedValue, UsageErr> {
+    typed::coerce(ty, raw).map_err(|expected| UsageErr::InvalidType {
+        name: name.to_string(),
+        value: redacted(sensitive, raw).to_string(),
+        expected: expected.to_string(),
+    })
+}
+
 fn validate_expression(
     name: &str,
     expression: Option<&str>,
     message: Option<&str>,
+    sensitive: bool,
     parsed: &ParseValue,
     errors: &mut Vec<UsageErr>,
 ) {
@@ -2750,7 +2871,7 @@ fn validate_expression(
         };
         errors.push(UsageErr::InvalidValue {
             name: name.to_string(),
-            value: value.clone(),
+            value: redacted(sensitive, value).to_string(),
             reason,
         });
         break;
@@ -2760,7 +2881,7 @@ fn validate_expression(
         let _ = message;
         errors.push(UsageErr::InvalidValue {
             name: name.to_string(),
-            value: value.clone(),
+            value: redacted(sensitive, value).to_string(),
             reason: "expression validation requires the `validation` feature".to_string(),
         });
     }
@@ -3257,6 +3378,8 @@ fn render_action_err(spec: &Spec, cmd: &SpecCommand, flag: &SpecFlag, spelling:
 struct ChoiceTarget<'a> {
     kind: &'a str,
     name: &'a str,
+    /// Whether the value is a secret, and so left out of the message.
+    sensitive: bool,
 }
 
 impl<'a> ChoiceTarget<'a> {
@@ -3264,6 +3387,7 @@ impl<'a> ChoiceTarget<'a> {
         Self {
             kind: "arg",
             name: &arg.name,
+            sensitive: arg.sensitive,
         }
     }
 
@@ -3271,6 +3395,7 @@ impl<'a> ChoiceTarget<'a> {
         Self {
             kind: "option",
             name: &flag.name,
+            sensitive: flag.sensitive,
         }
     }
 }
@@ -3357,6 +3482,54 @@ fn effective_unknown_flags(spec: &Spec, path: &[SpecCommand]) -> UnknownFlags {
         .unwrap_or_default()
 }
 
+/// How many of the words still to come would bind to a positional.
+///
+/// A word that is a known flag's separate value is not one: `optional required -f x` has one
+/// positional left after `optional`, not two. Only as precise as a look-ahead can be — a flag this
+/// command does not know is taken to have no value. Kept in step with usage-argv's
+/// `positional_words_ahead`, since the two parsers have to reserve the same words.
+fn positional_words_ahead(
+    input: &VecDeque<Token>,
+    available_flags: &BTreeMap<String, Arc<SpecFlag>>,
+    enable_flags: bool,
+) -> usize {
+    let mut count = 0;
+    let mut stopped = !enable_flags;
+    let mut words = input.iter();
+    while let Some(token) = words.next() {
+        let word = token.word.as_str();
+        if stopped || !is_flag_like(word) {
+            count += 1;
+        } else if word == "--" {
+            stopped = true;
+        } else if value_is_the_next_word(word, available_flags) {
+            words.next();
+        }
+    }
+    count
+}
+
+/// Whether a flag-like word leaves its value to the word after it.
+fn value_is_the_next_word(word: &str, available_flags: &BTreeMap<String, Arc<SpecFlag>>) -> bool {
+    if word.starts_with("--") {
+        return !word.contains('=')
+            && available_flags
+                .get(word)
+                .is_some_and(|flag| flag.arg.is_some());
+    }
+    // A short cluster: the first flag that takes a value takes the rest of the word, and only when
+    // there is no rest does it take the next one.
+    let shorts: Vec<char> = word.chars().skip(1).collect();
+    for (i, short) in shorts.iter().enumerate() {
+        match available_flags.get(&format!("-{short}")) {
+            Some(flag) if flag.arg.is_some() => return i + 1 == shorts.len(),
+            Some(_) => {}
+            None => return false,
+        }
+    }
+    false
+}
+
 /// Whether a token would be read as a flag, for the purpose of rejecting unknown
 /// ones.
 ///
@@ -3844,6 +4017,7 @@ fn choice_error(
     if choices.matches_with_env(value, custom_env) {
         return None;
     }
+    let value = redacted(target.sensitive, value);
     if let Some(env) = choices.env() {
         if values.is_empty() {
             return Some(format!(
@@ -3990,6 +4164,97 @@ impl ParseOutput {
     }
 }
 
+impl ParseValue {
+    /// The value read as `ty`, the declaration's `value_type`. A bool stays a bool: it is a
+    /// switch, whatever its flag's value would have been.
+    ///
+    /// After a successful [`Parser::parse`] every value already passed this, so the error
+    /// is for values assembled by hand, or by [`parse_partial`], which checks nothing. It
+    /// names the value as `name`, the way a parse error would.
+    pub fn typed(&self, name: &str, ty: Option<&Base>) -> Result<TypedValue, UsageErr> {
+        self.typed_as(name, ty, false)
+    }
+
+    fn typed_as(
+        &self,
+        name: &str,
+        ty: Option<&Base>,
+        sensitive: bool,
+    ) -> Result<TypedValue, UsageErr> {
+        let ty = ty.unwrap_or(&Base::String);
+        match self {
+            ParseValue::Bool(b) => Ok(TypedValue::Bool(*b)),
+            ParseValue::MultiBool(b) => Ok(TypedValue::List(
+                b.iter().copied().map(TypedValue::Bool).collect(),
+            )),
+            ParseValue::String(s) => coerce_named(name, ty, sensitive, s),
+            ParseValue::MultiString(values) => values
+                .iter()
+                .map(|s| coerce_named(name, ty, sensitive, s))
+                .collect::<Result<_, _>>()
+                .map(TypedValue::List),
+        }
+    }
+}
+
+impl ParseOutput {
+    /// The parsed value of the argument named `name`, as its declared type.
+    ///
+    /// `None` when it was not given. An error only for an output [`Parser::parse`] did not
+    /// check, as with [`ParseValue::typed`].
+    pub fn typed_arg(&self, name: &str) -> Option<Result<TypedValue, UsageErr>> {
+        let (arg, value) = self.args.iter().find(|(arg, _)| arg.name == name)?;
+        Some(value.typed_as(&arg.name, arg.value_type.as_ref(), arg.sensitive))
+    }
+
+    /// The parsed value of the flag named `name`, as its value's declared type, like
+    /// [`ParseOutput::typed_arg`].
+    pub fn typed_flag(&self, name: &str) -> Option<Result<TypedValue, UsageErr>> {
+        let (flag, value) = self.flags.iter().find(|(flag, _)| flag.name == name)?;
+        let ty = flag.arg.as_ref().and_then(|arg| arg.value_type.as_ref());
+        Some(value.typed_as(&flag.name, ty, flag.sensitive))
+    }
+
+    /// The effect of this invocation: [`SpecCommand::effect_of`] over the flags and args
+    /// the command line supplied.
+    ///
+    /// A value that came from the environment or a default is left out, which is the
+    /// precision `effect_of` asks its callers for. A bare flag whose value came from
+    /// `default_missing` is kept: the flag was typed, and the flag is what declares the effect.
+    pub fn effect(&self) -> Option<crate::SpecCommandEffect> {
+        self.effect_counting(|o| matches!(o, ValueOrigin::DefaultMissing))
+    }
+
+    /// The effect of running this invocation where it was parsed: [`ParseOutput::effect`], with
+    /// the values the environment supplied counted as well.
+    ///
+    /// What a gate wants, since a child started here inherits the same environment. A
+    /// `--force` set by `MYCLI_FORCE=1` destroys as much as one that was typed.
+    pub fn effect_in_environment(&self) -> Option<crate::SpecCommandEffect> {
+        self.effect_counting(|o| matches!(o, ValueOrigin::DefaultMissing | ValueOrigin::Env(_)))
+    }
+
+    /// [`SpecCommand::effect_of`] over the values with no recorded origin, which argv supplied,
+    /// and those with an origin `counts` accepts.
+    fn effect_counting(
+        &self,
+        counts: impl Fn(&ValueOrigin) -> bool,
+    ) -> Option<crate::SpecCommandEffect> {
+        let typed =
+            |origins: Option<&Vec<ValueOrigin>>| origins.is_none_or(|o| o.iter().any(&counts));
+        self.cmd.effect_of(
+            self.flags
+                .keys()
+                .filter(|f| typed(self.flag_origins.get(*f)))
+                .map(|f| f.as_ref()),
+            self.args
+                .keys()
+                .filter(|a| typed(self.arg_origins.get(*a)))
+                .map(|a| a.as_ref()),
+        )
+    }
+}
+
 impl Display for ParseValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -4005,7 +4270,8 @@ impl Display for ParseValue {
 fn render_token(token: &TokenBinding) -> String {
     let roles = token.roles.iter().map(render_role).join(", ");
     let synthesized = if token.synthesized { " (read as)" } else { "" };
-    format!("[{}] {}{synthesized}: {roles}", token.index, token.word)
+    let word = redacted(token.is_sensitive(), &token.word);
+    format!("[{}] {word}{synthesized}: {roles}", token.index)
 }
 
 fn render_role(role: &TokenRole) -> String {
@@ -4026,7 +4292,13 @@ fn render_role(role: &TokenRole) -> String {
             attached,
         } => {
             let attached = if *attached { ", attached" } else { "" };
-            format!("value of {} = {values:?}{attached}", flag.name)
+            match flag.sensitive {
+                true => format!("value of {} = {REDACTED}{attached}", flag.name),
+                false => format!("value of {} = {values:?}{attached}", flag.name),
+            }
+        }
+        TokenRole::Arg { arg, .. } if arg.sensitive => {
+            format!("arg {} = {REDACTED}", arg.name)
         }
         TokenRole::Arg { arg, values } => format!("arg {} = {values:?}", arg.name),
         TokenRole::Separator => "separator".to_string(),
@@ -4052,7 +4324,10 @@ impl Debug for ParseOutput {
                 &self
                     .args
                     .iter()
-                    .map(|(a, w)| format!("{}: {w}", a.name))
+                    .map(|(a, w)| match a.sensitive {
+                        true => format!("{}: {REDACTED}", a.name),
+                        false => format!("{}: {w}", a.name),
+                    })
                     .collect_vec(),
             )
             .field(
@@ -4068,7 +4343,10 @@ impl Debug for ParseOutput {
                 &self
                     .flags
                     .iter()
-                    .map(|(f, w)| format!("{}: {w}", f.name))
+                    .map(|(f, w)| match f.sensitive {
+                        true => format!("{}: {REDACTED}", f.name),
+                        false => format!("{}: {w}", f.name),
+                    })
                     .collect_vec(),
             )
             .field("flag_awaiting_value", &self.flag_awaiting_value)
@@ -6362,6 +6640,34 @@ arg "<required>"
         assert!(matches!(value, ParseValue::String(value) if value == "value"));
     }
 
+    #[test]
+    fn a_flags_value_is_not_counted_as_a_positional_still_to_come() {
+        let spec: Spec = r#"name "ex"
+bin "ex"
+allow_missing_positional #true
+flag "-f --file <file>"
+flag "-v --verbose"
+arg "[optional]"
+arg "<required>"
+"#
+        .parse()
+        .unwrap();
+        for argv in [
+            &["ex", "value", "-f", "spec.kdl"][..],
+            &["ex", "value", "--file", "spec.kdl"],
+            &["ex", "value", "-vf", "spec.kdl"],
+        ] {
+            let out = parse(&spec, &input(argv)).unwrap();
+            assert!(
+                !out.args.keys().any(|arg| arg.name == "optional"),
+                "{argv:?}"
+            );
+        }
+        // An attached value leaves the next word a positional.
+        let out = parse(&spec, &input(&["ex", "one", "--file=spec.kdl", "two"])).unwrap();
+        assert!(out.args.keys().any(|arg| arg.name == "optional"));
+    }
+
     #[test]
     fn unknown_flags_can_be_rejected_for_the_whole_cli() {
         let spec: Spec =
@@ -8366,6 +8672,50 @@ cmd "run" {
         assert_eq!(parsed.warnings.len(), 1, "{:?}", parsed.warnings);
     }
 
+    #[test]
+    fn an_experimental_item_needs_the_opt_in_the_spec_declares() {
+        let spec: Spec = r#"
+bin "test"
+experimental_env "TEST_EXPERIMENTAL"
+flag "--fast" stability="experimental"
+flag "--jobs <n>" stability="experimental" default="4"
+cmd "sync" stability="experimental"
+cmd "status"
+        "#
+        .parse()
+        .unwrap();
+
+        // Nothing experimental named, nothing refused: the default on --jobs is not a request.
+        parse_with_env(&spec, &["test", "status"], &[]).unwrap();
+
+        let err = parse_with_env(&spec, &["test", "--fast", "sync"], &[]).unwrap_err();
+        let err = format!("{err:?}");
+        assert!(
+            err.contains("`sync` is experimental: set TEST_EXPERIMENTAL=1 to use it"),
+            "{err}"
+        );
+        assert!(err.contains("`--fast` is experimental"), "{err}");
+
+        let parsed = parse_with_env(
+            &spec,
+            &["test", "--fast", "sync"],
+            &[("TEST_EXPERIMENTAL", "1")],
+        )
+        .unwrap();
+        assert_eq!(parsed.cmd.name, "sync");
+    }
+
+    #[test]
+    fn experimental_is_only_a_label_without_an_opt_in_variable() {
+        let spec: Spec = r#"
+bin "test"
+flag "--fast" stability="experimental"
+        "#
+        .parse()
+        .unwrap();
+        parse_with_env(&spec, &["test", "--fast"], &[]).unwrap();
+    }
+
     #[test]
     fn test_parser_with_custom_env_still_fails_when_missing() {
         let spec = spec_with_arg(
@@ -0,0 +1,1330 @@
+//! Writing a CLI's Rust source from its spec.
+//!
+//! The other generators read a spec to produce something *about* a CLI — docs, completions,
+//! a client. This one produces the CLI itself: the structs a derive turns back into the same
+//! spec, for a project whose interface was designed in KDL first, or that keeps a spec and
+//! wants the code to start from it rather than be transcribed. It runs once. What it writes is
+//! the author's to edit from then on, which is why each file says so instead of `@generated`.
+//!
+//! # Layout
+//!
+//! One module per command, where `mod` looks for it: the root is `mod.rs`, a subcommand of it
+//! `install.rs`, and a subcommand of that `install/tool.rs`. Each holds the command's struct,
+//! the enum of its subcommands, and an enum for each set of `choices` it declares. A command's
+//! help is its struct's doc comment, and the names and aliases it answers to are on its
+//! variant, which is the one place both derives read them from.
+//!
+//! # Types
+//!
+//! A field's type is read off the spec, and whatever a derive would infer from the type is
+//! left to it, so the attributes say only what the type cannot:
+//!
+//! | the spec says | the field is |
+//! | --- | --- |
+//! | a flag without a value | `bool` |
+//! | `count` | `u8`, counting |
+//! | `negate` | `bool`, with the negation beside it |
+//! | `var` | `Vec<T>` |
+//! | `choices` | an enum deriving `ValueEnum` |
+//! | `type="int"`, `"uint"`, `"float"`, `"path"` | `i64`, `u64`, `f64`, `PathBuf` |
+//! | a `default`, or required | `T` |
+//! | anything else | `Option<T>` |
+//!
+//! With [`Framework::Usage`], `Cli::to_kdl()` gives the spec back. A `count` flag comes back
+//! `var` as well, since repeating it is the point; a `type` comes back as nothing, because the
+//! field's type is where it went.
+//!
+//! What a derive has no spelling for is not guessed at. Each such thing is named in
+//! [`ScaffoldOutput::left_out`], for the author to carry over by hand.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use heck::{AsKebabCase, AsPascalCase};
+
+use crate::sdk::rust::KEYWORDS;
+use crate::sdk::{choice_variant_names, comment_lines, escape_rs_string};
+use crate::spec::config_type::Base;
+use crate::{Spec, SpecArg, SpecChoices, SpecCommand, SpecDoubleDashChoices, SpecFlag};
+
+/// Which derive the source is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    /// usage's own, through the `usage` facade crate (`usage-rs`).
+    Usage,
+    /// clap's, with its `derive` feature.
+    Clap,
+}
+
+impl Framework {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Clap => "clap",
+        }
+    }
+
+    /// The attribute a field's options go in, and a command's.
+    fn field_attr(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Clap => "arg",
+        }
+    }
+
+    fn command_attr(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Clap => "command",
+        }
+    }
+
+    /// The name a derive gives a variant unless told otherwise. Not the same for both: clap
+    /// segments words the way heck does, and usage's derive breaks before every uppercase
+    /// letter and turns every underscore into a dash.
+    fn default_name(self, variant: &str) -> String {
+        match self {
+            Self::Usage => {
+                let mut name = String::new();
+                for (i, c) in variant.chars().enumerate() {
+                    if c == '_' {
+                        name.push('-');
+                    } else if c.is_uppercase() {
+                        if i > 0 {
+                            name.push('-');
+                        }
+                        name.extend(c.to_lowercase());
+                    } else {
+                        name.push(c);
+                    }
+                }
+                name
+            }
+            Self::Clap => AsKebabCase(variant).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScaffoldOptions {
+    pub framework: Framework,
+    /// Where the spec was read from, for the comment each file opens with.
+    pub source_file: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ScaffoldOutput {
+    /// Relative to the directory the root module goes in.
+    pub files: Vec<ScaffoldFile>,
+    /// What the spec declares and the source does not, one line each.
+    pub left_out: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ScaffoldFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Write the modules for `spec`, the root's struct named `Cli`.
+pub fn generate(spec: &Spec, opts: &ScaffoldOptions) -> ScaffoldOutput {
+    let mut scaffold = Scaffold {
+        spec,
+        framework: opts.framework,
+        header: match &opts.source_file {
+            Some(source) => format!(
+                "// Scaffolded by usage-cli from {source}.\n// This is a starting point: edit it freely."
+            ),
+            None => "// Scaffolded by usage-cli.\n// This is a starting point: edit it freely."
+                .to_string(),
+        },
+        files: vec![],
+        left_out: vec![],
+    };
+    scaffold.spec_left_out();
+    scaffold.module(&spec.cmd, &[spec.bin.as_str()], Path::new("mod.rs"), "Cli");
+    ScaffoldOutput {
+        files: scaffold.files,
+        left_out: scaffold.left_out,
+    }
+}
+
+struct Scaffold<'a> {
+    spec: &'a Spec,
+    framework: Framework,
+    header: String,
+    files: Vec<ScaffoldFile>,
+    left_out: Vec<String>,
+}
+
+/// One field of a command's struct, as it will be written.
+struct Field {
+    docs: Vec<String>,
+    /// `usage` or `arg`, or for the subcommand field `usage` or `command`.
+    attr: &'static str,
+    attrs: Vec<String>,
+    ident: String,
+    ty: String,
+}
+
+/// One subcommand, as its parent's enum holds it.
+struct Variant {
+    name: String,
+    attrs: Vec<String>,
+    module: String,
+    ty: String,
+}
+
+/// A `choices` set, written as an enum in the module of the command declaring it.
+struct ChoiceEnum {
+    name: String,
+    choices: SpecChoices,
+}
+
+/// What one module needs, gathered before any of it is written: its imports depend on every
+/// field, and its enum names on every other name in it.
+#[derive(Default)]
+struct Module {
+    /// Type names already in use here, so an enum of choices cannot shadow the struct, the
+    /// subcommand enum, or a std type a field names.
+    types: HashSet<String>,
+    /// Field names already in use on the struct.
+    fields: HashSet<String>,
+    enums: Vec<ChoiceEnum>,
+    path_buf: bool,
+}
+
+impl Module {
+    fn new(type_name: &str) -> Self {
+        let mut module = Self::default();
+        for taken in [type_name, "Commands", "Option", "PathBuf", "String", "Vec"] {
+            module.types.insert(taken.to_string());
+        }
+        module
+    }
+
+    fn claim_type(&mut self, name: String) -> String {
+        claim(&mut self.types, name)
+    }
+
+    fn claim_field(&mut self, name: String) -> String {
+        claim(&mut self.fields, name)
+    }
+}
+
+/// `name`, or `name2`, `name3`, … where it is taken.
+fn claim(taken: &mut HashSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut n = 2;
+    while taken.contains(&unique) {
+        unique = format!("{name}{n}");
+        n += 1;
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+/// A snake_case identifier. A keyword takes a trailing underscore rather than `r#`, which
+/// cannot spell `self` or `crate`.
+fn ident(name: &str) -> String {
+    let mut ident = heck::AsSnakeCase(name).to_string();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident = format!("_{ident}");
+    }
+    if ident == "_" || KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// A PascalCase type or variant name, never `Self`.
+fn type_name(name: &str) -> String {
+    let mut name = AsPascalCase(name).to_string();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("V{name}");
+    }
+    if name == "Self" {
+        name.push('_');
+    }
+    name
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", escape_rs_string(s))
+}
+
+/// `key = "value"`.
+fn prop(key: &str, value: &str) -> String {
+    format!("{key} = {}", quote(value))
+}
+
+/// `key("a", "b")`, the list spelling usage's derive takes.
+fn list(key: &str, values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|v| quote(v)).collect();
+    format!("{key}({})", values.join(", "))
+}
+
+/// How a derive reads a doc comment: the first paragraph is the help, the whole comment the
+/// long help, and a line break inside a paragraph is a space.
+///
+/// Text that reads back the same way is written as a doc comment, which is how a person
+/// would write it. Anything else — a long help that does not begin with the help, or a line
+/// break meant as one — is spelled out as attributes, so it survives exactly.
+enum Help {
+    None,
+    Doc(String),
+    Attrs {
+        help: Option<String>,
+        long: Option<String>,
+    },
+}
+
+impl Help {
+    fn of(help: Option<&str>, long: Option<&str>) -> Self {
+        let flat = |p: &str| !p.is_empty() && !p.contains('\n') && p.trim() == p;
+        match (help, long) {
+            (None, None) => Self::None,
+            (Some(help), None) if flat(help) => Self::Doc(help.to_string()),
+            (Some(help), Some(long))
+                if flat(help)
+                    && long.split("\n\n").all(flat)
+                    && long.split("\n\n").next() == Some(help) =>
+            {
+                Self::Doc(long.to_string())
+            }
+            (help, long) => Self::Attrs {
+                help: help.map(str::to_string),
+                long: long.map(str::to_string),
+            },
+        }
+    }
+
+    /// The doc comment lines, and the attributes spelling out what they cannot.
+    fn write(self, help_key: &str, long_key: &str) -> (Vec<String>, Vec<String>) {
+        match self {
+            Self::None => (vec![], vec![]),
+            Self::Doc(text) => (comment_lines("///", &text), vec![]),
+            Self::Attrs { help, long } => {
+                let mut attrs = vec![];
+                if let Some(help) = help {
+                    attrs.push(prop(help_key, &help));
+                }
+                if let Some(long) = long {
+                    attrs.push(prop(long_key, &long));
+                }
+                (vec![], attrs)
+            }
+        }
+    }
+}
+
+/// `#[name(a, b)]` at `indent`, broken one item per line where rustfmt would break it.
+fn attribute(out: &mut String, indent: &str, name: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let inline = items.join(", ");
+    // rustfmt's `attr_fn_like_width`, and its line width.
+    if inline.len() <= 70 && indent.len() + name.len() + inline.len() + 5 <= 100 {
+        let _ = writeln!(out, "{indent}#[{name}({inline})]");
+    } else {
+        let _ = writeln!(out, "{indent}#[{name}(");
+        let items = items.join(&format!(",\n{indent}    "));
+        let _ = writeln!(out, "{indent}    {items}");
+        let _ = writeln!(out, "{indent})]");
+    }
+}
+
+impl Scaffold<'_> {
+    fn leave_out(&mut self, at: &str, what: &str) {
+        self.left_out.push(format!("{at}: {what}"));
+    }
+
+    /// What the spec declares above any one command.
+    fn spec_left_out(&mut self) {
+        let spec = self.spec;
+        let at = spec.bin.clone();
+        let usage = self.framework == Framework::Usage;
+        if !spec.config.is_empty() {
+            self.leave_out(&at, "config");
+        }
+        if !spec.complete.is_empty() {
+            self.leave_out(&at, "complete");
+        }
+        if !spec.examples.is_empty() {
+            self.leave_out(&at, "example");
+        }
+        if spec.long_version.is_some() {
+            self.leave_out(&at, "long_version");
+        }
+        if !usage && spec.default_subcommand.is_some() {
+            self.leave_out(&at, "default_subcommand");
+        }
+        if !usage && spec.name != spec.bin {
+            self.leave_out(&at, "name");
+        }
+        if !usage && spec.license.is_some() {
+            self.leave_out(&at, "license");
+        }
+    }
+
+    /// The attributes a root struct carries, which a subcommand's cannot.
+    fn root_attrs(&self) -> Vec<String> {
+        let spec = self.spec;
+        let mut attrs = vec![];
+        match self.framework {
+            Framework::Usage => {
+                attrs.push(prop("bin", &spec.bin));
+                if spec.name != spec.bin {
+                    attrs.push(prop("name", &spec.name));
+                }
+            }
+            Framework::Clap => attrs.push(prop("name", &spec.bin)),
+        }
+        if let Some(version) = &spec.version {
+            attrs.push(prop("version", version));
+        }
+        if let Some(author) = &spec.author {
+            attrs.push(prop("author", author));
+        }
+        if self.framework == Framework::Usage {
+            if let Some(license) = &spec.license {
+                attrs.push(prop("license", license));
+            }
+            if let Some(default) = &spec.default_subcommand {
+                attrs.push(prop("default_subcommand", default));
+            }
+        }
+        attrs
+    }
+
+    fn module(&mut self, cmd: &SpecCommand, path: &[&str], file: &Path, struct_name: &str) {
+        let at = path.join(" ");
+        let root = path.len() == 1;
+        let fw = self.framework;
+        let mut module = Module::new(struct_name);
+
+        // The subcommand field claims its name before any flag can.
+        let has_subcommands = cmd.subcommands.values().any(|sub| !sub.mounted);
+        let command_field = has_subcommands.then(|| module.claim_field("command".to_string()));
+        let mut fields = vec![];
+        for flag in &cmd.flags {
+            self.flag_fields(&mut module, cmd, flag, &at, &mut fields);
+        }
+        for arg in &cmd.args {
+            let field = self.arg_field(&mut module, cmd, arg, &at);
+            fields.push(field);
+        }
+
+        let mut modules = HashSet::new();
+        let mut variant_names = HashSet::new();
+        let mut variants = vec![];
+        let dir = if root {
+            PathBuf::new()
+        } else {
+            file.with_extension("")
+        };
+        for (name, sub) in &cmd.subcommands {
+            if sub.mounted {
+                self.leave_out(&format!("{at} {name}"), "mount");
+                continue;
+            }
+            let module_name = claim(&mut modules, ident(name));
+            let ty = type_name(name);
+            let variant = claim(&mut variant_names, ty.clone());
+            let mut sub_path = path.to_vec();
+            sub_path.push(name);
+            variants.push(Variant {
+                attrs: self.variant_attrs(name, &variant, sub, &sub_path.join(" ")),
+                name: variant,
+                module: module_name.clone(),
+                ty: ty.clone(),
+            });
+            self.module(sub, &sub_path, &dir.join(format!("{module_name}.rs")), &ty);
+        }
+
+        if let Some(ident) = command_field {
+            let required = cmd.subcommand_required || root && self.spec.cmd.subcommand_required;
+            fields.push(Field {
+                docs: vec![],
+                attr: fw.command_attr(),
+                attrs: vec!["subcommand".to_string()],
+                ident,
+                ty: if required {
+                    "Commands".to_string()
+                } else {
+                    "Option<Commands>".to_string()
+                },
+            });
+        }
+
+        let (docs, mut attrs) = if root {
+            Help::of(self.spec.about.as_deref(), self.spec.about_long.as_deref())
+        } else {
+            Help::of(cmd.help.as_deref(), cmd.help_long.as_deref())
+        }
+        .write("about", "long_about");
+        if root {
+            let mut root_attrs = self.root_attrs();
+            root_attrs.append(&mut attrs);
+            attrs = root_attrs;
+        }
+        self.command_attrs(cmd, root, &at, &mut attrs);
+
+        // The file, top to bottom.
+        let mut out = String::new();
+        let _ = writeln!(out, "{}\n", self.header);
+        let struct_derive = match (fw, root) {
+            (Framework::Usage, true) => "Cli",
+            (Framework::Clap, true) => "Parser",
+            (_, false) => "Args",
+        };
+        let mut derives = BTreeSet::from([struct_derive]);
+        if !variants.is_empty() {
+            derives.insert(match fw {
+                Framework::Usage => "Subcommands",
+                Framework::Clap => "Subcommand",
+            });
+        }
+        if !module.enums.is_empty() {
+            derives.insert("ValueEnum");
+        }
+        let derives: Vec<&str> = derives.into_iter().collect();
+        let mut uses = vec![match derives.as_slice() {
+            [derive] => format!("use {}::{derive};", fw.name()),
+            derives => format!("use {}::{{{}}};", fw.name(), derives.join(", ")),
+        }];
+        if module.path_buf {
+            uses.push("use std::path::PathBuf;".to_string());
+        }
+        uses.sort();
+        for line in uses {
+            let _ = writeln!(out, "{line}");
+        }
+        if !variants.is_empty() {
+            out.push('\n');
+            let mut mods: Vec<&str> = variants.iter().map(|v| v.module.as_str()).collect();
+            mods.sort();
+            for module in mods {
+                let _ = writeln!(out, "mod {module};");
+            }
+        }
+
+        out.push('\n');
+        for line in &docs {
+            let _ = writeln!(out, "{line}");
+        }
+        let _ = writeln!(out, "#[derive(Debug, {struct_derive})]");
+        attribute(&mut out, "", fw.command_attr(), &attrs);
+        if fields.is_empty() {
+            let _ = writeln!(out, "pub struct {struct_name} {{}}");
+        } else {
+            let _ = writeln!(out, "pub struct {struct_name} {{");
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                for line in &field.docs {
+                    let _ = writeln!(out, "    {line}");
+                }
+                attribute(&mut out, "    ", field.attr, &field.attrs);
+                let _ = writeln!(out, "    pub {}: {},", field.ident, field.ty);
+            }
+            let _ = writeln!(out, "}}");
+        }
+
+        if !variants.is_empty() {
+            out.push('\n');
+            let _ = writeln!(
+                out,
+                "#[derive(Debug, {})]",
+                match fw {
+                    Framework::Usage => "Subcommands",
+                    Framework::Clap => "Subcommand",
+                }
+            );
+            let _ = writeln!(out, "pub enum Commands {{");
+            for variant in &variants {
+                attribute(&mut out, "    ", fw.command_attr(), &variant.attrs);
+                let _ = writeln!(
+                    out,
+                    "    {}({}::{}),",
+                    variant.name, variant.module, variant.ty
+                );
+            }
+            let _ = writeln!(out, "}}");
+        }
+
+        for choice_enum in std::mem::take(&mut module.enums) {
+            out.push('\n');
+            self.choice_enum(&mut out, &choice_enum, &at);
+        }
+
+        self.files.push(ScaffoldFile {
+            path: file.to_path_buf(),
+            content: out,
+        });
+    }
+
+    /// The names and visibility of a subcommand, which both derives read off the variant.
+    fn variant_attrs(
+        &mut self,
+        name: &str,
+        variant: &str,
+        sub: &SpecCommand,
+        at: &str,
+    ) -> Vec<String> {
+        let mut attrs = vec![];
+        if self.framework.default_name(variant) != name {
+            attrs.push(prop("name", name));
+        }
+        match self.framework {
+            Framework::Usage => {
+                if !sub.aliases.is_empty() {
+                    attrs.push(list("alias", &sub.aliases));
+                }
+                if !sub.hidden_aliases.is_empty() {
+                    attrs.push(list("alias_hidden", &sub.hidden_aliases));
+                }
+                if sub.hide {
+                    attrs.push("hide".to_string());
+                }
+                if let Some(heading) = &sub.help_heading {
+                    attrs.push(prop("help_heading", heading));
+                }
+            }
+            Framework::Clap => {
+                for alias in &sub.aliases {
+                    attrs.push(prop("visible_alias", alias));
+                }
+                for alias in &sub.hidden_aliases {
+                    attrs.push(prop("alias", alias));
+                }
+                if sub.hide {
+                    attrs.push("hide = true".to_string());
+                }
+                if sub.help_heading.is_some() {
+                    self.leave_out(at, "help_heading");
+                }
+            }
+        }
+        attrs
+    }
+
+    /// What a command's struct says about the command beyond its help.
+    fn command_attrs(&mut self, cmd: &SpecCommand, root: bool, at: &str, attrs: &mut Vec<String>) {
+        let usage = self.framework == Framework::Usage;
+        let (before, before_long, after, after_long) = if root {
+            let spec = self.spec;
+            (
+                &spec.before_help,
+                &spec.before_help_long,
+                &spec.after_help,
+                &spec.after_help_long,
+            )
+        } else {
+            (
+                &cmd.before_help,
+                &cmd.before_help_long,
+                &cmd.after_help,
+                &cmd.after_help_long,
+            )
+        };
+        for (key, text) in [
+            ("before_help", before),
+            ("before_long_help", before_long),
+            ("after_help", after),
+            ("after_long_help", after_long),
+        ] {
+            if let Some(text) = text {
+                attrs.push(prop(key, text));
+            }
+        }
+        if let Some(deprecated) = &cmd.deprecated {
+            if usage {
+                attrs.push(prop("deprecated", deprecated));
+            } else {
+                self.leave_out(at, "deprecated");
+            }
+        }
+        if let Some(effect) = &cmd.effect {
+            if usage {
+                attrs.push(prop("effect", effect.as_str()));
+            } else {
+                self.leave_out(at, "effect");
+            }
+        }
+        for (present, what) in [
+            (cmd.stability.is_some(), "stability"),
+            (cmd.since.is_some(), "since"),
+            (!cmd.examples.is_empty(), "example"),
+            (!cmd.exits.is_empty(), "exit"),
+            (!cmd.outputs.is_empty(), "output"),
+            (!cmd.mounts.is_empty(), "mount"),
+            (!cmd.complete.is_empty(), "complete"),
+            (!cmd.groups.is_empty(), "group"),
+            (cmd.restart_token.is_some(), "restart_token"),
+            (cmd.unknown_flags.is_some(), "unknown_flags"),
+            (cmd.external_subcommand, "external_subcommand"),
+        ] {
+            if present {
+                self.leave_out(at, what);
+            }
+        }
+    }
+
+    /// The field a flag becomes, and for clap a `negate`d flag's second.
+    fn flag_fields(
+        &mut self,
+        module: &mut Module,
+        cmd: &SpecCommand,
+        flag: &SpecFlag,
+        at: &str,
+        fields: &mut Vec<Field>,
+    ) {
+        let fw = self.framework;
+        let usage = fw == Framework::Usage;
+        let at = format!("{at} {}", flag_label(flag));
+        let field_ident = module.claim_field(ident(&flag.name));
+        let (docs, help_attrs) =
+            Help::of(flag.help.as_deref(), flag.help_long.as_deref()).write("help", "long_help");
+
+        let mut attrs = vec![];
+        if let Some(short) = flag.short.first() {
+            if field_ident.starts_with(*short) {
+                attrs.push("short".to_string());
+            } else {
+                attrs.push(format!("short = '{}'", short.escape_default()));
+            }
+        }
+        if let Some(long) = flag.long.first() {
+            if field_ident.replace('_', "-") == *long {
+                attrs.push("long".to_string());
+            } else {
+                attrs.push(prop("long", long));
+            }
+        }
+        for alias in flag.long.iter().skip(1) {
+            attrs.push(prop("visible_alias", alias));
+        }
+        for alias in &flag.hidden_aliases {
+            attrs.push(prop("alias", alias));
+        }
+        if flag.short.len() > 1 || !flag.hidden_short_aliases.is_empty() {
+            self.leave_out(&at, "a second short");
+        }
+
+        let ty = match &flag.arg {
+            None if flag.count || (flag.var && !usage) => {
+                attrs.push(match fw {
+                    Framework::Usage => "count".to_string(),
+                    Framework::Clap => "action = clap::ArgAction::Count".to_string(),
+                });
+                if usage && flag.var && !flag.count {
+                    attrs.push("var".to_string());
+                }
+                "u8".to_string()
+            }
+            None => {
+                if flag.var {
+                    attrs.push("var".to_string());
+                }
+                "bool".to_string()
+            }
+            Some(arg) => {
+                let (ty, value_enum) = self.value_type(module, &flag.name, arg, &at);
+                if arg.name != field_ident.to_uppercase() {
+                    attrs.push(prop("value_name", &arg.name));
+                }
+                if value_enum {
+                    attrs.push("value_enum".to_string());
+                }
+                if flag.var {
+                    if usage {
+                        attrs.push("var".to_string());
+                    }
+                    if flag.required {
+                        attrs.push(required(fw));
+                    }
+                    format!("Vec<{ty}>")
+                } else if flag.required || !flag.default.is_empty() {
+                    ty
+                } else {
+                    format!("Option<{ty}>")
+                }
+            }
+        };
+        if flag.arg.is_none() && flag.required {
+            attrs.push(required(fw));
+        }
+        self.default_attrs(&flag.default, flag.arg.is_none(), &at, &mut attrs);
+        let mut negation_field = None;
+        if let Some(negate) = &flag.negate {
+            match fw {
+                Framework::Usage => attrs.push(prop("negate", negate)),
+                Framework::Clap => {
+                    // clap has no negation of its own; the convention is a second switch
+                    // overriding the first.
+                    let negation = module.claim_field(ident(negate.trim_start_matches('-')));
+                    attrs.push(prop("overrides_with", &negation));
+                    negation_field = Some(Field {
+                        docs: comment_lines("///", &format!("Negates {}", flag_label(flag))),
+                        attr: fw.field_attr(),
+                        attrs: vec![
+                            prop("long", negate.trim_start_matches('-')),
+                            prop("overrides_with", &field_ident),
+                        ],
+                        ident: negation,
+                        ty: "bool".to_string(),
+                    });
+                }
+            }
+        }
+        if flag.global {
+            attrs.push(switch(fw, "global"));
+        }
+        if flag.hide {
+            attrs.push(switch(fw, "hide"));
+        }
+        if flag.exclusive {
+            attrs.push(switch(fw, "exclusive"));
+        }
+        if flag.require_equals {
+            attrs.push(switch(fw, "require_equals"));
+        }
+        if let Some(env) = &flag.env {
+            attrs.push(prop("env", env));
+        }
+        self.env_attrs(&flag.env_fallback, &flag.deprecated_env, &at, &mut attrs);
+        if let Some(heading) = &flag.help_heading {
+            attrs.push(prop("help_heading", heading));
+        }
+        if let Some(deprecated) = &flag.deprecated {
+            if usage {
+                attrs.push(prop("deprecated", deprecated));
+            } else {
+                self.leave_out(&at, "deprecated");
+            }
+        }
+        if let Some(effect) = &flag.effect {
+            if usage {
+                attrs.push(prop("effect", effect.as_str()));
+            } else {
+                self.leave_out(&at, "effect");
+            }
+        }
+        if let Some(arg) = &flag.arg {
+            self.bounds(arg, &mut attrs);
+        }
+        self.selectors(cmd, "conflicts", &flag.conflicts, &at, &mut attrs);
+        self.selectors(cmd, "requires", &flag.requires, &at, &mut attrs);
+        self.selectors(cmd, "overrides", &flag.overrides, &at, &mut attrs);
+        for (present, what) in [
+            (!flag.required_if.is_empty(), "required_if"),
+            (!flag.required_if_eq.is_empty(), "required_if_eq"),
+            (!flag.required_if_eq_all.is_empty(), "required_if_eq_all"),
+            (!flag.required_unless.is_empty(), "required_unless"),
+            (!flag.required_unless_all.is_empty(), "required_unless_all"),
+            (!flag.requires_if.is_empty(), "requires_if"),
+            (!flag.default_if.is_empty(), "default_if"),
+            (flag.default_missing.is_some(), "default_missing"),
+            (flag.value_optional, "an optional value"),
+            (flag.stability.is_some(), "stability"),
+            (flag.since.is_some(), "since"),
+        ] {
+            if present {
+                self.leave_out(&at, what);
+            }
+        }
+        attrs.extend(help_attrs);
+
+        fields.push(Field {
+            docs,
+            attr: fw.field_attr(),
+            attrs,
+            ident: field_ident,
+            ty,
+        });
+        fields.extend(negation_field);
+    }
+
+    fn arg_field(
+        &mut self,
+        module: &mut Module,
+        cmd: &SpecCommand,
+        arg: &SpecArg,
+        at: &str,
+    ) -> Field {
+        let fw = self.framework;
+        let usage = fw == Framework::Usage;
+        let at = format!("{at} <{}>", arg.name);
+        let field_ident = module.claim_field(ident(&arg.name));
+        let (docs, help_attrs) =
+            Help::of(arg.help.as_deref(), arg.help_long.as_deref()).write("help", "long_help");
+        let mut attrs = vec![];
+        let (ty, value_enum) = self.value_type(module, &arg.name, arg, &at);
+        if arg.name != field_ident.to_uppercase() {
+            attrs.push(prop("value_name", &arg.name));
+        }
+        if value_enum {
+            attrs.push("value_enum".to_string());
+        }
+        let ty = if arg.var {
+            if arg.required {
+                attrs.push(required(fw));
+            }
+            format!("Vec<{ty}>")
+        } else if arg.required || !arg.default.is_empty() {
+            ty
+        } else {
+            format!("Option<{ty}>")
+        };
+        self.default_attrs(&arg.default, false, &at, &mut attrs);
+        match (&arg.double_dash, fw) {
+            (SpecDoubleDashChoices::Optional, _) => {}
+            (double_dash, Framework::Usage) => {
+                attrs.push(prop("double_dash", &double_dash.to_string()));
+            }
+            (SpecDoubleDashChoices::Required, Framework::Clap) => {
+                attrs.push("last = true".to_string());
+            }
+            (_, Framework::Clap) => self.leave_out(&at, "double_dash"),
+        }
+        if arg.hide {
+            attrs.push(switch(fw, "hide"));
+        }
+        if let Some(env) = &arg.env {
+            attrs.push(prop("env", env));
+        }
+        self.env_attrs(&arg.env_fallback, &arg.deprecated_env, &at, &mut attrs);
+        if let Some(heading) = &arg.help_heading {
+            attrs.push(prop("help_heading", heading));
+        }
+        if let Some(effect) = &arg.effect {
+            if usage {
+                attrs.push(prop("effect", effect.as_str()));
+            } else {
+                self.leave_out(&at, "effect");
+            }
+        }
+        self.bounds(arg, &mut attrs);
+        self.selectors(cmd, "conflicts", &arg.conflicts, &at, &mut attrs);
+        self.selectors(cmd, "requires", &arg.requires, &at, &mut attrs);
+        for (present, what) in [
+            (!arg.required_if.is_empty(), "required_if"),
+            (!arg.required_if_eq.is_empty(), "required_if_eq"),
+            (!arg.required_if_eq_all.is_empty(), "required_if_eq_all"),
+            (!arg.required_unless.is_empty(), "required_unless"),
+            (!arg.required_unless_all.is_empty(), "required_unless_all"),
+            (arg.value_terminator.is_some(), "value_terminator"),
+            (arg.stability.is_some(), "stability"),
+            (arg.since.is_some(), "since"),
+        ] {
+            if present {
+                self.leave_out(&at, what);
+            }
+        }
+        attrs.extend(help_attrs);
+        Field {
+            docs,
+            attr: fw.field_attr(),
+            attrs,
+            ident: field_ident,
+            ty,
+        }
+    }
+
+    /// The type one value becomes, and whether it is an enum of choices.
+    fn value_type(
+        &mut self,
+        module: &mut Module,
+        name: &str,
+        arg: &SpecArg,
+        at: &str,
+    ) -> (String, bool) {
+        if let Some(choices) = &arg.choices {
+            if choices.strict {
+                let name = module.claim_type(type_name(name));
+                module.enums.push(ChoiceEnum {
+                    name: name.clone(),
+                    choices: choices.clone(),
+                });
+                return (name, true);
+            }
+            self.leave_out(at, "choices that are not strict");
+        }
+        let Some(value_type) = &arg.value_type else {
+            return ("String".to_string(), false);
+        };
+        let ty = match value_type {
+            Base::Int => "i64",
+            Base::Uint => "u64",
+            Base::Float => "f64",
+            Base::Path => {
+                module.path_buf = true;
+                "PathBuf"
+            }
+            _ => "String",
+        };
+        self.leave_out(at, &format!("type={value_type}, as the field's type {ty}"));
+        (ty.to_string(), false)
+    }
+
+    fn default_attrs(
+        &mut self,
+        default: &[String],
+        switch: bool,
+        at: &str,
+        attrs: &mut Vec<String>,
+    ) {
+        match (self.framework, default) {
+            (_, []) => {}
+            (Framework::Usage, defaults) => {
+                for default in defaults {
+                    attrs.push(prop("default", default));
+                }
+            }
+            (Framework::Clap, _) if switch => self.leave_out(at, "a default on a switch"),
+            (Framework::Clap, [default]) => attrs.push(prop("default_value", default)),
+            (Framework::Clap, defaults) => {
+                let defaults: Vec<String> = defaults.iter().map(|d| quote(d)).collect();
+                attrs.push(format!("default_values = [{}]", defaults.join(", ")));
+            }
+        }
+    }
+
+    fn env_attrs(
+        &mut self,
+        fallback: &[String],
+        deprecated: &[String],
+        at: &str,
+        attrs: &mut Vec<String>,
+    ) {
+        match self.framework {
+            Framework::Usage => {
+                if !fallback.is_empty() {
+                    attrs.push(list("env_fallback", fallback));
+                }
+                if !deprecated.is_empty() {
+                    attrs.push(list("deprecated_env", deprecated));
+                }
+            }
+            Framework::Clap => {
+                if !fallback.is_empty() {
+                    self.leave_out(at, "env_fallback");
+                }
+                if !deprecated.is_empty() {
+                    self.leave_out(at, "deprecated_env");
+                }
+            }
+        }
+    }
+
+    /// `var_min` and `var_max`: separate bounds for usage, one range for clap.
+    fn bounds(&self, arg: &SpecArg, attrs: &mut Vec<String>) {
+        match self.framework {
+            Framework::Usage => {
+                if let Some(min) = arg.var_min {
+                    attrs.push(format!("var_min = {min}"));
+                }
+                if let Some(max) = arg.var_max {
+                    attrs.push(format!("var_max = {max}"));
+                }
+            }
+            Framework::Clap => match (arg.var_min, arg.var_max) {
+                (None, None) => {}
+                (Some(min), None) => attrs.push(format!("num_args = {min}..")),
+                (min, Some(max)) => attrs.push(format!("num_args = {}..={max}", min.unwrap_or(0))),
+            },
+        }
+    }
+
+    /// `conflicts`, `requires` and `overrides`. usage's derive takes the spec's own selectors;
+    /// clap names the field.
+    fn selectors(
+        &mut self,
+        cmd: &SpecCommand,
+        key: &str,
+        selectors: &[String],
+        at: &str,
+        attrs: &mut Vec<String>,
+    ) {
+        if selectors.is_empty() {
+            return;
+        }
+        match self.framework {
+            Framework::Usage => attrs.push(if selectors.len() == 1 {
+                prop(key, &selectors[0])
+            } else {
+                list(key, selectors)
+            }),
+            Framework::Clap => {
+                let key = match key {
+                    "conflicts" => "conflicts_with",
+                    "overrides" => "overrides_with",
+                    other => other,
+                };
+                for selector in selectors {
+                    match selected(cmd, selector) {
+                        Some(id) => attrs.push(prop(key, &id)),
+                        None => self.leave_out(at, &format!("{key} {selector}")),
+                    }
+                }
+            }
+        }
+    }
+
+    fn choice_enum(&mut self, out: &mut String, choice_enum: &ChoiceEnum, at: &str) {
+        let fw = self.framework;
+        let choices = &choice_enum.choices;
+        let variants = choice_variant_names(&choices.choices).into_iter().map(|v| {
+            if v == "Self" {
+                "Self_".to_string()
+            } else {
+                v
+            }
+        });
+        let _ = writeln!(
+            out,
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]"
+        );
+        if choices.ignore_case {
+            match fw {
+                Framework::Usage => {
+                    let _ = writeln!(out, "#[usage(ignore_case)]");
+                }
+                Framework::Clap => self.leave_out(at, "ignore_case"),
+            }
+        }
+        let _ = writeln!(out, "pub enum {} {{", choice_enum.name);
+        for (choice, variant) in choices.choices.iter().zip(variants) {
+            let detail = choices.details.iter().find(|d| d.value == *choice);
+            if let Some(help) = detail.and_then(|d| d.help.as_deref()) {
+                for line in comment_lines("///", help) {
+                    let _ = writeln!(out, "    {line}");
+                }
+            }
+            let mut attrs = vec![];
+            if fw.default_name(&variant) != *choice {
+                attrs.push(prop("name", choice));
+            }
+            if detail.is_some_and(|d| d.hide) {
+                attrs.push(switch(fw, "hide"));
+            }
+            if detail.is_some_and(|d| !d.aliases.is_empty()) {
+                self.leave_out(at, &format!("aliases of {choice}"));
+            }
+            let attr = match fw {
+                Framework::Usage => "usage",
+                Framework::Clap => "value",
+            };
+            attribute(out, "    ", attr, &attrs);
+            let _ = writeln!(out, "    {variant},");
+        }
+        let _ = writeln!(out, "}}");
+    }
+}
+
+/// `-f --force`, as a flag is named where something about it is reported.
+fn flag_label(flag: &SpecFlag) -> String {
+    match flag.long.first() {
+        Some(long) => format!("--{long}"),
+        None => format!("-{}", flag.name),
+    }
+}
+
+/// A bare switch for usage, `= true` for clap.
+fn switch(fw: Framework, key: &str) -> String {
+    match fw {
+        Framework::Usage => key.to_string(),
+        Framework::Clap => format!("{key} = true"),
+    }
+}
+
+fn required(fw: Framework) -> String {
+    switch(fw, "required")
+}
+
+/// The field a spec selector — `--long`, `-s`, or a positional's name — names, as clap's id.
+fn selected(cmd: &SpecCommand, selector: &str) -> Option<String> {
+    if let Some(long) = selector.strip_prefix("--") {
+        cmd.flags
+            .iter()
+            .find(|f| f.long.iter().any(|l| l == long))
+            .map(|f| ident(&f.name))
+    } else if let Some(short) = selector.strip_prefix('-') {
+        let mut chars = short.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        cmd.flags
+            .iter()
+            .find(|f| f.short.contains(&c))
+            .map(|f| ident(&f.name))
+    } else {
+        cmd.args
+            .iter()
+            .find(|a| a.name == selector)
+            .map(|a| ident(&a.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Framework, ScaffoldOptions, ScaffoldOutput};
+    use crate::Spec;
+
+    fn scaffold(spec: &str, framework: Framework) -> ScaffoldOutput {
+        let spec: Spec = spec.parse().unwrap();
+        super::generate(
+            &spec,
+            &ScaffoldOptions {
+                framework,
+                source_file: Some("test.usage.kdl".to_string()),
+            },
+        )
+    }
+
+    fn get_file<'a>(output: &'a ScaffoldOutput, name: &str) -> &'a str {
+        output
+            .files
+            .iter()
+            .find(|f| f.path.to_str() == Some(name))
+            .unwrap_or_else(|| panic!("{name} should exist"))
+            .content
+            .as_str()
+    }
+
+    const SPEC: &str = r##"
+        bin "mytool"
+        version "1.2.3"
+        about "A powerful CLI tool"
+
+        flag "-v --verbose" help="Verbosity level" count=#true var=#true global=#true
+        flag "-C --config <path>" help="Config file path" type="path" env="MYTOOL_CONFIG"
+        flag "--color" help="Color the output" negate="--no-color"
+        flag "--format <format>" default="text" {
+            choices "text" "json"
+        }
+
+        cmd "build" help="Build the project" deprecated="Use compile instead" {
+            alias "b"
+            arg "<target>" help="Build target" {
+                choices "debug" "release"
+            }
+            flag "-j --jobs <n>" help="Parallel jobs" type="uint" default="4"
+            flag "--feature <name>" var=#true
+            flag "--release" help="Build in release mode" conflicts="--profile"
+            flag "--profile <name>"
+            cmd "plan" help="Show the build plan" hide=#true
+        }
+    "##;
+
+    #[test]
+    fn test_usage_root() {
+        let output = scaffold(SPEC, Framework::Usage);
+        insta::assert_snapshot!(get_file(&output, "mod.rs"));
+    }
+
+    #[test]
+    fn test_usage_subcommand() {
+        let output = scaffold(SPEC, Framework::Usage);
+        insta::assert_snapshot!(get_file(&output, "build.rs"));
+        assert!(get_file(&output, "build/plan.rs").contains("pub struct Plan {}"));
+    }
+
+    #[test]
+    fn test_clap_root() {
+        let output = scaffold(SPEC, Framework::Clap);
+        insta::assert_snapshot!(get_file(&output, "mod.rs"));
+    }
+
+    #[test]
+    fn test_clap_subcommand() {
+        let output = scaffold(SPEC, Framework::Clap);
+        insta::assert_snapshot!(get_file(&output, "build.rs"));
+    }
+
+    /// Help a doc comment would read back differently is spelled out instead.
+    #[test]
+    fn test_help_that_is_not_a_doc_comment() {
+        let output = scaffold(
+            r#"
+                bin "app"
+                flag "--a" help="Short" long_help="Not starting with the short help"
+                flag "--b" help="Two\nlines"
+                flag "--c" help="Short" long_help="Short\n\nAnd more"
+            "#,
+            Framework::Usage,
+        );
+        let root = get_file(&output, "mod.rs");
+        assert!(
+            root.contains(
+                r#"#[usage(long, help = "Short", long_help = "Not starting with the short help")]"#
+            ),
+            "{root}"
+        );
+        assert!(
+            root.contains(r#"#[usage(long, help = "Two\nlines")]"#),
+            "{root}"
+        );
+        assert!(
+            root.contains("    /// Short\n    ///\n    /// And more\n    #[usage(long)]\n"),
+            "{root}"
+        );
+    }
+
+    /// Keywords, and the name the subcommand field takes, are renamed; the long is kept.
+    #[test]
+    fn test_reserved_names() {
+        let output = scaffold(
+            r#"
+                bin "app"
+                flag "--type <t>"
+                flag "--command"
+                arg "[self]"
+                cmd "run"
+            "#,
+            Framework::Usage,
+        );
+        let root = get_file(&output, "mod.rs");
+        assert!(
+            root.contains(r#"#[usage(long = "type", value_name = "t")]"#),
+            "{root}"
+        );
+        assert!(root.contains("pub type_: Option<String>,"), "{root}");
+        assert!(root.contains(r#"#[usage(long = "command")]"#), "{root}");
+        assert!(root.contains("pub command2: bool,"), "{root}");
+        assert!(root.contains("pub self_: Option<String>,"), "{root}");
+        assert!(root.contains("pub command: Option<Commands>,"), "{root}");
+    }
+
+    /// What a derive cannot say is reported rather than dropped.
+    #[test]
+    fn test_left_out() {
+        let spec = r#"
+            bin "app"
+            flag "--level <n>" type="int" deprecated="no longer read"
+            cmd "run" effect="write" stability="beta" {
+                example "app run"
+                flag "--x" required_unless="--y"
+                flag "--y"
+            }
+        "#;
+        insta::assert_snapshot!(scaffold(spec, Framework::Usage).left_out.join("\n"), @r"
+        app --level: type=int, as the field's type i64
+        app run --x: required_unless
+        app run: stability
+        app run: example
+        ");
+        insta::assert_snapshot!(scaffold(spec, Framework::Clap).left_out.join("\n"), @r"
+        app --level: type=int, as the field's type i64
+        app --level: deprecated
+        app run --x: required_unless
+        app run: effect
+        app run: stability
+        app run: example
+        ");
+    }
+}
@@ -0,0 +1,995 @@
+use std::path::PathBuf;
+
+use heck::AsPascalCase;
+
+use crate::sdk::{
+    choice_variant_names, collect_choice_types, command_flags, command_type_name, comment_lines,
+    escape_go_string, flag_spelling, ChoiceTypeMap, CodeWriter, FieldShape, SdkFile, SdkOptions,
+    SdkOutput,
+};
+use crate::spec::arg::SpecDoubleDashChoices;
+use crate::spec::cmd::SpecCommand;
+use crate::spec::config::{SpecConfigProp, SpecConfigValue};
+use crate::spec::config_type::Base;
+use crate::spec::data_types::SpecDataTypes;
+use crate::{Spec, SpecArg, SpecFlag};
+
+mod runtime;
+
+/// Generates one package: the directory goes into the caller's module as it is, so it
+/// brings no `go.mod` and imports nothing outside the standard library.
+pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
+    let package_name = opts
+        .package_name
+        .clone()
+        .unwrap_or_else(|| spec.bin.clone());
+    let package = go_package(&package_name);
+
+    SdkOutput {
+        files: vec![
+            SdkFile {
+                path: PathBuf::from("types.go"),
+                content: render_types(spec, &package_name, &package, &opts.source_file),
+            },
+            SdkFile {
+                path: PathBuf::from("client.go"),
+                content: render_client(spec, &package_name, &package, &opts.source_file),
+            },
+            SdkFile {
+                path: PathBuf::from("runtime.go"),
+                content: format!(
+                    "{}\n\npackage {package}\n\n{}",
+                    generated_header(&None),
+                    runtime::RUNTIME_GO
+                ),
+            },
+        ],
+    }
+}
+
+/// Go's own marker for generated code, which `go vet`, linters and code review tools look
+/// for by its exact wording — so not the `@generated` line the other languages get.
+fn generated_header(source: &Option<String>) -> String {
+    match source {
+        Some(s) => format!("// Code generated by usage-cli from {s}. DO NOT EDIT."),
+        None => "// Code generated by usage-cli. DO NOT EDIT.".to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// types.go
+// ---------------------------------------------------------------------------
+
+fn render_types(
+    spec: &Spec,
+    package_name: &str,
+    package: &str,
+    source_file: &Option<String>,
+) -> String {
+    let mut w = CodeWriter::with_indent("\t");
+
+    w.line(&generated_header(source_file));
+    w.line("");
+    w.line(&format!("package {package}"));
+
+    // spec metadata
+    let metadata = [
+        ("Version", &spec.version),
+        ("About", &spec.about),
+        ("Author", &spec.author),
+    ];
+    if metadata.iter().any(|(_, value)| value.is_some()) {
+        w.line("");
+    }
+    for (name, value) in metadata {
+        if let Some(value) = value {
+            w.line(&format!("const {name} = \"{}\"", escape_go_string(value)));
+        }
+    }
+
+    let choice_types = collect_choice_types(&spec.cmd);
+    for (name, choices) in choice_types.iter() {
+        w.line("");
+        w.line(&format!("// {name} is one of the values the CLI accepts."));
+        w.line(&format!("type {name} string"));
+        w.line("");
+        w.line("const (");
+        w.indent();
+        let rows = choice_variant_names(choices)
+            .into_iter()
+            .zip(choices)
+            .map(|(variant, choice)| {
+                vec![
+                    format!("{name}{variant}"),
+                    name.to_string(),
+                    format!("= \"{}\"", escape_go_string(choice)),
+                ]
+            })
+            .collect();
+        for line in align(rows) {
+            w.line(&line);
+        }
+        w.dedent();
+        w.line(")");
+    }
+
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
+    if !global_flags.is_empty() {
+        let fields = global_flags
+            .iter()
+            .map(|flag| flag_field(flag, &spec.cmd.name, &choice_types))
+            .collect();
+        w.line("");
+        w.line("// GlobalFlags holds the flags available on all subcommands.");
+        render_struct("GlobalFlags", fields, &mut w);
+    }
+
+    render_command_types(
+        &spec.cmd,
+        package_name,
+        &spec.bin,
+        &spec.cmd.name,
+        &choice_types,
+        &global_flags,
+        &mut w,
+    );
+
+    if !spec.config.props.is_empty() {
+        w.line("");
+        render_config(spec, package_name, &mut w);
+    }
+
+    w.finish()
+}
+
+fn render_command_types(
+    cmd: &SpecCommand,
+    package_name: &str,
+    bin: &str,
+    root_cmd_name: &str,
+    choice_types: &ChoiceTypeMap,
+    global_flags: &[&SpecFlag],
+    w: &mut CodeWriter,
+) {
+    if cmd.hide {
+        return;
+    }
+
+    let name = command_type_name(cmd, package_name);
+    let invocation = std::iter::once(bin)
+        .chain(cmd.full_cmd.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let visible_args: Vec<&SpecArg> = cmd.args.iter().filter(|a| !a.hide).collect();
+
+    if !visible_args.is_empty() {
+        let fields = visible_args
+            .iter()
+            .map(|arg| arg_field(arg, &cmd.name, choice_types))
+            .collect();
+        w.line("");
+        w.line(&format!(
+            "// {name}Args holds the arguments to \"{invocation}\"."
+        ));
+        render_struct(&format!("{name}Args"), fields, w);
+    }
+
+    if cmd.flags.iter().any(|f| !f.hide) {
+        let fields = command_flags(cmd, global_flags)
+            .into_iter()
+            .map(|flag| {
+                let lookup_cmd = if flag.global {
+                    root_cmd_name
+                } else {
+                    &cmd.name
+                };
+                flag_field(flag, lookup_cmd, choice_types)
+            })
+            .collect();
+        w.line("");
+        w.line(&format!(
+            "// {name}Flags holds the flags to \"{invocation}\"."
+        ));
+        render_struct(&format!("{name}Flags"), fields, w);
+    }
+
+    for subcmd in cmd.subcommands.values() {
+        render_command_types(
+            subcmd,
+            package_name,
+            bin,
+            root_cmd_name,
+            choice_types,
+            global_flags,
+            w,
+        );
+    }
+}
+
+/// A field of a struct, with the comment lines above it.
+struct Field {
+    ident: String,
+    ty: String,
+    doc: Vec<String>,
+}
+
+/// The type of one value of an arg or flag, and how it is held.
+fn field_type(shape: FieldShape, ty: &str) -> String {
+    match shape {
+        FieldShape::One => ty.to_string(),
+        FieldShape::Optional => format!("*{ty}"),
+        FieldShape::Many => format!("[]{ty}"),
+        FieldShape::Switch => "bool".to_string(),
+        FieldShape::Negatable => "*bool".to_string(),
+        FieldShape::Count => "int".to_string(),
+    }
+}
+
+fn arg_field(arg: &SpecArg, cmd_name: &str, choice_types: &ChoiceTypeMap) -> Field {
+    let mut doc = vec![];
+    if let Some(help) = &arg.help {
+        doc.push(help.clone());
+    }
+    if !arg.default.is_empty() {
+        doc.push(format!("Defaults to {:?}.", arg.default.join(" ")));
+    }
+    let ty = value_go_type(arg, cmd_name, &arg.name, choice_types);
+    Field {
+        ident: go_ident(&arg.name),
+        ty: field_type(FieldShape::of_arg(arg), &ty),
+        doc,
+    }
+}
+
+fn flag_field(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -> Field {
+    let mut doc = vec![];
+    if let Some(help) = &flag.help {
+        doc.push(help.clone());
+    }
+    if !flag.default.is_empty() {
+        doc.push(format!("Defaults to {:?}.", flag.default.join(" ")));
+    }
+    if let Some(env) = &flag.env {
+        doc.push(format!("Environment variable: {env}."));
+    }
+    if let Some(deprecated) = &flag.deprecated {
+        doc.push(format!("Deprecated: {deprecated}"));
+    }
+    let ty = match &flag.arg {
+        Some(arg) => value_go_type(arg, cmd_name, &flag.name, choice_types),
+        None => "bool".to_string(),
+    };
+    Field {
+        ident: flag_field_ident(flag),
+        ty: field_type(FieldShape::of_flag(flag), &ty),
+        doc,
+    }
+}
+
+fn render_struct(name: &str, fields: Vec<Field>, w: &mut CodeWriter) {
+    w.line(&format!("type {name} struct {{"));
+    w.indent();
+    render_fields(fields, w);
+    w.dedent();
+    w.line("}");
+}
+
+/// Struct fields as gofmt lays them out: a field's comment ends the run of fields whose
+/// types line up, and the field under it starts the next.
+fn render_fields(fields: Vec<Field>, w: &mut CodeWriter) {
+    let mut run: Vec<Vec<String>> = vec![];
+    for field in fields {
+        if !field.doc.is_empty() {
+            for line in align(std::mem::take(&mut run)) {
+                w.line(&line);
+            }
+            render_doc(&field.doc, w);
+        }
+        run.push(vec![field.ident, field.ty]);
+    }
+    for line in align(run) {
+        w.line(&line);
+    }
+}
+
+fn render_doc(doc: &[String], w: &mut CodeWriter) {
+    for (i, part) in doc.iter().enumerate() {
+        if i > 0 {
+            w.line("//");
+        }
+        for line in comment_lines("//", part) {
+            w.line(&line);
+        }
+    }
+}
+
+/// Rows of cells, each but the last padded to its column's width plus one space.
+fn align(rows: Vec<Vec<String>>) -> Vec<String> {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            rows.iter()
+                .filter_map(|row| row.get(c).map(|cell| cell.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    rows.into_iter()
+        .map(|row| {
+            let last = row.len() - 1;
+            row.iter()
+                .enumerate()
+                .map(|(c, cell)| {
+                    if c == last {
+                        cell.clone()
+                    } else {
+                        format!("{cell:<width$} ", width = widths[c])
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn render_config(spec: &Spec, package_name: &str, w: &mut CodeWriter) {
+    let config_name = format!("{}Config", AsPascalCase(package_name));
+    let mut fields = vec![];
+    let mut defaults = vec![];
+    for (name, prop) in &spec.config.props {
+        let ident = go_ident(name);
+        let (ty, default) = config_prop_type(prop);
+        let doc = prop.help.iter().cloned().collect();
+        match default {
+            Some(default) => {
+                fields.push(Field {
+                    ident: ident.clone(),
+                    ty,
+                    doc,
+                });
+                defaults.push((ident, default));
+            }
+            None => fields.push(Field {
+                ident,
+                ty: format!("*{ty}"),
+                doc,
+            }),
+        }
+    }
+
+    w.line(&format!(
+        "// {config_name} holds the settings {} reads from its config.",
+        spec.bin
+    ));
+    render_struct(&config_name, fields, w);
+    w.line("");
+    w.line(&format!(
+        "// Default{config_name} returns a {config_name} holding each setting's default."
+    ));
+    w.line(&format!("func Default{config_name}() {config_name} {{"));
+    w.indent();
+    if defaults.is_empty() {
+        w.line(&format!("return {config_name}{{}}"));
+    } else {
+        w.line(&format!("var c {config_name}"));
+        for (ident, default) in defaults {
+            w.line(&format!("c.{ident} = {default}"));
+        }
+        w.line("return c");
+    }
+    w.dedent();
+    w.line("}");
+}
+
+/// The Go type of a config prop, and its default as a literal of that type. A default the
+/// declared type cannot hold is left out, as the Rust generator does.
+fn config_prop_type(prop: &SpecConfigProp) -> (String, Option<String>) {
+    let data_type = match (&prop.data_type, &prop.default) {
+        (SpecDataTypes::Null, Some(SpecConfigValue::Bool(_))) => &SpecDataTypes::Boolean,
+        (SpecDataTypes::Null, Some(SpecConfigValue::Int(_))) => &SpecDataTypes::Integer,
+        (SpecDataTypes::Null, Some(SpecConfigValue::Float(_))) => &SpecDataTypes::Float,
+        (SpecDataTypes::Null, Some(SpecConfigValue::String(_))) => &SpecDataTypes::String,
+        (data_type, _) => data_type,
+    };
+    let default = match (data_type, &prop.default) {
+        (SpecDataTypes::Boolean, Some(SpecConfigValue::Bool(b))) => Some(b.to_string()),
+        (SpecDataTypes::Integer | SpecDataTypes::Float, Some(SpecConfigValue::Int(i))) => {
+            Some(i.to_string())
+        }
+        (SpecDataTypes::Float, Some(SpecConfigValue::Float(f))) if f.is_finite() => {
+            Some(format!("{f:?}"))
+        }
+        (SpecDataTypes::String, Some(SpecConfigValue::String(s))) => {
+            Some(format!("\"{}\"", escape_go_string(s)))
+        }
+        _ => None,
+    };
+    let ty = match data_type {
+        SpecDataTypes::Boolean => "bool",
+        SpecDataTypes::Integer => "int64",
+        SpecDataTypes::Float => "float64",
+        SpecDataTypes::String | SpecDataTypes::Null => "string",
+    };
+    (ty.to_string(), default)
+}
+
+/// The Go type of one value: its choice type, else its declared `type=`. A duration stays
+/// the text the CLI reads (`"1h30m"`), as a path does.
+fn value_go_type(
+    arg: &SpecArg,
+    cmd_name: &str,
+    item_name: &str,
+    choice_types: &ChoiceTypeMap,
+) -> String {
+    if arg.choices.is_some() {
+        if let Some(resolved) = choice_types.lookup(cmd_name, item_name) {
+            return resolved.to_string();
+        }
+    }
+    match arg.value_type {
+        Some(Base::Int) => "int64",
+        Some(Base::Uint) => "uint64",
+        Some(Base::Float) => "float64",
+        Some(Base::Bool) => "bool",
+        _ => "string",
+    }
+    .to_string()
+}
+
+/// `expr`, a value of `arg`'s type, as an argv word.
+fn to_word(arg: &SpecArg, expr: &str) -> String {
+    if arg.choices.is_some() {
+        return format!("string({expr})");
+    }
+    match arg.value_type {
+        Some(Base::Int) => format!("strconv.FormatInt({expr}, 10)"),
+        Some(Base::Uint) => format!("strconv.FormatUint({expr}, 10)"),
+        Some(Base::Float) => format!("strconv.FormatFloat({expr}, 'g', -1, 64)"),
+        Some(Base::Bool) => format!("strconv.FormatBool({expr})"),
+        _ => expr.to_string(),
+    }
+}
+
+fn flag_field_ident(flag: &SpecFlag) -> String {
+    if let Some(long) = flag.long.first() {
+        return go_ident(long);
+    }
+    if let Some(short) = flag.short.first() {
+        return go_ident(&short.to_string());
+    }
+    go_ident(&flag.name)
+}
+
+/// An exported identifier. Keywords are all lowercase, so none can clash with one.
+fn go_ident(name: &str) -> String {
+    let ident = AsPascalCase(name).to_string();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("X{ident}")
+    } else {
+        ident
+    }
+}
+
+const GO_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+/// A package name: lowercase letters and digits only, as Go style has them.
+fn go_package(name: &str) -> String {
+    let mut package: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if package.is_empty() || package.starts_with(|c: char| c.is_ascii_digit()) {
+        package = format!("sdk{package}");
+    }
+    if GO_KEYWORDS.contains(&package.as_str()) {
+        package.push_str("sdk");
+    }
+    package
+}
+
+// ---------------------------------------------------------------------------
+// client.go
+// ---------------------------------------------------------------------------
+
+fn render_client(
+    spec: &Spec,
+    package_name: &str,
+    package: &str,
+    source_file: &Option<String>,
+) -> String {
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
+
+    let mut body = CodeWriter::with_indent("\t");
+    let struct_name = command_type_name(&spec.cmd, package_name);
+    body.line(&format!(
+        "// New returns a client for the binary at binPath, or for {} on PATH when binPath is \"\".",
+        spec.bin
+    ));
+    body.line(&format!("func New(binPath string) *{struct_name} {{"));
+    body.indent();
+    body.line("if binPath == \"\" {");
+    body.indent();
+    body.line(&format!("binPath = \"{}\"", escape_go_string(&spec.bin)));
+    body.dedent();
+    body.line("}");
+    body.line(&format!(
+        "return new{struct_name}(&CliRunner{{BinPath: binPath}})"
+    ));
+    body.dedent();
+    body.line("}");
+    render_command(&spec.cmd, &struct_name, &global_flags, &spec.bin, &mut body);
+    let body = body.finish();
+
+    let mut w = CodeWriter::with_indent("\t");
+    w.line(&generated_header(source_file));
+    w.line("");
+    w.line(&format!("package {package}"));
+    w.line("");
+    if body.contains("strconv.") {
+        w.line("import (");
+        w.indent();
+        w.line("\"context\"");
+        w.line("\"strconv\"");
+        w.dedent();
+        w.line(")");
+    } else {
+        w.line("import \"context\"");
+    }
+    w.line("");
+    let mut out = w.finish();
+    out.push_str(&body);
+    out
+}
+
+fn render_command(
+    cmd: &SpecCommand,
+    struct_name: &str,
+    global_flags: &[&SpecFlag],
+    bin: &str,
+    w: &mut CodeWriter,
+) {
+    let visible_subcmds: Vec<_> = cmd.subcommands.iter().filter(|(_, c)| !c.hide).collect();
+    let visible_args: Vec<&SpecArg> = cmd.args.iter().filter(|a| !a.hide).collect();
+    let has_own_flags = cmd.flags.iter().any(|f| !f.hide);
+    let has_args = !visible_args.is_empty();
+
+    // `Exec` is the method every command has, so a subcommand by that name takes another.
+    let field_name = |name: &str| match go_ident(name) {
+        ident if ident == "Exec" => "ExecCmd".to_string(),
+        ident => ident,
+    };
+
+    // struct, with a field per subcommand
+    let mut doc = vec![];
+    if let Some(help) = &cmd.help {
+        doc.push(help.clone());
+    } else if let Some(about) = &cmd.help_long {
+        doc.push(about.clone());
+    }
+    if let Some(deprecated) = &cmd.deprecated {
+        doc.push(format!("Deprecated: {deprecated}"));
+    }
+    if !cmd.aliases.is_empty() {
+        doc.push(format!("Aliases: {}", cmd.aliases.join(", ")));
+    }
+    let invocation = std::iter::once(bin)
+        .chain(cmd.full_cmd.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    w.line("");
+    // Go doc comments start with the name they document.
+    w.line(&format!("// {struct_name} runs \"{invocation}\"."));
+    for part in &doc {
+        w.line("//");
+        for line in comment_lines("//", part) {
+            w.line(&line);
+        }
+    }
+    w.line(&format!("type {struct_name} struct {{"));
+    w.indent();
+    let mut fields = vec![Field {
+        ident: "runner".to_string(),
+        ty: "*CliRunner".to_string(),
+        doc: vec![],
+    }];
+    for (name, subcmd) in &visible_subcmds {
+        let mut doc = vec![];
+        if let Some(help) = &subcmd.help {
+            doc.push(help.clone());
+        }
+        if let Some(deprecated) = &subcmd.deprecated {
+            doc.push(format!("Deprecated: {deprecated}"));
+        }
+        fields.push(Field {
+            ident: field_name(name),
+            ty: format!("*{}", command_type_name(subcmd, "")),
+            doc,
+        });
+    }
+    let mut taken: Vec<String> = fields.iter().map(|f| f.ident.clone()).collect();
+    taken.push("Exec".to_string());
+    render_fields(fields, w);
+    w.dedent();
+    w.line("}");
+
+    // constructor
+    w.line("");
+    w.line(&format!(
+        "func new{struct_name}(runner *CliRunner) *{struct_name} {{"
+    ));
+    w.indent();
+    if visible_subcmds.is_empty() {
+        w.line(&format!("return &{struct_name}{{runner: runner}}"));
+    } else {
+        w.line(&format!("c := &{struct_name}{{runner: runner}}"));
+        for (name, subcmd) in &visible_subcmds {
+            w.line(&format!(
+                "c.{} = new{}(runner)",
+                field_name(name),
+                command_type_name(subcmd, "")
+            ));
+        }
+        w.line("return c");
+    }
+    w.dedent();
+    w.line("}");
+
+    // alias methods
+    for (name, subcmd) in &visible_subcmds {
+        for alias in &subcmd.aliases {
+            let ident = go_ident(alias);
+            if taken.contains(&ident) {
+                continue;
+            }
+            let target = field_name(name);
+            let sub_struct = command_type_name(subcmd, "");
+            w.line("");
+            w.line(&format!("// {ident} is an alias for {target}."));
+            w.line(&format!(
+                "func (c *{struct_name}) {ident}() *{sub_struct} {{"
+            ));
+            w.indent();
+            w.line(&format!("return c.{target}"));
+            w.dedent();
+            w.line("}");
+            taken.push(ident);
+        }
+    }
+
+    // Exec
+    let flags_type = if has_own_flags {
+        format!("{struct_name}Flags")
+    } else if !global_flags.is_empty() {
+        "GlobalFlags".to_string()
+    } else {
+        String::new()
+    };
+    let has_flags = !flags_type.is_empty();
+    let mut params = vec!["ctx context.Context".to_string()];
+    if has_args {
+        params.push(format!("args {struct_name}Args"));
+    }
+    if has_flags {
+        params.push(format!("flags *{flags_type}"));
+    }
+
+    w.line("");
+    if cmd.usage.is_empty() {
+        w.line("// Exec runs the command.");
+    } else {
+        w.line(&format!("// Exec runs \"{}\".", cmd.usage));
+    }
+    for example in &cmd.examples {
+        let label = example.header.as_deref().unwrap_or("Example");
+        w.line("//");
+        w.line(&format!("// {label}:"));
+        w.line("//");
+        for line in example.code.lines() {
+            w.line(&format!("//\t{line}"));
+        }
+    }
+    w.line(&format!(
+        "func (c *{struct_name}) Exec({}) (*CliResult, error) {{",
+        params.join(", ")
+    ));
+    w.indent();
+    let path = cmd
+        .full_cmd
+        .iter()
+        .map(|s| format!("\"{}\"", escape_go_string(s)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    w.line(&format!("argv := []string{{{path}}}"));
+    // Flags go ahead of the positionals: after a `--` they would be read as positionals
+    // themselves.
+    if has_flags {
+        w.line("argv = append(argv, c.flagArgs(flags)...)");
+    }
+    for arg in visible_args.iter().filter(|a| !after_double_dash(a)) {
+        render_arg_append(arg, w);
+    }
+    if visible_args.iter().any(|a| after_double_dash(a)) {
+        w.line("argv = append(argv, \"--\")");
+        for arg in visible_args.iter().filter(|a| after_double_dash(a)) {
+            render_arg_append(arg, w);
+        }
+    } else if visible_args
+        .iter()
+        .any(|a| matches!(a.double_dash, SpecDoubleDashChoices::Automatic))
+    {
+        w.line("// double_dash=automatic: \"--\" is implied after the first positional arg");
+    }
+    w.line("return c.runner.Run(ctx, argv)");
+    w.dedent();
+    w.line("}");
+
+    if has_flags {
+        w.line("");
+        w.line(&format!(
+            "func (c *{struct_name}) flagArgs(flags *{flags_type}) []string {{"
+        ));
+        w.indent();
+        w.line("var result []string");
+        w.line("if flags == nil {");
+        w.indent();
+        w.line("return result");
+        w.dedent();
+        w.line("}");
+        for flag in command_flags(cmd, global_flags) {
+            render_flag_append(flag, w);
+        }
+        w.line("return result");
+        w.dedent();
+        w.line("}");
+    }
+
+    for (_, subcmd) in &visible_subcmds {
+        let sub_struct = command_type_name(subcmd, "");
+        render_command(subcmd, &sub_struct, global_flags, bin, w);
+    }
+}
+
+fn after_double_dash(arg: &SpecArg) -> bool {
+    matches!(arg.double_dash, SpecDoubleDashChoices::Required)
+}
+
+/// A block of one statement under `open`, an `if` or a `for`.
+fn block(w: &mut CodeWriter, open: &str, body: &str) {
+    w.line(&format!("{open} {{"));
+    w.indent();
+    w.line(body);
+    w.dedent();
+    w.line("}");
+}
+
+fn render_arg_append(arg: &SpecArg, w: &mut CodeWriter) {
+    let ident = go_ident(&arg.name);
+    match FieldShape::of_arg(arg) {
+        FieldShape::One => w.line(&format!(
+            "argv = append(argv, {})",
+            to_word(arg, &format!("args.{ident}"))
+        )),
+        FieldShape::Many if to_word(arg, "v") == "v" => {
+            w.line(&format!("argv = append(argv, args.{ident}...)"))
+        }
+        FieldShape::Many => block(
+            w,
+            &format!("for _, v := range args.{ident}"),
+            &format!("argv = append(argv, {})", to_word(arg, "v")),
+        ),
+        _ => block(
+            w,
+            &format!("if args.{ident} != nil"),
+            &format!(
+                "argv = append(argv, {})",
+                to_word(arg, &format!("*args.{ident}"))
+            ),
+        ),
+    }
+}
+
+fn render_flag_append(flag: &SpecFlag, w: &mut CodeWriter) {
+    let ident = flag_field_ident(flag);
+    let spelling = escape_go_string(&flag_spelling(flag));
+    let push = format!("result = append(result, \"{spelling}\")");
+    let value = |expr: &str| {
+        let word = match &flag.arg {
+            Some(arg) => to_word(arg, expr),
+            None => expr.to_string(),
+        };
+        format!("result = append(result, \"{spelling}\", {word})")
+    };
+    match FieldShape::of_flag(flag) {
+        FieldShape::Switch => block(w, &format!("if flags.{ident}"), &push),
+        FieldShape::Negatable => {
+            let negate = escape_go_string(flag.negate.as_deref().unwrap_or_default());
+            w.line(&format!("if flags.{ident} != nil {{"));
+            w.indent();
+            w.line(&format!("if *flags.{ident} {{"));
+            w.indent();
+            w.line(&push);
+            w.dedent();
+            w.line("} else {");
+            w.indent();
+            w.line(&format!("result = append(result, \"{negate}\")"));
+            w.dedent();
+            w.line("}");
+            w.dedent();
+            w.line("}");
+        }
+        FieldShape::Count => block(w, &format!("for i := 0; i < flags.{ident}; i++"), &push),
+        FieldShape::One => w.line(&value(&format!("flags.{ident}"))),
+        FieldShape::Optional => block(
+            w,
+            &format!("if flags.{ident} != nil"),
+            &value(&format!("*flags.{ident}")),
+        ),
+        FieldShape::Many => block(w, &format!("for _, v := range flags.{ident}"), &value("v")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk::{SdkLanguage, SdkOptions};
+    use crate::test::SPEC_KITCHEN_SINK;
+    use crate::Spec;
+
+    fn make_opts() -> SdkOptions {
+        SdkOptions {
+            language: SdkLanguage::Go,
+            package_name: None,
+            source_file: Some("test.usage.kdl".to_string()),
+        }
+    }
+
+    fn get_file<'a>(output: &'a crate::sdk::SdkOutput, name: &str) -> &'a str {
+        output
+            .files
+            .iter()
+            .find(|f| f.path.to_str() == Some(name))
+            .unwrap_or_else(|| panic!("{name} should exist"))
+            .content
+            .as_str()
+    }
+
+    fn full_feature_spec() -> Spec {
+        r##"
+            bin "mytool"
+            version "1.2.3"
+            about "A powerful CLI tool"
+
+            flag "-v --verbose" help="Verbosity level" count=#true global=#true
+            flag "-C --config <path>" help="Config file path" global=#true env="MYTOOL_CONFIG"
+            flag "--dry-run" help="Show what would be done" negate="--no-dry-run"
+
+            arg "input" help="Input file" required=#true
+            arg "[extra]..." help="Extra files"
+
+            cmd "build" help="Build the project" deprecated="Use compile instead" {
+                alias "b"
+                arg "target" help="Build target" {
+                    choices "debug" "release"
+                }
+                arg "output" help="Output directory" double_dash="required"
+                flag "-j --jobs <n>" help="Parallel jobs" var=#true type="uint"
+                flag "--release" help="Build in release mode"
+                example "mytool build --release debug out" header="Build in release mode" lang="bash"
+            }
+
+            cmd "status" help="Show status"
+
+            config {
+                prop "port" default=8080 data_type=integer help="Port number"
+                prop "host" data_type=string
+            }
+        "##
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_go_types() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.go"));
+    }
+
+    #[test]
+    fn test_go_client() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "client.go"));
+    }
+
+    #[test]
+    fn test_go_runtime() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "runtime.go"));
+    }
+
+    #[test]
+    fn test_go_full_feature_types() {
+        let output = crate::sdk::generate(&full_feature_spec(), &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.go"));
+    }
+
+    #[test]
+    fn test_go_full_feature_client() {
+        let output = crate::sdk::generate(&full_feature_spec(), &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "client.go"));
+    }
+
+    /// A package name is what Go allows of the bin name, and never a keyword.
+    #[test]
+    fn test_go_package_name() {
+        assert_eq!(super::go_package("my-cli"), "mycli");
+        assert_eq!(super::go_package("7z"), "sdk7z");
+        assert_eq!(super::go_package("go"), "gosdk");
+        let spec: Spec = "bin \"my-cli\"".parse().unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        for file in &output.files {
+            assert!(
+                file.content.contains("\npackage mycli\n"),
+                "{}",
+                file.content
+            );
+        }
+    }
+
+    /// A subcommand named `exec` cannot be a field beside the `Exec` method.
+    #[test]
+    fn test_go_exec_subcommand() {
+        let spec: Spec = r#"
+            bin "app"
+            cmd "exec" {
+                alias "x"
+                alias "exec-cmd"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        let client = get_file(&output, "client.go");
+        assert!(client.contains("\tExecCmd *Exec\n"), "{client}");
+        assert!(client.contains("func (c *App) X() *Exec {"), "{client}");
+        assert!(!client.contains("func (c *App) ExecCmd()"), "{client}");
+    }
+}
@@ -0,0 +1,70 @@
+/// Everything after the package clause, which names the caller's package.
+pub const RUNTIME_GO: &str = r#"import (
+	"bytes"
+	"context"
+	"errors"
+	"io/fs"
+	"os/exec"
+)
+
+// CliResult is the result of a CLI invocation.
+type CliResult struct {
+	Stdout   string
+	Stderr   string
+	ExitCode int
+}
+
+// Ok reports whether the CLI exited with status 0.
+func (r *CliResult) Ok() bool {
+	return r.ExitCode == 0
+}
+
+// CliError reports a CLI binary that could not be started.
+type CliError struct {
+	BinPath string
+	Err     error
+}
+
+func (e *CliError) Error() string {
+	if errors.Is(e.Err, exec.ErrNotFound) || errors.Is(e.Err, fs.ErrNotExist) {
+		return "CLI binary not found: " + e.BinPath
+	}
+	return "failed to run " + e.BinPath + ": " + e.Err.Error()
+}
+
+func (e *CliError) Unwrap() error {
+	return e.Err
+}
+
+// CliRunner runs a CLI binary as a subprocess.
+type CliRunner struct {
+	BinPath string
+}
+
+// Run runs the binary with args and waits for it to exit. A non-zero exit is a result,
+// not an error.
+func (r *CliRunner) Run(ctx context.Context, args []string) (*CliResult, error) {
+	var stdout, stderr bytes.Buffer
+	cmd := exec.CommandContext(ctx, r.BinPath, args...)
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	err := cmd.Run()
+	if ctxErr := ctx.Err(); ctxErr != nil {
+		return nil, ctxErr
+	}
+	var exitErr *exec.ExitError
+	if err != nil && !errors.As(err, &exitErr) {
+		return nil, &CliError{BinPath: r.BinPath, Err: err}
+	}
+	return &CliResult{
+		Stdout:   stdout.String(),
+		Stderr:   stderr.String(),
+		ExitCode: cmd.ProcessState.ExitCode(),
+	}, nil
+}
+
+// Ptr returns a pointer to v, for the optional fields of args and flags.
+func Ptr[T any](v T) *T {
+	return &v
+}
+"#;
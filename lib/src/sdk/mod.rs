@@ -5,16 +5,23 @@ use std::path::PathBuf;
 use heck::AsPascalCase;
 use indexmap::IndexMap;
 
+use serde_json::Value;
+
+use crate::error::UsageErr;
 use crate::spec::cmd::SpecCommand;
-use crate::Spec;
+use crate::{Spec, SpecArg, SpecFlag, SpecOutput, SpecOutputFormat};
 
+pub mod go;
 pub mod python;
+pub mod rust;
 pub mod typescript;
 
 #[derive(Debug, Clone)]
 pub enum SdkLanguage {
     TypeScript,
     Python,
+    Go,
+    Rust,
 }
 
 #[derive(Debug, Clone)]
@@ -35,10 +42,65 @@ pub struct SdkFile {
     pub content: String,
 }
 
-pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
-    match opts.language {
+/// The SDK for `spec` in `opts.language`.
+///
+/// Go and Rust declare a type per command and cannot declare one twice, so two commands
+/// whose names come out the same once cased — `my-tool` and `my_tool`, or `sync` under two
+/// parents — are refused, naming both, rather than written as a package that does not build.
+pub fn generate(spec: &Spec, opts: &SdkOptions) -> Result<SdkOutput, UsageErr> {
+    let package_name = opts.package_name.as_deref().unwrap_or(&spec.bin);
+    Ok(match opts.language {
         SdkLanguage::TypeScript => typescript::generate(spec, opts),
         SdkLanguage::Python => python::generate(spec, opts),
+        SdkLanguage::Go => {
+            refuse_type_name_clash("go", spec, package_name)?;
+            go::generate(spec, opts)
+        }
+        SdkLanguage::Rust => {
+            refuse_type_name_clash("rust", spec, package_name)?;
+            rust::generate(spec, opts)
+        }
+    })
+}
+
+/// The first two visible commands that would generate the same type name.
+fn refuse_type_name_clash(
+    generator: &'static str,
+    spec: &Spec,
+    package_name: &str,
+) -> Result<(), UsageErr> {
+    fn walk<'a>(
+        cmd: &'a SpecCommand,
+        package_name: &str,
+        seen: &mut IndexMap<String, &'a SpecCommand>,
+    ) -> Option<(&'a SpecCommand, &'a SpecCommand, String)> {
+        let name = command_type_name(cmd, package_name);
+        if let Some(first) = seen.get(&name) {
+            return Some((first, cmd, name));
+        }
+        seen.insert(name, cmd);
+        cmd.subcommands
+            .values()
+            .filter(|sub| !sub.hide)
+            .find_map(|sub| walk(sub, package_name, seen))
+    }
+    let shown = |cmd: &SpecCommand| {
+        std::iter::once(&spec.bin)
+            .chain(&cmd.full_cmd)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    match walk(&spec.cmd, package_name, &mut IndexMap::new()) {
+        Some((first, second, name)) => Err(UsageErr::UnsupportedFeature {
+            generator,
+            feature: format!(
+                "commands `{}` and `{}`, which would both be the type `{name}`",
+                shown(first),
+                shown(second)
+            ),
+        }),
+        None => Ok(()),
     }
 }
 
@@ -54,13 +116,14 @@ pub(crate) fn escape_py_docstring(s: &str) -> String {
 
 /// Escape a string for a double-quoted literal.
 ///
-/// Backslashes, quotes, and control characters — the last of these because neither language
-/// can carry one literally inside a quoted string, so a value with a newline in it wrote a
-/// module that fails to import rather than one that says something wrong. Help text and
-/// config defaults both really do contain them.
+/// Backslashes, quotes, and control characters — the last of these because no target
+/// language can carry one literally inside a quoted string, so a value with a newline in it
+/// wrote a module that fails to import rather than one that says something wrong. Help text
+/// and config defaults both really do contain them.
 ///
-/// Python and TypeScript spell all of these the same way, so one function serves both.
-fn escape_string_literal(s: &str) -> String {
+/// Every target spells the common escapes the same way; they differ only in how they spell
+/// any other control character, which `control` writes.
+fn escape_string_literal(s: &str, control: fn(char) -> String) -> String {
     let mut out = String::with_capacity(s.len());
     for c in s.chars() {
         match c {
@@ -69,21 +132,51 @@ fn escape_string_literal(s: &str) -> String {
             '\n' => out.push_str(r"\n"),
             '\r' => out.push_str(r"\r"),
             '\t' => out.push_str(r"\t"),
-            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c if c.is_control() => out.push_str(&control(c)),
             c => out.push(c),
         }
     }
     out
 }
 
+/// `\xNN` names a character in Python and TypeScript strings.
+fn hex_escape(c: char) -> String {
+    format!("\\x{:02x}", c as u32)
+}
+
 /// Escape a string for a Python literal.
 pub(crate) fn escape_py_string(s: &str) -> String {
-    escape_string_literal(s)
+    escape_string_literal(s, hex_escape)
 }
 
 /// Escape a string for a TypeScript literal.
 pub(crate) fn escape_ts_string(s: &str) -> String {
-    escape_string_literal(s)
+    escape_string_literal(s, hex_escape)
+}
+
+/// Escape a string for a Go literal. Go's `\xNN` is a byte rather than a character, which
+/// for a C1 control is a stray byte that is not UTF-8; `\uNNNN` is the character.
+pub(crate) fn escape_go_string(s: &str) -> String {
+    escape_string_literal(s, |c| format!("\\u{:04x}", c as u32))
+}
+
+/// Escape a string for a Rust literal, where `\xNN` stops at `\x7f`.
+pub(crate) fn escape_rs_string(s: &str) -> String {
+    escape_string_literal(s, |c| format!("\\u{{{:x}}}", c as u32))
+}
+
+/// Help text as comment lines, one per line of the text, each behind `prefix`.
+pub(crate) fn comment_lines(prefix: &str, text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| {
+            let line = line.trim_end();
+            if line.is_empty() {
+                prefix.to_string()
+            } else {
+                format!("{prefix} {line}")
+            }
+        })
+        .collect()
 }
 
 /// A simple code writer with indentation management.
@@ -158,6 +251,98 @@ pub(crate) fn command_type_name(cmd: &SpecCommand, package_name: &str) -> String
     }
 }
 
+/// A PascalCase name for each choice, for the languages that name choices rather than
+/// spell them: Rust enum variants and Go constants.
+///
+/// Distinct even where two choices differ only in case or punctuation, and never starting
+/// with a digit, so each stands alone as an identifier.
+pub(crate) fn choice_variant_names(choices: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(choices.len());
+    for choice in choices {
+        let mut name = AsPascalCase(choice).to_string();
+        if name.is_empty() {
+            name = "Empty".to_string();
+        } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name = format!("V{name}");
+        }
+        let mut unique = name.clone();
+        let mut n = 2;
+        while names.contains(&unique) {
+            unique = format!("{name}{n}");
+            n += 1;
+        }
+        names.push(unique);
+    }
+    names
+}
+
+/// How a generated field holds an arg or flag, for the languages whose field types say
+/// whether a value may be left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldShape {
+    /// A value that must be given.
+    One,
+    /// A value that may be left out, for the CLI to default.
+    Optional,
+    /// Any number of values, none included.
+    Many,
+    /// A boolean flag: given or not.
+    Switch,
+    /// A boolean flag with a `negate` spelling: given, negated, or neither.
+    Negatable,
+    /// A flag given once per time it is counted.
+    Count,
+}
+
+impl FieldShape {
+    pub fn of_arg(arg: &SpecArg) -> Self {
+        if arg.var {
+            Self::Many
+        } else if arg.required && arg.default.is_empty() {
+            Self::One
+        } else {
+            Self::Optional
+        }
+    }
+
+    /// A repeatable boolean flag counts too: all it can say is how many times it was given.
+    pub fn of_flag(flag: &SpecFlag) -> Self {
+        match &flag.arg {
+            _ if flag.count => Self::Count,
+            None if flag.var => Self::Count,
+            None if flag.negate.is_some() => Self::Negatable,
+            None => Self::Switch,
+            Some(_) if flag.var => Self::Many,
+            Some(_) if flag.required && flag.default.is_empty() => Self::One,
+            Some(_) => Self::Optional,
+        }
+    }
+}
+
+/// A command's flags as its generated flags type holds them: the root's global flags first,
+/// then its own that are not among them.
+pub(crate) fn command_flags<'a>(
+    cmd: &'a SpecCommand,
+    global_flags: &[&'a SpecFlag],
+) -> Vec<&'a SpecFlag> {
+    let own = cmd
+        .flags
+        .iter()
+        .filter(|f| !f.hide && !global_flags.iter().any(|gf| gf.name == f.name));
+    global_flags.iter().copied().chain(own).collect()
+}
+
+/// The word a flag is passed as: its first long name, else its first short one.
+pub(crate) fn flag_spelling(flag: &SpecFlag) -> String {
+    if let Some(long) = flag.long.first() {
+        format!("--{long}")
+    } else if let Some(short) = flag.short.first() {
+        format!("-{short}")
+    } else {
+        format!("--{}", flag.name)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Choice type collection with collision detection
 // ---------------------------------------------------------------------------
@@ -351,6 +536,338 @@ fn collect_type_imports_recursive(
     }
 }
 
+// ---------------------------------------------------------------------------
+// Exit status errors
+// ---------------------------------------------------------------------------
+
+/// The typed error for each non-zero exit status a spec declares, for the SDKs that raise
+/// one when a command exits with it.
+///
+/// One class per distinct status, shared by every command that exits with it: a status a
+/// subcommand inherits is the same error its parent raises, so one `catch` covers both.
+pub(crate) struct ExitErrorMap {
+    /// The classes, in the order the spec first declares them.
+    pub classes: Vec<ExitErrorClass>,
+    /// `full_cmd` -> (code, class name), for each command that exits with any.
+    by_cmd: IndexMap<Vec<String>, Vec<(u8, String)>>,
+}
+
+pub(crate) struct ExitErrorClass {
+    pub name: String,
+    pub code: u8,
+    pub help: Option<String>,
+    status_name: Option<String>,
+}
+
+impl ExitErrorMap {
+    /// The statuses `cmd` raises an error for, ordered by code.
+    pub fn for_cmd(&self, cmd: &SpecCommand) -> &[(u8, String)] {
+        self.by_cmd
+            .get(&cmd.full_cmd)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+}
+
+/// Names the runtime module already exports, which a generated error must not shadow.
+const RUNTIME_ERROR_NAMES: [&str; 2] = ["CliError", "CliExitError"];
+
+/// Collects the error classes for every visible command's exit statuses, its inherited
+/// ones included. Exit 0 is success whatever it is documented as, so it raises nothing.
+pub(crate) fn collect_exit_errors(spec: &Spec) -> ExitErrorMap {
+    fn walk(spec: &Spec, cmd: &SpecCommand, map: &mut ExitErrorMap) {
+        if cmd.hide {
+            return;
+        }
+        let mut raised = Vec::new();
+        for exit in spec.exits_for(cmd).iter().filter(|exit| exit.code != 0) {
+            let existing = map.classes.iter().find(|class| {
+                class.code == exit.code && class.help == exit.help && class.status_name == exit.name
+            });
+            let name = match existing {
+                Some(class) => class.name.clone(),
+                None => {
+                    let name = exit_class_name(exit, &map.classes);
+                    map.classes.push(ExitErrorClass {
+                        name: name.clone(),
+                        code: exit.code,
+                        help: exit.help.clone(),
+                        status_name: exit.name.clone(),
+                    });
+                    name
+                }
+            };
+            raised.push((exit.code, name));
+        }
+        if !raised.is_empty() {
+            map.by_cmd.insert(cmd.full_cmd.clone(), raised);
+        }
+        for subcmd in cmd.subcommands.values() {
+            walk(spec, subcmd, map);
+        }
+    }
+
+    let mut map = ExitErrorMap {
+        classes: Vec::new(),
+        by_cmd: IndexMap::new(),
+    };
+    walk(spec, &spec.cmd, &mut map);
+    map
+}
+
+/// `LockfileOutOfDateError` for `exit 3 "lockfile out of date"`: the status's name, else
+/// its help, else its code. A name another status already has gets the code added, and a
+/// number after that if even that is taken.
+fn exit_class_name(exit: &crate::SpecExit, taken: &[ExitErrorClass]) -> String {
+    let base = exit
+        .name
+        .as_deref()
+        .or(exit.help.as_deref())
+        .map(|s| AsPascalCase(s).to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| format!("Exit{}", exit.code));
+    let base = if base.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Exit{base}")
+    } else {
+        base
+    };
+    let base = base.strip_suffix("Error").unwrap_or(&base).to_string();
+    let is_taken = |name: &str| {
+        RUNTIME_ERROR_NAMES.contains(&name) || taken.iter().any(|class| class.name == name)
+    };
+    let name = format!("{base}Error");
+    if !is_taken(&name) {
+        return name;
+    }
+    let mut name = format!("{base}Exit{}Error", exit.code);
+    let mut n = 2;
+    while is_taken(&name) {
+        name = format!("{base}Exit{}Error{n}", exit.code);
+        n += 1;
+    }
+    name
+}
+
+// ---------------------------------------------------------------------------
+// Output types
+// ---------------------------------------------------------------------------
+
+/// A JSON Schema, read as far as a generated type can say it. What only a schema can say —
+/// a pattern, a minimum — stays with the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonShape {
+    Any,
+    Null,
+    Bool,
+    Integer,
+    Number,
+    String,
+    /// `enum` or `const`: the values themselves, which both TypeScript and Python spell as
+    /// literal types.
+    Literals(Vec<Value>),
+    Array(Box<JsonShape>),
+    Object(Vec<JsonField>),
+    /// An object with no declared properties, whose values all have one shape.
+    Map(Box<JsonShape>),
+    /// A named schema, by the type generated for it.
+    Ref(String),
+    Union(Vec<JsonShape>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JsonField {
+    pub name: String,
+    pub required: bool,
+    pub help: Option<String>,
+    pub shape: JsonShape,
+}
+
+impl JsonShape {
+    pub fn read(schema: &Value) -> Self {
+        // `true`, the schema anything conforms to, and anything unreadable are both `Any`.
+        let Value::Object(map) = schema else {
+            return Self::Any;
+        };
+        if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+            return match reference.strip_prefix("#/$defs/") {
+                Some(name) => Self::Ref(schema_type_name(name)),
+                None => Self::Any,
+            };
+        }
+        if let Some(value) = map.get("const") {
+            return Self::Literals(vec![value.clone()]);
+        }
+        if let Some(Value::Array(values)) = map.get("enum") {
+            return Self::Literals(values.clone());
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(options)) = map.get(key) {
+                return Self::union(options.iter().map(Self::read).collect());
+            }
+        }
+        match map.get("type") {
+            Some(Value::String(ty)) => Self::of_type(ty, map),
+            Some(Value::Array(types)) => Self::union(
+                types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|ty| Self::of_type(ty, map))
+                    .collect(),
+            ),
+            _ if map.contains_key("properties") => Self::of_type("object", map),
+            _ => Self::Any,
+        }
+    }
+
+    fn of_type(ty: &str, map: &serde_json::Map<String, Value>) -> Self {
+        match ty {
+            "null" => Self::Null,
+            "boolean" => Self::Bool,
+            "integer" => Self::Integer,
+            "number" => Self::Number,
+            "string" => Self::String,
+            "array" => Self::Array(Box::new(map.get("items").map_or(Self::Any, Self::read))),
+            "object" => {
+                let required: Vec<&str> = map
+                    .get("required")
+                    .and_then(Value::as_array)
+                    .map(|names| names.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                match map.get("properties") {
+                    Some(Value::Object(props)) if !props.is_empty() => Self::Object(
+                        props
+                            .iter()
+                            .map(|(name, schema)| JsonField {
+                                name: name.clone(),
+                                required: required.contains(&name.as_str()),
+                                help: schema
+                                    .get("description")
+                                    .and_then(Value::as_str)
+                                    .map(str::to_string),
+                                shape: Self::read(schema),
+                            })
+                            .collect(),
+                    ),
+                    _ => Self::Map(Box::new(match map.get("additionalProperties") {
+                        Some(values @ Value::Object(_)) => Self::read(values),
+                        _ => Self::Any,
+                    })),
+                }
+            }
+            _ => Self::Any,
+        }
+    }
+
+    fn union(shapes: Vec<Self>) -> Self {
+        if shapes.contains(&Self::Any) || shapes.is_empty() {
+            return Self::Any;
+        }
+        match <[Self; 1]>::try_from(shapes) {
+            Ok([only]) => only,
+            Err(shapes) => Self::Union(shapes),
+        }
+    }
+}
+
+/// The type generated for a top-level `schema`.
+pub(crate) fn schema_type_name(name: &str) -> String {
+    AsPascalCase(name).to_string()
+}
+
+/// The type a structured output parses into: its named schema's, else one named for the
+/// command. `None` for an output that declares no schema, whose methods return whatever
+/// the JSON holds.
+pub(crate) fn output_type_name(
+    cmd: &SpecCommand,
+    output: &SpecOutput,
+    package_name: &str,
+) -> Option<String> {
+    if let Some(name) = &output.type_name {
+        return Some(schema_type_name(name));
+    }
+    output.schema.as_ref()?;
+    let suffix = match output.format {
+        SpecOutputFormat::Jsonl => "OutputLine",
+        _ => "Output",
+    };
+    Some(format!("{}{suffix}", command_type_name(cmd, package_name)))
+}
+
+/// Every output type a spec generates, in order: its named schemas, then each visible
+/// command's inline ones.
+pub(crate) fn collect_output_types(spec: &Spec, package_name: &str) -> Vec<(String, JsonShape)> {
+    fn walk(cmd: &SpecCommand, package_name: &str, types: &mut Vec<(String, JsonShape)>) {
+        if cmd.hide {
+            return;
+        }
+        for output in &cmd.outputs {
+            if let (Some(schema), Some(name)) =
+                (&output.schema, output_type_name(cmd, output, package_name))
+            {
+                types.push((name, JsonShape::read(schema)));
+            }
+        }
+        for subcmd in cmd.subcommands.values() {
+            walk(subcmd, package_name, types);
+        }
+    }
+    let mut types: Vec<(String, JsonShape)> = spec
+        .schemas
+        .values()
+        .map(|named| {
+            (
+                schema_type_name(&named.name),
+                JsonShape::read(&named.schema),
+            )
+        })
+        .collect();
+    walk(&spec.cmd, package_name, &mut types);
+    types
+}
+
+/// A JSON output an SDK generates a method for, and what that method sets to ask for it.
+pub(crate) struct TypedOutput<'a> {
+    pub output: &'a SpecOutput,
+    /// The flag that switches to it, from the command's flags type, with the value it is
+    /// set to: `None` for a switch, which is set to `true`. No flag for the default output.
+    pub flag: Option<(&'a SpecFlag, Option<&'a str>)>,
+    pub type_name: Option<String>,
+}
+
+/// The structured outputs of `cmd` an SDK can ask for. One switched by a flag its flags
+/// type does not hold — a hidden one — has no method, since nothing could set it.
+pub(crate) fn typed_outputs<'a>(
+    cmd: &'a SpecCommand,
+    global_flags: &[&'a SpecFlag],
+    package_name: &str,
+) -> Vec<TypedOutput<'a>> {
+    let flags = command_flags(cmd, global_flags);
+    cmd.outputs
+        .iter()
+        .filter(|output| output.is_structured())
+        .filter_map(|output| {
+            let flag = match output.switch() {
+                None => None,
+                Some((name, value)) => {
+                    let flag = flags
+                        .iter()
+                        .find(|flag| crate::spec::flag_matches_selector(flag, name))?;
+                    Some((*flag, value))
+                }
+            };
+            Some(TypedOutput {
+                output,
+                flag,
+                type_name: output_type_name(cmd, output, package_name),
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,6 +882,53 @@ mod tests {
         assert!(displayed.contains("world"));
     }
 
+    #[test]
+    fn commands_that_would_share_a_type_are_refused_by_go_and_rust() {
+        let spec: Spec = r#"
+name "ex"
+bin "ex"
+cmd "remote" { cmd "sync" }
+cmd "my-tool"
+cmd "my_tool"
+        "#
+        .parse()
+        .unwrap();
+        let opts = |language| SdkOptions {
+            language,
+            package_name: None,
+            source_file: None,
+        };
+        let err = generate(&spec, &opts(SdkLanguage::Rust)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rust cannot express commands `ex my-tool` and `ex my_tool`, which would both be the type `MyTool`"
+        );
+        assert!(generate(&spec, &opts(SdkLanguage::Go)).is_err());
+        assert!(generate(&spec, &opts(SdkLanguage::TypeScript)).is_ok());
+
+        let cousins: Spec = r#"
+name "ex"
+bin "ex"
+cmd "local" { cmd "sync" }
+cmd "remote" { cmd "sync" }
+        "#
+        .parse()
+        .unwrap();
+        let err = generate(&cousins, &opts(SdkLanguage::Go)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "go cannot express commands `ex local sync` and `ex remote sync`, which would both be the type `Sync`"
+        );
+
+        // The root is named for the package.
+        let root: Spec = "name \"ex\"\nbin \"ex\"\ncmd \"ex\"\n".parse().unwrap();
+        let err = generate(&root, &opts(SdkLanguage::Rust)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "rust cannot express commands `ex` and `ex ex`, which would both be the type `Ex`"
+        );
+    }
+
     #[test]
     fn test_command_type_name_empty() {
         let cmd = SpecCommand::default();
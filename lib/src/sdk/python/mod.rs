@@ -3,14 +3,18 @@ use std::path::PathBuf;
 use heck::AsPascalCase;
 
 use crate::sdk::{
-    collect_choice_types, collect_type_imports, command_type_name, escape_py_docstring,
-    escape_py_string, generated_header, ChoiceTypeMap, CodeWriter, SdkFile, SdkOptions, SdkOutput,
+    collect_choice_types, collect_exit_errors, collect_output_types, collect_type_imports,
+    command_type_name, escape_py_docstring, escape_py_string, generated_header, typed_outputs,
+    ChoiceTypeMap, CodeWriter, ExitErrorMap, JsonShape, SdkFile, SdkOptions, SdkOutput,
+    TypedOutput,
 };
 use crate::spec::arg::SpecDoubleDashChoices;
 use crate::spec::cmd::SpecCommand;
 use crate::spec::config::{SpecConfigProp, SpecConfigValue};
+use crate::spec::config_type::Base;
 use crate::spec::data_types::SpecDataTypes;
-use crate::{Spec, SpecArg, SpecFlag};
+use crate::typed::{self, TypedValue};
+use crate::{Spec, SpecArg, SpecFlag, SpecOutputFormat};
 
 fn sanitize_py_comment(text: &str) -> String {
     text.replace(['\n', '\r'], " ")
@@ -23,8 +27,9 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
         .package_name
         .clone()
         .unwrap_or_else(|| spec.bin.clone());
+    let exits = collect_exit_errors(spec);
 
-    SdkOutput {
+    let mut output = SdkOutput {
         files: vec![
             SdkFile {
                 path: PathBuf::from("types.py"),
@@ -32,7 +37,7 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
             },
             SdkFile {
                 path: PathBuf::from("client.py"),
-                content: render_client(spec, &package_name, &opts.source_file),
+                content: render_client(spec, &package_name, &exits, &opts.source_file),
             },
             SdkFile {
                 path: PathBuf::from("runtime.py"),
@@ -40,15 +45,68 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
             },
             SdkFile {
                 path: PathBuf::from("__init__.py"),
-                content: render_init(&package_name),
+                content: render_init(&package_name, !exits.is_empty()),
             },
         ],
-    }
+    };
+    // Only for a spec that declares a status to raise, as in the TypeScript client.
+    if !exits.is_empty() {
+        output.files.push(SdkFile {
+            path: PathBuf::from("errors.py"),
+            content: render_errors(&exits, &opts.source_file),
+        });
+    }
+    output
 }
 
-fn render_init(package_name: &str) -> String {
+fn render_init(package_name: &str, has_errors: bool) -> String {
     let class_name = AsPascalCase(package_name).to_string();
-    format!("from .client import {class_name}\nfrom .runtime import CliResult, CliRunner\nfrom .types import *\n")
+    let mut init = format!("from .client import {class_name}\nfrom .runtime import CliExitError, CliResult, CliRunner\nfrom .types import *\n");
+    if has_errors {
+        init.push_str("from .errors import *\n");
+    }
+    init
+}
+
+// ---------------------------------------------------------------------------
+// errors.py
+// ---------------------------------------------------------------------------
+
+fn render_errors(exits: &ExitErrorMap, source_file: &Option<String>) -> String {
+    let mut w = CodeWriter::with_indent("    ");
+
+    w.line(&generated_header("#", source_file));
+    w.line("from __future__ import annotations");
+    w.line("from .runtime import CliExitError, CliResult");
+
+    for class in &exits.classes {
+        let message = match &class.help {
+            Some(help) => help.clone(),
+            None => format!("exited with status {}", class.code),
+        };
+        w.line("");
+        w.line("");
+        w.line(&format!("class {}(CliExitError):", class.name));
+        w.indent();
+        w.line(&format!(
+            "\"\"\"Exit status {}: {}\"\"\"",
+            class.code,
+            escape_py_docstring(&message)
+        ));
+        w.line("");
+        w.line(&format!("exit_code = {}", class.code));
+        w.line("");
+        w.line("def __init__(self, result: CliResult) -> None:");
+        w.indent();
+        w.line(&format!(
+            "super().__init__(result, \"{}\")",
+            escape_py_string(&message)
+        ));
+        w.dedent();
+        w.dedent();
+    }
+
+    w.finish()
 }
 
 // ---------------------------------------------------------------------------
@@ -61,7 +119,17 @@ fn render_types(spec: &Spec, package_name: &str, source_file: &Option<String>) -
     w.line(&generated_header("#", source_file));
     w.line("from __future__ import annotations");
     w.line("from dataclasses import dataclass");
-    w.line("from typing import Literal, Optional");
+    let output_types = collect_output_types(spec, package_name);
+    if output_types.is_empty() {
+        w.line("from typing import Literal, Optional");
+    } else {
+        w.line("from typing import TYPE_CHECKING, Any, Literal, Optional, TypedDict, Union");
+        // Annotations are never evaluated at runtime here, so `NotRequired` need only exist
+        // for a type checker: the runtime's Python may predate it.
+        w.line("");
+        w.line("if TYPE_CHECKING:");
+        w.line("    from typing import NotRequired");
+    }
     w.line("");
 
     // spec metadata
@@ -165,9 +233,114 @@ fn render_types(spec: &Spec, package_name: &str, source_file: &Option<String>) -
         w.dedent();
     }
 
+    render_output_types(&output_types, &mut w);
+
     w.finish()
 }
 
+/// Each output type: a `TypedDict` for an object, an alias for anything else. The classes
+/// come first, since an alias is evaluated where it stands and may name one.
+fn render_output_types(types: &[(String, JsonShape)], w: &mut CodeWriter) {
+    let (classes, aliases): (Vec<_>, Vec<_>) = types.iter().partition(|(_, shape)| {
+        matches!(shape, JsonShape::Object(fields) if fields.iter().all(|f| is_py_field_name(&f.name)))
+    });
+    for (name, shape) in classes {
+        let JsonShape::Object(fields) = shape else {
+            continue;
+        };
+        w.line("");
+        w.line("");
+        w.line(&format!("class {name}(TypedDict):"));
+        w.indent();
+        for field in fields {
+            if let Some(help) = &field.help {
+                w.line(&format!("# {}", sanitize_py_comment(help)));
+            }
+            let py_type = shape_py_type(&field.shape);
+            if field.required {
+                w.line(&format!("{}: {py_type}", field.name));
+            } else {
+                w.line(&format!("{}: NotRequired[{py_type}]", field.name));
+            }
+        }
+        w.dedent();
+    }
+    if !aliases.is_empty() {
+        w.line("");
+    }
+    for (name, shape) in aliases {
+        w.line("");
+        w.line(&format!("{name} = {}", shape_py_type(shape)));
+    }
+}
+
+/// The Python type for a value of `shape`. Only a schema's own top level gets a class: a
+/// nested object is a `dict`.
+fn shape_py_type(shape: &JsonShape) -> String {
+    match shape {
+        JsonShape::Any => "Any".to_string(),
+        JsonShape::Null => "None".to_string(),
+        JsonShape::Bool => "bool".to_string(),
+        JsonShape::Integer => "int".to_string(),
+        JsonShape::Number => "float".to_string(),
+        JsonShape::String => "str".to_string(),
+        JsonShape::Literals(values) => {
+            let literals: Option<Vec<String>> = values
+                .iter()
+                .map(|value| match value {
+                    serde_json::Value::String(s) => Some(format!("\"{}\"", escape_py_string(s))),
+                    serde_json::Value::Bool(true) => Some("True".to_string()),
+                    serde_json::Value::Bool(false) => Some("False".to_string()),
+                    serde_json::Value::Null => Some("None".to_string()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+                .collect();
+            match literals {
+                Some(literals) => format!("Literal[{}]", literals.join(", ")),
+                None => "Any".to_string(),
+            }
+        }
+        JsonShape::Array(items) => format!("list[{}]", shape_py_type(items)),
+        JsonShape::Object(_) => "dict[str, Any]".to_string(),
+        JsonShape::Map(values) => format!("dict[str, {}]", shape_py_type(values)),
+        JsonShape::Ref(name) => name.clone(),
+        JsonShape::Union(shapes) => {
+            let types: Vec<String> = shapes
+                .iter()
+                .filter(|shape| **shape != JsonShape::Null)
+                .map(shape_py_type)
+                .collect();
+            let union = match types.as_slice() {
+                [only] => only.clone(),
+                _ => format!("Union[{}]", types.join(", ")),
+            };
+            if shapes.contains(&JsonShape::Null) {
+                format!("Optional[{union}]")
+            } else {
+                union
+            }
+        }
+    }
+}
+
+/// Whether a JSON property can be a field of a `TypedDict` class. One that cannot — a
+/// hyphenated or keyword name — makes its object a plain `dict`.
+fn is_py_field_name(name: &str) -> bool {
+    const KEYWORDS: [&str; 35] = [
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "try", "while", "with", "yield",
+    ];
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&name)
+}
+
 fn render_command_types(
     cmd: &SpecCommand,
     package_name: &str,
@@ -263,10 +436,10 @@ fn render_args_dataclass(
                 // has default value
                 let default_val = &arg.default[0];
                 format!(
-                    "{}: Optional[{}] = \"{}\"",
+                    "{}: Optional[{}] = {}",
                     sanitize_py_ident(&arg.name),
                     py_type,
-                    escape_py_string(default_val)
+                    py_literal(arg, default_val)
                 )
             } else if !is_required_no_default {
                 // optional without explicit default
@@ -351,10 +524,11 @@ fn render_flags_dataclass(
                         sanitize_py_comment(default_val)
                     )
                 } else {
-                    format!(
-                        "{prop_name}: Optional[{py_type}] = \"{}\"",
-                        escape_py_string(default_val)
-                    )
+                    let literal = match &flag.arg {
+                        Some(arg) => py_literal(arg, default_val),
+                        None => format!("\"{}\"", escape_py_string(default_val)),
+                    };
+                    format!("{prop_name}: Optional[{py_type}] = {literal}")
                 }
             } else if optional {
                 format!("{prop_name}: Optional[{py_type}] = None")
@@ -402,7 +576,7 @@ fn arg_py_type(arg: &SpecArg, cmd_name: &str, choice_types: &ChoiceTypeMap) -> S
             format!("Literal[{union}]")
         }
     } else {
-        "str".to_string()
+        value_py_type(arg).to_string()
     };
 
     if arg.var {
@@ -412,6 +586,42 @@ fn arg_py_type(arg: &SpecArg, cmd_name: &str, choice_types: &ChoiceTypeMap) -> S
     }
 }
 
+/// The Python type of one value, from its declared `type=`. A duration stays the text
+/// the CLI reads (`"1h30m"`), as a path does.
+fn value_py_type(arg: &SpecArg) -> &'static str {
+    match arg.value_type {
+        Some(Base::Int | Base::Uint) => "int",
+        Some(Base::Float) => "float",
+        Some(Base::Bool) => "bool",
+        _ => "str",
+    }
+}
+
+/// A default as a literal of its declared type.
+///
+/// Written bare only once the parser's own reading of it succeeded, for the reason the
+/// config dataclass gives: text from the spec must never reach generated code as an
+/// expression. Anything else is a quoted string, which is what an untyped default is.
+fn py_literal(arg: &SpecArg, raw: &str) -> String {
+    match arg.value_type.as_ref().map(|ty| typed::coerce(ty, raw)) {
+        Some(Ok(TypedValue::Bool(b))) => if b { "True" } else { "False" }.to_string(),
+        Some(Ok(TypedValue::Int(i))) => i.to_string(),
+        Some(Ok(TypedValue::Uint(u))) => u.to_string(),
+        // `inf` and `nan` are not Python literals.
+        Some(Ok(TypedValue::Float(f))) if f.is_finite() => format!("{f:?}"),
+        _ => format!("\"{}\"", escape_py_string(raw)),
+    }
+}
+
+/// A variadic arg's values as argv words, through `str` when they are typed.
+fn extend_args(arg: &SpecArg, ident: &str) -> String {
+    if value_py_type(arg) == "str" {
+        format!("args.{ident}")
+    } else {
+        format!("str(v) for v in args.{ident}")
+    }
+}
+
 fn flag_py_type(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -> String {
     if flag.count {
         return "int".to_string();
@@ -432,7 +642,7 @@ fn flag_py_type(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -
                     format!("Literal[{union}]")
                 }
             } else {
-                "str".to_string()
+                value_py_type(arg).to_string()
             };
 
             if flag.var {
@@ -481,7 +691,9 @@ fn sanitize_py_ident(name: &str) -> String {
         | "is" | "as" | "break" | "continue" | "assert" | "type" | "input" | "id" | "list"
         | "dict" | "set" | "print" | "range" | "format" | "help" | "vars" | "dir" | "exec"
         | "exit" | "quit" | "bool" | "int" | "str" | "float" | "bytes" | "object" | "super"
-        | "property" | "static" | "true" | "false" | "none" => format!("_{snake}"),
+        | "property" | "static" | "true" | "false" | "none" | "exec_json" | "exec_jsonl" => {
+            format!("_{snake}")
+        }
         _ => snake,
     }
 }
@@ -490,13 +702,37 @@ fn sanitize_py_ident(name: &str) -> String {
 // client.py
 // ---------------------------------------------------------------------------
 
-fn render_client(spec: &Spec, package_name: &str, source_file: &Option<String>) -> String {
+fn render_client(
+    spec: &Spec,
+    package_name: &str,
+    exits: &ExitErrorMap,
+    source_file: &Option<String>,
+) -> String {
     let mut w = CodeWriter::with_indent("    ");
 
     w.line(&generated_header("#", source_file));
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
+    let outputs = collect_typed_outputs(&spec.cmd, &global_flags, package_name);
+
     w.line("from __future__ import annotations");
-    w.line("from typing import Optional");
+    if outputs.iter().any(|typed| typed.flag.is_some()) {
+        w.line("from dataclasses import replace");
+    }
+    if outputs.iter().any(|typed| typed.type_name.is_none()) {
+        w.line("from typing import Any, Optional");
+    } else {
+        w.line("from typing import Optional");
+    }
     w.line("from .runtime import CliResult, CliRunner");
+    if !exits.is_empty() {
+        let names: Vec<&str> = exits.classes.iter().map(|c| c.name.as_str()).collect();
+        w.line(&format!("from .errors import {}", names.join(", ")));
+    }
 
     // collect imports from types
     let choice_types = collect_choice_types(&spec.cmd);
@@ -506,6 +742,7 @@ fn render_client(spec: &Spec, package_name: &str, source_file: &Option<String>)
     if has_global_flags {
         all_imports.push("GlobalFlags".to_string());
     }
+    all_imports.extend(outputs.into_iter().filter_map(|typed| typed.type_name));
     all_imports.sort();
     all_imports.dedup();
     if !all_imports.is_empty() {
@@ -514,13 +751,6 @@ fn render_client(spec: &Spec, package_name: &str, source_file: &Option<String>)
 
     w.line("");
 
-    let global_flags: Vec<&SpecFlag> = spec
-        .cmd
-        .flags
-        .iter()
-        .filter(|f| f.global && !f.hide)
-        .collect();
-
     let class_name = AsPascalCase(package_name).to_string();
     render_class(
         &spec.cmd,
@@ -528,6 +758,7 @@ fn render_client(spec: &Spec, package_name: &str, source_file: &Option<String>)
         true,
         &global_flags,
         &spec.bin,
+        exits,
         &mut w,
     );
 
@@ -540,6 +771,7 @@ fn render_class(
     is_root: bool,
     global_flags: &[&SpecFlag],
     bin_name: &str,
+    exits: &ExitErrorMap,
     w: &mut CodeWriter,
 ) {
     let visible_subcmds: Vec<_> = cmd.subcommands.iter().filter(|(_, c)| !c.hide).collect();
@@ -624,6 +856,9 @@ fn render_class(
         let label = example.header.as_deref().unwrap_or("Example");
         exec_doc.push(format!("{label}: {code}", code = example.code));
     }
+    for (code, name) in exits.for_cmd(cmd) {
+        exec_doc.push(format!("Raises {name} on exit status {code}."));
+    }
 
     w.line("");
     if !exec_doc.is_empty() {
@@ -670,7 +905,8 @@ fn render_class(
             let ident = sanitize_py_ident(&arg.name);
             if arg.var {
                 w.line(&format!(
-                    "if args.{ident} is not None: cmd_args.extend(args.{ident})"
+                    "if args.{ident} is not None: cmd_args.extend({})",
+                    extend_args(arg, &ident)
                 ));
             } else {
                 w.line(&format!(
@@ -689,7 +925,8 @@ fn render_class(
                 let ident = sanitize_py_ident(&arg.name);
                 if arg.var {
                     w.line(&format!(
-                        "if args.{ident} is not None: cmd_args.extend(args.{ident})"
+                        "if args.{ident} is not None: cmd_args.extend({})",
+                        extend_args(arg, &ident)
                     ));
                 } else {
                     w.line(&format!(
@@ -704,9 +941,15 @@ fn render_class(
 
     if has_flags {
         w.line("flag_args = self._build_flag_args(flags)");
-        w.line("return self._runner.run(cmd_args + flag_args)");
+        w.line(&format!(
+            "return self._runner.run(cmd_args + flag_args{})",
+            exits_table(exits, cmd)
+        ));
     } else {
-        w.line("return self._runner.run(cmd_args)");
+        w.line(&format!(
+            "return self._runner.run(cmd_args{})",
+            exits_table(exits, cmd)
+        ));
     }
 
     w.dedent();
@@ -735,6 +978,11 @@ fn render_class(
         w.dedent();
     }
 
+    for typed in typed_outputs(cmd, global_flags, class_name) {
+        w.line("");
+        render_typed_exec(&typed, class_name, has_args, &flags_type, w);
+    }
+
     // alias properties for subcommand aliases
     for (name, subcmd) in &visible_subcmds {
         for alias in &subcmd.aliases {
@@ -760,8 +1008,96 @@ fn render_class(
     for (name, subcmd) in &visible_subcmds {
         w.line("");
         let sub_class = AsPascalCase(name).to_string();
-        render_class(subcmd, &sub_class, false, global_flags, bin_name, w);
+        render_class(subcmd, &sub_class, false, global_flags, bin_name, exits, w);
+    }
+}
+
+/// `exec_json` or `exec_jsonl`: `exec` with the flag that switches to the output set, and
+/// what it prints parsed. A run that fails raises rather than parsing its error output.
+fn render_typed_exec(
+    typed: &TypedOutput,
+    class_name: &str,
+    has_args: bool,
+    flags_type: &str,
+    w: &mut CodeWriter,
+) {
+    let (method, format) = match typed.output.format {
+        SpecOutputFormat::Jsonl => ("exec_jsonl", "jsonl"),
+        _ => ("exec_json", "json"),
+    };
+    let value_type = typed.type_name.as_deref().unwrap_or("Any");
+    let returns = match typed.output.format {
+        SpecOutputFormat::Jsonl => format!("list[{value_type}]"),
+        _ => value_type.to_string(),
+    };
+    let mut params = vec!["self".to_string()];
+    let mut call = Vec::new();
+    if has_args {
+        params.push(format!("args: {class_name}Args"));
+        call.push("args");
+    }
+    if !flags_type.is_empty() {
+        params.push(format!("flags: Optional[{flags_type}] = None"));
+        call.push("flags");
     }
+    w.line(&format!(
+        "def {method}({}) -> {returns}:",
+        params.join(", ")
+    ));
+    w.indent();
+    let mut doc = match &typed.output.flag {
+        Some(flag) => format!("Runs with {flag} and parses what it prints"),
+        None => "Parses what this command prints".to_string(),
+    };
+    if let Some(help) = &typed.output.help {
+        doc.push_str(&format!(": {help}"));
+    }
+    w.line(&format!("\"\"\"{}\"\"\"", escape_py_docstring(&doc)));
+    if let Some((flag, value)) = typed.flag {
+        let prop = flag_property_name_py(flag);
+        let value = match value {
+            Some(value) => format!("\"{}\"", escape_py_string(value)),
+            None => "True".to_string(),
+        };
+        w.line(&format!(
+            "flags = replace(flags, {prop}={value}) if flags is not None else {flags_type}({prop}={value})"
+        ));
+    }
+    w.line(&format!(
+        "return self._runner.parse(self.exec({}), \"{format}\")",
+        call.join(", ")
+    ));
+    w.dedent();
+}
+
+/// Every visible command's typed outputs, for the client's imports.
+fn collect_typed_outputs<'a>(
+    cmd: &'a SpecCommand,
+    global_flags: &[&'a SpecFlag],
+    package_name: &str,
+) -> Vec<TypedOutput<'a>> {
+    if cmd.hide {
+        return vec![];
+    }
+    let mut outputs = typed_outputs(cmd, global_flags, package_name);
+    for subcmd in cmd.subcommands.values() {
+        outputs.extend(collect_typed_outputs(subcmd, global_flags, package_name));
+    }
+    outputs
+}
+
+/// The second argument to `run` for a command that declares exit statuses: each code and
+/// the error it raises. Empty for one that declares none.
+fn exits_table(exits: &ExitErrorMap, cmd: &SpecCommand) -> String {
+    let raised = exits.for_cmd(cmd);
+    if raised.is_empty() {
+        return String::new();
+    }
+    let entries: Vec<String> = raised
+        .iter()
+        .map(|(code, name)| format!("{code}: {name}"))
+        .collect();
+    format!(", {{{}}}", entries.join(", "))
 }
 
 fn render_flag_build_py(flag: &SpecFlag, w: &mut CodeWriter) {
@@ -842,25 +1178,25 @@ mod tests {
 
     #[test]
     fn test_python_types() {
-        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "types.py"));
     }
 
     #[test]
     fn test_python_client() {
-        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.py"));
     }
 
     #[test]
     fn test_python_runtime() {
-        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "runtime.py"));
     }
 
     #[test]
     fn test_python_init() {
-        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "__init__.py"));
     }
 
@@ -905,14 +1241,14 @@ mod tests {
     #[test]
     fn test_python_full_feature_types() {
         let spec = full_feature_spec();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "types.py"));
     }
 
     #[test]
     fn test_python_full_feature_client() {
         let spec = full_feature_spec();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.py"));
     }
 
@@ -930,7 +1266,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.py"));
     }
 
@@ -941,7 +1277,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.py"));
     }
 
@@ -957,7 +1293,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(!client.contains("def exec(self, ,"));
         assert!(
@@ -966,6 +1302,37 @@ mod tests {
         insta::assert_snapshot!(client);
     }
 
+    /// Declared types become Python's, with defaults as literals only when they read as the type.
+    #[test]
+    fn test_python_typed_values() {
+        let spec: Spec = r##"
+            bin "srv"
+            flag "--port <port>" type="uint" default="8080"
+            flag "--ratio <r>" type="float" default="inf"
+            flag "--verify <v>" type="bool" default="T"
+            arg "[jobs]..." type="int"
+        "##
+        .parse()
+        .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        let types = get_file(&output, "types.py");
+        assert!(types.contains("port: Optional[int] = 8080"), "{types}");
+        assert!(
+            types.contains("ratio: Optional[float] = \"inf\""),
+            "{types}"
+        );
+        assert!(types.contains("verify: Optional[bool] = True"), "{types}");
+        assert!(
+            types.contains("jobs: Optional[list[int]] = None"),
+            "{types}"
+        );
+        let client = get_file(&output, "client.py");
+        assert!(
+            client.contains("cmd_args.extend(str(v) for v in args.jobs)"),
+            "{client}"
+        );
+    }
+
     /// Choice type collision: same arg name with different choices in different subcommands.
     #[test]
     fn test_python_choice_collision() {
@@ -984,7 +1351,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         // Must have separate choice types due to collision
         assert!(types.contains("BuildEnvChoice"));
@@ -1004,7 +1371,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains(r#"mode: Optional[str] = "fast""#));
         assert!(types.contains("output: str"));
@@ -1032,7 +1399,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("class MyappConfig"));
         insta::assert_snapshot!(types);
@@ -1058,7 +1425,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
 
         assert!(
@@ -1101,7 +1468,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("VisibleArgs"));
         assert!(!types.contains("SecretArgs"));
@@ -1122,7 +1489,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         insta::assert_snapshot!(types);
         let client = get_file(&output, "client.py");
@@ -1134,6 +1501,63 @@ mod tests {
     }
 
     /// double_dash=automatic, examples in exec doc, global flags with flags-only subcommand.
+    #[test]
+    fn test_python_exit_errors() {
+        let spec: Spec = r##"
+            bin "pkg"
+            exit 1 "something failed"
+            cmd "install" {
+                exit 3 "lockfile out of date"
+                exit 4 "network unreachable" name="offline"
+                arg "<name>"
+            }
+            cmd "check" {
+                exit 0 "clean"
+            }
+        "##
+        .parse()
+        .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "errors.py"));
+        insta::assert_snapshot!(get_file(&output, "client.py"));
+        assert!(get_file(&output, "__init__.py").contains("from .errors import *"));
+    }
+
+    #[test]
+    fn test_python_typed_outputs() {
+        let spec: Spec = r###"
+            bin "pkg"
+            flag "--format <format>" global=#true
+            schema "Package" ##"""
+            {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "The package name"},
+                    "version": {"type": ["string", "null"]},
+                    "kind": {"enum": ["lib", "bin"]}
+                },
+                "required": ["name"]
+            }
+            """##
+            schema "Meta" ##"{"properties": {"class": {"type": "integer"}, "created-at": {"type": "string"}}}"##
+            cmd "list" {
+                flag "--json"
+                output "text"
+                output "json" flag="--json" help="every package" schema=##"{"type": "array", "items": {"$ref": "#/$defs/Package"}}"##
+                output "jsonl" flag="--format jsonl" type="Package"
+            }
+            cmd "show" {
+                arg "<name>"
+                output "json"
+            }
+        "###
+        .parse()
+        .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.py"));
+        insta::assert_snapshot!(get_file(&output, "client.py"));
+    }
+
     #[test]
     fn test_python_exec_edge_cases() {
         let spec: Spec = r##"
@@ -1149,7 +1573,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains("double_dash=automatic"));
         assert!(client.contains("Basic run: runner run hello"));
@@ -1171,7 +1595,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         // optional arg without default should have = None
         assert!(types.contains("name: Optional[str] = None"));
@@ -1197,7 +1621,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         // deeply nested class must exist
         assert!(client.contains("class Db:"));
@@ -1219,7 +1643,7 @@ mod tests {
             package_name: Some("my_custom_sdk".to_string()),
             source_file: None,
         };
-        let output = crate::sdk::generate(&spec, &opts);
+        let output = crate::sdk::generate(&spec, &opts).unwrap();
         let init = get_file(&output, "__init__.py");
         assert!(init.contains("MyCustomSdk"));
         insta::assert_snapshot!(init);
@@ -1238,7 +1662,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         // "info" subcommand has no own flags, only global flags => GlobalFlags type
         assert!(client.contains("Optional[GlobalFlags]"));
@@ -1256,7 +1680,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("Literal[\"bash\", \"zsh\", \"fish\"]"));
         insta::assert_snapshot!(types);
@@ -1271,7 +1695,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("Env: APP_CONFIG"));
         insta::assert_snapshot!(types);
@@ -1287,7 +1711,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("verbose"));
         assert!(!types.contains("debug"));
@@ -1302,7 +1726,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains("--dry-run"));
         assert!(client.contains("--no-dry-run"));
@@ -1318,7 +1742,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains(r#""--verbose""#));
         assert!(client.contains("flags.verbose"));
@@ -1334,7 +1758,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains(r#"list[str]"#));
         assert!(types.contains(r#"default: latest"#));
@@ -1352,7 +1776,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("Aliases: fmt"));
         let client = get_file(&output, "client.py");
@@ -1370,7 +1794,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("no_cache: bool = False"));
         insta::assert_snapshot!(types);
@@ -1388,7 +1812,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("verbose: bool = False"));
         assert!(types.contains("dry_run: bool = True"));
@@ -1407,7 +1831,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains(r#"host: str = "localhost""#));
         assert!(types.contains(r#"name: str = "myapp""#));
@@ -1426,7 +1850,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("class AppConfig:"));
         insta::assert_snapshot!(types);
@@ -1441,7 +1865,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("list[str]"));
         let client = get_file(&output, "client.py");
@@ -1462,7 +1886,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains("app greet hello"));
         insta::assert_snapshot!(client);
@@ -1477,7 +1901,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         // required flag without default should NOT be Optional
         assert!(types.contains("token: str"));
@@ -1498,7 +1922,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         // GlobalFlags should have list[bool] and list[str] types
         assert!(types.contains("list[bool]"));
@@ -1523,7 +1947,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains("double_dash=automatic"));
         assert!(client.contains("Basic run: runner run hello"));
@@ -1552,7 +1976,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.py");
         assert!(types.contains("MyappConfig"));
         insta::assert_snapshot!(types);
@@ -1573,7 +1997,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = crate::sdk::generate(&spec, &make_opts());
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.py");
         assert!(client.contains("double_dash=automatic"));
         insta::assert_snapshot!(client);
@@ -1,7 +1,9 @@
 pub const RUNTIME_PY: &str = r#"# Runtime module for usage-generated SDK clients. Do not edit manually.
 from __future__ import annotations
 
+import json
 import subprocess
+from typing import Any, Optional
 
 
 class CliResult:
@@ -17,20 +19,44 @@ class CliResult:
         return self.exit_code == 0
 
 
+class CliExitError(Exception):
+    """A non-zero exit status the spec declares. Clients raise a subclass for each."""
+
+    def __init__(self, result: CliResult, message: str) -> None:
+        super().__init__(message)
+        self.result = result
+        self.exit_code = result.exit_code
+
+
 class CliRunner:
     """Runs a CLI binary via subprocess."""
 
     def __init__(self, bin_path: str) -> None:
         self.bin_path = bin_path
 
-    def run(self, args: list[str]) -> CliResult:
+    def run(
+        self, args: list[str], exits: Optional[dict[int, type[CliExitError]]] = None
+    ) -> CliResult:
         try:
-            result = subprocess.run(
+            completed = subprocess.run(
                 [self.bin_path, *args],
                 capture_output=True,
                 text=True,
             )
-            return CliResult(result.stdout, result.stderr, result.returncode)
         except FileNotFoundError:
             raise RuntimeError(f"CLI binary not found: {self.bin_path}")
+        result = CliResult(completed.stdout, completed.stderr, completed.returncode)
+        error = (exits or {}).get(result.exit_code)
+        if error is not None:
+            raise error(result)
+        return result
+
+    def parse(self, result: CliResult, format: str) -> Any:
+        """What result printed, parsed: one JSON document, or for "jsonl" a list of one per
+        line. A run that failed raises rather than parsing whatever it printed instead."""
+        if not result.ok:
+            raise CliExitError(result, f"exited with status {result.exit_code}")
+        if format == "json":
+            return json.loads(result.stdout)
+        return [json.loads(line) for line in result.stdout.splitlines() if line.strip()]
 "#;
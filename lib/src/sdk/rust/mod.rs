@@ -0,0 +1,1036 @@
+use std::path::PathBuf;
+
+use heck::{AsPascalCase, AsSnakeCase};
+
+use crate::sdk::{
+    choice_variant_names, collect_choice_types, collect_type_imports, command_flags,
+    command_type_name, comment_lines, escape_rs_string, flag_spelling, generated_header,
+    ChoiceTypeMap, CodeWriter, FieldShape, SdkFile, SdkOptions, SdkOutput,
+};
+use crate::spec::arg::SpecDoubleDashChoices;
+use crate::spec::cmd::SpecCommand;
+use crate::spec::config::{SpecConfigProp, SpecConfigValue};
+use crate::spec::config_type::Base;
+use crate::spec::data_types::SpecDataTypes;
+use crate::{Spec, SpecArg, SpecFlag};
+
+mod runtime;
+
+/// Generates a module rather than a crate: the directory is dropped into the caller's own
+/// crate and declared with `mod`, so it brings no manifest and depends on nothing but `std`.
+pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
+    let package_name = opts
+        .package_name
+        .clone()
+        .unwrap_or_else(|| spec.bin.clone());
+
+    SdkOutput {
+        files: vec![
+            SdkFile {
+                path: PathBuf::from("types.rs"),
+                content: render_types(spec, &package_name, &opts.source_file),
+            },
+            SdkFile {
+                path: PathBuf::from("client.rs"),
+                content: render_client(spec, &package_name, &opts.source_file),
+            },
+            SdkFile {
+                path: PathBuf::from("runtime.rs"),
+                content: runtime::RUNTIME_RS.to_string(),
+            },
+            SdkFile {
+                path: PathBuf::from("mod.rs"),
+                content: render_mod(&opts.source_file),
+            },
+        ],
+    }
+}
+
+fn render_mod(source_file: &Option<String>) -> String {
+    format!(
+        "{}\nmod client;\nmod runtime;\nmod types;\n\npub use client::*;\npub use runtime::{{CliError, CliResult, CliRunner}};\npub use types::*;\n",
+        generated_header("//", source_file)
+    )
+}
+
+// ---------------------------------------------------------------------------
+// types.rs
+// ---------------------------------------------------------------------------
+
+fn render_types(spec: &Spec, package_name: &str, source_file: &Option<String>) -> String {
+    let mut w = CodeWriter::with_indent("    ");
+
+    w.line(&generated_header("//", source_file));
+
+    // spec metadata
+    let metadata = [
+        ("VERSION", &spec.version),
+        ("ABOUT", &spec.about),
+        ("AUTHOR", &spec.author),
+    ];
+    if metadata.iter().any(|(_, value)| value.is_some()) {
+        w.line("");
+    }
+    for (name, value) in metadata {
+        if let Some(value) = value {
+            w.line(&format!(
+                "pub const {name}: &str = \"{}\";",
+                escape_rs_string(value)
+            ));
+        }
+    }
+
+    let choice_types = collect_choice_types(&spec.cmd);
+    for (name, choices) in choice_types.iter() {
+        w.line("");
+        render_choice_enum(name, choices, &mut w);
+    }
+
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
+    if !global_flags.is_empty() {
+        let fields = global_flags
+            .iter()
+            .map(|flag| flag_field(flag, &spec.cmd.name, &choice_types))
+            .collect();
+        w.line("");
+        w.line("/// Global flags available on all subcommands.");
+        render_struct("GlobalFlags", fields, &mut w);
+    }
+
+    render_command_types(
+        &spec.cmd,
+        package_name,
+        &spec.bin,
+        &spec.cmd.name,
+        &choice_types,
+        &global_flags,
+        &mut w,
+    );
+
+    if !spec.config.props.is_empty() {
+        w.line("");
+        render_config(spec, package_name, &mut w);
+    }
+
+    w.finish()
+}
+
+fn render_choice_enum(name: &str, choices: &[String], w: &mut CodeWriter) {
+    // `Self` is the one PascalCase word that cannot name a variant.
+    let variants: Vec<String> = choice_variant_names(choices)
+        .into_iter()
+        .map(|v| if v == "Self" { "Self_".to_string() } else { v })
+        .collect();
+    w.line("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]");
+    w.line(&format!("pub enum {name} {{"));
+    w.indent();
+    for variant in &variants {
+        w.line(&format!("{variant},"));
+    }
+    w.dedent();
+    w.line("}");
+    w.line("");
+    w.line(&format!("impl {name} {{"));
+    w.indent();
+    w.line("/// The value as the CLI reads it.");
+    w.line("pub fn as_str(&self) -> &'static str {");
+    w.indent();
+    w.line("match self {");
+    w.indent();
+    for (variant, choice) in variants.iter().zip(choices) {
+        w.line(&format!(
+            "Self::{variant} => \"{}\",",
+            escape_rs_string(choice)
+        ));
+    }
+    w.dedent();
+    w.line("}");
+    w.dedent();
+    w.line("}");
+    w.dedent();
+    w.line("}");
+    w.line("");
+    w.line(&format!("impl std::fmt::Display for {name} {{"));
+    w.indent();
+    w.line("fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {");
+    w.indent();
+    w.line("f.write_str(self.as_str())");
+    w.dedent();
+    w.line("}");
+    w.dedent();
+    w.line("}");
+}
+
+fn render_command_types(
+    cmd: &SpecCommand,
+    package_name: &str,
+    bin: &str,
+    root_cmd_name: &str,
+    choice_types: &ChoiceTypeMap,
+    global_flags: &[&SpecFlag],
+    w: &mut CodeWriter,
+) {
+    if cmd.hide {
+        return;
+    }
+
+    // The struct of a command named `self` is `Self_`, but its args are `SelfArgs`.
+    let name = command_type_name(cmd, package_name);
+    let invocation = std::iter::once(bin)
+        .chain(cmd.full_cmd.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let visible_args: Vec<&SpecArg> = cmd.args.iter().filter(|a| !a.hide).collect();
+
+    if !visible_args.is_empty() {
+        let fields = visible_args
+            .iter()
+            .map(|arg| arg_field(arg, &cmd.name, choice_types))
+            .collect();
+        w.line("");
+        w.line(&format!("/// Arguments to `{invocation}`."));
+        render_struct(&format!("{name}Args"), fields, w);
+    }
+
+    if cmd.flags.iter().any(|f| !f.hide) {
+        let fields = command_flags(cmd, global_flags)
+            .into_iter()
+            .map(|flag| {
+                let lookup_cmd = if flag.global {
+                    root_cmd_name
+                } else {
+                    &cmd.name
+                };
+                flag_field(flag, lookup_cmd, choice_types)
+            })
+            .collect();
+        w.line("");
+        w.line(&format!("/// Flags to `{invocation}`."));
+        render_struct(&format!("{name}Flags"), fields, w);
+    }
+
+    for subcmd in cmd.subcommands.values() {
+        render_command_types(
+            subcmd,
+            package_name,
+            bin,
+            root_cmd_name,
+            choice_types,
+            global_flags,
+            w,
+        );
+    }
+}
+
+/// A field of an args or flags struct.
+struct Field {
+    ident: String,
+    /// The type of one value.
+    ty: String,
+    shape: FieldShape,
+    doc: Vec<String>,
+}
+
+impl Field {
+    fn field_type(&self) -> String {
+        match self.shape {
+            FieldShape::One => self.ty.clone(),
+            FieldShape::Optional => format!("Option<{}>", self.ty),
+            FieldShape::Many => format!("Vec<{}>", self.ty),
+            FieldShape::Switch => "bool".to_string(),
+            FieldShape::Negatable => "Option<bool>".to_string(),
+            FieldShape::Count => "u32".to_string(),
+        }
+    }
+
+    /// A string parameter takes anything that becomes one, so a caller can pass `&str`.
+    fn param_type(&self) -> String {
+        let value = if self.ty == "String" {
+            "impl Into<String>".to_string()
+        } else {
+            self.ty.clone()
+        };
+        match self.shape {
+            FieldShape::One | FieldShape::Optional => value,
+            FieldShape::Many => format!("impl IntoIterator<Item = {value}>"),
+            FieldShape::Switch | FieldShape::Negatable => "bool".to_string(),
+            FieldShape::Count => "u32".to_string(),
+        }
+    }
+
+    fn param_value(&self) -> String {
+        let ident = &self.ident;
+        let into = if self.ty == "String" { ".into()" } else { "" };
+        match self.shape {
+            FieldShape::One => format!("{ident}{into}"),
+            FieldShape::Optional | FieldShape::Negatable => format!("Some({ident}{into})"),
+            FieldShape::Many if self.ty == "String" => {
+                format!("{ident}.into_iter().map(Into::into).collect()")
+            }
+            FieldShape::Many => format!("{ident}.into_iter().collect()"),
+            FieldShape::Switch | FieldShape::Count => ident.clone(),
+        }
+    }
+}
+
+fn arg_field(arg: &SpecArg, cmd_name: &str, choice_types: &ChoiceTypeMap) -> Field {
+    let mut doc = vec![];
+    if let Some(help) = &arg.help {
+        doc.push(help.clone());
+    }
+    if !arg.default.is_empty() {
+        doc.push(format!("Defaults to `{}`.", arg.default.join(" ")));
+    }
+    Field {
+        ident: rs_ident(&arg.name),
+        ty: value_rs_type(arg, cmd_name, &arg.name, choice_types),
+        shape: FieldShape::of_arg(arg),
+        doc,
+    }
+}
+
+fn flag_field(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -> Field {
+    let mut doc = vec![];
+    if let Some(help) = &flag.help {
+        doc.push(help.clone());
+    }
+    if !flag.default.is_empty() {
+        doc.push(format!("Defaults to `{}`.", flag.default.join(" ")));
+    }
+    if let Some(env) = &flag.env {
+        doc.push(format!("Environment variable: `{env}`."));
+    }
+    if let Some(deprecated) = &flag.deprecated {
+        doc.push(format!("Deprecated: {deprecated}"));
+    }
+    let ty = match &flag.arg {
+        Some(arg) => value_rs_type(arg, cmd_name, &flag.name, choice_types),
+        None => "bool".to_string(),
+    };
+    Field {
+        ident: flag_field_ident(flag),
+        ty,
+        shape: FieldShape::of_flag(flag),
+        doc,
+    }
+}
+
+/// A struct of fields, with a constructor taking those that must be given and a setter for
+/// each of the rest. `Default` too when none must be.
+fn render_struct(name: &str, fields: Vec<Field>, w: &mut CodeWriter) {
+    let required: Vec<&Field> = fields
+        .iter()
+        .filter(|f| f.shape == FieldShape::One)
+        .collect();
+    if required.is_empty() {
+        w.line("#[derive(Debug, Clone, Default)]");
+    } else {
+        w.line("#[derive(Debug, Clone)]");
+    }
+    w.line(&format!("pub struct {name} {{"));
+    w.indent();
+    for field in &fields {
+        render_doc(&field.doc, w);
+        w.line(&format!("pub {}: {},", field.ident, field.field_type()));
+    }
+    w.dedent();
+    w.line("}");
+    w.line("");
+    w.line(&format!("impl {name} {{"));
+    w.indent();
+    let params = required
+        .iter()
+        .map(|f| format!("{}: {}", f.ident, f.param_type()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    w.line(&format!("pub fn new({params}) -> Self {{"));
+    w.indent();
+    if required.is_empty() {
+        w.line("Self::default()");
+    } else {
+        w.line("Self {");
+        w.indent();
+        for field in &fields {
+            let value = match field.shape {
+                FieldShape::One => field.param_value(),
+                FieldShape::Optional | FieldShape::Negatable => "None".to_string(),
+                FieldShape::Many => "Vec::new()".to_string(),
+                FieldShape::Switch => "false".to_string(),
+                FieldShape::Count => "0".to_string(),
+            };
+            if value == field.ident {
+                w.line(&format!("{value},"));
+            } else {
+                w.line(&format!("{}: {value},", field.ident));
+            }
+        }
+        w.dedent();
+        w.line("}");
+    }
+    w.dedent();
+    w.line("}");
+    for field in fields.iter().filter(|f| f.shape != FieldShape::One) {
+        let ident = &field.ident;
+        w.line("");
+        render_doc(&field.doc, w);
+        w.line(&format!(
+            "pub fn {ident}(mut self, {ident}: {}) -> Self {{",
+            field.param_type()
+        ));
+        w.indent();
+        w.line(&format!("self.{ident} = {};", field.param_value()));
+        w.line("self");
+        w.dedent();
+        w.line("}");
+    }
+    w.dedent();
+    w.line("}");
+}
+
+fn render_doc(doc: &[String], w: &mut CodeWriter) {
+    for (i, part) in doc.iter().enumerate() {
+        if i > 0 {
+            w.line("///");
+        }
+        for line in comment_lines("///", part) {
+            w.line(&line);
+        }
+    }
+}
+
+fn render_config(spec: &Spec, package_name: &str, w: &mut CodeWriter) {
+    let config_name = format!("{}Config", AsPascalCase(package_name));
+    let props: Vec<(String, String, Option<String>, &SpecConfigProp)> = spec
+        .config
+        .props
+        .iter()
+        .map(|(name, prop)| {
+            let (ty, default) = config_prop_type(prop);
+            (rs_ident(name), ty, default, prop)
+        })
+        .collect();
+
+    w.line(&format!(
+        "/// Settings `{}` reads from its config.",
+        escape_rs_string(&spec.bin)
+    ));
+    w.line("#[derive(Debug, Clone, PartialEq)]");
+    w.line(&format!("pub struct {config_name} {{"));
+    w.indent();
+    for (ident, ty, default, prop) in &props {
+        if let Some(help) = &prop.help {
+            for line in comment_lines("///", help) {
+                w.line(&line);
+            }
+        }
+        if default.is_some() {
+            w.line(&format!("pub {ident}: {ty},"));
+        } else {
+            w.line(&format!("pub {ident}: Option<{ty}>,"));
+        }
+    }
+    w.dedent();
+    w.line("}");
+    w.line("");
+    w.line(&format!("impl Default for {config_name} {{"));
+    w.indent();
+    w.line("fn default() -> Self {");
+    w.indent();
+    w.line("Self {");
+    w.indent();
+    for (ident, _, default, _) in &props {
+        let value = default.as_deref().unwrap_or("None");
+        w.line(&format!("{ident}: {value},"));
+    }
+    w.dedent();
+    w.line("}");
+    w.dedent();
+    w.line("}");
+    w.dedent();
+    w.line("}");
+}
+
+/// The Rust type of a config prop, and its default as a literal of that type.
+///
+/// Typed by the declaration, or by the default when there is none. A default the declared
+/// type cannot hold is left out — the field is then optional, as for a prop with no
+/// default — rather than written as something that fails to compile.
+fn config_prop_type(prop: &SpecConfigProp) -> (String, Option<String>) {
+    let data_type = match (&prop.data_type, &prop.default) {
+        (SpecDataTypes::Null, Some(SpecConfigValue::Bool(_))) => &SpecDataTypes::Boolean,
+        (SpecDataTypes::Null, Some(SpecConfigValue::Int(_))) => &SpecDataTypes::Integer,
+        (SpecDataTypes::Null, Some(SpecConfigValue::Float(_))) => &SpecDataTypes::Float,
+        (SpecDataTypes::Null, Some(SpecConfigValue::String(_))) => &SpecDataTypes::String,
+        (data_type, _) => data_type,
+    };
+    let default = match (data_type, &prop.default) {
+        (SpecDataTypes::Boolean, Some(SpecConfigValue::Bool(b))) => Some(b.to_string()),
+        (SpecDataTypes::Integer, Some(SpecConfigValue::Int(i))) => Some(i.to_string()),
+        (SpecDataTypes::Float, Some(SpecConfigValue::Int(i))) => Some(format!("{i}.0")),
+        (SpecDataTypes::Float, Some(SpecConfigValue::Float(f))) if f.is_finite() => {
+            Some(format!("{f:?}"))
+        }
+        (SpecDataTypes::String, Some(SpecConfigValue::String(s))) => {
+            Some(format!("\"{}\".to_string()", escape_rs_string(s)))
+        }
+        _ => None,
+    };
+    let ty = match data_type {
+        SpecDataTypes::Boolean => "bool",
+        SpecDataTypes::Integer => "i64",
+        SpecDataTypes::Float => "f64",
+        SpecDataTypes::String | SpecDataTypes::Null => "String",
+    };
+    (ty.to_string(), default)
+}
+
+/// The Rust type of one value: its choice enum, else its declared `type=`. A duration stays
+/// the text the CLI reads (`"1h30m"`), as a path does.
+fn value_rs_type(
+    arg: &SpecArg,
+    cmd_name: &str,
+    item_name: &str,
+    choice_types: &ChoiceTypeMap,
+) -> String {
+    if arg.choices.is_some() {
+        if let Some(resolved) = choice_types.lookup(cmd_name, item_name) {
+            return resolved.to_string();
+        }
+    }
+    match arg.value_type {
+        Some(Base::Int) => "i64",
+        Some(Base::Uint) => "u64",
+        Some(Base::Float) => "f64",
+        Some(Base::Bool) => "bool",
+        _ => "String",
+    }
+    .to_string()
+}
+
+fn flag_field_ident(flag: &SpecFlag) -> String {
+    if let Some(long) = flag.long.first() {
+        return rs_ident(long);
+    }
+    if let Some(short) = flag.short.first() {
+        return rs_ident(&short.to_string());
+    }
+    rs_ident(&flag.name)
+}
+
+/// A command's type name, where `Self` cannot be one.
+fn rs_type_name(cmd: &SpecCommand, package_name: &str) -> String {
+    match command_type_name(cmd, package_name) {
+        name if name == "Self" => "Self_".to_string(),
+        name => name,
+    }
+}
+
+/// Rust's keywords, strict and reserved. Shared with the scaffold, whose identifiers have
+/// the same problem.
+pub(crate) const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// The names the generated structs take for methods of their own.
+const METHODS: &[&str] = &["exec", "flag_args", "new", "with_bin"];
+
+/// A snake_case identifier. A keyword takes a trailing underscore rather than `r#`, which
+/// cannot spell `self` or `crate`.
+fn rs_ident(name: &str) -> String {
+    let mut ident = AsSnakeCase(name).to_string();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident = format!("_{ident}");
+    }
+    if ident == "_" || KEYWORDS.contains(&ident.as_str()) || METHODS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+// ---------------------------------------------------------------------------
+// client.rs
+// ---------------------------------------------------------------------------
+
+fn render_client(spec: &Spec, package_name: &str, source_file: &Option<String>) -> String {
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
+
+    let mut body = CodeWriter::with_indent("    ");
+    let struct_name = rs_type_name(&spec.cmd, package_name);
+    render_command(
+        &spec.cmd,
+        &struct_name,
+        true,
+        &global_flags,
+        &spec.bin,
+        &mut body,
+    );
+    let body = body.finish();
+
+    // Only the types a signature names: a choice type reaches the client inside a struct.
+    let choice_types = collect_choice_types(&spec.cmd);
+    let mut imports = collect_type_imports(&spec.cmd, package_name, &choice_types);
+    imports.push("GlobalFlags".to_string());
+    imports.retain(|name| {
+        body.match_indices(&format!("&{name}")).any(|(at, found)| {
+            !body[at + found.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+        })
+    });
+    imports.sort();
+    imports.dedup();
+
+    let mut w = CodeWriter::with_indent("    ");
+    w.line(&generated_header("//", source_file));
+    w.line("use super::runtime::{CliError, CliResult, CliRunner};");
+    match imports.as_slice() {
+        [] => {}
+        [one] => w.line(&format!("use super::types::{one};")),
+        many => w.line(&format!("use super::types::{{{}}};", many.join(", "))),
+    }
+    w.line("");
+    let mut out = w.finish();
+    out.push_str(&body);
+    out
+}
+
+fn render_command(
+    cmd: &SpecCommand,
+    struct_name: &str,
+    is_root: bool,
+    global_flags: &[&SpecFlag],
+    bin_name: &str,
+    w: &mut CodeWriter,
+) {
+    let visible_subcmds: Vec<_> = cmd.subcommands.iter().filter(|(_, c)| !c.hide).collect();
+    let visible_args: Vec<&SpecArg> = cmd.args.iter().filter(|a| !a.hide).collect();
+    let has_own_flags = cmd.flags.iter().any(|f| !f.hide);
+    let has_args = !visible_args.is_empty();
+
+    // doc on struct
+    let mut struct_doc = Vec::new();
+    if let Some(help) = &cmd.help {
+        struct_doc.push(help.clone());
+    } else if let Some(about) = &cmd.help_long {
+        struct_doc.push(about.clone());
+    }
+    if !cmd.aliases.is_empty() {
+        struct_doc.push(format!("Aliases: {}", cmd.aliases.join(", ")));
+    }
+    render_doc(&struct_doc, w);
+    w.line("#[derive(Debug, Clone)]");
+    w.line(&format!("pub struct {struct_name} {{"));
+    w.indent();
+    w.line("runner: CliRunner,");
+    w.dedent();
+    w.line("}");
+    w.line("");
+
+    w.line(&format!("impl {struct_name} {{"));
+    w.indent();
+
+    // constructors
+    if is_root {
+        w.line(&format!(
+            "/// Runs `{}` as found on `PATH`.",
+            escape_rs_string(bin_name)
+        ));
+        w.line("pub fn new() -> Self {");
+        w.indent();
+        w.line(&format!(
+            "Self::with_bin(\"{}\")",
+            escape_rs_string(bin_name)
+        ));
+        w.dedent();
+        w.line("}");
+        w.line("");
+        w.line("/// Runs the binary at `bin_path`.");
+        w.line("pub fn with_bin(bin_path: impl Into<std::path::PathBuf>) -> Self {");
+        w.indent();
+        w.line("Self {");
+        w.indent();
+        w.line("runner: CliRunner::new(bin_path),");
+        w.dedent();
+        w.line("}");
+        w.dedent();
+        w.line("}");
+    }
+
+    // subcommand accessors
+    let mut methods: Vec<String> = vec![];
+    for (name, subcmd) in &visible_subcmds {
+        let sub_struct = rs_type_name(subcmd, "");
+        let ident = rs_ident(name);
+        if is_root || !methods.is_empty() {
+            w.line("");
+        }
+        if let Some(help) = &subcmd.help {
+            for line in comment_lines("///", help) {
+                w.line(&line);
+            }
+        }
+        if let Some(deprecated) = &subcmd.deprecated {
+            w.line(&format!(
+                "#[deprecated(note = \"{}\")]",
+                escape_rs_string(deprecated)
+            ));
+        }
+        w.line(&format!("pub fn {ident}(&self) -> {sub_struct} {{"));
+        w.indent();
+        w.line(&format!("{sub_struct} {{"));
+        w.indent();
+        w.line("runner: self.runner.clone(),");
+        w.dedent();
+        w.line("}");
+        w.dedent();
+        w.line("}");
+        methods.push(ident);
+    }
+
+    // alias accessors, built directly so that a deprecated command's alias does not warn
+    // inside this file
+    for (name, subcmd) in &visible_subcmds {
+        let sub_struct = rs_type_name(subcmd, "");
+        for alias in &subcmd.aliases {
+            let ident = rs_ident(alias);
+            if methods.contains(&ident) {
+                continue;
+            }
+            w.line("");
+            w.line(&format!("/// Alias for [`Self::{}`].", rs_ident(name)));
+            w.line(&format!("pub fn {ident}(&self) -> {sub_struct} {{"));
+            w.indent();
+            w.line(&format!("{sub_struct} {{"));
+            w.indent();
+            w.line("runner: self.runner.clone(),");
+            w.dedent();
+            w.line("}");
+            w.dedent();
+            w.line("}");
+            methods.push(ident);
+        }
+    }
+
+    // exec
+    let type_prefix = if is_root {
+        struct_name.to_string()
+    } else {
+        command_type_name(cmd, "")
+    };
+    let flags_type = if has_own_flags {
+        format!("{type_prefix}Flags")
+    } else if !global_flags.is_empty() {
+        "GlobalFlags".to_string()
+    } else {
+        String::new()
+    };
+    let has_flags = !flags_type.is_empty();
+    let sig = if has_args && has_flags {
+        format!("pub fn exec(&self, args: &{type_prefix}Args, flags: &{flags_type}) -> Result<CliResult, CliError> {{")
+    } else if has_args {
+        format!("pub fn exec(&self, args: &{type_prefix}Args) -> Result<CliResult, CliError> {{")
+    } else if has_flags {
+        format!("pub fn exec(&self, flags: &{flags_type}) -> Result<CliResult, CliError> {{")
+    } else {
+        "pub fn exec(&self) -> Result<CliResult, CliError> {".to_string()
+    };
+
+    if is_root || !methods.is_empty() {
+        w.line("");
+    }
+    let mut exec_doc = vec![];
+    if !cmd.usage.is_empty() {
+        exec_doc.push(format!("`{}`", cmd.usage));
+    }
+    for example in &cmd.examples {
+        let label = example.header.as_deref().unwrap_or("Example");
+        let lang = if example.lang.is_empty() {
+            "text"
+        } else {
+            &example.lang
+        };
+        exec_doc.push(format!("{label}:\n\n```{lang}\n{}\n```", example.code));
+    }
+    render_doc(&exec_doc, w);
+    w.line(&sig);
+    w.indent();
+
+    let path = cmd
+        .full_cmd
+        .iter()
+        .map(|s| format!("\"{}\".to_string()", escape_rs_string(s)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !has_args && !has_flags {
+        w.line(&format!("self.runner.run(&[{path}])"));
+    } else {
+        if path.is_empty() {
+            w.line("let mut argv: Vec<String> = Vec::new();");
+        } else {
+            w.line(&format!("let mut argv: Vec<String> = vec![{path}];"));
+        }
+        // Flags go ahead of the positionals: after a `--` they would be read as positionals
+        // themselves.
+        if has_flags {
+            w.line("Self::flag_args(flags, &mut argv);");
+        }
+        for arg in visible_args.iter().filter(|a| !after_double_dash(a)) {
+            render_arg_push(arg, w);
+        }
+        if visible_args.iter().any(|a| after_double_dash(a)) {
+            w.line("argv.push(\"--\".to_string());");
+            for arg in visible_args.iter().filter(|a| after_double_dash(a)) {
+                render_arg_push(arg, w);
+            }
+        } else if visible_args
+            .iter()
+            .any(|a| matches!(a.double_dash, SpecDoubleDashChoices::Automatic))
+        {
+            w.line("// double_dash=automatic: \"--\" is implied after the first positional arg");
+        }
+        w.line("self.runner.run(&argv)");
+    }
+    w.dedent();
+    w.line("}");
+
+    if has_flags {
+        w.line("");
+        w.line(&format!(
+            "fn flag_args(flags: &{flags_type}, argv: &mut Vec<String>) {{"
+        ));
+        w.indent();
+        for flag in command_flags(cmd, global_flags) {
+            render_flag_push(flag, w);
+        }
+        w.dedent();
+        w.line("}");
+    }
+
+    w.dedent();
+    w.line("}");
+
+    if is_root {
+        w.line("");
+        w.line(&format!("impl Default for {struct_name} {{"));
+        w.indent();
+        w.line("fn default() -> Self {");
+        w.indent();
+        w.line("Self::new()");
+        w.dedent();
+        w.line("}");
+        w.dedent();
+        w.line("}");
+    }
+
+    for (_, subcmd) in &visible_subcmds {
+        w.line("");
+        let sub_struct = rs_type_name(subcmd, "");
+        render_command(subcmd, &sub_struct, false, global_flags, bin_name, w);
+    }
+}
+
+fn after_double_dash(arg: &SpecArg) -> bool {
+    matches!(arg.double_dash, SpecDoubleDashChoices::Required)
+}
+
+fn render_arg_push(arg: &SpecArg, w: &mut CodeWriter) {
+    let ident = rs_ident(&arg.name);
+    match FieldShape::of_arg(arg) {
+        FieldShape::One => w.line(&format!("argv.push(args.{ident}.to_string());")),
+        FieldShape::Many => w.line(&format!(
+            "argv.extend(args.{ident}.iter().map(|v| v.to_string()));"
+        )),
+        _ => w.line(&format!(
+            "if let Some(v) = &args.{ident} {{ argv.push(v.to_string()); }}"
+        )),
+    }
+}
+
+fn render_flag_push(flag: &SpecFlag, w: &mut CodeWriter) {
+    let ident = flag_field_ident(flag);
+    let spelling = escape_rs_string(&flag_spelling(flag));
+    let push = format!("argv.push(\"{spelling}\".to_string())");
+    match FieldShape::of_flag(flag) {
+        FieldShape::Switch => w.line(&format!("if flags.{ident} {{ {push}; }}")),
+        FieldShape::Negatable => {
+            let negate = escape_rs_string(flag.negate.as_deref().unwrap_or_default());
+            w.line(&format!("match flags.{ident} {{"));
+            w.indent();
+            w.line(&format!("Some(true) => {push},"));
+            w.line(&format!("Some(false) => argv.push(\"{negate}\".to_string()),"));
+            w.line("None => {}");
+            w.dedent();
+            w.line("}");
+        }
+        FieldShape::Count => w.line(&format!("for _ in 0..flags.{ident} {{ {push}; }}")),
+        FieldShape::One => w.line(&format!(
+            "argv.extend([\"{spelling}\".to_string(), flags.{ident}.to_string()]);"
+        )),
+        FieldShape::Optional => w.line(&format!(
+            "if let Some(v) = &flags.{ident} {{ argv.extend([\"{spelling}\".to_string(), v.to_string()]); }}"
+        )),
+        FieldShape::Many => w.line(&format!(
+            "for v in &flags.{ident} {{ argv.extend([\"{spelling}\".to_string(), v.to_string()]); }}"
+        )),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::sdk::{SdkLanguage, SdkOptions};
+    use crate::test::SPEC_KITCHEN_SINK;
+    use crate::Spec;
+
+    fn make_opts() -> SdkOptions {
+        SdkOptions {
+            language: SdkLanguage::Rust,
+            package_name: None,
+            source_file: Some("test.usage.kdl".to_string()),
+        }
+    }
+
+    fn get_file<'a>(output: &'a crate::sdk::SdkOutput, name: &str) -> &'a str {
+        output
+            .files
+            .iter()
+            .find(|f| f.path.to_str() == Some(name))
+            .unwrap_or_else(|| panic!("{name} should exist"))
+            .content
+            .as_str()
+    }
+
+    fn full_feature_spec() -> Spec {
+        r##"
+            bin "mytool"
+            version "1.2.3"
+            about "A powerful CLI tool"
+
+            flag "-v --verbose" help="Verbosity level" count=#true global=#true
+            flag "-C --config <path>" help="Config file path" global=#true env="MYTOOL_CONFIG"
+            flag "--dry-run" help="Show what would be done" negate="--no-dry-run"
+
+            arg "input" help="Input file" required=#true
+            arg "[extra]..." help="Extra files"
+
+            cmd "build" help="Build the project" deprecated="Use compile instead" {
+                alias "b"
+                arg "target" help="Build target" {
+                    choices "debug" "release"
+                }
+                arg "output" help="Output directory" double_dash="required"
+                flag "-j --jobs <n>" help="Parallel jobs" var=#true type="uint"
+                flag "--release" help="Build in release mode"
+                example "mytool build --release debug out" header="Build in release mode" lang="bash"
+            }
+
+            cmd "status" help="Show status"
+
+            config {
+                prop "port" default=8080 data_type=integer help="Port number"
+                prop "host" data_type=string
+            }
+        "##
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rust_types() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.rs"));
+    }
+
+    #[test]
+    fn test_rust_client() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "client.rs"));
+    }
+
+    #[test]
+    fn test_rust_mod() {
+        let output = crate::sdk::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "mod.rs"));
+    }
+
+    #[test]
+    fn test_rust_full_feature_types() {
+        let output = crate::sdk::generate(&full_feature_spec(), &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.rs"));
+    }
+
+    #[test]
+    fn test_rust_full_feature_client() {
+        let output = crate::sdk::generate(&full_feature_spec(), &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "client.rs"));
+    }
+
+    /// Names that are keywords, or that the generated code takes for itself, are renamed.
+    #[test]
+    fn test_rust_reserved_names() {
+        let spec: Spec = r##"
+            bin "app"
+            arg "type"
+            flag "--self"
+            flag "--new <n>"
+            cmd "exec" help="Run something"
+            cmd "self" {
+                arg "mode" {
+                    choices "self" "1x" "one-x" "OneX"
+                }
+            }
+        "##
+        .parse()
+        .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        let types = get_file(&output, "types.rs");
+        assert!(types.contains("pub type_: String,"), "{types}");
+        assert!(types.contains("pub self_: bool,"), "{types}");
+        assert!(types.contains("pub new_: Option<String>,"), "{types}");
+        assert!(
+            types.contains("    Self_,\n    V1x,\n    OneX,\n    OneX2,\n"),
+            "{types}"
+        );
+        let client = get_file(&output, "client.rs");
+        assert!(client.contains("pub fn exec_(&self) -> Exec {"), "{client}");
+        assert!(
+            client.contains("pub fn self_(&self) -> Self_ {"),
+            "{client}"
+        );
+        assert!(types.contains("pub struct SelfArgs {"), "{types}");
+        assert!(
+            client.contains("use super::types::{AppArgs, AppFlags, SelfArgs};"),
+            "{client}"
+        );
+    }
+
+    /// Text from the spec reaches string literals escaped, control characters included.
+    #[test]
+    fn test_rust_escapes_literals() {
+        let spec: Spec = "bin \"app\"\nabout \"say \\\"hi\\\"\\u{85}\\n\"\nconfig { prop \"p\" data_type=string default=\"a\\\\b\" }"
+            .parse()
+            .unwrap();
+        let output = crate::sdk::generate(&spec, &make_opts()).unwrap();
+        let types = get_file(&output, "types.rs");
+        assert!(
+            types.contains(r#"pub const ABOUT: &str = "say \"hi\"\u{85}\n";"#),
+            "{types}"
+        );
+        assert!(types.contains(r#"p: "a\\b".to_string(),"#), "{types}");
+    }
+}
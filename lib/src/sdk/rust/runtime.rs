@@ -0,0 +1,73 @@
+pub const RUNTIME_RS: &str = r#"// Runtime module for usage-generated SDK clients. Do not edit manually.
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Result of a CLI invocation.
+#[derive(Debug, Clone)]
+pub struct CliResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl CliResult {
+    pub fn ok(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// A CLI binary that could not be started.
+#[derive(Debug)]
+pub struct CliError {
+    pub bin_path: PathBuf,
+    pub source: io::Error,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.source.kind() == io::ErrorKind::NotFound {
+            write!(f, "CLI binary not found: {}", self.bin_path.display())
+        } else {
+            write!(f, "failed to run {}: {}", self.bin_path.display(), self.source)
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Runs a CLI binary as a subprocess.
+#[derive(Debug, Clone)]
+pub struct CliRunner {
+    bin_path: PathBuf,
+}
+
+impl CliRunner {
+    pub fn new(bin_path: impl Into<PathBuf>) -> Self {
+        Self {
+            bin_path: bin_path.into(),
+        }
+    }
+
+    pub fn run(&self, args: &[String]) -> Result<CliResult, CliError> {
+        let output = Command::new(&self.bin_path)
+            .args(args)
+            .output()
+            .map_err(|source| CliError {
+                bin_path: self.bin_path.clone(),
+                source,
+            })?;
+        Ok(CliResult {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            // A process ended by a signal has no exit code of its own.
+            exit_code: output.status.code().unwrap_or(1),
+        })
+    }
+}
+"#;
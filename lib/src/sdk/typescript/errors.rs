@@ -0,0 +1,43 @@
+use crate::sdk::{escape_jsdoc, escape_ts_string, generated_header, CodeWriter, ExitErrorMap};
+
+/// `errors.ts`: a class for each non-zero exit status the spec declares, which the client
+/// rejects with when a command exits with it.
+pub fn render(exits: &ExitErrorMap, source_file: &Option<String>) -> String {
+    let mut w = CodeWriter::new();
+
+    w.line(&generated_header("//", source_file));
+    w.line("import { CliExitError, CliResult } from \"./runtime\";");
+
+    for class in &exits.classes {
+        let message = match &class.help {
+            Some(help) => help.clone(),
+            None => format!("exited with status {}", class.code),
+        };
+        w.line("");
+        w.line(&format!(
+            "/** Exit status {}: {} */",
+            class.code,
+            escape_jsdoc(&message)
+        ));
+        w.line(&format!(
+            "export class {} extends CliExitError {{",
+            class.name
+        ));
+        w.indent();
+        w.line(&format!("static readonly exitCode = {};", class.code));
+        w.line("");
+        w.line("constructor(binPath: string, result: CliResult) {");
+        w.indent();
+        w.line(&format!(
+            "super(binPath, result, \"{}\");",
+            escape_ts_string(&message)
+        ));
+        w.line(&format!("this.name = \"{}\";", class.name));
+        w.dedent();
+        w.line("}");
+        w.dedent();
+        w.line("}");
+    }
+
+    w.finish()
+}
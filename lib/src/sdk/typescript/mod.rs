@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 
-use crate::sdk::{SdkFile, SdkOptions, SdkOutput};
+use crate::sdk::{collect_exit_errors, SdkFile, SdkOptions, SdkOutput};
 use crate::Spec;
 
+mod errors;
 mod runtime;
 mod types;
 mod wrappers;
@@ -12,8 +13,9 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
         .package_name
         .clone()
         .unwrap_or_else(|| spec.bin.clone());
+    let exits = collect_exit_errors(spec);
 
-    SdkOutput {
+    let mut output = SdkOutput {
         files: vec![
             SdkFile {
                 path: PathBuf::from("types.ts"),
@@ -21,7 +23,7 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
             },
             SdkFile {
                 path: PathBuf::from("client.ts"),
-                content: wrappers::render(spec, &package_name, &opts.source_file),
+                content: wrappers::render(spec, &package_name, &exits, &opts.source_file),
             },
             SdkFile {
                 path: PathBuf::from("runtime.ts"),
@@ -29,13 +31,26 @@ pub fn generate(spec: &Spec, opts: &SdkOptions) -> SdkOutput {
             },
             SdkFile {
                 path: PathBuf::from("index.ts"),
-                content: render_index(&package_name),
+                content: render_index(&package_name, !exits.is_empty()),
             },
         ],
+    };
+    // Only for a spec that declares a status to raise, so one that declares none generates
+    // what it always did.
+    if !exits.is_empty() {
+        output.files.push(SdkFile {
+            path: PathBuf::from("errors.ts"),
+            content: errors::render(&exits, &opts.source_file),
+        });
     }
+    output
 }
 
-fn render_index(package_name: &str) -> String {
+fn render_index(package_name: &str, has_errors: bool) -> String {
     let class_name = heck::AsPascalCase(package_name).to_string();
-    format!("export {{ {class_name} }} from \"./client\";\nexport {{ CliResult, CliError }} from \"./runtime\";\nexport * from \"./types\";\n")
+    let mut index = format!("export {{ {class_name} }} from \"./client\";\nexport {{ CliResult, CliError, CliExitError }} from \"./runtime\";\nexport * from \"./types\";\n");
+    if has_errors {
+        index.push_str("export * from \"./errors\";\n");
+    }
+    index
 }
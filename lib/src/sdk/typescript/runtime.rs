@@ -23,10 +23,29 @@ export class CliError extends Error {
   }
 }
 
+/** A non-zero exit status the spec declares. Clients reject with a subclass for each. */
+export class CliExitError extends CliError {
+  constructor(
+    binPath: string,
+    public readonly result: CliResult,
+    message: string,
+  ) {
+    super(binPath, message);
+    this.name = "CliExitError";
+  }
+
+  get exitCode(): number {
+    return this.result.exitCode;
+  }
+}
+
+/** The error each declared exit status rejects with, by code. */
+export type ExitErrors = Record<number, new (binPath: string, result: CliResult) => CliExitError>;
+
 export class CliRunner {
   constructor(private binPath: string) {}
 
-  async run(args: string[]): Promise<CliResult> {
+  async run(args: string[], exits: ExitErrors = {}): Promise<CliResult> {
     return new Promise<CliResult>((resolve, reject) => {
       const child: ChildProcess = spawn(this.binPath, args, {
         stdio: ["pipe", "pipe", "pipe"],
@@ -43,9 +62,32 @@ export class CliRunner {
         }
       });
       child.on("close", (code: number | null) => {
-        resolve(new CliResult(stdout, stderr, code ?? 1));
+        const result = new CliResult(stdout, stderr, code ?? 1);
+        const ExitError = exits[result.exitCode];
+        if (ExitError) {
+          reject(new ExitError(this.binPath, result));
+        } else {
+          resolve(result);
+        }
       });
     });
   }
+
+  /**
+   * What `result` printed, parsed: one JSON document, or for `"jsonl"` one per line. A
+   * run that failed rejects rather than parsing whatever it printed instead.
+   */
+  parse<T>(result: CliResult, format: "json" | "jsonl"): T {
+    if (!result.ok) {
+      throw new CliExitError(this.binPath, result, `exited with status ${result.exitCode}`);
+    }
+    if (format === "json") {
+      return JSON.parse(result.stdout) as T;
+    }
+    return result.stdout
+      .split("\n")
+      .filter((line) => line.trim() !== "")
+      .map((line) => JSON.parse(line)) as T;
+  }
 }
 "#;
@@ -2,12 +2,13 @@ use heck::AsPascalCase;
 
 use crate::spec::cmd::SpecCommand;
 use crate::spec::config::SpecConfigProp;
+use crate::spec::config_type::Base;
 use crate::spec::data_types::SpecDataTypes;
 use crate::{Spec, SpecArg, SpecFlag};
 
 use crate::sdk::{
-    collect_choice_types, command_type_name, escape_jsdoc, escape_ts_string, generated_header,
-    ChoiceTypeMap, CodeWriter,
+    collect_choice_types, collect_output_types, command_type_name, escape_jsdoc, escape_ts_string,
+    generated_header, ChoiceTypeMap, CodeWriter, JsonShape,
 };
 
 pub fn render(spec: &Spec, package_name: &str, source_file: &Option<String>) -> String {
@@ -110,9 +111,96 @@ pub fn render(spec: &Spec, package_name: &str, source_file: &Option<String>) ->
         w.line("}");
     }
 
+    for (name, shape) in collect_output_types(spec, package_name) {
+        w.line("");
+        match &shape {
+            JsonShape::Object(fields) => {
+                w.line(&format!("export interface {name} {{"));
+                w.indent();
+                for field in fields {
+                    if let Some(help) = &field.help {
+                        w.line(&format!("/** {} */", escape_jsdoc(help)));
+                    }
+                    let optional = if field.required { "" } else { "?" };
+                    w.line(&format!(
+                        "{}{optional}: {};",
+                        ts_property_key(&field.name),
+                        shape_ts_type(&field.shape)
+                    ));
+                }
+                w.dedent();
+                w.line("}");
+            }
+            shape => w.line(&format!("export type {name} = {};", shape_ts_type(shape))),
+        }
+    }
+
     w.finish()
 }
 
+/// The TypeScript type for a value of `shape`. An object nested in another is written
+/// inline; only a schema's own top level gets an interface.
+pub(crate) fn shape_ts_type(shape: &JsonShape) -> String {
+    match shape {
+        JsonShape::Any => "unknown".to_string(),
+        JsonShape::Null => "null".to_string(),
+        JsonShape::Bool => "boolean".to_string(),
+        JsonShape::Integer | JsonShape::Number => "number".to_string(),
+        JsonShape::String => "string".to_string(),
+        JsonShape::Literals(values) => {
+            // Only a scalar has a literal type; an enum of objects is whatever its values are.
+            if values.iter().any(|v| v.is_object() || v.is_array()) {
+                return "unknown".to_string();
+            }
+            let literals: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+            literals.join(" | ")
+        }
+        JsonShape::Array(items) => {
+            let items_type = shape_ts_type(items);
+            if items_type.contains(" | ") {
+                format!("({items_type})[]")
+            } else {
+                format!("{items_type}[]")
+            }
+        }
+        JsonShape::Object(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|field| {
+                    let optional = if field.required { "" } else { "?" };
+                    format!(
+                        "{}{optional}: {}",
+                        ts_property_key(&field.name),
+                        shape_ts_type(&field.shape)
+                    )
+                })
+                .collect();
+            format!("{{ {} }}", fields.join("; "))
+        }
+        JsonShape::Map(values) => format!("Record<string, {}>", shape_ts_type(values)),
+        JsonShape::Ref(name) => name.clone(),
+        JsonShape::Union(shapes) => shapes
+            .iter()
+            .map(shape_ts_type)
+            .collect::<Vec<_>>()
+            .join(" | "),
+    }
+}
+
+/// A JSON property name as an object type's key: bare where it can be, quoted otherwise.
+fn ts_property_key(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        name.to_string()
+    } else {
+        format!("\"{}\"", escape_ts_string(name))
+    }
+}
+
 fn render_command_types(
     cmd: &SpecCommand,
     package_name: &str,
@@ -258,7 +346,7 @@ fn arg_ts_type(arg: &SpecArg, cmd_name: &str, choice_types: &ChoiceTypeMap) -> S
                 .join(" | ")
         }
     } else {
-        "string".to_string()
+        value_ts_type(arg).to_string()
     };
 
     if arg.var {
@@ -287,7 +375,7 @@ fn flag_ts_type(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -
                         .join(" | ")
                 }
             } else {
-                "string".to_string()
+                value_ts_type(arg).to_string()
             };
 
             if flag.var {
@@ -306,6 +394,18 @@ fn flag_ts_type(flag: &SpecFlag, cmd_name: &str, choice_types: &ChoiceTypeMap) -
     }
 }
 
+/// The TypeScript type of one value, from its declared `type=`.
+///
+/// A duration stays a string: `"1h30m"` is how the CLI reads it, and a number of
+/// milliseconds would need a unit convention the spec does not state.
+pub(crate) fn value_ts_type(arg: &SpecArg) -> &'static str {
+    match arg.value_type {
+        Some(Base::Int | Base::Uint | Base::Float) => "number",
+        Some(Base::Bool) => "boolean",
+        _ => "string",
+    }
+}
+
 fn config_prop_type(prop: &SpecConfigProp) -> String {
     match prop.data_type {
         SpecDataTypes::String => "string".to_string(),
@@ -331,7 +431,7 @@ pub(crate) fn sanitize_ident(name: &str) -> String {
     match camel.as_str() {
         "function" | "class" | "const" | "let" | "var" | "type" | "interface" | "new"
         | "delete" | "return" | "export" | "import" | "default" | "in" | "instanceof" | "exec"
-        | "runner" | "buildFlagArgs" | "constructor" => {
+        | "runner" | "buildFlagArgs" | "constructor" | "execJson" | "execJsonl" => {
             format!("_{camel}")
         }
         _ => camel,
@@ -364,25 +464,25 @@ mod tests {
 
     #[test]
     fn test_typescript_types() {
-        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "types.ts"));
     }
 
     #[test]
     fn test_typescript_client() {
-        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.ts"));
     }
 
     #[test]
     fn test_typescript_runtime() {
-        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "runtime.ts"));
     }
 
     #[test]
     fn test_typescript_index() {
-        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts());
+        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "index.ts"));
     }
 
@@ -431,14 +531,14 @@ mod tests {
     #[test]
     fn test_full_feature_types() {
         let spec = full_feature_spec();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "types.ts"));
     }
 
     #[test]
     fn test_full_feature_client() {
         let spec = full_feature_spec();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.ts"));
     }
 
@@ -455,7 +555,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         // just verify it doesn't crash and has the config interface
         let types = get_file(&output, "types.ts");
         assert!(types.contains("MyappConfig"));
@@ -479,7 +579,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.ts"));
     }
 
@@ -502,10 +602,35 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.ts"));
     }
 
+    /// Declared types become TypeScript's, and go back through `String` on the way out.
+    #[test]
+    fn test_typed_values() {
+        let spec: Spec = r##"
+            bin "srv"
+            flag "--port <port>" type="uint"
+            flag "--debug-level <n>" type="bool"
+            flag "--timeout <d>" type="duration"
+            arg "<ratios>..." type="float"
+        "##
+        .parse()
+        .unwrap();
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
+        let types = get_file(&output, "types.ts");
+        assert!(types.contains("port?: number;"), "{types}");
+        assert!(types.contains("debugLevel?: boolean;"), "{types}");
+        assert!(types.contains("timeout?: string;"), "{types}");
+        assert!(types.contains("ratios: number[];"), "{types}");
+        let client = get_file(&output, "client.ts");
+        assert!(
+            client.contains("cmdArgs.push(...args.ratios.map(String));"),
+            "{client}"
+        );
+    }
+
     /// Minimal spec with no args, no flags, no subcommands.
     #[test]
     fn test_minimal_spec() {
@@ -514,7 +639,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         insta::assert_snapshot!(get_file(&output, "client.ts"));
     }
 
@@ -531,7 +656,7 @@ mod tests {
             package_name: Some("MyCustomSdk".to_string()),
             source_file: None,
         };
-        let output = super::super::super::generate(&spec, &opts);
+        let output = super::super::super::generate(&spec, &opts).unwrap();
         insta::assert_snapshot!(get_file(&output, "index.ts"));
     }
 
@@ -553,7 +678,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("BuildEnvChoice"));
         assert!(types.contains("DeployEnvChoice"));
@@ -574,7 +699,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("async exec(flags?: StatusFlags): Promise<CliResult>"));
         insta::assert_snapshot!(client);
@@ -600,7 +725,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("MyappConfig"));
         insta::assert_snapshot!(types);
@@ -620,7 +745,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("VisibleArgs"));
         assert!(!types.contains("SecretArgs"));
@@ -644,7 +769,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("double_dash=automatic"));
         assert!(client.contains("@example Basic run"));
@@ -666,7 +791,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("mode?: string"));
         assert!(types.contains("output: string"));
@@ -686,7 +811,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("name?: string"));
         insta::assert_snapshot!(types);
@@ -703,7 +828,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains(r#""bash" | "zsh" | "fish""#));
         insta::assert_snapshot!(types);
@@ -718,7 +843,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("APP_CONFIG"));
         insta::assert_snapshot!(types);
@@ -734,7 +859,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("verbose"));
         assert!(!types.contains("debug"));
@@ -749,7 +874,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("--dry-run"));
         assert!(client.contains("--no-dry-run"));
@@ -765,7 +890,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("-v"));
         insta::assert_snapshot!(client);
@@ -780,7 +905,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("string[]"));
         let client = get_file(&output, "client.ts");
@@ -797,7 +922,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("fmt"));
         let client = get_file(&output, "client.ts");
@@ -815,7 +940,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         // required flag without default should NOT have "?"
         assert!(types.contains("token: string;"));
@@ -836,7 +961,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         // GlobalFlags should have boolean[] and string[] types
         assert!(types.contains("boolean[]"));
@@ -853,7 +978,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("@default false"));
         insta::assert_snapshot!(types);
@@ -871,7 +996,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("AppConfig"));
         insta::assert_snapshot!(types);
@@ -886,7 +1011,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("string[]"));
         let client = get_file(&output, "client.ts");
@@ -907,7 +1032,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("verbose?: boolean"));
         assert!(types.contains("dry_run?: boolean"));
@@ -926,7 +1051,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         assert!(types.contains("host?: string"));
         assert!(types.contains("name?: string"));
@@ -934,6 +1059,84 @@ mod tests {
     }
 
     /// Example without lang — covers example rendering without language tag.
+    fn exits_spec() -> Spec {
+        r##"
+            bin "pkg"
+            exit 1 "something failed"
+            exit 2 "usage error"
+            cmd "install" {
+                exit 3 "lockfile out of date"
+                exit 4 "network unreachable" name="offline"
+                arg "<name>"
+            }
+            cmd "check" {
+                exit 0 "clean"
+                exit 3 "lockfile out of date"
+            }
+            cmd "verify" {
+                exit 3 "the package is not in the lockfile" name="lockfile out of date"
+            }
+        "##
+        .parse()
+        .unwrap()
+    }
+
+    /// One class per status, shared where commands share it, named apart where two
+    /// statuses would otherwise share a name. Exit 0 raises nothing.
+    #[test]
+    fn test_typescript_exit_errors() {
+        let output = super::super::super::generate(&exits_spec(), &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "errors.ts"));
+        insta::assert_snapshot!(get_file(&output, "client.ts"));
+        assert!(get_file(&output, "index.ts").contains("export * from \"./errors\";"));
+    }
+
+    /// A method per JSON output, returning the type its schema declares.
+    #[test]
+    fn test_typescript_typed_outputs() {
+        let spec: Spec = r###"
+            bin "pkg"
+            flag "--format <format>" global=#true
+            schema "Package" ##"""
+            {
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "description": "The package name"},
+                    "version": {"type": ["string", "null"]},
+                    "kind": {"enum": ["lib", "bin"]}
+                },
+                "required": ["name"]
+            }
+            """##
+            schema "Meta" ##"{"properties": {"class": {"type": "integer"}, "created-at": {"type": "string"}}}"##
+            cmd "list" {
+                flag "--json"
+                output "text"
+                output "json" flag="--json" help="every package" schema=##"{"type": "array", "items": {"$ref": "#/$defs/Package"}}"##
+                output "jsonl" flag="--format jsonl" type="Package"
+            }
+            cmd "show" {
+                arg "<name>"
+                output "json"
+            }
+        "###
+        .parse()
+        .unwrap();
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
+        insta::assert_snapshot!(get_file(&output, "types.ts"));
+        insta::assert_snapshot!(get_file(&output, "client.ts"));
+    }
+
+    #[test]
+    fn test_typescript_no_exits_no_errors_module() {
+        let output = super::super::super::generate(&SPEC_KITCHEN_SINK, &make_opts()).unwrap();
+        assert!(output
+            .files
+            .iter()
+            .all(|f| f.path.to_str() != Some("errors.ts")));
+        assert!(!get_file(&output, "index.ts").contains("./errors"));
+    }
+
     #[test]
     fn test_typescript_example_without_lang() {
         let spec: Spec = r##"
@@ -945,7 +1148,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("app greet hello"));
         insta::assert_snapshot!(client);
@@ -966,7 +1169,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let types = get_file(&output, "types.ts");
         insta::assert_snapshot!(types);
         let client = get_file(&output, "client.ts");
@@ -990,7 +1193,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         // "info" subcommand has no own flags, only global flags => GlobalFlags type
         assert!(client.contains("GlobalFlags"));
@@ -1012,7 +1215,7 @@ mod tests {
         "##
         .parse()
         .unwrap();
-        let output = super::super::super::generate(&spec, &make_opts());
+        let output = super::super::super::generate(&spec, &make_opts()).unwrap();
         let client = get_file(&output, "client.ts");
         assert!(client.contains("double_dash=automatic"));
         insta::assert_snapshot!(client);
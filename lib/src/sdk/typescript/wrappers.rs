@@ -2,27 +2,51 @@ use heck::AsPascalCase;
 
 use crate::sdk::{
     collect_choice_types, collect_type_imports, escape_jsdoc, escape_ts_string, generated_header,
-    CodeWriter,
+    typed_outputs, CodeWriter, ExitErrorMap, TypedOutput,
 };
 use crate::spec::arg::SpecDoubleDashChoices;
 use crate::spec::cmd::SpecCommand;
-use crate::{Spec, SpecArg, SpecFlag};
+use crate::{Spec, SpecArg, SpecFlag, SpecOutputFormat};
 
-use super::types::{flag_property_name, sanitize_ident};
+use super::types::{flag_property_name, sanitize_ident, value_ts_type};
 
-pub fn render(spec: &Spec, package_name: &str, source_file: &Option<String>) -> String {
+pub fn render(
+    spec: &Spec,
+    package_name: &str,
+    exits: &ExitErrorMap,
+    source_file: &Option<String>,
+) -> String {
     let mut w = CodeWriter::new();
 
     w.line(&generated_header("//", source_file));
     w.line("import { CliRunner, CliResult } from \"./runtime\";");
+    if !exits.is_empty() {
+        let names: Vec<&str> = exits.classes.iter().map(|c| c.name.as_str()).collect();
+        w.line(&format!(
+            "import {{ {} }} from \"./errors\";",
+            names.join(", ")
+        ));
+    }
+
+    // collect root-level global flags for propagation to subcommands
+    let global_flags: Vec<&SpecFlag> = spec
+        .cmd
+        .flags
+        .iter()
+        .filter(|f| f.global && !f.hide)
+        .collect();
 
     // collect all type imports needed
     let choice_types = collect_choice_types(&spec.cmd);
     let type_imports = collect_type_imports(&spec.cmd, package_name, &choice_types);
+    let output_imports = collect_output_imports(&spec.cmd, &global_flags, package_name);
     let has_global_flags = spec.cmd.flags.iter().any(|f| f.global && !f.hide);
-    if has_global_flags {
+    if has_global_flags || !output_imports.is_empty() {
         let mut all_imports = type_imports;
-        all_imports.push("GlobalFlags".to_string());
+        if has_global_flags {
+            all_imports.push("GlobalFlags".to_string());
+        }
+        all_imports.extend(output_imports);
         all_imports.sort();
         all_imports.dedup();
         w.line(&format!(
@@ -38,14 +62,6 @@ pub fn render(spec: &Spec, package_name: &str, source_file: &Option<String>) ->
 
     w.line("");
 
-    // collect root-level global flags for propagation to subcommands
-    let global_flags: Vec<&SpecFlag> = spec
-        .cmd
-        .flags
-        .iter()
-        .filter(|f| f.global && !f.hide)
-        .collect();
-
     let class_name = AsPascalCase(package_name).to_string();
 
     // render the root class (the main entry point)
@@ -55,6 +71,7 @@ pub fn render(spec: &Spec, package_name: &str, source_file: &Option<String>) ->
         true,
         &global_flags,
         &spec.bin,
+        exits,
         &mut w,
     );
 
@@ -75,6 +92,7 @@ fn render_class(
     is_root: bool,
     global_flags: &[&SpecFlag],
     bin_name: &str,
+    exits: &ExitErrorMap,
     w: &mut CodeWriter,
 ) {
     let visible_subcmds: Vec<_> = cmd.subcommands.iter().filter(|(_, c)| !c.hide).collect();
@@ -199,6 +217,9 @@ fn render_class(
     if !cmd.usage.is_empty() {
         exec_doc.push(cmd.usage.clone());
     }
+    for (code, name) in exits.for_cmd(cmd) {
+        exec_doc.push(format!("@throws {{{name}}} on exit status {code}"));
+    }
     for example in &cmd.examples {
         let label = example.header.as_deref().unwrap_or("Example");
         let lang = if example.lang.is_empty() {
@@ -252,8 +273,9 @@ fn render_class(
                 }
                 let ident = sanitize_ident(&arg.name);
                 if arg.var {
+                    let spread = spread_args(arg, &ident);
                     w.line(&format!(
-                        "if (args.{ident} !== undefined) {{ cmdArgs.push(...args.{ident}); }}"
+                        "if (args.{ident} !== undefined) {{ cmdArgs.push(...{spread}); }}"
                     ));
                 } else {
                     w.line(&format!(
@@ -271,8 +293,9 @@ fn render_class(
                     }
                     let ident = sanitize_ident(&arg.name);
                     if arg.var {
+                        let spread = spread_args(arg, &ident);
                         w.line(&format!(
-                            "if (args.{ident} !== undefined) {{ cmdArgs.push(...args.{ident}); }}"
+                            "if (args.{ident} !== undefined) {{ cmdArgs.push(...{spread}); }}"
                         ));
                     } else {
                         w.line(&format!(
@@ -290,9 +313,15 @@ fn render_class(
         // add flags
         if has_flags {
             w.line("const flagArgs = this.buildFlagArgs(flags);");
-            w.line("return this.runner.run([...cmdArgs, ...flagArgs]);");
+            w.line(&format!(
+                "return this.runner.run([...cmdArgs, ...flagArgs]{});",
+                exits_table(exits, cmd)
+            ));
         } else {
-            w.line("return this.runner.run(cmdArgs);");
+            w.line(&format!(
+                "return this.runner.run(cmdArgs{});",
+                exits_table(exits, cmd)
+            ));
         }
 
         w.dedent();
@@ -326,11 +355,19 @@ fn render_class(
         w.line("async exec(): Promise<CliResult> {");
         w.indent();
         let path = subcmd_path(cmd);
-        w.line(&format!("return this.runner.run([{path}]);"));
+        w.line(&format!(
+            "return this.runner.run([{path}]{});",
+            exits_table(exits, cmd)
+        ));
         w.dedent();
         w.line("}");
     }
 
+    for typed in typed_outputs(cmd, global_flags, class_name) {
+        w.line("");
+        render_typed_exec(&typed, &args_param, &flags_param, &flags_type, w);
+    }
+
     w.dedent();
     w.line("}");
 
@@ -338,7 +375,109 @@ fn render_class(
     for (name, subcmd) in &visible_subcmds {
         w.line("");
         let sub_class = AsPascalCase(name).to_string();
-        render_class(subcmd, &sub_class, false, global_flags, bin_name, w);
+        render_class(subcmd, &sub_class, false, global_flags, bin_name, exits, w);
+    }
+}
+
+/// `execJson` or `execJsonl`: `exec` with the flag that switches to the output set, and
+/// what it prints parsed. A run that fails rejects rather than parsing its error output.
+fn render_typed_exec(
+    typed: &TypedOutput,
+    args_param: &str,
+    flags_param: &str,
+    flags_type: &str,
+    w: &mut CodeWriter,
+) {
+    let (method, format) = match typed.output.format {
+        SpecOutputFormat::Jsonl => ("execJsonl", "jsonl"),
+        _ => ("execJson", "json"),
+    };
+    let value_type = typed.type_name.as_deref().unwrap_or("unknown");
+    let returns = match typed.output.format {
+        SpecOutputFormat::Jsonl => format!("{value_type}[]"),
+        _ => value_type.to_string(),
+    };
+
+    let mut doc = match &typed.output.flag {
+        Some(flag) => format!("Runs with `{flag}` and parses what it prints"),
+        None => "Parses what this command prints".to_string(),
+    };
+    if let Some(help) = &typed.output.help {
+        doc.push_str(&format!(": {help}"));
+    }
+    w.line(&format!("/** {} */", escape_jsdoc(&doc)));
+
+    let mut call = Vec::new();
+    if !args_param.is_empty() {
+        call.push("args".to_string());
+    }
+    match typed.flag {
+        Some((flag, value)) => {
+            let prop = flag_property_name(flag);
+            let value = match value {
+                Some(value) => format!("\"{}\"", escape_ts_string(value)),
+                None => "true".to_string(),
+            };
+            call.push(format!("{{ ...flags, {prop}: {value} }}"));
+        }
+        None if !flags_type.is_empty() => call.push("flags".to_string()),
+        None => {}
+    }
+    w.line(&format!(
+        "async {method}({args_param}{flags_param}): Promise<{returns}> {{"
+    ));
+    w.indent();
+    w.line(&format!(
+        "const result = await this.exec({});",
+        call.join(", ")
+    ));
+    w.line(&format!(
+        "return this.runner.parse<{returns}>(result, \"{format}\");"
+    ));
+    w.dedent();
+    w.line("}");
+}
+
+/// The output types a client's methods return, for its import from `./types`.
+fn collect_output_imports(
+    cmd: &SpecCommand,
+    global_flags: &[&SpecFlag],
+    package_name: &str,
+) -> Vec<String> {
+    if cmd.hide {
+        return vec![];
+    }
+    let mut imports: Vec<String> = typed_outputs(cmd, global_flags, package_name)
+        .into_iter()
+        .filter_map(|typed| typed.type_name)
+        .collect();
+    for subcmd in cmd.subcommands.values() {
+        imports.extend(collect_output_imports(subcmd, global_flags, package_name));
+    }
+    imports
+}
+
+/// The second argument to `run` for a command that declares exit statuses: each code and
+/// the error it rejects with. Empty for one that declares none.
+fn exits_table(exits: &ExitErrorMap, cmd: &SpecCommand) -> String {
+    let raised = exits.for_cmd(cmd);
+    if raised.is_empty() {
+        return String::new();
+    }
+    let entries: Vec<String> = raised
+        .iter()
+        .map(|(code, name)| format!("{code}: {name}"))
+        .collect();
+    format!(", {{ {} }}", entries.join(", "))
+}
+
+/// A variadic arg's values as argv words. Typed values are numbers or booleans, which
+/// a spawned process cannot take, so they go through `String` as single values do.
+fn spread_args(arg: &SpecArg, ident: &str) -> String {
+    if value_ts_type(arg) == "string" {
+        format!("args.{ident}")
+    } else {
+        format!("args.{ident}.map(String)")
     }
 }
 
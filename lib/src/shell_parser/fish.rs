@@ -0,0 +1,76 @@
+//! The prelude for fish.
+//!
+//! fish is not sh, and a second parse loop written in it would be a second implementation
+//! of the grammar to keep in step with the first. So the fish prelude carries the sh one
+//! and runs it with `sh -c`, which every system fish runs on has: the parse is the one the
+//! corpus checks, and what this adds is the fish needed to read its answer back.
+
+use std::fmt::Write as _;
+
+use super::posix::{self, Export};
+use super::{generated_comment, Shell, ShellParserOptions, Tree};
+
+pub(super) fn emit(tree: &Tree, opts: &ShellParserOptions) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{}
+#
+# Parses the arguments of the script that sources it, the way `usage exec` would, and
+# exports each flag and argument as usage_<name>. Source it with the script's arguments:
+#
+#     source ./{bin}.parse.fish $argv
+#
+# A command line the spec does not accept prints why and exits 1. -h and --help print
+# help and exit 0.
+#
+# The parse itself runs under sh, so fish and sh scripts read a command line the same way.
+",
+        generated_comment(opts),
+        bin = tree.spec.bin,
+    );
+
+    // What a previous parse, or the caller's environment, left behind would otherwise read
+    // as a flag nobody gave.
+    let mut keys: Vec<&str> = tree.flags.iter().map(|f| f.key.as_str()).collect();
+    keys.extend(tree.args.iter().map(|a| a.key.as_str()));
+    keys.sort_unstable();
+    keys.dedup();
+    for key in keys {
+        let _ = writeln!(out, "set -e usage_{key}");
+    }
+
+    let script = posix::body(tree, Shell::Posix, Export::Print) + "\n__usage_parse \"$@\"\n";
+    let _ = writeln!(
+        out,
+        "set -l __usage_out (sh -c {} {} $argv | string split0)",
+        quote(&script),
+        quote(&tree.spec.bin)
+    );
+    out.push_str(
+        r#"if test "$__usage_out[1]" != __usage_ok
+    # Nothing on stdout is a refusal, already explained on stderr. Anything else is what
+    # -h, --help or --version asked for.
+    if test (count $__usage_out) -eq 0
+        exit 1
+    end
+    printf '%s\n' $__usage_out
+    exit 0
+end
+set -g __usage_path $__usage_out[2]
+if test (count $__usage_out) -gt 2
+    for __usage_entry in $__usage_out[3..-1]
+        set -l __usage_kv (string split -m 1 = -- $__usage_entry)
+        set -gx $__usage_kv[1] $__usage_kv[2]
+    end
+end
+"#,
+    );
+    out
+}
+
+/// A fish word that reads back as exactly `s`. Inside single quotes fish honors two
+/// escapes, `\\` and `\'`, and nothing else.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'"))
+}
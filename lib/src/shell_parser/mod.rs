@@ -0,0 +1,572 @@
+//! Compiling a spec into a shell prelude that parses its own command line.
+//!
+//! `usage exec` and `usage bash` parse a script's arguments by running usage, so every
+//! invocation of the script needs usage installed. What this emits instead is the parse
+//! itself, written in the script's own shell: a block of functions that reads `"$@"`,
+//! exports the same `usage_*` variables `usage exec` would, and exits non-zero with a
+//! message when the command line does not fit the spec. Nothing is spawned and nothing
+//! needs to be on `PATH`.
+//!
+//! # What it implements
+//!
+//! The [argv grammar], and the part of the post-binding layer a script relies on: `env`
+//! and `default` fallbacks, `choices`, `required`, and the `var_min`/`var_max` bounds.
+//! The corpus in `corpus/` is run against the output in every shell that is installed,
+//! so agreement with usage-lib is a measurement rather than an intention.
+//!
+//! # What it refuses
+//!
+//! Anything that compares one value against another — `conflicts`, `requires`,
+//! `required_if`, `default_if`, `overrides` — and the routing features that change what
+//! a word selects: `default_subcommand`, `external_subcommand`, `multicall`, `mount`.
+//! A `validate` expression, and a `type` that can turn a value down: everything but
+//! `string` and the types usage only records, which it reads as strings too.
+//! Each would double the size of the emitted code for a feature few scripts declare, and
+//! a parser that silently ignored one would accept command lines the spec rejects. So
+//! [`generate`] names the first one it finds instead, and the spec's author can decide.
+//!
+//! [argv grammar]: https://usage.jdx.dev/spec/argv
+
+mod fish;
+mod posix;
+
+use std::str::FromStr;
+
+use heck::ToSnakeCase;
+
+use crate::error::{Result, UsageErr};
+use crate::spec::config_type::Base;
+use crate::spec::unknown_flags::UnknownFlags;
+use crate::{Spec, SpecArg, SpecChoices, SpecCommand, SpecFlag, SpecFlagAction};
+
+/// The shell a prelude is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Fish,
+    /// Plain `sh`: dash, busybox, and anything else POSIX describes.
+    Posix,
+    Zsh,
+}
+
+impl FromStr for Shell {
+    type Err = UsageErr;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "fish" => Ok(Shell::Fish),
+            "posix" => Ok(Shell::Posix),
+            "zsh" => Ok(Shell::Zsh),
+            _ => Err(UsageErr::UnsupportedShell(s.to_string())),
+        }
+    }
+}
+
+/// How to emit.
+#[derive(Debug, Clone)]
+pub struct ShellParserOptions {
+    pub shell: Shell,
+    /// The spec's path, for the `@generated` line.
+    pub source_file: Option<String>,
+}
+
+/// Turn a spec into a prelude that parses the sourcing script's arguments.
+///
+/// Fails, naming the feature, for a spec that declares something the prelude cannot
+/// express. See the [module documentation](self) for which those are.
+pub fn generate(spec: &Spec, opts: &ShellParserOptions) -> Result<String> {
+    check(spec)?;
+    let tree = Tree::new(spec)?;
+    Ok(match opts.shell {
+        Shell::Fish => fish::emit(&tree, opts),
+        shell => posix::emit(&tree, shell, opts),
+    })
+}
+
+/// The spec, flattened into what the emitted tables are built from.
+///
+/// Commands, flags and arguments are numbered in declaration order, and the emitted code
+/// refers to them by number. Names would need escaping into identifiers; numbers do not.
+struct Tree<'a> {
+    spec: &'a Spec,
+    commands: Vec<Command<'a>>,
+    flags: Vec<Flag<'a>>,
+    args: Vec<Arg<'a>>,
+}
+
+struct Command<'a> {
+    cmd: &'a SpecCommand,
+    /// The subcommands, by the index into [`Tree::commands`] and the name that selects each.
+    subcommands: Vec<(usize, &'a str)>,
+    /// `unknown_flags` as it applies here: the nearest command that states it, then the spec.
+    unknown: UnknownFlags,
+    /// Every spelling a flag-like token can take here, and what it names. Own flags come
+    /// first, then each ancestor's globals nearest first, so a redeclaration shadows.
+    spellings: Vec<(String, Target)>,
+    /// Every flag a spelling here reaches, which is what `env` and `default` fill once
+    /// this is the command the line selected.
+    in_scope: Vec<usize>,
+    /// This command's positionals, by index into [`Tree::args`].
+    args: Vec<usize>,
+    /// Whether the word `help` asks about the subcommands rather than binding.
+    help_word: bool,
+}
+
+struct Flag<'a> {
+    flag: &'a SpecFlag,
+    /// `usage_<key>` is what it is exported as.
+    key: String,
+}
+
+struct Arg<'a> {
+    arg: &'a SpecArg,
+    key: String,
+}
+
+/// What a spelling names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Flag {
+        id: usize,
+        negated: bool,
+    },
+    /// `-h`, `-?`, `--help`, `-V` and `--version`, where nothing declares them.
+    Builtin(&'static str),
+}
+
+impl<'a> Tree<'a> {
+    fn new(spec: &'a Spec) -> Result<Self> {
+        let mut tree = Tree {
+            spec,
+            commands: vec![],
+            flags: vec![],
+            args: vec![],
+        };
+        let root_unknown = spec
+            .cmd
+            .unknown_flags
+            .or(spec.unknown_flags)
+            .unwrap_or_default();
+        tree.add(&spec.cmd, &[], root_unknown)?;
+        Ok(tree)
+    }
+
+    /// Number `cmd` and everything beneath it. `inherited` is each ancestor's globals,
+    /// nearest first.
+    fn add(
+        &mut self,
+        cmd: &'a SpecCommand,
+        inherited: &[usize],
+        unknown: UnknownFlags,
+    ) -> Result<usize> {
+        let index = self.commands.len();
+        let own: Vec<usize> = cmd
+            .flags
+            .iter()
+            .map(|flag| {
+                self.flags.push(Flag {
+                    flag,
+                    key: key(&flag.name)?,
+                });
+                Ok(self.flags.len() - 1)
+            })
+            .collect::<Result<_>>()?;
+        let args = cmd
+            .args
+            .iter()
+            .map(|arg| {
+                self.args.push(Arg {
+                    arg,
+                    key: key(&arg.name)?,
+                });
+                Ok(self.args.len() - 1)
+            })
+            .collect::<Result<_>>()?;
+
+        let mut spellings: Vec<(String, Target)> = vec![];
+        let mut in_scope = vec![];
+        for &id in own.iter().chain(inherited) {
+            let flag = self.flags[id].flag;
+            let longs =
+                (flag.long.iter().chain(&flag.hidden_aliases)).map(|l| (format!("--{l}"), false));
+            let shorts = (flag.short.iter().chain(&flag.hidden_short_aliases))
+                .map(|s| (format!("-{s}"), false));
+            let negate = flag.negate.iter().map(|n| (n.clone(), true));
+            let mut reached = false;
+            for (spelling, negated) in longs.chain(shorts).chain(negate) {
+                if spellings.iter().all(|(s, _)| *s != spelling) {
+                    spellings.push((spelling, Target::Flag { id, negated }));
+                    reached = true;
+                }
+            }
+            if reached {
+                in_scope.push(id);
+            }
+        }
+        let mut builtin = |spelling: &str, target: &'static str| {
+            if spellings.iter().all(|(s, _)| s != spelling) {
+                spellings.push((spelling.to_string(), Target::Builtin(target)));
+            }
+        };
+        if self.spec.disable_help != Some(true) && !cmd.disable_help_flag {
+            builtin("-h", "help");
+            builtin("-?", "help");
+            builtin("--help", "long-help");
+        }
+        let versioned = self.spec.version.is_some() || self.spec.long_version.is_some();
+        if index == 0 && versioned && !cmd.disable_version_flag {
+            builtin("-V", "version");
+            builtin("--version", "long-version");
+        }
+
+        self.commands.push(Command {
+            cmd,
+            subcommands: vec![],
+            unknown,
+            spellings,
+            in_scope,
+            args,
+            help_word: self.spec.disable_help != Some(true)
+                && !cmd.disable_help_subcommand
+                && !cmd.subcommands.is_empty(),
+        });
+
+        // The globals below here: this command's, then what it inherited, so the nearest
+        // declaration is found first.
+        let globals: Vec<usize> = own
+            .iter()
+            .copied()
+            .filter(|id| self.flags[*id].flag.global)
+            .chain(inherited.iter().copied())
+            .collect();
+        for (name, sub) in &cmd.subcommands {
+            let unknown = sub.unknown_flags.unwrap_or(unknown);
+            let child = self.add(sub, &globals, unknown)?;
+            self.commands[index]
+                .subcommands
+                .push((child, name.as_str()));
+        }
+        Ok(index)
+    }
+}
+
+/// The variable a flag or argument is exported as, less its `usage_` prefix.
+///
+/// The same spelling `usage exec` uses. A name that does not make a shell identifier is
+/// refused rather than mangled into one, since the script would be reading a variable
+/// nobody could predict.
+fn key(name: &str) -> Result<String> {
+    let key = name.to_snake_case();
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(unsupported(format!(
+            "the name `{name}`, which is not a shell variable name as `usage_{key}`"
+        )));
+    }
+    Ok(key)
+}
+
+fn unsupported(feature: String) -> UsageErr {
+    UsageErr::UnsupportedFeature {
+        generator: "shell-parser",
+        feature,
+    }
+}
+
+/// Refuse a spec that declares anything the prelude does not implement.
+fn check(spec: &Spec) -> Result<()> {
+    let spec_level = [
+        (spec.multicall, "multicall"),
+        (spec.default_subcommand.is_some(), "default_subcommand"),
+    ];
+    if let Some((_, feature)) = spec_level.iter().find(|(declared, _)| *declared) {
+        return Err(unsupported(format!("`{feature}`")));
+    }
+    check_command(&spec.cmd)
+}
+
+fn check_command(cmd: &SpecCommand) -> Result<()> {
+    let at = |feature: &str| {
+        let name = match cmd.full_cmd.is_empty() {
+            true => "the root command".to_string(),
+            false => format!("`{}`", cmd.full_cmd.join(" ")),
+        };
+        Err(unsupported(format!("`{feature}` (on {name})")))
+    };
+    let declared = [
+        (!cmd.mounts.is_empty(), "mount"),
+        (cmd.external_subcommand, "external_subcommand"),
+        (cmd.restart_token.is_some(), "restart_token"),
+        (!cmd.groups.is_empty(), "group"),
+        (cmd.arg_required_else_help, "arg_required_else_help"),
+        (!cmd.args_override_self, "args_override_self=#false"),
+        (cmd.subcommand_negates_reqs, "subcommand_negates_reqs"),
+        (
+            cmd.args_conflicts_with_subcommands,
+            "args_conflicts_with_subcommands",
+        ),
+        (
+            cmd.subcommand_precedence_over_arg,
+            "subcommand_precedence_over_arg",
+        ),
+        (cmd.allow_missing_positional, "allow_missing_positional"),
+    ];
+    if let Some((_, feature)) = declared.iter().find(|(declared, _)| *declared) {
+        return at(feature);
+    }
+    for flag in &cmd.flags {
+        let declared = [
+            (!flag.required_if.is_empty(), "required_if"),
+            (!flag.required_if_eq.is_empty(), "required_if_eq"),
+            (!flag.required_if_eq_all.is_empty(), "required_if_eq_all"),
+            (!flag.required_unless.is_empty(), "required_unless"),
+            (!flag.required_unless_all.is_empty(), "required_unless_all"),
+            (!flag.overrides.is_empty(), "overrides"),
+            (!flag.conflicts.is_empty(), "conflicts"),
+            (!flag.requires.is_empty(), "requires"),
+            (!flag.requires_if.is_empty(), "requires_if"),
+            (!flag.default_if.is_empty(), "default_if"),
+            (flag.exclusive, "exclusive"),
+            (flag.value_optional, "value_optional"),
+            (flag.action != SpecFlagAction::Set, "action"),
+        ];
+        if let Some((_, feature)) = declared.iter().find(|(declared, _)| *declared) {
+            return at(&format!("{feature}` on `{}", flag.name));
+        }
+        if let Some(arg) = &flag.arg {
+            check_arg(arg).or_else(|feature| at(&format!("{feature}` on `{}", flag.name)))?;
+        }
+    }
+    for arg in &cmd.args {
+        check_arg(arg).or_else(|feature| at(&format!("{feature}` on `{}", arg.name)))?;
+    }
+    cmd.subcommands.values().try_for_each(check_command)
+}
+
+/// The first thing an argument declares that the prelude does not implement.
+fn check_arg(arg: &SpecArg) -> std::result::Result<(), &'static str> {
+    let declared = [
+        (arg.delimiter.is_some(), "delimiter"),
+        (arg.value_terminator.is_some(), "value_terminator"),
+        (!arg.conflicts.is_empty(), "conflicts"),
+        (!arg.requires.is_empty(), "requires"),
+        (!arg.required_if.is_empty(), "required_if"),
+        (!arg.required_if_eq.is_empty(), "required_if_eq"),
+        (!arg.required_if_eq_all.is_empty(), "required_if_eq_all"),
+        (!arg.required_unless.is_empty(), "required_unless"),
+        (!arg.required_unless_all.is_empty(), "required_unless_all"),
+        (arg.validate.is_some(), "validate"),
+        (
+            arg.value_type
+                .as_ref()
+                .is_some_and(|ty| !matches!(ty, Base::String | Base::Object | Base::Custom(_))),
+            "type",
+        ),
+    ];
+    if let Some((_, feature)) = declared.iter().find(|(declared, _)| *declared) {
+        return Err(feature);
+    }
+    match &arg.choices {
+        Some(choices) if choices.env().is_some() => Err("choices env"),
+        Some(choices) if choices.ignore_case => Err("choices ignore_case"),
+        Some(choices) if choices.details.iter().any(|c| !c.aliases.is_empty()) => {
+            Err("choice aliases")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The values a strict `choices` accepts, or `None` where anything goes.
+fn choices(choices: Option<&SpecChoices>) -> Option<&[String]> {
+    choices.filter(|c| c.strict).map(|c| c.choices.as_slice())
+}
+
+/// How a flag holds what it is given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// `true` or `false`.
+    Switch,
+    /// How many times it was given: `count`, or a `var` switch, which `usage exec`
+    /// reports the same way.
+    Count,
+    /// The text it was given: one value, or a list written the way `shell_words::join`
+    /// writes one.
+    Value,
+}
+
+impl Flag<'_> {
+    fn kind(&self) -> Kind {
+        match (&self.flag.arg, self.flag.count || self.flag.var) {
+            (Some(_), _) => Kind::Value,
+            (None, true) => Kind::Count,
+            (None, false) => Kind::Switch,
+        }
+    }
+
+    /// Whether repeated values collect into a list rather than replace one another.
+    fn list(&self) -> bool {
+        self.flag.arg.is_some() && (self.flag.var || self.variadic())
+    }
+
+    fn variadic(&self) -> bool {
+        self.flag.arg.as_ref().is_some_and(|arg| arg.var)
+    }
+
+    /// What error messages call it: its first long name, else its first short.
+    fn label(&self) -> String {
+        match (self.flag.long.first(), self.flag.short.first()) {
+            (Some(long), _) => format!("--{long}"),
+            (None, Some(short)) => format!("-{short}"),
+            (None, None) => self.flag.name.clone(),
+        }
+    }
+
+    /// Its default, on the flag or else on the flag's argument.
+    fn default(&self) -> &[String] {
+        match (&self.flag.default, &self.flag.arg) {
+            (default, _) if !default.is_empty() => default,
+            (_, Some(arg)) => &arg.default,
+            _ => &[],
+        }
+    }
+
+    fn choices(&self) -> Option<&[String]> {
+        choices(self.flag.arg.as_ref().and_then(|arg| arg.choices.as_ref()))
+    }
+
+    fn env(&self) -> Vec<&str> {
+        self.flag.env_names().collect()
+    }
+}
+
+impl Arg<'_> {
+    fn label(&self) -> String {
+        format!("<{}>", self.arg.name)
+    }
+
+    fn choices(&self) -> Option<&[String]> {
+        choices(self.arg.choices.as_ref())
+    }
+
+    fn env(&self) -> Vec<&str> {
+        self.arg
+            .env
+            .iter()
+            .chain(&self.arg.env_fallback)
+            .chain(&self.arg.deprecated_env)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// What `-h` or `--help` prints for a command.
+#[cfg(feature = "docs")]
+fn help(spec: &Spec, cmd: &SpecCommand, long: bool) -> String {
+    crate::docs::cli::render_help(spec, cmd, long)
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(not(feature = "docs"))]
+fn help(spec: &Spec, cmd: &SpecCommand, _long: bool) -> String {
+    format!("Usage: {} {}", spec.bin, cmd.usage())
+        .trim_end()
+        .to_string()
+}
+
+/// What `-V` or `--version` prints: the concise version for the short spelling and the
+/// long one for the long, each standing in for the other, as usage-lib answers.
+fn version(spec: &Spec, long: bool) -> &str {
+    let value = match long {
+        true => spec.long_version.as_ref().or(spec.version.as_ref()),
+        false => spec.version.as_ref().or(spec.long_version.as_ref()),
+    };
+    value.map(String::as_str).unwrap_or_default()
+}
+
+/// Whether an environment variable's text sets a switch: the spellings usage-lib reads
+/// as true, and nothing else.
+const TRUE_SPELLINGS: [&str; 4] = ["1", "true", "True", "TRUE"];
+
+fn is_true(value: &str) -> bool {
+    TRUE_SPELLINGS.contains(&value)
+}
+
+/// The `@generated` line every emitted prelude starts with.
+fn generated_comment(opts: &ShellParserOptions) -> String {
+    match &opts.source_file {
+        Some(source_file) => format!("# @generated by usage-cli from {source_file}"),
+        None => "# @generated by usage-cli from usage spec".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_for(spec: &str) -> Result<String> {
+        let spec: Spec = spec.parse().unwrap();
+        generate(
+            &spec,
+            &ShellParserOptions {
+                shell: Shell::Posix,
+                source_file: None,
+            },
+        )
+    }
+
+    #[test]
+    fn a_feature_it_cannot_express_is_named() {
+        let err = generate_for(
+            "name \"ex\"\nbin \"ex\"\ncmd \"run\" {\n    flag \"--file <f>\" conflicts=\"--stdin\"\n    flag \"--stdin\"\n}\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "shell-parser cannot express `conflicts` on `file` (on `run`)"
+        );
+    }
+
+    #[test]
+    fn a_value_it_cannot_check_is_refused_rather_than_let_through() {
+        let err =
+            generate_for("name \"ex\"\nbin \"ex\"\narg \"<port>\" type=\"uint\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "shell-parser cannot express `type` on `port` (on the root command)"
+        );
+        let err = generate_for(
+            "name \"ex\"\nbin \"ex\"\nflag \"--tag <tag>\" {\n    arg \"<tag>\" validate=\"value startsWith 'v'\"\n}\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("`validate` on `tag`"), "{err}");
+        // A string is what the prelude hands over anyway.
+        assert!(generate_for("name \"ex\"\nbin \"ex\"\narg \"<name>\" type=\"string\"\n").is_ok());
+    }
+
+    #[test]
+    fn a_name_that_is_no_variable_is_refused() {
+        let err = generate_for("name \"ex\"\nbin \"ex\"\narg \"<ö>\"\n").unwrap_err();
+        assert!(err.to_string().contains("`ö`"), "{err}");
+    }
+
+    #[test]
+    fn a_redeclared_global_shadows_the_inherited_one() {
+        let spec: Spec = "name \"ex\"\nbin \"ex\"\nflag \"-j --jobs <n>\" global=#true\ncmd \"run\" {\n    flag \"-j --jobs <n>\"\n}\n"
+            .parse()
+            .unwrap();
+        let tree = Tree::new(&spec).unwrap();
+        let run = &tree.commands[1];
+        assert_eq!(run.in_scope, vec![1]);
+        assert_eq!(
+            run.spellings[0],
+            (
+                "--jobs".to_string(),
+                Target::Flag {
+                    id: 1,
+                    negated: false
+                }
+            )
+        );
+    }
+}
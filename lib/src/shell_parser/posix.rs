@@ -0,0 +1,761 @@
+//! The prelude for `sh`, bash and zsh, which share one body.
+//!
+//! The parse loop is [`RUNTIME`], written once by hand. What this emits around it are the
+//! spec's tables, as functions the loop calls by number: `__usage_lookup 2 --jobs` says
+//! which flag `--jobs` is on command 2, `__usage_flag 5` how flag 5 takes its value.
+//! Keeping the loop fixed is what makes the output reviewable — the part that differs
+//! between two specs is data.
+
+use std::fmt::Write as _;
+
+use super::{
+    generated_comment, help, is_true, version, Command, Kind, Shell, ShellParserOptions, Target,
+    Tree,
+};
+use crate::SpecDoubleDashChoices;
+
+const RUNTIME: &str = include_str!("runtime.sh");
+
+pub(super) fn emit(tree: &Tree, shell: Shell, opts: &ShellParserOptions) -> String {
+    let mut out = String::new();
+    let source = match shell {
+        Shell::Zsh => "source",
+        _ => ".",
+    };
+    let _ = writeln!(
+        out,
+        "{}
+#
+# Parses the arguments of the script that sources it, the way `usage exec` would, and
+# exports each flag and argument as usage_<name>. Source it before reading \"$@\":
+#
+#     {source} ./{bin}.parse.{ext}
+#
+# A command line the spec does not accept prints why and exits 1. -h and --help print
+# help and exit 0.
+",
+        generated_comment(opts),
+        bin = tree.spec.bin,
+        ext = match shell {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            _ => "sh",
+        },
+    );
+    out.push_str(&body(tree, shell, Export::Environment));
+    out.push_str("\n__usage_parse \"$@\"\n");
+    out
+}
+
+/// Where a parse's results go.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(super) enum Export {
+    /// Into the sourcing shell's environment.
+    Environment,
+    /// To stdout, as `usage_<key>=<value>` entries each ending in a NUL, after one entry
+    /// reading `__usage_ok` and one holding the subcommand path — for a shell that runs
+    /// this one rather than sourcing it.
+    Print,
+}
+
+/// The tables and the runtime: everything but the header and the call.
+pub(super) fn body(tree: &Tree, shell: Shell, export_to: Export) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "__usage_bin={}\n", quote(&tree.spec.bin));
+    reset(&mut out, tree);
+    command(&mut out, tree);
+    lookup(&mut out, tree);
+    flag(&mut out, tree);
+    arg(&mut out, tree);
+    subcommand(&mut out, tree);
+    choose(&mut out, tree);
+    help_fn(&mut out, tree);
+    version_fn(&mut out, tree);
+    finish(&mut out, tree);
+    export(&mut out, tree, export_to);
+    match shell {
+        // zsh neither splits nor globs an unquoted expansion unless told to, and counts
+        // arrays from 1. The runtime assumes sh on all three, so ask for it; `-L` puts
+        // zsh's own options back when the parse returns.
+        Shell::Zsh => out.push_str(&RUNTIME.replacen(
+            "__usage_parse() {\n",
+            "__usage_parse() {\n  emulate -L sh\n",
+            1,
+        )),
+        _ => out.push_str(RUNTIME),
+    }
+    out
+}
+
+/// A shell word that reads back as exactly `s`.
+///
+/// Always quoted, unlike `shell_words::quote`: these end up in `case` patterns, where a
+/// bare `-?` would match any two characters.
+pub(super) fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Write `name() { ... }`, where `body` is already indented. A shell function cannot be
+/// empty, so one with nothing to do says `:`.
+fn function(out: &mut String, name: &str, body: &str) {
+    let body = match body.is_empty() {
+        true => "  :\n",
+        false => body,
+    };
+    let _ = writeln!(out, "{name}() {{\n{body}}}\n");
+}
+
+/// Write `case <subject> in ... esac`, with `arms` already written as `pattern) ...`.
+fn case(subject: &str, arms: &[String], otherwise: Option<&str>) -> String {
+    let mut body = format!("  case {subject} in\n");
+    for arm in arms {
+        let _ = writeln!(body, "    {arm}");
+    }
+    if let Some(otherwise) = otherwise {
+        let _ = writeln!(body, "    *) {otherwise} ;;");
+    }
+    body.push_str("  esac\n");
+    body
+}
+
+/// Forget everything a previous parse in this shell set, the caller's environment included:
+/// a `usage_jobs` inherited from a parent would otherwise read as a flag nobody gave.
+fn reset(out: &mut String, tree: &Tree) {
+    let mut body = String::new();
+    for id in 0..tree.flags.len() {
+        let _ = writeln!(body, "  unset __usage_F{id} __usage_S{id} __usage_N{id}");
+    }
+    for id in 0..tree.args.len() {
+        let _ = writeln!(body, "  unset __usage_A{id} __usage_T{id} __usage_M{id}");
+    }
+    let mut keys: Vec<&str> = tree.flags.iter().map(|f| f.key.as_str()).collect();
+    keys.extend(tree.args.iter().map(|a| a.key.as_str()));
+    keys.sort_unstable();
+    keys.dedup();
+    for key in keys {
+        let _ = writeln!(body, "  unset usage_{key}");
+    }
+    function(out, "__usage_reset", &body);
+}
+
+fn command(out: &mut String, tree: &Tree) {
+    let arms: Vec<String> = tree
+        .commands
+        .iter()
+        .enumerate()
+        .map(|(id, cmd)| {
+            format!(
+                "{id}) __usage_unknown={} __usage_helpword={} ;;",
+                cmd.unknown.as_str(),
+                cmd.help_word as u8
+            )
+        })
+        .collect();
+    function(out, "__usage_command", &case("$1", &arms, None));
+}
+
+/// Which flag a spelling names on a command, grouped so each target is one arm.
+fn lookup(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (id, cmd) in tree.commands.iter().enumerate() {
+        let mut targets: Vec<(Target, Vec<&str>)> = vec![];
+        for (spelling, target) in &cmd.spellings {
+            match targets.iter_mut().find(|(t, _)| t == target) {
+                Some((_, spellings)) => spellings.push(spelling),
+                None => targets.push((*target, vec![spelling])),
+            }
+        }
+        for (target, spellings) in targets {
+            let pattern = spellings
+                .iter()
+                .map(|s| quote(&format!("{id}:{s}")))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            let action = match target {
+                Target::Flag { id, negated: false } => format!("__usage_f={id}"),
+                Target::Flag { id, negated: true } => {
+                    format!("__usage_f={id} __usage_negated=1")
+                }
+                Target::Builtin(builtin) => format!("__usage_f={builtin}"),
+            };
+            arms.push(format!("{pattern}) {action} ;;"));
+        }
+    }
+    let body = format!(
+        "  __usage_negated=0\n{}",
+        case("$1:$2", &arms, Some("return 1"))
+    );
+    function(out, "__usage_lookup", &body);
+}
+
+/// How each flag takes its value. Only what differs from a plain switch is written.
+fn flag(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (id, flag) in tree.flags.iter().enumerate() {
+        let mut set = vec![];
+        match flag.kind() {
+            Kind::Switch => {}
+            Kind::Count => set.push("__usage_kind=c".to_string()),
+            Kind::Value => set.push("__usage_kind=v".to_string()),
+        }
+        if flag.list() {
+            set.push("__usage_list=1".to_string());
+        }
+        if let Some(arg) = flag.flag.arg.as_ref().filter(|arg| arg.var) {
+            set.push("__usage_variadic=1".to_string());
+            if let Some(max) = arg.var_max {
+                set.push(format!("__usage_max={max}"));
+            }
+            if let Some(min) = arg.var_min {
+                set.push(format!("__usage_min={min}"));
+            }
+        }
+        if flag.flag.allow_hyphen_values() {
+            set.push("__usage_hyphen=1".to_string());
+        }
+        if flag.flag.require_equals {
+            set.push("__usage_equals=1".to_string());
+        }
+        if let Some(missing) = &flag.flag.default_missing {
+            set.push("__usage_hasmissing=1".to_string());
+            set.push(format!("__usage_dmissing={}", quote(missing)));
+        }
+        if flag.flag.bool_value {
+            set.push("__usage_boolvalue=1".to_string());
+        }
+        if flag
+            .flag
+            .arg
+            .as_ref()
+            .is_some_and(|arg| arg.allow_negative_numbers)
+        {
+            set.push("__usage_negok=1".to_string());
+        }
+        set.push(format!("__usage_label={}", quote(&flag.label())));
+        arms.push(format!("{id}) {} ;;", set.join(" ")));
+    }
+    let defaults = [
+        "kind=b",
+        "list=0",
+        "variadic=0",
+        "max=",
+        "min=",
+        "hyphen=0",
+        "equals=0",
+        "hasmissing=0",
+        "dmissing=",
+        "boolvalue=0",
+        "negok=0",
+    ];
+    let mut body = String::new();
+    for default in defaults {
+        let _ = writeln!(body, "  __usage_{default}");
+    }
+    body.push_str(&case("$1", &arms, None));
+    function(out, "__usage_flag", &body);
+}
+
+/// The argument at each position of each command.
+fn arg(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (cmd_id, cmd) in tree.commands.iter().enumerate() {
+        for (position, &id) in cmd.args.iter().enumerate() {
+            let arg = &tree.args[id];
+            let mut set = vec![format!("__usage_a={id}")];
+            if arg.arg.var {
+                set.push("__usage_avar=1".to_string());
+                if let Some(max) = arg.arg.var_max {
+                    set.push(format!("__usage_amax={max}"));
+                }
+            }
+            match arg.arg.double_dash {
+                SpecDoubleDashChoices::Optional => {}
+                SpecDoubleDashChoices::Required => set.push("__usage_dd=r".to_string()),
+                SpecDoubleDashChoices::Automatic => set.push("__usage_dd=a".to_string()),
+                SpecDoubleDashChoices::Preserve => set.push("__usage_dd=p".to_string()),
+            }
+            if arg.arg.allow_negative_numbers {
+                set.push("__usage_anegok=1".to_string());
+            }
+            set.push(format!("__usage_label={}", quote(&arg.label())));
+            arms.push(format!("{cmd_id}:{position}) {} ;;", set.join(" ")));
+        }
+    }
+    let body = format!(
+        "  __usage_avar=0\n  __usage_amax=\n  __usage_dd=o\n  __usage_anegok=0\n{}",
+        case("$1:$2", &arms, Some("return 1"))
+    );
+    function(out, "__usage_arg", &body);
+}
+
+/// Which subcommand a word selects. Every command's names come before any alias, since a
+/// `case` takes the first arm that matches and a name outranks another command's alias.
+fn subcommand(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (id, cmd) in tree.commands.iter().enumerate() {
+        let select = |sub: usize, name: &str, spelling: &str| {
+            format!(
+                "{}) __usage_sub={sub} __usage_subname={} ;;",
+                quote(&format!("{id}:{spelling}")),
+                quote(name)
+            )
+        };
+        for &(sub, name) in &cmd.subcommands {
+            arms.push(select(sub, name, name));
+        }
+        for &(sub, name) in &cmd.subcommands {
+            let sub_cmd = tree.commands[sub].cmd;
+            for alias in sub_cmd.aliases.iter().chain(&sub_cmd.hidden_aliases) {
+                arms.push(select(sub, name, alias));
+            }
+        }
+    }
+    function(
+        out,
+        "__usage_subcommand",
+        &case("$1:$2", &arms, Some("return 1")),
+    );
+}
+
+/// The message `choices` refuses a value with, as a shell word with the value spliced in.
+fn invalid(label: &str, value: &str, choices: &[String]) -> String {
+    format!(
+        "{}{value}{}",
+        quote(&format!("invalid value for {label}: ")),
+        quote(&format!(" (expected one of: {})", choices.join(", ")))
+    )
+}
+
+/// The strict `choices` of every flag and argument that declares them.
+fn choose(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    let mut arm = |target: String, label: String, choices: &[String]| {
+        let pattern = match choices.is_empty() {
+            // Nothing is one of no choices, and `case` has no empty alternation.
+            true => "*) __usage_fail ".to_string() + &invalid(&label, "\"$2\"", choices),
+            false => format!(
+                "{}) ;;\n          *) __usage_fail {}",
+                choices
+                    .iter()
+                    .map(|c| quote(c))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                invalid(&label, "\"$2\"", choices)
+            ),
+        };
+        arms.push(format!(
+            "{target})\n        case $2 in\n          {pattern} ;;\n        esac\n        ;;"
+        ));
+    };
+    for (id, flag) in tree.flags.iter().enumerate() {
+        if let Some(choices) = flag.choices() {
+            arm(format!("F{id}"), flag.label(), choices);
+        }
+    }
+    for (id, a) in tree.args.iter().enumerate() {
+        if let Some(choices) = a.choices() {
+            arm(format!("A{id}"), a.label(), choices);
+        }
+    }
+    function(out, "__usage_choose", &case("$1", &arms, None));
+}
+
+fn help_fn(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (id, cmd) in tree.commands.iter().enumerate() {
+        for long in [false, true] {
+            arms.push(format!(
+                "{id}:{}) printf '%s\\n' {} ;;",
+                long as u8,
+                quote(&help(tree.spec, cmd.cmd, long))
+            ));
+        }
+    }
+    function(out, "__usage_help", &case("$1:$2", &arms, None));
+}
+
+fn version_fn(out: &mut String, tree: &Tree) {
+    let arms = [false, true].map(|long| {
+        format!(
+            "{}) printf '%s\\n' {} ;;",
+            long as u8,
+            quote(version(tree.spec, long))
+        )
+    });
+    function(out, "__usage_version", &case("$1", &arms, None));
+}
+
+/// A shell test that no variable among `names` is set. Set-but-empty counts as set, which
+/// is what usage-lib reads.
+fn none_set(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("[ -z \"${{{name}+x}}\" ]"))
+        .collect::<Vec<_>>()
+        .join(" && ")
+}
+
+/// The bounds a list of `count` values breaks, as the complaint usage-lib makes about it.
+fn bounds(label: &str, count: usize, min: Option<usize>, max: Option<usize>) -> Option<String> {
+    if min.is_some_and(|min| count < min) {
+        return Some(format!("too few values for {label}"));
+    }
+    if max.is_some_and(|max| count > max) {
+        return Some(format!("too many values for {label}"));
+    }
+    None
+}
+
+/// Everything decided after the last token, for the command the line selected.
+///
+/// In usage-lib's order: the environment and defaults for the positionals, then the
+/// environment for every flag, then the flags' defaults — each checked against `choices`
+/// as it lands, and a bad one ends the parse. Then what is still missing, which reads the
+/// spec rather than what landed: a `required` flag with a `default` is never missing, an
+/// argument only counts its primary `env`.
+fn finish(out: &mut String, tree: &Tree) {
+    let mut arms = vec![];
+    for (id, cmd) in tree.commands.iter().enumerate() {
+        let mut body = String::new();
+        arg_fallbacks(&mut body, tree, cmd);
+        flag_env(&mut body, tree, cmd);
+        flag_defaults(&mut body, tree, cmd);
+        requirements(&mut body, tree, id);
+        if body.is_empty() {
+            continue;
+        }
+        arms.push(format!("{id})\n{body}      ;;"));
+    }
+    function(out, "__usage_finish", &case("$__usage_c", &arms, None));
+}
+
+/// Line `lines` into `body` at the depth of a `case` arm in [`finish`].
+fn push(body: &mut String, lines: &str) {
+    for line in lines.lines() {
+        let _ = writeln!(body, "      {line}");
+    }
+}
+
+/// `if`/`elif` over the variables in `names`, running `fill` with the first one set and
+/// `otherwise` where none is.
+fn first_set(names: &[&str], fill: impl Fn(&str) -> String, otherwise: Option<String>) -> String {
+    let mut out = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let keyword = if i == 0 { "if" } else { "elif" };
+        let _ = writeln!(out, "{keyword} [ -n \"${{{name}+x}}\" ]; then");
+        out.push_str(&indent(&fill(name)));
+    }
+    match (out.is_empty(), otherwise) {
+        (true, otherwise) => otherwise.map(|o| o + "\n").unwrap_or_default(),
+        (false, Some(otherwise)) => format!("{out}else\n{}fi\n", indent(&otherwise)),
+        (false, None) => out + "fi\n",
+    }
+}
+
+fn arg_fallbacks(body: &mut String, tree: &Tree, cmd: &Command) {
+    for &id in &cmd.args {
+        let a = &tree.args[id];
+        let env = a.env();
+        let default = &a.arg.default;
+        if env.is_empty() && default.is_empty() {
+            continue;
+        }
+        let (min, max) = (a.arg.var_min, a.arg.var_max);
+        let from_env = |name: &str| {
+            let mut fill = format!("__usage_fill_arg {id} \"${name}\" {}", a.arg.var as u8);
+            if let Some(complaint) = bounds(&a.label(), 1, min, max).filter(|_| a.arg.var) {
+                let _ = write!(fill, "\n__usage_later {}", quote(&complaint));
+            }
+            fill
+        };
+        let from_default = (!default.is_empty()).then(|| {
+            let values = match a.arg.var {
+                true => &default[..],
+                false => &default[..1],
+            };
+            let mut fill = match a.choices().and_then(|c| refused(values, c)) {
+                Some(bad) => format!(
+                    "__usage_fail {}",
+                    invalid(&a.label(), &quote(bad), a.choices().unwrap_or_default())
+                ),
+                None => format!(
+                    "__usage_A{id}={} __usage_T{id}=2",
+                    quote(&list_or_one(values, a.arg.var))
+                ),
+            };
+            let complaint = bounds(&a.label(), values.len(), min, max).filter(|_| a.arg.var);
+            if let Some(complaint) = complaint {
+                let _ = write!(fill, "\n__usage_later {}", quote(&complaint));
+            }
+            fill
+        });
+        let inner = first_set(&env, from_env, from_default);
+        push(
+            body,
+            &format!(
+                "if [ -z \"${{__usage_T{id}-}}\" ]; then\n{}fi",
+                indent(&inner)
+            ),
+        );
+    }
+}
+
+fn flag_env(body: &mut String, tree: &Tree, cmd: &Command) {
+    for &id in &cmd.in_scope {
+        let flag = &tree.flags[id];
+        let env = flag.env();
+        if env.is_empty() {
+            continue;
+        }
+        let from_env = |name: &str| match flag.kind() {
+            Kind::Value => {
+                let mut fill = format!("__usage_fill_flag {id} \"${name}\"");
+                for complaint in value_bounds(flag, 1) {
+                    let _ = write!(fill, "\n__usage_later {}", quote(&complaint));
+                }
+                fill
+            }
+            Kind::Switch | Kind::Count => format!("__usage_fill_switch {id} \"${name}\""),
+        };
+        let inner = first_set(&env, from_env, None);
+        push(
+            body,
+            &format!(
+                "if [ -z \"${{__usage_S{id}-}}\" ]; then\n{}fi",
+                indent(&inner)
+            ),
+        );
+    }
+}
+
+fn flag_defaults(body: &mut String, tree: &Tree, cmd: &Command) {
+    for &id in &cmd.in_scope {
+        let flag = &tree.flags[id];
+        let default = flag.default();
+        if default.is_empty() {
+            continue;
+        }
+        let fill = match flag.kind() {
+            Kind::Value => {
+                let values = match flag.list() {
+                    true => default,
+                    false => &default[..1],
+                };
+                let mut fill = match flag.choices().and_then(|c| refused(values, c)) {
+                    Some(bad) => format!(
+                        "__usage_fail {}",
+                        invalid(
+                            &flag.label(),
+                            &quote(bad),
+                            flag.choices().unwrap_or_default()
+                        )
+                    ),
+                    None => format!(
+                        "__usage_F{id}={} __usage_S{id}=2",
+                        quote(&list_or_one(values, flag.list()))
+                    ),
+                };
+                for complaint in value_bounds(flag, values.len()) {
+                    let _ = write!(fill, "\n__usage_later {}", quote(&complaint));
+                }
+                fill
+            }
+            // A `var` switch counts the defaults that read as true; anything else is one
+            // switch, even a `count`, because that is the value usage-lib binds.
+            _ if flag.flag.var => {
+                let trues = default.iter().filter(|d| is_true(d)).count();
+                let mut fill = format!("__usage_F{id}={trues} __usage_S{id}=2");
+                if let Some(complaint) = bounds(
+                    &flag.label(),
+                    default.len(),
+                    flag.flag.var_min,
+                    flag.flag.var_max,
+                ) {
+                    let _ = write!(fill, "\n__usage_later {}", quote(&complaint));
+                }
+                fill
+            }
+            _ => format!("__usage_F{id}={} __usage_S{id}=2", is_true(&default[0])),
+        };
+        push(
+            body,
+            &format!(
+                "if [ -z \"${{__usage_S{id}-}}\" ]; then\n{}fi",
+                indent(&fill)
+            ),
+        );
+    }
+}
+
+/// The complaints a fallback of `count` values earns a value flag: `var_min` and
+/// `var_max` on the flag for a repeatable one, and on its argument for a variadic one.
+fn value_bounds(flag: &super::Flag, count: usize) -> Vec<String> {
+    let label = flag.label();
+    let mut out = vec![];
+    if flag.flag.var {
+        out.extend(bounds(&label, count, flag.flag.var_min, flag.flag.var_max));
+    }
+    if let Some(arg) = flag.flag.arg.as_ref().filter(|arg| arg.var) {
+        out.extend(bounds(&label, count, arg.var_min, arg.var_max));
+    }
+    out
+}
+
+/// What is still missing or out of bounds once the fallbacks have landed.
+fn requirements(body: &mut String, tree: &Tree, id: usize) {
+    let cmd = &tree.commands[id];
+    if cmd.cmd.subcommand_required && !cmd.subcommands.is_empty() {
+        let mut names: Vec<&str> = cmd
+            .subcommands
+            .iter()
+            .filter(|(sub, _)| !tree.commands[*sub].cmd.hide)
+            .map(|(_, name)| *name)
+            .collect();
+        names.sort_unstable();
+        let message = format!("missing subcommand (one of: {})", names.join(", "));
+        push(body, &format!("__usage_fail {}", quote(&message)));
+    }
+    // Every command on the way here, outermost first: a parent's positional that stayed
+    // empty because a word selected a subcommand is as missing as one of this command's.
+    let path = path_to(tree, id);
+    let path_args = || path.iter().flat_map(|&c| &tree.commands[c].args);
+    for &arg_id in path_args() {
+        let a = &tree.args[arg_id];
+        if !a.arg.required || !a.arg.default.is_empty() {
+            continue;
+        }
+        let mut test = format!("[ \"${{__usage_T{arg_id}-}}\" != 1 ]");
+        if let Some(env) = &a.arg.env {
+            let _ = write!(test, " && {}", none_set(&[env]));
+        }
+        let message = format!("missing required argument: {}", a.label());
+        push(
+            body,
+            &format!("if {test}; then\n  __usage_fail {}\nfi", quote(&message)),
+        );
+    }
+    for &flag_id in &cmd.in_scope {
+        let flag = &tree.flags[flag_id];
+        if !flag.flag.required || !flag.default().is_empty() {
+            continue;
+        }
+        let mut test = format!("[ \"${{__usage_S{flag_id}-}}\" != 1 ]");
+        let env = flag.env();
+        if !env.is_empty() {
+            let _ = write!(test, " && {}", none_set(&env));
+        }
+        let message = format!("missing required flag: {}", flag.label());
+        push(
+            body,
+            &format!("if {test}; then\n  __usage_fail {}\nfi", quote(&message)),
+        );
+    }
+    for &arg_id in path_args() {
+        let a = &tree.args[arg_id];
+        if a.arg.var {
+            let given = format!("[ \"${{__usage_T{arg_id}-}}\" = 1 ]");
+            let count = format!("\"$__usage_M{arg_id}\"");
+            check_bounds(
+                body,
+                &given,
+                &count,
+                &a.label(),
+                a.arg.var_min,
+                a.arg.var_max,
+            );
+        }
+    }
+    for &flag_id in &cmd.in_scope {
+        let flag = &tree.flags[flag_id];
+        if flag.flag.var {
+            let given = format!("[ \"${{__usage_S{flag_id}-}}\" = 1 ]");
+            let count = format!("\"$__usage_N{flag_id}\"");
+            let (min, max) = (flag.flag.var_min, flag.flag.var_max);
+            check_bounds(body, &given, &count, &flag.label(), min, max);
+        }
+    }
+}
+
+fn check_bounds(
+    body: &mut String,
+    given: &str,
+    count: &str,
+    label: &str,
+    min: Option<usize>,
+    max: Option<usize>,
+) {
+    let checks = [("-lt", min, "too few"), ("-gt", max, "too many")];
+    for (op, bound, complaint) in checks {
+        if let Some(bound) = bound {
+            let message = quote(&format!("{complaint} values for {label}"));
+            push(
+                body,
+                &format!(
+                    "if {given} && [ {count} {op} {bound} ]; then\n  __usage_fail {message}\nfi"
+                ),
+            );
+        }
+    }
+}
+
+/// The commands from the root to `id`, root first.
+fn path_to(tree: &Tree, id: usize) -> Vec<usize> {
+    let mut path = vec![id];
+    while let Some(parent) = tree
+        .commands
+        .iter()
+        .position(|c| c.subcommands.iter().any(|(sub, _)| *sub == path[0]))
+    {
+        path.insert(0, parent);
+    }
+    path
+}
+
+/// Export whatever was bound. Arguments after flags, so an argument wins a name they share,
+/// as it does in `usage exec`.
+fn export(out: &mut String, tree: &Tree, export_to: Export) {
+    let mut body = String::new();
+    if export_to == Export::Print {
+        body.push_str("  printf '__usage_ok\\0%s\\0' \"$__usage_path\"\n");
+    }
+    let bound = tree
+        .flags
+        .iter()
+        .enumerate()
+        .map(|(id, flag)| (format!("S{id}"), format!("F{id}"), &flag.key))
+        .chain(
+            (tree.args.iter().enumerate())
+                .map(|(id, arg)| (format!("T{id}"), format!("A{id}"), &arg.key)),
+        );
+    for (set, value, key) in bound {
+        let action = match export_to {
+            Export::Environment => format!("export usage_{key}=\"$__usage_{value}\""),
+            Export::Print => format!("printf 'usage_{key}=%s\\0' \"$__usage_{value}\""),
+        };
+        let _ = writeln!(
+            body,
+            "  if [ -n \"${{__usage_{set}-}}\" ]; then {action}; fi"
+        );
+    }
+    function(out, "__usage_export", &body);
+}
+
+/// The first of `values` that `choices` does not accept.
+fn refused<'a>(values: &'a [String], choices: &[String]) -> Option<&'a str> {
+    values
+        .iter()
+        .find(|v| !choices.contains(v))
+        .map(String::as_str)
+}
+
+/// Values as the prelude holds them: a list joined the way `usage exec` joins one.
+fn list_or_one(values: &[String], list: bool) -> String {
+    match list {
+        true => shell_words::join(values),
+        false => values[0].clone(),
+    }
+}
+
+fn indent(lines: &str) -> String {
+    lines.lines().map(|line| format!("  {line}\n")).collect()
+}
@@ -0,0 +1,445 @@
+
+__usage_nl='
+'
+__usage_tab=$(printf '\t')
+
+__usage_fail() {
+  printf '%s: %s\n' "$__usage_bin" "$1" >&2
+  exit 1
+}
+
+# What shell_words::join writes for one word, so a list reads the same as under `usage exec`.
+__usage_quote() {
+  case $1 in
+    '') __usage_q="''" ;;
+    *[\|\&\;\<\>\(\)\$\`\\\"\ \*\?\[\#\~\=\%\']* | *"$__usage_nl"* | *"$__usage_tab"*)
+      __usage_r=$1
+      __usage_q=
+      while :; do
+        case $__usage_r in
+          *\'*)
+            __usage_q=$__usage_q${__usage_r%%\'*}"'\\''"
+            __usage_r=${__usage_r#*\'}
+            ;;
+          *) break ;;
+        esac
+      done
+      __usage_q="'$__usage_q$__usage_r'"
+      ;;
+    *) __usage_q=$1 ;;
+  esac
+}
+
+__usage_flaglike() {
+  case $1 in
+    -?*) return 0 ;;
+    *) return 1 ;;
+  esac
+}
+
+# Digits, at most one `.`, and an optional exponent: `-1`, `-2.5`, `-1e5`, not `-inf`.
+__usage_negative() {
+  case $1 in
+    -*) ;;
+    *) return 1 ;;
+  esac
+  __usage_m=${1#-}
+  __usage_x=
+  case $__usage_m in
+    *[eE]*)
+      __usage_x=${__usage_m#*[eE]}
+      __usage_m=${__usage_m%%[eE]*}
+      __usage_x=${__usage_x#[+-]}
+      case $__usage_x in
+        '' | *[!0-9]*) return 1 ;;
+      esac
+      ;;
+  esac
+  case $__usage_m in
+    *[!0-9.]* | *.*.*) return 1 ;;
+    *[0-9]*) return 0 ;;
+    *) return 1 ;;
+  esac
+}
+
+__usage_set() {
+  eval "__usage_$1=\$2"
+}
+
+__usage_get() {
+  eval "__usage_v=\${__usage_$1-}"
+}
+
+# One value for flag $1: a list grows, anything else is replaced.
+__usage_bind_flag() {
+  __usage_choose "F$1" "$2"
+  __usage_set "S$1" 1
+  if [ "$__usage_list" = 1 ]; then
+    __usage_quote "$2"
+    __usage_get "F$1"
+    if [ -n "$__usage_v" ]; then
+      __usage_set "F$1" "$__usage_v $__usage_q"
+    else
+      __usage_set "F$1" "$__usage_q"
+    fi
+    __usage_get "N$1"
+    __usage_set "N$1" $((${__usage_v:-0} + 1))
+  else
+    __usage_set "F$1" "$2"
+  fi
+}
+
+# A flag that holds no value: $2 is true or false.
+__usage_bind_switch() {
+  __usage_set "S$1" 1
+  __usage_get "N$1"
+  __usage_set "N$1" $((${__usage_v:-0} + 1))
+  if [ "$__usage_kind" = c ]; then
+    __usage_get "F$1"
+    if [ "$2" = true ]; then
+      __usage_set "F$1" $((${__usage_v:-0} + 1))
+    else
+      __usage_set "F$1" "${__usage_v:-0}"
+    fi
+  else
+    __usage_set "F$1" "$2"
+  fi
+}
+
+# Flag $1 from the environment or a default, where no token gave it.
+__usage_fill_flag() {
+  __usage_flag "$1"
+  __usage_bind_flag "$1" "$2"
+  __usage_set "S$1" 2
+}
+
+# A switch from the environment: the spellings usage-lib reads as true, and false otherwise.
+__usage_fill_switch() {
+  case $2 in
+    1 | true | True | TRUE) __usage_set "F$1" true ;;
+    *) __usage_set "F$1" false ;;
+  esac
+  __usage_set "S$1" 2
+}
+
+# Argument $1 from the environment: one value, which a variadic argument holds as a list.
+__usage_fill_arg() {
+  __usage_choose "A$1" "$2"
+  if [ "$3" = 1 ]; then
+    __usage_quote "$2"
+    __usage_set "A$1" "$__usage_q"
+  else
+    __usage_set "A$1" "$2"
+  fi
+  __usage_set "T$1" 2
+}
+
+# A complaint usage-lib only makes once the others have had their turn.
+__usage_later() {
+  if [ -z "$__usage_late" ]; then
+    __usage_late=$1
+  fi
+}
+
+# A bool_value switch's attached value, read through the spelling that carried it.
+__usage_switch_value() {
+  if [ "$__usage_attached" = 0 ] || [ "$__usage_boolvalue" = 0 ]; then
+    __usage_sv=$1
+    return
+  fi
+  case $__usage_value in
+    true) __usage_sv=$1 ;;
+    false) if [ "$1" = true ]; then __usage_sv=false; else __usage_sv=true; fi ;;
+    *) __usage_fail "invalid value for $__usage_label: $__usage_value (expected one of: true, false)" ;;
+  esac
+}
+
+# A value flag with nothing after it: its default_missing, or an error.
+__usage_missing() {
+  if [ "$__usage_hasmissing" = 1 ]; then
+    __usage_value=$__usage_dmissing
+  else
+    __usage_fail "missing value for $__usage_label"
+  fi
+}
+
+# Bind the value just read for flag $1, and keep collecting if its argument is variadic.
+__usage_took() {
+  __usage_bind_flag "$1" "$__usage_value"
+  if [ "$__usage_variadic" = 1 ]; then
+    __usage_col=$1
+    __usage_cn=1
+    __usage_cmax=$__usage_max
+    __usage_cmin=$__usage_min
+    __usage_cneg=$__usage_negok
+    __usage_clabel=$__usage_label
+    if [ -n "$__usage_cmax" ] && [ "$__usage_cn" -ge "$__usage_cmax" ]; then
+      __usage_collected
+    fi
+  fi
+}
+
+# A variadic flag's run is over: it has to have taken its var_min.
+__usage_collected() {
+  if [ -n "$__usage_col" ] && [ -n "$__usage_cmin" ] && [ "$__usage_cn" -lt "$__usage_cmin" ]; then
+    __usage_fail "too few values for $__usage_clabel"
+  fi
+  __usage_col=
+}
+
+__usage_descend() {
+  __usage_c=$1
+  __usage_path="$__usage_path $2"
+  __usage_ap=0
+  __usage_at=0
+  __usage_af=0
+  __usage_command "$__usage_c"
+}
+
+# Offer a word to the current command's positionals.
+__usage_word() {
+  if ! __usage_arg "$__usage_c" "$__usage_ap"; then
+    __usage_fail "unexpected argument: $1"
+  fi
+  if [ "$__usage_dd" = r ] && [ "$__usage_sep" = 0 ]; then
+    __usage_fail "argument $__usage_label can only be given after --"
+  fi
+  __usage_af=1
+  if [ "$__usage_dd" = a ]; then
+    __usage_stop=1
+  fi
+  __usage_choose "A$__usage_a" "$1"
+  __usage_set "T$__usage_a" 1
+  if [ "$__usage_avar" = 1 ]; then
+    __usage_quote "$1"
+    __usage_get "A$__usage_a"
+    if [ -n "$__usage_v" ]; then
+      __usage_set "A$__usage_a" "$__usage_v $__usage_q"
+    else
+      __usage_set "A$__usage_a" "$__usage_q"
+    fi
+    __usage_get "M$__usage_a"
+    __usage_set "M$__usage_a" $((${__usage_v:-0} + 1))
+    __usage_at=$((__usage_at + 1))
+    if [ -n "$__usage_amax" ] && [ "$__usage_at" -ge "$__usage_amax" ]; then
+      __usage_ap=$((__usage_ap + 1))
+      __usage_at=0
+    fi
+  else
+    __usage_set "A$__usage_a" "$1"
+    __usage_ap=$((__usage_ap + 1))
+    __usage_at=0
+  fi
+}
+
+# Whether every letter of a short token names a flag, stopping at one that takes a value.
+__usage_bundle_known() {
+  __usage_rest=${1#-}
+  while [ -n "$__usage_rest" ]; do
+    __usage_letter=${__usage_rest%"${__usage_rest#?}"}
+    __usage_rest=${__usage_rest#?}
+    if ! __usage_lookup "$__usage_c" "-$__usage_letter"; then
+      return 1
+    fi
+    # `-?` is a whole token, not a letter anyone bundles.
+    if [ "$__usage_f" = help ] && [ "$__usage_letter" = '?' ] && [ "$1" != '-?' ]; then
+      return 1
+    fi
+    case $__usage_f in
+      [0-9]*)
+        __usage_flag "$__usage_f"
+        if [ "$__usage_kind" = v ]; then
+          return 0
+        fi
+        ;;
+    esac
+  done
+  return 0
+}
+
+# The built-in flags: help for this command, or the program's version.
+__usage_builtin() {
+  case $1 in
+    help) __usage_help "$__usage_c" 0 ;;
+    long-help) __usage_help "$__usage_c" 1 ;;
+    version) __usage_version 0 ;;
+    long-version) __usage_version 1 ;;
+  esac
+  exit 0
+}
+
+__usage_parse() {
+  __usage_reset
+  __usage_c=0
+  __usage_path=
+  __usage_stop=0
+  __usage_sep=0
+  __usage_ap=0
+  __usage_at=0
+  __usage_af=0
+  __usage_col=
+  __usage_late=
+  __usage_command 0
+  while [ $# -gt 0 ]; do
+    __usage_t=$1
+    # A variadic flag keeps taking words until one of them could be something else.
+    if [ -n "$__usage_col" ]; then
+      if [ "$__usage_t" != -- ] && { ! __usage_flaglike "$__usage_t" || { [ "$__usage_cneg" = 1 ] && __usage_negative "$__usage_t"; }; }; then
+        shift
+        __usage_flag "$__usage_col"
+        __usage_bind_flag "$__usage_col" "$__usage_t"
+        __usage_cn=$((__usage_cn + 1))
+        if [ -n "$__usage_cmax" ] && [ "$__usage_cn" -ge "$__usage_cmax" ]; then
+          __usage_collected
+        fi
+        continue
+      fi
+      __usage_collected
+    fi
+    shift
+    __usage_want=
+    if [ "$__usage_stop" = 1 ] && { [ "$__usage_t" != -- ] || [ "$__usage_sep" = 1 ]; }; then
+      __usage_word "$__usage_t"
+      continue
+    fi
+    if [ "$__usage_t" = -- ]; then
+      if __usage_arg "$__usage_c" "$__usage_ap" && [ "$__usage_dd" = p ]; then
+        __usage_word "$__usage_t"
+        continue
+      fi
+      __usage_stop=1
+      __usage_sep=1
+      # An explicit separator unlocks an argument that required one.
+      __usage_i=$__usage_ap
+      while __usage_arg "$__usage_c" "$__usage_i"; do
+        if [ "$__usage_dd" = r ]; then
+          __usage_ap=$__usage_i
+          __usage_at=0
+          break
+        fi
+        __usage_i=$((__usage_i + 1))
+      done
+      continue
+    fi
+    __usage_numeric=0
+    case $__usage_t in
+      -[0-9])
+        if __usage_lookup "$__usage_c" "$__usage_t"; then
+          __usage_numeric=1
+        fi
+        ;;
+    esac
+    if [ "$__usage_numeric" = 0 ] && __usage_negative "$__usage_t" && __usage_arg "$__usage_c" "$__usage_ap" && [ "$__usage_anegok" = 1 ]; then
+      __usage_word "$__usage_t"
+      continue
+    fi
+    if __usage_flaglike "$__usage_t"; then
+      case $__usage_t in
+        --*)
+          __usage_name=${__usage_t%%=*}
+          __usage_attached=0
+          __usage_value=
+          case $__usage_t in
+            *=*)
+              __usage_attached=1
+              __usage_value=${__usage_t#*=}
+              ;;
+          esac
+          if __usage_lookup "$__usage_c" "$__usage_name"; then
+            case $__usage_f in
+              [0-9]*) ;;
+              *) __usage_builtin "$__usage_f" ;;
+            esac
+            __usage_flag "$__usage_f"
+            if [ "$__usage_negated" = 1 ]; then
+              __usage_switch_value false
+              __usage_bind_switch "$__usage_f" "$__usage_sv"
+            elif [ "$__usage_kind" != v ]; then
+              __usage_switch_value true
+              __usage_bind_switch "$__usage_f" "$__usage_sv"
+            elif [ "$__usage_attached" = 1 ]; then
+              __usage_took "$__usage_f"
+            else
+              __usage_want=$__usage_f
+            fi
+          elif [ "$__usage_unknown" = error ]; then
+            __usage_fail "unknown flag: $__usage_t"
+          else
+            __usage_word "$__usage_t"
+            continue
+          fi
+          ;;
+        *)
+          # Check the whole bundle before applying any of it: one unknown letter makes the
+          # token a word, with none of its letters set.
+          if ! __usage_bundle_known "$__usage_t"; then
+            if [ "$__usage_unknown" = error ]; then
+              __usage_fail "unknown flag: $__usage_t"
+            fi
+            __usage_word "$__usage_t"
+            continue
+          fi
+          __usage_rest=${__usage_t#-}
+          while [ -n "$__usage_rest" ]; do
+            __usage_letter=${__usage_rest%"${__usage_rest#?}"}
+            __usage_rest=${__usage_rest#?}
+            __usage_lookup "$__usage_c" "-$__usage_letter"
+            case $__usage_f in
+              [0-9]*) ;;
+              *) __usage_builtin "$__usage_f" ;;
+            esac
+            __usage_flag "$__usage_f"
+            if [ "$__usage_kind" != v ]; then
+              __usage_attached=0
+              __usage_bind_switch "$__usage_f" true
+              continue
+            fi
+            # A letter that takes a value ends the token.
+            if [ -z "$__usage_rest" ]; then
+              __usage_want=$__usage_f
+            else
+              __usage_value=${__usage_rest#=}
+              __usage_took "$__usage_f"
+            fi
+            break
+          done
+          ;;
+      esac
+      if [ -n "$__usage_want" ]; then
+        __usage_flag "$__usage_want"
+        if [ "$__usage_equals" = 1 ]; then
+          __usage_missing
+        elif [ $# -gt 0 ] && { [ "$__usage_hyphen" = 1 ] || ! __usage_flaglike "$1" || { [ "$__usage_negok" = 1 ] && __usage_negative "$1"; }; }; then
+          __usage_value=$1
+          shift
+        else
+          __usage_missing
+        fi
+        __usage_took "$__usage_want"
+      fi
+      continue
+    fi
+    # A word selects a subcommand only while this command has bound no positional.
+    if [ "$__usage_af" = 0 ]; then
+      if __usage_subcommand "$__usage_c" "$__usage_t"; then
+        __usage_descend "$__usage_sub" "$__usage_subname"
+        continue
+      fi
+      if [ "$__usage_t" = help ] && [ "$__usage_helpword" = 1 ]; then
+        while [ $# -gt 0 ] && __usage_subcommand "$__usage_c" "$1"; do
+          __usage_c=$__usage_sub
+          shift
+        done
+        __usage_help "$__usage_c" 1
+        exit 0
+      fi
+    fi
+    __usage_word "$__usage_t"
+  done
+  __usage_collected
+  __usage_finish
+  if [ -n "$__usage_late" ]; then
+    __usage_fail "$__usage_late"
+  fi
+  __usage_export
+}
@@ -6,10 +6,13 @@ use std::str::FromStr;
 
 use crate::error::UsageErr;
 use crate::spec::builder::SpecArgBuilder;
-use crate::spec::context::ParsingContext;
+use crate::spec::config_type::Base;
+use crate::spec::config_type::SpecConfigType;
+use crate::spec::context::{ParsingContext, SourceLocation};
 use crate::spec::effect::{SpecCommandEffect, EFFECT_VALUES};
-use crate::spec::helpers::{string_entry, NodeHelper};
+use crate::spec::helpers::{string_entry, NodeHelper, ParseEntry};
 use crate::spec::is_false;
+use crate::spec::stability::{stability, SpecStability};
 use crate::{string, SpecChoices};
 #[cfg(feature = "clap")]
 use crate::{SpecChoice, SpecChoiceAlias};
@@ -121,6 +124,10 @@ pub struct SpecArg {
     /// Hide this argument only from long help.
     #[serde(skip_serializing_if = "is_false")]
     pub hide_long_help: bool,
+    /// A secret: its value is masked wherever usage renders one — `usage explain`, parse
+    /// errors, generated docs — and its environment value is never shown in help.
+    #[serde(skip_serializing_if = "is_false")]
+    pub sensitive: bool,
     /// Arguments and flags that cannot be given alongside this positional.
     ///
     /// A bare selector names another positional by name; flag selectors keep their
@@ -151,6 +158,16 @@ pub struct SpecArg {
     /// Valid choices for this argument
     #[serde(skip_serializing_if = "Option::is_none")]
     pub choices: Option<SpecChoices>,
+    /// The type each value must read as, from `type=`. `None` is a string.
+    ///
+    /// Checked by the parser after binding, like [`SpecArg::validate`], and what generators
+    /// render instead of `string`. Never `object`: a command-line word cannot be a table.
+    #[serde(
+        rename = "type",
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_value_type"
+    )]
+    pub value_type: Option<Base>,
     /// A portable expr expression that must return true for each raw value.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub validate: Option<String>,
@@ -161,6 +178,12 @@ pub struct SpecArg {
     /// See [`crate::spec::effect::SpecCommandEffect`]; never lowers it.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effect: Option<SpecCommandEffect>,
+    /// How much this argument promises to stay as it is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stability: Option<SpecStability>,
+    /// The version that introduced this argument.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
     /// Environment variable that can provide this argument's value
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<String>,
@@ -177,6 +200,9 @@ pub struct SpecArg {
     /// Explicit placement within its help section.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_order: Option<usize>,
+    /// Where this argument was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
 }
 
 impl SpecArg {
@@ -196,6 +222,7 @@ impl SpecArg {
 
     pub(crate) fn parse(ctx: &ParsingContext, node: &NodeHelper) -> Result<Self, UsageErr> {
         let mut arg: SpecArg = node.arg(0)?.ensure_string()?.parse()?;
+        arg.source = Some(ctx.locate(node.node.name().span()));
         for (k, v) in node.props() {
             match k {
                 "help" => arg.help = Some(v.ensure_string()?),
@@ -237,6 +264,7 @@ impl SpecArg {
                 "hide_possible_values" => arg.hide_possible_values = v.ensure_bool()?,
                 "hide_short_help" => arg.hide_short_help = v.ensure_bool()?,
                 "hide_long_help" => arg.hide_long_help = v.ensure_bool()?,
+                "sensitive" => arg.sensitive = v.ensure_bool()?,
                 "conflicts" => arg.conflicts = vec![v.ensure_string()?],
                 "requires" => arg.requires = vec![v.ensure_string()?],
                 "required_if" => arg.required_if = vec![v.ensure_string()?],
@@ -261,8 +289,11 @@ impl SpecArg {
                 "deprecated_env" => arg.deprecated_env = vec![v.ensure_string()?],
                 "validate" => arg.validate = v.ensure_string().map(Some)?,
                 "validate_error" => arg.validate_error = v.ensure_string().map(Some)?,
+                "type" => arg.value_type = Some(value_type(ctx, &v)?),
                 "help_heading" => arg.help_heading = v.ensure_string().map(Some)?,
                 "display_order" => arg.display_order = v.ensure_usize().map(Some)?,
+                "stability" => arg.stability = Some(stability(ctx, &v)?),
+                "since" => arg.since = Some(v.ensure_string()?),
                 k => bail_parse!(ctx, v.entry.span(), "unsupported arg key {k}"),
             }
         }
@@ -288,6 +319,7 @@ impl SpecArg {
                 "env_fallback" => arg.env_fallback = string_args(&child)?,
                 "deprecated_env" => arg.deprecated_env = string_args(&child)?,
                 "validate" => arg.validate = child.arg(0)?.ensure_string().map(Some)?,
+                "type" => arg.value_type = Some(value_type(ctx, &child.arg(0)?)?),
                 "validate_error" => {
                     arg.validate_error = child.arg(0)?.ensure_string().map(Some)?;
                 }
@@ -297,6 +329,8 @@ impl SpecArg {
                 "display_order" => {
                     arg.display_order = child.arg(0)?.ensure_usize().map(Some)?;
                 }
+                "stability" => arg.stability = Some(stability(ctx, &child.arg(0)?)?),
+                "since" => arg.since = Some(child.arg(0)?.ensure_string()?),
                 "default" => {
                     // Support both single value and multiple values
                     // default "bar"            -> vec!["bar"]
@@ -339,6 +373,7 @@ impl SpecArg {
                 "hide_possible_values" => arg.hide_possible_values = child.arg(0)?.ensure_bool()?,
                 "hide_short_help" => arg.hide_short_help = child.arg(0)?.ensure_bool()?,
                 "hide_long_help" => arg.hide_long_help = child.arg(0)?.ensure_bool()?,
+                "sensitive" => arg.sensitive = child.arg(0)?.ensure_bool()?,
                 "conflicts" => {
                     arg.conflicts = child
                         .ensure_arg_len(1..)?
@@ -535,6 +570,9 @@ impl From<&SpecArg> for KdlNode {
                 node.push(KdlEntry::new_prop(name, true));
             }
         }
+        if arg.sensitive {
+            node.push(KdlEntry::new_prop("sensitive", true));
+        }
         if arg.conflicts.len() == 1 {
             node.push(string_entry(Some("conflicts"), &arg.conflicts[0]));
         } else if !arg.conflicts.is_empty() {
@@ -586,6 +624,9 @@ impl From<&SpecArg> for KdlNode {
                 node.push(string_entry(Some("validate_error"), error));
             }
         }
+        if let Some(ty) = &arg.value_type {
+            node.push(string_entry(Some("type"), &ty.to_string()));
+        }
         if let Some(help_heading) = &arg.help_heading {
             node.push(string_entry(Some("help_heading"), help_heading));
         }
@@ -595,6 +636,12 @@ impl From<&SpecArg> for KdlNode {
         if let Some(effect) = &arg.effect {
             node.push(string_entry(Some("effect"), effect.as_str()));
         }
+        if let Some(stability) = &arg.stability {
+            node.push(string_entry(Some("stability"), stability.as_str()));
+        }
+        if let Some(since) = &arg.since {
+            node.push(string_entry(Some("since"), since));
+        }
         if let Some(choices) = &arg.choices {
             let children = node.children_mut().get_or_insert_with(KdlDocument::new);
             children.nodes_mut().push(choices.into());
@@ -603,6 +650,42 @@ impl From<&SpecArg> for KdlNode {
     }
 }
 
+/// An arg's `type=`: one of the config grammar's base types.
+///
+/// Shared with flags, whose `type=` describes their value. A container is refused with the
+/// spelling that does mean it — a command line's list is a variadic arg or a repeated flag,
+/// and `list<int>` on one would leave two places saying how many values there are.
+pub(crate) fn value_type(ctx: &ParsingContext, v: &ParseEntry<'_>) -> Result<Base, UsageErr> {
+    let raw = v.ensure_string()?;
+    match raw.parse::<SpecConfigType>() {
+        Ok(SpecConfigType::Base(base)) if crate::typed::is_arg_type(&base) => Ok(base),
+        Ok(SpecConfigType::Base(_)) => bail_parse!(
+            ctx,
+            v.entry.span(),
+            "an argument cannot be of type {raw}; a command-line value is one word"
+        ),
+        Ok(_) => bail_parse!(
+            ctx,
+            v.entry.span(),
+            "an argument's type is the type of each value, and {raw} is a container; \
+             use var=#true for several values"
+        ),
+        // Re-labelled at this entry: the grammar's own error has no span to point at.
+        Err(UsageErr::InvalidInput(why, ..)) => bail_parse!(ctx, v.entry.span(), "{why}"),
+        Err(err) => Err(err),
+    }
+}
+
+fn serialize_value_type<S: serde::Serializer>(
+    ty: &Option<Base>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match ty {
+        Some(ty) => serializer.collect_str(ty),
+        None => serializer.serialize_none(),
+    }
+}
+
 fn string_args(node: &NodeHelper<'_>) -> Result<Vec<String>, UsageErr> {
     node.ensure_arg_len(1..)?
         .args()
@@ -987,6 +1070,7 @@ impl From<&clap::Arg> for SpecArg {
             hide_possible_values: arg.is_hide_possible_values_set(),
             hide_short_help: arg.is_hide_short_help_set(),
             hide_long_help: arg.is_hide_long_help_set(),
+            sensitive: false,
             conflicts: Vec::new(),
             requires: Vec::new(),
             required_if: Vec::new(),
@@ -998,12 +1082,16 @@ impl From<&clap::Arg> for SpecArg {
             choices: None,
             validate: None,
             validate_error: None,
+            value_type: None,
             effect: None,
+            stability: None,
+            since: None,
             env: None,
             env_fallback: Vec::new(),
             deprecated_env: Vec::new(),
             help_heading: arg.get_help_heading().map(|s| s.to_string()),
             display_order: Some(arg.get_display_order()),
+            source: None,
         };
         arg.choices = choices;
 
@@ -1163,6 +1251,142 @@ flag "--trigger"
     }
 }
 
+#[cfg(test)]
+mod value_type_tests {
+    use std::time::Duration;
+
+    use crate::parse::Parser;
+    use crate::spec::config_type::Base;
+    use crate::typed::TypedValue;
+    use crate::Spec;
+
+    fn spec() -> Spec {
+        r#"
+name "ex"
+bin "ex"
+flag "-j --jobs <n>" type="int"
+flag "--wait" default="1m30s" {
+    arg "<d>" type="duration"
+}
+arg "<port>" type="uint"
+arg "[hosts]..." type="url"
+        "#
+        .parse()
+        .unwrap()
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn a_type_round_trips_on_args_and_flag_values() {
+        let reparsed: Spec = spec().to_string().parse().unwrap();
+        assert_eq!(reparsed.cmd.args[0].value_type, Some(Base::Uint));
+        let jobs = reparsed
+            .cmd
+            .flags
+            .iter()
+            .find(|f| f.name == "jobs")
+            .unwrap();
+        assert_eq!(jobs.arg.as_ref().unwrap().value_type, Some(Base::Int));
+        let wait = reparsed
+            .cmd
+            .flags
+            .iter()
+            .find(|f| f.name == "wait")
+            .unwrap();
+        assert_eq!(wait.arg.as_ref().unwrap().value_type, Some(Base::Duration));
+    }
+
+    #[test]
+    fn a_type_a_command_line_cannot_hold_is_refused_with_the_spec() {
+        for (kdl, says) in [
+            (
+                r#"arg "<x>" type="object""#,
+                "a command-line value is one word",
+            ),
+            (r#"arg "<x>" type="list<int>""#, "use var=#true"),
+            (r#"flag "--x" type="int""#, "takes none"),
+            (r#"arg "<x>" type="int>""#, "not a config type"),
+        ] {
+            let err = format!("name \"ex\"\nbin \"ex\"\n{kdl}\n")
+                .parse::<Spec>()
+                .unwrap_err();
+            assert!(format!("{err:?}").contains(says), "{kdl}: {err:?}");
+        }
+    }
+
+    #[test]
+    fn the_parser_refuses_a_value_that_is_not_its_type() {
+        let spec = spec();
+        let err = Parser::new(&spec)
+            .parse(&words(&["ex", "-j", "two", "80"]))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid value for jobs: two: expected an integer"),
+            "{err}"
+        );
+        let err = Parser::new(&spec)
+            .parse(&words(&["ex", "80", "https://a.example", "b.example"]))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("b.example: expected a URL"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn parsed_values_come_back_as_their_types_defaults_included() {
+        let spec = spec();
+        let out = Parser::new(&spec)
+            .parse(&words(&["ex", "--jobs=-2", "8080", "https://a.example"]))
+            .unwrap();
+        let typed = |value: Option<Result<TypedValue, _>>| value.unwrap().unwrap();
+        assert_eq!(typed(out.typed_flag("jobs")), TypedValue::Int(-2));
+        assert_eq!(
+            typed(out.typed_flag("wait")),
+            TypedValue::Duration(Duration::from_secs(90))
+        );
+        assert_eq!(typed(out.typed_arg("port")), TypedValue::Uint(8080));
+        assert_eq!(
+            typed(out.typed_arg("hosts")),
+            TypedValue::List(vec![TypedValue::Url("https://a.example".into())])
+        );
+        assert!(out.typed_arg("nothing").is_none());
+    }
+
+    #[test]
+    fn a_value_nothing_checked_is_refused_by_name() {
+        // `parse_partial` checks no types, so the refusal comes from asking for one.
+        let spec = spec();
+        let out = crate::parse::parse_partial(&spec, &words(&["ex", "-j", "many", "80"])).unwrap();
+        let err = out.typed_flag("jobs").unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for jobs: many: expected an integer"
+        );
+    }
+
+    #[test]
+    fn an_environment_value_is_judged_like_a_typed_one() {
+        let spec: Spec = r#"
+name "ex"
+bin "ex"
+arg "[port]" env="PORT" type="uint"
+        "#
+        .parse()
+        .unwrap();
+        let env = [("PORT".to_string(), "-1".to_string())].into();
+        let err = Parser::new(&spec)
+            .with_env(env)
+            .parse(&words(&["ex"]))
+            .unwrap_err();
+        assert!(err.to_string().contains("non-negative integer"), "{err}");
+    }
+}
+
 #[cfg(test)]
 mod delimiter_tests {
     use crate::Spec;
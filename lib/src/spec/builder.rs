@@ -31,7 +31,10 @@
 //! ```
 
 use crate::spec::cmd::SpecExample;
+use crate::spec::config_type::Base;
 use crate::spec::effect::SpecCommandEffect;
+use crate::spec::exit::SpecExit;
+use crate::spec::stability::SpecStability;
 use crate::{
     spec::arg::SpecDoubleDashChoices, SpecArg, SpecChoices, SpecCommand, SpecDefaultIf, SpecFlag,
     SpecRequiredIfEq, SpecRequiresIf,
@@ -237,6 +240,12 @@ impl SpecFlagBuilder {
         self
     }
 
+    /// Mark its value as a secret, masked wherever usage renders it
+    pub fn sensitive(mut self, is_sensitive: bool) -> Self {
+        self.inner.sensitive = is_sensitive;
+        self
+    }
+
     /// Set as count flag
     pub fn count(mut self, is_count: bool) -> Self {
         self.inner.count = is_count;
@@ -463,6 +472,18 @@ impl SpecFlagBuilder {
         self
     }
 
+    /// Set how much this flag promises to stay as it is
+    pub fn stability(mut self, stability: SpecStability) -> Self {
+        self.inner.stability = Some(stability);
+        self
+    }
+
+    /// Set the version that introduced this flag
+    pub fn since(mut self, version: impl Into<String>) -> Self {
+        self.inner.since = Some(version.into());
+        self
+    }
+
     /// Build the final SpecFlag
     #[must_use]
     pub fn build(mut self) -> SpecFlag {
@@ -673,6 +694,12 @@ impl SpecArgBuilder {
         self
     }
 
+    /// Mark its value as a secret, masked wherever usage renders it
+    pub fn sensitive(mut self, is_sensitive: bool) -> Self {
+        self.inner.sensitive = is_sensitive;
+        self
+    }
+
     /// Set environment variable name
     /// Heading to list this under in help output.
     pub fn help_heading(mut self, help_heading: impl Into<String>) -> Self {
@@ -730,6 +757,12 @@ impl SpecArgBuilder {
         self
     }
 
+    /// Set the type each value must read as.
+    pub fn value_type(mut self, ty: Base) -> Self {
+        self.inner.value_type = Some(ty);
+        self
+    }
+
     /// Set choices from an environment variable
     #[cfg(feature = "unstable_choices_env")]
     pub fn choices_env(mut self, env: impl Into<String>) -> Self {
@@ -756,6 +789,18 @@ impl SpecArgBuilder {
         self
     }
 
+    /// Set how much this argument promises to stay as it is
+    pub fn stability(mut self, stability: SpecStability) -> Self {
+        self.inner.stability = Some(stability);
+        self
+    }
+
+    /// Set the version that introduced this argument
+    pub fn since(mut self, version: impl Into<String>) -> Self {
+        self.inner.since = Some(version.into());
+        self
+    }
+
     /// Build the final SpecArg
     #[must_use]
     pub fn build(mut self) -> SpecArg {
@@ -965,6 +1010,18 @@ impl SpecCommandBuilder {
         self
     }
 
+    /// Set how much this command promises to stay as it is
+    pub fn stability(mut self, stability: SpecStability) -> Self {
+        self.inner.stability = Some(stability);
+        self
+    }
+
+    /// Set the version that introduced this command
+    pub fn since(mut self, version: impl Into<String>) -> Self {
+        self.inner.since = Some(version.into());
+        self
+    }
+
     /// Set deprecated message
     pub fn deprecated(mut self, msg: impl Into<String>) -> Self {
         self.inner.deprecated = Some(msg.into());
@@ -1058,6 +1115,12 @@ impl SpecCommandBuilder {
         self
     }
 
+    /// Declare what an exit status means (can be called multiple times)
+    pub fn exit(mut self, exit: SpecExit) -> Self {
+        self.inner.exits.push(exit);
+        self
+    }
+
     /// Build the final SpecCommand
     #[must_use]
     pub fn build(mut self) -> SpecCommand {
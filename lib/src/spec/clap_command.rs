@@ -0,0 +1,1414 @@
+//! A `clap::Command` built from a spec: the other direction from
+//! `impl From<&clap::Command> for Spec`.
+//!
+//! For a CLI that keeps clap as its parser while the KDL file becomes the source of
+//! truth. Everything clap has a setter for is carried over: subcommands and their
+//! aliases, globals, groups, `conflicts`/`requires`/`overrides` and the conditional
+//! requirements, `env`, defaults, choices, typed values and the `hide_*` family.
+//!
+//! What clap has no setter for is not dropped silently. [`command_with_report`] lists
+//! it in a [`FidelityReport`], the mirror of clap_usage's report for the forward
+//! direction: a clean report means a clap binary built from the spec parses what the
+//! spec says it parses. Config files, mounts and `effect` are the usual entries —
+//! they are things usage does around the parse rather than in it.
+//!
+//! The one thing reported as an error instead is a spec clap would refuse to build: a
+//! selector that names no argument, two arguments with one id, or a required
+//! positional after an optional one. clap panics on those in `debug_assert`, and a
+//! panic is a worse place to find out than here.
+
+use std::collections::BTreeSet;
+
+use clap::builder::{ArgPredicate, PossibleValue, PossibleValuesParser};
+use clap::{Arg, ArgAction, ArgGroup, Command, ValueHint};
+
+use crate::error::UsageErr;
+use crate::spec::config_type::Base;
+use crate::spec::unknown_flags::UnknownFlags;
+use crate::{Spec, SpecArg, SpecChoices, SpecCommand, SpecDoubleDashChoices, SpecFlag};
+use crate::{SpecComplete, SpecFlagAction, SpecStability};
+
+/// A spec feature that the generated clap command cannot carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum FidelityFeature {
+    /// A `config` block: clap reads no files.
+    Config,
+    /// A `mount`, which discovers subcommands by running a command.
+    Mount,
+    /// `default_subcommand`.
+    DefaultSubcommand,
+    /// `effect` on a command, flag or argument.
+    Effect,
+    /// `deprecated` on a command, flag or argument.
+    Deprecation,
+    /// `env_fallback` and `deprecated_env`: clap reads one variable.
+    Environment,
+    /// `negate`, which clap can only spell as a second switch.
+    Negation,
+    /// `action="help_all"`.
+    HelpAll,
+    /// Bounds on a flag's values, or a `double_dash` clap cannot place.
+    ValueArity,
+    /// A value type clap has no parser for, or one that choices replaced.
+    ValueType,
+    /// `validate`.
+    Validation,
+    /// Non-strict choices, or a visible choice alias.
+    Choices,
+    /// A completion clap's `ValueHint` does not cover.
+    Completion,
+    /// `required_if` naming a global a parent command declares: clap spells it as a
+    /// `requires` on that global, which is not this command's to change.
+    Requirement,
+    /// `restart_token`.
+    RestartToken,
+    /// `help_heading` on a command.
+    CommandHeading,
+    /// `examples`.
+    Examples,
+    /// `exit`, which clap has nowhere to document.
+    ExitStatus,
+    /// `output`: what a command prints is no part of what clap parses.
+    Output,
+    /// `stability` and `since`, and with them the `experimental_env` gate.
+    Stability,
+    /// `help_template` and `disable_help`.
+    HelpTemplate,
+    /// `license` and `repository`.
+    Metadata,
+}
+
+/// One spec feature the clap command does not carry.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FidelityLoss {
+    /// Command path, rooted at the spec's name.
+    pub command: Vec<String>,
+    /// clap argument ID when the loss belongs to one argument.
+    pub argument: Option<String>,
+    /// The feature that cannot be represented.
+    pub feature: FidelityFeature,
+    /// The spec declaration that triggered the report.
+    pub detail: String,
+}
+
+/// Structured, deterministic losses found while building a clap command from a spec.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FidelityReport {
+    losses: Vec<FidelityLoss>,
+}
+
+impl FidelityReport {
+    /// Every detected loss, sorted by command, argument, feature, and detail.
+    pub fn losses(&self) -> &[FidelityLoss] {
+        &self.losses
+    }
+
+    /// Whether every spec feature was carried into the clap command.
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+}
+
+/// Build a `clap::Command` from `spec`, with what it could not carry.
+///
+/// ```
+/// let spec: usage::Spec = r#"
+///     bin "tool"
+///     flag "-v --verbose" count=#true
+///     arg "<file>"
+/// "#
+/// .parse()
+/// .unwrap();
+/// let (cmd, report) = usage::spec::clap_command::command_with_report(&spec).unwrap();
+/// assert!(report.is_lossless());
+/// let matches = cmd.try_get_matches_from(["tool", "-vv", "a.txt"]).unwrap();
+/// assert_eq!(matches.get_count("verbose"), 2);
+/// ```
+pub fn command_with_report(spec: &Spec) -> Result<(Command, FidelityReport), UsageErr> {
+    let mut builder = Builder {
+        spec,
+        losses: BTreeSet::new(),
+    };
+    let cmd = builder.root()?;
+    let report = FidelityReport {
+        losses: builder.losses.into_iter().collect(),
+    };
+    Ok((cmd, report))
+}
+
+impl TryFrom<&Spec> for Command {
+    type Error = UsageErr;
+
+    /// [`command_with_report`], for a caller that has already read the report.
+    fn try_from(spec: &Spec) -> Result<Self, Self::Error> {
+        command_with_report(spec).map(|(cmd, _)| cmd)
+    }
+}
+
+struct Builder<'a> {
+    spec: &'a Spec,
+    losses: BTreeSet<FidelityLoss>,
+}
+
+/// Where a command sits: its path, and what it inherits from the commands above it.
+struct Scope<'a> {
+    path: Vec<String>,
+    globals: Vec<&'a SpecFlag>,
+    unknown_flags: UnknownFlags,
+}
+
+impl<'a> Builder<'a> {
+    fn root(&mut self) -> Result<Command, UsageErr> {
+        let spec = self.spec;
+        let name = if spec.name.is_empty() {
+            &spec.bin
+        } else {
+            &spec.name
+        };
+        let scope = Scope {
+            path: vec![name.clone()],
+            globals: Vec::new(),
+            unknown_flags: spec.unknown_flags.unwrap_or_default(),
+        };
+        let mut cmd = Command::new(name.clone());
+        if !spec.bin.is_empty() && spec.bin != *name {
+            cmd = cmd.bin_name(spec.bin.clone());
+        }
+        if let Some(version) = &spec.version {
+            cmd = cmd.version(version.clone());
+        }
+        if let Some(long_version) = &spec.long_version {
+            cmd = cmd.long_version(long_version.clone());
+        }
+        if let Some(author) = &spec.author {
+            cmd = cmd.author(author.clone());
+        }
+        // The root's help is the spec's `about`, which is also where a spec read from clap
+        // puts it; `cmd.help` only fills in when the spec says it the other way.
+        if let Some(about) = spec.about.as_ref().or(spec.cmd.help.as_ref()) {
+            cmd = cmd.about(about.clone());
+        }
+        if let Some(about) = spec.about_long.as_ref().or(spec.cmd.help_long.as_ref()) {
+            cmd = cmd.long_about(about.clone());
+        }
+        if let Some(help) = &spec.before_help {
+            cmd = cmd.before_help(help.clone());
+        }
+        if let Some(help) = &spec.before_help_long {
+            cmd = cmd.before_long_help(help.clone());
+        }
+        if let Some(help) = &spec.after_help {
+            cmd = cmd.after_help(help.clone());
+        }
+        if let Some(help) = &spec.after_help_long {
+            cmd = cmd.after_long_help(help.clone());
+        }
+        if spec.multicall {
+            cmd = cmd.multicall(true);
+        }
+
+        if !spec.config.is_empty() {
+            self.loss(&scope.path, None, FidelityFeature::Config, "config".into());
+        }
+        if let Some(default) = &spec.default_subcommand {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::DefaultSubcommand,
+                format!("default_subcommand={default}"),
+            );
+        }
+        if let Some(env) = &spec.experimental_env {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::Stability,
+                format!("experimental_env={env}"),
+            );
+        }
+        if !spec.examples.is_empty() {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::Examples,
+                "examples".into(),
+            );
+        }
+        if spec.help_template.is_some() {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::HelpTemplate,
+                "help_template".into(),
+            );
+        }
+        if spec.disable_help.is_some() {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::HelpTemplate,
+                "disable_help".into(),
+            );
+        }
+        if let Some(license) = &spec.license {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::Metadata,
+                format!("license={license}"),
+            );
+        }
+        if let Some(repository) = &spec.repository {
+            self.loss(
+                &scope.path,
+                None,
+                FidelityFeature::Metadata,
+                format!("repository={repository}"),
+            );
+        }
+
+        self.command(cmd, &spec.cmd, &scope)
+    }
+
+    /// Fill in `cmd` from `spec_cmd`, then its subcommands.
+    fn command(
+        &mut self,
+        mut cmd: Command,
+        spec_cmd: &'a SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<Command, UsageErr> {
+        let path = &scope.path;
+        if let Some(deprecated) = &spec_cmd.deprecated {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::Deprecation,
+                format!("deprecated={deprecated}"),
+            );
+        }
+        if let Some(effect) = spec_cmd.effect {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::Effect,
+                format!("effect={effect}"),
+            );
+        }
+        self.stability_losses(path, None, spec_cmd.stability, spec_cmd.since.as_deref());
+        for mount in &spec_cmd.mounts {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::Mount,
+                format!("mount run={}", mount.run),
+            );
+        }
+        if let Some(token) = &spec_cmd.restart_token {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::RestartToken,
+                format!("restart_token={token}"),
+            );
+        }
+        if let Some(heading) = &spec_cmd.help_heading {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::CommandHeading,
+                format!("help_heading={heading}"),
+            );
+        }
+        if !spec_cmd.examples.is_empty() && path.len() > 1 {
+            self.loss(path, None, FidelityFeature::Examples, "examples".into());
+        }
+        for exit in &spec_cmd.exits {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::ExitStatus,
+                format!("exit {}", exit.code),
+            );
+        }
+        for output in &spec_cmd.outputs {
+            self.loss(
+                path,
+                None,
+                FidelityFeature::Output,
+                format!("output {}", output.format),
+            );
+        }
+
+        self.check_ids(spec_cmd, scope)?;
+        self.check_positional_order(spec_cmd, scope)?;
+
+        cmd = settings(cmd, spec_cmd);
+        let claims = |short: char, long: &str| {
+            spec_cmd
+                .flags
+                .iter()
+                .chain(scope.globals.iter().copied())
+                .any(|f| f.short.contains(&short) || f.long.iter().any(|l| l == long))
+        };
+        // clap adds `-h --help` and `-V --version` of its own, and refuses a second
+        // argument spelled the same way. A spec that declares them means its own.
+        if claims('h', "help") {
+            cmd = cmd.disable_help_flag(true);
+        }
+        if claims('V', "version") {
+            cmd = cmd.disable_version_flag(true);
+        }
+        if spec_cmd.subcommands.contains_key("help") {
+            cmd = cmd.disable_help_subcommand(true);
+        }
+
+        let mut args = Vec::new();
+        // `required_if` is the one relationship clap spells on the *other* argument:
+        // "required if `--b` is given" is `--b` requiring this one.
+        let mut reverse_requires = Vec::new();
+        for flag in &spec_cmd.flags {
+            let (arg, negation) = self.flag(flag, spec_cmd, scope)?;
+            for selector in &flag.required_if {
+                reverse_requires.push((self.resolve(selector, spec_cmd, scope)?, flag_id(flag)));
+            }
+            args.push(arg);
+            args.extend(negation);
+        }
+        for positional in &spec_cmd.args {
+            let arg = self.positional(positional, spec_cmd, scope)?;
+            for selector in &positional.required_if {
+                reverse_requires.push((
+                    self.resolve(selector, spec_cmd, scope)?,
+                    positional.name.clone(),
+                ));
+            }
+            args.push(arg);
+        }
+        for (requirer, required) in reverse_requires {
+            match args.iter_mut().find(|a| a.get_id() == requirer.as_str()) {
+                Some(arg) => *arg = std::mem::take(arg).requires(required),
+                None => self.loss(
+                    path,
+                    Some(&required),
+                    FidelityFeature::Requirement,
+                    format!("required_if={requirer}, which a parent command declares"),
+                ),
+            }
+        }
+        cmd = cmd.args(args);
+
+        for group in &spec_cmd.groups {
+            let members = group
+                .members
+                .iter()
+                .map(|member| self.resolve(member, spec_cmd, scope))
+                .collect::<Result<Vec<_>, _>>()?;
+            cmd = cmd.group(
+                ArgGroup::new(group.name.clone())
+                    .args(members)
+                    .required(group.required)
+                    .multiple(group.multiple),
+            );
+        }
+
+        let mut globals = scope.globals.clone();
+        globals.extend(spec_cmd.flags.iter().filter(|f| f.global));
+        let unknown_flags = spec_cmd.unknown_flags.unwrap_or(scope.unknown_flags);
+        for (name, sub) in &spec_cmd.subcommands {
+            let mut sub_path = path.clone();
+            sub_path.push(name.clone());
+            let sub_scope = Scope {
+                path: sub_path,
+                globals: globals.clone(),
+                unknown_flags: sub.unknown_flags.unwrap_or(unknown_flags),
+            };
+            let mut sub_cmd = Command::new(name.clone());
+            if let Some(help) = &sub.help {
+                sub_cmd = sub_cmd.about(help.clone());
+            }
+            if let Some(help) = &sub.help_long {
+                sub_cmd = sub_cmd.long_about(help.clone());
+            }
+            let sub_cmd = self.command(sub_cmd, sub, &sub_scope)?;
+            cmd = cmd.subcommand(sub_cmd);
+        }
+        Ok(cmd)
+    }
+
+    /// The switch or option for `flag`, and a second switch when it has a `negate`.
+    fn flag(
+        &mut self,
+        flag: &SpecFlag,
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<(Arg, Option<Arg>), UsageErr> {
+        let id = flag_id(flag);
+        let path = &scope.path;
+        let mut arg = Arg::new(id.clone());
+        let mut shorts = flag.short.iter().copied();
+        if let Some(short) = shorts.next() {
+            arg = arg.short(short).visible_short_aliases(shorts);
+        }
+        arg = arg.short_aliases(flag.hidden_short_aliases.iter().copied());
+        let mut longs = flag.long.iter().cloned();
+        if let Some(long) = longs.next() {
+            arg = arg.long(long).visible_aliases(longs);
+        }
+        arg = arg.aliases(flag.hidden_aliases.iter().cloned());
+        arg = help(arg, flag.help.as_ref(), flag.help_long.as_ref());
+        arg = arg
+            .required(flag.required)
+            .hide(flag.hide)
+            .hide_default_value(flag.hide_default_value || flag.sensitive)
+            .hide_env(flag.hide_env)
+            .hide_env_values(flag.hide_env_values || flag.sensitive)
+            .hide_possible_values(flag.hide_possible_values)
+            .hide_short_help(flag.hide_short_help)
+            .hide_long_help(flag.hide_long_help)
+            .global(flag.global)
+            .exclusive(flag.exclusive);
+        if let Some(env) = &flag.env {
+            arg = arg.env(env.clone());
+        }
+        if let Some(heading) = &flag.help_heading {
+            arg = arg.help_heading(heading.clone());
+        }
+        if let Some(order) = flag.display_order {
+            arg = arg.display_order(order);
+        }
+
+        arg = arg.action(match (flag.action, &flag.arg) {
+            (SpecFlagAction::Help, _) => ArgAction::Help,
+            (SpecFlagAction::HelpShort, _) => ArgAction::HelpShort,
+            (SpecFlagAction::HelpLong, _) => ArgAction::HelpLong,
+            (SpecFlagAction::HelpAll, _) => {
+                self.loss(
+                    path,
+                    Some(&id),
+                    FidelityFeature::HelpAll,
+                    "action=help_all, built as action=help".into(),
+                );
+                ArgAction::Help
+            }
+            (SpecFlagAction::Version, _) => ArgAction::Version,
+            (SpecFlagAction::Set, None) if flag.count || flag.var => ArgAction::Count,
+            (SpecFlagAction::Set, None) => ArgAction::SetTrue,
+            (SpecFlagAction::Set, Some(_)) if flag.var => ArgAction::Append,
+            (SpecFlagAction::Set, Some(_)) => ArgAction::Set,
+        });
+
+        if let Some(value) = &flag.arg {
+            if value.value_names.len() > 1 {
+                arg = arg
+                    .value_names(value.value_names.iter().cloned())
+                    .num_args(value.value_names.len());
+            } else {
+                arg = arg.value_name(value.name.clone());
+            }
+            if flag.value_optional {
+                arg = arg.num_args(0..=1);
+            }
+            if flag.var && (flag.var_min.is_some() || flag.var_max.is_some()) {
+                self.loss(
+                    path,
+                    Some(&id),
+                    FidelityFeature::ValueArity,
+                    format!(
+                        "var_min={:?}, var_max={:?} count values across occurrences",
+                        flag.var_min, flag.var_max
+                    ),
+                );
+            }
+            if let Some(missing) = &flag.default_missing {
+                arg = arg.default_missing_value(missing.clone());
+            }
+            arg = arg.require_equals(flag.require_equals);
+            arg = self.value(arg, &id, value, cmd, scope);
+        } else if flag.bool_value {
+            self.loss(
+                path,
+                Some(&id),
+                FidelityFeature::ValueType,
+                "bool_value: clap switches take no `=true`".into(),
+            );
+        }
+        if !flag.default.is_empty() {
+            arg = arg.default_values(flag.default.iter().cloned());
+        }
+
+        for selector in &flag.conflicts {
+            arg = arg.conflicts_with(self.resolve(selector, cmd, scope)?);
+        }
+        for selector in &flag.requires {
+            arg = arg.requires(self.resolve(selector, cmd, scope)?);
+        }
+        for selector in &flag.overrides {
+            arg = arg.overrides_with(self.resolve(selector, cmd, scope)?);
+        }
+        for requires_if in &flag.requires_if {
+            let other = self.resolve(&requires_if.requires, cmd, scope)?;
+            arg = arg.requires_if(requires_if.value.clone(), other);
+        }
+        for default_if in &flag.default_if {
+            let other = self.resolve(&default_if.selector, cmd, scope)?;
+            let predicate = match &default_if.when {
+                Some(value) => ArgPredicate::from(value.clone()),
+                None => ArgPredicate::IsPresent,
+            };
+            arg = arg.default_value_if(other, predicate, default_if.value.clone());
+        }
+        arg = self.requirements(
+            arg,
+            Requirements {
+                required_if_eq: &flag.required_if_eq,
+                required_if_eq_all: &flag.required_if_eq_all,
+                required_unless: &flag.required_unless,
+                required_unless_all: &flag.required_unless_all,
+            },
+            cmd,
+            scope,
+        )?;
+
+        self.common_losses(
+            path,
+            &id,
+            flag.deprecated.as_deref(),
+            flag.effect.map(|e| e.to_string()),
+            &flag.env_fallback,
+            &flag.deprecated_env,
+        );
+        self.stability_losses(path, Some(&id), flag.stability, flag.since.as_deref());
+
+        let negation = match &flag.negate {
+            Some(negate) => {
+                let long = negate.strip_prefix("--").unwrap_or(negate);
+                self.loss(
+                    path,
+                    Some(&id),
+                    FidelityFeature::Negation,
+                    format!("negate={negate}, built as a switch overriding --{id}"),
+                );
+                arg = arg.overrides_with(long.to_string());
+                Some(
+                    Arg::new(long.to_string())
+                        .long(long.to_string())
+                        .action(ArgAction::SetTrue)
+                        .overrides_with(id.clone())
+                        .hide(flag.hide)
+                        .global(flag.global),
+                )
+            }
+            None => None,
+        };
+        Ok((arg, negation))
+    }
+
+    fn positional(
+        &mut self,
+        positional: &SpecArg,
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<Arg, UsageErr> {
+        let id = positional.name.clone();
+        let path = &scope.path;
+        let mut arg = Arg::new(id.clone()).required(positional.required);
+        arg = help(arg, positional.help.as_ref(), positional.help_long.as_ref());
+        let last = cmd.args.last().is_some_and(|a| a.name == positional.name);
+        if positional.var {
+            let min = positional.var_min.unwrap_or(1).max(1);
+            arg = arg.action(ArgAction::Append);
+            arg = match positional.var_max {
+                Some(max) => arg.num_args(min..=max.max(min)),
+                None => arg.num_args(min..),
+            };
+        } else if positional.value_names.len() > 1 {
+            arg = arg
+                .value_names(positional.value_names.iter().cloned())
+                .num_args(positional.value_names.len());
+        }
+        match &positional.double_dash {
+            SpecDoubleDashChoices::Optional => {}
+            SpecDoubleDashChoices::Required if last => arg = arg.last(true),
+            SpecDoubleDashChoices::Automatic if last && positional.var => {
+                arg = arg.trailing_var_arg(true).allow_hyphen_values(true);
+            }
+            double_dash => self.loss(
+                path,
+                Some(&id),
+                FidelityFeature::ValueArity,
+                format!(
+                    "double_dash={double_dash} on {}",
+                    if last {
+                        "a single-valued positional"
+                    } else {
+                        "a positional before the last"
+                    }
+                ),
+            ),
+        }
+        // What `unknown_flags="value"` means to clap: an unknown dash-word is a value
+        // for whichever positional is next, which is also how a spec read from clap
+        // arrives at it.
+        if scope.unknown_flags == UnknownFlags::Value {
+            arg = arg.allow_hyphen_values(true);
+        }
+        arg = arg
+            .hide(positional.hide)
+            .hide_default_value(positional.hide_default_value || positional.sensitive)
+            .hide_env(positional.hide_env)
+            .hide_env_values(positional.hide_env_values || positional.sensitive)
+            .hide_possible_values(positional.hide_possible_values)
+            .hide_short_help(positional.hide_short_help)
+            .hide_long_help(positional.hide_long_help);
+        if let Some(env) = &positional.env {
+            arg = arg.env(env.clone());
+        }
+        if let Some(heading) = &positional.help_heading {
+            arg = arg.help_heading(heading.clone());
+        }
+        if let Some(order) = positional.display_order {
+            arg = arg.display_order(order);
+        }
+        if !positional.default.is_empty() {
+            arg = arg.default_values(positional.default.iter().cloned());
+        }
+        arg = self.value(arg, &id, positional, cmd, scope);
+        for selector in &positional.conflicts {
+            arg = arg.conflicts_with(self.resolve(selector, cmd, scope)?);
+        }
+        for selector in &positional.requires {
+            arg = arg.requires(self.resolve(selector, cmd, scope)?);
+        }
+        arg = self.requirements(
+            arg,
+            Requirements {
+                required_if_eq: &positional.required_if_eq,
+                required_if_eq_all: &positional.required_if_eq_all,
+                required_unless: &positional.required_unless,
+                required_unless_all: &positional.required_unless_all,
+            },
+            cmd,
+            scope,
+        )?;
+        self.common_losses(
+            path,
+            &id,
+            None,
+            positional.effect.map(|e| e.to_string()),
+            &positional.env_fallback,
+            &positional.deprecated_env,
+        );
+        self.stability_losses(
+            path,
+            Some(&id),
+            positional.stability,
+            positional.since.as_deref(),
+        );
+        Ok(arg)
+    }
+
+    /// What a value says about itself, for a positional or a flag's value: its parser,
+    /// splitting, and completion.
+    fn value(
+        &mut self,
+        mut arg: Arg,
+        id: &str,
+        value: &SpecArg,
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Arg {
+        let path = &scope.path;
+        if let Some(delimiter) = value.delimiter {
+            arg = arg.value_delimiter(delimiter);
+        }
+        if let Some(terminator) = &value.value_terminator {
+            arg = arg.value_terminator(terminator.clone());
+        }
+        arg = arg.allow_negative_numbers(value.allow_negative_numbers);
+        if let Some(validate) = &value.validate {
+            self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Validation,
+                format!("validate={validate}"),
+            );
+        }
+        match (&value.choices, &value.value_type) {
+            (Some(choices), value_type) if choices.strict => {
+                arg = self.choices(arg, id, path, choices);
+                if let Some(value_type) = value_type.as_ref().filter(|t| **t != Base::String) {
+                    self.loss(
+                        path,
+                        Some(id),
+                        FidelityFeature::ValueType,
+                        format!("type={value_type}, read as one of its choices"),
+                    );
+                }
+            }
+            (choices, value_type) => {
+                if let Some(choices) = choices {
+                    self.loss(
+                        path,
+                        Some(id),
+                        FidelityFeature::Choices,
+                        format!("strict=#false choices {}", choices.choices.join(", ")),
+                    );
+                }
+                if let Some(value_type) = value_type {
+                    arg = self.value_type(arg, id, path, value_type);
+                }
+            }
+        }
+        match completion(cmd, &self.spec.complete, &value.name) {
+            Some(SpecComplete {
+                type_: Some(type_),
+                run: None,
+                ..
+            }) => match value_hint(type_) {
+                // clap only takes this hint on a positional that ends the command line.
+                Some(ValueHint::CommandWithArguments)
+                    if !(arg.is_trailing_var_arg_set() || arg.is_last_set()) =>
+                {
+                    arg = arg.value_hint(ValueHint::CommandString);
+                }
+                Some(hint) => arg = arg.value_hint(hint),
+                None => self.loss(
+                    path,
+                    Some(id),
+                    FidelityFeature::Completion,
+                    format!("complete type={type_}"),
+                ),
+            },
+            Some(complete) => self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Completion,
+                format!("complete {}", complete.name),
+            ),
+            None => {}
+        }
+        arg
+    }
+
+    fn choices(&mut self, arg: Arg, id: &str, path: &[String], choices: &SpecChoices) -> Arg {
+        let values = choices.choices.iter().map(|choice| {
+            let mut value = PossibleValue::new(choice.clone());
+            if let Some(detail) = choices.details.iter().find(|d| d.value == *choice) {
+                if let Some(help) = &detail.help {
+                    value = value.help(help.clone());
+                }
+                value = value.hide(detail.hide);
+                for alias in &detail.aliases {
+                    // clap's choice aliases are always hidden; a visible one still parses.
+                    if !alias.hide {
+                        self.losses.insert(FidelityLoss {
+                            command: path.to_vec(),
+                            argument: Some(id.to_string()),
+                            feature: FidelityFeature::Choices,
+                            detail: format!("visible alias {} of {choice}", alias.value),
+                        });
+                    }
+                    value = value.alias(alias.value.clone());
+                }
+            }
+            value
+        });
+        let values: Vec<_> = values.collect();
+        arg.value_parser(PossibleValuesParser::new(values))
+            .ignore_case(choices.ignore_case)
+    }
+
+    fn value_type(&mut self, arg: Arg, id: &str, path: &[String], value_type: &Base) -> Arg {
+        match value_type {
+            Base::String => arg,
+            Base::Bool => arg.value_parser(clap::value_parser!(bool)),
+            Base::Int => arg.value_parser(clap::value_parser!(i64)),
+            Base::Uint => arg.value_parser(clap::value_parser!(u64)),
+            Base::Float => arg.value_parser(clap::value_parser!(f64)),
+            Base::Path => arg.value_parser(clap::value_parser!(std::path::PathBuf)),
+            Base::Url | Base::Duration | Base::Object | Base::Custom(_) => {
+                self.loss(
+                    path,
+                    Some(id),
+                    FidelityFeature::ValueType,
+                    format!("type={value_type}, read as a string"),
+                );
+                arg
+            }
+        }
+    }
+
+    fn requirements(
+        &mut self,
+        mut arg: Arg,
+        requirements: Requirements<'_>,
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<Arg, UsageErr> {
+        for eq in requirements.required_if_eq {
+            arg = arg.required_if_eq(self.resolve(&eq.selector, cmd, scope)?, eq.value.clone());
+        }
+        if !requirements.required_if_eq_all.is_empty() {
+            let ifs = requirements
+                .required_if_eq_all
+                .iter()
+                .map(|eq| Ok((self.resolve(&eq.selector, cmd, scope)?, eq.value.clone())))
+                .collect::<Result<Vec<_>, UsageErr>>()?;
+            arg = arg.required_if_eq_all(ifs);
+        }
+        if !requirements.required_unless.is_empty() {
+            let ids = self.resolve_all(requirements.required_unless, cmd, scope)?;
+            arg = arg.required_unless_present_any(ids);
+        }
+        if !requirements.required_unless_all.is_empty() {
+            let ids = self.resolve_all(requirements.required_unless_all, cmd, scope)?;
+            arg = arg.required_unless_present_all(ids);
+        }
+        Ok(arg)
+    }
+
+    fn stability_losses(
+        &mut self,
+        path: &[String],
+        id: Option<&str>,
+        stability: Option<SpecStability>,
+        since: Option<&str>,
+    ) {
+        if let Some(stability) = stability {
+            self.loss(
+                path,
+                id,
+                FidelityFeature::Stability,
+                format!("stability={stability}"),
+            );
+        }
+        if let Some(since) = since {
+            self.loss(
+                path,
+                id,
+                FidelityFeature::Stability,
+                format!("since={since}"),
+            );
+        }
+    }
+
+    fn common_losses(
+        &mut self,
+        path: &[String],
+        id: &str,
+        deprecated: Option<&str>,
+        effect: Option<String>,
+        env_fallback: &[String],
+        deprecated_env: &[String],
+    ) {
+        if let Some(deprecated) = deprecated {
+            self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Deprecation,
+                format!("deprecated={deprecated}"),
+            );
+        }
+        if let Some(effect) = effect {
+            self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Effect,
+                format!("effect={effect}"),
+            );
+        }
+        for env in env_fallback {
+            self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Environment,
+                format!("env_fallback={env}"),
+            );
+        }
+        for env in deprecated_env {
+            self.loss(
+                path,
+                Some(id),
+                FidelityFeature::Environment,
+                format!("deprecated_env={env}"),
+            );
+        }
+    }
+
+    /// The clap id a selector — `--long`, `-s`, or a positional's name — refers to, among
+    /// the command's own arguments and the globals it inherits.
+    fn resolve(
+        &self,
+        selector: &str,
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<String, UsageErr> {
+        let flags = || cmd.flags.iter().chain(scope.globals.iter().copied());
+        let found = if let Some(long) = selector.strip_prefix("--") {
+            flags().find_map(|f| {
+                if f.long.iter().chain(&f.hidden_aliases).any(|l| l == long) {
+                    Some(flag_id(f))
+                } else {
+                    f.negate
+                        .as_deref()
+                        .map(|n| n.strip_prefix("--").unwrap_or(n))
+                        .filter(|n| *n == long)
+                        .map(str::to_string)
+                }
+            })
+        } else if let Some(short) = selector.strip_prefix('-') {
+            let mut chars = short.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => flags()
+                    .find(|f| f.short.contains(&c) || f.hidden_short_aliases.contains(&c))
+                    .map(flag_id),
+                _ => None,
+            }
+        } else {
+            cmd.args
+                .iter()
+                .find(|a| a.name == selector)
+                .map(|a| a.name.clone())
+        };
+        found.ok_or_else(|| UsageErr::UnsupportedFeature {
+            generator: "clap",
+            feature: format!(
+                "a reference to `{selector}` in `{}`, which names no flag or argument",
+                scope.path.join(" ")
+            ),
+        })
+    }
+
+    fn resolve_all(
+        &self,
+        selectors: &[String],
+        cmd: &SpecCommand,
+        scope: &Scope<'a>,
+    ) -> Result<Vec<String>, UsageErr> {
+        selectors
+            .iter()
+            .map(|selector| self.resolve(selector, cmd, scope))
+            .collect()
+    }
+
+    fn check_ids(&self, cmd: &SpecCommand, scope: &Scope<'a>) -> Result<(), UsageErr> {
+        let mut seen = BTreeSet::new();
+        let negations = cmd
+            .flags
+            .iter()
+            .filter_map(|f| f.negate.as_deref())
+            .map(|n| n.strip_prefix("--").unwrap_or(n).to_string());
+        let ids = cmd
+            .flags
+            .iter()
+            .map(flag_id)
+            .chain(negations)
+            .chain(cmd.args.iter().map(|a| a.name.clone()));
+        for id in ids {
+            if !seen.insert(id.clone()) {
+                return Err(UsageErr::UnsupportedFeature {
+                    generator: "clap",
+                    feature: format!(
+                        "two arguments with the id `{id}` in `{}`",
+                        scope.path.join(" ")
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// clap's rule, which usage does not share: a required positional cannot follow an
+    /// optional one unless the command allows missing positionals.
+    fn check_positional_order(&self, cmd: &SpecCommand, scope: &Scope<'a>) -> Result<(), UsageErr> {
+        if cmd.allow_missing_positional {
+            return Ok(());
+        }
+        let mut optional = None;
+        for arg in &cmd.args {
+            if !arg.required {
+                optional.get_or_insert(&arg.name);
+            } else if let Some(optional) =
+                optional.filter(|_| arg.double_dash != SpecDoubleDashChoices::Required)
+            {
+                return Err(UsageErr::UnsupportedFeature {
+                    generator: "clap",
+                    feature: format!(
+                        "the required <{}> after the optional [{optional}] in `{}`",
+                        arg.name,
+                        scope.path.join(" ")
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn loss(
+        &mut self,
+        path: &[String],
+        argument: Option<&str>,
+        feature: FidelityFeature,
+        detail: String,
+    ) {
+        self.losses.insert(FidelityLoss {
+            command: path.to_vec(),
+            argument: argument.map(str::to_string),
+            feature,
+            detail,
+        });
+    }
+}
+
+struct Requirements<'s> {
+    required_if_eq: &'s [crate::SpecRequiredIfEq],
+    required_if_eq_all: &'s [crate::SpecRequiredIfEq],
+    required_unless: &'s [String],
+    required_unless_all: &'s [String],
+}
+
+/// The settings a command carries straight across, each the inverse of what
+/// `From<&clap::Command> for SpecCommand` reads.
+fn settings(mut cmd: Command, spec: &SpecCommand) -> Command {
+    if let Some(help) = &spec.before_help {
+        cmd = cmd.before_help(help.clone());
+    }
+    if let Some(help) = &spec.before_help_long {
+        cmd = cmd.before_long_help(help.clone());
+    }
+    if let Some(help) = &spec.after_help {
+        cmd = cmd.after_help(help.clone());
+    }
+    if let Some(help) = &spec.after_help_long {
+        cmd = cmd.after_long_help(help.clone());
+    }
+    if let Some(heading) = &spec.subcommand_help_heading {
+        cmd = cmd.subcommand_help_heading(heading.clone());
+    }
+    if let Some(name) = &spec.subcommand_value_name {
+        cmd = cmd.subcommand_value_name(name.clone());
+    }
+    if let Some(width) = spec.term_width {
+        cmd = cmd.term_width(width);
+    }
+    if let Some(width) = spec.max_term_width {
+        cmd = cmd.max_term_width(width);
+    }
+    if let Some(order) = spec.display_order {
+        cmd = cmd.display_order(order);
+    }
+    cmd.hide(spec.hide)
+        .visible_aliases(spec.aliases.iter().cloned())
+        .aliases(spec.hidden_aliases.iter().cloned())
+        .subcommand_required(spec.subcommand_required)
+        .allow_external_subcommands(spec.external_subcommand)
+        .next_line_help(spec.next_line_help)
+        .flatten_help(spec.flatten_help)
+        .arg_required_else_help(spec.arg_required_else_help)
+        .disable_help_flag(spec.disable_help_flag)
+        .disable_help_subcommand(spec.disable_help_subcommand)
+        .disable_version_flag(spec.disable_version_flag)
+        .dont_delimit_trailing_values(spec.dont_delimit_trailing_values)
+        .args_override_self(spec.args_override_self)
+        .subcommand_negates_reqs(spec.subcommand_negates_reqs)
+        .args_conflicts_with_subcommands(spec.args_conflicts_with_subcommands)
+        .subcommand_precedence_over_arg(spec.subcommand_precedence_over_arg)
+        .allow_missing_positional(spec.allow_missing_positional)
+}
+
+fn help(mut arg: Arg, help: Option<&String>, help_long: Option<&String>) -> Arg {
+    if let Some(help) = help {
+        arg = arg.help(help.clone());
+    }
+    if let Some(help) = help_long.filter(|long| Some(*long) != help) {
+        arg = arg.long_help(help.clone());
+    }
+    arg
+}
+
+/// A flag's clap id is its name, which is also the id a spec read from clap gives it.
+fn flag_id(flag: &SpecFlag) -> String {
+    flag.name.clone()
+}
+
+/// The completion for a value named `name`: the command's own, then the spec's.
+fn completion<'s>(
+    cmd: &'s SpecCommand,
+    global: &'s indexmap::IndexMap<String, SpecComplete>,
+    name: &str,
+) -> Option<&'s SpecComplete> {
+    let name = name.to_lowercase();
+    cmd.complete.get(&name).or_else(|| global.get(&name))
+}
+
+/// The inverse of `value_hint_type`, for the types clap has a hint for.
+fn value_hint(type_: &str) -> Option<ValueHint> {
+    Some(match type_ {
+        "none" => ValueHint::Other,
+        "path" => ValueHint::AnyPath,
+        "file" => ValueHint::FilePath,
+        "dir" => ValueHint::DirPath,
+        "executable" => ValueHint::ExecutablePath,
+        "command" => ValueHint::CommandName,
+        "command_args" => ValueHint::CommandWithArguments,
+        "username" => ValueHint::Username,
+        "hostname" => ValueHint::Hostname,
+        "url" => ValueHint::Url,
+        "email" => ValueHint::EmailAddress,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_with_report, FidelityFeature};
+    use crate::error::UsageErr;
+    use crate::Spec;
+    use clap::Command;
+
+    fn build(spec: &str) -> Command {
+        let spec: Spec = spec.parse().unwrap();
+        let cmd = Command::try_from(&spec).unwrap();
+        cmd.clone().debug_assert();
+        cmd
+    }
+
+    const TOOL: &str = r#"
+        name "tool"
+        bin "tool"
+        version "1.2.3"
+        about "Build things"
+        flag "-v --verbose" help="Say more" count=#true global=#true
+        flag "--color <when>" default="auto" {
+            choices "auto" "always" "never"
+        }
+        flag "--json" conflicts="--color"
+        flag "--token <token>" hide=#true env="TOOL_TOKEN"
+        cmd "build" help="Build a target" {
+            alias "b"
+            alias "mk" hide=#true
+            flag "--release"
+            flag "--profile <name>" requires="--release"
+            flag "--jobs <n>" type="int"
+            flag "--out <dir>" required_if="--release"
+            flag "--zip"
+            flag "--tar"
+            arg "<target>"
+            arg "[extra]..." var=#true
+            group "format" "--zip" "--tar"
+        }
+    "#;
+
+    #[test]
+    fn a_spec_clap_can_express_builds_without_losses() {
+        let spec: Spec = TOOL.parse().unwrap();
+        let (cmd, report) = command_with_report(&spec).unwrap();
+        cmd.debug_assert();
+        assert_eq!(report.losses(), []);
+    }
+
+    #[test]
+    fn the_command_parses_what_the_spec_does() {
+        let cmd = build(TOOL);
+
+        let m = cmd
+            .clone()
+            .try_get_matches_from(["tool", "build", "-vv", "--jobs", "4", "x", "a", "b"])
+            .unwrap();
+        let (name, build) = m.subcommand().unwrap();
+        assert_eq!(name, "build");
+        assert_eq!(
+            build.get_count("verbose"),
+            2,
+            "a global reaches the subcommand"
+        );
+        assert_eq!(build.get_one::<i64>("jobs"), Some(&4));
+        assert_eq!(build.get_one::<String>("target").unwrap(), "x");
+        let extra: Vec<_> = build.get_many::<String>("extra").unwrap().collect();
+        assert_eq!(extra, ["a", "b"]);
+        assert_eq!(m.get_one::<String>("color").unwrap(), "auto");
+
+        let refused = |argv: &[&str]| cmd.clone().try_get_matches_from(argv).unwrap_err().kind();
+        use clap::error::ErrorKind;
+        assert_eq!(
+            refused(&["tool", "--json", "--color", "never"]),
+            ErrorKind::ArgumentConflict
+        );
+        assert_eq!(
+            refused(&["tool", "--color", "sepia"]),
+            ErrorKind::InvalidValue
+        );
+        assert_eq!(
+            refused(&["tool", "build", "--profile", "p", "x"]),
+            ErrorKind::MissingRequiredArgument
+        );
+        assert_eq!(
+            refused(&["tool", "build", "--release", "x"]),
+            ErrorKind::MissingRequiredArgument,
+            "required_if is --release requiring --out"
+        );
+        assert_eq!(
+            refused(&["tool", "build", "--zip", "--tar", "x"]),
+            ErrorKind::ArgumentConflict
+        );
+        assert_eq!(
+            refused(&["tool", "build", "--jobs", "many", "x"]),
+            ErrorKind::ValueValidation
+        );
+        assert!(cmd
+            .clone()
+            .try_get_matches_from(["tool", "mk", "x"])
+            .is_ok());
+    }
+
+    #[test]
+    fn help_keeps_hidden_things_hidden() {
+        let mut cmd = build(TOOL);
+        let help = cmd.render_help().to_string();
+        assert!(help.contains("--verbose"));
+        assert!(!help.contains("--token"), "{help}");
+        let build_help = cmd
+            .find_subcommand_mut("build")
+            .unwrap()
+            .render_help()
+            .to_string();
+        assert!(!build_help.contains("mk"), "{build_help}");
+    }
+
+    #[test]
+    fn reading_the_command_back_gives_the_spec_again() {
+        let spec: Spec = TOOL.parse().unwrap();
+        let back = Spec::from(&Command::try_from(&spec).unwrap());
+        assert_eq!(back.version.as_deref(), Some("1.2.3"));
+        assert_eq!(back.about.as_deref(), Some("Build things"));
+
+        let flag = |cmd: &crate::SpecCommand, name: &str| {
+            cmd.flags.iter().find(|f| f.name == name).cloned().unwrap()
+        };
+        let verbose = flag(&back.cmd, "verbose");
+        assert!(verbose.count && verbose.global);
+        assert_eq!(verbose.short, ['v']);
+        let color = flag(&back.cmd, "color");
+        assert_eq!(color.default, ["auto"]);
+        assert_eq!(
+            color.arg.unwrap().choices.unwrap().choices,
+            ["auto", "always", "never"]
+        );
+        assert_eq!(flag(&back.cmd, "json").conflicts, ["--color"]);
+        // `env` is carried too, but the forward bridge does not read it back.
+        assert!(flag(&back.cmd, "token").hide);
+
+        let build = &back.cmd.subcommands["build"];
+        assert_eq!(build.aliases, ["b"]);
+        assert_eq!(build.hidden_aliases, ["mk"]);
+        assert_eq!(build.groups[0].members, ["--zip", "--tar"]);
+        let args: Vec<_> = build
+            .args
+            .iter()
+            .map(|a| (&a.name, a.required, a.var))
+            .collect();
+        assert_eq!(
+            args,
+            [
+                (&"target".to_string(), true, false),
+                (&"extra".to_string(), false, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn what_clap_cannot_say_is_reported() {
+        let spec: Spec = r#"
+            name "tool"
+            license "MIT"
+            config {
+                prop "color" default="auto"
+            }
+            flag "--color" negate="--no-color"
+            flag "--level <n>" env="LEVEL" env_fallback="OLD_LEVEL" since="2.0.0"
+            cmd "rm" effect="destructive" deprecated="use delete" stability="experimental" {
+                exit 3 "nothing to remove"
+                output "text"
+                arg "<path>" type="duration"
+                flag "-a --all" action="help_all"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let (cmd, report) = command_with_report(&spec).unwrap();
+        cmd.debug_assert();
+        let lines: Vec<_> = report
+            .losses()
+            .iter()
+            .map(|loss| {
+                format!(
+                    "{} {} {:?}: {}",
+                    loss.command.join(" "),
+                    loss.argument.as_deref().unwrap_or("-"),
+                    loss.feature,
+                    loss.detail
+                )
+            })
+            .collect();
+        insta::assert_snapshot!(lines.join("\n"), @r"
+        tool - Config: config
+        tool - Metadata: license=MIT
+        tool color Negation: negate=--no-color, built as a switch overriding --color
+        tool level Environment: env_fallback=OLD_LEVEL
+        tool level Stability: since=2.0.0
+        tool rm - Effect: effect=destructive
+        tool rm - Deprecation: deprecated=use delete
+        tool rm - ExitStatus: exit 3
+        tool rm - Output: output text
+        tool rm - Stability: stability=experimental
+        tool rm all HelpAll: action=help_all, built as action=help
+        tool rm path ValueType: type=duration, read as a string
+        ");
+        assert!(!report.is_lossless());
+        assert!(report
+            .losses()
+            .iter()
+            .any(|loss| loss.feature == FidelityFeature::Negation));
+    }
+
+    #[test]
+    fn a_selector_that_names_nothing_is_an_error_not_a_panic() {
+        let spec: Spec = r#"
+            name "tool"
+            cmd "go" {
+                flag "--fast" conflicts="--slow"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let err = Command::try_from(&spec).unwrap_err();
+        assert!(matches!(
+            err,
+            UsageErr::UnsupportedFeature {
+                generator: "clap",
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "clap cannot express a reference to `--slow` in `tool go`, which names no flag or argument"
+        );
+    }
+
+    #[test]
+    fn a_required_positional_after_an_optional_one_is_an_error() {
+        let spec: Spec = r#"
+            name "tool"
+            arg "[first]"
+            arg "<second>"
+        "#
+        .parse()
+        .unwrap();
+        let err = Command::try_from(&spec).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "clap cannot express the required <second> after the optional [first] in `tool`"
+        );
+    }
+}
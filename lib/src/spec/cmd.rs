@@ -4,13 +4,16 @@ use std::sync::OnceLock;
 use crate::error::UsageErr;
 use crate::sh::sh;
 use crate::spec::builder::SpecCommandBuilder;
-use crate::spec::context::ParsingContext;
+use crate::spec::context::{ParsingContext, SourceLocation};
 use crate::spec::effect::{SpecCommandEffect, EFFECT_VALUES};
+use crate::spec::exit::SpecExit;
 use crate::spec::flagset::SpecUse;
 use crate::spec::group::SpecGroup;
 use crate::spec::helpers::{string_entry, NodeHelper};
 use crate::spec::is_false;
 use crate::spec::mount::SpecMount;
+use crate::spec::output::SpecOutput;
+use crate::spec::stability::{stability, SpecStability};
 use crate::spec::unknown_flags::UnknownFlags;
 use crate::{Spec, SpecArg, SpecComplete, SpecFlag};
 use indexmap::IndexMap;
@@ -77,6 +80,13 @@ pub struct SpecCommand {
     /// Not inherited by subcommands.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effect: Option<SpecCommandEffect>,
+    /// How much this command promises to stay as it is. Not inherited by subcommands, for the
+    /// reason [`Self::effect`] is not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stability: Option<SpecStability>,
+    /// The version that introduced this command.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
     /// What to do here with a flag-like token that names no declared flag.
     ///
     /// Unset means "whatever encloses this command decided" — the nearest command
@@ -206,9 +216,21 @@ pub struct SpecCommand {
     pub after_help_md: Option<String>,
     /// Usage examples for this command
     pub examples: Vec<SpecExample>,
+    /// What this command's exit statuses mean, beyond those it inherits
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exits: Vec<SpecExit>,
+    /// What this command prints, one entry per format, and the flag that asks for each
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<SpecOutput>,
     /// Custom completers for arguments
     #[serde(skip_serializing_if = "IndexMap::is_empty")]
     pub complete: IndexMap<String, SpecComplete>,
+    /// Where this command was declared, for one read from a spec file.
+    ///
+    /// The `cmd` node for a subcommand, and `bin` (or `name`) for the root, which has no
+    /// node of its own.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
 
     /// Cache for subcommand name lookups (including aliases).
     ///
@@ -233,12 +255,15 @@ impl Default for SpecCommand {
             deprecated_warn_at: None,
             deprecated_remove_at: None,
             effect: None,
+            stability: None,
+            since: None,
             unknown_flags: None,
             hide: false,
             help_heading: None,
             display_order: None,
             mounted: false,
             flags_from_mount: false,
+            source: None,
             subcommand_required: false,
             subcommand_help_heading: None,
             subcommand_value_name: None,
@@ -271,6 +296,8 @@ impl Default for SpecCommand {
             after_help_long: None,
             after_help_md: None,
             examples: vec![],
+            exits: vec![],
+            outputs: vec![],
             subcommand_lookup: OnceLock::new(),
             complete: IndexMap::new(),
         }
@@ -284,6 +311,9 @@ pub struct SpecExample {
     pub header: Option<String>,
     pub help: Option<String>,
     pub lang: String,
+    /// Where this example was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
 }
 
 impl SpecExample {
@@ -341,6 +371,7 @@ impl SpecCommand {
         node.ensure_arg_len(1..=1)?;
         let mut cmd = Self {
             name: node.arg(0)?.ensure_string()?.to_string(),
+            source: Some(ctx.locate(node.node.name().span())),
             ..Default::default()
         };
         for (k, v) in node.props() {
@@ -412,6 +443,8 @@ impl SpecCommand {
                     }
                 }
                 "restart_token" => cmd.restart_token = Some(v.ensure_string()?),
+                "stability" => cmd.stability = Some(stability(ctx, &v)?),
+                "since" => cmd.since = Some(v.ensure_string()?),
                 "deprecated" => {
                     cmd.deprecated = match v.value.as_bool() {
                         Some(true) => Some("deprecated".to_string()),
@@ -447,6 +480,8 @@ impl SpecCommand {
                     cmd.args.push(arg);
                 }
                 "mount" => cmd.mounts.push(SpecMount::parse(ctx, &child)?),
+                "exit" => SpecExit::parse_into(ctx, &child, &mut cmd.exits)?,
+                "output" => SpecOutput::parse_into(ctx, &child, &mut cmd.outputs)?,
                 "group" => cmd.groups.push(SpecGroup::parse(ctx, &child)?),
                 "cmd" => {
                     let node = SpecCommand::parse(ctx, &child)?;
@@ -479,6 +514,7 @@ impl SpecCommand {
                             k => bail_parse!(ctx, v.entry.span(), "unsupported example key {k}"),
                         }
                     }
+                    example.source = Some(ctx.locate(child.node.name().span()));
                     cmd.examples.push(example);
                 }
                 "help" => {
@@ -593,6 +629,10 @@ impl SpecCommand {
                 "restart_token" => {
                     cmd.restart_token = Some(child.ensure_arg_len(1..=1)?.arg(0)?.ensure_string()?)
                 }
+                "stability" => {
+                    cmd.stability = Some(stability(ctx, &child.ensure_arg_len(1..=1)?.arg(0)?)?)
+                }
+                "since" => cmd.since = Some(child.ensure_arg_len(1..=1)?.arg(0)?.ensure_string()?),
                 "deprecated" => {
                     cmd.deprecated = match child.arg(0)?.value.as_bool() {
                         Some(true) => Some("deprecated".to_string()),
@@ -619,6 +659,8 @@ impl SpecCommand {
         self.args.is_empty()
             && self.flags.is_empty()
             && self.mounts.is_empty()
+            && self.exits.is_empty()
+            && self.outputs.is_empty()
             && self.subcommands.is_empty()
     }
     pub fn usage(&self) -> String {
@@ -706,6 +748,8 @@ impl SpecCommand {
             aliases,
             hidden_aliases,
             examples,
+            exits,
+            outputs,
             hide,
             help_heading,
             display_order,
@@ -734,7 +778,10 @@ impl SpecCommand {
             deprecated_warn_at,
             deprecated_remove_at,
             effect,
+            stability,
+            since,
             unknown_flags,
+            source,
             // Recomputed from the merged command, never carried over.
             full_cmd: _,
             usage: _,
@@ -745,6 +792,11 @@ impl SpecCommand {
         if !name.is_empty() {
             self.name = name;
         }
+        // The command already declared is the one a finding about it should point at; a
+        // mounted program's spec was read from its output rather than from a file.
+        if self.source.is_none() {
+            self.source = source;
+        }
         if help.is_some() {
             self.help = help;
         }
@@ -810,6 +862,12 @@ impl SpecCommand {
         if !examples.is_empty() {
             self.examples = examples;
         }
+        if !exits.is_empty() {
+            self.exits = exits;
+        }
+        if !outputs.is_empty() {
+            self.outputs = outputs;
+        }
         self.hide = hide;
         if help_heading.is_some() {
             self.help_heading = help_heading;
@@ -846,6 +904,12 @@ impl SpecCommand {
         if effect.is_some() {
             self.effect = effect;
         }
+        if stability.is_some() {
+            self.stability = stability;
+        }
+        if since.is_some() {
+            self.since = since;
+        }
         if unknown_flags.is_some() {
             self.unknown_flags = unknown_flags;
         }
@@ -1002,6 +1066,8 @@ impl From<&SpecCommand> for KdlNode {
             deprecated_warn_at,
             deprecated_remove_at,
             effect,
+            stability,
+            since,
             flags,
             args,
             mounts,
@@ -1009,12 +1075,15 @@ impl From<&SpecCommand> for KdlNode {
             subcommands,
             complete,
             examples,
+            exits,
+            outputs,
             // Resolved while the spec was read: whatever a `use` named is among `flags`
             // by now, so emitting the request too would declare those flags twice.
             uses: _,
             // Derived from the spec rather than written by it.
             full_cmd: _,
             usage: _,
+            source: _,
             mounted: _,
             flags_from_mount: _,
             subcommand_lookup: _,
@@ -1177,6 +1246,12 @@ impl From<&SpecCommand> for KdlNode {
             node.entries_mut()
                 .push(string_entry(Some("effect"), effect.as_str()));
         }
+        if let Some(stability) = stability {
+            node.push(string_entry(Some("stability"), stability.as_str()));
+        }
+        if let Some(since) = since {
+            node.push(string_entry(Some("since"), since));
+        }
         if let Some(unknown_flags) = unknown_flags {
             node.entries_mut()
                 .push(string_entry(Some("unknown_flags"), unknown_flags.as_str()));
@@ -1203,6 +1278,14 @@ impl From<&SpecCommand> for KdlNode {
             let children = node.children_mut().get_or_insert_with(KdlDocument::new);
             children.nodes_mut().push(cmd.into());
         }
+        for exit in exits {
+            let children = node.children_mut().get_or_insert_with(KdlDocument::new);
+            children.nodes_mut().push(exit.into());
+        }
+        for output in outputs {
+            let children = node.children_mut().get_or_insert_with(KdlDocument::new);
+            children.nodes_mut().push(output.into());
+        }
         for example in examples {
             let children = node.children_mut().get_or_insert_with(KdlDocument::new);
             children.nodes_mut().push(example.into());
@@ -1610,7 +1693,7 @@ license "MIT"
 flag "-v --verbose" help="Verbose logging" global=#true count=#true
 arg "<dir>" help="Directory to use"
 
-cmd "install" help="Install a package" subcommand_required=#false {
+cmd "install" help="Install a package" subcommand_required=#false since="1.2.0" {
     alias "i"
     alias "add" hide=#true
     long_help "The long help for install"
@@ -1628,6 +1711,8 @@ cmd "install" help="Install a package" subcommand_required=#false {
     complete "pkg" run="mycli list --available" descriptions=#true
     example "mycli install foo" header="Install foo" help="Installs foo" lang="sh"
     example "mycli install bar"
+    exit 3 "lockfile out of date" name="stale"
+    output "json" flag="--force" help="What was installed" schema="{\"type\":\"object\"}"
     cmd "from" help="Install from a source" {
         arg "<src>"
     }
@@ -1635,7 +1720,7 @@ cmd "install" help="Install a package" subcommand_required=#false {
 cmd "wrapped" help="Wraps another CLI" {
     mount run="mycli plugin usage-spec"
 }
-cmd "remove" help="Remove a package" deprecated="use `uninstall`" effect="destructive"
+cmd "remove" help="Remove a package" deprecated="use `uninstall`" effect="destructive" stability="stable"
 cmd "run" restart_token=":::" help="Run tasks"
 cmd "exec" external_subcommand=#true help="Run an external command"
 cmd "hidden" hide=#true
@@ -1670,8 +1755,12 @@ cmd "hidden" hide=#true
             "after_help_md",
             "deprecated",
             "effect",
+            "stability",
+            "since",
             "restart_token",
             "examples",
+            "exits",
+            "outputs",
             "complete",
             "mounts",
             "aliases",
@@ -1679,7 +1768,7 @@ cmd "hidden" hide=#true
         ] {
             let populated = match key {
                 // These sit on other commands in the fixture.
-                "deprecated" | "effect" => {
+                "deprecated" | "effect" | "stability" => {
                     original["cmd"]["subcommands"]["remove"].get(key).is_some()
                 }
                 "restart_token" => original["cmd"]["subcommands"]["run"].get(key).is_some(),
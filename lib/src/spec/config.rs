@@ -209,6 +209,19 @@ pub struct SpecConfig {
     /// Config file locations, in ascending precedence: the last one named wins. This is
     /// the rc-style chain that docs have to describe and a resolver has to walk.
     pub files: Vec<SpecConfigFile>,
+    /// Named profiles, when the files hold any: `[profiles.<name>]` tables, or sibling files
+    /// like `ex.prod.toml`, overlaid on the base by whichever one is selected.
+    pub profiles: Option<SpecConfigProfiles>,
+}
+
+/// What selects a named profile.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct SpecConfigProfiles {
+    /// The environment variable that names the profile.
+    pub env: Option<String>,
+    /// The flag that names it, which wins over the variable.
+    pub flag: Option<String>,
 }
 
 /// A source kind's display metadata.
@@ -382,6 +395,21 @@ impl SpecConfig {
                     refuse_children(ctx, &node, "file")?;
                     config.files.push(file);
                 }
+                "profiles" => {
+                    node.ensure_arg_len(0..=0)?;
+                    let mut profiles = SpecConfigProfiles::default();
+                    for (k, v) in node.props() {
+                        match k {
+                            "env" => profiles.env = Some(v.ensure_string()?),
+                            "flag" => profiles.flag = Some(v.ensure_string()?),
+                            k => {
+                                bail_parse!(ctx, node.span(), "unsupported config profiles key {k}")
+                            }
+                        }
+                    }
+                    refuse_children(ctx, &node, "profiles")?;
+                    config.profiles = Some(profiles);
+                }
                 k => bail_parse!(ctx, node.node.name().span(), "unsupported config key {k}"),
             }
         }
@@ -408,16 +436,22 @@ impl SpecConfig {
         if !other.files.is_empty() {
             self.files = other.files.clone();
         }
+        if other.profiles.is_some() {
+            self.profiles = other.profiles.clone();
+        }
     }
 }
 
 impl SpecConfig {
     /// Whether there is nothing to write out.
     ///
-    /// All three, not just props: a `config` block that declares only where files live is a
-    /// perfectly good one, and reporting it empty made the writer drop it.
+    /// Every declaration, not just props: a `config` block that declares only where files live
+    /// is a perfectly good one, and reporting it empty made the writer drop it.
     pub fn is_empty(&self) -> bool {
-        self.props.is_empty() && self.sources.is_empty() && self.files.is_empty()
+        self.props.is_empty()
+            && self.sources.is_empty()
+            && self.files.is_empty()
+            && self.profiles.is_none()
     }
 }
 
@@ -474,6 +508,12 @@ pub struct SpecConfigProp {
     /// A named parser for turning one string into this type — `list_by_comma` and friends.
     /// Vocabulary rather than code, so any implementation can honor it.
     pub parse: Option<String>,
+    /// Expand `${env:NAME}`, `${config:key}` and `~` in its value, and read a relative path as
+    /// relative to the file that wrote it.
+    pub interpolate: bool,
+    /// A token or a password: shown as `<redacted>` wherever a value is rendered, and
+    /// `writeOnly` in the JSON schema.
+    pub sensitive: bool,
     /// Where `config set` should write this, when it is not the usual file.
     pub writes_to: Option<String>,
     pub examples: Vec<String>,
@@ -599,6 +639,12 @@ impl SpecConfigProp {
         if let Some(parse) = &self.parse {
             node.push(string_entry(Some("parse"), parse));
         }
+        if self.interpolate {
+            node.push(KdlEntry::new_prop("interpolate", true));
+        }
+        if self.sensitive {
+            node.push(KdlEntry::new_prop("sensitive", true));
+        }
         if let Some(writes_to) = &self.writes_to {
             node.push(string_entry(Some("writes_to"), writes_to));
         }
@@ -757,6 +803,8 @@ impl SpecConfigProp {
                 "hide" => prop.hide = v.ensure_bool()?,
                 "since" => prop.since = Some(v.ensure_string()?),
                 "parse" => prop.parse = Some(v.ensure_string()?),
+                "interpolate" => prop.interpolate = v.ensure_bool()?,
+                "sensitive" => prop.sensitive = v.ensure_bool()?,
                 "writes_to" => prop.writes_to = Some(v.ensure_string()?),
                 k => bail_parse!(ctx, node.span(), "unsupported config prop key {k}"),
             }
@@ -956,6 +1004,8 @@ impl Default for SpecConfigProp {
             hide: false,
             since: None,
             parse: None,
+            interpolate: false,
+            sensitive: false,
             writes_to: None,
             examples: Vec::new(),
             default_list: Vec::new(),
@@ -999,6 +1049,16 @@ impl From<&SpecConfig> for KdlNode {
             }
             doc.nodes_mut().push(node);
         }
+        if let Some(profiles) = &config.profiles {
+            let mut node = KdlNode::new("profiles");
+            if let Some(env) = &profiles.env {
+                node.push(string_entry(Some("env"), env));
+            }
+            if let Some(flag) = &profiles.flag {
+                node.push(string_entry(Some("flag"), flag));
+            }
+            doc.nodes_mut().push(node);
+        }
         for (key, prop) in &config.props {
             doc.nodes_mut().push(prop.to_kdl_node(key.to_string()));
         }
@@ -1321,6 +1381,7 @@ config {
     file "~/.config/hk/config.pkl" scope="global"
     file "hk.pkl" findup=#true
     file ".hkrc" format="ini"
+    profiles env="HK_PROFILE" flag="--profile"
     prop "jobs" type="uint" default=0 default_note="0 = auto-detect" \
         help="Number of parallel jobs" since="1.0.0" help_heading="Performance" {
         cli "--jobs" "-j"
@@ -1348,7 +1409,8 @@ config {
     }
     prop "old.key" deprecated="Use new.key" renamed_to="new.key" \
         deprecated_warn_at="2026.12.0" deprecated_remove_at="2027.12.0"
-    prop "urls" type="map<string, url>" parse="list_by_comma" writes_to="npmrc"
+    prop "urls" type="map<string, url>" parse="list_by_comma" interpolate=#true writes_to="npmrc"
+    prop "token" type="string" sensitive=#true
 }
 "##
         .parse()
@@ -1400,6 +1462,8 @@ config {
         assert_eq!(spec.config.props["trusted"].scope, SpecConfigScope::Global);
         assert_eq!(spec.config.props["ci"].scope, SpecConfigScope::Env);
         assert!(spec.config.props["ci"].hide);
+        assert!(spec.config.props["urls"].interpolate);
+        assert!(spec.config.props["token"].sensitive);
         assert_eq!(
             spec.config.props["ci"].extensions,
             [
@@ -1546,6 +1610,31 @@ config {
         assert!(spec.to_string().contains("file x.toml"), "{spec}");
     }
 
+    #[test]
+    fn what_selects_a_profile_survives_a_round_trip() {
+        let spec = Spec::parse(
+            &Default::default(),
+            "name \"x\"\nbin \"x\"\nconfig {\n  profiles env=\"X_PROFILE\" flag=\"--profile\"\n}\n",
+        )
+        .unwrap();
+        let profiles = spec.config.profiles.as_ref().expect("declared");
+        assert_eq!(profiles.env.as_deref(), Some("X_PROFILE"));
+        assert_eq!(profiles.flag.as_deref(), Some("--profile"));
+        assert!(!spec.config.is_empty());
+        let round_tripped: Spec = spec.to_string().parse().unwrap();
+        assert_eq!(round_tripped.config.profiles, spec.config.profiles);
+
+        let err = Spec::parse(
+            &Default::default(),
+            "name \"x\"\nbin \"x\"\nconfig {\n  profiles var=\"X\"\n}\n",
+        )
+        .unwrap_err();
+        assert!(
+            detail_of(&err).contains("unsupported config profiles key var"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn a_name_that_is_not_a_string_is_refused() {
         // These are all names — an environment variable, a flag, a key in another source.
@@ -1,11 +1,20 @@
 use crate::error::UsageErr;
 use miette::{NamedSource, SourceSpan};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Default)]
 pub struct ParsingContext {
     pub(crate) file: PathBuf,
     pub(crate) spec: String,
+    /// Where each line of [`Self::spec`] starts in the file: its line number, and the column
+    /// its text begins at once the comment marker is stripped.
+    ///
+    /// Empty when the spec is the file as written. A script's spec is cut out of its
+    /// `#USAGE` comments, so its first line is rarely the file's first line, and a location
+    /// counted in the text that was parsed would point at the wrong one.
+    pub(crate) lines: Vec<(usize, usize)>,
 }
 
 impl ParsingContext {
@@ -13,6 +22,33 @@ impl ParsingContext {
         Self {
             file: file.to_path_buf(),
             spec: spec.to_string(),
+            lines: vec![],
+        }
+    }
+
+    /// The same, for a spec cut out of a file rather than read whole.
+    pub(crate) fn extracted(file: &Path, spec: &str, lines: Vec<(usize, usize)>) -> Self {
+        Self {
+            lines,
+            ..Self::new(file, spec)
+        }
+    }
+
+    /// Where in the file a span of the parsed text starts.
+    pub(crate) fn locate(&self, span: SourceSpan) -> SourceLocation {
+        let before = self.spec.get(..span.offset()).unwrap_or(&self.spec);
+        let line = before.matches('\n').count();
+        let column = before[before.rfind('\n').map_or(0, |newline| newline + 1)..]
+            .chars()
+            .count();
+        let (line, column) = match self.lines.get(line) {
+            Some((at, indent)) => (*at, indent + column + 1),
+            None => (line + 1, column + 1),
+        };
+        SourceLocation {
+            file: self.file.clone(),
+            line,
+            column,
         }
     }
 
@@ -22,6 +58,40 @@ impl ParsingContext {
     }
 }
 
+/// Where a declaration was read from.
+///
+/// Kept on the commands, flags, arguments, examples and views a spec file declares, so a
+/// tool reporting on the spec can point at a line rather than describe the path to one.
+/// Lines and columns count from 1, and a column counts characters rather than bytes.
+/// `file` is empty for a spec that was parsed from a string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    /// A location a tool found by reading a spec's text itself, rather than one the parser kept.
+    pub fn new(file: impl Into<PathBuf>, line: usize, column: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.file.as_os_str().is_empty() {
+            write!(f, "{}:", self.file.display())?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +110,26 @@ mod tests {
         assert_eq!(ctx.spec, "");
     }
 
+    #[test]
+    fn test_locate() {
+        let ctx = ParsingContext::new(Path::new("a.kdl"), "name \"a\"\n  flag \"-v\"\n");
+        let at = ctx.locate((11, 4).into());
+        assert_eq!((at.line, at.column), (2, 3));
+        assert_eq!(at.to_string(), "a.kdl:2:3");
+    }
+
+    #[test]
+    fn test_locate_in_an_extracted_spec() {
+        // `flag "-v"` cut out of `#USAGE flag "-v"` on the file's fourth line.
+        let ctx = ParsingContext::extracted(
+            Path::new("a.sh"),
+            "bin \"a\"\nflag \"-v\"",
+            vec![(3, 7), (4, 7)],
+        );
+        let at = ctx.locate((8, 4).into());
+        assert_eq!((at.line, at.column), (4, 8));
+    }
+
     #[test]
     fn test_build_err() {
         let ctx = ParsingContext::new(Path::new("test.kdl"), "invalid content");
@@ -107,6 +107,7 @@ impl crate::SpecCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     use std::str::FromStr;
 
     #[test]
@@ -218,6 +219,61 @@ cmd "settings" effect="read" {
         );
     }
 
+    #[test]
+    fn test_a_parsed_invocation_counts_only_what_the_line_supplied() {
+        use crate::parse::Parser;
+        use crate::Spec;
+        // A default that writes is not the user asking to write: `--output` below
+        // always has a value, and counting it would make every `export` a write.
+        let spec: Spec = r#"
+bin "x"
+cmd "export" effect="read" {
+    flag "--output <file>" effect="write" default="out.txt"
+    flag "--prune" effect="destructive" default_missing="all" {
+        arg "[scope]"
+    }
+}
+        "#
+        .parse()
+        .unwrap();
+        let parse = |line: &[&str]| {
+            let argv: Vec<String> = line.iter().map(|s| s.to_string()).collect();
+            Parser::new(&spec).parse(&argv).unwrap().effect()
+        };
+        assert_eq!(parse(&["x", "export"]), Some(SpecCommandEffect::Read));
+        assert_eq!(
+            parse(&["x", "export", "--output", "a.txt"]),
+            Some(SpecCommandEffect::Write)
+        );
+        // Typed bare, its value filled in by the declaration: still typed.
+        assert_eq!(
+            parse(&["x", "export", "--prune"]),
+            Some(SpecCommandEffect::Destructive)
+        );
+    }
+
+    #[test]
+    fn test_the_environment_counts_where_the_command_would_run() {
+        use crate::parse::Parser;
+        use crate::Spec;
+        let spec: Spec = r#"
+bin "x"
+cmd "deploy" effect="write" {
+    flag "--force" effect="destructive" env="X_FORCE"
+}
+        "#
+        .parse()
+        .unwrap();
+        let argv = vec!["x".to_string(), "deploy".to_string()];
+        let env = HashMap::from([("X_FORCE".to_string(), "1".to_string())]);
+        let out = Parser::new(&spec).with_env(env).parse(&argv).unwrap();
+        assert_eq!(out.effect(), Some(SpecCommandEffect::Write));
+        assert_eq!(
+            out.effect_in_environment(),
+            Some(SpecCommandEffect::Destructive)
+        );
+    }
+
     #[test]
     fn test_unknown_effect_on_a_flag_is_an_error() {
         use crate::Spec;
@@ -0,0 +1,206 @@
+use kdl::{KdlEntry, KdlNode};
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::spec::context::{ParsingContext, SourceLocation};
+use crate::spec::helpers::{string_entry, NodeHelper};
+
+/// What one exit status of a command means.
+///
+/// `exit 3 "lockfile out of date"`. Declared on the root or on any command, and
+/// inherited: a subcommand exits with its parent's statuses too, unless it declares the
+/// same code itself, which then means what the subcommand says. [`crate::Spec::exits_for`]
+/// is that resolution.
+///
+/// Manpages list these under EXIT STATUS, help and markdown under "Exit status", and the
+/// TypeScript and Python SDKs raise a typed error for each non-zero one.
+#[derive(Debug, Default, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SpecExit {
+    /// The status, 0 through 255: what a shell's `$?` can hold.
+    pub code: u8,
+    /// What the status means, as a reader of help would want it said.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// An identifier for the status, which SDKs name its error after.
+    ///
+    /// Without one they use the help: `"lockfile out of date"` raises
+    /// `LockfileOutOfDateError`. A status whose help is a sentence wants a name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Where this status was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
+}
+
+impl SpecExit {
+    /// An exit status meaning `help`.
+    pub fn new(code: u8, help: impl Into<String>) -> Self {
+        Self {
+            code,
+            help: Some(help.into()),
+            ..Default::default()
+        }
+    }
+
+    /// The identifier SDKs name this status's error after.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Reads an `exit` node onto `exits`, a command's own. A code said twice by one command
+    /// would mean whichever a reader happened to see first, so it is refused.
+    pub(crate) fn parse_into(
+        ctx: &ParsingContext,
+        node: &NodeHelper,
+        exits: &mut Vec<SpecExit>,
+    ) -> Result<()> {
+        let exit = Self::parse(ctx, node)?;
+        if exits.iter().any(|e| e.code == exit.code) {
+            bail_parse!(
+                ctx,
+                node.arg(0)?.entry.span(),
+                "exit {} is already declared on this command",
+                exit.code
+            );
+        }
+        exits.push(exit);
+        Ok(())
+    }
+
+    fn parse(ctx: &ParsingContext, node: &NodeHelper) -> Result<Self> {
+        node.ensure_arg_len(1..=2)?;
+        let code = node.arg(0)?;
+        let code = match code.value.as_integer().map(u8::try_from) {
+            Some(Ok(code)) => code,
+            _ => bail_parse!(
+                ctx,
+                code.entry.span(),
+                "an exit status is a number from 0 to 255"
+            ),
+        };
+        let mut exit = SpecExit {
+            code,
+            source: Some(ctx.locate(node.node.name().span())),
+            ..Default::default()
+        };
+        if node.args().count() > 1 {
+            exit.help = Some(node.arg(1)?.ensure_string()?);
+        }
+        for (k, v) in node.props() {
+            match k {
+                "help" => exit.help = Some(v.ensure_string()?),
+                "name" => exit.name = Some(v.ensure_string()?),
+                k => bail_parse!(ctx, v.entry.span(), "unsupported exit key {k}"),
+            }
+        }
+        Ok(exit)
+    }
+}
+
+impl From<&SpecExit> for KdlNode {
+    fn from(exit: &SpecExit) -> KdlNode {
+        let mut node = KdlNode::new("exit");
+        node.push(KdlEntry::new(i128::from(exit.code)));
+        if let Some(help) = &exit.help {
+            node.push(string_entry(None, help));
+        }
+        if let Some(name) = &exit.name {
+            node.push(string_entry(Some("name"), name));
+        }
+        node
+    }
+}
+
+/// `inherited` with `own` laid over it: a code declared in both means what `own` says.
+/// Ordered by code, which is how every page that lists them reads.
+pub(crate) fn overlay(inherited: &[SpecExit], own: &[SpecExit]) -> Vec<SpecExit> {
+    let mut exits: Vec<SpecExit> = inherited
+        .iter()
+        .filter(|exit| !own.iter().any(|o| o.code == exit.code))
+        .chain(own)
+        .cloned()
+        .collect();
+    exits.sort_by_key(|exit| exit.code);
+    exits
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Spec;
+
+    #[test]
+    fn a_subcommand_inherits_its_parents_statuses_and_can_redefine_them() {
+        let spec: Spec = r#"
+            bin "tool"
+            exit 1 "something failed"
+            exit 3 "lockfile out of date" name="lockfile_outdated"
+            cmd "install" {
+                exit 3 "the package is not in the lockfile"
+                exit 4 "network unreachable"
+                cmd "one"
+            }
+        "#
+        .parse()
+        .unwrap();
+        let codes = |path: &[&str]| {
+            let mut cmd = &spec.cmd;
+            for name in path {
+                cmd = &cmd.subcommands[*name];
+            }
+            spec.exits_for(cmd)
+                .into_iter()
+                .map(|e| format!("{} {}", e.code, e.help.unwrap_or_default()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes(&[]), ["1 something failed", "3 lockfile out of date"]);
+        let install = [
+            "1 something failed",
+            "3 the package is not in the lockfile",
+            "4 network unreachable",
+        ];
+        assert_eq!(codes(&["install"]), install);
+        assert_eq!(codes(&["install", "one"]), install);
+        assert_eq!(spec.cmd.exits[1].name.as_deref(), Some("lockfile_outdated"));
+    }
+
+    #[test]
+    fn statuses_survive_a_round_trip() {
+        let spec: Spec = r#"
+            bin "tool"
+            exit 2 "usage error"
+            cmd "check" {
+                exit 3 "lockfile out of date" name="stale"
+                exit 0
+            }
+        "#
+        .parse()
+        .unwrap();
+        let again: Spec = spec.to_string().parse().unwrap();
+        assert_eq!(again.to_string(), spec.to_string());
+        insta::assert_snapshot!(spec, @r#"
+        name tool
+        bin tool
+        exit 2 "usage error"
+        cmd check {
+            exit 3 "lockfile out of date" name=stale
+            exit 0
+        }
+        "#);
+    }
+
+    #[test]
+    fn a_command_says_what_a_status_means_once() {
+        let err = "cmd \"a\" {\n  exit 3 \"one\"\n  exit 3 \"two\"\n}"
+            .parse::<Spec>()
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("exit 3 is already declared on this command"));
+    }
+
+    #[test]
+    fn a_status_is_a_byte() {
+        let err = r#"exit 256 "too big""#.parse::<Spec>().unwrap_err();
+        assert!(format!("{err:?}").contains("an exit status is a number from 0 to 255"));
+    }
+}
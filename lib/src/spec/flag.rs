@@ -9,10 +9,11 @@ use crate::error::UsageErr::InvalidFlag;
 use crate::error::{Result, UsageErr};
 use crate::spec::arg::SpecDoubleDashChoices;
 use crate::spec::builder::SpecFlagBuilder;
-use crate::spec::context::ParsingContext;
+use crate::spec::context::{ParsingContext, SourceLocation};
 use crate::spec::effect::{SpecCommandEffect, EFFECT_VALUES};
 use crate::spec::helpers::{string_entry, NodeHelper};
 use crate::spec::is_false;
+use crate::spec::stability::{stability, SpecStability};
 use crate::{string, SpecArg, SpecChoices, SpecRequiredIfEq};
 
 /// A non-binding action performed when a flag is supplied.
@@ -192,6 +193,10 @@ pub struct SpecFlag {
     /// Hide this flag only from long help.
     #[serde(skip_serializing_if = "is_false")]
     pub hide_long_help: bool,
+    /// A secret: its value is masked wherever usage renders one — `usage explain`, parse
+    /// errors, generated docs — and its environment value is never shown in help.
+    #[serde(skip_serializing_if = "is_false")]
+    pub sensitive: bool,
     /// Whether this flag is available to all subcommands
     pub global: bool,
     /// Whether this is a count flag (e.g., -vvv counts as 3)
@@ -285,6 +290,12 @@ pub struct SpecFlag {
     /// See [`crate::spec::effect::SpecCommandEffect`]; never lowers it.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effect: Option<SpecCommandEffect>,
+    /// How much this flag promises to stay as it is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stability: Option<SpecStability>,
+    /// The version that introduced this flag.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
     /// Environment variable that can set this flag's value
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<String>,
@@ -308,6 +319,10 @@ pub struct SpecFlag {
     /// Whether this flag binds a value or requests help/version output.
     #[serde(skip_serializing_if = "is_set_action")]
     pub action: SpecFlagAction,
+    /// Where this flag was declared, for one read from a spec file. A flag a `use` brought
+    /// in points at its declaration in the flagset.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
 }
 
 fn is_set_action(action: &SpecFlagAction) -> bool {
@@ -331,10 +346,12 @@ impl SpecFlag {
 
     pub(crate) fn parse(ctx: &ParsingContext, node: &NodeHelper) -> Result<Self> {
         let mut flag: Self = node.arg(0)?.ensure_string()?.parse()?;
+        flag.source = Some(ctx.locate(node.node.name().span()));
         let mut allow_hyphen_values = false;
         let mut allow_negative_numbers = false;
         let mut value_terminator: Option<String> = None;
         let mut delimiter: Option<String> = None;
+        let mut value_type = None;
         for (k, v) in node.props() {
             match k {
                 "help" => flag.help = Some(v.ensure_string()?),
@@ -355,6 +372,7 @@ impl SpecFlag {
                 "hide_possible_values" => flag.hide_possible_values = v.ensure_bool()?,
                 "hide_short_help" => flag.hide_short_help = v.ensure_bool()?,
                 "hide_long_help" => flag.hide_long_help = v.ensure_bool()?,
+                "sensitive" => flag.sensitive = v.ensure_bool()?,
                 "deprecated" => {
                     flag.deprecated = match v.value.as_bool() {
                         Some(true) => Some("deprecated".into()),
@@ -364,6 +382,8 @@ impl SpecFlag {
                 }
                 "deprecated_warn_at" => flag.deprecated_warn_at = Some(v.ensure_string()?),
                 "deprecated_remove_at" => flag.deprecated_remove_at = Some(v.ensure_string()?),
+                "stability" => flag.stability = Some(stability(ctx, &v)?),
+                "since" => flag.since = Some(v.ensure_string()?),
                 "global" => flag.global = v.ensure_bool()?,
                 "count" => flag.count = v.ensure_bool()?,
                 "action" => {
@@ -397,6 +417,8 @@ impl SpecFlag {
                 // is: the value is what gets split, and `flag "--tags <tag>"` is where a
                 // reader writes something about that value.
                 "delimiter" => delimiter = Some(v.ensure_string()?),
+                // Also the value's, for the same reason: `flag "--port <n>" type="int"`.
+                "type" => value_type = Some(crate::spec::arg::value_type(ctx, &v)?),
                 "effect" => {
                     let raw = v.ensure_string()?;
                     match raw.parse() {
@@ -486,6 +508,7 @@ impl SpecFlag {
                 }
                 "hide_short_help" => flag.hide_short_help = child.arg(0)?.ensure_bool()?,
                 "hide_long_help" => flag.hide_long_help = child.arg(0)?.ensure_bool()?,
+                "sensitive" => flag.sensitive = child.arg(0)?.ensure_bool()?,
                 "deprecated" => {
                     flag.deprecated = match child.arg(0)?.ensure_bool() {
                         Ok(true) => Some("deprecated".into()),
@@ -499,6 +522,8 @@ impl SpecFlag {
                 "deprecated_remove_at" => {
                     flag.deprecated_remove_at = Some(child.arg(0)?.ensure_string()?)
                 }
+                "stability" => flag.stability = Some(stability(ctx, &child.arg(0)?)?),
+                "since" => flag.since = Some(child.arg(0)?.ensure_string()?),
                 "global" => flag.global = child.arg(0)?.ensure_bool()?,
                 "count" => flag.count = child.arg(0)?.ensure_bool()?,
                 "action" => {
@@ -518,6 +543,7 @@ impl SpecFlag {
                 "value_terminator" => {
                     value_terminator = Some(child.arg(0)?.ensure_string()?);
                 }
+                "type" => value_type = Some(crate::spec::arg::value_type(ctx, &child.arg(0)?)?),
                 "default" => {
                     // Support both single value and multiple values
                     // default "bar"            -> vec!["bar"]
@@ -690,6 +716,17 @@ impl SpecFlag {
             };
             arg.allow_negative_numbers = true;
         }
+        if let Some(ty) = value_type {
+            let Some(arg) = flag.arg.as_mut() else {
+                bail_parse!(
+                    ctx,
+                    node.node.name().span(),
+                    "`type` describes a value, and flag --{} takes none",
+                    flag.name
+                );
+            };
+            arg.value_type = Some(ty);
+        }
         if let Some(terminator) = value_terminator {
             let Some(arg) = flag.arg.as_mut() else {
                 bail_parse!(
@@ -996,6 +1033,9 @@ impl From<&SpecFlag> for KdlNode {
                 node.push(KdlEntry::new_prop(name, true));
             }
         }
+        if flag.sensitive {
+            node.push(KdlEntry::new_prop("sensitive", true));
+        }
         if flag.global {
             node.push(KdlEntry::new_prop("global", true));
         }
@@ -1101,6 +1141,12 @@ impl From<&SpecFlag> for KdlNode {
         if let Some(effect) = &flag.effect {
             node.push(string_entry(Some("effect"), effect.as_str()));
         }
+        if let Some(stability) = &flag.stability {
+            node.push(string_entry(Some("stability"), stability.as_str()));
+        }
+        if let Some(since) = &flag.since {
+            node.push(string_entry(Some("since"), since));
+        }
         if let Some(deprecated) = &flag.deprecated {
             node.push(string_entry(Some("deprecated"), deprecated));
         }
@@ -1457,6 +1503,7 @@ impl From<&clap::Arg> for SpecFlag {
             hide_possible_values: c.is_hide_possible_values_set(),
             hide_short_help: c.is_hide_short_help_set(),
             hide_long_help: c.is_hide_long_help_set(),
+            sensitive: false,
             global: c.is_global_set(),
             arg,
             count: matches!(c.get_action(), clap::ArgAction::Count),
@@ -1476,11 +1523,14 @@ impl From<&clap::Arg> for SpecFlag {
             // clap has no way to express this; consumers set it on the derived
             // spec (see the effect docs).
             effect: None,
+            stability: None,
+            since: None,
             env: None,
             env_fallback: vec![],
             deprecated_env: vec![],
             help_heading: c.get_help_heading().map(|s| s.to_string()),
             display_order: Some(c.get_display_order()),
+            source: None,
         };
         if c.is_allow_hyphen_values_set() {
             if let Some(arg) = &mut flag.arg {
@@ -1492,52 +1542,6 @@ impl From<&clap::Arg> for SpecFlag {
     }
 }
 
-// #[cfg(feature = "clap")]
-// impl From<&SpecFlag> for clap::Arg {
-//     fn from(flag: &SpecFlag) -> Self {
-//         let mut a = clap::Arg::new(&flag.name);
-//         if let Some(desc) = &flag.help {
-//             a = a.help(desc);
-//         }
-//         if flag.required {
-//             a = a.required(true);
-//         }
-//         if let Some(arg) = &flag.arg {
-//             a = a.value_name(&arg.name);
-//             if arg.var {
-//                 a = a.action(clap::ArgAction::Append)
-//             } else {
-//                 a = a.action(clap::ArgAction::Set)
-//             }
-//         } else {
-//             a = a.action(clap::ArgAction::SetTrue)
-//         }
-//         // let mut a = clap::Arg::new(&flag.name)
-//         //     .required(flag.required)
-//         //     .action(clap::ArgAction::SetTrue);
-//         if let Some(short) = flag.short.first() {
-//             a = a.short(*short);
-//         }
-//         if let Some(long) = flag.long.first() {
-//             a = a.long(long);
-//         }
-//         for short in flag.short.iter().skip(1) {
-//             a = a.visible_short_alias(*short);
-//         }
-//         for long in flag.long.iter().skip(1) {
-//             a = a.visible_alias(long);
-//         }
-//         // cmd = cmd.arg(a);
-//         // if flag.multiple {
-//         //     a = a.multiple(true);
-//         // }
-//         // if flag.hide {
-//         //     a = a.hide_possible_values(true);
-//         // }
-//         a
-//     }
-// }
-
 impl Display for SpecFlag {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.usage())
@@ -62,7 +62,7 @@ fn quoted_format(value_repr: &str) -> KdlEntryFormat {
 /// Create a KdlEntry for a string value, using KDL raw multiline string syntax (`#"""..."""#`)
 /// when the value contains newlines. The number of `#` characters is automatically determined
 /// to ensure the value can be embedded safely.
-pub(crate) fn string_entry(key: Option<&str>, value: &str) -> KdlEntry {
+pub fn string_entry(key: Option<&str>, value: &str) -> KdlEntry {
     let mut entry = match key {
         Some(k) => KdlEntry::new_prop(k, KdlValue::String(value.to_string())),
         None => KdlEntry::new(KdlValue::String(value.to_string())),
@@ -0,0 +1,336 @@
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use indexmap::IndexMap;
+use kdl::{KdlDocument, KdlNode};
+use regex::Regex;
+use serde::Serialize;
+use strum::{Display as StrumDisplay, EnumString};
+
+use crate::error::UsageErr;
+use crate::spec::context::{ParsingContext, SourceLocation};
+use crate::spec::helpers::{string_entry, NodeHelper};
+
+/// How `usage lint` treats its rules for this spec.
+///
+/// Written as a `lint` node whose children name a rule and the level to report it at:
+///
+/// ```kdl
+/// lint {
+///     missing-flag-help "off"
+///     unsorted-flags "error"
+/// }
+/// ```
+///
+/// The spec only records what it was told. Which names are rules, and what a level means
+/// for the exit status, belong to the linter — so a spec naming a rule this version of
+/// usage does not have still reads, and the linter is the one to say so.
+#[derive(Debug, Default, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SpecLint {
+    /// The level each named rule reports at, by rule name.
+    pub rules: IndexMap<String, SpecLintRule>,
+    /// The `// usage-lint-disable` comments in the spec's files.
+    ///
+    /// Not serialized, and not re-emitted: a comment is not part of what the spec says, and
+    /// the ranges it covers are only meaningful against the file they were read from.
+    #[serde(skip)]
+    pub suppressions: Vec<SpecLintSuppression>,
+}
+
+/// One rule's entry in a `lint` node.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SpecLintRule {
+    pub level: SpecLintLevel,
+    /// Where the rule was named, for one read from a file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
+}
+
+/// The level a rule reports at. `off` drops its findings altogether.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, StrumDisplay, Serialize)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SpecLintLevel {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A `// usage-lint-disable` comment, and the lines it silences.
+///
+/// The comment goes on the line above a node or at the end of the node's first line, and
+/// covers the node with everything inside it: disabling a rule on a `cmd` disables it for
+/// that command's flags, arguments and subcommands too. With no rule names it covers every
+/// rule.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SpecLintSuppression {
+    /// The rules it names. Empty for all of them.
+    pub rules: Vec<String>,
+    /// Where the comment is.
+    pub source: SourceLocation,
+    /// The lines of [`SourceLocation::file`] it covers, from the node's first to its last.
+    pub lines: RangeInclusive<usize>,
+}
+
+impl SpecLintSuppression {
+    /// Whether a finding of `rule` at `at` is silenced by this comment.
+    pub fn covers(&self, rule: &str, at: &SourceLocation) -> bool {
+        at.file == self.source.file
+            && self.lines.contains(&at.line)
+            && (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+    }
+}
+
+/// The level values a `lint` node may use, for error messages.
+const LEVELS: &str = "off, info, warning, error";
+
+impl SpecLint {
+    pub(crate) fn parse(ctx: &ParsingContext, node: &NodeHelper<'_>) -> Result<Self, UsageErr> {
+        let mut lint = Self::default();
+        node.ensure_arg_len(0..=0)?;
+        for child in node.children() {
+            let raw = child.ensure_arg_len(1..=1)?.arg(0)?.ensure_string()?;
+            let Ok(level) = raw.parse() else {
+                bail_parse!(
+                    ctx,
+                    child.span(),
+                    "unsupported lint level {raw}, expected one of: {LEVELS}"
+                );
+            };
+            let rule = SpecLintRule {
+                level,
+                source: Some(ctx.locate(child.node.name().span())),
+            };
+            lint.rules.insert(child.name().to_string(), rule);
+        }
+        Ok(lint)
+    }
+
+    /// Read rule levels from a file of their own, holding a `lint` node as a spec would.
+    ///
+    /// How one set of levels is shared by every spec in a repository, or applied to a spec
+    /// its linter does not get to edit.
+    pub fn parse_file(file: &Path) -> Result<Self, UsageErr> {
+        let input = crate::spec::read_to_string(file)?;
+        let ctx = ParsingContext::new(file, &input);
+        let kdl: KdlDocument = input
+            .parse()
+            .map_err(|err: kdl::KdlError| UsageErr::KdlError(err))?;
+        let mut lint = Self::default();
+        for node in kdl.nodes().iter().map(|n| NodeHelper::new(&ctx, n)) {
+            match node.name() {
+                "lint" => lint.merge(Self::parse(&ctx, &node)?),
+                k => bail_parse!(
+                    ctx,
+                    node.node.name().span(),
+                    "unsupported lint config key {k}"
+                ),
+            }
+        }
+        Ok(lint)
+    }
+
+    /// Whether it sets no levels. Its comments do not count: they are not written back out.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The level `rule` was given, if it was named.
+    pub fn level(&self, rule: &str) -> Option<SpecLintLevel> {
+        self.rules.get(rule).map(|r| r.level)
+    }
+
+    /// Whether any comment silences a finding of `rule` at `at`.
+    pub fn suppressed(&self, rule: &str, at: &SourceLocation) -> bool {
+        self.suppressions.iter().any(|s| s.covers(rule, at))
+    }
+
+    /// Take another set of levels over this one: a rule both name gets `other`'s level.
+    pub fn merge(&mut self, other: SpecLint) {
+        self.rules.extend(other.rules);
+        self.suppressions.extend(other.suppressions);
+    }
+}
+
+impl From<&SpecLint> for KdlNode {
+    fn from(lint: &SpecLint) -> Self {
+        let mut node = KdlNode::new("lint");
+        let doc = node.children_mut().get_or_insert_with(KdlDocument::new);
+        for (name, rule) in &lint.rules {
+            let mut child = KdlNode::new(name.as_str());
+            child.push(string_entry(None, &rule.level.to_string()));
+            doc.nodes_mut().push(child);
+        }
+        node
+    }
+}
+
+/// `// usage-lint-disable`, then the rules it names, separated by spaces or commas.
+static DIRECTIVE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"//\s*usage-lint-disable(?:\s+([^/]*))?\s*$").unwrap());
+
+/// Every `// usage-lint-disable` comment in a parsed document, with the node each covers.
+///
+/// Read from the text rather than from the document's trivia: where KDL keeps a comment
+/// depends on what surrounds it — one after a block's `{` belongs to the block's first
+/// child — while what an author means by it depends only on the line it is on.
+pub(crate) fn suppressions(ctx: &ParsingContext, doc: &KdlDocument) -> Vec<SpecLintSuppression> {
+    if !ctx.spec.contains("usage-lint-disable") {
+        return vec![];
+    }
+    let mut starts = vec![0];
+    starts.extend(ctx.spec.match_indices('\n').map(|(i, _)| i + 1));
+    let mut found = vec![];
+    collect(ctx, &starts, doc, &mut found);
+    found
+}
+
+fn collect(
+    ctx: &ParsingContext,
+    starts: &[usize],
+    doc: &KdlDocument,
+    found: &mut Vec<SpecLintSuppression>,
+) {
+    let line_of = |offset: usize| starts.partition_point(|start| *start <= offset) - 1;
+    let text = |line: usize| {
+        let end = starts.get(line + 1).map_or(ctx.spec.len(), |next| next - 1);
+        &ctx.spec[starts[line]..end]
+    };
+    for node in doc.nodes() {
+        let span = node.span();
+        let first = line_of(node.name().span().offset());
+        let last = line_of(span.offset() + span.len().saturating_sub(1));
+        let lines =
+            ctx.locate((starts[first], 0).into()).line..=ctx.locate((starts[last], 0).into()).line;
+        // The node's own first line, then the comments directly above it.
+        let above = (0..first)
+            .rev()
+            .take_while(|line| text(*line).trim_start().starts_with("//"));
+        for line in std::iter::once(first).chain(above) {
+            let Some(captures) = DIRECTIVE.captures(text(line)) else {
+                continue;
+            };
+            let rules = captures.get(1).map_or(vec![], |names| {
+                names
+                    .as_str()
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|name| !name.is_empty())
+                    .map(String::from)
+                    .collect()
+            });
+            let at = starts[line] + captures.get(0).unwrap().start();
+            found.push(SpecLintSuppression {
+                rules,
+                source: ctx.locate((at, 0).into()),
+                lines: lines.clone(),
+            });
+        }
+        if let Some(children) = node.children() {
+            collect(ctx, starts, children, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spec;
+
+    /// What each comment in a spec read from a string names, and the lines it covers.
+    fn covered(spec: &str) -> Vec<(Vec<String>, RangeInclusive<usize>)> {
+        let ctx = ParsingContext::new(Path::new(""), spec);
+        let doc: KdlDocument = spec.parse().unwrap();
+        suppressions(&ctx, &doc)
+            .into_iter()
+            .map(|s| (s.rules, s.lines))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_lint_node() {
+        let spec: Spec = r#"
+bin "demo"
+lint {
+    missing-flag-help "off"
+    unsorted-flags "error"
+}
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            spec.lint.level("missing-flag-help"),
+            Some(SpecLintLevel::Off)
+        );
+        assert_eq!(
+            spec.lint.level("unsorted-flags"),
+            Some(SpecLintLevel::Error)
+        );
+        assert_eq!(spec.lint.level("missing-arg-help"), None);
+        assert_eq!(
+            spec.lint.rules["unsorted-flags"]
+                .source
+                .as_ref()
+                .unwrap()
+                .line,
+            5
+        );
+        // Written back out, so a spec that was generated keeps its levels.
+        assert!(spec
+            .to_string()
+            .contains("lint {\n    missing-flag-help off\n"));
+    }
+
+    #[test]
+    fn test_parse_lint_node_refuses_an_unknown_level() {
+        let err = "lint { missing-flag-help \"loud\" }"
+            .parse::<Spec>()
+            .unwrap_err();
+        let UsageErr::InvalidInput(reason, ..) = &err else {
+            panic!("{err:?}");
+        };
+        assert!(reason.contains("expected one of: off"), "{reason}");
+    }
+
+    #[test]
+    fn test_suppression_covers_the_node_below_it_and_its_children() {
+        let found = covered(
+            r#"bin "demo"
+// usage-lint-disable missing-flag-help, missing-arg-help
+cmd "a" {
+    flag "--x"
+}
+flag "--y" // usage-lint-disable
+cmd "b" { // usage-lint-disable missing-cmd-help
+    flag "--z"
+}
+"#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                (
+                    vec!["missing-flag-help".into(), "missing-arg-help".into()],
+                    3..=5
+                ),
+                (vec![], 6..=6),
+                (vec!["missing-cmd-help".into()], 7..=9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suppression_in_a_script_covers_the_script_lines() {
+        let spec = Spec::parse_script_str(
+            "#!/bin/sh\n#USAGE bin \"demo\"\n#USAGE // usage-lint-disable\n#USAGE flag \"--x\"\n",
+        )
+        .unwrap();
+        let suppression = &spec.lint.suppressions[0];
+        assert_eq!(suppression.lines, 4..=4);
+        assert_eq!((suppression.source.line, suppression.source.column), (3, 8));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod arg;
 pub mod builder;
 pub mod choices;
+#[cfg(feature = "clap")]
+pub mod clap_command;
 pub mod cmd;
 pub mod complete;
 pub mod config;
@@ -8,14 +10,20 @@ pub mod config_type;
 mod context;
 pub mod data_types;
 pub mod effect;
+pub mod exit;
 pub mod flag;
 pub mod flagset;
 pub mod group;
 pub mod helpers;
+pub mod lint;
 pub mod mount;
+pub mod output;
+pub mod stability;
 pub mod unknown_flags;
 pub mod view;
 
+pub use context::SourceLocation;
+
 use indexmap::IndexMap;
 use kdl::{KdlDocument, KdlEntry, KdlNode};
 use log::{info, warn};
@@ -34,7 +42,8 @@ use crate::spec::config::SpecConfig;
 use crate::spec::context::ParsingContext;
 use crate::spec::flagset::{SpecFlagSet, SpecUse};
 use crate::spec::helpers::{string_entry, NodeHelper};
-use crate::{SpecArg, SpecComplete, SpecFlag};
+use crate::spec::lint::SpecLint;
+use crate::{SpecArg, SpecComplete, SpecExit, SpecFlag, SpecOutput, SpecSchema};
 use view::SpecView;
 
 #[derive(Debug, Default, Clone, Serialize)]
@@ -53,6 +62,10 @@ pub struct Spec {
     /// Named executable surfaces promoted from commands in this canonical spec.
     #[serde(skip_serializing_if = "IndexMap::is_empty")]
     pub views: IndexMap<String, SpecView>,
+    /// Named JSON Schemas for commands' outputs, by name: the `$defs` every output schema
+    /// can refer to.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub schemas: IndexMap<String, SpecSchema>,
     /// Reusable flag declarations, by name.
     ///
     /// Not serialized, and not re-emitted: a `use` is resolved while the file is read, so by
@@ -139,6 +152,16 @@ pub struct Spec {
     /// What to do with a flag-like token that names no declared flag, for the whole
     /// CLI. A command may override it; see [`SpecCommand::unknown_flags`].
     pub unknown_flags: Option<crate::spec::unknown_flags::UnknownFlags>,
+    /// The environment variable that opts in to experimental commands, flags and arguments.
+    ///
+    /// Unset, `stability="experimental"` is a label and nothing more. Set, the parser refuses
+    /// an experimental item a command line names unless this variable is `1` or `true`, so a
+    /// user cannot come to depend on one by accident.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental_env: Option<String>,
+    /// How `usage lint` treats its rules for this spec, and the comments that silence them.
+    #[serde(skip_serializing_if = "SpecLint::is_empty")]
+    pub lint: SpecLint,
 }
 
 impl Spec {
@@ -192,12 +215,31 @@ impl Spec {
         Self::parse_file_with_metadata_inference(file, true)
     }
 
+    /// Parse a spec from what a file holds, as [`Self::parse_file`] would parse the file if
+    /// `text` were on disk.
+    ///
+    /// For an editor, whose buffer is ahead of the file it came from: `include`s resolve
+    /// against `file`'s directory, a script's spec is cut out of its `#USAGE` comments, and
+    /// `bin` falls back to the file name, all as they would for the saved file.
+    #[must_use = "parsing result should be used"]
+    pub fn parse_file_contents(file: &Path, text: &str) -> Result<Spec, UsageErr> {
+        Self::parse_contents(file, text, true)
+    }
+
     fn parse_file_with_metadata_inference(
         file: &Path,
         infer_metadata_from_filename: bool,
     ) -> Result<Spec, UsageErr> {
-        let spec = split_script(file)?;
-        let ctx = ParsingContext::new(file, &spec);
+        Self::parse_contents(file, &read_to_string(file)?, infer_metadata_from_filename)
+    }
+
+    fn parse_contents(
+        file: &Path,
+        text: &str,
+        infer_metadata_from_filename: bool,
+    ) -> Result<Spec, UsageErr> {
+        let (spec, lines) = split_script(text);
+        let ctx = ParsingContext::extracted(file, &spec, lines);
         let mut schema = Self::parse(&ctx, &spec)?;
         if infer_metadata_from_filename && schema.bin.is_empty() {
             schema.bin = file
@@ -245,14 +287,14 @@ impl Spec {
     }
 
     fn parse_script_with_path(input: &str, file: &Path) -> Result<Spec, UsageErr> {
-        let raw = extract_usage_from_comments(input);
-        let ctx = ParsingContext::new(file, &raw);
+        let (raw, lines) = extract_usage_lines(input);
+        let ctx = ParsingContext::extracted(file, &raw, lines);
         Self::parse(&ctx, &raw)
     }
 
     #[deprecated]
     pub fn parse_spec(input: &str) -> Result<Spec, UsageErr> {
-        Self::parse(&Default::default(), input)
+        Self::parse(&ParsingContext::new(Path::new(""), input), input)
     }
 
     pub fn is_empty(&self) -> bool {
@@ -263,9 +305,27 @@ impl Spec {
             && self.config.is_empty()
             && self.complete.is_empty()
             && self.views.is_empty()
+            && self.schemas.is_empty()
             && self.examples.is_empty()
     }
 
+    /// What each exit status of `cmd` means: those it declares, over those its ancestors do.
+    ///
+    /// `cmd` is found by its `full_cmd`, so it must be a command of this spec. The result is
+    /// ordered by code.
+    pub fn exits_for(&self, cmd: &SpecCommand) -> Vec<SpecExit> {
+        let mut exits = exit::overlay(&[], &self.cmd.exits);
+        let mut current = &self.cmd;
+        for name in &cmd.full_cmd {
+            let Some(next) = current.find_subcommand(name) else {
+                break;
+            };
+            current = next;
+            exits = exit::overlay(&exits, &current.exits);
+        }
+        exits
+    }
+
     /// Materialize one declared executable view.
     ///
     /// This is a cold-path operation for documentation and completion generation. The canonical
@@ -286,6 +346,9 @@ impl Spec {
             })?;
         }
         let mut promoted = command.clone();
+        // The promoted command's statuses are what its root now exits with, so its ancestors'
+        // come along rather than being lost with them.
+        promoted.exits = self.exits_for(command);
         let matches_selector = |flag: &SpecFlag, selector: &str| {
             selector
                 .strip_prefix("--")
@@ -445,11 +508,19 @@ impl Spec {
         if !ctx.file.as_os_str().is_empty() {
             schema.sources.push(ctx.file.clone());
         }
+        // The root command has no node of its own. What names it is `bin`, or `name` without
+        // one, so that is where a finding about the root points.
+        let mut named_at = None;
+        let mut bin_at = None;
         for node in kdl.nodes().iter().map(|n| NodeHelper::new(ctx, n)) {
             match node.name() {
-                "name" => schema.name = node.arg(0)?.ensure_string()?,
+                "name" => {
+                    schema.name = node.arg(0)?.ensure_string()?;
+                    named_at = Some(ctx.locate(node.node.name().span()));
+                }
                 "bin" => {
                     schema.bin = node.arg(0)?.ensure_string()?;
+                    bin_at = Some(ctx.locate(node.node.name().span()));
                     if schema.name.is_empty() {
                         schema.name.clone_from(&schema.bin);
                     }
@@ -511,6 +582,15 @@ impl Spec {
                 // subcommands by running something. A CLI whose top-level commands
                 // come from plugins has no other way to say so.
                 "mount" => schema.cmd.mounts.push(crate::SpecMount::parse(ctx, &node)?),
+                "exit" => SpecExit::parse_into(ctx, &node, &mut schema.cmd.exits)?,
+                "output" => SpecOutput::parse_into(ctx, &node, &mut schema.cmd.outputs)?,
+                "schema" => {
+                    let named = SpecSchema::parse(ctx, &node)?;
+                    if schema.schemas.contains_key(&named.name) {
+                        bail_parse!(ctx, node.span(), "a schema may be declared only once");
+                    }
+                    schema.schemas.insert(named.name.clone(), named);
+                }
                 "cmd" => {
                     let node: SpecCommand = SpecCommand::parse(ctx, &node)?;
                     schema.cmd.subcommands.insert(node.name.to_string(), node);
@@ -528,6 +608,7 @@ impl Spec {
                     schema.cmd.uses.push(SpecUse::parse(ctx, &node, at)?);
                 }
                 "config" => schema.config = SpecConfig::parse(ctx, &node)?,
+                "lint" => schema.lint.merge(SpecLint::parse(ctx, &node)?),
                 "complete" => {
                     let complete = SpecComplete::parse(ctx, &node)?;
                     schema.complete.insert(complete.name.clone(), complete);
@@ -563,6 +644,7 @@ impl Spec {
                 "default_subcommand" => {
                     schema.default_subcommand = Some(node.arg(0)?.ensure_string()?)
                 }
+                "experimental_env" => schema.experimental_env = Some(node.arg(0)?.ensure_string()?),
                 "multicall" => {
                     schema.multicall = node.arg(0)?.ensure_bool()?;
                     schema.multicall_set = true;
@@ -639,6 +721,7 @@ impl Spec {
                             k => bail_parse!(ctx, v.entry.span(), "unsupported example key {k}"),
                         }
                     }
+                    example.source = Some(ctx.locate(node.node.name().span()));
                     schema.examples.push(example);
                 }
                 "include" => {
@@ -707,8 +790,16 @@ impl Spec {
         } else {
             schema.bin.clone()
         };
+        if let Some(at) = bin_at.or(named_at) {
+            schema.cmd.source = Some(at);
+        }
+        schema
+            .lint
+            .suppressions
+            .extend(lint::suppressions(ctx, &kdl));
         // Before ancestors, because a command's usage string is built from its flags.
         flagset::expand(ctx, &mut schema.cmd, &mut schema.flagsets)?;
+        output::check(ctx, &schema)?;
         set_subcommand_ancestors(&mut schema.cmd, &[]);
         Ok(schema)
     }
@@ -761,8 +852,10 @@ impl Spec {
             self.multicall_set = true;
         }
         merge_opt!(unknown_flags);
+        merge_opt!(experimental_env);
         merge_extend!(complete);
         merge_extend!(views);
+        merge_extend!(schemas);
         // An included file's sets are visible to the file that includes it, which is how a
         // spec keeps its shared declarations in a file of their own. Its own `use` nodes are
         // already resolved by the time it gets here, so nothing is expanded twice. Two files
@@ -777,6 +870,7 @@ impl Spec {
         if !other.config.is_empty() {
             self.config.merge(&other.config);
         }
+        self.lint.merge(other.lint);
         self.cmd.merge(other.cmd);
     }
 }
@@ -801,7 +895,7 @@ pub(crate) fn spec_flag_forms_overlap(a: &SpecFlag, b: &SpecFlag) -> bool {
         || short_forms(a).any(|name| short_forms(b).any(|other| other == name))
 }
 
-fn flag_matches_selector(flag: &SpecFlag, selector: &str) -> bool {
+pub(crate) fn flag_matches_selector(flag: &SpecFlag, selector: &str) -> bool {
     selector.strip_prefix("--").is_some_and(|name| {
         flag.long
             .iter()
@@ -841,7 +935,7 @@ fn check_usage_version(version: &str) {
 ///
 /// `std::fs::read_to_string` reports "No such file or directory" and nothing about which
 /// file, and these paths come from a command line.
-fn read_to_string(file: &Path) -> Result<String, UsageErr> {
+pub(crate) fn read_to_string(file: &Path) -> Result<String, UsageErr> {
     std::fs::read_to_string(file).map_err(|err| UsageErr::FileError(err, file.to_path_buf()))
 }
 
@@ -856,24 +950,47 @@ static HAS_USAGE_COMMENT: LazyLock<Regex> =
 /// A comment line with nothing on it, which continues a spec rather than ending it.
 static BLANK_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:#|//|::)\s*$").unwrap());
 
-fn split_script(file: &Path) -> Result<String, UsageErr> {
-    let full = read_to_string(file)?;
+/// The spec a file holds, with where each of its lines came from when it was cut out of
+/// comments rather than being the file as written.
+fn split_script(full: &str) -> (String, Vec<(usize, usize)>) {
     // If file has a shebang and USAGE comments, extract the spec from comments
     if full.starts_with("#!") && full.lines().any(|l| HAS_USAGE_COMMENT.is_match(l)) {
-        return Ok(extract_usage_from_comments(&full));
+        return extract_usage_lines(full);
     }
     // Otherwise treat the whole file as a KDL spec (e.g., .usage.kdl files)
-    Ok(full)
+    (full.to_string(), vec![])
 }
 
-fn extract_usage_from_comments(full: &str) -> String {
+/// For a script whose spec is in its `#USAGE` comments, where each line of that spec was
+/// taken from: the script's line, counted from 1, and the column the line's text starts at,
+/// counted in characters from 0. `None` for a file that is a spec as written.
+///
+/// What maps a span in a parse error back to the script, since the error's source is the
+/// spec that was cut out of it rather than the file.
+pub fn script_lines(text: &str) -> Option<Vec<(usize, usize)>> {
+    let (_, lines) = split_script(text);
+    (!lines.is_empty()).then_some(lines)
+}
+
+/// The spec in a script's `#USAGE` comments, and for each of its lines the line of the
+/// script it was taken from and the column its text starts at there.
+fn extract_usage_lines(full: &str) -> (String, Vec<(usize, usize)>) {
     let mut usage = vec![];
+    let mut lines = vec![];
     let mut found = false;
-    for line in full.lines() {
+    for (number, line) in full.lines().enumerate() {
         if let Some(captures) = USAGE_COMMENT.captures(line) {
             found = true;
-            let content = captures.get(1).map_or("", |m| m.as_str());
-            usage.push(content.trim());
+            let (start, content) = captures
+                .get(1)
+                .map_or((line.len(), ""), |m| (m.start(), m.as_str()));
+            let text = content.trim();
+            let indent = line[..start].chars().count()
+                + content[..content.len() - content.trim_start().len()]
+                    .chars()
+                    .count();
+            usage.push(text);
+            lines.push((number + 1, indent));
         } else if found {
             // Allow blank comment lines to continue parsing
             if BLANK_COMMENT.is_match(line) {
@@ -883,7 +1000,7 @@ fn extract_usage_from_comments(full: &str) -> String {
             break;
         }
     }
-    usage.join("\n")
+    (usage.join("\n"), lines)
 }
 
 fn set_subcommand_ancestors(cmd: &mut SpecCommand, ancestors: &[String]) {
@@ -1005,6 +1122,11 @@ impl Display for Spec {
             node.push(string_entry(None, default_subcommand));
             nodes.push(node);
         }
+        if let Some(env) = &self.experimental_env {
+            let mut node = KdlNode::new("experimental_env");
+            node.push(string_entry(None, env));
+            nodes.push(node);
+        }
         if self.multicall_set {
             let mut node = KdlNode::new("multicall");
             node.push(KdlEntry::new(self.multicall));
@@ -1134,6 +1256,15 @@ impl Display for Spec {
         for group in self.cmd.groups.iter() {
             nodes.push(group.into());
         }
+        for exit in self.cmd.exits.iter() {
+            nodes.push(exit.into());
+        }
+        for output in self.cmd.outputs.iter() {
+            nodes.push(output.into());
+        }
+        for schema in self.schemas.values() {
+            nodes.push(schema.into());
+        }
         for example in self.examples.iter() {
             nodes.push(example.into());
         }
@@ -1156,6 +1287,9 @@ impl Display for Spec {
         if !self.config.is_empty() {
             nodes.push((&self.config).into());
         }
+        if !self.lint.is_empty() {
+            nodes.push((&self.lint).into());
+        }
         doc.autoformat_config(&kdl::FormatConfigBuilder::new().build());
         write!(f, "{doc}")
     }
@@ -1165,7 +1299,9 @@ impl FromStr for Spec {
     type Err = UsageErr;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::parse(&Default::default(), s)
+        // The text goes in the context too, or every location would be counted in an
+        // empty string and land on the first line.
+        Self::parse(&ParsingContext::new(Path::new(""), s), s)
     }
 }
 
@@ -1197,6 +1333,17 @@ impl From<&clap::Command> for Spec {
     }
 }
 
+/// What the value of a `sensitive` flag or argument is rendered as.
+pub const REDACTED: &str = "<redacted>";
+
+/// `value`, or [`REDACTED`] when it belongs to a `sensitive` flag or argument.
+pub fn redacted(sensitive: bool, value: &str) -> &str {
+    match sensitive {
+        true => REDACTED,
+        false => value,
+    }
+}
+
 #[inline]
 pub fn is_true(b: &bool) -> bool {
     *b
@@ -1616,7 +1763,7 @@ cmd "run"
         $(
             #[test]
             fn $name() {
-                let result = extract_usage_from_comments($input);
+                let (result, _) = extract_usage_lines($input);
                 let expected = $expected.trim_start_matches('\n').trim_end();
                 assert_eq!(result, expected);
             }
@@ -2003,6 +2150,20 @@ echo "hello"
         assert_eq!(spec.cmd.flags[0].long, ["foo"]);
     }
 
+    #[test]
+    fn test_parse_script_str_locates_declarations_in_the_script() {
+        // Counted in the script, not in the spec cut out of its comments: the flag is on the
+        // script's fourth line, after `#USAGE ` and two spaces of indent.
+        let spec = Spec::parse_script_str(
+            "#!/bin/bash\n# a script\n#USAGE bin \"test\"\n#USAGE   flag \"--foo\"\n",
+        )
+        .unwrap();
+        let at = spec.cmd.flags[0].source.as_ref().unwrap();
+        assert_eq!((at.line, at.column), (4, 10));
+        let root = spec.cmd.source.as_ref().unwrap();
+        assert_eq!((root.line, root.column), (3, 8));
+    }
+
     #[test]
     fn test_parse_script_str_rejects_relative_includes() {
         let err = Spec::parse_script_str(r#"#USAGE include file="relative.usage.kdl""#)
@@ -2031,6 +2192,24 @@ echo "hello"
         assert!(spec.cmd.name.is_empty());
     }
 
+    #[test]
+    fn file_contents_parse_as_the_file_would_if_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let included = dir.path().join("flags.usage.kdl");
+        let root = dir.path().join("tool");
+        std::fs::write(&included, "flag \"--verbose\"\n").unwrap();
+
+        // Not on disk: the text is all there is of it.
+        let text = "#!/usr/bin/env bash\n#USAGE include file=\"./flags.usage.kdl\"\n#USAGE  arg \"<file>\"\necho\n";
+        let spec = Spec::parse_file_contents(&root, text).unwrap();
+
+        assert_eq!(spec.bin, "tool");
+        assert!(spec.cmd.flags.iter().any(|f| f.name == "verbose"));
+        assert_eq!(spec.sources, vec![root, included]);
+        assert_eq!(script_lines(text), Some(vec![(2, 7), (3, 8)]));
+        assert_eq!(script_lines("arg \"<file>\"\n"), None);
+    }
+
     #[test]
     fn injected_nested_mounts_ignore_the_mounted_specs_root_default() {
         let mut spec: Spec = "mount run=outer".parse().unwrap();
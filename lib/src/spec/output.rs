@@ -0,0 +1,470 @@
+use kdl::{KdlEntry, KdlNode};
+use miette::SourceSpan;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::spec::cmd::SpecCommand;
+use crate::spec::context::{ParsingContext, SourceLocation};
+use crate::spec::helpers::{string_entry, NodeHelper};
+use crate::{Spec, SpecFlag};
+
+/// How a command's output is laid out.
+#[derive(
+    Debug, Default, Clone, Copy, Serialize, PartialEq, Eq, strum::EnumString, strum::Display,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SpecOutputFormat {
+    /// One JSON document.
+    Json,
+    /// One JSON document per line.
+    Jsonl,
+    /// Text for a person to read, which nothing should parse.
+    #[default]
+    Text,
+}
+
+/// What a command prints, and how to ask for it.
+///
+/// `output "json" flag="--json" type="Package"`. A command declares one per format it can
+/// print, and at most one without a `flag`: the one it prints when nobody asks. The schema
+/// is inline (`schema=#"{"type": "array"}"#`) or names a top-level `schema` node, which is
+/// what makes one shape shareable between commands.
+///
+/// The TypeScript and Python SDKs generate a typed method for each JSON output, markdown
+/// lists them under "Output", and the MCP server advertises the schema as the command's
+/// `outputSchema`.
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SpecOutput {
+    pub format: SpecOutputFormat,
+    /// The flag that switches to this output: `--json`, or `--format json` for one that
+    /// takes a value. `None` for what the command prints by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// A JSON Schema for the output, written inline. For `jsonl`, the schema of one line.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+    /// The name of a top-level `schema` the output conforms to, in place of an inline one.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    /// Where this output was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
+    /// Kept for the errors about its flag and type, which can only be raised once the whole
+    /// file is read.
+    #[serde(skip)]
+    pub(crate) span: SourceSpan,
+}
+
+/// A named JSON Schema, declared once at the top level of a spec.
+///
+/// `schema "Package" #"{"type": "object", ...}"#`. An output names it with `type=`, and any
+/// schema can refer to it as `{"$ref": "#/$defs/Package"}`: these are the spec's `$defs`.
+#[derive(Debug, Default, Clone, Serialize)]
+#[non_exhaustive]
+pub struct SpecSchema {
+    pub name: String,
+    pub schema: Value,
+    /// Where this schema was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
+}
+
+impl Default for SpecOutput {
+    fn default() -> Self {
+        Self {
+            format: SpecOutputFormat::default(),
+            flag: None,
+            help: None,
+            schema: None,
+            type_name: None,
+            source: None,
+            // Nowhere to point, as for a flagset: only an output read from a file can fail
+            // the checks this span serves.
+            span: (0, 0).into(),
+        }
+    }
+}
+
+impl SpecOutput {
+    /// An output in `format`, printed by default.
+    pub fn new(format: SpecOutputFormat) -> Self {
+        Self {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// The flag that switches to this output.
+    pub fn flag(mut self, flag: impl Into<String>) -> Self {
+        self.flag = Some(flag.into());
+        self
+    }
+
+    /// An inline JSON Schema for the output.
+    pub fn schema(mut self, schema: Value) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// The top-level schema the output conforms to.
+    pub fn type_name(mut self, name: impl Into<String>) -> Self {
+        self.type_name = Some(name.into());
+        self
+    }
+
+    /// Whether a program can parse this output.
+    pub fn is_structured(&self) -> bool {
+        self.format != SpecOutputFormat::Text
+    }
+
+    /// The flag's own spelling and, for one that takes a value, the value: `--format json`
+    /// and `--format=json` are both `("--format", Some("json"))`.
+    pub fn switch(&self) -> Option<(&str, Option<&str>)> {
+        let flag = self.flag.as_deref()?.trim();
+        match flag.split_once(['=', ' ']) {
+            Some((name, value)) => Some((name, Some(value.trim()))),
+            None => Some((flag, None)),
+        }
+    }
+
+    /// Reads an `output` node onto `outputs`, a command's own.
+    pub(crate) fn parse_into(
+        ctx: &ParsingContext,
+        node: &NodeHelper,
+        outputs: &mut Vec<SpecOutput>,
+    ) -> Result<()> {
+        let output = Self::parse(ctx, node)?;
+        // Two of a format would give an SDK two methods of one name, and a reader no way
+        // to tell which one the command means.
+        if outputs.iter().any(|o| o.format == output.format) {
+            bail_parse!(
+                ctx,
+                node.arg(0)?.entry.span(),
+                "{} output is already declared on this command",
+                output.format
+            );
+        }
+        if output.flag.is_none() && outputs.iter().any(|o| o.flag.is_none()) {
+            bail_parse!(
+                ctx,
+                node.span(),
+                "a command prints one output when no flag asks for another: give this one a `flag`"
+            );
+        }
+        outputs.push(output);
+        Ok(())
+    }
+
+    fn parse(ctx: &ParsingContext, node: &NodeHelper) -> Result<Self> {
+        node.ensure_arg_len(1..=1)?;
+        let format = node.arg(0)?;
+        let mut output = SpecOutput {
+            format: match format.ensure_string()?.parse() {
+                Ok(format) => format,
+                Err(_) => bail_parse!(ctx, format.entry.span(), "an output is json, jsonl or text"),
+            },
+            source: Some(ctx.locate(node.node.name().span())),
+            span: node.span(),
+            ..Default::default()
+        };
+        for (k, v) in node.props() {
+            match k {
+                "flag" => output.flag = Some(v.ensure_string()?),
+                "help" => output.help = Some(v.ensure_string()?),
+                "schema" => {
+                    output.schema = Some(parse_schema(ctx, &v.ensure_string()?, v.entry.span())?)
+                }
+                "type" => output.type_name = Some(v.ensure_string()?),
+                k => bail_parse!(ctx, v.entry.span(), "unsupported output key {k}"),
+            }
+        }
+        if output.schema.is_some() && output.type_name.is_some() {
+            bail_parse!(
+                ctx,
+                node.span(),
+                "an output has an inline `schema` or a `type`, not both"
+            );
+        }
+        if !output.is_structured() && (output.schema.is_some() || output.type_name.is_some()) {
+            bail_parse!(
+                ctx,
+                node.span(),
+                "text output has no schema: it is for people, not programs"
+            );
+        }
+        Ok(output)
+    }
+}
+
+impl SpecSchema {
+    /// A schema named `name`.
+    pub fn new(name: impl Into<String>, schema: Value) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn parse(ctx: &ParsingContext, node: &NodeHelper) -> Result<Self> {
+        node.ensure_arg_len(2..=2)?;
+        let body = node.arg(1)?;
+        let schema = parse_schema(ctx, &body.ensure_string()?, body.entry.span())?;
+        if let Some((k, v)) = node.props().into_iter().next() {
+            bail_parse!(ctx, v.entry.span(), "unsupported schema key {k}");
+        }
+        Ok(Self {
+            name: node.arg(0)?.ensure_string()?,
+            schema,
+            source: Some(ctx.locate(node.node.name().span())),
+        })
+    }
+}
+
+fn parse_schema(ctx: &ParsingContext, text: &str, span: SourceSpan) -> Result<Value> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(schema @ (Value::Object(_) | Value::Bool(_))) => Ok(schema),
+        Ok(_) => bail_parse!(ctx, span, "a JSON Schema is an object"),
+        Err(err) => bail_parse!(ctx, span, "not a JSON Schema: {err}"),
+    }
+}
+
+impl From<&SpecOutput> for KdlNode {
+    fn from(output: &SpecOutput) -> KdlNode {
+        let mut node = KdlNode::new("output");
+        node.push(KdlEntry::new(output.format.to_string()));
+        if let Some(flag) = &output.flag {
+            node.push(string_entry(Some("flag"), flag));
+        }
+        if let Some(help) = &output.help {
+            node.push(string_entry(Some("help"), help));
+        }
+        if let Some(schema) = &output.schema {
+            node.push(string_entry(Some("schema"), &schema.to_string()));
+        }
+        if let Some(name) = &output.type_name {
+            node.push(string_entry(Some("type"), name));
+        }
+        node
+    }
+}
+
+impl From<&SpecSchema> for KdlNode {
+    fn from(schema: &SpecSchema) -> KdlNode {
+        let mut node = KdlNode::new("schema");
+        node.push(string_entry(None, &schema.name));
+        let body = serde_json::to_string_pretty(&schema.schema).expect("a JSON value serializes");
+        node.push(string_entry(None, &body));
+        node
+    }
+}
+
+impl Spec {
+    /// The JSON Schema `output` conforms to, standing alone: a `type` is resolved, and the
+    /// named schemas it refers to come along as its `$defs`. `None` for one that declares
+    /// no schema.
+    pub fn output_schema(&self, output: &SpecOutput) -> Option<Value> {
+        let mut schema = match (&output.schema, &output.type_name) {
+            (Some(schema), _) => schema.clone(),
+            (None, Some(name)) => serde_json::json!({ "$ref": format!("#/$defs/{name}") }),
+            (None, None) => return None,
+        };
+        let mut defs = serde_json::Map::new();
+        let mut pending = vec![schema.clone()];
+        while let Some(next) = pending.pop() {
+            for name in schema_refs(&next) {
+                if defs.contains_key(&name) {
+                    continue;
+                }
+                if let Some(named) = self.schemas.get(&name) {
+                    pending.push(named.schema.clone());
+                    defs.insert(name, named.schema.clone());
+                }
+            }
+        }
+        if let (Value::Object(map), false) = (&mut schema, defs.is_empty()) {
+            let own = map
+                .entry("$defs")
+                .or_insert_with(|| Value::Object(Default::default()));
+            if let Value::Object(own) = own {
+                for (name, def) in defs {
+                    own.entry(name).or_insert(def);
+                }
+            }
+        }
+        Some(schema)
+    }
+}
+
+/// The names every `{"$ref": "#/$defs/<name>"}` in `schema` refers to.
+pub(crate) fn schema_refs(schema: &Value) -> Vec<String> {
+    let mut refs = vec![];
+    let mut pending = vec![schema];
+    while let Some(next) = pending.pop() {
+        match next {
+            Value::Object(map) => {
+                if let Some(name) = map
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .and_then(|r| r.strip_prefix("#/$defs/"))
+                {
+                    refs.push(name.to_string());
+                }
+                pending.extend(map.values());
+            }
+            Value::Array(items) => pending.extend(items),
+            _ => {}
+        }
+    }
+    refs
+}
+
+/// Refuses an output whose `flag` the command cannot be given or whose `type` names no
+/// schema. Run once the whole file is read, because a flag may be declared after the
+/// output that names it, and a schema anywhere at the top level.
+pub(crate) fn check(ctx: &ParsingContext, spec: &Spec) -> Result<()> {
+    fn walk<'a>(
+        ctx: &ParsingContext,
+        spec: &Spec,
+        cmd: &'a SpecCommand,
+        globals: &mut Vec<&'a SpecFlag>,
+    ) -> Result<()> {
+        for output in &cmd.outputs {
+            if let Some(name) = &output.type_name {
+                if !spec.schemas.contains_key(name) {
+                    bail_parse!(
+                        ctx,
+                        output.span,
+                        "output type \"{name}\" names no schema: declare it with `schema \"{name}\" ...`"
+                    );
+                }
+            }
+            let Some((name, value)) = output.switch() else {
+                continue;
+            };
+            let flag = cmd
+                .flags
+                .iter()
+                .chain(globals.iter().copied())
+                .find(|flag| crate::spec::flag_matches_selector(flag, name));
+            let Some(flag) = flag else {
+                bail_parse!(
+                    ctx,
+                    output.span,
+                    "output flag {name} is not a flag of this command"
+                );
+            };
+            if flag.var || flag.count {
+                bail_parse!(
+                    ctx,
+                    output.span,
+                    "output flag {name} is repeatable: one that switches output is given once"
+                );
+            }
+            match (&flag.arg, value) {
+                (Some(_), None) => bail_parse!(
+                    ctx,
+                    output.span,
+                    "output flag {name} takes a value: say which, as in `flag=\"{name} json\"`"
+                ),
+                (None, Some(_)) => {
+                    bail_parse!(ctx, output.span, "output flag {name} takes no value")
+                }
+                _ => {}
+            }
+        }
+        let depth = globals.len();
+        globals.extend(cmd.flags.iter().filter(|flag| flag.global));
+        for subcmd in cmd.subcommands.values() {
+            walk(ctx, spec, subcmd, globals)?;
+        }
+        globals.truncate(depth);
+        Ok(())
+    }
+    walk(ctx, spec, &spec.cmd, &mut vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Spec;
+
+    const SPEC: &str = r###"
+        bin "pkg"
+        flag "--format <format>" global=#true
+        schema "Package" #"{"type": "object", "properties": {"name": {"type": "string"}}, "required": ["name"]}"#
+        cmd "list" {
+            flag "--json"
+            output "text"
+            output "json" flag="--json" help="every package" schema=##"{"type": "array", "items": {"$ref": "#/$defs/Package"}}"##
+            output "jsonl" flag="--format jsonl" type="Package"
+        }
+    "###;
+
+    #[test]
+    fn outputs_survive_a_round_trip() {
+        let spec: Spec = SPEC.parse().unwrap();
+        let again: Spec = spec.to_string().parse().unwrap();
+        assert_eq!(again.to_string(), spec.to_string());
+        let list = &again.cmd.subcommands["list"];
+        assert_eq!(list.outputs.len(), 3);
+        assert_eq!(list.outputs[2].switch(), Some(("--format", Some("jsonl"))));
+        assert_eq!(
+            again.schemas["Package"].schema,
+            spec.schemas["Package"].schema
+        );
+    }
+
+    #[test]
+    fn an_output_schema_brings_the_schemas_it_refers_to() {
+        let spec: Spec = SPEC.parse().unwrap();
+        let list = &spec.cmd.subcommands["list"];
+        assert_eq!(spec.output_schema(&list.outputs[0]), None);
+        let json = spec.output_schema(&list.outputs[1]).unwrap();
+        assert_eq!(json["items"]["$ref"], "#/$defs/Package");
+        assert_eq!(json["$defs"]["Package"]["required"][0], "name");
+        let jsonl = spec.output_schema(&list.outputs[2]).unwrap();
+        assert_eq!(jsonl["$ref"], "#/$defs/Package");
+        assert!(jsonl["$defs"]["Package"].is_object());
+    }
+
+    #[test]
+    fn an_output_names_a_flag_the_command_has() {
+        let err = r#"cmd "list" { output "json" flag="--json" }"#.parse::<Spec>().unwrap_err();
+        assert!(format!("{err:?}").contains("output flag --json is not a flag of this command"));
+        let err = r#"cmd "list" { flag "--format <f>"; output "json" flag="--format" }"#
+            .parse::<Spec>()
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("takes a value"));
+    }
+
+    #[test]
+    fn an_output_type_names_a_schema() {
+        let err = r#"cmd "list" { output "json" type="Package" }"#
+            .parse::<Spec>()
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("names no schema"));
+    }
+
+    #[test]
+    fn a_schema_is_json() {
+        let err = r#"cmd "list" { output "json" schema="{nope" }"#
+            .parse::<Spec>()
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("not a JSON Schema"));
+        let err = r#"cmd "list" { output "text" schema="{}" }"#.parse::<Spec>().unwrap_err();
+        assert!(format!("{err:?}").contains("text output has no schema"));
+    }
+
+    #[test]
+    fn a_command_prints_one_thing_by_default() {
+        let err = r#"cmd "list" { output "text"; output "json" }"#
+            .parse::<Spec>()
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("give this one a `flag`"));
+    }
+}
@@ -0,0 +1,176 @@
+use serde::Serialize;
+use strum::{Display as StrumDisplay, EnumString};
+
+use crate::error::UsageErr;
+use crate::spec::context::ParsingContext;
+use crate::spec::helpers::ParseEntry;
+
+/// How much a command, flag or argument promises to stay as it is.
+///
+/// A declaration rather than a policy: `usage diff` reads it to decide what a removal costs,
+/// docs and help show it beside the item, and a spec that declares `experimental_env` has the
+/// parser refuse experimental items until the user opts in. Nothing else changes, so a spec
+/// that never says `stability` reads exactly as it did.
+///
+/// Ordered least to most settled, so `Ord` answers "is this at least beta".
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, EnumString, StrumDisplay, Serialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SpecStability {
+    /// May change or disappear in any release, without deprecation.
+    Experimental,
+    /// Settled in shape, but still free to change before it is declared stable.
+    Beta,
+    /// Does not disappear without being deprecated first.
+    Stable,
+}
+
+impl SpecStability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Experimental => "experimental",
+            Self::Beta => "beta",
+            Self::Stable => "stable",
+        }
+    }
+
+    /// Whether this item made a compatibility promise. Only `stable` does: removing a beta
+    /// flag is a change its users were told to expect.
+    pub fn is_promised(&self) -> bool {
+        *self == Self::Stable
+    }
+}
+
+/// The set of values accepted by `stability=`, for error messages.
+pub(crate) const STABILITY_VALUES: &str = "experimental, beta, stable";
+
+/// A `stability=` property or `stability` child's value.
+pub(crate) fn stability(
+    ctx: &ParsingContext,
+    v: &ParseEntry<'_>,
+) -> Result<SpecStability, UsageErr> {
+    let raw = v.ensure_string()?;
+    match raw.parse() {
+        Ok(stability) => Ok(stability),
+        Err(_) => bail_parse!(
+            ctx,
+            v.entry.span(),
+            "unsupported stability {raw}, expected one of: {STABILITY_VALUES}"
+        ),
+    }
+}
+
+/// The short label help output puts beside an item: `experimental`, `beta, since 2.1.0`.
+///
+/// `stable` is what an item is expected to be, so it earns no label of its own; its `since`
+/// still does.
+pub fn badge(stability: Option<SpecStability>, since: Option<&str>) -> Option<String> {
+    let stability = stability
+        .filter(|s| !s.is_promised())
+        .map(|s| s.as_str().to_string());
+    let since = since.map(|since| format!("since {since}"));
+    match (stability, since) {
+        (None, None) => None,
+        (Some(s), None) | (None, Some(s)) => Some(s),
+        (Some(stability), Some(since)) => Some(format!("{stability}, {since}")),
+    }
+}
+
+/// Whether `value`, read from a spec's `experimental_env`, opts in. The same reading a boolean
+/// flag gives its environment variable, so `1` and `true` work and `0` does not.
+pub(crate) fn opted_in(value: Option<&str>) -> bool {
+    matches!(value, Some("1" | "true" | "True" | "TRUE"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spec;
+
+    #[test]
+    fn test_parse_on_commands_flags_and_args() {
+        let spec: Spec = r#"
+bin "x"
+cmd "sync" stability="experimental" since="2024.3.0" {
+    flag "--prune" stability="beta"
+    arg "[remote]" since="2024.4.0"
+}
+cmd "status" {
+    stability "stable"
+    since "1.0.0"
+}
+        "#
+        .parse()
+        .unwrap();
+        let sync = &spec.cmd.subcommands["sync"];
+        assert_eq!(sync.stability, Some(SpecStability::Experimental));
+        assert_eq!(sync.since.as_deref(), Some("2024.3.0"));
+        assert_eq!(sync.flags[0].stability, Some(SpecStability::Beta));
+        assert_eq!(sync.args[0].since.as_deref(), Some("2024.4.0"));
+        let status = &spec.cmd.subcommands["status"];
+        assert_eq!(status.stability, Some(SpecStability::Stable));
+        assert_eq!(status.since.as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn test_unknown_stability_is_an_error() {
+        let err = r#"
+bin "x"
+cmd "sync" stability="alpha"
+        "#
+        .parse::<Spec>()
+        .unwrap_err();
+        assert!(
+            format!("{err:?}").contains("expected one of: experimental, beta, stable"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_stability_and_since_roundtrip_through_kdl() {
+        let spec: Spec = r#"
+bin "x"
+experimental_env "X_EXPERIMENTAL"
+cmd "sync" stability="experimental" since="2024.3.0" {
+    flag "--prune" stability="beta" since="2024.4.0"
+    arg "[remote]" stability="stable"
+}
+        "#
+        .parse()
+        .unwrap();
+        let reparsed: Spec = spec.to_string().parse().unwrap();
+        assert_eq!(reparsed.experimental_env.as_deref(), Some("X_EXPERIMENTAL"));
+        let sync = &reparsed.cmd.subcommands["sync"];
+        assert_eq!(sync.stability, Some(SpecStability::Experimental));
+        assert_eq!(sync.since.as_deref(), Some("2024.3.0"));
+        assert_eq!(sync.flags[0].stability, Some(SpecStability::Beta));
+        assert_eq!(sync.flags[0].since.as_deref(), Some("2024.4.0"));
+        assert_eq!(sync.args[0].stability, Some(SpecStability::Stable));
+    }
+
+    #[test]
+    fn test_badge_leaves_stable_unlabelled() {
+        assert_eq!(badge(None, None), None);
+        assert_eq!(badge(Some(SpecStability::Stable), None), None);
+        assert_eq!(
+            badge(Some(SpecStability::Stable), Some("1.0.0")).as_deref(),
+            Some("since 1.0.0")
+        );
+        assert_eq!(
+            badge(Some(SpecStability::Experimental), None).as_deref(),
+            Some("experimental")
+        );
+        assert_eq!(
+            badge(Some(SpecStability::Beta), Some("2.1.0")).as_deref(),
+            Some("beta, since 2.1.0")
+        );
+    }
+
+    #[test]
+    fn test_ordering_is_least_to_most_settled() {
+        assert!(SpecStability::Experimental < SpecStability::Beta);
+        assert!(SpecStability::Beta < SpecStability::Stable);
+    }
+}
@@ -4,7 +4,7 @@ use kdl::{KdlEntry, KdlNode};
 use serde::Serialize;
 
 use crate::error::Result;
-use crate::spec::context::ParsingContext;
+use crate::spec::context::{ParsingContext, SourceLocation};
 use crate::spec::helpers::{string_entry, NodeHelper};
 
 /// A named executable surface derived from one command in the canonical spec.
@@ -27,6 +27,9 @@ pub struct SpecView {
     pub all_globals: bool,
     /// Root-global selectors to carry when [`Self::all_globals`] is false.
     pub globals: Vec<String>,
+    /// Where this view was declared, for one read from a spec file.
+    #[serde(skip)]
+    pub source: Option<SourceLocation>,
 }
 
 impl SpecView {
@@ -36,6 +39,7 @@ impl SpecView {
             name: id.clone(),
             bin: id.clone(),
             id,
+            source: Some(ctx.locate(node.node.name().span())),
             ..Self::default()
         };
         for (key, value) in node.props() {
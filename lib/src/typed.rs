@@ -0,0 +1,191 @@
+//! Reading an argument's raw text as the type its spec declares.
+//!
+//! The config side has had a type grammar for a while ([`crate::spec::config_type`]); this is
+//! the same [`Base`] names applied to positionals and flag values. Only base types: an arg is
+//! already a list when it is variadic, and the rest of the grammar (`map`, `option`, unions)
+//! describes shapes a command line cannot spell.
+//!
+//! The spellings are the ones the generated Go parser accepts, because that runtime is the
+//! one a spec's users meet most often and the two must not disagree about what `--retries 1e3`
+//! means: integers are decimal, booleans are `strconv.ParseBool`'s set, and durations are
+//! `time.ParseDuration`'s notation.
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::spec::config_type::Base;
+
+/// A raw value read as its declared type.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TypedValue {
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    /// Also what an undeclared type, or one this version does not know, reads as.
+    String(String),
+    /// Not checked for existence: a path an output is about to be written to is legitimate.
+    Path(PathBuf),
+    Url(String),
+    Duration(Duration),
+    /// Every value of a variadic argument or a repeated flag, in the order given.
+    List(Vec<TypedValue>),
+}
+
+/// The type as prose, for the error a refused value reports.
+pub fn describe(ty: &Base) -> &'static str {
+    match ty {
+        Base::Bool => "a boolean",
+        Base::Int => "an integer",
+        Base::Uint => "a non-negative integer",
+        Base::Float => "a number",
+        Base::Path => "a path",
+        Base::Url => "a URL",
+        Base::Duration => "a duration",
+        Base::String | Base::Object | Base::Custom(_) => "a string",
+    }
+}
+
+/// Whether an argument may declare this type.
+///
+/// `object` is a config-file table, which no command-line word can be. Refused when the spec
+/// is read rather than when a value arrives, so the author hears about it and not their users.
+pub fn is_arg_type(ty: &Base) -> bool {
+    !matches!(ty, Base::Object)
+}
+
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*:\S+$").unwrap());
+
+/// Read `raw` as `ty`, or say what it should have been.
+///
+/// No trimming: `" 8"` is not a port number on the command line, where the shell already
+/// removed any whitespace the user meant as a separator.
+pub fn coerce(ty: &Base, raw: &str) -> Result<TypedValue, &'static str> {
+    let refused = || describe(ty);
+    match ty {
+        Base::Bool => parse_bool(raw).map(TypedValue::Bool).ok_or_else(refused),
+        Base::Int => raw.parse().map(TypedValue::Int).map_err(|_| refused()),
+        Base::Uint => raw.parse().map(TypedValue::Uint).map_err(|_| refused()),
+        Base::Float => raw.parse().map(TypedValue::Float).map_err(|_| refused()),
+        Base::Path if raw.is_empty() => Err(refused()),
+        Base::Path => Ok(TypedValue::Path(PathBuf::from(raw))),
+        Base::Url if URL.is_match(raw) => Ok(TypedValue::Url(raw.to_string())),
+        Base::Url => Err(refused()),
+        Base::Duration => parse_duration(raw)
+            .map(TypedValue::Duration)
+            .ok_or_else(refused),
+        Base::String | Base::Object | Base::Custom(_) => Ok(TypedValue::String(raw.to_string())),
+    }
+}
+
+/// `strconv.ParseBool`'s spellings, exactly. Not `yes`/`no`: the Go runtime would refuse
+/// them, and a value one parser accepts and another rejects is worse than either answer.
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw {
+        "1" | "t" | "T" | "true" | "TRUE" | "True" => Some(true),
+        "0" | "f" | "F" | "false" | "FALSE" | "False" => Some(false),
+        _ => None,
+    }
+}
+
+/// `time.ParseDuration`'s notation: a sequence of decimal numbers with units — `1h30m`,
+/// `1.5s`, `300ms` — or a bare `0`.
+///
+/// A negative duration is refused. Go can hold one and `std::time::Duration` cannot; an
+/// argument that means "how long to wait" has no use for it anyway.
+fn parse_duration(raw: &str) -> Option<Duration> {
+    let rest = raw.strip_prefix('+').unwrap_or(raw);
+    if rest == "0" {
+        return Some(Duration::ZERO);
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0f64;
+    let mut rest = rest;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number = &rest[..number_len];
+        if number.is_empty() || number == "." || number.matches('.').count() > 1 {
+            return None;
+        }
+        let value: f64 = number.parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let nanos_per_unit = match &rest[..unit_len] {
+            "ns" => 1.0,
+            "us" | "µs" | "μs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += value * nanos_per_unit;
+    }
+    Some(Duration::from_nanos(total.round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_are_decimal_and_untrimmed() {
+        assert_eq!(coerce(&Base::Int, "-3"), Ok(TypedValue::Int(-3)));
+        assert_eq!(coerce(&Base::Int, "0x10"), Err("an integer"));
+        assert_eq!(coerce(&Base::Int, " 8"), Err("an integer"));
+        assert_eq!(coerce(&Base::Uint, "-1"), Err("a non-negative integer"));
+        assert_eq!(coerce(&Base::Float, "1e3"), Ok(TypedValue::Float(1000.0)));
+    }
+
+    #[test]
+    fn booleans_take_the_go_runtime_spellings_and_no_others() {
+        assert_eq!(coerce(&Base::Bool, "T"), Ok(TypedValue::Bool(true)));
+        assert_eq!(coerce(&Base::Bool, "0"), Ok(TypedValue::Bool(false)));
+        assert_eq!(coerce(&Base::Bool, "yes"), Err("a boolean"));
+    }
+
+    #[test]
+    fn durations_use_go_notation() {
+        let d = |raw| coerce(&Base::Duration, raw);
+        assert_eq!(
+            d("1h30m"),
+            Ok(TypedValue::Duration(Duration::from_secs(5400)))
+        );
+        assert_eq!(
+            d("1.5s"),
+            Ok(TypedValue::Duration(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            d("300µs"),
+            Ok(TypedValue::Duration(Duration::from_micros(300)))
+        );
+        assert_eq!(d("0"), Ok(TypedValue::Duration(Duration::ZERO)));
+        for refused in ["", "5", "s", "1d", "-1s", "1..5s"] {
+            assert_eq!(d(refused), Err("a duration"), "{refused:?}");
+        }
+    }
+
+    #[test]
+    fn urls_need_a_scheme_and_paths_need_text() {
+        assert!(coerce(&Base::Url, "https://example.com").is_ok());
+        assert_eq!(coerce(&Base::Url, "example.com"), Err("a URL"));
+        assert_eq!(coerce(&Base::Path, ""), Err("a path"));
+        assert_eq!(
+            coerce(&Base::Custom("semver".into()), "1.x"),
+            Ok(TypedValue::String("1.x".into()))
+        );
+    }
+}
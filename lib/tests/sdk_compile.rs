@@ -57,8 +57,11 @@ fn full_spec() -> Spec {
                 choices "1" "2" "3" "4" "5"
             }
             flag "--json" help="Output as JSON"
+            output "json" flag="--json" type="LogEntry"
         }
 
+        schema "LogEntry" #"{"type": "object", "properties": {"level": {"type": "integer"}, "message": {"type": "string"}}, "required": ["level"]}"#
+
         cmd "status" help="Show status" {
             flag "--json" help="Output as JSON"
         }
@@ -108,7 +111,8 @@ fn test_python_sdk_imports() {
             package_name: None,
             source_file: None,
         },
-    );
+    )
+    .unwrap();
 
     let dir = tempfile::tempdir().unwrap();
     // Python files are flat in the package directory
@@ -159,7 +163,8 @@ fn test_typescript_sdk_typechecks() {
             package_name: None,
             source_file: None,
         },
-    );
+    )
+    .unwrap();
 
     let dir = tempfile::tempdir().unwrap();
     write_sdk_to_dir(&output, dir.path());
@@ -218,3 +223,131 @@ fn test_typescript_sdk_typechecks() {
         panic!("Generated TypeScript SDK does not typecheck:\nstdout: {stdout}\nstderr: {stderr}");
     }
 }
+
+// ---------------------------------------------------------------------------
+// Rust
+// ---------------------------------------------------------------------------
+
+fn rustc(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+        .args(["--edition", "2021"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to run rustc")
+}
+
+#[test]
+fn test_rust_sdk_compiles() {
+    let spec = full_spec();
+    let output = usage::sdk::generate(
+        &spec,
+        &SdkOptions {
+            language: SdkLanguage::Rust,
+            package_name: None,
+            source_file: None,
+        },
+    )
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    // A module of the crate that uses it, as it is meant to be dropped in
+    write_sdk_to_dir(&output, &dir.path().join("mytool_sdk"));
+    fs::write(dir.path().join("lib.rs"), "pub mod mytool_sdk;\n").unwrap();
+
+    let result = rustc(
+        dir.path(),
+        &[
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+            "-D",
+            "warnings",
+            "lib.rs",
+        ],
+    );
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        panic!("Generated Rust SDK does not compile:\n{stderr}");
+    }
+
+    // And the argv it builds, through `echo`.
+    if cfg!(unix) {
+        let main = r#"
+            #![allow(dead_code, deprecated, unused_imports)]
+            mod mytool_sdk;
+            use mytool_sdk::*;
+
+            fn main() {
+                let cli = Mytool::with_bin("echo");
+                let flags = BuildFlags::new().verbose(2).jobs(["4", "8"]).release(true);
+                let args = BuildArgs::new(TargetChoice::Release, "dist");
+                let result = cli.build().exec(&args, &flags).unwrap();
+                assert!(result.ok());
+                print!("{}", result.stdout);
+            }
+        "#;
+        fs::write(dir.path().join("main.rs"), main).unwrap();
+        let result = rustc(dir.path(), &["-o", "sdk_main", "main.rs"]);
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr);
+            panic!("A program using the generated Rust SDK does not compile:\n{stderr}");
+        }
+        let run = Command::new(dir.path().join("sdk_main")).output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&run.stdout),
+            "build --verbose --verbose --jobs 4 --jobs 8 --release release -- dist\n"
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Go
+// ---------------------------------------------------------------------------
+
+/// Run by `mise run test:go`, where a Go toolchain is certain: skipping quietly without
+/// one would pass without the generator's output ever being checked.
+#[test]
+#[ignore = "needs go"]
+fn test_go_sdk_vets() {
+    let spec = full_spec();
+    let output = usage::sdk::generate(
+        &spec,
+        &SdkOptions {
+            language: SdkLanguage::Go,
+            package_name: None,
+            source_file: None,
+        },
+    )
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    write_sdk_to_dir(&output, dir.path());
+    fs::write(
+        dir.path().join("go.mod"),
+        "module example.com/mytool\n\ngo 1.21\n",
+    )
+    .unwrap();
+
+    let result = Command::new("go")
+        .args(["vet", "./..."])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to run go vet");
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        panic!("Generated Go SDK does not vet:\n{stderr}");
+    }
+
+    let result = Command::new("gofmt")
+        .arg("-l")
+        .arg(dir.path())
+        .output()
+        .expect("Failed to run gofmt");
+    let unformatted = String::from_utf8_lossy(&result.stdout);
+    assert!(
+        unformatted.trim().is_empty(),
+        "Generated Go SDK is not gofmt-clean:\n{unformatted}"
+    );
+}
@@ -320,6 +320,38 @@ fn an_install_plan_names_this_clis_own_binary() {
     assert_eq!(embedded.path.file_name().unwrap(), "view-host.fish");
 }
 
+#[cfg(feature = "completions")]
+#[test]
+fn an_uninstall_removes_only_the_script_an_install_wrote() {
+    use usage::install::{Env, OnForeign, Platform, Removed};
+
+    let dir = std::env::temp_dir().join(format!("usage-rs-uninstall-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let env = Env::new(
+        Platform::current(),
+        [("HOME".to_string(), dir.clone().into())],
+    )
+    .with("XDG_CONFIG_HOME", dir.join("config"))
+    .with("PATH", dir.join("bin"));
+
+    let done = ViewHost::install_completion(usage::complete::Shell::Fish, &env, OnForeign::Refuse)
+        .unwrap();
+    let gone = ViewHost::uninstall_completion(usage::complete::Shell::Fish, &env).unwrap();
+    assert_eq!(gone.removed, Removed::Deleted);
+    assert!(!done.plan.path.exists());
+
+    // Nothing on `PATH`, so every-shell planning has nothing to say, and every-shell removal finds
+    // nothing left to take.
+    assert!(ViewHost::completion_install_plan_all_shells(&env)
+        .unwrap()
+        .is_empty());
+    assert!(ViewHost::uninstall_completion_all_shells(&env)
+        .unwrap()
+        .iter()
+        .all(|gone| gone.removed == Removed::Absent));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 #[cfg(feature = "completions")]
 #[test]
 fn an_install_plan_follows_the_runtime_identity_and_not_the_portable_one() {